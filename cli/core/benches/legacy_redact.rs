@@ -0,0 +1,414 @@
+//! The hand-written scanners `redact.rs` used before the built-in rules became
+//! regexes, kept verbatim as the baseline for `benches/redaction.rs`.
+
+/// Apply every legacy scanner in the old built-in order.
+pub fn redact(input: &str) -> String {
+    let scanners: [fn(&str) -> String; 10] = [
+        redact_openai_like_keys,
+        redact_aws_access_keys,
+        redact_github_tokens,
+        redact_google_api_keys,
+        redact_slack_tokens,
+        redact_bearer_tokens,
+        redact_jwt_tokens,
+        redact_pem_keys,
+        redact_connection_strings,
+        redact_secret_assignments,
+    ];
+    scanners.iter().fold(input.to_string(), |text, scanner| scanner(&text))
+}
+
+fn redact_openai_like_keys(input: &str) -> String {
+    let mut output = String::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0usize;
+
+    while i < chars.len() {
+        if i + 3 <= chars.len() && chars[i] == 's' && chars[i + 1] == 'k' && chars[i + 2] == '-' {
+            let mut j = i + 3;
+            while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_' || chars[j] == '-') {
+                j += 1;
+            }
+            if j.saturating_sub(i + 3) >= 20 {
+                output.push_str("sk-[REDACTED]");
+                i = j;
+                continue;
+            }
+        }
+        output.push(chars[i]);
+        i += 1;
+    }
+
+    output
+}
+
+fn redact_aws_access_keys(input: &str) -> String {
+    let mut output = String::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0usize;
+
+    while i < chars.len() {
+        if i + 20 <= chars.len()
+            && chars[i] == 'A'
+            && chars[i + 1] == 'K'
+            && chars[i + 2] == 'I'
+            && chars[i + 3] == 'A'
+        {
+            let mut valid = true;
+            for ch in chars.iter().take(i + 20).skip(i + 4) {
+                if !ch.is_ascii_uppercase() && !ch.is_ascii_digit() {
+                    valid = false;
+                    break;
+                }
+            }
+            if valid {
+                output.push_str("AKIA[REDACTED]");
+                i += 20;
+                continue;
+            }
+        }
+        output.push(chars[i]);
+        i += 1;
+    }
+
+    output
+}
+
+fn redact_github_tokens(input: &str) -> String {
+    let mut output = String::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0usize;
+
+    let prefixes: &[&str] = &["ghp_", "gho_", "ghs_", "ghr_"];
+    while i < chars.len() {
+        let mut matched = false;
+        for prefix in prefixes {
+            let pchars: Vec<char> = prefix.chars().collect();
+            if i + pchars.len() <= chars.len() && chars[i..i + pchars.len()] == pchars[..] {
+                let mut j = i + pchars.len();
+                while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_') {
+                    j += 1;
+                }
+                if j.saturating_sub(i + pchars.len()) >= 20 {
+                    output.push_str(prefix);
+                    output.push_str("[REDACTED]");
+                    i = j;
+                    matched = true;
+                    break;
+                }
+            }
+        }
+        if matched { continue; }
+
+        // github_pat_ prefix
+        let pat_prefix = "github_pat_";
+        let pat_chars: Vec<char> = pat_prefix.chars().collect();
+        if i + pat_chars.len() <= chars.len() && chars[i..i + pat_chars.len()] == pat_chars[..] {
+            let mut j = i + pat_chars.len();
+            while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_') {
+                j += 1;
+            }
+            if j.saturating_sub(i + pat_chars.len()) >= 20 {
+                output.push_str("github_pat_[REDACTED]");
+                i = j;
+                continue;
+            }
+        }
+
+        output.push(chars[i]);
+        i += 1;
+    }
+    output
+}
+
+fn redact_google_api_keys(input: &str) -> String {
+    let mut output = String::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0usize;
+
+    while i < chars.len() {
+        if i + 4 <= chars.len()
+            && chars[i] == 'A' && chars[i + 1] == 'I' && chars[i + 2] == 'z' && chars[i + 3] == 'a'
+        {
+            let mut j = i + 4;
+            while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_' || chars[j] == '-') {
+                j += 1;
+            }
+            if j.saturating_sub(i + 4) >= 20 {
+                output.push_str("AIza[REDACTED]");
+                i = j;
+                continue;
+            }
+        }
+        output.push(chars[i]);
+        i += 1;
+    }
+    output
+}
+
+fn redact_slack_tokens(input: &str) -> String {
+    let mut output = String::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0usize;
+
+    let prefixes: &[&str] = &["xoxb-", "xoxp-", "xoxs-"];
+    while i < chars.len() {
+        let mut matched = false;
+        for prefix in prefixes {
+            let pchars: Vec<char> = prefix.chars().collect();
+            if i + pchars.len() <= chars.len() && chars[i..i + pchars.len()] == pchars[..] {
+                let mut j = i + pchars.len();
+                while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '-') {
+                    j += 1;
+                }
+                if j.saturating_sub(i + pchars.len()) >= 10 {
+                    output.push_str(prefix);
+                    output.push_str("[REDACTED]");
+                    i = j;
+                    matched = true;
+                    break;
+                }
+            }
+        }
+        if matched { continue; }
+        output.push(chars[i]);
+        i += 1;
+    }
+    output
+}
+
+fn redact_jwt_tokens(input: &str) -> String {
+    let mut output = String::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0usize;
+
+    fn is_base64url(ch: char) -> bool {
+        ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'
+    }
+
+    while i < chars.len() {
+        if i + 3 <= chars.len() && chars[i] == 'e' && chars[i + 1] == 'y' && chars[i + 2] == 'J' {
+            let mut j = i + 3;
+            // First segment
+            while j < chars.len() && is_base64url(chars[j]) { j += 1; }
+            let seg1_len = j - (i + 3);
+            if seg1_len >= 10 && j < chars.len() && chars[j] == '.' {
+                j += 1;
+                let seg2_start = j;
+                while j < chars.len() && is_base64url(chars[j]) { j += 1; }
+                let seg2_len = j - seg2_start;
+                if seg2_len >= 10 && j < chars.len() && chars[j] == '.' {
+                    j += 1;
+                    let seg3_start = j;
+                    while j < chars.len() && is_base64url(chars[j]) { j += 1; }
+                    let seg3_len = j - seg3_start;
+                    if seg3_len >= 10 {
+                        output.push_str("[REDACTED_JWT]");
+                        i = j;
+                        continue;
+                    }
+                }
+            }
+        }
+        output.push(chars[i]);
+        i += 1;
+    }
+    output
+}
+
+fn redact_pem_keys(input: &str) -> String {
+    let mut out = input.to_string();
+    // Replace PEM private key blocks
+    while let Some(start) = out.find("-----BEGIN ") {
+        let header_end = match out[start..].find("-----\n").or_else(|| out[start..].find("-----\r")) {
+            Some(pos) => start + pos + 5,
+            None => break,
+        };
+        // Check this is a PRIVATE KEY header
+        let header = &out[start..header_end];
+        if !header.contains("PRIVATE KEY") {
+            // Skip past this marker to avoid infinite loop
+            let placeholder_pos = start + "-----BEGIN ".len();
+            if placeholder_pos >= out.len() { break; }
+            // Move on by replacing nothing, just advance search
+            let after = &out[header_end..];
+            if let Some(end_marker) = after.find("-----END ") {
+                let block_end_pos = header_end + end_marker;
+                if let Some(line_end) = out[block_end_pos..].find("-----") {
+                    let final_end = block_end_pos + line_end + 5;
+                    // Skip newline after end marker
+                    let final_end = if final_end < out.len() && (out.as_bytes()[final_end] == b'\n' || out.as_bytes()[final_end] == b'\r') {
+                        final_end + 1
+                    } else {
+                        final_end
+                    };
+                    out = format!("{}{}", &out[..start], &out[final_end..]);
+                    continue;
+                }
+            }
+            break;
+        }
+        // Find end marker
+        let after = &out[header_end..];
+        if let Some(end_pos) = after.find("-----END ") {
+            let end_start = header_end + end_pos;
+            if let Some(end_line) = out[end_start..].find("-----\n").or_else(|| out[end_start..].find("-----\r")).or_else(|| {
+                // Could be at end of string
+                if out[end_start..].ends_with("-----") { Some(out[end_start..].len() - 5) } else { None }
+            }) {
+                let final_end = end_start + end_line + 5;
+                let final_end = if final_end < out.len() && (out.as_bytes()[final_end] == b'\n' || out.as_bytes()[final_end] == b'\r') {
+                    final_end + 1
+                } else {
+                    final_end
+                };
+                out = format!("{}[REDACTED_PEM_KEY]{}", &out[..start], &out[final_end..]);
+            } else {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+    out
+}
+
+fn redact_connection_strings(input: &str) -> String {
+    let mut out = input.to_string();
+    let protocols = ["postgres://", "postgresql://", "mysql://", "mongodb://", "redis://", "amqp://"];
+    for proto in protocols {
+        let mut search_from = 0usize;
+        loop {
+            let lower = out.to_ascii_lowercase();
+            let Some(pos) = lower[search_from..].find(proto) else { break; };
+            let start = search_from + pos;
+            let url_start = start;
+            let proto_end = start + proto.len();
+            // Find end of URL (whitespace, quote, or end of string)
+            let mut end = proto_end;
+            while end < out.len() {
+                let ch = out.as_bytes()[end] as char;
+                if ch.is_ascii_whitespace() || ch == '"' || ch == '\'' { break; }
+                end += 1;
+            }
+            let proto_actual = &out[url_start..proto_end];
+            let replacement = format!("{}[REDACTED]", proto_actual);
+            out.replace_range(url_start..end, &replacement);
+            search_from = url_start + replacement.len();
+        }
+    }
+    out
+}
+
+fn redact_bearer_tokens(input: &str) -> String {
+    let mut out = input.to_string();
+    let mut search_from = 0usize;
+
+    loop {
+        let lower = out.to_ascii_lowercase();
+        let Some(relative_start) = lower[search_from..].find("bearer ") else {
+            break;
+        };
+        let start = search_from + relative_start;
+        let token_start = start + "bearer ".len();
+        let mut token_end = token_start;
+        let bytes = out.as_bytes();
+        while token_end < bytes.len() {
+            let ch = bytes[token_end] as char;
+            if ch.is_ascii_alphanumeric() || ch == '.' || ch == '_' || ch == '-' {
+                token_end += 1;
+            } else {
+                break;
+            }
+        }
+        if token_end.saturating_sub(token_start) < 10 {
+            search_from = token_end.max(start + "bearer ".len());
+            continue;
+        }
+        out.replace_range(start..token_end, "Bearer [REDACTED]");
+        search_from = start + "Bearer [REDACTED]".len();
+    }
+    out
+}
+
+fn redact_secret_assignments(input: &str) -> String {
+    let keywords = ["api_key", "api-key", "apikey", "token", "secret", "password"];
+    let mut output = input.to_string();
+
+    for key in keywords {
+        output = redact_assignment_for_key(&output, key);
+    }
+
+    output
+}
+
+fn redact_assignment_for_key(input: &str, keyword: &str) -> String {
+    let mut out = input.to_string();
+    let mut search_from = 0usize;
+
+    while search_from < out.len() {
+        let lower = out.to_ascii_lowercase();
+        let Some(relative) = lower[search_from..].find(keyword) else {
+            break;
+        };
+        let start = search_from + relative;
+
+        let mut idx = start + keyword.len();
+        while idx < out.len() && out.as_bytes()[idx].is_ascii_whitespace() {
+            idx += 1;
+        }
+        if idx >= out.len() {
+            break;
+        }
+
+        let separator = out.as_bytes()[idx] as char;
+        if separator != ':' && separator != '=' {
+            search_from = start + keyword.len();
+            continue;
+        }
+
+        idx += 1;
+        while idx < out.len() && out.as_bytes()[idx].is_ascii_whitespace() {
+            idx += 1;
+        }
+        if idx >= out.len() {
+            break;
+        }
+
+        let quote = out.as_bytes()[idx] as char;
+        let quoted = quote == '"' || quote == '\'';
+        if quoted {
+            idx += 1;
+        }
+
+        let value_start = idx;
+        while idx < out.len() {
+            let ch = out.as_bytes()[idx] as char;
+            if quoted {
+                if ch == quote {
+                    break;
+                }
+            } else if ch.is_ascii_whitespace() || ch == ',' || ch == ';' {
+                break;
+            }
+            idx += 1;
+        }
+
+        if idx > value_start {
+            // Include closing quote in replacement range if present
+            let end = if quoted && idx < out.len() && out.as_bytes()[idx] as char == quote {
+                idx + 1
+            } else {
+                idx
+            };
+            // Replace from keyword start through end of value (including quotes) with keyword=[REDACTED]
+            let replacement = format!("{}=[REDACTED]", keyword);
+            out.replace_range(start..end, &replacement);
+            search_from = start + replacement.len();
+        } else {
+            search_from = idx.saturating_add(1);
+        }
+    }
+
+    out
+}
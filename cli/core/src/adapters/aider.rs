@@ -0,0 +1,457 @@
+//! Aider keeps history per repository: `.aider.chat.history.md` (markdown
+//! transcript, one `# aider chat started at ...` block per session) and
+//! `.aider.input.history` (timestamped user inputs) in the directory Aider
+//! ran in. A session's cwd is that directory.
+//!
+//! Histories are found in `--cwd` and its parent directories, plus anything
+//! under `BRIDGE_AIDER_PROJECTS_DIR` / `[scan_roots] aider`. There is no
+//! default store to scan.
+
+use super::{common, AgentAdapter};
+use crate::agents::{
+    self, BridgeErrorCode, CwdMatch, MessageWindow, ReadOptions, SearchQuery, Session, TranscriptMessage, TurnKind,
+};
+use crate::redact::redact_sensitive_text;
+use crate::utils::normalize_path;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CHAT_HISTORY: &str = ".aider.chat.history.md";
+pub const INPUT_HISTORY: &str = ".aider.input.history";
+const SESSION_HEADER: &str = "# aider chat started at ";
+/// Session id used when only `.aider.input.history` exists.
+const INPUT_ONLY_ID: &str = "input-history";
+
+pub struct AiderAdapter;
+
+/// One chat session parsed from a history file.
+#[derive(Debug, Default)]
+struct AiderSession {
+    id: String,
+    model: Option<String>,
+    prompts: Vec<String>,
+    replies: Vec<String>,
+    order: Vec<TurnKind>,
+}
+
+/// A project directory holding Aider history, and its sessions oldest first.
+struct Project {
+    dir: PathBuf,
+    source: PathBuf,
+    sessions: Vec<AiderSession>,
+    input_only: bool,
+}
+
+impl AgentAdapter for AiderAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let cwd = normalize_path(&options.cwd)?;
+        let projects = load_projects(Some(&cwd))?;
+        let mut warnings = Vec::new();
+
+        let (project, session) = match options.id.as_deref() {
+            Some(id) => select_by_id(&projects, id, options)?,
+            None => {
+                let project = projects
+                    .iter()
+                    .find(|project| options.cwd_match.matches(&project.dir, &cwd))
+                    .or_else(|| nearest_ancestor(&projects, &cwd))
+                    .or_else(|| {
+                        let newest = projects.first();
+                        if newest.is_some() {
+                            warnings.push(format!(
+                                "Warning: no Aider session matched cwd {}; falling back to latest session.",
+                                cwd.display()
+                            ));
+                        }
+                        newest
+                    })
+                    .ok_or_else(|| {
+                        BridgeErrorCode::NotFound.error(format!(
+                            "No Aider session found. Looked for {} in {} and its parent directories.",
+                            CHAT_HISTORY,
+                            cwd.display()
+                        ))
+                    })?;
+                let session = project
+                    .sessions
+                    .last()
+                    .ok_or_else(|| {
+                        BridgeErrorCode::EmptySession.error(format!("Aider session has no messages: {}", project.source.display()))
+                    })?;
+                (project, session)
+            }
+        };
+        if project.input_only {
+            warnings.push(format!(
+                "Warning: no {} in {}; showing inputs from {}.",
+                CHAT_HISTORY,
+                project.dir.display(),
+                INPUT_HISTORY
+            ));
+        }
+
+        Ok(to_session(project, session, options.window(), warnings))
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let expected = cwd.map(normalize_path).transpose()?;
+        let projects = load_projects(expected.as_deref().or(working_dir().as_deref()))?;
+        let mut entries = Vec::new();
+        for project in &projects {
+            if !expected.as_ref().map_or(true, |expected| in_scope(project, expected, cwd_match)) {
+                continue;
+            }
+            for session in project.sessions.iter().rev() {
+                entries.push(json!({
+                    "session_id": session.id,
+                    "agent": "aider",
+                    "cwd": project.dir.to_string_lossy(),
+                    "model": session.model,
+                    "modified_at": agents::file_modified_iso(&project.source),
+                    "file_path": project.source.to_string_lossy(),
+                }));
+                if entries.len() >= limit {
+                    return Ok(entries);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let expected = cwd.map(normalize_path).transpose()?;
+        let projects = load_projects(expected.as_deref().or(working_dir().as_deref()))?;
+        let mut entries = Vec::new();
+        for project in &projects {
+            if !expected.as_ref().map_or(true, |expected| in_scope(project, expected, cwd_match)) {
+                continue;
+            }
+            for session in project.sessions.iter().rev() {
+                let matches = query.matches_turns(
+                    &session.prompts,
+                    &session.replies,
+                    Some(&session.id),
+                    Some(&project.dir.to_string_lossy()),
+                );
+                if !matches {
+                    continue;
+                }
+                entries.push(json!({
+                    "session_id": session.id,
+                    "agent": "aider",
+                    "cwd": project.dir.to_string_lossy(),
+                    "modified_at": agents::file_modified_iso(&project.source),
+                    "file_path": project.source.to_string_lossy(),
+                }));
+                if entries.len() >= limit {
+                    return Ok(entries);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// The newest session of a `.aider.chat.history.md` transcript.
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        let sessions = parse_chat_history(text);
+        let session = sessions.last()
+            .ok_or_else(|| BridgeErrorCode::EmptySession.error(format!("Aider session has no messages: {}", name)))?;
+        let project = Project { dir: PathBuf::new(), source: PathBuf::from(name), sessions: Vec::new(), input_only: false };
+        let mut parsed = to_session(&project, session, window, Vec::new());
+        parsed.cwd = None;
+        parsed.timestamp = None;
+        Ok(parsed)
+    }
+
+    fn first_prompt(&self, text: &str, session_id: Option<&str>) -> Option<String> {
+        if session_id == Some(INPUT_ONLY_ID) {
+            return parse_input_history(text).into_iter().next();
+        }
+        let sessions = parse_chat_history(text);
+        let session = match session_id {
+            Some(id) => sessions.iter().find(|session| session.id == id),
+            None => sessions.last(),
+        }?;
+        session.prompts.first().cloned()
+    }
+
+    fn transcript(&self, text: &str, session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        if session_id == Some(INPUT_ONLY_ID) {
+            let inputs = parse_input_history(text);
+            return agents::interleave(&inputs, &[], &vec![TurnKind::Prompt; inputs.len()]);
+        }
+        let sessions = parse_chat_history(text);
+        let session = match session_id {
+            Some(id) => sessions.iter().find(|session| session.id == id),
+            None => sessions.last(),
+        };
+        session
+            .map(|session| agents::interleave(&session.prompts, &session.replies, &session.order))
+            .unwrap_or_default()
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        Err(anyhow!("fsck checks JSON session files; Aider history is markdown"))
+    }
+}
+
+fn to_session(project: &Project, session: &AiderSession, window: MessageWindow, warnings: Vec<String>) -> Session {
+    // With only input history there are no replies, so the inputs stand in.
+    let messages = if project.input_only { &session.prompts } else { &session.replies };
+    let selected = window.select(messages).iter().map(|text| redact_sensitive_text(text)).collect::<Vec<_>>();
+    let content = if messages.is_empty() {
+        "[No text content]".to_string()
+    } else {
+        agents::join_messages(&selected)
+    };
+    Session {
+        agent: "aider",
+        content,
+        messages: if messages.is_empty() { Vec::new() } else { selected.clone() },
+        source: project.source.to_string_lossy().to_string(),
+        warnings,
+        session_id: Some(session.id.clone()),
+        cwd: Some(project.dir.to_string_lossy().to_string()),
+        timestamp: agents::file_modified_iso(&project.source),
+        message_count: messages.len(),
+        messages_returned: selected.len(),
+        tool_usage: None,
+        timing: None,
+        model: session.model.clone(),
+        usage: None,
+    }
+}
+
+fn select_by_id<'a>(projects: &'a [Project], id: &str, options: &ReadOptions) -> Result<(&'a Project, &'a AiderSession)> {
+    let candidates = projects
+        .iter()
+        .flat_map(|project| project.sessions.iter().map(move |session| (project, session)))
+        .filter(|(_, session)| options.id_match.matches(&session.id, id))
+        .collect::<Vec<_>>();
+    common::pick_by_id(candidates, id, options, "Aider", |(_, session)| session.id.clone())
+}
+
+/// Histories apply to the directory Aider ran in and everything below it.
+fn in_scope(project: &Project, cwd: &Path, cwd_match: CwdMatch) -> bool {
+    cwd_match.matches(&project.dir, cwd) || cwd.starts_with(&project.dir)
+}
+
+/// The project whose directory is the closest parent of `cwd`.
+fn nearest_ancestor<'a>(projects: &'a [Project], cwd: &Path) -> Option<&'a Project> {
+    projects
+        .iter()
+        .filter(|project| cwd.starts_with(&project.dir))
+        .max_by_key(|project| project.dir.components().count())
+}
+
+/// Without `--cwd`, `list` and `search` still pick up the history of the repo
+/// they run in.
+fn working_dir() -> Option<PathBuf> {
+    std::env::current_dir().ok()
+}
+
+/// Every project with Aider history under the configured roots or at/above
+/// `cwd`, newest history first.
+fn load_projects(cwd: Option<&Path>) -> Result<Vec<Project>> {
+    let roots = common::configured_roots("aider", "BRIDGE_AIDER_PROJECTS_DIR").unwrap_or_default();
+    let mut dirs = common::collect_paths(&roots, &|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name == CHAT_HISTORY || name == INPUT_HISTORY)
+    })?
+    .into_iter()
+    .filter_map(|path| path.parent().map(Path::to_path_buf))
+    .collect::<Vec<_>>();
+    if let Some(cwd) = cwd {
+        dirs.extend(
+            cwd.ancestors()
+                .filter(|dir| dir.join(CHAT_HISTORY).is_file() || dir.join(INPUT_HISTORY).is_file())
+                .map(Path::to_path_buf),
+        );
+    }
+
+    let mut projects: Vec<Project> = Vec::new();
+    for dir in dirs {
+        let dir = normalize_path(&dir.to_string_lossy())?;
+        if projects.iter().any(|project| project.dir == dir) {
+            continue;
+        }
+        if let Some(project) = load_project(dir)? {
+            projects.push(project);
+        }
+    }
+    projects.sort_by_key(|project| std::cmp::Reverse(agents::file_modified_iso(&project.source)));
+    Ok(projects)
+}
+
+fn load_project(dir: PathBuf) -> Result<Option<Project>> {
+    let chat = dir.join(CHAT_HISTORY);
+    let input = dir.join(INPUT_HISTORY);
+    let (source, input_only) = if chat.is_file() {
+        (chat, false)
+    } else if input.is_file() {
+        (input, true)
+    } else {
+        return Ok(None);
+    };
+    if fs::metadata(&source).map(|meta| meta.len() > common::max_file_size()).unwrap_or(false) {
+        return Err(anyhow!(
+            "Skipped {} (exceeds {}MB size limit)",
+            source.display(),
+            common::max_file_size() / (1024 * 1024)
+        ));
+    }
+    let raw = fs::read(&source).map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to read {}: {}", source.display(), err)))?;
+    let text = String::from_utf8_lossy(&raw);
+    let sessions = if input_only {
+        vec![AiderSession {
+            id: INPUT_ONLY_ID.to_string(),
+            prompts: parse_input_history(&text),
+            ..AiderSession::default()
+        }]
+    } else {
+        parse_chat_history(&text)
+    };
+    Ok(Some(Project { dir, source, sessions, input_only }))
+}
+
+/// Split a chat transcript into sessions. `#### ` lines are user input, `>`
+/// lines are Aider's own output (startup banner, applied edits, commits), and
+/// everything else is the assistant's reply. Code fences are kept verbatim.
+fn parse_chat_history(text: &str) -> Vec<AiderSession> {
+    let mut sessions: Vec<AiderSession> = Vec::new();
+    let mut reply: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    let mut after_prompt = false;
+
+    fn flush(sessions: &mut [AiderSession], reply: &mut Vec<&str>) {
+        let text = reply.join("\n").trim().to_string();
+        reply.clear();
+        if let (Some(session), false) = (sessions.last_mut(), text.is_empty()) {
+            session.replies.push(text);
+            session.order.push(TurnKind::Reply);
+        }
+    }
+
+    for line in text.lines() {
+        let continues_prompt = std::mem::take(&mut after_prompt);
+        if in_fence {
+            in_fence = !line.trim_start().starts_with("```");
+            reply.push(line);
+            continue;
+        }
+        if let Some(started) = line.strip_prefix(SESSION_HEADER) {
+            flush(&mut sessions, &mut reply);
+            sessions.push(AiderSession { id: session_id(started), ..AiderSession::default() });
+        } else if let Some(prompt) = line.strip_prefix("#### ") {
+            flush(&mut sessions, &mut reply);
+            let session = match sessions.last_mut() {
+                Some(session) => session,
+                None => {
+                    sessions.push(AiderSession { id: "unknown".to_string(), ..AiderSession::default() });
+                    sessions.last_mut().expect("just pushed")
+                }
+            };
+            // Multi-line input is written as consecutive `#### ` lines.
+            match session.prompts.last_mut().filter(|_| continues_prompt) {
+                Some(last) => {
+                    last.push('\n');
+                    last.push_str(prompt);
+                }
+                None => {
+                    session.prompts.push(prompt.to_string());
+                    session.order.push(TurnKind::Prompt);
+                }
+            }
+            after_prompt = true;
+        } else if line == ">" || line.starts_with("> ") {
+            if let Some(session) = sessions.last_mut().filter(|session| session.model.is_none()) {
+                session.model = banner_model(line);
+            }
+        } else if !sessions.is_empty() {
+            in_fence = line.trim_start().starts_with("```");
+            reply.push(line);
+        }
+    }
+    flush(&mut sessions, &mut reply);
+    sessions
+}
+
+/// `2024-05-14 09:12:03` -> `20240514-091203`.
+fn session_id(started: &str) -> String {
+    let digits = started.chars().filter(char::is_ascii_digit).collect::<String>();
+    if digits.len() >= 14 {
+        format!("{}-{}", &digits[..8], &digits[8..14])
+    } else {
+        started.trim().to_string()
+    }
+}
+
+/// Model name from the startup banner (`> Model: gpt-4o with diff edit format`,
+/// `> Main model: claude-3-5-sonnet ...`).
+fn banner_model(line: &str) -> Option<String> {
+    let rest = line.trim_start_matches('>').trim_start();
+    ["Main model:", "Models:", "Model:"]
+        .iter()
+        .find_map(|label| rest.strip_prefix(label))
+        .and_then(|value| value.split_whitespace().next())
+        .map(|model| model.trim_end_matches(',').to_string())
+}
+
+/// `.aider.input.history`: `# <timestamp>` headers followed by `+`-prefixed
+/// input lines.
+fn parse_input_history(text: &str) -> Vec<String> {
+    let mut inputs: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        if line.starts_with("# ") {
+            inputs.extend(current.take());
+        } else if let Some(input) = line.strip_prefix('+') {
+            match current.as_mut() {
+                Some(text) => {
+                    text.push('\n');
+                    text.push_str(input);
+                }
+                None => current = Some(input.to_string()),
+            }
+        }
+    }
+    inputs.extend(current);
+    inputs.retain(|input| !input.trim().is_empty());
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_chat_history, parse_input_history};
+
+    #[test]
+    fn parses_sessions_prompts_replies_and_model() {
+        let history = "# aider chat started at 2024-05-14 09:12:03\n\n> Aider v0.50.0\n> Main model: gpt-4o with diff edit format\n\n#### add a --verbose flag\n#### to the cli\n\nHere is the change:\n\n```python\n#### not a prompt\n> not output\n```\n\n> Applied edit to cli.py\n\n# aider chat started at 2024-05-15 10:00:00\n\n#### now the README\n\nDone.\n";
+        let sessions = parse_chat_history(history);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, "20240514-091203");
+        assert_eq!(sessions[0].model.as_deref(), Some("gpt-4o"));
+        assert_eq!(sessions[0].prompts, vec!["add a --verbose flag\nto the cli"]);
+        assert_eq!(
+            sessions[0].replies,
+            vec!["Here is the change:\n\n```python\n#### not a prompt\n> not output\n```"]
+        );
+        assert_eq!(sessions[1].replies, vec!["Done."]);
+
+        let inputs = parse_input_history("\n# 2024-05-14 09:12:10.1\n+first\n+second line\n\n# 2024-05-14 09:13:00.2\n+/quit\n");
+        assert_eq!(inputs, vec!["first\nsecond line", "/quit"]);
+    }
+}
@@ -0,0 +1,485 @@
+//! Claude Code writes one JSONL transcript per session under
+//! `~/.claude/projects/<escaped project path>/`. User and assistant lines
+//! carry the cwd, the model, tool calls and results as content blocks, and
+//! token usage; `summary` lines hold the titles Claude Code generates.
+
+use super::common::{
+    chunk_paths, collect_from_roots, collect_paths, display_roots, find_latest_by_cwd, has_extension, max_file_size, read_jsonl_lines,
+    scan_roots, select_by_id, SCAN_CHUNK,
+};
+use super::AgentAdapter;
+use crate::agents::{
+    self, extract_text, file_modified_iso, first_lines_model, join_messages, parse_iso_millis, session_meta, session_title,
+    substantive_prompt, value_text, within_span, BridgeErrorCode, CwdMatch, MessageWindow, ParsedContent, ReadOptions,
+    SearchQuery, Session, TimeSpan, Timing, TokenUsage, ToolUsage, TranscriptMessage, TurnKind,
+};
+use crate::index::{SessionIndex, SessionMeta};
+use crate::redact::redact_sensitive_text;
+use crate::utils::{expand_home, normalize_path};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct ClaudeAdapter;
+
+impl AgentAdapter for ClaudeAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let base_dirs = base_dirs();
+        if !base_dirs.iter().any(|dir| dir.exists()) {
+            return Err(BridgeErrorCode::NotFound.error(format!("Claude projects directory not found: {}", display_roots(&base_dirs))));
+        }
+
+        let mut warnings = Vec::new();
+        let files = collect_from_roots(&base_dirs, true, &|file_path| has_extension(file_path, "jsonl"))?;
+        let target_file = if let Some(id_value) = options.id.as_deref() {
+            select_by_id(&files, id_value, options, "Claude")?
+        } else {
+            if files.is_empty() {
+                return Err(BridgeErrorCode::NotFound.error("No Claude session found."));
+            }
+
+            let expected_cwd = normalize_path(&options.cwd)?;
+            if let Some(scoped) = find_latest_by_cwd(&files, &expected_cwd, options.cwd_match, &mut SessionIndex::load("claude"), scan_meta) {
+                scoped
+            } else {
+                warnings.push(format!(
+                    "Warning: no Claude session matched cwd {}; falling back to latest session.",
+                    expected_cwd.display()
+                ));
+                files[0].path.clone()
+            }
+        };
+
+        let mut parsed = parse_file(&target_file, options.window(), options.span)?;
+        warnings.append(&mut parsed.warnings);
+        Ok(ParsedContent { warnings, ..parsed }.into_session("claude", target_file.to_string_lossy().to_string()))
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let files = collect_from_roots(&base_dirs(), true, &|p| has_extension(p, "jsonl"))?;
+        let expected_cwd = cwd.map(normalize_path).transpose()?;
+        let mut index = SessionIndex::load("claude");
+        let mut entries = Vec::new();
+        let files = files.chunks(SCAN_CHUNK).flat_map(|chunk| {
+            let metas = index.lookup_many(&chunk_paths(chunk), scan_meta);
+            chunk.iter().zip(metas).collect::<Vec<_>>()
+        });
+        for (file, meta) in files {
+            let file_cwd = meta.cwd.map(PathBuf::from);
+            if let Some(expected) = expected_cwd.as_ref() {
+                if !file_cwd.as_ref().map(|c| cwd_match.matches(c, expected)).unwrap_or(false) {
+                    continue;
+                }
+            }
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            entries.push(serde_json::json!({
+                "session_id": session_id,
+                "agent": "claude",
+                "cwd": file_cwd.map(|p| p.to_string_lossy().to_string()),
+                "model": meta.model,
+                "modified_at": file_modified_iso(&file.path),
+                "file_path": file.path.to_string_lossy().to_string(),
+                "title": meta.title,
+                "summary": meta.summary,
+            }));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let files = collect_from_roots(&base_dirs(), true, &|p| has_extension(p, "jsonl"))?;
+        let expected_cwd = cwd.map(normalize_path).transpose()?;
+        let mut index = SessionIndex::load("claude");
+        let mut entries = Vec::new();
+        let files = files.chunks(SCAN_CHUNK).flat_map(|chunk| {
+            let metas = index.lookup_many(&chunk_paths(chunk), scan_meta);
+            chunk.iter().zip(metas).collect::<Vec<_>>()
+        });
+
+        for (file, meta) in files {
+            if entries.len() >= limit { break; }
+
+            let file_cwd = meta.cwd.map(PathBuf::from);
+            if let Some(expected) = expected_cwd.as_ref() {
+                if !file_cwd.as_ref().map(|c| cwd_match.matches(c, expected)).unwrap_or(false) {
+                    continue;
+                }
+            }
+
+            if fs::metadata(&file.path).map(|m| m.len() > max_file_size()).unwrap_or(false) {
+                continue;
+            }
+
+            let content = match fs::read_to_string(&file.path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            let cwd_text = file_cwd.as_ref().map(|p| p.to_string_lossy().to_string());
+            if query.matches_session("claude", &content, Some(&session_id), cwd_text.as_deref()) {
+                entries.push(serde_json::json!({
+                    "session_id": session_id,
+                    "agent": "claude",
+                    "cwd": cwd_text,
+                    "modified_at": file_modified_iso(&file.path),
+                    "file_path": file.path.to_string_lossy().to_string(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        let lines = text.lines().map(str::to_string).collect();
+        Ok(parse_lines(lines, Path::new(name), None, window, None)?.into_session("claude", name.to_string()))
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        first_user_prompt(agents::jsonl_values(text))
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        agents::jsonl_values(text)
+            .filter(|json| json["isMeta"] != true)
+            .filter_map(|json| {
+                let content = &json["message"]["content"];
+                let (role, text) = match json["type"].as_str() {
+                    Some("user") => ("user", substantive_prompt(&message_text(content))?),
+                    Some("assistant") => ("assistant", message_text(content)),
+                    _ => return None,
+                };
+                Some(TranscriptMessage::new(role, text, &json["timestamp"]))
+            })
+            .collect()
+    }
+
+    fn tool_texts(&self, text: &str) -> Vec<String> {
+        agents::jsonl_values(text)
+            .flat_map(|json| json["message"]["content"].as_array().cloned().unwrap_or_default())
+            .filter_map(|item| match item["type"].as_str()? {
+                "tool_use" => Some(format!("{} {}", item["name"].as_str().unwrap_or(""), value_text(&item["input"])?)),
+                "tool_result" => Some(extract_text(&item["content"])),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn supports_time_span(&self) -> bool {
+        true
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        base_dirs()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        collect_paths(&base_dirs(), &|path| has_extension(path, "jsonl"))
+    }
+}
+
+fn base_dirs() -> Vec<PathBuf> {
+    scan_roots(
+        "claude",
+        "BRIDGE_CLAUDE_PROJECTS_DIR",
+        expand_home("~/.claude/projects").unwrap_or_else(|| PathBuf::from("~/.claude/projects")),
+    )
+}
+
+fn first_user_prompt(entries: impl Iterator<Item = Value>) -> Option<String> {
+    entries
+        .filter(|json| json["type"] == "user" && json["isMeta"] != true)
+        .find_map(|json| substantive_prompt(&message_text(&json["message"]["content"])))
+}
+
+fn parse_file(path: &Path, window: MessageWindow, span: Option<TimeSpan>) -> Result<ParsedContent> {
+    parse_lines(read_jsonl_lines(path)?, path, file_modified_iso(path), window, span)
+}
+
+fn parse_lines(
+    lines: Vec<String>,
+    path: &Path,
+    timestamp: Option<String>,
+    window: MessageWindow,
+    span: Option<TimeSpan>,
+) -> Result<ParsedContent> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut stamps: Vec<Option<i64>> = Vec::new();
+    let mut skipped = 0usize;
+    let mut session_cwd: Option<String> = None;
+    let mut tools = ToolUsage::default();
+    let mut timing = Timing::default();
+    let mut model: Option<String> = None;
+    let mut tokens = TokenUsage::default();
+    let mut usage_message_ids: HashSet<String> = HashSet::new();
+
+    for line in &lines {
+        match serde_json::from_str::<Value>(line) {
+            Ok(json) => {
+                // Claude Code splits one API response across several lines that
+                // repeat the same usage object; count each message id once.
+                if json["type"] == "assistant" && json["message"]["usage"].is_object() {
+                    let first_sighting = json["message"]["id"]
+                        .as_str()
+                        .map(|id| usage_message_ids.insert(id.to_string()))
+                        .unwrap_or(true);
+                    if first_sighting {
+                        tokens.add_claude(&json["message"]["usage"]);
+                    }
+                }
+                if let Some(cwd) = json["cwd"].as_str() {
+                    if session_cwd.is_none() {
+                        session_cwd = Some(cwd.to_string());
+                    }
+                }
+
+                let message = if json.get("message").is_some() {
+                    &json["message"]
+                } else {
+                    &json
+                };
+
+                let items = message["content"].as_array();
+                let has_text = message["content"].is_string()
+                    || items.into_iter().flatten().any(|item| item["type"] == "text");
+                let kind = match (json["type"].as_str(), message["role"].as_str()) {
+                    (Some("user"), _) | (_, Some("user")) if has_text => TurnKind::Prompt,
+                    (Some("assistant"), _) | (_, Some("assistant")) if has_text => TurnKind::Reply,
+                    _ => TurnKind::Other,
+                };
+                timing.observe(json["timestamp"].as_str(), kind);
+                if let Some(found) = line_model(&json) {
+                    model = Some(found);
+                }
+
+                for item in items.into_iter().flatten() {
+                    match item["type"].as_str().unwrap_or("") {
+                        "tool_use" => {
+                            tools.record_call(item["id"].as_str(), item["name"].as_str().unwrap_or("unknown"));
+                        }
+                        "tool_result" => {
+                            tools.record_result(item["tool_use_id"].as_str(), item["is_error"] == true);
+                        }
+                        _ => {}
+                    }
+                }
+
+                let is_assistant = json["type"] == "assistant"
+                    || message["role"]
+                        .as_str()
+                        .map(|role| role.eq_ignore_ascii_case("assistant"))
+                        .unwrap_or(false);
+
+                if !is_assistant {
+                    continue;
+                }
+
+                let content_field = if message.get("content").is_some() {
+                    &message["content"]
+                } else {
+                    &json["content"]
+                };
+                let text = message_text(content_field);
+                if !text.is_empty() {
+                    messages.push(text);
+                    stamps.push(json["timestamp"].as_str().and_then(parse_iso_millis));
+                }
+            }
+            Err(_) => skipped += 1,
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if skipped > 0 {
+        warnings.push(format!(
+            "Warning: skipped {} unparseable line(s) in {}",
+            skipped,
+            path.display()
+        ));
+    }
+
+    let message_count = messages.len();
+    let session_id = path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string());
+    let tool_usage = Some(tools.into_value());
+    let timing = timing.into_value();
+    let usage = Some(tokens.into_value());
+
+    if !messages.is_empty() {
+        if span.is_some() || window.is_multi() {
+            let kept = within_span(&messages, &stamps, span);
+            let selected = window.select(&kept);
+            let messages_returned = selected.len();
+            let texts = selected.iter().map(|text| redact_sensitive_text(text)).collect::<Vec<String>>();
+            return Ok(ParsedContent {
+                content: join_messages(&texts),
+                messages: texts,
+                warnings,
+                session_id,
+                cwd: session_cwd,
+                timestamp,
+                message_count,
+                messages_returned,
+                tool_usage,
+                timing,
+                model,
+                usage,
+            });
+        }
+        let content = redact_sensitive_text(messages.last().unwrap());
+        return Ok(ParsedContent {
+            content: content.clone(),
+            messages: vec![content],
+            warnings,
+            session_id,
+            cwd: session_cwd,
+            timestamp,
+            message_count,
+            messages_returned: 1,
+            tool_usage,
+            timing,
+            model,
+            usage,
+        });
+    }
+
+    Ok(ParsedContent {
+        content: redact_sensitive_text(&format!(
+            "Could not extract assistant messages. Showing last 20 raw lines:\n{}",
+            lines
+                .iter()
+                .rev()
+                .take(20)
+                .cloned()
+                .collect::<Vec<String>>()
+                .into_iter()
+                .rev()
+                .collect::<Vec<String>>()
+                .join("\n")
+        )),
+        messages: Vec::new(),
+        warnings,
+        session_id,
+        cwd: session_cwd,
+        timestamp,
+        message_count,
+        messages_returned: 0,
+        tool_usage,
+        timing,
+        model,
+        usage,
+    })
+}
+
+fn message_text(value: &Value) -> String {
+    if let Some(raw) = value.as_str() {
+        return raw.to_string();
+    }
+
+    if let Some(parts) = value.as_array() {
+        return parts
+            .iter()
+            .filter_map(|part| {
+                if part["type"].as_str().unwrap_or("") == "text" {
+                    Some(part["text"].as_str().unwrap_or(""))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<&str>>()
+            .join("");
+    }
+
+    String::new()
+}
+
+fn lines_cwd(lines: &[String]) -> Option<PathBuf> {
+    lines
+        .iter()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .find_map(|json| normalize_path(json["cwd"].as_str()?).ok())
+}
+
+fn scan_meta(file_path: &Path) -> SessionMeta {
+    let Ok(lines) = read_jsonl_lines(file_path) else {
+        return SessionMeta::default();
+    };
+    let cwd = lines_cwd(&lines);
+    let model = first_lines_model(&lines, line_model);
+    let entries = || lines.iter().filter_map(|line| serde_json::from_str::<Value>(line).ok());
+    let title = first_user_prompt(entries()).map(|prompt| session_title(&redact_sensitive_text(&prompt)));
+    // Claude appends a new summary entry as the conversation grows; the last is current.
+    let summary = entries()
+        .filter(|json| json["type"] == "summary")
+        .filter_map(|json| json["summary"].as_str().map(redact_sensitive_text))
+        .next_back();
+    let parsed = parse_lines(lines, file_path, file_modified_iso(file_path), MessageWindow::Last(1), None).ok();
+    SessionMeta { title, summary, ..session_meta(cwd, model, parsed) }
+}
+
+fn line_model(json: &Value) -> Option<String> {
+    let model = json["message"]["model"].as_str()?;
+    // Claude Code writes "<synthetic>" for locally generated messages.
+    if json["type"] != "assistant" || model.starts_with('<') {
+        return None;
+    }
+    Some(model.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_lines, scan_meta};
+    use crate::agents::{parse_time_bound, MessageRange, MessageWindow, TimeSpan};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn time_span_keeps_replies_inside_bounds() {
+        let line = |ts: &str, text: &str| {
+            format!(r#"{{"type":"assistant","timestamp":"{}","message":{{"role":"assistant","content":"{}"}}}}"#, ts, text)
+        };
+        let lines = vec![
+            line("2026-01-15T09:00:00Z", "early"),
+            line("2026-01-15T10:30:00Z", "middle"),
+            line("2026-01-15T12:00:00Z", "late"),
+        ];
+        let span = TimeSpan {
+            since: Some(parse_time_bound("2026-01-15T10:00:00Z").unwrap()),
+            until: Some(parse_time_bound("2026-01-15T11:00:00+00:00").unwrap()),
+        };
+        let all = MessageWindow::Range(MessageRange { from: 0, to: None });
+        let parsed = parse_lines(lines, Path::new("s.jsonl"), None, all, Some(span)).unwrap();
+        assert_eq!(parsed.content, "middle");
+        assert_eq!(parsed.message_count, 3);
+
+        assert_eq!(parse_time_bound("2026-01-15").unwrap(), span.since.unwrap() - 10 * 3600 * 1000);
+        assert!(parse_time_bound("2h").unwrap() < parse_time_bound("1h").unwrap());
+        assert!(parse_time_bound("soon").is_err());
+    }
+    #[test]
+    fn list_title_is_the_first_real_prompt_and_summary_the_latest() {
+        let path = std::env::temp_dir().join(format!("bridge-claude-meta-{}.jsonl", std::process::id()));
+        let lines = [
+            r#"{"type":"summary","summary":"Early draft"}"#,
+            r#"{"type":"user","isMeta":true,"message":{"content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","cwd":"/work/app","message":{"content":"Add retries to the upload client"}}"#,
+            r#"{"type":"assistant","message":{"content":"Done."}}"#,
+            r#"{"type":"summary","summary":"Upload client retries"}"#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+        let meta = scan_meta(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(meta.title.as_deref(), Some("Add retries to the upload client"));
+        assert_eq!(meta.summary.as_deref(), Some("Upload client retries"));
+    }
+}
@@ -0,0 +1,455 @@
+//! Cline and its Roo Code fork store each task in the extension's VS Code
+//! globalStorage as `tasks/<task-id>/`, with the model transcript in
+//! `api_conversation_history.json` and the UI event log in `ui_messages.json`.
+//! A task is a session; its id is the task folder name. The cwd comes from
+//! the workspace metadata the extension records with the task.
+
+use super::{common, AgentAdapter};
+use crate::agents::{
+    self, BridgeErrorCode, CwdMatch, MessageWindow, ReadOptions, SearchQuery, Session, TranscriptMessage, TurnKind,
+};
+use crate::redact::redact_sensitive_text;
+use crate::utils::normalize_path;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct ClineAdapter;
+
+const API_HISTORY: &str = "api_conversation_history.json";
+const UI_MESSAGES: &str = "ui_messages.json";
+/// Extension ids of Cline and Roo Code.
+const EXTENSIONS: &[&str] = &["saoudrizwan.claude-dev", "rooveterinaryinc.roo-cline"];
+/// VS Code builds whose globalStorage may hold the extensions.
+const EDITORS: &[&str] = &["Code", "Code - Insiders", "VSCodium", "Cursor", "Windsurf"];
+/// Markers the extensions put in front of the workspace path in the
+/// `<environment_details>` block of user turns.
+const CWD_MARKERS: &[&str] = &["Current Working Directory (", "Current Workspace Directory ("];
+
+#[derive(Debug, Default)]
+struct Task {
+    id: String,
+    source: PathBuf,
+    cwd: Option<PathBuf>,
+    model: Option<String>,
+    prompts: Vec<String>,
+    replies: Vec<String>,
+    order: Vec<TurnKind>,
+    from_ui_log: bool,
+}
+
+impl AgentAdapter for ClineAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let dirs = task_dirs()?;
+        if dirs.is_empty() {
+            return Err(BridgeErrorCode::NotFound.error(format!(
+                "No Cline session found. Task storage not found: {}",
+                display(&base_dirs())
+            )));
+        }
+        let mut warnings = Vec::new();
+        let task = match options.id.as_deref() {
+            Some(id) => select_by_id(&dirs, id, options)?,
+            None => {
+                let expected = normalize_path(&options.cwd)?;
+                let scoped = dirs.iter().find_map(|dir| {
+                    load_task(dir)
+                        .ok()
+                        .filter(|task| task.cwd.as_ref().is_some_and(|cwd| options.cwd_match.matches(cwd, &expected)))
+                });
+                match scoped {
+                    Some(task) => task,
+                    None => {
+                        warnings.push(format!(
+                            "Warning: no Cline session matched cwd {}; falling back to latest session.",
+                            expected.display()
+                        ));
+                        load_task(&dirs[0])?
+                    }
+                }
+            }
+        };
+        if task.from_ui_log {
+            warnings.push(format!("Warning: no {} for task {}; read {} instead.", API_HISTORY, task.id, UI_MESSAGES));
+        }
+        let mut session = to_session(&task, options.window())?;
+        session.warnings = warnings;
+        Ok(session)
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for dir in task_dirs()? {
+            let Ok(task) = load_task(&dir) else {
+                continue;
+            };
+            if !in_scope(&task, expected.as_deref(), cwd_match) {
+                continue;
+            }
+            entries.push(json!({
+                "session_id": task.id,
+                "agent": "cline",
+                "cwd": task.cwd.map(|cwd| cwd.to_string_lossy().to_string()),
+                "model": task.model,
+                "modified_at": agents::file_modified_iso(&task.source),
+                "file_path": task.source.to_string_lossy(),
+            }));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for dir in task_dirs()? {
+            if entries.len() >= limit {
+                break;
+            }
+            let Ok(task) = load_task(&dir) else {
+                continue;
+            };
+            if !in_scope(&task, expected.as_deref(), cwd_match) {
+                continue;
+            }
+            let cwd_text = task.cwd.as_ref().map(|cwd| cwd.to_string_lossy().to_string());
+            let matches = query.matches_turns(&task.prompts, &task.replies, Some(&task.id), cwd_text.as_deref());
+            if matches {
+                entries.push(json!({
+                    "session_id": task.id,
+                    "agent": "cline",
+                    "cwd": cwd_text,
+                    "modified_at": agents::file_modified_iso(&task.source),
+                    "file_path": task.source.to_string_lossy(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        let json: Value = serde_json::from_str(text)
+            .map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to parse {}: {}", name, err)))?;
+        let mut task = parse_transcript(name, &json);
+        task.source = PathBuf::from(name);
+        let mut session = to_session(&task, window)?;
+        session.timestamp = None;
+        Ok(session)
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        let json: Value = serde_json::from_str(text).ok()?;
+        parse_api_history(&json)
+            .prompts
+            .into_iter()
+            .next()
+            .or_else(|| parse_ui_messages(&json).prompts.into_iter().next())
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        let Ok(json) = serde_json::from_str::<Value>(text) else {
+            return Vec::new();
+        };
+        let mut task = parse_api_history(&json);
+        if task.order.is_empty() {
+            task = parse_ui_messages(&json);
+        }
+        agents::interleave(&task.prompts, &task.replies, &task.order)
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        base_dirs()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        session_files()
+    }
+}
+
+/// Task storage roots: `--data-dir`, `BRIDGE_CLINE_DATA_DIR`, or
+/// `[scan_roots] cline`, else each editor's `globalStorage/<extension>/tasks`.
+fn base_dirs() -> Vec<PathBuf> {
+    common::configured_roots("cline", "BRIDGE_CLINE_DATA_DIR").unwrap_or_else(|| {
+        let Some(config) = dirs::config_dir() else {
+            return Vec::new();
+        };
+        EDITORS
+            .iter()
+            .flat_map(|editor| {
+                let storage = config.join(editor).join("User").join("globalStorage");
+                EXTENSIONS.iter().map(move |extension| storage.join(extension).join("tasks"))
+            })
+            .collect()
+    })
+}
+
+/// Task folders holding a transcript, newest first.
+fn task_dirs() -> Result<Vec<PathBuf>> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for file in session_files()? {
+        if let Some(dir) = file.parent().filter(|dir| !dirs.iter().any(|seen| seen == dir)) {
+            dirs.push(dir.to_path_buf());
+        }
+    }
+    Ok(dirs)
+}
+
+/// Every task transcript file under the Cline roots, newest first.
+fn session_files() -> Result<Vec<PathBuf>> {
+    common::collect_paths(&base_dirs(), &|path| {
+        path.file_name().is_some_and(|name| name == API_HISTORY || name == UI_MESSAGES)
+    })
+}
+
+fn select_by_id(dirs: &[PathBuf], id: &str, options: &ReadOptions) -> Result<Task> {
+    let candidates = dirs
+        .iter()
+        .filter(|dir| options.id_match.matches(&task_id(dir), id))
+        .collect::<Vec<_>>();
+    load_task(common::pick_by_id(candidates, id, options, "Cline", |dir| task_id(dir))?)
+}
+
+fn task_id(dir: &Path) -> String {
+    dir.file_name().and_then(|name| name.to_str()).unwrap_or("unknown").to_string()
+}
+
+fn in_scope(task: &Task, expected: Option<&Path>, cwd_match: CwdMatch) -> bool {
+    match expected {
+        None => true,
+        Some(expected) => task.cwd.as_ref().is_some_and(|cwd| cwd_match.matches(cwd, expected)),
+    }
+}
+
+fn display(roots: &[PathBuf]) -> String {
+    roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    if fs::metadata(path).map(|meta| meta.len() > common::max_file_size()).unwrap_or(false) {
+        return Err(anyhow!(
+            "Skipped {} (exceeds {}MB size limit)",
+            path.display(),
+            common::max_file_size() / (1024 * 1024)
+        ));
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to read {}: {}", path.display(), err)))?;
+    serde_json::from_str(&text).map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to parse {}: {}", path.display(), err)))
+}
+
+/// Load a task from the API history, or from the UI log when the history is
+/// missing. Model and cwd also come from the task's metadata files.
+fn load_task(dir: &Path) -> Result<Task> {
+    let api = dir.join(API_HISTORY);
+    let mut task = if api.is_file() {
+        let mut task = parse_api_history(&read_json(&api)?);
+        task.source = api;
+        task
+    } else {
+        let ui = dir.join(UI_MESSAGES);
+        let mut task = parse_ui_messages(&read_json(&ui)?);
+        task.source = ui;
+        task.from_ui_log = true;
+        task
+    };
+    task.id = task_id(dir);
+
+    // Cline records the models used per task; Roo Code keeps a history item
+    // with the workspace it ran in.
+    if let Ok(metadata) = read_json(&dir.join("task_metadata.json")) {
+        if let Some(model) = metadata["model_usage"]
+            .as_array()
+            .and_then(|usage| usage.last())
+            .and_then(|entry| entry["model_id"].as_str())
+        {
+            task.model = Some(model.to_string());
+        }
+    }
+    if task.cwd.is_none() {
+        if let Ok(item) = read_json(&dir.join("history_item.json")) {
+            task.cwd = ["workspace", "cwd"]
+                .iter()
+                .find_map(|key| item[*key].as_str())
+                .and_then(|cwd| normalize_path(cwd).ok());
+        }
+    }
+    Ok(task)
+}
+
+/// Anthropic-format messages: `[{"role", "content": string | blocks}]`.
+/// Only text blocks count; tool calls and results are skipped.
+fn parse_api_history(json: &Value) -> Task {
+    let mut task = Task::default();
+    for message in json.as_array().into_iter().flatten() {
+        let text = text_blocks(&message["content"]);
+        match message["role"].as_str() {
+            Some("user") => {
+                if task.cwd.is_none() {
+                    task.cwd = environment_cwd(&text);
+                }
+                if let Some(prompt) = user_text(&text) {
+                    task.prompts.push(prompt);
+                    task.order.push(TurnKind::Prompt);
+                }
+            }
+            Some("assistant") if !text.trim().is_empty() => {
+                task.replies.push(text.trim().to_string());
+                task.order.push(TurnKind::Reply);
+            }
+            _ => {}
+        }
+    }
+    task
+}
+
+/// UI event log: `say: "task"` and `say: "user_feedback"` are user input;
+/// `say: "text"` and `say: "completion_result"` are assistant output.
+fn parse_ui_messages(json: &Value) -> Task {
+    let mut task = Task::default();
+    for event in json.as_array().into_iter().flatten() {
+        let text = event["text"].as_str().unwrap_or("").trim();
+        if text.is_empty() || event["type"] != "say" {
+            continue;
+        }
+        match event["say"].as_str() {
+            Some("task" | "user_feedback") => {
+                task.prompts.push(text.to_string());
+                task.order.push(TurnKind::Prompt);
+            }
+            Some("text" | "completion_result") => {
+                task.replies.push(text.to_string());
+                task.order.push(TurnKind::Reply);
+            }
+            Some("api_req_started") if task.cwd.is_none() => {
+                let request = serde_json::from_str::<Value>(text).ok();
+                let request = request.as_ref().and_then(|value| value["request"].as_str()).unwrap_or("");
+                task.cwd = environment_cwd(request);
+            }
+            _ => {}
+        }
+    }
+    task
+}
+
+fn text_blocks(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// The workspace path from an `<environment_details>` block, e.g.
+/// `# Current Working Directory (/Users/me/app) Files`.
+fn environment_cwd(text: &str) -> Option<PathBuf> {
+    CWD_MARKERS.iter().find_map(|marker| {
+        let start = text.find(marker)? + marker.len();
+        let end = text[start..].find(')')?;
+        normalize_path(text[start..start + end].trim()).ok()
+    })
+}
+
+/// What the user typed: `<task>` / `<feedback>` tags unwrapped, and the
+/// `<environment_details>` block the extension appends dropped.
+fn user_text(text: &str) -> Option<String> {
+    let text = match text.find("<environment_details>") {
+        Some(start) => &text[..start],
+        None => text,
+    };
+    let mut cleaned = text.trim();
+    for tag in ["task", "feedback", "answer"] {
+        if let Some(inner) = cleaned
+            .strip_prefix(&format!("<{}>", tag))
+            .and_then(|rest| rest.trim_end().strip_suffix(&format!("</{}>", tag)))
+        {
+            cleaned = inner.trim();
+        }
+    }
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
+fn to_session(task: &Task, window: MessageWindow) -> Result<Session> {
+    if task.replies.is_empty() {
+        return Err(BridgeErrorCode::EmptySession.error(format!("Cline session has no messages: {}", task.source.display())));
+    }
+    let selected = window.select(&task.replies).iter().map(|text| redact_sensitive_text(text)).collect::<Vec<_>>();
+    let messages_returned = selected.len();
+    Ok(Session {
+        agent: "cline",
+        content: agents::join_messages(&selected),
+        messages: selected,
+        source: task.source.to_string_lossy().to_string(),
+        warnings: Vec::new(),
+        session_id: (!task.id.is_empty()).then(|| task.id.clone()),
+        cwd: task.cwd.as_ref().map(|cwd| cwd.to_string_lossy().to_string()),
+        timestamp: agents::file_modified_iso(&task.source),
+        message_count: task.replies.len(),
+        messages_returned,
+        tool_usage: None,
+        timing: None,
+        model: task.model.clone(),
+        usage: None,
+    })
+}
+
+fn parse_transcript(name: &str, json: &Value) -> Task {
+    if name.ends_with(UI_MESSAGES) {
+        parse_ui_messages(json)
+    } else {
+        parse_api_history(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_api_history, parse_ui_messages};
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parses_api_history_and_ui_log() {
+        let history = json!([
+            {"role": "user", "content": [
+                {"type": "text", "text": "<task>\nAdd retries\n</task>"},
+                {"type": "text", "text": "<environment_details>\n# Current Working Directory (/work/app) Files\nsrc/\n</environment_details>"}
+            ]},
+            {"role": "assistant", "content": [
+                {"type": "text", "text": "I'll read the client first."},
+                {"type": "tool_use", "name": "read_file", "input": {"path": "client.rs"}}
+            ]},
+            {"role": "user", "content": [{"type": "tool_result", "content": "fn main() {}"}]},
+            {"role": "assistant", "content": "Retries added."}
+        ]);
+        let task = parse_api_history(&history);
+        assert_eq!(task.prompts, vec!["Add retries"]);
+        assert_eq!(task.replies, vec!["I'll read the client first.", "Retries added."]);
+        assert_eq!(task.cwd, Some(PathBuf::from("/work/app")));
+
+        let ui = json!([
+            {"type": "say", "say": "task", "text": "Add retries"},
+            {"type": "say", "say": "api_req_started", "text": "{\"request\":\"# Current Workspace Directory (/work/roo) Files\"}"},
+            {"type": "say", "say": "text", "text": "Working on it"},
+            {"type": "ask", "ask": "followup", "text": "Which client?"},
+            {"type": "say", "say": "completion_result", "text": "Done"}
+        ]);
+        let task = parse_ui_messages(&ui);
+        assert_eq!(task.prompts, vec!["Add retries"]);
+        assert_eq!(task.replies, vec!["Working on it", "Done"]);
+        assert_eq!(task.cwd, Some(PathBuf::from("/work/roo")));
+    }
+}
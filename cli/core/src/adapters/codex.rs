@@ -0,0 +1,571 @@
+//! Codex CLI writes one JSONL rollout per session under
+//! `~/.codex/sessions/YYYY/MM/DD/`, and moves sessions archived from its UI
+//! to `~/.codex/archived_sessions/`. Each line is a `{type, payload}` record:
+//! `session_meta` (id, cwd), `turn_context` (model), `response_item` (messages
+//! and tool traffic), and `event_msg` (token counts, compaction). Older
+//! rollouts hold bare items instead; `normalize_record` upgrades them.
+
+use super::common::{
+    chunk_paths, collect_from_roots, collect_paths, find_latest_by_cwd, has_extension, max_file_size, read_jsonl_lines, scan_roots,
+    select_by_id, SCAN_CHUNK,
+};
+use super::AgentAdapter;
+use crate::agents::{
+    self, extract_text, file_modified_iso, first_lines_model, join_messages, parse_iso_millis, session_meta, substantive_prompt,
+    value_text, within_span, BridgeErrorCode, CwdMatch, MessageWindow, ParsedContent, ReadOptions, SearchQuery, Session, TimeSpan,
+    Timing, TokenUsage, ToolUsage, TranscriptMessage, TurnKind,
+};
+use crate::index::{SessionIndex, SessionMeta};
+use crate::redact::redact_sensitive_text;
+use crate::utils::{expand_home, normalize_path};
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// `--include-archived` for the command being run.
+static INCLUDE_ARCHIVED: AtomicBool = AtomicBool::new(false);
+/// Lines of an older Codex rollout searched for its environment context.
+const CWD_SCAN_LINES: usize = 20;
+
+pub struct CodexAdapter;
+
+impl AgentAdapter for CodexAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let base_dirs = base_dirs();
+        if !base_dirs.iter().any(|dir| dir.exists()) {
+            return Err(BridgeErrorCode::NotFound.error("No Codex session found."));
+        }
+
+        let mut warnings = Vec::new();
+        let target_file = if let Some(id_value) = options.id.as_deref() {
+            // An id names one session, so archived sessions (as `list
+            // --include-archived` shows them) are readable by id too.
+            let files = collect_from_roots(&with_archives(base_dirs), true, &|file_path| has_extension(file_path, "jsonl"))?;
+            select_by_id(&files, id_value, options, "Codex")?
+        } else {
+            let files = collect_from_roots(&base_dirs, true, &|file_path| has_extension(file_path, "jsonl"))?;
+            if files.is_empty() {
+                return Err(BridgeErrorCode::NotFound.error("No Codex session found."));
+            }
+
+            let expected_cwd = normalize_path(&options.cwd)?;
+            if let Some(scoped) = find_latest_by_cwd(&files, &expected_cwd, options.cwd_match, &mut SessionIndex::load("codex"), scan_meta) {
+                scoped
+            } else {
+                warnings.push(format!(
+                    "Warning: no Codex session matched cwd {}; falling back to latest session.",
+                    expected_cwd.display()
+                ));
+                files[0].path.clone()
+            }
+        };
+
+        let mut parsed = parse_file(&target_file, options.window(), options.span)?;
+        warnings.append(&mut parsed.warnings);
+        Ok(ParsedContent { warnings, ..parsed }.into_session("codex", target_file.to_string_lossy().to_string()))
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let files = collect_from_roots(&scan_dirs(), true, &|p| has_extension(p, "jsonl"))?;
+        let expected_cwd = cwd.map(normalize_path).transpose()?;
+        let mut index = SessionIndex::load("codex");
+        let mut entries = Vec::new();
+        let files = files.chunks(SCAN_CHUNK).flat_map(|chunk| {
+            let metas = index.lookup_many(&chunk_paths(chunk), scan_meta);
+            chunk.iter().zip(metas).collect::<Vec<_>>()
+        });
+        for (file, meta) in files {
+            let file_cwd = meta.cwd.map(PathBuf::from);
+            if let Some(expected) = expected_cwd.as_ref() {
+                if !file_cwd.as_ref().map(|c| cwd_match.matches(c, expected)).unwrap_or(false) {
+                    continue;
+                }
+            }
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            entries.push(serde_json::json!({
+                "session_id": session_id,
+                "agent": "codex",
+                "cwd": file_cwd.map(|p| p.to_string_lossy().to_string()),
+                "model": meta.model,
+                "modified_at": file_modified_iso(&file.path),
+                "file_path": file.path.to_string_lossy().to_string(),
+            }));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let files = collect_from_roots(&scan_dirs(), true, &|p| has_extension(p, "jsonl"))?;
+        let expected_cwd = cwd.map(normalize_path).transpose()?;
+        let mut index = SessionIndex::load("codex");
+        let mut entries = Vec::new();
+        let files = files.chunks(SCAN_CHUNK).flat_map(|chunk| {
+            let metas = index.lookup_many(&chunk_paths(chunk), scan_meta);
+            chunk.iter().zip(metas).collect::<Vec<_>>()
+        });
+
+        for (file, meta) in files {
+            if entries.len() >= limit { break; }
+
+            let file_cwd = meta.cwd.map(PathBuf::from);
+            if let Some(expected) = expected_cwd.as_ref() {
+                if !file_cwd.as_ref().map(|c| cwd_match.matches(c, expected)).unwrap_or(false) {
+                    continue;
+                }
+            }
+
+            if fs::metadata(&file.path).map(|m| m.len() > max_file_size()).unwrap_or(false) {
+                continue;
+            }
+
+            let content = match fs::read_to_string(&file.path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            let cwd_text = file_cwd.as_ref().map(|p| p.to_string_lossy().to_string());
+            if query.matches_session("codex", &content, Some(&session_id), cwd_text.as_deref()) {
+                entries.push(serde_json::json!({
+                    "session_id": session_id,
+                    "agent": "codex",
+                    "cwd": cwd_text,
+                    "modified_at": file_modified_iso(&file.path),
+                    "file_path": file.path.to_string_lossy().to_string(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        let lines = text.lines().map(str::to_string).collect();
+        Ok(parse_lines(lines, Path::new(name), None, window, None)?.into_session("codex", name.to_string()))
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        agents::jsonl_values(text)
+            .map(normalize_record)
+            .filter(|json| {
+                json["type"] == "response_item"
+                    && json["payload"]["type"] == "message"
+                    && json["payload"]["role"] == "user"
+            })
+            .find_map(|json| substantive_prompt(&extract_text(&json["payload"]["content"])))
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        let lines = agents::jsonl_values(text).map(normalize_record).collect::<Vec<_>>();
+        let is_item = |json: &Value| json["type"] == "response_item" && json["payload"]["type"] == "message";
+        // Newer logs repeat each reply as an `agent_message` event; only
+        // fall back to those when there are no message items.
+        let has_items = lines.iter().any(|json| is_item(json) && json["payload"]["role"] == "assistant");
+        lines
+            .iter()
+            .filter_map(|json| {
+                let payload = &json["payload"];
+                let (role, text) = if is_item(json) {
+                    match payload["role"].as_str() {
+                        Some("user") => ("user", substantive_prompt(&extract_text(&payload["content"]))?),
+                        Some("assistant") => ("assistant", extract_text(&payload["content"])),
+                        _ => return None,
+                    }
+                } else if !has_items && json["type"] == "event_msg" && payload["type"] == "agent_message" {
+                    ("assistant", extract_text(&payload["message"]))
+                } else if let Some(summary) = compaction_text(json) {
+                    ("assistant", format!("[Context compacted] {}", summary))
+                } else {
+                    return None;
+                };
+                Some(TranscriptMessage::new(role, text, &json["timestamp"]))
+            })
+            .collect()
+    }
+
+    fn tool_texts(&self, text: &str) -> Vec<String> {
+        agents::jsonl_values(text)
+            .map(normalize_record)
+            .filter(|json| json["type"] == "response_item")
+            .filter_map(|json| {
+                let payload = &json["payload"];
+                let name = payload["name"].as_str().unwrap_or("");
+                match payload["type"].as_str()? {
+                    "function_call" => Some(format!("{} {}", name, value_text(&payload["arguments"])?)),
+                    "custom_tool_call" => Some(format!("{} {}", name, value_text(&payload["input"])?)),
+                    "local_shell_call" => value_text(&payload["action"]),
+                    "function_call_output" | "custom_tool_call_output" => value_text(&payload["output"]),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn supports_time_span(&self) -> bool {
+        true
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        base_dirs()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        collect_paths(&base_dirs(), &|path| has_extension(path, "jsonl"))
+    }
+}
+
+fn base_dirs() -> Vec<PathBuf> {
+    scan_roots(
+        "codex",
+        "BRIDGE_CODEX_SESSIONS_DIR",
+        expand_home("~/.codex/sessions").unwrap_or_else(|| PathBuf::from("~/.codex/sessions")),
+    )
+}
+
+/// Make Codex `list` and `search` also scan `archived_sessions`, until set
+/// again.
+pub fn set_include_archived(include: bool) {
+    INCLUDE_ARCHIVED.store(include, Ordering::Relaxed);
+}
+
+/// Codex roots for `list` and `search`: the session roots, plus their
+/// archives under `--include-archived`.
+fn scan_dirs() -> Vec<PathBuf> {
+    let roots = base_dirs();
+    if INCLUDE_ARCHIVED.load(Ordering::Relaxed) {
+        with_archives(roots)
+    } else {
+        roots
+    }
+}
+
+/// `roots` plus the `archived_sessions` directory beside each one, where Codex
+/// moves sessions archived from its UI.
+fn with_archives(mut roots: Vec<PathBuf>) -> Vec<PathBuf> {
+    let archived = roots
+        .iter()
+        .filter_map(|root| root.parent().map(|parent| parent.join("archived_sessions")))
+        .collect::<Vec<_>>();
+    for dir in archived {
+        if !roots.contains(&dir) {
+            roots.push(dir);
+        }
+    }
+    roots
+}
+
+/// Codex tool outputs carry failure either as `success: false`, a JSON
+/// string with `metadata.exit_code`, or a plain `Exit code: N` preamble.
+fn output_failed(output: &Value) -> bool {
+    if let Some(success) = output["success"].as_bool() {
+        return !success;
+    }
+    let Some(text) = output.as_str() else {
+        return false;
+    };
+    if let Ok(parsed) = serde_json::from_str::<Value>(text) {
+        if let Some(code) = parsed["metadata"]["exit_code"].as_i64() {
+            return code != 0;
+        }
+    }
+    text.strip_prefix("Exit code: ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|code| code.parse::<i64>().ok())
+        .map(|code| code != 0)
+        .unwrap_or(false)
+}
+
+/// Record types Codex rollouts held as bare lines before it wrapped them in
+/// `response_item`.
+const LEGACY_ITEMS: &[&str] = &[
+    "message",
+    "reasoning",
+    "function_call",
+    "function_call_output",
+    "custom_tool_call",
+    "custom_tool_call_output",
+    "local_shell_call",
+];
+
+/// A Codex rollout line in the current `{type, payload}` shape. Older rollouts
+/// open with a bare `{id, timestamp, …}` header and hold bare items
+/// (`{"type": "message", …}`); those become `session_meta` and `response_item`
+/// records. Anything else, including their `record_type: state` snapshots,
+/// passes through unchanged.
+fn normalize_record(json: Value) -> Value {
+    if json.get("payload").is_some() {
+        return json;
+    }
+    match json["type"].as_str() {
+        Some(kind) if LEGACY_ITEMS.contains(&kind) => {
+            serde_json::json!({ "type": "response_item", "timestamp": json["timestamp"].clone(), "payload": json })
+        }
+        None if json["id"].is_string() && json.get("record_type").is_none() => {
+            serde_json::json!({ "type": "session_meta", "timestamp": json["timestamp"].clone(), "payload": json })
+        }
+        _ => json,
+    }
+}
+
+/// Text of a Codex compaction record: the summary Codex wrote in place of the
+/// turns it dropped from the model's context.
+fn compaction_text(json: &Value) -> Option<String> {
+    if json["type"] != "compacted" {
+        return None;
+    }
+    let text = extract_text(&json["payload"]["message"]);
+    (!text.trim().is_empty()).then_some(text)
+}
+
+fn parse_file(path: &Path, window: MessageWindow, span: Option<TimeSpan>) -> Result<ParsedContent> {
+    parse_lines(read_jsonl_lines(path)?, path, file_modified_iso(path), window, span)
+}
+
+/// `path` only names the session: it labels warnings and supplies the id
+/// fallback, so in-memory transcripts parse without filesystem access.
+fn parse_lines(
+    lines: Vec<String>,
+    path: &Path,
+    timestamp: Option<String>,
+    window: MessageWindow,
+    span: Option<TimeSpan>,
+) -> Result<ParsedContent> {
+    let mut messages: Vec<Value> = Vec::new();
+    let mut stamps: Vec<Option<i64>> = Vec::new();
+    let mut skipped = 0usize;
+    let mut session_cwd: Option<String> = None;
+    let mut session_id: Option<String> = None;
+    let mut tools = ToolUsage::default();
+    let mut timing = Timing::default();
+    let mut model: Option<String> = None;
+    let mut tokens = TokenUsage::default();
+
+    for line in &lines {
+        match serde_json::from_str::<Value>(line).map(normalize_record) {
+            Ok(json) => {
+                if json["type"] == "event_msg" && json["payload"]["type"] == "token_count" {
+                    let total = &json["payload"]["info"]["total_token_usage"];
+                    if total.is_object() {
+                        tokens.set_codex_total(total);
+                    }
+                }
+                let is_message = json["type"] == "response_item" && json["payload"]["type"] == "message";
+                let kind = if is_message && json["payload"]["role"] == "user" {
+                    TurnKind::Prompt
+                } else if (is_message && json["payload"]["role"] == "assistant")
+                    || (json["type"] == "event_msg" && json["payload"]["type"] == "agent_message")
+                {
+                    TurnKind::Reply
+                } else {
+                    TurnKind::Other
+                };
+                timing.observe(json["timestamp"].as_str(), kind);
+                if let Some(found) = line_model(&json) {
+                    model = Some(found);
+                }
+
+                if json["type"] == "response_item" {
+                    let payload = &json["payload"];
+                    match payload["type"].as_str().unwrap_or("") {
+                        "function_call" | "custom_tool_call" | "local_shell_call" => {
+                            let name = payload["name"].as_str().unwrap_or(
+                                if payload["type"] == "local_shell_call" { "shell" } else { "unknown" },
+                            );
+                            tools.record_call(payload["call_id"].as_str(), name);
+                        }
+                        "function_call_output" | "custom_tool_call_output" => {
+                            tools.record_result(payload["call_id"].as_str(), output_failed(&payload["output"]));
+                        }
+                        _ => {}
+                    }
+                }
+                if json["type"] == "session_meta" {
+                    if let Some(cwd) = json["payload"]["cwd"].as_str() {
+                        session_cwd = Some(cwd.to_string());
+                    }
+                    if let Some(id) = json["payload"]["session_id"].as_str().or(json["payload"]["id"].as_str()) {
+                        session_id = Some(id.to_string());
+                    }
+                }
+                if json["type"] == "response_item" && json["payload"]["type"] == "message" {
+                    messages.push(json["payload"].clone());
+                    stamps.push(json["timestamp"].as_str().and_then(parse_iso_millis));
+                } else if json["type"] == "event_msg" && json["payload"]["type"] == "agent_message" {
+                    let payload = &json["payload"];
+                    messages.push(serde_json::json!({
+                        "role": "assistant",
+                        "content": payload["message"].clone()
+                    }));
+                    stamps.push(json["timestamp"].as_str().and_then(parse_iso_millis));
+                }
+            }
+            Err(_) => skipped += 1,
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if skipped > 0 {
+        warnings.push(format!(
+            "Warning: skipped {} unparseable line(s) in {}",
+            skipped,
+            path.display()
+        ));
+    }
+
+    let message_count = messages.iter().filter(|m| {
+        m["role"].as_str().unwrap_or("").eq_ignore_ascii_case("assistant")
+    }).count();
+
+    let tool_usage = Some(tools.into_value());
+    let timing = timing.into_value();
+    let usage = Some(tokens.into_value());
+
+    if session_id.is_none() {
+        session_id = path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string());
+    }
+    if session_cwd.is_none() {
+        session_cwd = lines_cwd(&lines).map(|cwd| cwd.to_string_lossy().to_string());
+    }
+
+    let assistant_msgs: Vec<&Value> = within_span(&messages, &stamps, span).into_iter().filter(|m| {
+        m["role"].as_str().unwrap_or("").eq_ignore_ascii_case("assistant")
+    }).collect();
+
+    if !messages.is_empty() {
+        if span.is_some() || (window.is_multi() && !assistant_msgs.is_empty()) {
+            let selected = window.select(&assistant_msgs);
+            let messages_returned = selected.len();
+            let texts = selected.iter().map(|m| {
+                let text = extract_text(&m["content"]);
+                if text.is_empty() { "[No text content]".to_string() } else { redact_sensitive_text(&text) }
+            }).collect::<Vec<String>>();
+            return Ok(ParsedContent {
+                content: join_messages(&texts),
+                messages: texts,
+                warnings,
+                session_id,
+                cwd: session_cwd,
+                timestamp,
+                message_count,
+                messages_returned,
+                tool_usage,
+                timing,
+                model,
+                usage,
+            });
+        }
+
+        let selected = assistant_msgs.last().cloned().or_else(|| messages.last());
+        if let Some(message) = selected {
+            let text = extract_text(&message["content"]);
+            let content = if text.is_empty() {
+                "[No text content]".to_string()
+            } else {
+                redact_sensitive_text(&text)
+            };
+            return Ok(ParsedContent {
+                content: content.clone(),
+                messages: vec![content],
+                warnings,
+                session_id,
+                cwd: session_cwd,
+                timestamp,
+                message_count,
+                messages_returned: 1,
+                tool_usage,
+                timing,
+                model,
+                usage,
+            });
+        }
+    }
+
+    Ok(ParsedContent {
+        content: redact_sensitive_text(&format!(
+            "Could not extract structured messages. Showing last 20 raw lines:\n{}",
+            lines
+                .iter()
+                .rev()
+                .take(20)
+                .cloned()
+                .collect::<Vec<String>>()
+                .into_iter()
+                .rev()
+                .collect::<Vec<String>>()
+                .join("\n")
+        )),
+        messages: Vec::new(),
+        warnings,
+        session_id,
+        cwd: session_cwd,
+        timestamp,
+        message_count,
+        messages_returned: 0,
+        tool_usage,
+        timing,
+        model,
+        usage,
+    })
+}
+
+fn lines_cwd(lines: &[String]) -> Option<PathBuf> {
+    let json = normalize_record(serde_json::from_str(lines.first()?).ok()?);
+    if let Some(cwd) = json["payload"]["cwd"].as_str() {
+        return normalize_path(cwd).ok();
+    }
+    // Older rollouts only record the cwd in the injected environment context.
+    lines.iter().take(CWD_SCAN_LINES).find_map(|line| {
+        let json = normalize_record(serde_json::from_str(line).ok()?);
+        if json["payload"]["role"] != "user" {
+            return None;
+        }
+        let text = extract_text(&json["payload"]["content"]);
+        let start = text.find("<cwd>")? + "<cwd>".len();
+        let end = start + text[start..].find("</cwd>")?;
+        normalize_path(text[start..end].trim()).ok()
+    })
+}
+
+/// Index metadata for a Codex session: one read serves the cwd, the first
+/// model, and the parsed message count.
+fn scan_meta(file_path: &Path) -> SessionMeta {
+    let Ok(lines) = read_jsonl_lines(file_path) else {
+        return SessionMeta::default();
+    };
+    let cwd = lines_cwd(&lines);
+    let model = first_lines_model(&lines, line_model);
+    let parsed = parse_lines(lines, file_path, file_modified_iso(file_path), MessageWindow::Last(1), None).ok();
+    session_meta(cwd, model, parsed)
+}
+
+fn line_model(json: &Value) -> Option<String> {
+    if json["type"] != "session_meta" && json["type"] != "turn_context" {
+        return None;
+    }
+    json["payload"]["model"].as_str().map(|m| m.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::output_failed;
+
+    #[test]
+    fn codex_tool_output_failure_forms() {
+        use serde_json::json;
+        assert!(output_failed(&json!("{\"output\":\"\",\"metadata\":{\"exit_code\":2}}")));
+        assert!(!output_failed(&json!("{\"output\":\"ok\",\"metadata\":{\"exit_code\":0}}")));
+        assert!(output_failed(&json!("Exit code: 1\nWall time: 0.2 seconds")));
+        assert!(output_failed(&json!({ "content": "boom", "success": false })));
+        assert!(!output_failed(&json!("plain output")));
+    }
+}
@@ -0,0 +1,453 @@
+//! Helpers every adapter shares: finding session files under the scan
+//! roots (newest first, bounded by the scan and file size limits), picking
+//! one by `--id`, and resolving each agent's store roots from `--data-dir`,
+//! env vars, and the config file.
+
+use crate::agents::{BridgeErrorCode, CwdMatch, ReadOptions};
+use crate::config;
+use crate::index::{SessionIndex, SessionMeta};
+use crate::utils::expand_home;
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+const DEFAULT_MAX_FILE_SIZE_MB: u64 = 50;
+/// `--max-file-size` and `--data-dir` for the command being run; the REPL
+/// runs several per process. 0 means no `--max-file-size`.
+static MAX_FILE_SIZE_FLAG_MB: AtomicU64 = AtomicU64::new(0);
+static DATA_DIR_FLAGS: RwLock<Option<HashMap<&'static str, PathBuf>>> = RwLock::new(None);
+const DEFAULT_MAX_SCAN_FILES: usize = 1000;
+/// Files whose metadata is extracted in parallel before `list`/`search`/`read`
+/// check whether they already have enough matches.
+pub(crate) const SCAN_CHUNK: usize = 64;
+
+/// Set when any directory scan in this process stopped at the file limit.
+static SCAN_TRUNCATED: AtomicBool = AtomicBool::new(false);
+
+/// A session file found by a scan, with its modification time for ordering.
+#[derive(Clone)]
+pub(crate) struct FileEntry {
+    pub(crate) path: PathBuf,
+    pub(crate) mtime_ns: u128,
+}
+
+/// Pick the session whose file stem matches `id` under `options.id_match`.
+/// `files` must already be sorted newest-first. See `pick_by_id`.
+pub(crate) fn select_by_id(files: &[FileEntry], id: &str, options: &ReadOptions, agent_label: &str) -> Result<PathBuf> {
+    let paths = files.iter().map(|file| file.path.clone()).collect::<Vec<_>>();
+    select_path_by_id(&paths, id, options, agent_label)
+}
+
+/// `select_by_id` for adapters that collect plain paths (`collect_paths`).
+pub(crate) fn select_path_by_id(paths: &[PathBuf], id: &str, options: &ReadOptions, agent_label: &str) -> Result<PathBuf> {
+    let candidates = paths
+        .iter()
+        .filter(|path| options.id_match.matches(&file_stem(path), id))
+        .cloned()
+        .collect();
+    pick_by_id(candidates, id, options, agent_label, |path| file_stem(path))
+}
+
+/// The one of `candidates` (sessions `id` matched, newest first) that `id`
+/// selects: the one whose `session_id` equals it, else the only candidate.
+/// Several are an `AMBIGUOUS_ID` error listing them; with
+/// `options.all_matching` an exact id no longer wins, so the error lists
+/// every match.
+pub(crate) fn pick_by_id<T>(
+    mut candidates: Vec<T>,
+    id: &str,
+    options: &ReadOptions,
+    agent_label: &str,
+    session_id: impl Fn(&T) -> String,
+) -> Result<T> {
+    if !options.all_matching {
+        if let Some(exact) = candidates.iter().position(|candidate| session_id(candidate) == id) {
+            return Ok(candidates.swap_remove(exact));
+        }
+    }
+    match candidates.len() {
+        0 => Err(BridgeErrorCode::NotFound.error(format!("No {} session found.", agent_label))),
+        1 => Ok(candidates.remove(0)),
+        _ => Err(ambiguous_id(agent_label, id, candidates.iter().map(session_id).collect())),
+    }
+}
+
+/// The `AMBIGUOUS_ID` error for an `--id` that matched every one of
+/// `candidates` (session ids, newest first) without equalling any. The
+/// message names the first ten; the error carries them all.
+pub(crate) fn ambiguous_id(agent_label: &str, id: &str, candidates: Vec<String>) -> anyhow::Error {
+    let count = candidates.len();
+    let listed = candidates.iter().take(10).map(String::as_str).collect::<Vec<_>>().join(", ");
+    let more = if count > 10 { format!(" (and {} more)", count - 10) } else { String::new() };
+    BridgeErrorCode::AmbiguousId.error_with_candidates(
+        format!(
+            "{} session id '{}' is ambiguous: matches {} sessions: {}{}. Use a longer id or --exact.",
+            agent_label, id, count, listed, more
+        ),
+        candidates,
+    )
+}
+
+/// Resolve a plugin-managed session file under `dir` (newest first, or by
+/// `id`) and read its raw bytes for the plugin to parse.
+#[cfg(feature = "wasm-plugins")]
+pub fn read_plugin_session_file(
+    dir: &Path,
+    extension: Option<&str>,
+    options: &ReadOptions,
+    agent_label: &str,
+) -> Result<(PathBuf, Vec<u8>)> {
+    let files = collect_matching_files(dir, true, &|file_path| {
+        extension.map(|ext| has_extension(file_path, ext)).unwrap_or(true)
+    })?;
+    let target_file = match options.id.as_deref() {
+        Some(id_value) => select_by_id(&files, id_value, options, agent_label)?,
+        None => files
+            .first()
+            .map(|file| file.path.clone())
+            .ok_or_else(|| BridgeErrorCode::NotFound.error(format!("No {} session found.", agent_label)))?,
+    };
+
+    let meta = fs::metadata(&target_file)?;
+    if meta.len() > max_file_size() {
+        return Err(anyhow!(
+            "Skipped {} (exceeds {}MB size limit)",
+            target_file.display(),
+            max_file_size() / (1024 * 1024)
+        ));
+    }
+    let bytes = fs::read(&target_file)?;
+    Ok((target_file, bytes))
+}
+
+pub(crate) fn file_stem(path: &Path) -> String {
+    path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string()
+}
+
+pub(crate) fn read_jsonl_lines(path: &Path) -> Result<Vec<String>> {
+    let meta = fs::metadata(path)?;
+    if meta.len() > max_file_size() {
+        return Err(anyhow!(
+            "Skipped {} (exceeds {}MB size limit)",
+            path.display(),
+            max_file_size() / (1024 * 1024)
+        ));
+    }
+    let file = fs::File::open(path)?;
+    let reader = BufReader::new(file);
+    Ok(reader.lines().map_while(Result::ok).collect())
+}
+
+pub(crate) fn find_latest_by_cwd(
+    files: &[FileEntry],
+    expected_cwd: &Path,
+    cwd_match: CwdMatch,
+    index: &mut SessionIndex,
+    scan: fn(&Path) -> SessionMeta,
+) -> Option<PathBuf> {
+    for chunk in files.chunks(SCAN_CHUNK) {
+        let metas = index.lookup_many(&chunk_paths(chunk), scan);
+        for (file, meta) in chunk.iter().zip(metas) {
+            if let Some(file_cwd) = meta.cwd {
+                if cwd_match.matches(Path::new(&file_cwd), expected_cwd) {
+                    return Some(file.path.clone());
+                }
+            }
+        }
+    }
+    None
+}
+
+pub(crate) fn chunk_paths(chunk: &[FileEntry]) -> Vec<&Path> {
+    chunk.iter().map(|file| file.path.as_path()).collect()
+}
+
+pub(crate) fn collect_matching_files<F>(dir: &Path, recursive: bool, predicate: &F) -> Result<Vec<FileEntry>>
+where
+    F: Fn(&Path) -> bool,
+{
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let limit = max_scan_files();
+    let mut paths = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+
+    'scan: while let Some(current) = stack.pop() {
+        let entries = match fs::read_dir(&current) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_type = match entry.file_type() {
+                Ok(ft) => ft,
+                Err(_) => continue,
+            };
+
+            // Skip symlinks (Phase 6)
+            if file_type.is_symlink() {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                if recursive {
+                    stack.push(path);
+                }
+                continue;
+            }
+
+            if !predicate(&path) {
+                continue;
+            }
+
+            if paths.len() >= limit {
+                SCAN_TRUNCATED.store(true, Ordering::Relaxed);
+                break 'scan;
+            }
+            paths.push(path);
+        }
+    }
+
+    // Directory walking is cheap; the per-file stat is what scales with the store.
+    let mut matches = paths
+        .into_par_iter()
+        .map(|path| {
+            let mtime = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            let mtime_ns = mtime
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            FileEntry { path, mtime_ns }
+        })
+        .collect::<Vec<_>>();
+    sort_files_by_mtime_desc(&mut matches);
+    Ok(matches)
+}
+
+/// `--max-file-size`, or `None` to fall back to the env var and config.
+pub fn set_max_file_size_mb(megabytes: Option<u64>) {
+    MAX_FILE_SIZE_FLAG_MB.store(megabytes.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Largest session file parsed, in bytes: `--max-file-size`, then
+/// `BRIDGE_MAX_FILE_SIZE_MB`, then `[limits] max_file_size_mb` from the config
+/// file, then 50 MB.
+pub(crate) fn max_file_size() -> u64 {
+    let megabytes = Some(MAX_FILE_SIZE_FLAG_MB.load(Ordering::Relaxed))
+        .filter(|value| *value > 0)
+        .or_else(|| {
+            std::env::var("BRIDGE_MAX_FILE_SIZE_MB")
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        })
+        .or_else(|| config::section("limits")["max_file_size_mb"].as_u64())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_FILE_SIZE_MB);
+    megabytes.saturating_mul(1024 * 1024)
+}
+
+/// Most session files one directory scan collects: `BRIDGE_MAX_SCAN_FILES`,
+/// then `[limits] max_scan_files` from the config file, then 1000.
+pub(crate) fn max_scan_files() -> usize {
+    std::env::var("BRIDGE_MAX_SCAN_FILES")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .or_else(|| {
+            config::section("limits")["max_scan_files"]
+                .as_u64()
+                .map(|value| value as usize)
+        })
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_SCAN_FILES)
+}
+
+/// Whether a scan hit the file limit, so results may omit sessions.
+pub fn scan_truncated() -> bool {
+    SCAN_TRUNCATED.load(Ordering::Relaxed)
+}
+
+/// Forget earlier truncated scans, before running another command.
+pub fn reset_scan_truncated() {
+    SCAN_TRUNCATED.store(false, Ordering::Relaxed);
+}
+
+pub fn scan_truncation_warning() -> String {
+    format!(
+        "Warning: session scan stopped after {} files; the newest session may be missing. Raise BRIDGE_MAX_SCAN_FILES to scan more.",
+        max_scan_files()
+    )
+}
+
+/// Paths of the files under `roots` that satisfy `predicate`, newest first.
+pub(crate) fn collect_paths<F>(roots: &[PathBuf], predicate: &F) -> Result<Vec<PathBuf>>
+where
+    F: Fn(&Path) -> bool,
+{
+    Ok(collect_from_roots(roots, true, predicate)?.into_iter().map(|file| file.path).collect())
+}
+
+/// `collect_matching_files` over every scan root, merged newest-first.
+pub(crate) fn collect_from_roots<F>(roots: &[PathBuf], recursive: bool, predicate: &F) -> Result<Vec<FileEntry>>
+where
+    F: Fn(&Path) -> bool,
+{
+    let mut matches = Vec::new();
+    for root in roots {
+        matches.extend(collect_matching_files(root, recursive, predicate)?);
+    }
+    sort_files_by_mtime_desc(&mut matches);
+    Ok(matches)
+}
+
+pub(crate) fn display_roots(roots: &[PathBuf]) -> String {
+    roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn sort_files_by_mtime_desc(files: &mut [FileEntry]) {
+    files.sort_by(|a, b| {
+        b.mtime_ns.cmp(&a.mtime_ns).then_with(|| {
+            a.path
+                .to_string_lossy()
+                .cmp(&b.path.to_string_lossy())
+        })
+    });
+}
+
+pub(crate) fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .and_then(|value| value.to_str())
+        .map(|value| value.eq_ignore_ascii_case(ext))
+        .unwrap_or(false)
+}
+
+/// Per-agent store roots from `--data-dir`, which win over env vars and
+/// config, replacing those of any earlier command.
+pub fn set_data_dirs(dirs: HashMap<&'static str, PathBuf>) {
+    if let Ok(mut flags) = DATA_DIR_FLAGS.write() {
+        *flags = Some(dirs);
+    }
+}
+
+/// Session store roots for `agent`. `--data-dir` wins, then the agent's env
+/// var (a single root), then `[scan_roots] <agent>` from the config file (a
+/// path or a list of paths), then the agent's default location.
+pub(crate) fn scan_roots(agent: &str, env_var: &str, default: PathBuf) -> Vec<PathBuf> {
+    configured_roots(agent, env_var).unwrap_or_else(|| vec![default])
+}
+
+/// The roots `scan_roots` would use before falling back to a default, for
+/// agents that have no default store.
+pub(crate) fn configured_roots(agent: &str, env_var: &str) -> Option<Vec<PathBuf>> {
+    let flagged = DATA_DIR_FLAGS.read().ok().and_then(|flags| flags.as_ref()?.get(agent).cloned());
+    if let Some(root) = flagged {
+        return Some(vec![root]);
+    }
+    if let Some(root) = std::env::var(env_var).ok().and_then(|value| expand_home(&value)) {
+        return Some(vec![root]);
+    }
+    let roots = config_roots(&config::section("scan_roots")[agent]);
+    (!roots.is_empty()).then_some(roots)
+}
+
+/// A `[scan_roots]` entry: one path or a list of paths, `~` expanded.
+fn config_roots(entry: &Value) -> Vec<PathBuf> {
+    let configured = match entry {
+        Value::String(path) => vec![path.as_str()],
+        Value::Array(paths) => paths.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    configured.into_iter().filter_map(expand_home).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        collect_paths, config_roots, configured_roots, has_extension, max_file_size, select_by_id, set_data_dirs, set_max_file_size_mb,
+        FileEntry, DEFAULT_MAX_FILE_SIZE_MB,
+    };
+    use crate::agents::{IdMatch, ReadOptions};
+    use serde_json::json;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn entries(paths: &[&str]) -> Vec<FileEntry> {
+        paths
+            .iter()
+            .map(|p| FileEntry { path: PathBuf::from(p), mtime_ns: 0 })
+            .collect()
+    }
+
+    #[test]
+    fn id_match_prefers_exact_stem_and_reports_ambiguity() {
+        let files = entries(&["/s/abc-123/abc-1234.jsonl", "/s/abc-123.jsonl", "/s/abc-999.jsonl"]);
+        let options = |id_match| ReadOptions { id_match, ..ReadOptions::default() };
+        let exact = select_by_id(&files, "abc-123", &options(IdMatch::Substring), "Codex").unwrap();
+        assert_eq!(exact, PathBuf::from("/s/abc-123.jsonl"));
+
+        let err = select_by_id(&files, "abc", &options(IdMatch::Prefix), "Codex").unwrap_err().to_string();
+        assert!(err.contains("matches 3 sessions"), "got: {}", err);
+        assert!(err.contains("abc-999"), "got: {}", err);
+
+        assert!(select_by_id(&files, "1234", &options(IdMatch::Prefix), "Codex").is_err());
+        assert!(select_by_id(&files, "abc-99", &options(IdMatch::Exact), "Codex").is_err());
+
+        let all = ReadOptions { all_matching: true, ..options(IdMatch::Substring) };
+        let err = select_by_id(&files, "abc-123", &all, "Codex").unwrap_err().to_string();
+        assert!(err.contains("matches 2 sessions"), "got: {}", err);
+    }
+    #[test]
+    fn max_file_size_flag_overrides_the_default_until_cleared() {
+        set_max_file_size_mb(Some(3));
+        let flagged = max_file_size();
+        set_max_file_size_mb(None);
+        assert_eq!(flagged, 3 * 1024 * 1024);
+        assert_eq!(max_file_size(), DEFAULT_MAX_FILE_SIZE_MB * 1024 * 1024);
+    }
+
+    #[test]
+    fn data_dir_flag_wins_over_the_env_var_for_its_agent_only() {
+        std::env::set_var("BRIDGE_TEST_DATA_DIR", "/env/store");
+        set_data_dirs([("flagged-agent", PathBuf::from("/flag/store"))].into_iter().collect());
+        assert_eq!(configured_roots("flagged-agent", "BRIDGE_TEST_DATA_DIR"), Some(vec![PathBuf::from("/flag/store")]));
+        assert_eq!(configured_roots("other-agent", "BRIDGE_TEST_DATA_DIR"), Some(vec![PathBuf::from("/env/store")]));
+    }
+
+    #[test]
+    fn scan_roots_config_takes_one_path_or_a_list() {
+        assert_eq!(config_roots(&json!("/srv/codex")), [PathBuf::from("/srv/codex")]);
+        assert_eq!(config_roots(&json!(["/a", 7, "/b"])), [PathBuf::from("/a"), PathBuf::from("/b")]);
+        assert!(config_roots(&json!(null)).is_empty());
+    }
+
+    #[test]
+    fn sessions_are_collected_from_every_root() {
+        let base = std::env::temp_dir().join(format!("bridge-roots-{}", std::process::id()));
+        let roots = [base.join("laptop"), base.join("backup"), base.join("missing")];
+        fs::create_dir_all(roots[0].join("2026/01")).unwrap();
+        fs::create_dir_all(&roots[1]).unwrap();
+        fs::write(roots[0].join("2026/01/a.jsonl"), "{}").unwrap();
+        fs::write(roots[1].join("b.jsonl"), "{}").unwrap();
+        fs::write(roots[1].join("notes.txt"), "").unwrap();
+
+        let mut found = collect_paths(&roots, &|path| has_extension(path, "jsonl")).unwrap();
+        fs::remove_dir_all(&base).unwrap();
+        found.sort();
+        assert_eq!(found, [roots[1].join("b.jsonl"), roots[0].join("2026/01/a.jsonl")]);
+    }
+}
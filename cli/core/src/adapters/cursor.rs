@@ -0,0 +1,437 @@
+//! Cursor keeps chat state per workspace under
+//! `<Cursor data dir>/User/workspaceStorage/<hash>/`, next to a
+//! `workspace.json` naming the folder the workspace was opened on. Chats are
+//! read from JSON or JSONL exports of that state.
+
+use super::common::{ambiguous_id, collect_from_roots, collect_paths, display_roots, file_stem, max_file_size, scan_roots};
+use super::AgentAdapter;
+use crate::agents::{
+    self, extract_text, file_modified_iso, join_messages, substantive_prompt, BridgeErrorCode, CwdMatch, MessageWindow,
+    ReadOptions, SearchQuery, Session, TranscriptMessage,
+};
+use crate::redact::redact_sensitive_text;
+use crate::utils::{expand_home, normalize_path};
+use anyhow::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct CursorAdapter;
+
+impl AgentAdapter for CursorAdapter {
+    /// Cursor file stems are not unique across workspaces, so `--id` also
+    /// matches `<workspace hash>/<stem>` (see `select_by_workspace_id`).
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let id = options.id.as_deref();
+        let base_dirs = base_dirs();
+        if !base_dirs.iter().any(|dir| dir.exists()) {
+            return Err(BridgeErrorCode::NotFound.error(format!(
+                "No Cursor session found. Data directory not found: {}",
+                display_roots(&base_dirs)
+            )));
+        }
+
+        let workspaces_dirs = workspace_dirs();
+        if !workspaces_dirs.iter().any(|dir| dir.exists()) {
+            return Err(BridgeErrorCode::NotFound.error(format!(
+                "No Cursor session found. Workspace storage not found: {}",
+                display_roots(&workspaces_dirs)
+            )));
+        }
+
+        // Look for composer/chat state files in workspace storage
+        let files = collect_from_roots(&workspaces_dirs, true, &is_chat_file)?;
+
+        if files.is_empty() {
+            return Err(BridgeErrorCode::NotFound.error("No Cursor session found."));
+        }
+
+        let mut warnings = Vec::new();
+        let target_file = if let Some(id) = id {
+            let paths = files.iter().map(|file| file.path.clone()).collect::<Vec<_>>();
+            select_by_workspace_id(&paths, id, options)?
+        } else {
+            let expected_cwd = normalize_path(&options.cwd)?;
+            let scoped = files.iter().find(|file| {
+                workspace_cwd(&file.path).is_some_and(|cwd| options.cwd_match.matches(&cwd, &expected_cwd))
+            });
+            match scoped {
+                Some(file) => file.path.clone(),
+                None => {
+                    warnings.push(format!(
+                        "Warning: no Cursor session matched cwd {}; falling back to latest session.",
+                        expected_cwd.display()
+                    ));
+                    files[0].path.clone()
+                }
+            }
+        };
+
+        let mut session = export_session(
+            &target_file.to_string_lossy(),
+            &fs::read_to_string(&target_file)?,
+            options.window(),
+        );
+        session.warnings = warnings;
+        session.cwd = workspace_cwd(&target_file).map(|cwd| cwd.to_string_lossy().to_string());
+        session.timestamp = file_modified_iso(&target_file);
+        Ok(session)
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let files = collect_from_roots(&workspace_dirs(), true, &is_chat_file)?;
+
+        let expected_cwd = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for file in files {
+            let file_cwd = workspace_cwd(&file.path);
+            if let Some(expected) = expected_cwd.as_ref() {
+                if !file_cwd.as_ref().is_some_and(|c| cwd_match.matches(c, expected)) {
+                    continue;
+                }
+            }
+
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            entries.push(serde_json::json!({
+                "session_id": session_id,
+                "agent": "cursor",
+                "cwd": file_cwd.map(|p| p.to_string_lossy().to_string()),
+                "model": serde_json::Value::Null,
+                "modified_at": file_modified_iso(&file.path),
+                "file_path": file.path.to_string_lossy().to_string(),
+            }));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let files = collect_from_roots(&workspace_dirs(), true, &is_chat_file)?;
+
+        let expected_cwd = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+
+        for file in files {
+            if entries.len() >= limit { break; }
+
+            let file_cwd = workspace_cwd(&file.path);
+            if let Some(expected) = expected_cwd.as_ref() {
+                if !file_cwd.as_ref().is_some_and(|c| cwd_match.matches(c, expected)) {
+                    continue;
+                }
+            }
+
+            if fs::metadata(&file.path).map(|m| m.len() > max_file_size()).unwrap_or(false) {
+                continue;
+            }
+
+            let content = match fs::read_to_string(&file.path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            let cwd_text = file_cwd.as_ref().map(|p| p.to_string_lossy().to_string());
+            if query.matches_session("cursor", &content, Some(&session_id), cwd_text.as_deref()) {
+                entries.push(serde_json::json!({
+                    "session_id": session_id,
+                    "agent": "cursor",
+                    "cwd": cwd_text,
+                    "modified_at": file_modified_iso(&file.path),
+                    "file_path": file.path.to_string_lossy().to_string(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        Ok(export_session(name, text, window))
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        let is_user = |json: &Value| json["role"] == "user";
+        match serde_json::from_str::<Value>(text) {
+            Ok(json) => json["messages"]
+                .as_array()?
+                .iter()
+                .filter(|message| is_user(message))
+                .find_map(|message| substantive_prompt(&extract_text(&message["content"]))),
+            Err(_) => agents::jsonl_values(text)
+                .filter(is_user)
+                .find_map(|json| substantive_prompt(&extract_text(&json["content"]))),
+        }
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        let items = match serde_json::from_str::<Value>(text) {
+            Ok(json) => json["messages"].as_array().cloned().unwrap_or_default(),
+            Err(_) => agents::jsonl_values(text).collect(),
+        };
+        items
+            .iter()
+            .filter_map(|item| {
+                let role = match item["role"].as_str() {
+                    Some("user") => "user",
+                    Some("assistant") => "assistant",
+                    _ => return None,
+                };
+                Some(TranscriptMessage::new(role, extract_text(&item["content"]), &item["timestamp"]))
+            })
+            .collect()
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        base_dirs()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        collect_paths(&workspace_dirs(), &is_chat_file)
+    }
+}
+
+fn base_dirs() -> Vec<PathBuf> {
+    // macOS: ~/Library/Application Support/Cursor
+    // Linux: ~/.cursor
+    let default = if cfg!(target_os = "macos") {
+        dirs::home_dir()
+            .map(|h| h.join("Library/Application Support/Cursor"))
+            .unwrap_or_else(|| PathBuf::from("~/.cursor"))
+    } else {
+        expand_home("~/.cursor").unwrap_or_else(|| PathBuf::from("~/.cursor"))
+    };
+    scan_roots("cursor", "BRIDGE_CURSOR_DATA_DIR", default)
+}
+
+/// Composer/chat state files in Cursor workspace storage.
+fn is_chat_file(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    (name.ends_with(".json") || name.ends_with(".jsonl"))
+        && (name.contains("chat") || name.contains("composer") || name.contains("conversation"))
+}
+
+/// The folder a Cursor chat belongs to, from its workspace storage
+/// directory's `workspace.json`.
+fn workspace_cwd(file: &Path) -> Option<PathBuf> {
+    crate::adapters::windsurf::workspace_cwd(file)
+}
+
+/// `<workspace hash>/<stem>` for a chat file under `workspaceStorage`; the
+/// stem alone repeats across workspaces (every one may have a `chat.json`).
+fn workspace_session_id(path: &Path) -> String {
+    let workspace = path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.parent().and_then(Path::file_name).is_some_and(|name| name == "workspaceStorage"))
+        .or_else(|| path.parent())
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{}/{}", workspace, file_stem(path))
+}
+
+/// `common::select_path_by_id` for Cursor: `id` may name either the stem or
+/// `<workspace hash>/<stem>`. A unique exact match wins (unless
+/// `all_matching`); several matches fail with `AMBIGUOUS_ID`, listing the
+/// `<workspace hash>/<stem>` ids.
+fn select_by_workspace_id(paths: &[PathBuf], id: &str, options: &ReadOptions) -> Result<PathBuf> {
+    let candidates = paths
+        .iter()
+        .map(|path| (path, workspace_session_id(path)))
+        .filter(|(path, key)| options.id_match.matches(key, id) || options.id_match.matches(&file_stem(path), id))
+        .collect::<Vec<_>>();
+
+    let exact = candidates
+        .iter()
+        .filter(|(path, key)| key == id || file_stem(path) == id)
+        .collect::<Vec<_>>();
+    if exact.len() == 1 && !options.all_matching {
+        return Ok(exact[0].0.clone());
+    }
+
+    match candidates.len() {
+        0 => Err(BridgeErrorCode::NotFound.error("No Cursor session found.")),
+        1 => Ok(candidates[0].0.clone()),
+        _ => Err(ambiguous_id("Cursor", id, candidates.into_iter().map(|(_, key)| key).collect())),
+    }
+}
+
+fn workspace_dirs() -> Vec<PathBuf> {
+    base_dirs()
+        .into_iter()
+        .map(|base_dir| base_dir.join("User").join("workspaceStorage"))
+        .collect()
+}
+
+/// Read result for a Cursor chat export's text. `source` names the file and
+/// supplies the session id; cwd and timestamp are left to the caller.
+fn export_session(source: &str, text: &str, window: MessageWindow) -> Session {
+    let (content, messages, message_count, messages_returned) = match replies(text) {
+        Ok(replies) if replies.is_empty() => ("[No assistant messages found]".to_string(), Vec::new(), 0, 0),
+        Ok(replies) => {
+            let selected = window.select(&replies).iter().map(|reply| redact_sensitive_text(reply)).collect::<Vec<_>>();
+            let returned = selected.len();
+            (join_messages(&selected), selected, replies.len(), returned)
+        }
+        Err(raw) => (redact_sensitive_text(&raw), Vec::new(), 0, 0),
+    };
+    Session {
+        agent: "cursor",
+        content,
+        messages,
+        source: source.to_string(),
+        warnings: Vec::new(),
+        session_id: Some(file_stem(Path::new(source))).filter(|stem| !stem.is_empty()),
+        cwd: None,
+        timestamp: None,
+        message_count,
+        messages_returned,
+        tool_usage: None,
+        timing: None,
+        model: None,
+        usage: None,
+    }
+}
+
+/// Assistant replies of a Cursor chat export (JSON or JSONL), oldest first.
+/// A file with no recognizable conversation yields `Err` with the text to show
+/// instead: the pretty-printed JSON, or the raw tail of the file.
+fn replies(content_str: &str) -> Result<Vec<String>, String> {
+    // Try JSON first, then JSONL
+    if let Ok(json) = serde_json::from_str::<Value>(content_str) {
+        if let Some(messages) = json.get("messages").and_then(|m| m.as_array()) {
+            Ok(messages
+                .iter()
+                .filter(|m| m["role"].as_str() == Some("assistant"))
+                .map(|m| {
+                    let text = extract_text(&m["content"]);
+                    if text.is_empty() { "[No text content]".to_string() } else { text }
+                })
+                .collect())
+        } else if let Some(text) = json.get("content").and_then(|c| c.as_str()) {
+            Ok(vec![text.to_string()])
+        } else {
+            Err(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    } else {
+        // JSONL format
+        let mut messages = Vec::new();
+        for line in content_str.lines().filter(|l| !l.is_empty()) {
+            if let Ok(json) = serde_json::from_str::<Value>(line) {
+                if json["role"].as_str() == Some("assistant") {
+                    if let Some(text) = json["content"].as_str() {
+                        messages.push(text.to_string());
+                    }
+                }
+            }
+        }
+        if messages.is_empty() {
+            Err(content_str.lines().rev().take(20).collect::<Vec<&str>>().into_iter().rev().collect::<Vec<&str>>().join("\n"))
+        } else {
+            Ok(messages)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_session, select_by_workspace_id, workspace_cwd};
+    use crate::agents::{error_candidates, error_code, BridgeErrorCode, IdMatch, MessageWindow, ReadOptions};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    const CHAT: &str = r#"{"messages": [
+        {"role": "user", "content": "Fix the parser"},
+        {"role": "assistant", "content": "first reply"},
+        {"role": "assistant", "content": "second reply"},
+        {"role": "assistant", "content": "third reply"}
+    ]}"#;
+
+    #[test]
+    fn message_count_covers_every_reply_not_just_the_window() {
+        let session = export_session("/ws/aaa111/chat.json", CHAT, MessageWindow::Last(1));
+        assert_eq!(session.message_count, 3);
+        assert_eq!(session.messages_returned, 1);
+        assert_eq!(session.content, "third reply");
+    }
+
+    #[test]
+    fn last_returns_the_newest_replies_oldest_first() {
+        let session = export_session("/ws/aaa111/chat.json", CHAT, MessageWindow::Last(2));
+        assert_eq!(session.messages, ["second reply", "third reply"]);
+        assert_eq!(session.messages_returned, 2);
+    }
+
+    #[test]
+    fn exports_without_replies_count_no_messages() {
+        let session = export_session("/ws/aaa111/chat.json", r#"{"title": "empty"}"#, MessageWindow::Last(3));
+        assert_eq!(session.message_count, 0);
+        assert_eq!(session.messages_returned, 0);
+    }
+
+    #[test]
+    fn stems_shared_across_workspaces_are_ambiguous() {
+        let root = PathBuf::from("/cursor/User/workspaceStorage");
+        let paths = vec![root.join("bbb222/chat.json"), root.join("aaa111/chat.json"), root.join("aaa111/composer.json")];
+
+        let options = |id_match| ReadOptions { id_match, ..ReadOptions::default() };
+
+        let error = select_by_workspace_id(&paths, "chat", &options(IdMatch::Exact)).unwrap_err();
+        assert_eq!(error_code(&error), BridgeErrorCode::AmbiguousId);
+        assert_eq!(error_candidates(&error), ["bbb222/chat", "aaa111/chat"]);
+
+        let picked = select_by_workspace_id(&paths, "aaa111/chat", &options(IdMatch::Substring)).unwrap();
+        assert_eq!(picked, root.join("aaa111/chat.json"));
+        let picked = select_by_workspace_id(&paths, "compo", &options(IdMatch::Prefix)).unwrap();
+        assert_eq!(picked, root.join("aaa111/composer.json"));
+        assert!(select_by_workspace_id(&paths, "ccc", &options(IdMatch::Substring)).is_err());
+    }
+    /// `<tmp>/<name>/workspaceStorage/aaa111/chat.json`, with `workspace.json`
+    /// beside it when given.
+    fn workspace_chat(name: &str, workspace_json: Option<&str>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bridge-cursor-{}-{}", name, std::process::id())).join("workspaceStorage/aaa111");
+        fs::create_dir_all(&dir).unwrap();
+        if let Some(text) = workspace_json {
+            fs::write(dir.join("workspace.json"), text).unwrap();
+        }
+        fs::write(dir.join("chat.json"), "{}").unwrap();
+        dir.join("chat.json")
+    }
+
+    fn remove_store(chat: &Path) {
+        fs::remove_dir_all(chat.ancestors().nth(3).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn workspace_json_folder_becomes_the_chat_cwd() {
+        let project = std::env::temp_dir().canonicalize().unwrap();
+        let chat = workspace_chat("folder", Some(&format!(r#"{{"folder": "file://{}"}}"#, project.display())));
+        let cwd = workspace_cwd(&chat);
+        remove_store(&chat);
+        assert_eq!(cwd, Some(project));
+    }
+
+    #[test]
+    fn missing_workspace_json_leaves_the_cwd_unknown() {
+        let chat = workspace_chat("missing", None);
+        let cwd = workspace_cwd(&chat);
+        remove_store(&chat);
+        assert_eq!(cwd, None);
+    }
+
+    #[test]
+    fn malformed_workspace_json_leaves_the_cwd_unknown() {
+        let chat = workspace_chat("malformed", Some(r#"{"folder": "file:///repo""#));
+        let cwd = workspace_cwd(&chat);
+        remove_store(&chat);
+        assert_eq!(cwd, None);
+    }
+}
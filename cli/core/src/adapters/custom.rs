@@ -0,0 +1,466 @@
+//! Generic adapters declared in `adapters.toml` under the config directory,
+//! for agents the bridge has no built-in adapter for. Each `[[adapter]]`
+//! names the agent, a glob matching its session files, the file format, and
+//! selectors that pull the role, content, and cwd out of each record:
+//!
+//! ```toml
+//! [[adapter]]
+//! name = "acme"
+//! glob = "~/.acme/sessions/**/*.jsonl"
+//! format = "jsonl"              # or "json"
+//! role = "$.message.role"
+//! content = "$.message.content"
+//! cwd = "$.cwd"                 # optional
+//! ```
+//!
+//! Selectors are dot paths from the record (`$.` is optional) with `[N]`
+//! indexes; `[-1]` counts from the end. A `json` file holds one session whose
+//! records sit at `messages` (default `$.messages`); `cwd` is tried on the
+//! document before its records.
+
+use super::{common, AgentAdapter};
+use crate::agents::{self, BridgeErrorCode, CwdMatch, MessageWindow, ReadOptions, SearchQuery, Session, TranscriptMessage, TurnKind};
+use crate::config;
+use crate::redact::redact_sensitive_text;
+use crate::utils::{expand_home, normalize_path};
+use anyhow::{anyhow, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static SPECS: OnceLock<Vec<Spec>> = OnceLock::new();
+
+const DEFAULT_USER_ROLES: &[&str] = &["user", "human"];
+const DEFAULT_ASSISTANT_ROLES: &[&str] = &["assistant", "model"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Jsonl,
+    Json,
+}
+
+/// One `[[adapter]]` entry from `adapters.toml`.
+struct Spec {
+    name: &'static str,
+    root: PathBuf,
+    matcher: GlobMatcher,
+    format: Format,
+    messages: String,
+    role: String,
+    content: String,
+    cwd: Option<String>,
+    user_roles: Vec<String>,
+    assistant_roles: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Conversation {
+    cwd: Option<String>,
+    prompts: Vec<String>,
+    replies: Vec<String>,
+    order: Vec<TurnKind>,
+}
+
+pub struct CustomAdapter {
+    spec: &'static Spec,
+}
+
+/// The adapter declared as `name` in `adapters.toml`, if any.
+pub fn adapter(name: &str) -> Option<CustomAdapter> {
+    specs().iter().find(|spec| spec.name == name).map(|spec| CustomAdapter { spec })
+}
+
+/// Names of the adapters declared in `adapters.toml`, in file order.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    specs().iter().map(|spec| spec.name)
+}
+
+/// Location of the custom adapter declarations.
+pub fn adapters_path() -> PathBuf {
+    config::config_dir().join("adapters.toml")
+}
+
+/// The declarations, loaded once per process. Like the config file, a
+/// missing file declares nothing and a malformed entry is reported on stderr
+/// and skipped so the built-in adapters keep working.
+fn specs() -> &'static [Spec] {
+    SPECS.get_or_init(|| {
+        let path = adapters_path();
+        let Ok(raw) = fs::read_to_string(&path) else {
+            return Vec::new();
+        };
+        let document = match toml::from_str::<Value>(&raw) {
+            Ok(document) => document,
+            Err(err) => {
+                eprintln!("Warning: ignoring invalid adapters file {}: {}", path.display(), err);
+                return Vec::new();
+            }
+        };
+        let mut specs: Vec<Spec> = Vec::new();
+        for entry in document["adapter"].as_array().into_iter().flatten() {
+            match Spec::parse(entry, &specs) {
+                Ok(spec) => specs.push(spec),
+                Err(err) => eprintln!("Warning: skipped custom adapter in {}: {}", path.display(), err),
+            }
+        }
+        specs
+    })
+}
+
+impl Spec {
+    fn parse(entry: &Value, declared: &[Spec]) -> Result<Self> {
+        let raw_name = entry["name"].as_str().ok_or_else(|| anyhow!("missing `name`"))?;
+        let name = raw_name.trim().to_ascii_lowercase().replace('_', "-");
+        if name.is_empty() || !name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-') {
+            return Err(anyhow!("invalid name '{}': use letters, digits, and '-'", raw_name));
+        }
+        if super::is_reserved_name(&name) {
+            return Err(anyhow!("'{}' is a built-in agent name", name));
+        }
+        if declared.iter().any(|spec| spec.name == name) {
+            return Err(anyhow!("'{}' is declared more than once", name));
+        }
+
+        let field = |key: &str| {
+            entry[key].as_str().map(str::to_string).ok_or_else(|| anyhow!("'{}' is missing `{}`", name, key))
+        };
+        let pattern = field("glob")?;
+        let pattern = expand_home(&pattern).unwrap_or_else(|| PathBuf::from(&pattern));
+        let matcher = GlobBuilder::new(&pattern.to_string_lossy())
+            .literal_separator(true)
+            .build()
+            .map_err(|err| anyhow!("'{}' has an invalid glob: {}", name, err))?
+            .compile_matcher();
+        let format = match field("format")?.to_ascii_lowercase().as_str() {
+            "jsonl" => Format::Jsonl,
+            "json" => Format::Json,
+            other => return Err(anyhow!("'{}' has unsupported format '{}': use jsonl or json", name, other)),
+        };
+        let roles = |key: &str, default: &[&str]| match &entry[key] {
+            Value::String(role) => vec![role.to_ascii_lowercase()],
+            Value::Array(roles) => roles.iter().filter_map(Value::as_str).map(str::to_ascii_lowercase).collect(),
+            _ => default.iter().map(|role| role.to_string()).collect(),
+        };
+
+        Ok(Spec {
+            role: field("role")?,
+            content: field("content")?,
+            cwd: entry["cwd"].as_str().map(str::to_string),
+            messages: entry["messages"].as_str().unwrap_or("$.messages").to_string(),
+            user_roles: roles("user_role", DEFAULT_USER_ROLES),
+            assistant_roles: roles("assistant_role", DEFAULT_ASSISTANT_ROLES),
+            root: glob_root(&pattern),
+            matcher,
+            format,
+            // Declarations are read once per process, so leaking the names is bounded.
+            name: Box::leak(name.into_boxed_str()),
+        })
+    }
+
+    fn parse_conversation(&self, text: &str) -> Conversation {
+        let mut conversation = Conversation::default();
+        match self.format {
+            Format::Jsonl => {
+                for record in agents::jsonl_values(text) {
+                    self.observe(&record, &mut conversation);
+                }
+            }
+            Format::Json => {
+                let Ok(document) = serde_json::from_str::<Value>(text) else {
+                    return conversation;
+                };
+                conversation.cwd = self.cwd_of(&document);
+                for record in select(&document, &self.messages).and_then(Value::as_array).into_iter().flatten() {
+                    self.observe(record, &mut conversation);
+                }
+            }
+        }
+        conversation
+    }
+
+    fn observe(&self, record: &Value, conversation: &mut Conversation) {
+        if conversation.cwd.is_none() {
+            conversation.cwd = self.cwd_of(record);
+        }
+        let role = select(record, &self.role).and_then(Value::as_str).unwrap_or("").to_ascii_lowercase();
+        let text = select(record, &self.content).map(agents::extract_text).unwrap_or_default();
+        if text.trim().is_empty() {
+            return;
+        }
+        if self.user_roles.contains(&role) {
+            conversation.prompts.push(text);
+            conversation.order.push(TurnKind::Prompt);
+        } else if self.assistant_roles.contains(&role) {
+            conversation.replies.push(text);
+            conversation.order.push(TurnKind::Reply);
+        }
+    }
+
+    fn cwd_of(&self, value: &Value) -> Option<String> {
+        let cwd = select(value, self.cwd.as_deref()?)?.as_str()?.trim();
+        (!cwd.is_empty()).then(|| cwd.to_string())
+    }
+
+    /// Every file the glob matches, newest first.
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        let matches = |path: &Path| self.matcher.is_match(path);
+        common::collect_paths(std::slice::from_ref(&self.root), &matches)
+    }
+
+    fn read_conversation(&self, path: &Path) -> Result<Conversation> {
+        if fs::metadata(path).map(|meta| meta.len() > common::max_file_size()).unwrap_or(false) {
+            return Err(anyhow!(
+                "Skipped {} (exceeds {}MB size limit)",
+                path.display(),
+                common::max_file_size() / (1024 * 1024)
+            ));
+        }
+        let raw = fs::read(path).map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to read {}: {}", path.display(), err)))?;
+        Ok(self.parse_conversation(&String::from_utf8_lossy(&raw)))
+    }
+
+    fn to_session(&self, source: &str, conversation: Conversation, window: MessageWindow) -> Result<Session> {
+        if conversation.replies.is_empty() {
+            return Err(BridgeErrorCode::EmptySession.error(format!("{} session has no messages: {}", self.name, source)));
+        }
+        let replies = &conversation.replies;
+        let selected = window.select(replies).iter().map(|text| redact_sensitive_text(text)).collect::<Vec<_>>();
+        let messages_returned = selected.len();
+        Ok(Session {
+            agent: self.name,
+            content: agents::join_messages(&selected),
+            messages: selected,
+            source: source.to_string(),
+            warnings: Vec::new(),
+            session_id: Some(common::file_stem(Path::new(source))).filter(|stem| !stem.is_empty()),
+            cwd: conversation.cwd,
+            timestamp: None,
+            message_count: replies.len(),
+            messages_returned,
+            tool_usage: None,
+            timing: None,
+            model: None,
+            usage: None,
+        })
+    }
+}
+
+impl AgentAdapter for CustomAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let spec = self.spec;
+        if !spec.root.exists() {
+            return Err(BridgeErrorCode::NotFound.error(format!(
+                "No {} session found. Data directory not found: {}",
+                spec.name,
+                spec.root.display()
+            )));
+        }
+        let files = spec.session_files()?;
+        let mut warnings = Vec::new();
+        let target = match options.id.as_deref() {
+            Some(id) => common::select_path_by_id(&files, id, options, spec.name)?,
+            None => {
+                let newest = files.first().ok_or_else(|| BridgeErrorCode::NotFound.error(format!("No {} session found.", spec.name)))?;
+                let expected = normalize_path(&options.cwd)?;
+                let scoped = files.iter().find(|file| {
+                    spec.read_conversation(file)
+                        .is_ok_and(|conversation| in_scope(conversation.cwd.as_deref(), Some(&expected), options.cwd_match))
+                });
+                match scoped {
+                    Some(scoped) => scoped.clone(),
+                    None => {
+                        warnings.push(format!(
+                            "Warning: no {} session matched cwd {}; falling back to latest session.",
+                            spec.name,
+                            expected.display()
+                        ));
+                        newest.clone()
+                    }
+                }
+            }
+        };
+
+        let conversation = spec.read_conversation(&target)?;
+        let mut session = spec.to_session(&target.to_string_lossy(), conversation, options.window())?;
+        session.warnings = warnings;
+        session.timestamp = agents::file_modified_iso(&target);
+        Ok(session)
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let spec = self.spec;
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for file in spec.session_files()? {
+            let Ok(conversation) = spec.read_conversation(&file) else {
+                continue;
+            };
+            if !in_scope(conversation.cwd.as_deref(), expected.as_deref(), cwd_match) {
+                continue;
+            }
+            entries.push(json!({
+                "session_id": common::file_stem(&file),
+                "agent": spec.name,
+                "cwd": conversation.cwd,
+                "modified_at": agents::file_modified_iso(&file),
+                "file_path": file.to_string_lossy(),
+            }));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let spec = self.spec;
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for file in spec.session_files()? {
+            if entries.len() >= limit {
+                break;
+            }
+            let Ok(conversation) = spec.read_conversation(&file) else {
+                continue;
+            };
+            if !in_scope(conversation.cwd.as_deref(), expected.as_deref(), cwd_match) {
+                continue;
+            }
+            let session_id = common::file_stem(&file);
+            let matches = query.matches_turns(
+                &conversation.prompts,
+                &conversation.replies,
+                Some(&session_id),
+                conversation.cwd.as_deref(),
+            );
+            if matches {
+                entries.push(json!({
+                    "session_id": session_id,
+                    "agent": spec.name,
+                    "cwd": conversation.cwd,
+                    "modified_at": agents::file_modified_iso(&file),
+                    "file_path": file.to_string_lossy(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        self.spec.to_session(name, self.spec.parse_conversation(text), window)
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        self.spec.parse_conversation(text).prompts.into_iter().next()
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        let conversation = self.spec.parse_conversation(text);
+        agents::interleave(&conversation.prompts, &conversation.replies, &conversation.order)
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        vec![self.spec.root.clone()]
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        self.spec.session_files()
+    }
+}
+
+fn in_scope(session_cwd: Option<&str>, expected: Option<&Path>, cwd_match: CwdMatch) -> bool {
+    match expected {
+        None => true,
+        Some(expected) => session_cwd
+            .and_then(|cwd| normalize_path(cwd).ok())
+            .is_some_and(|cwd| cwd_match.matches(&cwd, expected)),
+    }
+}
+
+/// The directory a glob walk starts from: its leading components without
+/// wildcards (`~/.acme/sessions/**/*.jsonl` -> `~/.acme/sessions`).
+fn glob_root(pattern: &Path) -> PathBuf {
+    let mut root = PathBuf::new();
+    for component in pattern.components() {
+        if component.as_os_str().to_string_lossy().contains(['*', '?', '[', '{']) {
+            break;
+        }
+        root.push(component);
+    }
+    if root.is_file() {
+        root.pop();
+    }
+    root
+}
+
+/// Resolve a selector such as `$.message.content` or `parts[0].text`.
+fn select<'a>(value: &'a Value, selector: &str) -> Option<&'a Value> {
+    let path = selector.trim().trim_start_matches('$').trim_start_matches('.');
+    let mut current = value;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let (key, indexes) = segment.split_once('[').unwrap_or((segment, ""));
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indexes.split('[').filter(|index| !index.is_empty()) {
+            let index = index.trim_end_matches(']').trim().parse::<i64>().ok()?;
+            let items = current.as_array()?;
+            let index = if index < 0 { items.len().checked_sub(index.unsigned_abs() as usize)? } else { index as usize };
+            current = items.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select, Spec};
+    use serde_json::json;
+
+    #[test]
+    fn selectors_pull_roles_content_and_cwd_from_records() {
+        let record = json!({ "message": { "role": "user", "parts": [{ "text": "a" }, { "text": "b" }] } });
+        assert_eq!(select(&record, "$.message.role"), Some(&json!("user")));
+        assert_eq!(select(&record, "message.parts[0].text"), Some(&json!("a")));
+        assert_eq!(select(&record, "$.message.parts[-1].text"), Some(&json!("b")));
+        assert_eq!(select(&record, "message.parts[2]"), None);
+
+        let entry = json!({
+            "name": "Acme",
+            "glob": "/tmp/acme/**/*.json",
+            "format": "json",
+            "messages": "$.history",
+            "role": "$.speaker",
+            "content": "$.body",
+            "cwd": "$.project.path",
+            "assistant_role": "bot",
+        });
+        let spec = Spec::parse(&entry, &[]).unwrap();
+        assert_eq!(spec.name, "acme");
+        assert_eq!(spec.root, std::path::PathBuf::from("/tmp/acme"));
+        let conversation = spec.parse_conversation(
+            &json!({
+                "project": { "path": "/work/acme" },
+                "history": [
+                    { "speaker": "USER", "body": "fix it" },
+                    { "speaker": "bot", "body": [{ "text": "fixed" }] },
+                ],
+            })
+            .to_string(),
+        );
+        assert_eq!(conversation.cwd.as_deref(), Some("/work/acme"));
+        assert_eq!(conversation.prompts, vec!["fix it"]);
+        assert_eq!(conversation.replies, vec!["fixed"]);
+
+        let builtin = json!({ "name": "claude-code", "glob": "*", "format": "jsonl", "role": "r", "content": "c" });
+        assert!(Spec::parse(&builtin, &[]).is_err());
+    }
+}
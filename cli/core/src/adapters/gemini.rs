@@ -0,0 +1,593 @@
+//! Gemini CLI keeps each project's chats as JSON documents under
+//! `~/.gemini/tmp/<sha256 of the project root>/chats/session-*.json`, next to
+//! the saved chats (`checkpoint-<tag>.json`) and restore checkpoints
+//! (`checkpoints/`) of the same project. Sessions do not record their cwd;
+//! `file_cwd` recovers it from the project hash.
+
+use super::common::{
+    collect_matching_files, collect_paths, file_stem, has_extension, max_file_size, scan_roots, select_by_id, sort_files_by_mtime_desc,
+    FileEntry,
+};
+use super::AgentAdapter;
+use crate::agents::{
+    extract_text, file_modified_iso, join_messages, parse_iso_millis, substantive_prompt, value_text, BridgeErrorCode, CwdMatch,
+    MessageWindow, ParsedContent, ReadOptions, SearchQuery, Session, TimeSpan, Timing, TranscriptMessage, TurnKind,
+};
+use crate::redact::redact_sensitive_text;
+use crate::gemini_projects::ProjectRoots;
+use crate::utils::{expand_home, hash_path, is_system_directory, normalize_path};
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// `--include-checkpoints` for the command being run.
+static INCLUDE_CHECKPOINTS: AtomicBool = AtomicBool::new(false);
+
+pub struct GeminiAdapter;
+
+impl AgentAdapter for GeminiAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let dirs = resolve_chat_dirs(options.chats_dir.as_deref(), &options.cwd)?;
+        if dirs.is_empty() {
+            return Err(BridgeErrorCode::NotFound.error("No Gemini session found. Searched chats directories:"));
+        }
+
+        let target_file = if let Some(id_value) = options.id.as_deref() {
+            let mut candidates = Vec::new();
+            for dir in &dirs {
+                let mut files = collect_matching_files(dir, false, &|file_path| has_extension(file_path, "json"))?;
+                candidates.append(&mut files);
+            }
+            // Saved chats and restore checkpoints outlive `chats/` cleanup, so an
+            // id can name one of them even when no chats directory is left.
+            let projects = match options.chats_dir {
+                Some(_) => dirs.iter().filter_map(|dir| dir.parent().map(Path::to_path_buf)).collect(),
+                None => project_dirs(None)?,
+            };
+            candidates.extend(checkpoint_files(&projects)?);
+            sort_files_by_mtime_desc(&mut candidates);
+            select_by_id(&candidates, id_value, options, "Gemini")?
+        } else {
+            let mut candidates = Vec::new();
+            for dir in &dirs {
+                let mut files = collect_matching_files(dir, false, &is_session_file)?;
+                candidates.append(&mut files);
+            }
+            sort_files_by_mtime_desc(&mut candidates);
+            candidates
+                .first()
+                .map(|f| f.path.clone())
+                .context("No Gemini session found.")?
+        };
+
+        let parsed = parse_file(&target_file, options.window(), options.span)?;
+        let cwd = file_cwd(&target_file, &mut ProjectRoots::load(&[&options.cwd]));
+        Ok(ParsedContent { cwd: cwd.map(|cwd| cwd.to_string_lossy().to_string()), ..parsed }
+            .into_session("gemini", target_file.to_string_lossy().to_string()))
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let mut roots = ProjectRoots::load(&cwd.into_iter().collect::<Vec<_>>());
+        let candidates = listing_files(cwd, cwd_match, &mut roots)?;
+        let mut entries = Vec::new();
+        for file in candidates.iter().take(limit) {
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            entries.push(serde_json::json!({
+                "session_id": session_id,
+                "agent": "gemini",
+                "cwd": file_cwd(&file.path, &mut roots).map(|cwd| cwd.to_string_lossy().to_string()),
+                "model": file_model(&file.path),
+                "modified_at": file_modified_iso(&file.path),
+                "file_path": file.path.to_string_lossy().to_string(),
+            }));
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let mut roots = ProjectRoots::load(&cwd.into_iter().collect::<Vec<_>>());
+        let candidates = listing_files(cwd, cwd_match, &mut roots)?;
+
+        let mut entries = Vec::new();
+
+        for file in candidates {
+            if entries.len() >= limit { break; }
+
+            if fs::metadata(&file.path).map(|m| m.len() > max_file_size()).unwrap_or(false) {
+                continue;
+            }
+
+            let content = match fs::read_to_string(&file.path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            let session_id = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+            if query.matches_session("gemini", &content, Some(&session_id), None) {
+                entries.push(serde_json::json!({
+                    "session_id": session_id,
+                    "agent": "gemini",
+                    "cwd": file_cwd(&file.path, &mut roots).map(|cwd| cwd.to_string_lossy().to_string()),
+                    "modified_at": file_modified_iso(&file.path),
+                    "file_path": file.path.to_string_lossy().to_string(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        Ok(parse_text(text, Path::new(name), None, window, None)?.into_session("gemini", name.to_string()))
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        let session = document(serde_json::from_str(text).ok()?);
+        let is_user = |value: &Value| value.as_str().is_some_and(|v| v.eq_ignore_ascii_case("user"));
+        match session["messages"].as_array() {
+            Some(messages) => messages
+                .iter()
+                .filter(|message| is_user(&message["type"]))
+                .find_map(|message| substantive_prompt(&extract_text(&message["content"]))),
+            None => session["history"]
+                .as_array()?
+                .iter()
+                .filter(|turn| is_user(&turn["role"]))
+                .find_map(|turn| substantive_prompt(&extract_text(&turn["parts"]))),
+        }
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        let Ok(session) = serde_json::from_str::<Value>(text).map(document) else {
+            return Vec::new();
+        };
+        let role_of = |value: &Value| match value.as_str().map(str::to_ascii_lowercase).as_deref() {
+            Some("user") => Some("user"),
+            Some("gemini" | "assistant" | "model") => Some("assistant"),
+            _ => None,
+        };
+        match session["messages"].as_array() {
+            Some(items) => items
+                .iter()
+                .filter_map(|item| Some(TranscriptMessage::new(role_of(&item["type"])?, extract_text(&item["content"]), &item["timestamp"])))
+                .collect(),
+            None => session["history"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|turn| Some(TranscriptMessage::new(role_of(&turn["role"])?, extract_text(&turn["parts"]), &Value::Null)))
+                .collect(),
+        }
+    }
+
+    fn tool_texts(&self, text: &str) -> Vec<String> {
+        serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|session| session["messages"].as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|message| message["toolCalls"].as_array().cloned().unwrap_or_default())
+            .filter_map(|call| value_text(&call))
+            .collect()
+    }
+
+    fn supports_time_span(&self) -> bool {
+        true
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        tmp_base_dirs()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        collect_paths(&tmp_base_dirs(), &is_session_file)
+    }
+}
+
+pub(crate) fn tmp_base_dirs() -> Vec<PathBuf> {
+    scan_roots(
+        "gemini",
+        "BRIDGE_GEMINI_TMP_DIR",
+        expand_home("~/.gemini/tmp").unwrap_or_else(|| PathBuf::from("~/.gemini/tmp")),
+    )
+}
+
+fn parse_file(path: &Path, window: MessageWindow, span: Option<TimeSpan>) -> Result<ParsedContent> {
+    let meta = fs::metadata(path)?;
+    if meta.len() > max_file_size() {
+        return Err(anyhow!(
+            "Skipped {} (exceeds {}MB size limit)",
+            path.display(),
+            max_file_size() / (1024 * 1024)
+        ));
+    }
+    let raw_content = fs::read_to_string(path)?;
+    parse_text(&raw_content, path, file_modified_iso(path), window, span)
+}
+
+fn parse_text(
+    raw_content: &str,
+    path: &Path,
+    timestamp: Option<String>,
+    window: MessageWindow,
+    span: Option<TimeSpan>,
+) -> Result<ParsedContent> {
+    let session = document(
+        serde_json::from_str(raw_content).map_err(|e| BridgeErrorCode::ParseFailed.error(format!("Failed to parse Gemini JSON: {}", e)))?,
+    );
+
+    let session_id = session["sessionId"].as_str().map(|s| s.to_string())
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string()));
+
+    if let Some(messages) = session["messages"].as_array() {
+        let assistant_count = messages.iter().filter(|m| {
+            m["type"].as_str().map(|t| {
+                let lower = t.to_ascii_lowercase();
+                lower == "gemini" || lower == "assistant" || lower == "model"
+            }).unwrap_or(false)
+        }).count();
+
+        let is_assistant_msg = |m: &&Value| {
+            m["type"].as_str().map(|t| {
+                let lower = t.to_ascii_lowercase();
+                lower == "gemini" || lower == "assistant" || lower == "model"
+            }).unwrap_or(false)
+        };
+
+        let assistant_msgs: Vec<&Value> = messages
+            .iter()
+            .filter(is_assistant_msg)
+            .filter(|m| span.map_or(true, |span| span.contains(m["timestamp"].as_str().and_then(parse_iso_millis))))
+            .collect();
+
+        let mut timing = Timing::default();
+        for message in messages {
+            let kind = if is_assistant_msg(&message) {
+                TurnKind::Reply
+            } else if message["type"].as_str().is_some_and(|t| t.eq_ignore_ascii_case("user")) {
+                TurnKind::Prompt
+            } else {
+                TurnKind::Other
+            };
+            timing.observe(message["timestamp"].as_str(), kind);
+        }
+        let timing = timing.into_value();
+        let model = session_model(&session);
+
+        if span.is_some() || (window.is_multi() && !assistant_msgs.is_empty()) {
+            let selected = window.select(&assistant_msgs);
+            let messages_returned = selected.len();
+            let texts = selected.iter().map(|m| {
+                let text = extract_text(&m["content"]);
+                if text.is_empty() { "[No text content]".to_string() } else { redact_sensitive_text(&text) }
+            }).collect::<Vec<String>>();
+            return Ok(ParsedContent {
+                content: join_messages(&texts),
+                messages: texts,
+                warnings: Vec::new(),
+                session_id,
+                cwd: None,
+                timestamp,
+                message_count: assistant_count,
+                messages_returned,
+                tool_usage: None,
+                timing: timing.clone(),
+                model: model.clone(),
+                usage: None,
+            });
+        }
+
+        let selected = messages.iter().rev().find(is_assistant_msg).or_else(|| messages.last());
+
+        if let Some(message) = selected {
+            let text = extract_text(&message["content"]);
+            let content = if text.is_empty() {
+                "[No text content]".to_string()
+            } else {
+                redact_sensitive_text(&text)
+            };
+            return Ok(ParsedContent {
+                content: content.clone(),
+                messages: vec![content],
+                warnings: Vec::new(),
+                session_id,
+                cwd: None,
+                timestamp,
+                message_count: assistant_count,
+                messages_returned: 1,
+                tool_usage: None,
+                timing,
+                model,
+                usage: None,
+            });
+        }
+        return Err(BridgeErrorCode::EmptySession.error("Gemini session has no messages."));
+    }
+
+    if let Some(history) = session["history"].as_array() {
+        if span.is_some() {
+            return Err(anyhow!(
+                "Gemini history sessions have no per-message timestamps; --since/--until cannot be applied: {}",
+                path.display()
+            ));
+        }
+        let assistant_count = history.iter().filter(|t| {
+            !t["role"].as_str().map(|r| r.eq_ignore_ascii_case("user")).unwrap_or(false)
+        }).count();
+
+        let extract_turn_text = |turn: &Value| -> String {
+            let parts = &turn["parts"];
+            if let Some(arr) = parts.as_array() {
+                // Function call and response parts carry no text.
+                arr.iter().filter_map(|part| part["text"].as_str()).collect::<Vec<&str>>().join("\n")
+            } else if let Some(raw) = parts.as_str() {
+                raw.to_string()
+            } else {
+                "[No text content]".to_string()
+            }
+        };
+
+        let is_not_user = |t: &&Value| {
+            !t["role"].as_str().map(|role| role.eq_ignore_ascii_case("user")).unwrap_or(false)
+        };
+
+        let assistant_turns: Vec<&Value> = history.iter().filter(is_not_user).collect();
+
+        if window.is_multi() && !assistant_turns.is_empty() {
+            let selected = window.select(&assistant_turns);
+            let messages_returned = selected.len();
+            let texts = selected.iter().map(|t| redact_sensitive_text(&extract_turn_text(t))).collect::<Vec<String>>();
+            return Ok(ParsedContent {
+                content: join_messages(&texts),
+                messages: texts,
+                warnings: Vec::new(),
+                session_id,
+                cwd: None,
+                timestamp,
+                message_count: assistant_count,
+                messages_returned,
+                tool_usage: None,
+                timing: None,
+                model: None,
+                usage: None,
+            });
+        }
+
+        let selected = history.iter().rev().find(is_not_user).or_else(|| history.last());
+        if let Some(turn) = selected {
+            let content = redact_sensitive_text(&extract_turn_text(turn));
+            return Ok(ParsedContent {
+                content: content.clone(),
+                messages: vec![content],
+                warnings: Vec::new(),
+                session_id,
+                cwd: None,
+                timestamp,
+                message_count: assistant_count,
+                messages_returned: 1,
+                tool_usage: None,
+                timing: None,
+                model: None,
+                usage: None,
+            });
+        }
+
+        return Err(BridgeErrorCode::EmptySession.error("Gemini history is empty."));
+    }
+
+    Err(BridgeErrorCode::ParseFailed.error(
+        "Unknown Gemini session schema. Supported fields: messages, history."
+    ))
+}
+
+fn session_model(session: &Value) -> Option<String> {
+    session["messages"]
+        .as_array()?
+        .iter()
+        .rev()
+        .find_map(|message| message["model"].as_str())
+        .or_else(|| session["model"].as_str())
+        .map(|m| m.to_string())
+}
+
+fn file_model(file_path: &Path) -> Option<String> {
+    let meta = fs::metadata(file_path).ok()?;
+    if meta.len() > max_file_size() {
+        return None;
+    }
+    let session: Value = serde_json::from_str(&fs::read_to_string(file_path).ok()?).ok()?;
+    session_model(&session)
+}
+
+fn resolve_chat_dirs(chats_dir: Option<&str>, cwd: &str) -> Result<Vec<PathBuf>> {
+    if let Some(dir) = chats_dir {
+        let expanded = expand_home(dir).context("Invalid Gemini chats directory")?;
+        if is_system_directory(&expanded) {
+            return Err(anyhow!("Refusing to scan system directory: {}", expanded.display()));
+        }
+        return if expanded.exists() {
+            Ok(vec![expanded])
+        } else {
+            Ok(Vec::new())
+        };
+    }
+
+    let mut ordered = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let add_dir = |dir: PathBuf, ordered_dirs: &mut Vec<PathBuf>, seen_dirs: &mut std::collections::HashSet<PathBuf>| {
+        if !dir.exists() {
+            return;
+        }
+        if seen_dirs.insert(dir.clone()) {
+            ordered_dirs.push(dir);
+        }
+    };
+
+    let normalized_cwd = normalize_path(cwd)?;
+    let scoped_hash = hash_path(&normalized_cwd);
+
+    let tmp_bases = tmp_base_dirs();
+    for tmp_base in &tmp_bases {
+        add_dir(
+            tmp_base.join(&scoped_hash).join("chats"),
+            &mut ordered,
+            &mut seen,
+        );
+    }
+
+    for tmp_base in &tmp_bases {
+        if let Ok(entries) = fs::read_dir(tmp_base) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    add_dir(path.join("chats"), &mut ordered, &mut seen);
+                }
+            }
+        }
+    }
+
+    Ok(ordered)
+}
+
+/// Per-project directories (`<tmp>/<project hash>`) in the Gemini tmp roots;
+/// only the one for `cwd` when given.
+pub(crate) fn project_dirs(cwd: Option<&str>) -> Result<Vec<PathBuf>> {
+    if let Some(scope) = cwd {
+        let normalized_cwd = normalize_path(scope)?;
+        let scoped_hash = hash_path(&normalized_cwd);
+        return Ok(tmp_base_dirs()
+            .into_iter()
+            .map(|tmp_base| tmp_base.join(&scoped_hash))
+            .filter(|dir| dir.is_dir())
+            .collect());
+    }
+
+    let mut ordered = Vec::new();
+    for tmp_base in tmp_base_dirs() {
+        if let Ok(entries) = fs::read_dir(&tmp_base) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    ordered.push(path);
+                }
+            }
+        }
+    }
+    Ok(ordered)
+}
+
+/// Session files `list` and `search` consider, newest first: each project's
+/// chats, plus its saved chats and restore checkpoints under
+/// `--include-checkpoints`. An exact `cwd` names one project by its hash;
+/// other match modes need each project's root, so only projects whose root
+/// `roots` can resolve are kept.
+fn listing_files(cwd: Option<&str>, cwd_match: CwdMatch, roots: &mut ProjectRoots) -> Result<Vec<FileEntry>> {
+    let projects = match cwd {
+        Some(scope) if cwd_match != CwdMatch::Exact => {
+            let expected = normalize_path(scope)?;
+            project_dirs(None)?
+                .into_iter()
+                .filter(|project| {
+                    let root = project.file_name().and_then(|hash| roots.resolve(&hash.to_string_lossy()));
+                    root.is_some_and(|root| cwd_match.matches(&root, &expected))
+                })
+                .collect()
+        }
+        _ => project_dirs(cwd)?,
+    };
+    let mut candidates = Vec::new();
+    for project in &projects {
+        candidates.extend(collect_matching_files(&project.join("chats"), false, &is_session_file)?);
+    }
+    if INCLUDE_CHECKPOINTS.load(Ordering::Relaxed) {
+        candidates.extend(checkpoint_files(&projects)?);
+    }
+    sort_files_by_mtime_desc(&mut candidates);
+    Ok(candidates)
+}
+
+/// Make Gemini `list` and `search` also return saved chats and checkpoints,
+/// until set again.
+pub fn set_include_checkpoints(include: bool) {
+    INCLUDE_CHECKPOINTS.store(include, Ordering::Relaxed);
+}
+
+/// Conversations Gemini CLI keeps outside `chats/` in each project directory:
+/// `/chat save <tag>` writes `checkpoint-<tag>.json`, and checkpointing writes
+/// a restore point to `checkpoints/` before each file-modifying tool call.
+fn checkpoint_files(projects: &[PathBuf]) -> Result<Vec<FileEntry>> {
+    let mut files = Vec::new();
+    for project in projects {
+        files.extend(collect_matching_files(project, false, &is_saved_chat)?);
+        files.extend(collect_matching_files(&project.join("checkpoints"), false, &|p| has_extension(p, "json"))?);
+    }
+    Ok(files)
+}
+
+fn is_saved_chat(path: &Path) -> bool {
+    has_extension(path, "json") && file_stem(path).starts_with("checkpoint-")
+}
+
+/// A Gemini session document in the `{messages}` or `{history}` shape. Saved
+/// chats are a bare array of turns, and restore checkpoints keep the model's
+/// turns as `clientHistory` (their `history` holds UI items instead).
+fn document(session: Value) -> Value {
+    match session {
+        Value::Array(turns) => serde_json::json!({ "history": turns }),
+        mut session if session["clientHistory"].is_array() => {
+            let turns = session["clientHistory"].take();
+            serde_json::json!({ "history": turns, "model": session["model"].clone() })
+        }
+        session => session,
+    }
+}
+
+/// Project directory (`<tmp>/<hash>`) of a Gemini session file.
+fn project_dir(file: &Path) -> Option<&Path> {
+    let parent = file.parent()?;
+    match parent.file_name().and_then(|name| name.to_str()) {
+        Some("chats" | "checkpoints") => parent.parent(),
+        _ => Some(parent),
+    }
+}
+
+/// Working directory of a Gemini session. The project directory is named by
+/// the SHA-256 of the project root, which cannot be reversed: the root is
+/// either a known directory with that hash (`gemini_projects`) or recovered
+/// from restore checkpoints, which record the absolute path of the file a tool
+/// was about to change; the ancestor of that path whose hash matches is the
+/// project root.
+fn file_cwd(file: &Path, roots: &mut ProjectRoots) -> Option<PathBuf> {
+    let project = project_dir(file)?;
+    let hash = project.file_name()?.to_str()?;
+    if let Some(root) = roots.resolve(hash) {
+        return Some(root);
+    }
+    let checkpoints = collect_matching_files(&project.join("checkpoints"), false, &|p| has_extension(p, "json")).ok()?;
+    let root = checkpoints.iter().find_map(|checkpoint| {
+        if fs::metadata(&checkpoint.path).map(|m| m.len() > max_file_size()).unwrap_or(true) {
+            return None;
+        }
+        let json: Value = serde_json::from_str(&fs::read_to_string(&checkpoint.path).ok()?).ok()?;
+        let touched = json["filePath"].as_str().or(json["toolCall"]["args"]["file_path"].as_str())?;
+        Path::new(touched).ancestors().skip(1).find(|dir| hash_path(dir) == hash).map(Path::to_path_buf)
+    })?;
+    roots.record(&root);
+    Some(root)
+}
+
+fn is_session_file(path: &Path) -> bool {
+    has_extension(path, "json")
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with("session-"))
+            .unwrap_or(false)
+}
@@ -0,0 +1,244 @@
+pub mod common;
+pub mod codex;
+pub mod gemini;
+pub mod claude;
+pub mod cursor;
+pub mod aider;
+pub mod windsurf;
+pub mod cline;
+pub mod zed;
+pub mod custom;
+
+use crate::agents::{self, BridgeErrorCode, CwdMatch, IdMatch, MessageWindow, ReadOptions, SearchQuery, Session, TranscriptMessage};
+use crate::theme;
+use anyhow::Result;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+const AGENT_NAMES: &[&str] = &["codex", "gemini", "claude", "cursor", "aider", "windsurf", "cline", "zed"];
+
+/// Common alternate spellings, mapped to the canonical agent name.
+const AGENT_ALIASES: &[(&str, &str)] = &[
+    ("claude-code", "claude"),
+    ("codex-cli", "codex"),
+    ("gpt", "codex"),
+    ("gemini-cli", "gemini"),
+    ("cursor-agent", "cursor"),
+    ("aider-chat", "aider"),
+    ("cascade", "windsurf"),
+    ("roo", "cline"),
+    ("roo-code", "cline"),
+    ("roo-cline", "cline"),
+];
+
+/// Former names of renamed agents: still accepted, with a warning on stderr.
+/// Add `(old, new)` here when an adapter is renamed.
+const DEPRECATED_AGENT_NAMES: &[(&str, &str)] = &[];
+
+/// Map an agent name from `--agent`, `--source`, or a handoff packet to its
+/// canonical adapter name. Matching ignores case and treats `_` as `-`.
+pub fn normalize_agent(raw: &str) -> Result<&'static str> {
+    let key = raw.trim().to_ascii_lowercase().replace('_', "-");
+    if let Some(name) = AGENT_NAMES.iter().find(|name| **name == key) {
+        return Ok(name);
+    }
+    if let Some((_, name)) = AGENT_ALIASES.iter().find(|(alias, _)| *alias == key) {
+        return Ok(name);
+    }
+    if let Some(name) = custom::names().find(|name| *name == key) {
+        return Ok(name);
+    }
+    #[cfg(feature = "wasm-plugins")]
+    if let Some(name) = crate::plugin::names().find(|name| *name == key) {
+        return Ok(name);
+    }
+    if let Some((old, name)) = DEPRECATED_AGENT_NAMES.iter().find(|(old, _)| *old == key) {
+        eprintln!(
+            "{}",
+            theme::paint("warning", &format!("Warning: agent name '{}' is deprecated; use '{}'", old, name))
+        );
+        return Ok(name);
+    }
+    Err(BridgeErrorCode::UnsupportedAgent.error(format!("Unsupported agent: {}", raw.trim())))
+}
+
+/// Whether `name` is a built-in agent, alias, former name, or `all`; adapters
+/// declared in `adapters.toml` and WASM plugins may not use these.
+pub(crate) fn is_reserved_name(name: &str) -> bool {
+    name == "all"
+        || AGENT_NAMES.contains(&name)
+        || AGENT_ALIASES.iter().any(|(alias, _)| *alias == name)
+        || DEPRECATED_AGENT_NAMES.iter().any(|(old, _)| *old == name)
+}
+
+/// One agent's session store. Each adapter owns where its sessions live and
+/// how they are parsed; the filesystem-free methods take a session file's
+/// text, so exported files (`parse_session_bytes`), the search index, and
+/// `export` share the rules `read` uses.
+pub trait AgentAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session>;
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>>;
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>>;
+    /// `read_session` for a session file's text. `name` is the file name; it
+    /// labels warnings and supplies the session id when the text has none.
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session>;
+    /// The first substantive user prompt, unredacted. `session_id` picks one
+    /// session from files that hold several.
+    fn first_prompt(&self, text: &str, session_id: Option<&str>) -> Option<String>;
+    /// Every user and assistant message, oldest first and unredacted.
+    /// `session_id` picks one session from files that hold several.
+    fn transcript(&self, text: &str, session_id: Option<&str>) -> Vec<TranscriptMessage>;
+    /// Tool calls and tool outputs, for `search --in tool`.
+    fn tool_texts(&self, _text: &str) -> Vec<String> {
+        Vec::new()
+    }
+    /// Whether messages carry timestamps, so `--since/--until` apply.
+    fn supports_time_span(&self) -> bool {
+        false
+    }
+    /// Directories the sessions live under, for watchers.
+    fn store_roots(&self) -> Vec<PathBuf>;
+    /// Every session file in the store, newest first, for `fsck`.
+    fn session_files(&self) -> Result<Vec<PathBuf>>;
+}
+
+/// Returns the adapter for the given agent name.
+pub fn get_adapter(agent: &str) -> Option<Box<dyn AgentAdapter>> {
+    match agent {
+        "codex" => Some(Box::new(codex::CodexAdapter)),
+        "gemini" => Some(Box::new(gemini::GeminiAdapter)),
+        "claude" => Some(Box::new(claude::ClaudeAdapter)),
+        "cursor" => Some(Box::new(cursor::CursorAdapter)),
+        "aider" => Some(Box::new(aider::AiderAdapter)),
+        "windsurf" => Some(Box::new(windsurf::WindsurfAdapter)),
+        "cline" => Some(Box::new(cline::ClineAdapter)),
+        "zed" => Some(Box::new(zed::ZedAdapter)),
+        _ => custom_adapter(agent),
+    }
+}
+
+/// An adapter declared in `adapters.toml`, else a WASM plugin.
+fn custom_adapter(agent: &str) -> Option<Box<dyn AgentAdapter>> {
+    if let Some(adapter) = custom::adapter(agent) {
+        return Some(Box::new(adapter));
+    }
+    #[cfg(feature = "wasm-plugins")]
+    if let Some(adapter) = crate::plugin::adapter(agent) {
+        return Some(Box::new(adapter));
+    }
+    None
+}
+
+/// Canonical names of every registered adapter: the built-ins, then those
+/// declared in `adapters.toml`, then WASM plugins.
+pub fn agent_names() -> Vec<&'static str> {
+    let names = AGENT_NAMES.iter().copied().chain(custom::names());
+    #[cfg(feature = "wasm-plugins")]
+    let names = names.chain(crate::plugin::names());
+    names.collect()
+}
+
+/// `list_sessions` across every adapter, merged newest first (`--agent all`).
+pub fn list_all(cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Vec<Value> {
+    fan_out(limit, |adapter| adapter.list_sessions(cwd, cwd_match, limit))
+}
+
+/// `search_sessions` across every adapter, merged newest first (`--agent all`).
+pub fn search_all(query: &SearchQuery, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Vec<Value> {
+    fan_out(limit, |adapter| adapter.search_sessions(query, cwd, cwd_match, limit))
+}
+
+/// `read` every session `options.id` matches (`--all-matching`), newest
+/// first: the only match, or each candidate of the `AMBIGUOUS_ID` error it
+/// raises with `all_matching` set (even when one equals the id), read by
+/// exact id.
+pub fn read_matching<T>(options: &ReadOptions, read: impl Fn(&ReadOptions) -> Result<T>) -> Result<Vec<T>> {
+    match read(&ReadOptions { all_matching: true, ..options.clone() }) {
+        Err(err) if agents::error_code(&err) == BridgeErrorCode::AmbiguousId => agents::error_candidates(&err)
+            .iter()
+            .map(|id| {
+                read(&ReadOptions { id: Some(id.clone()), id_match: IdMatch::Exact, all_matching: false, ..options.clone() })
+            })
+            .collect(),
+        result => Ok(vec![result?]),
+    }
+}
+
+/// Run `query` on every adapter, sort the merged entries by `modified_at`
+/// (newest first; ties keep registry order), and keep `limit`. An agent whose
+/// store cannot be read is skipped with a warning instead of failing the rest.
+fn fan_out<F>(limit: usize, query: F) -> Vec<Value>
+where
+    F: Fn(&dyn AgentAdapter) -> Result<Vec<Value>>,
+{
+    let mut entries = Vec::new();
+    for agent in agent_names() {
+        let Some(adapter) = get_adapter(agent) else {
+            continue;
+        };
+        match query(adapter.as_ref()) {
+            Ok(found) => entries.extend(found),
+            Err(err) => eprintln!("{}", theme::paint("warning", &format!("Warning: skipped {}: {}", agent, err))),
+        }
+    }
+    entries.sort_by_key(|entry| Reverse(entry["modified_at"].as_str().unwrap_or("").to_string()));
+    entries.truncate(limit);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{common, fan_out, normalize_agent, read_matching};
+    use crate::agents::ReadOptions;
+    use anyhow::{anyhow, Result};
+    use serde_json::json;
+    use std::cell::Cell;
+
+    #[test]
+    fn normalize_agent_accepts_aliases_and_any_case() {
+        assert_eq!(normalize_agent("Claude").unwrap(), "claude");
+        assert_eq!(normalize_agent("claude_code").unwrap(), "claude");
+        assert_eq!(normalize_agent(" GPT ").unwrap(), "codex");
+        assert_eq!(normalize_agent("gemini-cli").unwrap(), "gemini");
+        assert!(normalize_agent("copilot").is_err());
+    }
+
+    #[test]
+    fn agent_all_merges_newest_first_and_skips_failing_agents() {
+        let calls = Cell::new(0);
+        let merged = fan_out(3, |_| {
+            let call = calls.get();
+            calls.set(call + 1);
+            match call {
+                1 => Err(anyhow!("store unreadable")),
+                _ => Ok(vec![json!({"call": call, "modified_at": format!("2026-01-{:02}T00:00:00Z", (call * 5) % 9 + 1)})]),
+            }
+        });
+        let order = merged.iter().map(|entry| entry["call"].as_u64().unwrap()).collect::<Vec<_>>();
+        assert_eq!(order, [7, 5, 3]);
+    }
+
+    #[test]
+    fn read_matching_reads_every_candidate_of_an_ambiguous_id() {
+        let stems = ["rollout-b", "rollout-a", "abc-2", "abc", "abc-1", "other"];
+        let read = |options: &ReadOptions| -> Result<String> {
+            let id = options.id.as_deref().unwrap_or_default();
+            let paths = stems.iter().map(std::path::PathBuf::from).collect::<Vec<_>>();
+            let path = common::select_path_by_id(&paths, id, options, "Test")?;
+            Ok(format!("{:?} {}", options.id_match, path.display()))
+        };
+        let options = |id: &str| ReadOptions { id: Some(id.to_string()), ..ReadOptions::default() };
+
+        assert_eq!(read_matching(&options("rollout"), read).unwrap(), ["Exact rollout-b", "Exact rollout-a"]);
+        assert_eq!(read_matching(&options("abc"), read).unwrap(), ["Exact abc-2", "Exact abc", "Exact abc-1"]);
+        assert_eq!(read_matching(&options("oth"), read).unwrap(), ["Substring other"]);
+        assert!(read_matching(&options("missing"), read).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Windsurf is a VS Code fork; Cascade keeps conversation state as JSON or
+//! JSONL files in `User/workspaceStorage/<hash>/` (per workspace) and
+//! `User/globalStorage/` under its application-support directory. Each
+//! workspace storage directory has a `workspace.json` naming the folder it
+//! belongs to, which is the session cwd.
+
+use super::{common, AgentAdapter};
+use crate::agents::{self, BridgeErrorCode, CwdMatch, MessageWindow, ReadOptions, SearchQuery, Session, TranscriptMessage, TurnKind};
+use crate::redact::redact_sensitive_text;
+use crate::utils::normalize_path;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct WindsurfAdapter;
+
+/// Message arrays Cascade state and exports have used, in lookup order.
+const MESSAGE_KEYS: &[&str] = &["messages", "turns", "steps", "conversation"];
+const USER_ROLES: &[&str] = &["user", "human", "user_input"];
+const ASSISTANT_ROLES: &[&str] = &["assistant", "model", "bot", "ai", "cascade", "planner_response"];
+
+#[derive(Debug, Default)]
+struct Conversation {
+    model: Option<String>,
+    prompts: Vec<String>,
+    replies: Vec<String>,
+    order: Vec<TurnKind>,
+}
+
+impl AgentAdapter for WindsurfAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let base_dirs = base_dirs();
+        if !base_dirs.iter().any(|dir| dir.exists()) {
+            return Err(BridgeErrorCode::NotFound.error(format!(
+                "No Windsurf session found. Data directory not found: {}",
+                display(&base_dirs)
+            )));
+        }
+        let files = session_files()?;
+        let mut warnings = Vec::new();
+        let target = match options.id.as_deref() {
+            Some(id) => common::select_path_by_id(&files, id, options, "Windsurf")?,
+            None => {
+                let newest = files.first().ok_or_else(|| BridgeErrorCode::NotFound.error("No Windsurf session found."))?;
+                let expected = normalize_path(&options.cwd)?;
+                match files.iter().find(|file| {
+                    workspace_cwd(file).is_some_and(|cwd| options.cwd_match.matches(&cwd, &expected))
+                }) {
+                    Some(scoped) => scoped.clone(),
+                    None => {
+                        warnings.push(format!(
+                            "Warning: no Windsurf session matched cwd {}; falling back to latest session.",
+                            expected.display()
+                        ));
+                        newest.clone()
+                    }
+                }
+            }
+        };
+
+        let conversation = read_conversation(&target)?;
+        let mut session = to_session(&target.to_string_lossy(), &conversation, options.window())?;
+        session.warnings = warnings;
+        session.cwd = workspace_cwd(&target).map(|cwd| cwd.to_string_lossy().to_string());
+        session.timestamp = agents::file_modified_iso(&target);
+        Ok(session)
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for file in session_files()? {
+            let session_cwd = workspace_cwd(&file);
+            if !in_scope(session_cwd.as_deref(), expected.as_deref(), cwd_match) {
+                continue;
+            }
+            let model = read_conversation(&file).ok().and_then(|conversation| conversation.model);
+            entries.push(json!({
+                "session_id": file_stem(&file),
+                "agent": "windsurf",
+                "cwd": session_cwd.map(|cwd| cwd.to_string_lossy().to_string()),
+                "model": model,
+                "modified_at": agents::file_modified_iso(&file),
+                "file_path": file.to_string_lossy(),
+            }));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for file in session_files()? {
+            if entries.len() >= limit {
+                break;
+            }
+            let session_cwd = workspace_cwd(&file);
+            if !in_scope(session_cwd.as_deref(), expected.as_deref(), cwd_match) {
+                continue;
+            }
+            let Ok(conversation) = read_conversation(&file) else {
+                continue;
+            };
+            let cwd_text = session_cwd.as_ref().map(|cwd| cwd.to_string_lossy().to_string());
+            let matches =
+                query.matches_turns(&conversation.prompts, &conversation.replies, Some(&file_stem(&file)), cwd_text.as_deref());
+            if matches {
+                entries.push(json!({
+                    "session_id": file_stem(&file),
+                    "agent": "windsurf",
+                    "cwd": cwd_text,
+                    "modified_at": agents::file_modified_iso(&file),
+                    "file_path": file.to_string_lossy(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        let mut session = to_session(name, &parse_conversation(text), window)?;
+        session.session_id = Some(file_stem(Path::new(name))).filter(|stem| !stem.is_empty());
+        Ok(session)
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        parse_conversation(text).prompts.into_iter().next()
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        let conversation = parse_conversation(text);
+        agents::interleave(&conversation.prompts, &conversation.replies, &conversation.order)
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        base_dirs()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        session_files()
+    }
+}
+
+/// Windsurf application-support roots: `--data-dir`, `BRIDGE_WINDSURF_DATA_DIR`,
+/// or `[scan_roots] windsurf`, else the platform default
+/// (`~/Library/Application Support/Windsurf`, `~/.config/Windsurf`, `%APPDATA%\Windsurf`).
+fn base_dirs() -> Vec<PathBuf> {
+    common::configured_roots("windsurf", "BRIDGE_WINDSURF_DATA_DIR").unwrap_or_else(|| {
+        vec![dirs::config_dir()
+            .map(|dir| dir.join("Windsurf"))
+            .unwrap_or_else(|| PathBuf::from("~/.config/Windsurf"))]
+    })
+}
+
+/// Every Cascade state file under the Windsurf roots, newest first.
+fn session_files() -> Result<Vec<PathBuf>> {
+    let storage = base_dirs()
+        .into_iter()
+        .flat_map(|base| {
+            let user = base.join("User");
+            [user.join("workspaceStorage"), user.join("globalStorage")]
+        })
+        .collect::<Vec<_>>();
+    common::collect_paths(&storage, &is_cascade_file)
+}
+
+fn is_cascade_file(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("").to_ascii_lowercase();
+    (name.ends_with(".json") || name.ends_with(".jsonl"))
+        && (name.contains("cascade") || name.contains("conversation") || name.contains("chat"))
+}
+
+fn in_scope(session_cwd: Option<&Path>, expected: Option<&Path>, cwd_match: CwdMatch) -> bool {
+    match expected {
+        None => true,
+        Some(expected) => session_cwd.is_some_and(|cwd| cwd_match.matches(cwd, expected)),
+    }
+}
+
+/// The folder named by the `workspace.json` of the workspace storage
+/// directory holding `file`. Global-storage conversations have no cwd.
+/// Cursor shares the VS Code workspace storage layout and uses this too.
+pub(crate) fn workspace_cwd(file: &Path) -> Option<PathBuf> {
+    let workspace = file
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.file_name().is_some_and(|name| name != "workspaceStorage"))
+        .map(|dir| dir.join("workspace.json"))
+        .find(|path| path.is_file())?;
+    let json: Value = serde_json::from_str(&fs::read_to_string(workspace).ok()?).ok()?;
+    // Multi-root workspaces point at a `.code-workspace` file; use its folder.
+    let (uri, is_file) = match json["folder"].as_str() {
+        Some(folder) => (folder, false),
+        None => (json["workspace"].as_str()?, true),
+    };
+    let path = PathBuf::from(decode_file_uri(uri)?);
+    let path = if is_file { path.parent()?.to_path_buf() } else { path };
+    normalize_path(&path.to_string_lossy()).ok()
+}
+
+/// `file:///Users/me/my%20repo` -> `/Users/me/my repo`. Remote URIs
+/// (`vscode-remote://...`) have no local path.
+fn decode_file_uri(uri: &str) -> Option<String> {
+    let bytes = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes.get(index + 1..index + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        match (bytes[index], hex.and_then(|hex| u8::from_str_radix(hex, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    // Windows URIs carry a slash before the drive letter (`/c:/code`).
+    if let [b'/', drive, b':', ..] = decoded.as_slice() {
+        if drive.is_ascii_alphabetic() {
+            decoded.remove(0);
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("unknown").to_string()
+}
+
+fn display(roots: &[PathBuf]) -> String {
+    roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn read_conversation(path: &Path) -> Result<Conversation> {
+    if fs::metadata(path).map(|meta| meta.len() > common::max_file_size()).unwrap_or(false) {
+        return Err(anyhow!(
+            "Skipped {} (exceeds {}MB size limit)",
+            path.display(),
+            common::max_file_size() / (1024 * 1024)
+        ));
+    }
+    let raw = fs::read(path).map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to read {}: {}", path.display(), err)))?;
+    Ok(parse_conversation(&String::from_utf8_lossy(&raw)))
+}
+
+fn to_session(source: &str, conversation: &Conversation, window: MessageWindow) -> Result<Session> {
+    if conversation.replies.is_empty() {
+        return Err(BridgeErrorCode::EmptySession.error(format!("Windsurf session has no messages: {}", source)));
+    }
+    let replies = &conversation.replies;
+    let selected = window.select(replies).iter().map(|text| redact_sensitive_text(text)).collect::<Vec<_>>();
+    let messages_returned = selected.len();
+    Ok(Session {
+        agent: "windsurf",
+        content: agents::join_messages(&selected),
+        messages: selected,
+        source: source.to_string(),
+        warnings: Vec::new(),
+        session_id: Some(file_stem(Path::new(source))),
+        cwd: None,
+        timestamp: None,
+        message_count: replies.len(),
+        messages_returned,
+        tool_usage: None,
+        timing: None,
+        model: conversation.model.clone(),
+        usage: None,
+    })
+}
+
+/// A JSON document holding a message array (see `MESSAGE_KEYS`), a bare
+/// array of messages, or JSONL with one message per line.
+fn parse_conversation(text: &str) -> Conversation {
+    let (items, model) = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(items)) => (items, None),
+        Ok(json) => (
+            MESSAGE_KEYS
+                .iter()
+                .find_map(|key| json[*key].as_array().cloned())
+                .unwrap_or_default(),
+            json["model"].as_str().map(str::to_string),
+        ),
+        Err(_) => (
+            text.lines().filter_map(|line| serde_json::from_str::<Value>(line).ok()).collect(),
+            None,
+        ),
+    };
+
+    let mut conversation = Conversation { model, ..Conversation::default() };
+    for item in &items {
+        let role = ["role", "type", "source", "author"]
+            .iter()
+            .find_map(|key| item[*key].as_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let text = message_text(item);
+        if text.trim().is_empty() {
+            continue;
+        }
+        if USER_ROLES.contains(&role.as_str()) {
+            conversation.prompts.push(text);
+            conversation.order.push(TurnKind::Prompt);
+        } else if ASSISTANT_ROLES.contains(&role.as_str()) {
+            conversation.replies.push(text);
+            conversation.order.push(TurnKind::Reply);
+            if let Some(model) = item["model"].as_str() {
+                conversation.model = Some(model.to_string());
+            }
+        }
+    }
+    conversation
+}
+
+fn message_text(item: &Value) -> String {
+    ["content", "text", "message"]
+        .iter()
+        .map(|key| match &item[*key] {
+            Value::Object(inner) => agents::extract_text(inner.get("content").unwrap_or(&Value::Null)),
+            value => agents::extract_text(value),
+        })
+        .find(|text| !text.is_empty())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_file_uri, parse_conversation};
+
+    #[test]
+    fn parses_cascade_messages_and_workspace_uris() {
+        let state = r#"{"model": "swe-1", "messages": [
+            {"role": "user", "content": "Fix the login bug"},
+            {"role": "assistant", "content": [{"type": "text", "text": "Patched auth.rs"}]},
+            {"source": "USER", "text": "Add a test"},
+            {"source": "CASCADE", "message": {"content": "Added"}, "model": "claude-sonnet"}
+        ]}"#;
+        let conversation = parse_conversation(state);
+        assert_eq!(conversation.prompts, vec!["Fix the login bug", "Add a test"]);
+        assert_eq!(conversation.replies, vec!["Patched auth.rs", "Added"]);
+        assert_eq!(conversation.model.as_deref(), Some("claude-sonnet"));
+
+        let jsonl = "{\"role\":\"user\",\"content\":\"hi\"}\n{\"role\":\"assistant\",\"content\":\"hello\"}\n";
+        assert_eq!(parse_conversation(jsonl).replies, vec!["hello"]);
+
+        assert_eq!(decode_file_uri("file:///Users/me/my%20repo").as_deref(), Some("/Users/me/my repo"));
+        assert_eq!(decode_file_uri("file:///c%3A/code/app").as_deref(), Some("c:/code/app"));
+        assert_eq!(decode_file_uri("vscode-remote://ssh-remote+box/home/me"), None);
+    }
+}
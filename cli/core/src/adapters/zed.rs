@@ -0,0 +1,325 @@
+//! Zed saves assistant conversations as `<title> - <n>.zed.json` files.
+//! Current versions keep the whole conversation in one `text` buffer and
+//! mark where each message starts (`messages[].start`, role in
+//! `metadata.role` or the older `message_metadata` map). Early versions used a
+//! plain `messages: [{role, content}]` array. Conversations are not tied to a
+//! directory, so, as with Cursor, `--cwd` only filters `list` and `search` by
+//! conversations that mention the path.
+
+use super::{common, AgentAdapter};
+use crate::agents::{self, BridgeErrorCode, CwdMatch, MessageWindow, ReadOptions, SearchQuery, Session, TranscriptMessage, TurnKind};
+use crate::redact::redact_sensitive_text;
+use crate::utils::{expand_home, normalize_path};
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub struct ZedAdapter;
+
+const EXTENSION: &str = ".zed.json";
+
+#[derive(Debug, Default)]
+struct Conversation {
+    title: Option<String>,
+    model: Option<String>,
+    prompts: Vec<String>,
+    replies: Vec<String>,
+    order: Vec<TurnKind>,
+}
+
+impl AgentAdapter for ZedAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<Session> {
+        let roots = base_dirs();
+        if !roots.iter().any(|dir| dir.exists()) {
+            return Err(BridgeErrorCode::NotFound.error(format!(
+                "No Zed session found. Conversations directory not found: {}",
+                display(&roots)
+            )));
+        }
+        let files = session_files()?;
+        let target = match options.id.as_deref() {
+            Some(id) => select_by_id(&files, id, options)?,
+            None => files.first().cloned().ok_or_else(|| BridgeErrorCode::NotFound.error("No Zed session found."))?,
+        };
+        let conversation = read_conversation(&target)?;
+        let mut session = to_session(&target.to_string_lossy(), &conversation, options.window())?;
+        session.timestamp = agents::file_modified_iso(&target);
+        Ok(session)
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, _cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let mentions = cwd_text(cwd)?;
+        let mut entries = Vec::new();
+        for file in session_files()? {
+            let Ok(conversation) = read_conversation(&file) else {
+                continue;
+            };
+            if !mentions_cwd(&conversation, mentions.as_deref()) {
+                continue;
+            }
+            entries.push(json!({
+                "session_id": session_id(&file),
+                "agent": "zed",
+                "cwd": Value::Null,
+                "model": conversation.model,
+                "modified_at": agents::file_modified_iso(&file),
+                "file_path": file.to_string_lossy(),
+            }));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        _cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let mentions = cwd_text(cwd)?;
+        let mut entries = Vec::new();
+        for file in session_files()? {
+            if entries.len() >= limit {
+                break;
+            }
+            let Ok(conversation) = read_conversation(&file) else {
+                continue;
+            };
+            if !mentions_cwd(&conversation, mentions.as_deref()) {
+                continue;
+            }
+            let matches = query.matches_turns(
+                conversation.title.iter().chain(&conversation.prompts),
+                &conversation.replies,
+                Some(&session_id(&file)),
+                None,
+            );
+            if matches {
+                entries.push(json!({
+                    "session_id": session_id(&file),
+                    "agent": "zed",
+                    "cwd": Value::Null,
+                    "modified_at": agents::file_modified_iso(&file),
+                    "file_path": file.to_string_lossy(),
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, window: MessageWindow) -> Result<Session> {
+        let json: Value = serde_json::from_str(text)
+            .map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to parse {}: {}", name, err)))?;
+        to_session(name, &parse_conversation(&json), window)
+    }
+
+    fn first_prompt(&self, text: &str, _session_id: Option<&str>) -> Option<String> {
+        let json: Value = serde_json::from_str(text).ok()?;
+        parse_conversation(&json).prompts.into_iter().next()
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        let Ok(json) = serde_json::from_str::<Value>(text) else {
+            return Vec::new();
+        };
+        let conversation = parse_conversation(&json);
+        agents::interleave(&conversation.prompts, &conversation.replies, &conversation.order)
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        base_dirs()
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        session_files()
+    }
+}
+
+/// `--data-dir`, `BRIDGE_ZED_CONVERSATIONS_DIR`, or `[scan_roots] zed`, else
+/// `~/.config/zed/conversations` plus the data-directory location newer Zed
+/// builds use (`~/.local/share/zed`, `~/Library/Application Support/Zed`).
+fn base_dirs() -> Vec<PathBuf> {
+    common::configured_roots("zed", "BRIDGE_ZED_CONVERSATIONS_DIR").unwrap_or_else(|| {
+        let mut roots = Vec::new();
+        roots.extend(expand_home("~/.config/zed/conversations"));
+        let data_dir = dirs::data_dir().map(|dir| {
+            let name = if cfg!(target_os = "linux") { "zed" } else { "Zed" };
+            dir.join(name).join("conversations")
+        });
+        roots.extend(data_dir.filter(|dir| !roots.contains(dir)));
+        roots
+    })
+}
+
+/// Every saved conversation, newest first.
+fn session_files() -> Result<Vec<PathBuf>> {
+    common::collect_paths(&base_dirs(), &|path| {
+        path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.ends_with(EXTENSION))
+    })
+}
+
+/// File name without `.zed.json`, e.g. `Fix flaky test - 1`.
+fn session_id(path: &Path) -> String {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("unknown");
+    name.strip_suffix(EXTENSION).unwrap_or(name).to_string()
+}
+
+fn select_by_id(files: &[PathBuf], id: &str, options: &ReadOptions) -> Result<PathBuf> {
+    let candidates = files
+        .iter()
+        .filter(|file| options.id_match.matches(&session_id(file), id))
+        .cloned()
+        .collect();
+    common::pick_by_id(candidates, id, options, "Zed", |file| session_id(file))
+}
+
+fn cwd_text(cwd: Option<&str>) -> Result<Option<String>> {
+    Ok(cwd.map(normalize_path).transpose()?.map(|path| path.to_string_lossy().to_ascii_lowercase()))
+}
+
+fn mentions_cwd(conversation: &Conversation, cwd: Option<&str>) -> bool {
+    let Some(cwd) = cwd else {
+        return true;
+    };
+    conversation
+        .prompts
+        .iter()
+        .chain(&conversation.replies)
+        .any(|text| text.to_ascii_lowercase().contains(cwd))
+}
+
+fn display(roots: &[PathBuf]) -> String {
+    roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn read_conversation(path: &Path) -> Result<Conversation> {
+    if fs::metadata(path).map(|meta| meta.len() > common::max_file_size()).unwrap_or(false) {
+        return Err(anyhow!(
+            "Skipped {} (exceeds {}MB size limit)",
+            path.display(),
+            common::max_file_size() / (1024 * 1024)
+        ));
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to read {}: {}", path.display(), err)))?;
+    let json = serde_json::from_str(&text)
+        .map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to parse {}: {}", path.display(), err)))?;
+    Ok(parse_conversation(&json))
+}
+
+fn parse_conversation(json: &Value) -> Conversation {
+    let mut conversation = Conversation {
+        title: json["summary"].as_str().map(str::to_string),
+        model: match &json["model"] {
+            Value::String(model) => Some(model.clone()),
+            model => model["name"].as_str().or_else(|| model["id"].as_str()).map(str::to_string),
+        },
+        ..Conversation::default()
+    };
+    let messages = json["messages"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let buffer = json["text"].as_str();
+
+    for (index, message) in messages.iter().enumerate() {
+        let (role, text) = match buffer {
+            Some(buffer) => {
+                let start = message["start"].as_u64().unwrap_or(0) as usize;
+                let end = messages
+                    .get(index + 1)
+                    .and_then(|next| next["start"].as_u64())
+                    .map(|end| end as usize)
+                    .unwrap_or(buffer.len());
+                let role = message["metadata"]["role"]
+                    .as_str()
+                    .or_else(|| {
+                        let id = message["id"]["value"].as_u64().or_else(|| message["id"].as_u64())?;
+                        json["message_metadata"][id.to_string()]["role"].as_str()
+                    })
+                    .unwrap_or("");
+                (role, buffer.get(start..end.min(buffer.len())).unwrap_or("").to_string())
+            }
+            None => (message["role"].as_str().unwrap_or(""), agents::extract_text(&message["content"])),
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match role {
+            "user" => conversation.prompts.push(text.to_string()),
+            "assistant" => conversation.replies.push(text.to_string()),
+            _ => continue,
+        }
+        conversation.order.push(if role == "user" { TurnKind::Prompt } else { TurnKind::Reply });
+    }
+    conversation
+}
+
+fn to_session(source: &str, conversation: &Conversation, window: MessageWindow) -> Result<Session> {
+    if conversation.replies.is_empty() {
+        return Err(BridgeErrorCode::EmptySession.error(format!("Zed session has no messages: {}", source)));
+    }
+    let replies = &conversation.replies;
+    let selected = window.select(replies).iter().map(|text| redact_sensitive_text(text)).collect::<Vec<_>>();
+    let messages_returned = selected.len();
+    Ok(Session {
+        agent: "zed",
+        content: agents::join_messages(&selected),
+        messages: selected,
+        source: source.to_string(),
+        warnings: Vec::new(),
+        session_id: Some(session_id(Path::new(source))).filter(|id| !id.is_empty()),
+        cwd: None,
+        timestamp: None,
+        message_count: replies.len(),
+        messages_returned,
+        tool_usage: None,
+        timing: None,
+        model: conversation.model.clone(),
+        usage: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_conversation;
+    use serde_json::json;
+
+    #[test]
+    fn parses_buffer_ranges_and_legacy_messages() {
+        let text = "Why is CI red?\nThe cache key changed.\nFix it\nUpdated ci.yml.\n";
+        let saved = json!({
+            "zed": "context",
+            "version": "0.4.0",
+            "summary": "CI cache",
+            "text": text,
+            "messages": [
+                {"id": {"replica_id": 0, "value": 0}, "start": 0, "metadata": {"role": "user"}},
+                {"id": {"replica_id": 0, "value": 1}, "start": 15, "metadata": {"role": "assistant"}},
+                {"id": {"replica_id": 0, "value": 2}, "start": 38, "metadata": {"role": "user"}},
+                {"id": {"replica_id": 0, "value": 3}, "start": 45, "metadata": {"role": "assistant"}}
+            ]
+        });
+        let conversation = parse_conversation(&saved);
+        assert_eq!(conversation.title.as_deref(), Some("CI cache"));
+        assert_eq!(conversation.prompts, vec!["Why is CI red?", "Fix it"]);
+        assert_eq!(conversation.replies, vec!["The cache key changed.", "Updated ci.yml."]);
+
+        let v01 = json!({
+            "text": "hi\nhello",
+            "messages": [{"id": 0, "start": 0}, {"id": 1, "start": 3}],
+            "message_metadata": {"0": {"role": "user"}, "1": {"role": "assistant"}}
+        });
+        assert_eq!(parse_conversation(&v01).replies, vec!["hello"]);
+
+        let legacy = json!({"model": "gpt-4", "messages": [
+            {"role": "user", "content": "q"},
+            {"role": "assistant", "content": "a"}
+        ]});
+        let conversation = parse_conversation(&legacy);
+        assert_eq!(conversation.model.as_deref(), Some("gpt-4"));
+        assert_eq!(conversation.replies, vec!["a"]);
+    }
+}
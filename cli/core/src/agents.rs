@@ -0,0 +1,1687 @@
+use crate::adapters::common::{max_file_size, scan_truncated};
+use crate::index::SessionMeta;
+use crate::redact::redact_sensitive_text;
+use crate::theme;
+use crate::utils::{expand_home, sanitize_for_terminal};
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Longest session `title` in `list` output, in characters.
+const TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeErrorCode {
+    NotFound,
+    ParseFailed,
+    InvalidHandoff,
+    UnsupportedAgent,
+    UnsupportedMode,
+    IoError,
+    EmptySession,
+    AmbiguousId,
+}
+
+impl BridgeErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NOT_FOUND",
+            Self::ParseFailed => "PARSE_FAILED",
+            Self::InvalidHandoff => "INVALID_HANDOFF",
+            Self::UnsupportedAgent => "UNSUPPORTED_AGENT",
+            Self::UnsupportedMode => "UNSUPPORTED_MODE",
+            Self::IoError => "IO_ERROR",
+            Self::EmptySession => "EMPTY_SESSION",
+            Self::AmbiguousId => "AMBIGUOUS_ID",
+        }
+    }
+
+    /// Process exit status for a failure with this code. 1 stays the general
+    /// failure and 2 is left to usage errors and `report`/`compare` verdicts.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::IoError => 1,
+            Self::NotFound => 3,
+            Self::ParseFailed => 4,
+            Self::InvalidHandoff => 5,
+            Self::UnsupportedAgent => 6,
+            Self::UnsupportedMode => 7,
+            Self::EmptySession => 8,
+            Self::AmbiguousId => 9,
+        }
+    }
+
+    /// An error carrying this code, so `error_code` need not guess it from
+    /// `message`.
+    pub fn error(self, message: impl Into<String>) -> anyhow::Error {
+        self.error_with_candidates(message, Vec::new())
+    }
+
+    /// `error` for a failure the caller can fix by picking one of
+    /// `candidates`, which JSON output lists alongside the message.
+    pub fn error_with_candidates(self, message: impl Into<String>, candidates: Vec<String>) -> anyhow::Error {
+        anyhow::Error::new(BridgeError { code: self, message: message.into(), candidates })
+    }
+}
+
+/// A failure whose code is known where it happens. Build one with
+/// `BridgeErrorCode::error`; context added on top keeps the code.
+#[derive(Debug)]
+pub struct BridgeError {
+    pub code: BridgeErrorCode,
+    pub message: String,
+    /// Session ids that would resolve the failure, newest first.
+    pub candidates: Vec<String>,
+}
+
+impl std::fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+/// The code of `err`: that of the outermost `BridgeError` in its chain, or a
+/// guess from the message for errors raised without one.
+pub fn error_code(err: &anyhow::Error) -> BridgeErrorCode {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<BridgeError>())
+        .map_or_else(|| classify_error(&format!("{:#}", err)), |typed| typed.code)
+}
+
+/// The candidates of the outermost `BridgeError` in `err`'s chain, if any.
+pub fn error_candidates(err: &anyhow::Error) -> &[String] {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<BridgeError>())
+        .map_or(&[], |typed| typed.candidates.as_slice())
+}
+
+/// `err` with `code`, unless a `BridgeError` in its chain already names one.
+pub fn with_code(err: anyhow::Error, code: BridgeErrorCode) -> anyhow::Error {
+    if err.chain().any(|cause| cause.is::<BridgeError>()) {
+        err
+    } else {
+        code.error(format!("{:#}", err))
+    }
+}
+
+/// Guess a code from an error message, for errors without a `BridgeError`
+/// (clap's, and those from outside this crate).
+pub fn classify_error(message: &str) -> BridgeErrorCode {
+    let lower = message.to_ascii_lowercase();
+    if lower.contains("unsupported agent") || lower.contains("unknown agent") {
+        BridgeErrorCode::UnsupportedAgent
+    } else if lower.contains("unsupported mode") {
+        BridgeErrorCode::UnsupportedMode
+    } else if lower.contains("is ambiguous") {
+        BridgeErrorCode::AmbiguousId
+    } else if lower.contains("no") && lower.contains("session found")
+        || lower.contains("not found")
+        || lower.contains("no user prompt found")
+    {
+        BridgeErrorCode::NotFound
+    } else if lower.contains("failed to parse") || lower.contains("failed to read") {
+        BridgeErrorCode::ParseFailed
+    } else if lower.contains("missing required") || lower.contains("invalid handoff") || lower.contains("must provide session_id") {
+        BridgeErrorCode::InvalidHandoff
+    } else if lower.contains("has no messages") || lower.contains("history is empty") {
+        BridgeErrorCode::EmptySession
+    } else {
+        BridgeErrorCode::IoError
+    }
+}
+
+/// How a session's recorded cwd is compared against the requested `--cwd`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CwdMatch {
+    /// Session cwd must equal the requested path.
+    #[default]
+    Exact,
+    /// Session cwd must equal or sit beneath the requested path.
+    Prefix,
+    /// Session cwd must equal or sit above the requested path.
+    Ancestor,
+}
+
+impl CwdMatch {
+    pub fn matches(&self, session_cwd: &Path, expected: &Path) -> bool {
+        match self {
+            CwdMatch::Exact => session_cwd == expected,
+            CwdMatch::Prefix => session_cwd.starts_with(expected),
+            CwdMatch::Ancestor => expected.starts_with(session_cwd),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Session {
+    pub agent: &'static str,
+    pub content: String,
+    /// The selected messages `content` joins, oldest first; empty when
+    /// `content` is a raw-line fallback rather than parsed messages.
+    pub messages: Vec<String>,
+    pub source: String,
+    pub warnings: Vec<String>,
+    pub session_id: Option<String>,
+    pub cwd: Option<String>,
+    pub timestamp: Option<String>,
+    pub message_count: usize,
+    pub messages_returned: usize,
+    /// Tool name -> `{calls, failures}`; only agents that record tool calls.
+    pub tool_usage: Option<Value>,
+    /// Session start/end and per-reply durations, when messages carry timestamps.
+    pub timing: Option<Value>,
+    /// Model that produced the latest reply, when the session records it.
+    pub model: Option<String>,
+    /// Provider-reported token totals; only agents that record usage.
+    pub usage: Option<Value>,
+}
+
+/// How `--id` is matched against session file stems.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum IdMatch {
+    /// File stem must equal the id.
+    Exact,
+    /// File stem must start with the id.
+    Prefix,
+    /// File stem must contain the id; an exact stem match always wins.
+    #[default]
+    Substring,
+}
+
+impl IdMatch {
+    pub(crate) fn matches(&self, stem: &str, id: &str) -> bool {
+        match self {
+            IdMatch::Exact => stem == id,
+            IdMatch::Prefix => stem.starts_with(id),
+            IdMatch::Substring => stem.contains(id),
+        }
+    }
+}
+
+/// Session selection options shared by every adapter's `read_session`.
+#[derive(Debug, Clone)]
+pub struct ReadOptions {
+    pub id: Option<String>,
+    pub id_match: IdMatch,
+    pub cwd: String,
+    pub cwd_match: CwdMatch,
+    pub chats_dir: Option<String>,
+    pub last_n: usize,
+    /// `--from/--to` or `--offset/--limit`; replaces `last_n` when set.
+    pub range: Option<MessageRange>,
+    /// `--since/--until`; applied before `range`/`last_n`.
+    pub span: Option<TimeSpan>,
+    /// `--all-matching`: an id equal to one session no longer wins over the
+    /// others it matches, so `AMBIGUOUS_ID` lists them all (see
+    /// `adapters::read_matching`).
+    pub all_matching: bool,
+}
+
+impl ReadOptions {
+    pub fn window(&self) -> MessageWindow {
+        match self.range {
+            Some(range) => MessageWindow::Range(range),
+            None => MessageWindow::Last(self.last_n),
+        }
+    }
+}
+
+/// Assistant message indices `from..to` (0-based, `to` exclusive; open-ended
+/// when `to` is `None`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageRange {
+    pub from: usize,
+    pub to: Option<usize>,
+}
+
+/// Which assistant messages a read returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageWindow {
+    /// The newest N (`--last`).
+    Last(usize),
+    Range(MessageRange),
+}
+
+impl MessageWindow {
+    /// Whether anything other than the single latest message is wanted.
+    pub(crate) fn is_multi(&self) -> bool {
+        !matches!(self, MessageWindow::Last(n) if *n <= 1)
+    }
+
+    /// The part of `items` (oldest first) this window covers; empty when a
+    /// range starts past the end.
+    pub(crate) fn select<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        match *self {
+            MessageWindow::Last(n) => &items[items.len().saturating_sub(n.max(1))..],
+            MessageWindow::Range(MessageRange { from, to }) => {
+                let end = to.unwrap_or(items.len()).min(items.len());
+                &items[from.min(end)..end]
+            }
+        }
+    }
+}
+
+/// Epoch-millisecond bounds from `--since/--until`, both inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSpan {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
+impl TimeSpan {
+    /// Messages without a timestamp are never inside a span.
+    pub(crate) fn contains(&self, ms: Option<i64>) -> bool {
+        ms.is_some_and(|ms| self.since.map_or(true, |since| ms >= since) && self.until.map_or(true, |until| ms <= until))
+    }
+}
+
+/// What `search` looks for. Every `all_of` term must match, at least one
+/// `any_of` term must match when any are given, and no `not` term may match.
+/// Terms are case-insensitive substrings, or regexes with `--regex`.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    all_of: Vec<SearchTerm>,
+    any_of: Vec<SearchTerm>,
+    not: Vec<SearchTerm>,
+    /// `--in`: only this role's messages are searched.
+    pub role: Option<SearchRole>,
+    /// `--field`: what part of a session the terms are matched against.
+    pub field: SearchField,
+}
+
+/// Whose messages `search --in` looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SearchRole {
+    User,
+    Assistant,
+    /// Tool calls and their outputs
+    Tool,
+}
+
+/// What `search --field` matches against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SearchField {
+    /// Session content (the whole file, or one role's messages with --in)
+    #[default]
+    Content,
+    /// The session's recorded working directory
+    Cwd,
+    /// The session id
+    SessionId,
+}
+
+#[derive(Debug, Clone)]
+enum SearchTerm {
+    /// Stored ASCII-lowercased, matching how content is folded.
+    Substring(String),
+    Regex(regex::Regex),
+}
+
+impl SearchQuery {
+    /// A single case-insensitive substring, the plain `search <query>` form.
+    pub fn substring(query: &str) -> Self {
+        Self {
+            all_of: vec![SearchTerm::Substring(query.to_ascii_lowercase())],
+            any_of: Vec::new(),
+            not: Vec::new(),
+            role: None,
+            field: SearchField::Content,
+        }
+    }
+
+    /// `query` joins `all_of`. At least one positive term is required.
+    pub fn new(query: Option<&str>, all_of: &[String], any_of: &[String], not: &[String], regex: bool) -> Result<Self> {
+        let terms = |raw: &mut dyn Iterator<Item = &str>| -> Result<Vec<SearchTerm>> {
+            raw.map(|term| {
+                if regex {
+                    regex::RegexBuilder::new(term)
+                        .case_insensitive(true)
+                        .build()
+                        .map(SearchTerm::Regex)
+                        .map_err(|err| anyhow!("Invalid --regex pattern '{}': {}", term, err))
+                } else {
+                    Ok(SearchTerm::Substring(term.to_ascii_lowercase()))
+                }
+            })
+            .collect()
+        };
+        let search = Self {
+            all_of: terms(&mut query.into_iter().chain(all_of.iter().map(String::as_str)))?,
+            any_of: terms(&mut any_of.iter().map(String::as_str))?,
+            not: terms(&mut not.iter().map(String::as_str))?,
+            role: None,
+            field: SearchField::Content,
+        };
+        if search.all_of.is_empty() && search.any_of.is_empty() {
+            return Err(anyhow!("search needs a query, --all-of, or --any-of"));
+        }
+        Ok(search)
+    }
+
+    pub fn matches(&self, content: &str) -> bool {
+        let folded = content.to_ascii_lowercase();
+        let hit = |term: &SearchTerm| match term {
+            SearchTerm::Substring(needle) => folded.contains(needle.as_str()),
+            SearchTerm::Regex(pattern) => pattern.is_match(content),
+        };
+        self.all_of.iter().all(hit)
+            && (self.any_of.is_empty() || self.any_of.iter().any(hit))
+            && !self.not.iter().any(hit)
+    }
+
+    /// `matches` over a session held as separate messages; terms may be
+    /// satisfied by different messages.
+    pub fn matches_texts<'a>(&self, texts: impl IntoIterator<Item = &'a String>) -> bool {
+        self.matches(&texts.into_iter().map(String::as_str).collect::<Vec<_>>().join("\n"))
+    }
+
+    /// Match a session file's raw `content`, honoring `--in` and `--field`.
+    pub fn matches_session(&self, agent: &str, content: &str, session_id: Option<&str>, cwd: Option<&str>) -> bool {
+        match (self.field, self.role) {
+            (SearchField::Cwd, _) => cwd.is_some_and(|cwd| self.matches(cwd)),
+            (SearchField::SessionId, _) => session_id.is_some_and(|id| self.matches(id)),
+            (SearchField::Content, None) => self.matches(content),
+            (SearchField::Content, Some(SearchRole::Tool)) => self.matches_texts(&tool_texts(agent, content)),
+            (SearchField::Content, Some(role)) => {
+                let role = if role == SearchRole::User { "user" } else { "assistant" };
+                let messages = transcript(agent, content.as_bytes(), session_id);
+                self.matches_texts(messages.iter().filter(|message| message.role == role).map(|message| &message.text))
+            }
+        }
+    }
+
+    /// `matches_session` for adapters that already hold a session as prompts
+    /// and replies. They record no tool traffic, so `--in tool` never matches.
+    pub fn matches_turns<'a>(
+        &self,
+        prompts: impl IntoIterator<Item = &'a String>,
+        replies: impl IntoIterator<Item = &'a String>,
+        session_id: Option<&str>,
+        cwd: Option<&str>,
+    ) -> bool {
+        match (self.field, self.role) {
+            (SearchField::Cwd, _) => cwd.is_some_and(|cwd| self.matches(cwd)),
+            (SearchField::SessionId, _) => session_id.is_some_and(|id| self.matches(id)),
+            (SearchField::Content, None) => self.matches_texts(prompts.into_iter().chain(replies)),
+            (SearchField::Content, Some(SearchRole::User)) => self.matches_texts(prompts),
+            (SearchField::Content, Some(SearchRole::Assistant)) => self.matches_texts(replies),
+            (SearchField::Content, Some(SearchRole::Tool)) => false,
+        }
+    }
+}
+
+/// Tool calls (name and arguments) and tool outputs recorded in a session,
+/// for `search --in tool`.
+fn tool_texts(agent: &str, content: &str) -> Vec<String> {
+    crate::adapters::get_adapter(agent).map(|adapter| adapter.tool_texts(content)).unwrap_or_default()
+}
+
+/// The lines of a JSONL session that parse as JSON.
+pub(crate) fn jsonl_values(text: &str) -> impl Iterator<Item = Value> + '_ {
+    text.lines().filter_map(|line| serde_json::from_str::<Value>(line).ok())
+}
+
+/// A JSON value as searchable text: strings as-is, anything else serialized.
+pub(crate) fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Agents whose transcripts timestamp each message, so `--since/--until` can
+/// be applied.
+pub fn supports_time_span(agent: &str) -> bool {
+    crate::adapters::get_adapter(agent).is_some_and(|adapter| adapter.supports_time_span())
+}
+
+/// Parse a `--since/--until` value: RFC 3339 (`2026-01-15T10:30:00Z`), a bare
+/// date (`2026-01-15`, UTC midnight), or an age such as `90s`, `30m`, `2h`,
+/// `1d`, `1w` counted back from now.
+pub fn parse_time_bound(raw: &str) -> Result<i64> {
+    let raw = raw.trim();
+    if let Some(ms) = parse_iso_millis(raw) {
+        return Ok(ms);
+    }
+    if raw.len() == 10 {
+        if let Some(ms) = parse_iso_millis(&format!("{}T00:00:00Z", raw)) {
+            return Ok(ms);
+        }
+    }
+    let split = raw.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(raw.len());
+    let (amount, unit) = raw.split_at(split);
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        _ => 0,
+    };
+    let amount = amount.parse::<i64>().ok().filter(|_| seconds > 0).ok_or_else(|| {
+        anyhow!("Invalid time '{}': use an RFC 3339 timestamp, a YYYY-MM-DD date, or an age like 30m, 2h, 1d", raw)
+    })?;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as i64;
+    Ok(now - amount * seconds * 1000)
+}
+
+/// The items whose timestamp falls inside `span`; everything when `span` is
+/// `None`.
+pub(crate) fn within_span<'a, T>(items: &'a [T], stamps: &[Option<i64>], span: Option<TimeSpan>) -> Vec<&'a T> {
+    items
+        .iter()
+        .zip(stamps)
+        .filter(|(_, ms)| span.map_or(true, |span| span.contains(**ms)))
+        .map(|(item, _)| item)
+        .collect()
+}
+
+/// Selected messages joined the way `read` prints them, or a placeholder when
+/// a range selected nothing.
+pub(crate) fn join_messages<S: AsRef<str>>(texts: &[S]) -> String {
+    if texts.is_empty() {
+        return "[No messages in range]".to_string();
+    }
+    texts.iter().map(|text| text.as_ref()).collect::<Vec<_>>().join("\n---\n")
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            id: None,
+            id_match: IdMatch::default(),
+            cwd: ".".to_string(),
+            cwd_match: CwdMatch::default(),
+            chats_dir: None,
+            last_n: 1,
+            range: None,
+            span: None,
+            all_matching: false,
+        }
+    }
+}
+
+/// Parse an exported session file from its bytes with the same rules as
+/// `read`, without touching the filesystem. `name` is the original file name;
+/// it labels warnings and supplies the session id when the transcript has none.
+pub fn parse_session_bytes(agent: &str, name: &str, bytes: &[u8], last_n: usize) -> Result<Session> {
+    let adapter = crate::adapters::get_adapter(agent)
+        .ok_or_else(|| BridgeErrorCode::UnsupportedAgent.error(format!("Unsupported agent: {}", agent)))?;
+    adapter.parse_bytes(name, &String::from_utf8_lossy(bytes), MessageWindow::Last(last_n.max(1)))
+}
+
+/// Starts of user turns that agents inject themselves (environment and
+/// instruction context, slash-command echoes) rather than the user typing them.
+const INJECTED_PROMPT_PREFIXES: &[&str] = &[
+    "<environment_context>",
+    "<user_instructions>",
+    "<permissions instructions>",
+    "# AGENTS.md instructions",
+    "<command-name>",
+    "<command-message>",
+    "<local-command-stdout>",
+    "<local-command-stderr>",
+    "Caveat: The messages below were generated by the user while running local commands",
+    "This is the Gemini CLI. We are setting up the context for our chat.",
+];
+
+/// `text` without `<system-reminder>` blocks, trimmed, unless what is left is
+/// agent-injected context.
+pub(crate) fn substantive_prompt(text: &str) -> Option<String> {
+    let mut rest = text;
+    let mut kept = String::new();
+    while let Some(start) = rest.find("<system-reminder>") {
+        kept.push_str(&rest[..start]);
+        rest = match rest[start..].find("</system-reminder>") {
+            Some(end) => &rest[start + end + "</system-reminder>".len()..],
+            None => "",
+        };
+    }
+    kept.push_str(rest);
+    let prompt = kept.trim();
+    if prompt.is_empty() || INJECTED_PROMPT_PREFIXES.iter().any(|prefix| prompt.starts_with(prefix)) {
+        return None;
+    }
+    Some(prompt.to_string())
+}
+
+/// The first substantive user prompt in a session file's bytes: the task the
+/// session was started for. Redacted like `read` output. `session_id` picks
+/// one session from files that hold several (Aider).
+pub fn first_prompt(agent: &str, bytes: &[u8], session_id: Option<&str>) -> Option<String> {
+    let prompt = crate::adapters::get_adapter(agent)?.first_prompt(&String::from_utf8_lossy(bytes), session_id)?;
+    Some(redact_sensitive_text(&prompt))
+}
+
+/// One user or assistant message of a whole session, for `bridge export`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptMessage {
+    /// `user` or `assistant`.
+    pub role: &'static str,
+    pub text: String,
+    /// As recorded by the agent; `None` when the format has no per-message time.
+    pub timestamp: Option<String>,
+}
+
+impl TranscriptMessage {
+    pub(crate) fn new(role: &'static str, text: String, timestamp: &Value) -> Self {
+        Self { role, text, timestamp: timestamp.as_str().map(str::to_string) }
+    }
+}
+
+/// Every user and assistant message in a session file's bytes, oldest first
+/// and redacted like `read` output. Tool traffic and injected context are
+/// left out. `session_id` picks one session from files that hold several
+/// (Aider).
+pub fn transcript(agent: &str, bytes: &[u8], session_id: Option<&str>) -> Vec<TranscriptMessage> {
+    let Some(adapter) = crate::adapters::get_adapter(agent) else {
+        return Vec::new();
+    };
+    adapter
+        .transcript(&String::from_utf8_lossy(bytes), session_id)
+        .into_iter()
+        .filter(|message| !message.text.trim().is_empty())
+        .map(|message| TranscriptMessage { text: redact_sensitive_text(message.text.trim()), ..message })
+        .collect()
+}
+
+/// Put separately collected prompts and replies back in session order;
+/// `order` records which list each message was pushed to.
+pub(crate) fn interleave(prompts: &[String], replies: &[String], order: &[TurnKind]) -> Vec<TranscriptMessage> {
+    let (mut prompts, mut replies) = (prompts.iter(), replies.iter());
+    order
+        .iter()
+        .filter_map(|kind| {
+            let (role, text) = match kind {
+                TurnKind::Prompt => ("user", prompts.next()?),
+                TurnKind::Reply => ("assistant", replies.next()?),
+                TurnKind::Other => return None,
+            };
+            Some(TranscriptMessage { role, text: text.clone(), timestamp: None })
+        })
+        .collect()
+}
+
+/// Tallies tool invocations and failed results, pairing results with their
+/// call by id.
+#[derive(Default)]
+pub(crate) struct ToolUsage {
+    names_by_id: HashMap<String, String>,
+    counts: BTreeMap<String, (usize, usize)>,
+}
+
+impl ToolUsage {
+    pub(crate) fn record_call(&mut self, id: Option<&str>, name: &str) {
+        self.counts.entry(name.to_string()).or_default().0 += 1;
+        if let Some(id) = id {
+            self.names_by_id.insert(id.to_string(), name.to_string());
+        }
+    }
+
+    pub(crate) fn record_result(&mut self, id: Option<&str>, failed: bool) {
+        if !failed {
+            return;
+        }
+        if let Some(name) = id.and_then(|id| self.names_by_id.get(id)) {
+            if let Some(entry) = self.counts.get_mut(name) {
+                entry.1 += 1;
+            }
+        }
+    }
+
+    pub(crate) fn into_value(self) -> Value {
+        Value::Object(
+            self.counts
+                .into_iter()
+                .map(|(name, (calls, failures))| {
+                    (name, serde_json::json!({ "calls": calls, "failures": failures }))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Token totals summed from provider usage records, normalized so the four
+/// buckets are disjoint (Codex reports cached tokens inside `input_tokens`).
+#[derive(Default)]
+pub(crate) struct TokenUsage {
+    input: u64,
+    output: u64,
+    cache_read: u64,
+    cache_write: u64,
+    seen: bool,
+}
+
+impl TokenUsage {
+    pub(crate) fn add_claude(&mut self, usage: &Value) {
+        let field = |name: &str| usage[name].as_u64().unwrap_or(0);
+        self.input += field("input_tokens");
+        self.output += field("output_tokens");
+        self.cache_read += field("cache_read_input_tokens");
+        self.cache_write += field("cache_creation_input_tokens");
+        self.seen = true;
+    }
+
+    /// Codex `token_count` events carry a running total, so the latest one wins.
+    pub(crate) fn set_codex_total(&mut self, total: &Value) {
+        let field = |name: &str| total[name].as_u64().unwrap_or(0);
+        let cached = field("cached_input_tokens");
+        self.input = field("input_tokens").saturating_sub(cached);
+        self.output = field("output_tokens");
+        self.cache_read = cached;
+        self.cache_write = 0;
+        self.seen = true;
+    }
+
+    pub(crate) fn into_value(self) -> Value {
+        if !self.seen {
+            return Value::Null;
+        }
+        serde_json::json!({
+            "input_tokens": self.input,
+            "output_tokens": self.output,
+            "cache_read_tokens": self.cache_read,
+            "cache_write_tokens": self.cache_write,
+            "total_tokens": self.input + self.output + self.cache_read + self.cache_write,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TurnKind {
+    Prompt,
+    Reply,
+    Other,
+}
+
+/// Session timing from per-line timestamps. A reply's duration runs from the
+/// previous prompt or reply, so tool traffic in between counts as work time.
+#[derive(Default)]
+pub(crate) struct Timing {
+    first_ms: Option<i64>,
+    last_ms: Option<i64>,
+    previous_message_ms: Option<i64>,
+    turns: Vec<Value>,
+}
+
+impl Timing {
+    pub(crate) fn observe(&mut self, timestamp: Option<&str>, kind: TurnKind) {
+        let Some(raw) = timestamp else {
+            return;
+        };
+        let Some(ms) = parse_iso_millis(raw) else {
+            return;
+        };
+        self.first_ms = Some(self.first_ms.map_or(ms, |first| first.min(ms)));
+        self.last_ms = Some(self.last_ms.map_or(ms, |last| last.max(ms)));
+        if kind == TurnKind::Reply {
+            let duration = self.previous_message_ms.map(|prev| seconds_between(prev, ms));
+            self.turns.push(serde_json::json!({ "timestamp": raw, "duration_seconds": duration }));
+        }
+        if kind != TurnKind::Other {
+            self.previous_message_ms = Some(ms);
+        }
+    }
+
+    pub(crate) fn into_value(self) -> Option<Value> {
+        let (first, last) = (self.first_ms?, self.last_ms?);
+        Some(serde_json::json!({
+            "started_at": format_epoch_millis(first),
+            "ended_at": format_epoch_millis(last),
+            "duration_seconds": seconds_between(first, last),
+            "turns": self.turns,
+        }))
+    }
+}
+
+fn seconds_between(start_ms: i64, end_ms: i64) -> f64 {
+    (end_ms - start_ms).max(0) as f64 / 1000.0
+}
+
+/// What a session parser extracted from one file, before the adapter adds
+/// where it came from.
+pub(crate) struct ParsedContent {
+    pub(crate) content: String,
+    pub(crate) messages: Vec<String>,
+    pub(crate) warnings: Vec<String>,
+    pub(crate) session_id: Option<String>,
+    pub(crate) cwd: Option<String>,
+    pub(crate) timestamp: Option<String>,
+    pub(crate) message_count: usize,
+    pub(crate) messages_returned: usize,
+    pub(crate) tool_usage: Option<Value>,
+    pub(crate) timing: Option<Value>,
+    pub(crate) model: Option<String>,
+    pub(crate) usage: Option<Value>,
+}
+
+impl ParsedContent {
+    pub(crate) fn into_session(self, agent: &'static str, source: String) -> Session {
+        Session {
+            agent,
+            content: self.content,
+            messages: self.messages,
+            source,
+            warnings: self.warnings,
+            session_id: self.session_id,
+            cwd: self.cwd,
+            timestamp: self.timestamp,
+            message_count: self.message_count,
+            messages_returned: self.messages_returned,
+            tool_usage: self.tool_usage,
+            timing: self.timing,
+            model: self.model,
+            usage: self.usage,
+        }
+    }
+}
+
+pub(crate) fn extract_text(value: &Value) -> String {
+    if let Some(raw) = value.as_str() {
+        return raw.to_string();
+    }
+
+    if let Some(parts) = value.as_array() {
+        return parts
+            .iter()
+            .map(|part| {
+                if let Some(raw) = part.as_str() {
+                    raw.to_string()
+                } else {
+                    part["text"].as_str().unwrap_or("").to_string()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("");
+    }
+
+    String::new()
+}
+
+pub(crate) fn file_modified_iso(path: &Path) -> Option<String> {
+    fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .map(|mtime| {
+            let duration = mtime.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+            let secs = duration.as_secs();
+            let days = secs / 86400;
+            let time_secs = secs % 86400;
+            let hours = time_secs / 3600;
+            let minutes = (time_secs % 3600) / 60;
+            let seconds = time_secs % 60;
+            // Simple epoch-to-date calculation
+            let (year, month, day) = epoch_days_to_date(days);
+            format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hours, minutes, seconds)
+        })
+}
+
+fn epoch_days_to_date(days: u64) -> (u64, u64, u64) {
+    // Civil from days algorithm
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = if m <= 2 { y + 1 } else { y };
+    (y, m, d)
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Parse RFC 3339 timestamps (`2026-01-15T10:30:00.123Z`, `...+02:00`) into
+/// epoch milliseconds.
+pub(crate) fn parse_iso_millis(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    let field = |range: std::ops::Range<usize>| raw.get(range)?.parse::<i64>().ok();
+    if raw.len() < 19 || raw.as_bytes()[10] != b'T' && raw.as_bytes()[10] != b' ' {
+        return None;
+    }
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+
+    let mut rest = &raw[19..];
+    let mut millis = 0i64;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.chars().take_while(|ch| ch.is_ascii_digit()).count();
+        let padded = format!("{:0<3}", &fraction[..digits.min(3)]);
+        millis = padded.parse().ok()?;
+        rest = &fraction[digits..];
+    }
+    let offset_minutes = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let hours = rest.get(1..3)?.parse::<i64>().ok()?;
+            let minutes = rest.get(rest.len() - 2..)?.parse::<i64>().ok()?;
+            sign * (hours * 60 + minutes)
+        }
+    };
+
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second - offset_minutes * 60;
+    Some(seconds * 1000 + millis)
+}
+
+pub(crate) fn format_epoch_millis(ms: i64) -> String {
+    let secs = ms.div_euclid(1000).max(0) as u64;
+    let (year, month, day) = epoch_days_to_date(secs / 86400);
+    let time_secs = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time_secs / 3600,
+        (time_secs % 3600) / 60,
+        time_secs % 60,
+        ms.rem_euclid(1000)
+    )
+}
+
+/// First non-empty line of `prompt`, cut to `TITLE_CHARS` characters.
+pub(crate) fn session_title(prompt: &str) -> String {
+    let line = prompt.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    if line.chars().count() <= TITLE_CHARS {
+        return line.to_string();
+    }
+    format!("{}…", line.chars().take(TITLE_CHARS).collect::<String>())
+}
+
+pub(crate) fn session_meta(cwd: Option<PathBuf>, model: Option<String>, parsed: Option<ParsedContent>) -> SessionMeta {
+    let (session_id, message_count, timestamp) = parsed
+        .map(|parsed| (parsed.session_id, parsed.message_count, parsed.timestamp))
+        .unwrap_or_default();
+    SessionMeta {
+        session_id,
+        cwd: cwd.map(|path| path.to_string_lossy().to_string()),
+        model,
+        message_count,
+        timestamp,
+        ..SessionMeta::default()
+    }
+}
+
+/// First model recorded in a JSONL session.
+pub(crate) fn first_lines_model(lines: &[String], extractor: fn(&Value) -> Option<String>) -> Option<String> {
+    lines
+        .iter()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .find_map(|json| extractor(&json))
+}
+
+/// Flag each `list`/`search` entry `"truncated": true` when `truncated` (a
+/// scan stopped at the file limit), as `session_json` flags a read.
+pub fn flag_truncated(entries: &mut [Value], truncated: bool) {
+    if truncated {
+        for entry in entries {
+            entry["truncated"] = serde_json::json!(true);
+        }
+    }
+}
+
+/// The `read --json` object for a session.
+pub fn session_json(session: &Session) -> Value {
+    let mut report = serde_json::json!({
+        "agent": session.agent,
+        "source": session.source,
+        "content": session.content,
+        "warnings": session.warnings,
+        "session_id": session.session_id,
+        "cwd": session.cwd,
+        "model": session.model,
+        "timestamp": session.timestamp,
+        "message_count": session.message_count,
+        "messages_returned": session.messages_returned,
+    });
+    if scan_truncated() {
+        report["truncated"] = serde_json::json!(true);
+    }
+    if let Some(tool_usage) = &session.tool_usage {
+        report["tool_usage"] = tool_usage.clone();
+    }
+    if let Some(usage) = &session.usage {
+        report["usage"] = usage.clone();
+        if let Some(cost) = crate::pricing::cost_for(session.model.as_deref(), usage) {
+            report["cost"] = cost;
+        }
+    }
+    report
+}
+
+/// Every session file the scan roots hold for `agent`, newest first.
+pub fn session_files(agent: &str) -> Result<Vec<PathBuf>> {
+    crate::adapters::get_adapter(agent)
+        .ok_or_else(|| BridgeErrorCode::UnsupportedAgent.error(format!("Unsupported agent: {}", agent)))?.session_files()
+}
+
+/// Keep only the newest entry per (agent, project). Entries must already be
+/// sorted newest-first. Sessions without a recorded cwd are grouped by the
+/// directory holding the session file, which is project-scoped for Gemini.
+pub fn latest_per_project(entries: Vec<Value>) -> Vec<Value> {
+    let mut seen = std::collections::HashSet::new();
+    entries
+        .into_iter()
+        .filter(|entry| {
+            let agent = entry["agent"].as_str().unwrap_or("").to_string();
+            let project = entry["cwd"]
+                .as_str()
+                .map(|cwd| cwd.to_string())
+                .or_else(|| {
+                    entry["file_path"]
+                        .as_str()
+                        .and_then(|path| Path::new(path).parent())
+                        .map(|parent| parent.to_string_lossy().to_string())
+                })
+                .unwrap_or_default();
+            seen.insert((agent, project))
+        })
+        .collect()
+}
+
+/// Add what `list` shows about each entry's session besides where it lives:
+/// `title` (the first user prompt, else the agent's summary), `message_count`
+/// (user prompts and assistant replies, as `export` counts them), `size_bytes`,
+/// `created_at` (first message timestamp), and `duration_seconds` (first to
+/// last message timestamp). The count and times are `null` for files over
+/// the size limit or unreadable.
+pub fn describe_list_entries(entries: &mut [Value]) {
+    entries.par_iter_mut().for_each(describe_list_entry);
+}
+
+fn describe_list_entry(entry: &mut Value) {
+    let path = entry["file_path"].as_str().map(PathBuf::from);
+    let size = path.as_ref().and_then(|path| fs::metadata(path).ok()).map(|meta| meta.len());
+    let messages = path
+        .filter(|_| size.is_some_and(|size| size <= max_file_size()))
+        .and_then(|path| fs::read(path).ok())
+        .map(|bytes| transcript(entry["agent"].as_str().unwrap_or(""), &bytes, entry["session_id"].as_str()));
+
+    if entry["title"].is_null() {
+        let prompt = messages
+            .iter()
+            .flatten()
+            .filter(|message| message.role == "user")
+            .find_map(|message| substantive_prompt(&message.text));
+        entry["title"] = match prompt {
+            Some(prompt) => Value::from(session_title(&prompt)),
+            None => entry["summary"].clone(),
+        };
+    }
+    let times = messages
+        .iter()
+        .flatten()
+        .filter_map(|message| message.timestamp.as_deref().and_then(parse_iso_millis))
+        .collect::<Vec<_>>();
+    let (first, last) = (times.iter().min().copied(), times.iter().max().copied());
+    entry["message_count"] = serde_json::json!(messages.as_ref().map(Vec::len));
+    entry["size_bytes"] = serde_json::json!(size);
+    entry["created_at"] = serde_json::json!(first.map(format_epoch_millis));
+    entry["duration_seconds"] = serde_json::json!(first.zip(last).map(|(first, last)| seconds_between(first, last)));
+}
+
+/// `list --sort`: what entries are ordered by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListSort {
+    /// Last change to the session file
+    #[default]
+    Modified,
+    /// First message timestamp
+    Created,
+    /// Messages in the session
+    Messages,
+    /// Session file size
+    Size,
+}
+
+impl ListSort {
+    fn field(self) -> &'static str {
+        match self {
+            Self::Modified => "modified_at",
+            Self::Created => "created_at",
+            Self::Messages => "message_count",
+            Self::Size => "size_bytes",
+        }
+    }
+
+    /// Whether the key comes from `describe_list_entries`.
+    pub fn needs_details(self) -> bool {
+        self != Self::Modified
+    }
+}
+
+/// Order list entries by `sort`, newest or largest first, or oldest or
+/// smallest first with `reverse`. Entries without the key go last either
+/// way, and ties keep their order.
+pub fn sort_list_entries(entries: &mut [Value], sort: ListSort, reverse: bool) {
+    let field = sort.field();
+    entries.sort_by(|a, b| {
+        let (a, b) = (&a[field], &b[field]);
+        let order = match (a.as_u64(), b.as_u64()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a.as_str().cmp(&b.as_str()),
+        };
+        match (a.is_null(), b.is_null()) {
+            (false, false) if reverse => order,
+            (false, false) => order.reverse(),
+            (nulls_a, nulls_b) => nulls_a.cmp(&nulls_b),
+        }
+    });
+}
+
+/// Matcher for `list --filter-cwd`. `*` stays within one path component and
+/// `**` spans several; a leading `~` is the home directory.
+pub fn cwd_glob(pattern: &str) -> Result<globset::GlobMatcher> {
+    let pattern = expand_home(pattern).map(|path| path.to_string_lossy().to_string()).unwrap_or_else(|| pattern.to_string());
+    let glob = globset::GlobBuilder::new(&pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|err| anyhow!("Invalid --filter-cwd glob: {}", err))?;
+    Ok(glob.compile_matcher())
+}
+
+/// `list` and `search` text output: an aligned row per entry with its agent,
+/// session id, age, the tail of its cwd, and its title. Cells are sanitized
+/// for the terminal; agent names are colored per the theme.
+pub fn sessions_table(entries: &[Value]) -> String {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as i64;
+    sessions_table_at(entries, now)
+}
+
+fn sessions_table_at(entries: &[Value], now_ms: i64) -> String {
+    if entries.is_empty() {
+        return "No sessions found.\n".to_string();
+    }
+    let cell = |value: &Value| value.as_str().map(sanitize_for_terminal).unwrap_or_else(|| "-".to_string());
+    let mut rows = vec![["AGENT", "SESSION", "AGE", "CWD", "TITLE"].map(String::from)];
+    rows.extend(entries.iter().map(|entry| {
+        let age = entry["modified_at"].as_str().and_then(parse_iso_millis).map(|ms| age_label(now_ms - ms));
+        let cwd = entry["cwd"].as_str().map(cwd_tail);
+        [
+            cell(&entry["agent"]),
+            cell(&entry["session_id"]),
+            age.unwrap_or_else(|| "-".to_string()),
+            cwd.map(|cwd| sanitize_for_terminal(&cwd)).unwrap_or_else(|| "-".to_string()),
+            cell(&entry["title"]),
+        ]
+    }));
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, text) in widths.iter_mut().zip(row) {
+            *width = (*width).max(text.chars().count());
+        }
+    }
+    let mut table = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let agent = format!("{:<width$}", row[0], width = widths[0]);
+        let agent = if index == 0 { agent } else { theme::paint(&row[0], &agent) };
+        table.push_str(&format!(
+            "{}  {:<w1$}  {:<w2$}  {:<w3$}  {}",
+            agent,
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3]
+        ));
+        table.push('\n');
+    }
+    table
+}
+
+/// How long ago, in the units `--since` accepts: `45s`, `12m`, `3h`, `5d`.
+fn age_label(elapsed_ms: i64) -> String {
+    let seconds = elapsed_ms.max(0) / 1000;
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m", seconds / 60),
+        3600..=86_399 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86_400),
+    }
+}
+
+/// The last two components of `cwd`, after `…/` when more were dropped.
+fn cwd_tail(cwd: &str) -> String {
+    let parts = cwd.split(['/', '\\']).filter(|part| !part.is_empty()).collect::<Vec<_>>();
+    if parts.len() <= 2 {
+        return cwd.to_string();
+    }
+    format!("…/{}", parts[parts.len() - 2..].join("/"))
+}
+
+/// Directories an agent's sessions live under, for watchers. Empty for agents
+/// whose store depends on the working directory (Aider).
+pub fn store_roots(agent: &str) -> Vec<PathBuf> {
+    crate::adapters::get_adapter(agent).map(|adapter| adapter.store_roots()).unwrap_or_default()
+}
+
+// --- Trash Talk ---
+
+struct ActiveAgent {
+    agent: &'static str,
+    content: String,
+    message_count: usize,
+    session_id: String,
+}
+
+fn simple_hash(s: &str) -> usize {
+    let mut hash: i32 = 0;
+    for byte in s.bytes() {
+        hash = hash.wrapping_shl(5).wrapping_sub(hash).wrapping_add(byte as i32);
+    }
+    hash.unsigned_abs() as usize
+}
+
+fn pick_roast(agent: &str, content: &str, message_count: usize) -> &'static str {
+    const SHORT_ROASTS: &[&str] = &[
+        "That's it? My .gitignore has more content.",
+        "Blink and you'd miss that entire session.",
+    ];
+    const LONG_ROASTS: &[&str] = &[
+        "Wrote a novel, did we? Too bad nobody asked for War and Peace.",
+        "That session has more words than my last performance review.",
+    ];
+    const TEST_ROASTS: &[&str] = &[
+        "Oh look, someone actually writes tests. Show-off.",
+        "Testing? In this economy?",
+    ];
+    const TODO_ROASTS: &[&str] = &[
+        "Still leaving TODOs? That's a cry for help.",
+        "TODO: learn to finish things.",
+    ];
+    const BUG_ROASTS: &[&str] = &[
+        "Breaking things again? Classic.",
+        "Found a bug? Or just made one?",
+    ];
+    const CODEX_ROASTS: &[&str] = &[
+        "OpenAI's kid showing up to do chores. How responsible.",
+        "Codex: because copy-paste needed a rebrand.",
+    ];
+    const CLAUDE_ROASTS: &[&str] = &[
+        "Claude overthinking again? Shocking. Truly shocking.",
+        "Too polite to say no, too verbose to say yes.",
+    ];
+    const GEMINI_ROASTS: &[&str] = &[
+        "Did Gemini Google the answer? Old habits die hard.",
+        "Gemini: when one model isn't enough, use two and confuse both.",
+    ];
+    const CURSOR_ROASTS: &[&str] = &[
+        "An IDE that thinks it's an agent. Bless its heart.",
+        "Cursor: autocomplete with delusions of grandeur.",
+    ];
+    const GENERIC_ROASTS: &[&str] = &[
+        "Participation trophy earned.",
+        "Well, at least the process exited cleanly.",
+        "Not the worst I've seen. That's not a compliment.",
+    ];
+
+    let mut roasts: Vec<&str> = Vec::new();
+    if message_count < 5 { roasts.extend_from_slice(SHORT_ROASTS); }
+    if message_count > 30 { roasts.extend_from_slice(LONG_ROASTS); }
+
+    let lower = content.to_ascii_lowercase();
+    if lower.contains("test") || lower.contains("spec") || lower.contains("assert") {
+        roasts.extend_from_slice(TEST_ROASTS);
+    }
+    if lower.contains("todo") || lower.contains("fixme") || lower.contains("hack") {
+        roasts.extend_from_slice(TODO_ROASTS);
+    }
+    if lower.contains("error") || lower.contains("bug") || lower.contains("fix") {
+        roasts.extend_from_slice(BUG_ROASTS);
+    }
+
+    match agent {
+        "codex" => roasts.extend_from_slice(CODEX_ROASTS),
+        "claude" => roasts.extend_from_slice(CLAUDE_ROASTS),
+        "gemini" => roasts.extend_from_slice(GEMINI_ROASTS),
+        "cursor" => roasts.extend_from_slice(CURSOR_ROASTS),
+        _ => {}
+    }
+    roasts.extend_from_slice(GENERIC_ROASTS);
+
+    roasts[simple_hash(content) % roasts.len()]
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(c) => c.to_uppercase().to_string() + chars.as_str(),
+    }
+}
+
+pub fn trash_talk(cwd: &str) {
+    let mut active: Vec<ActiveAgent> = Vec::new();
+
+    for agent_name in crate::adapters::agent_names() {
+        let adapter = match crate::adapters::get_adapter(agent_name) {
+            Some(a) => a,
+            None => continue,
+        };
+        let has_sessions = adapter
+            .list_sessions(Some(cwd), CwdMatch::Exact, 1)
+            .is_ok_and(|entries| !entries.is_empty());
+        if !has_sessions {
+            continue;
+        }
+        let options = ReadOptions {
+            cwd: cwd.to_string(),
+            ..ReadOptions::default()
+        };
+        let session = match adapter.read_session(&options) {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        active.push(ActiveAgent {
+            agent: agent_name,
+            content: session.content,
+            message_count: session.message_count,
+            session_id: session.session_id.unwrap_or_else(|| "unknown".to_string()),
+        });
+    }
+
+    println!("\u{1f5d1}\u{fe0f}  TRASH TALK\n");
+
+    if active.is_empty() {
+        println!("No agents to trash-talk. It's lonely in here.");
+        println!("Try running some agents first \u{2014} I need material.");
+        return;
+    }
+
+    if active.len() == 1 {
+        let a = &active[0];
+        let roast = pick_roast(a.agent, &a.content, a.message_count);
+        println!(
+            "Target: {} ({}, {} messages)\n",
+            theme::paint(a.agent, &capitalize(a.agent)),
+            a.session_id,
+            a.message_count
+        );
+        println!("\"{}\"\n", roast);
+        println!("Verdict: {} is trying. Bless.", capitalize(a.agent));
+        return;
+    }
+
+    // Battle mode
+    active.sort_by_key(|a| std::cmp::Reverse(a.message_count));
+
+    println!("\u{1f4ca} Activity Report:");
+    for a in &active {
+        let name = format!("{:<8}", capitalize(a.agent));
+        println!("  {} {:>3} messages  ({})", theme::paint(a.agent, &name), a.message_count, a.session_id);
+    }
+    println!();
+
+    println!(
+        "\u{1f3c6} Winner: {} (by volume \u{2014} congrats on typing the most)",
+        theme::paint(active[0].agent, &capitalize(active[0].agent))
+    );
+    println!("\"Quantity over quality, but at least you showed up.\"\n");
+
+    for a in &active[1..] {
+        let roast = pick_roast(a.agent, &a.content, a.message_count);
+        println!("\u{1f480} {} ({} messages):", theme::paint(a.agent, &capitalize(a.agent)), a.message_count);
+        println!("\"{}\"\n", roast);
+    }
+
+    println!("Verdict: They're all trying their best. It's just not very good.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        describe_list_entries, error_candidates, error_code, first_prompt, flag_truncated, format_epoch_millis, latest_per_project, parse_iso_millis, parse_session_bytes,
+        session_title,
+        sessions_table_at, sort_list_entries, transcript, with_code, BridgeErrorCode, ListSort, CwdMatch, MessageRange, MessageWindow, SearchField, SearchQuery, SearchRole,
+    };
+    use anyhow::anyhow;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn typed_errors_keep_their_code_through_context() {
+        let parse = BridgeErrorCode::ParseFailed.error("Failed to parse a.json: key not found").context("reading a.json");
+        assert_eq!(error_code(&parse), BridgeErrorCode::ParseFailed);
+        assert_eq!(error_code(&anyhow!("No Codex session found.")), BridgeErrorCode::NotFound);
+
+        let kept = with_code(BridgeErrorCode::UnsupportedMode.error("Unsupported mode: x"), BridgeErrorCode::InvalidHandoff);
+        assert_eq!(error_code(&kept), BridgeErrorCode::UnsupportedMode);
+        let tagged = with_code(anyhow!("Each source must include string field: agent"), BridgeErrorCode::InvalidHandoff);
+        assert_eq!(error_code(&tagged), BridgeErrorCode::InvalidHandoff);
+        assert_eq!(tagged.to_string(), "Each source must include string field: agent");
+        assert!(error_candidates(&tagged).is_empty());
+        let ambiguous = BridgeErrorCode::AmbiguousId.error_with_candidates("Session id 'a' is ambiguous", vec!["ab".into()]);
+        let ambiguous = ambiguous.context("reading codex");
+        assert_eq!(error_code(&ambiguous), BridgeErrorCode::AmbiguousId);
+        assert_eq!(error_candidates(&ambiguous), ["ab".to_string()]);
+
+        let codes = [
+            BridgeErrorCode::NotFound,
+            BridgeErrorCode::ParseFailed,
+            BridgeErrorCode::InvalidHandoff,
+            BridgeErrorCode::UnsupportedAgent,
+            BridgeErrorCode::UnsupportedMode,
+            BridgeErrorCode::IoError,
+            BridgeErrorCode::EmptySession,
+            BridgeErrorCode::AmbiguousId,
+        ];
+        let mut exits = codes.map(|code| code.exit_code()).to_vec();
+        exits.sort();
+        exits.dedup();
+        assert_eq!(exits.len(), codes.len());
+        assert!(!exits.contains(&0) && !exits.contains(&2));
+    }
+
+    #[test]
+    fn truncated_scans_flag_every_list_entry() {
+        let mut entries = vec![json!({"session_id": "a"}), json!({"session_id": "b"})];
+        flag_truncated(&mut entries, false);
+        assert!(entries.iter().all(|entry| entry.get("truncated").is_none()));
+        flag_truncated(&mut entries, true);
+        assert!(entries.iter().all(|entry| entry["truncated"] == true));
+    }
+
+    #[test]
+    fn latest_per_project_keeps_the_newest_entry_per_agent_and_cwd() {
+        let entries = vec![
+            json!({"agent": "codex", "session_id": "new", "cwd": "/repo"}),
+            json!({"agent": "claude", "session_id": "claude", "cwd": "/repo"}),
+            json!({"agent": "codex", "session_id": "old", "cwd": "/repo"}),
+            json!({"agent": "gemini", "session_id": "g1", "cwd": null, "file_path": "/g/hash-a/chats/1.json"}),
+            json!({"agent": "gemini", "session_id": "g2", "cwd": null, "file_path": "/g/hash-b/chats/2.json"}),
+            json!({"agent": "gemini", "session_id": "g3", "cwd": null, "file_path": "/g/hash-a/chats/3.json"}),
+        ];
+        let kept = latest_per_project(entries).into_iter().map(|entry| entry["session_id"].clone()).collect::<Vec<_>>();
+        assert_eq!(kept, ["new", "claude", "g1", "g2"]);
+    }
+
+    #[test]
+    fn list_entries_gain_title_counts_size_and_duration() {
+        let dir = std::env::temp_dir().join(format!("bridge-describe-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("s1.jsonl");
+        let lines = [
+            r#"{"type":"user","timestamp":"2026-01-15T10:00:00Z","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","timestamp":"2026-01-15T10:00:05Z","message":{"role":"user","content":"Fix the flaky login test\nIt times out."}}"#,
+            r#"{"type":"assistant","timestamp":"2026-01-15T10:01:35.500Z","message":{"role":"assistant","content":"Raised the timeout."}}"#,
+        ];
+        std::fs::write(&file, lines.join("\n")).unwrap();
+        let path = file.to_string_lossy().to_string();
+        let mut entries = vec![
+            json!({"session_id": "s1", "agent": "claude", "file_path": path, "title": null, "summary": "Login fix"}),
+            json!({"session_id": "s2", "agent": "claude", "file_path": dir.join("gone.jsonl"), "summary": "Old work"}),
+        ];
+        describe_list_entries(&mut entries);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(entries[0]["title"], "Fix the flaky login test");
+        assert_eq!(entries[0]["message_count"], 2);
+        assert_eq!(entries[0]["size_bytes"], lines.join("\n").len());
+        assert_eq!(entries[0]["created_at"], "2026-01-15T10:00:05.000Z");
+        assert_eq!(entries[0]["duration_seconds"], 90.5);
+        assert_eq!(entries[1]["title"], "Old work");
+        assert!(entries[1]["message_count"].is_null() && entries[1]["size_bytes"].is_null());
+        assert!(entries[1]["duration_seconds"].is_null());
+    }
+
+    #[test]
+    fn list_sort_puts_missing_keys_last_in_both_directions() {
+        let mut entries = [("a", json!(10)), ("b", json!(null)), ("c", json!(300)), ("d", json!(10))]
+            .map(|(id, size)| json!({"session_id": id, "size_bytes": size}));
+        let ids = |entries: &[serde_json::Value]| entries.iter().map(|entry| entry["session_id"].clone()).collect::<Vec<_>>();
+        sort_list_entries(&mut entries, ListSort::Size, false);
+        assert_eq!(ids(&entries), ["c", "a", "d", "b"].map(|id| json!(id)));
+        sort_list_entries(&mut entries, ListSort::Size, true);
+        assert_eq!(ids(&entries), ["a", "d", "c", "b"].map(|id| json!(id)));
+        assert!(super::cwd_glob("/src/*/app").unwrap().is_match("/src/team/app"));
+        assert!(!super::cwd_glob("/src/*/app").unwrap().is_match("/src/team/x/app"));
+        assert!(super::cwd_glob("/src/**/app").unwrap().is_match("/src/team/x/app"));
+    }
+
+    #[test]
+    fn sessions_table_aligns_age_cwd_tail_and_title() {
+        let now = parse_iso_millis("2026-01-15T12:00:00Z").unwrap();
+        let entries = [
+            json!({"agent": "codex", "session_id": "rollout-1", "modified_at": "2026-01-15T11:58:30Z", "cwd": "/home/me/src/app", "title": "Fix login"}),
+            json!({"agent": "claude", "session_id": "abc", "modified_at": "2026-01-12T09:00:00Z", "cwd": null, "title": null}),
+        ];
+        assert_eq!(
+            sessions_table_at(&entries, now),
+            "AGENT   SESSION    AGE  CWD        TITLE\n\
+             codex   rollout-1  1m   …/src/app  Fix login\n\
+             claude  abc        3d   -          -\n"
+        );
+        assert_eq!(sessions_table_at(&[], now), "No sessions found.\n");
+    }
+
+    #[test]
+    fn search_query_combines_regex_and_boolean_terms() {
+        let terms = |items: &[&str]| items.iter().map(|item| item.to_string()).collect::<Vec<_>>();
+        let text = "ERROR: request timeout after 30s";
+        assert!(SearchQuery::substring("Timeout").matches(text));
+
+        let regex = SearchQuery::new(Some("error.*timeout"), &[], &[], &terms(&["resolved"]), true).unwrap();
+        assert!(regex.matches(text));
+        assert!(!regex.matches("error then timeout, since resolved"));
+
+        let any = SearchQuery::new(None, &terms(&["request"]), &terms(&["panic", "30s"]), &[], false).unwrap();
+        assert!(any.matches(text));
+        assert!(!any.matches("request failed with oom"));
+
+        assert!(SearchQuery::new(None, &[], &[], &terms(&["x"]), false).is_err());
+        assert!(SearchQuery::new(Some("("), &[], &[], &[], true).is_err());
+    }
+
+    const SCOPED_SESSION: &str = concat!(
+        r#"{"type":"user","cwd":"/work/app","message":{"role":"user","content":"fix the flaky test"}}"#, "\n",
+        r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"cargo test"}}]}}"#, "\n",
+        r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"timeout"}]}}"#, "\n",
+        r#"{"type":"assistant","message":{"role":"assistant","content":"Fixed."}}"#,
+    );
+
+    fn scoped_search(query: &str, role: Option<SearchRole>, field: SearchField) -> bool {
+        let mut search = SearchQuery::substring(query);
+        search.role = role;
+        search.field = field;
+        search.matches_session("claude", SCOPED_SESSION, Some("t1"), Some("/work/app"))
+    }
+
+    #[test]
+    fn search_in_scopes_matches_to_one_role() {
+        assert!(scoped_search("timeout", Some(SearchRole::Tool), SearchField::Content));
+        assert!(!scoped_search("timeout", Some(SearchRole::Assistant), SearchField::Content));
+        assert!(scoped_search("flaky", Some(SearchRole::User), SearchField::Content));
+    }
+
+    #[test]
+    fn search_field_matches_session_metadata_instead_of_content() {
+        assert!(scoped_search("work/app", None, SearchField::Cwd));
+        assert!(!scoped_search("t1", None, SearchField::Cwd));
+        assert!(!scoped_search("flaky", None, SearchField::Cwd));
+    }
+
+    #[test]
+    fn message_window_selects_last_and_ranges() {
+        let items = [0, 1, 2, 3, 4];
+        assert_eq!(MessageWindow::Last(2).select(&items), &[3, 4]);
+        assert_eq!(MessageWindow::Last(0).select(&items), &[4]);
+        let range = |from, to| MessageWindow::Range(MessageRange { from, to });
+        assert_eq!(range(1, Some(3)).select(&items), &[1, 2]);
+        assert_eq!(range(3, None).select(&items), &[3, 4]);
+        assert_eq!(range(4, Some(9)).select(&items), &[4]);
+        assert!(range(7, Some(9)).select(&items).is_empty());
+    }
+
+    #[test]
+    fn parses_iso_timestamps_with_fractions_and_offsets() {
+        let base = parse_iso_millis("2026-01-15T10:00:00Z").unwrap();
+        assert_eq!(format_epoch_millis(base), "2026-01-15T10:00:00.000Z");
+        assert_eq!(parse_iso_millis("2026-01-15T10:00:00.25Z"), Some(base + 250));
+        assert_eq!(parse_iso_millis("2026-01-15T12:00:00+02:00"), Some(base));
+        assert_eq!(parse_iso_millis("1999-12-31T23:59:59.999Z").map(format_epoch_millis).as_deref(), Some("1999-12-31T23:59:59.999Z"));
+        assert_eq!(parse_iso_millis("not a timestamp"), None);
+    }
+
+    #[test]
+    fn cwd_match_modes() {
+        let repo = Path::new("/work/repo");
+        let sub = Path::new("/work/repo/cli");
+        let sibling = Path::new("/work/repo-other");
+        assert!(CwdMatch::Exact.matches(repo, repo));
+        assert!(!CwdMatch::Exact.matches(sub, repo));
+        assert!(CwdMatch::Prefix.matches(sub, repo));
+        assert!(!CwdMatch::Prefix.matches(sibling, repo));
+        assert!(CwdMatch::Ancestor.matches(repo, sub));
+        assert!(!CwdMatch::Ancestor.matches(sub, repo));
+    }
+
+    #[test]
+    fn parses_session_bytes_without_filesystem() {
+        let claude = b"{\"type\":\"assistant\",\"cwd\":\"/w\",\"message\":{\"content\":\"key sk-abcdefghijklmnopqrstuvwxyz\"}}\nnot json\n";
+        let session = parse_session_bytes("claude", "abc-123.jsonl", claude, 1).unwrap();
+        assert_eq!(session.session_id.as_deref(), Some("abc-123"));
+        assert_eq!(session.content, "key sk-[REDACTED]");
+        assert_eq!(session.warnings, vec!["Warning: skipped 1 unparseable line(s) in abc-123.jsonl"]);
+        assert!(session.timestamp.is_none());
+
+        assert!(parse_session_bytes("gemini", "s.json", b"{", 1).is_err());
+        assert!(parse_session_bytes("aider", "s.md", b"", 1).is_err());
+
+        let cursor = br#"{"messages":[{"role":"assistant","content":"a1"},{"role":"user","content":"q"},{"role":"assistant","content":"a2"}]}"#;
+        let session = parse_session_bytes("cursor", "chat.json", cursor, 5).unwrap();
+        assert_eq!((session.content.as_str(), session.message_count, session.messages_returned), ("a1\n---\na2", 2, 2));
+        let raw = parse_session_bytes("cursor", "chat.json", b"{\"state\":1}", 1).unwrap();
+        assert_eq!((raw.message_count, raw.messages_returned), (0, 0));
+    }
+
+    #[test]
+    fn read_reports_the_latest_real_model() {
+        let claude = concat!(
+            r#"{"type":"assistant","message":{"model":"claude-sonnet-4","content":"first"}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"model":"<synthetic>","content":"local"}}"#,
+        );
+        let session = parse_session_bytes("claude", "a.jsonl", claude.as_bytes(), 1).unwrap();
+        assert_eq!(session.model.as_deref(), Some("claude-sonnet-4"));
+
+        let codex = concat!(
+            r#"{"type":"turn_context","payload":{"model":"gpt-5-codex"}}"#,
+            "\n",
+            r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"done"}]}}"#,
+        );
+        let session = parse_session_bytes("codex", "b.jsonl", codex.as_bytes(), 1).unwrap();
+        assert_eq!(session.model.as_deref(), Some("gpt-5-codex"));
+    }
+
+    #[test]
+    fn claude_usage_counts_each_message_id_once() {
+        let line = |id: &str, text: &str| {
+            format!(
+                r#"{{"type":"assistant","message":{{"id":"{}","content":"{}","usage":{{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100,"cache_creation_input_tokens":1}}}}}}"#,
+                id, text
+            )
+        };
+        let bytes = [line("msg_1", "part one"), line("msg_1", "part two"), line("msg_2", "next")].join("\n");
+        let session = parse_session_bytes("claude", "a.jsonl", bytes.as_bytes(), 1).unwrap();
+        assert_eq!(
+            session.usage.unwrap(),
+            json!({"input_tokens": 20, "output_tokens": 10, "cache_read_tokens": 200, "cache_write_tokens": 2, "total_tokens": 232})
+        );
+    }
+
+    #[test]
+    fn codex_usage_takes_the_latest_total_without_double_counting_cache() {
+        let count = |input: u64, cached: u64, output: u64| {
+            json!({"type": "event_msg", "payload": {"type": "token_count", "info": {"total_token_usage": {
+                "input_tokens": input, "cached_input_tokens": cached, "output_tokens": output
+            }}}})
+            .to_string()
+        };
+        let reply = r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"done"}]}}"#;
+        let bytes = [count(100, 40, 10), reply.to_string(), count(300, 200, 30)].join("\n");
+        let session = parse_session_bytes("codex", "b.jsonl", bytes.as_bytes(), 1).unwrap();
+        assert_eq!(
+            session.usage.unwrap(),
+            json!({"input_tokens": 100, "output_tokens": 30, "cache_read_tokens": 200, "cache_write_tokens": 0, "total_tokens": 330})
+        );
+    }
+
+    #[test]
+    fn parses_gemini_saved_chats_and_checkpoints() {
+        let saved = br#"[{"role":"user","parts":[{"text":"Plan"}]},{"role":"model","parts":[{"text":"Step one"}]}]"#;
+        let session = parse_session_bytes("gemini", "checkpoint-plan.json", saved, 1).unwrap();
+        assert_eq!((session.content.as_str(), session.message_count), ("Step one", 1));
+        assert_eq!(first_prompt("gemini", saved, None).as_deref(), Some("Plan"));
+
+        let checkpoint = br#"{"history":[{"type":"user","text":"ui"}],"clientHistory":[{"role":"model","parts":[{"text":"Writing"},{"functionCall":{"name":"write_file"}}]}]}"#;
+        let session = parse_session_bytes("gemini", "restore.json", checkpoint, 1).unwrap();
+        assert_eq!(session.content, "Writing");
+    }
+
+    #[test]
+    fn parses_legacy_codex_rollouts() {
+        let rollout = concat!(
+            "{\"id\":\"legacy-1\",\"timestamp\":\"2025-05-01T10:00:00Z\"}\n",
+            "{\"record_type\":\"state\"}\n",
+            "{\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"<environment_context><cwd>/work/old</cwd></environment_context>\"}]}\n",
+            "{\"type\":\"message\",\"role\":\"assistant\",\"content\":[{\"type\":\"output_text\",\"text\":\"Done.\"}]}\n",
+            "{\"type\":\"compacted\",\"payload\":{\"message\":\"Earlier: set up CI.\"}}\n",
+        );
+        let session = parse_session_bytes("codex", "rollout.jsonl", rollout.as_bytes(), 1).unwrap();
+        assert_eq!(session.content, "Done.");
+        assert_eq!(session.session_id.as_deref(), Some("legacy-1"));
+        assert_eq!(session.cwd.as_deref(), Some("/work/old"));
+        assert!(session.warnings.is_empty());
+
+        let texts = transcript("codex", rollout.as_bytes(), None).into_iter().map(|m| m.text).collect::<Vec<_>>();
+        assert_eq!(texts, vec!["Done.", "[Context compacted] Earlier: set up CI."]);
+    }
+
+    #[test]
+    fn first_prompt_skips_injected_context() {
+        let codex = concat!(
+            "{\"type\":\"response_item\",\"payload\":{\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"<environment_context>\\n<cwd>/w</cwd>\\n</environment_context>\"}]}}\n",
+            "{\"type\":\"response_item\",\"payload\":{\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"Fix the flaky test\"}]}}\n",
+        );
+        assert_eq!(first_prompt("codex", codex.as_bytes(), None).as_deref(), Some("Fix the flaky test"));
+
+        let claude = concat!(
+            "{\"type\":\"user\",\"isMeta\":true,\"message\":{\"content\":\"Caveat: ignore\"}}\n",
+            "{\"type\":\"user\",\"message\":{\"content\":\"<command-name>/clear</command-name>\"}}\n",
+            "{\"type\":\"user\",\"message\":{\"content\":[{\"type\":\"text\",\"text\":\"<system-reminder>ctx</system-reminder>\\nAdd retries\"}]}}\n",
+        );
+        assert_eq!(first_prompt("claude", claude.as_bytes(), None).as_deref(), Some("Add retries"));
+
+        let gemini = r#"{"history":[{"role":"user","parts":[{"text":"This is the Gemini CLI. We are setting up the context for our chat."}]},{"role":"user","parts":[{"text":"Ship it"}]}]}"#;
+        assert_eq!(first_prompt("gemini", gemini.as_bytes(), None).as_deref(), Some("Ship it"));
+        assert_eq!(first_prompt("codex", b"{}", None), None);
+
+        assert_eq!(session_title("\n  Add retries  \nthen ship"), "Add retries");
+        assert_eq!(session_title(&"é".repeat(81)), format!("{}…", "é".repeat(80)));
+    }
+}
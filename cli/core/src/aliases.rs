@@ -0,0 +1,142 @@
+use crate::config;
+use crate::report;
+use crate::utils::expand_home;
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::PathBuf;
+
+/// Location of the alias registry (`BRIDGE_ALIASES_FILE` overrides the default).
+pub fn registry_path() -> PathBuf {
+    std::env::var("BRIDGE_ALIASES_FILE")
+        .ok()
+        .and_then(|value| expand_home(&value))
+        .unwrap_or_else(|| config::config_dir().join("aliases.json"))
+}
+
+fn load() -> Result<Map<String, Value>> {
+    let path = registry_path();
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let raw = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read alias registry {}", path.display()))?;
+    let value: Value = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse alias registry {}", path.display()))?;
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(anyhow!("Failed to parse alias registry {}: expected a JSON object", path.display())),
+    }
+}
+
+fn save(aliases: &Map<String, Value>) -> Result<()> {
+    let path = registry_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    let text = format!("{}\n", serde_json::to_string_pretty(aliases)?);
+    fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.');
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Invalid alias name '{}': use letters, digits, '-', '_' or '.'",
+            name
+        ))
+    }
+}
+
+/// Store `name` -> `<agent>:<session-id>`, replacing any existing alias of that name.
+pub fn add(name: &str, target: &str) -> Result<Value> {
+    validate_name(name)?;
+    let spec = report::parse_source_arg(target)?;
+    let session_id = spec
+        .session_id
+        .ok_or_else(|| anyhow!("Alias target must be <agent>:<session-id>, got '{}'", target))?;
+
+    let mut aliases = load()?;
+    let entry = json!({ "agent": spec.agent, "session_id": session_id });
+    aliases.insert(name.to_string(), entry.clone());
+    save(&aliases)?;
+    Ok(json!({ "alias": name, "agent": entry["agent"], "session_id": entry["session_id"] }))
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let mut aliases = load()?;
+    if aliases.remove(name).is_none() {
+        return Err(anyhow!("Alias not found: {}", name));
+    }
+    save(&aliases)
+}
+
+pub fn list() -> Result<Vec<Value>> {
+    Ok(load()?
+        .into_iter()
+        .map(|(name, entry)| {
+            json!({
+                "alias": name,
+                "agent": entry["agent"],
+                "session_id": entry["session_id"],
+            })
+        })
+        .collect())
+}
+
+/// Resolve `id` through the alias registry for `agent`. Ids that are not
+/// aliases are returned unchanged; an alias registered for a different agent
+/// is an error rather than a silent mismatch.
+pub fn resolve_id(agent: &str, id: &str) -> Result<String> {
+    let aliases = load()?;
+    let Some(entry) = aliases.get(id) else {
+        return Ok(id.to_string());
+    };
+    let alias_agent = entry["agent"].as_str().unwrap_or("");
+    if alias_agent != agent {
+        return Err(anyhow!(
+            "Alias '{}' refers to a {} session, not {}",
+            id,
+            alias_agent,
+            agent
+        ));
+    }
+    entry["session_id"]
+        .as_str()
+        .map(|value| value.to_string())
+        .ok_or_else(|| anyhow!("Failed to parse alias registry entry: {}", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add, resolve_id};
+    use std::fs;
+
+    #[test]
+    fn aliases_resolve_only_for_their_own_agent() {
+        let dir = std::env::temp_dir().join(format!("bridge-aliases-{}", std::process::id()));
+        std::env::set_var("BRIDGE_ALIASES_FILE", dir.join("aliases.json"));
+
+        add("auth-fix", "codex:abc-123").unwrap();
+        let resolved = resolve_id("codex", "auth-fix");
+        let passthrough = resolve_id("codex", "def-456");
+        let mismatch = resolve_id("claude", "auth-fix");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(resolved.unwrap(), "abc-123");
+        assert_eq!(passthrough.unwrap(), "def-456");
+        assert_eq!(mismatch.unwrap_err().to_string(), "Alias 'auth-fix' refers to a codex session, not claude");
+    }
+
+    #[test]
+    fn alias_names_reject_separators() {
+        assert!(add("bad name", "codex:abc").is_err());
+        assert!(add("a/b", "codex:abc").is_err());
+    }
+}
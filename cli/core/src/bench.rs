@@ -0,0 +1,262 @@
+//! `bridge bench`: generate a synthetic session store and time the scanning,
+//! parsing, and redaction paths against it, so users can measure performance
+//! on their own machines and compare builds.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::adapters;
+use crate::agents::{CwdMatch, IdMatch, ReadOptions, SearchQuery};
+use crate::redact;
+
+const BENCH_CWD: &str = "/bench/project";
+/// Search query that appears in no generated session, so `search` scans all.
+const MISSING_QUERY: &str = "bench-query-without-matches";
+const WORDS: &[&str] = &[
+    "refactor", "parser", "session", "cargo", "test", "fixture", "adapter", "redaction", "timeout", "cache",
+    "scan", "index", "handoff", "report", "finding", "module", "trait", "error", "config", "release",
+];
+
+pub struct BenchOptions {
+    pub agent: &'static str,
+    pub files: usize,
+    pub size_kb: usize,
+    pub iterations: usize,
+    pub keep: bool,
+}
+
+/// Build the store, run every measurement, and return the `--json` object.
+/// The store is removed afterwards unless `keep` is set.
+pub fn run(options: &BenchOptions) -> Result<Value> {
+    let env_var = match options.agent {
+        "codex" => "BRIDGE_CODEX_SESSIONS_DIR",
+        "claude" => "BRIDGE_CLAUDE_PROJECTS_DIR",
+        other => return Err(anyhow!("bench supports codex and claude session stores, not {}", other)),
+    };
+    let root = std::env::temp_dir().join(format!("agent-bridge-bench-{}", std::process::id()));
+    let generated = generate_store(&root, options);
+    let result = generated.and_then(|files| {
+        // Point the adapter at the synthetic store and make sure a large
+        // store is scanned in full.
+        std::env::set_var(env_var, &root);
+        if options.files > 1000 {
+            std::env::set_var("BRIDGE_MAX_SCAN_FILES", options.files.to_string());
+        }
+        measure(options, &files)
+    });
+    if options.keep {
+        eprintln!("Kept synthetic store at {}", root.display());
+    } else {
+        let _ = fs::remove_dir_all(&root);
+    }
+    let mut report = result?;
+    report["store"] = json!(options.keep.then(|| root.to_string_lossy().to_string()));
+    Ok(report)
+}
+
+pub fn print_text(report: &Value) {
+    println!(
+        "bench {}: {} files x {} KB ({:.1} MB), {} iteration(s)",
+        report["agent"].as_str().unwrap_or(""),
+        report["files"],
+        report["file_size_kb"],
+        report["store_mb"].as_f64().unwrap_or(0.0),
+        report["iterations"]
+    );
+    for row in report["results"].as_array().into_iter().flatten() {
+        println!(
+            "  {:<8} mean {:>9.2} ms  min {:>9.2} ms  {:>12.1} {}",
+            row["operation"].as_str().unwrap_or(""),
+            row["mean_ms"].as_f64().unwrap_or(0.0),
+            row["min_ms"].as_f64().unwrap_or(0.0),
+            row["throughput"].as_f64().unwrap_or(0.0),
+            row["unit"].as_str().unwrap_or("")
+        );
+    }
+}
+
+fn measure(options: &BenchOptions, files: &[PathBuf]) -> Result<Value> {
+    let adapter = adapters::get_adapter(options.agent).context("bench adapter missing")?;
+    let contents = files
+        .iter()
+        .map(|path| fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display())))
+        .collect::<Result<Vec<_>>>()?;
+    let store_bytes: usize = contents.iter().map(String::len).sum();
+    let newest = files.last().context("bench store is empty")?;
+    let newest_bytes = contents.last().map(String::len).unwrap_or(0);
+    let newest_id = newest.file_stem().and_then(|stem| stem.to_str()).unwrap_or("").to_string();
+    let megabytes = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+
+    let mut results = Vec::new();
+    let list = time(options.iterations, || {
+        adapter.list_sessions(None, CwdMatch::Exact, usize::MAX).map(drop)
+    })?;
+    results.push(row("list", &list, files.len() as f64, "files/s"));
+
+    let search = time(options.iterations, || {
+        adapter.search_sessions(&SearchQuery::substring(MISSING_QUERY), None, CwdMatch::Exact, 10).map(drop)
+    })?;
+    results.push(row("search", &search, megabytes(store_bytes), "MB/s"));
+
+    let read_options = ReadOptions {
+        id: Some(newest_id),
+        id_match: IdMatch::Exact,
+        cwd: BENCH_CWD.to_string(),
+        ..ReadOptions::default()
+    };
+    let read = time(options.iterations, || adapter.read_session(&read_options).map(drop))?;
+    results.push(row("read", &read, megabytes(newest_bytes), "MB/s"));
+
+    // Redaction runs on message text, so time it line by line rather than
+    // on whole files.
+    let redact = time(options.iterations, || {
+        for line in contents.iter().flat_map(|content| content.lines()) {
+            drop(redact::redact_sensitive_text(line));
+        }
+        Ok(())
+    })?;
+    results.push(row("redact", &redact, megabytes(store_bytes), "MB/s"));
+
+    Ok(json!({
+        "agent": options.agent,
+        "files": files.len(),
+        "file_size_kb": options.size_kb,
+        "store_mb": round2(megabytes(store_bytes)),
+        "iterations": options.iterations,
+        "results": results,
+    }))
+}
+
+/// Run `operation` `iterations` times and return each wall-clock duration.
+fn time<F>(iterations: usize, mut operation: F) -> Result<Vec<Duration>>
+where
+    F: FnMut() -> Result<()>,
+{
+    (0..iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            operation()?;
+            Ok(start.elapsed())
+        })
+        .collect()
+}
+
+/// `work` units per mean second, e.g. files or megabytes.
+fn row(operation: &str, durations: &[Duration], work: f64, unit: &str) -> Value {
+    let millis = durations.iter().map(|duration| duration.as_secs_f64() * 1000.0).collect::<Vec<_>>();
+    let mean = millis.iter().sum::<f64>() / millis.len() as f64;
+    let min = millis.iter().copied().fold(f64::INFINITY, f64::min);
+    let throughput = if mean > 0.0 { work / (mean / 1000.0) } else { 0.0 };
+    json!({
+        "operation": operation,
+        "mean_ms": round2(mean),
+        "min_ms": round2(min),
+        "throughput": round2(throughput),
+        "unit": unit,
+    })
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Write `files` sessions of roughly `size_kb` each, oldest first.
+fn generate_store(root: &Path, options: &BenchOptions) -> Result<Vec<PathBuf>> {
+    let dir = match options.agent {
+        "codex" => root.join("2026").join("01").join("01"),
+        _ => root.join("-bench-project"),
+    };
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut files = Vec::with_capacity(options.files);
+    for index in 0..options.files.max(1) {
+        let path = dir.join(format!("bench-{:05}.jsonl", index));
+        let content = session_content(options.agent, options.size_kb * 1024, &mut seed);
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+fn session_content(agent: &str, target_bytes: usize, seed: &mut u64) -> String {
+    let mut lines = match agent {
+        "codex" => vec![
+            json!({"type": "session_meta", "payload": {"cwd": BENCH_CWD}}),
+            json!({"type": "turn_context", "payload": {"cwd": BENCH_CWD, "model": "bench-model"}}),
+        ],
+        _ => vec![json!({"cwd": BENCH_CWD})],
+    }
+    .into_iter()
+    .map(|line| line.to_string())
+    .collect::<Vec<_>>();
+    let mut size = lines.iter().map(|line| line.len() + 1).sum::<usize>();
+    let mut turn = 0usize;
+    while size < target_bytes {
+        let role = if turn % 2 == 0 { "user" } else { "assistant" };
+        let text = message_text(turn, seed);
+        let line = match agent {
+            "codex" => json!({
+                "type": "response_item",
+                "payload": {"type": "message", "role": role, "content": [{"type": "output_text", "text": text}]}
+            }),
+            _ => json!({
+                "type": role,
+                "cwd": BENCH_CWD,
+                "message": {"role": role, "content": [{"type": "text", "text": text}]}
+            }),
+        }
+        .to_string();
+        size += line.len() + 1;
+        lines.push(line);
+        turn += 1;
+    }
+    format!("{}\n", lines.join("\n"))
+}
+
+/// About 60 pseudo-random words; every fifth turn carries a fake credential
+/// so redaction has something to replace.
+fn message_text(turn: usize, seed: &mut u64) -> String {
+    let mut words = (0..60)
+        .map(|_| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            WORDS[(*seed % WORDS.len() as u64) as usize]
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    if turn % 5 == 0 {
+        words.push_str(&format!(" export API_KEY=bench{:020}", turn));
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{row, session_content, BENCH_CWD};
+    use crate::agents::parse_session_bytes;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn generated_sessions_reach_their_size_and_parse() {
+        for agent in ["codex", "claude"] {
+            let content = session_content(agent, 8 * 1024, &mut 7);
+            assert!(content.len() >= 8 * 1024, "{}: {} bytes", agent, content.len());
+            let session = parse_session_bytes(agent, "bench-00000.jsonl", content.as_bytes(), 1).unwrap();
+            assert_eq!(session.cwd.as_deref(), Some(BENCH_CWD), "{}", agent);
+        }
+    }
+
+    #[test]
+    fn rows_report_mean_min_and_throughput() {
+        let durations = [Duration::from_millis(10), Duration::from_millis(30)];
+        assert_eq!(
+            row("read", &durations, 2.0, "MB/s"),
+            json!({"operation": "read", "mean_ms": 20.0, "min_ms": 10.0, "throughput": 100.0, "unit": "MB/s"})
+        );
+    }
+}
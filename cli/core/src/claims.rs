@@ -0,0 +1,281 @@
+//! Claims a `verify` handoff asks the report to check against the working
+//! tree: that a file exists, that it contains some text, or that a command
+//! succeeds. Paths and commands resolve against the report's cwd, and
+//! commands only run with `report --allow-commands`. The Node CLI mirrors
+//! this in `scripts/read_session.cjs`.
+
+use crate::report::HandoffIssue;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Seconds a command claim may run before it is killed and fails.
+const COMMAND_TIMEOUT_SECS: u64 = 300;
+/// Characters of command output quoted in a failed claim's detail.
+const MAX_DETAIL_CHARS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimKind {
+    FileExists { path: String },
+    FileContains { path: String, text: String },
+    Command { run: String },
+}
+
+/// One entry of a handoff's `claims` array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Claim {
+    pub kind: ClaimKind,
+    pub description: Option<String>,
+}
+
+/// Fields each claim type requires besides `type`.
+fn required_fields(claim_type: &str) -> Option<&'static [&'static str]> {
+    match claim_type {
+        "file_exists" => Some(&["path"]),
+        "file_contains" => Some(&["path", "text"]),
+        "command" => Some(&["run"]),
+        _ => None,
+    }
+}
+
+fn unsupported_type(claim_type: &str) -> String {
+    format!("Unsupported claim type: {} (expected file_exists, file_contains, or command)", claim_type)
+}
+
+impl Claim {
+    pub fn parse(value: &Value) -> Result<Claim> {
+        if !value.is_object() {
+            return Err(anyhow!("Each claim must be an object"));
+        }
+        let claim_type = value["type"].as_str().ok_or_else(|| anyhow!("Each claim must include string field: type"))?;
+        let fields = required_fields(claim_type).ok_or_else(|| anyhow!(unsupported_type(claim_type)))?;
+        let mut values = Vec::new();
+        for field in fields {
+            match value[*field].as_str() {
+                Some(text) if !text.trim().is_empty() => values.push(text.to_string()),
+                _ => return Err(anyhow!("{} claims must include string field: {}", claim_type, field)),
+            }
+        }
+        let mut values = values.into_iter();
+        let mut next = || values.next().unwrap_or_default();
+        let kind = match claim_type {
+            "file_exists" => ClaimKind::FileExists { path: next() },
+            "file_contains" => ClaimKind::FileContains { path: next(), text: next() },
+            _ => ClaimKind::Command { run: next() },
+        };
+        let description = value["description"].as_str().filter(|text| !text.trim().is_empty()).map(str::to_string);
+        Ok(Claim { kind, description })
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self.kind {
+            ClaimKind::FileExists { .. } => "file_exists",
+            ClaimKind::FileContains { .. } => "file_contains",
+            ClaimKind::Command { .. } => "command",
+        }
+    }
+
+    /// The claim as a sentence: its `description`, or one built from its fields.
+    fn label(&self) -> String {
+        if let Some(description) = &self.description {
+            return description.clone();
+        }
+        match &self.kind {
+            ClaimKind::FileExists { path } => format!("{} exists", path),
+            ClaimKind::FileContains { path, text } => format!("{} contains \"{}\"", path, text),
+            ClaimKind::Command { run } => format!("`{}` succeeds", run),
+        }
+    }
+
+    /// The file or command a finding about this claim cites.
+    fn evidence(&self) -> &str {
+        match &self.kind {
+            ClaimKind::FileExists { path } | ClaimKind::FileContains { path, .. } => path,
+            ClaimKind::Command { run } => run,
+        }
+    }
+
+    /// `pass`, `fail`, or `skipped`, with a short detail.
+    fn check(&self, cwd: &str, allow_commands: bool) -> (&'static str, String) {
+        match &self.kind {
+            ClaimKind::FileExists { path } => {
+                if Path::new(cwd).join(path).exists() {
+                    ("pass", "found".to_string())
+                } else {
+                    ("fail", "not found".to_string())
+                }
+            }
+            ClaimKind::FileContains { path, text } => match std::fs::read(Path::new(cwd).join(path)) {
+                Ok(bytes) if String::from_utf8_lossy(&bytes).contains(text.as_str()) => ("pass", "found".to_string()),
+                Ok(_) => ("fail", "text not found".to_string()),
+                Err(_) => ("fail", "file not found".to_string()),
+            },
+            ClaimKind::Command { .. } if !allow_commands => {
+                ("skipped", "command claims run only with --allow-commands".to_string())
+            }
+            ClaimKind::Command { run } => run_command(run, cwd),
+        }
+    }
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Run `run` through the platform shell in `cwd`. A failure's detail quotes
+/// the last line of stderr, or of stdout when stderr is empty.
+fn run_command(run: &str, cwd: &str) -> (&'static str, String) {
+    let mut command = if cfg!(windows) { Command::new("cmd") } else { Command::new("sh") };
+    command.arg(if cfg!(windows) { "/C" } else { "-c" }).arg(run);
+    let spawned = command.current_dir(cwd).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(error) => return ("fail", format!("failed to start: {}", error)),
+    };
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let deadline = Instant::now() + Duration::from_secs(COMMAND_TIMEOUT_SECS);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Grandchildren may still hold the pipes, so the readers are left behind.
+                return ("fail", format!("timed out after {}s", COMMAND_TIMEOUT_SECS));
+            }
+        }
+    };
+    if status.success() {
+        return ("pass", "exited 0".to_string());
+    }
+    let (stdout, stderr) = (stdout.join().unwrap_or_default(), stderr.join().unwrap_or_default());
+    let mut detail = status.code().map_or("killed by signal".to_string(), |code| format!("exited {}", code));
+    let output = if stderr.trim().is_empty() { stdout } else { stderr };
+    if let Some(line) = output.lines().map(str::trim).rfind(|line| !line.is_empty()) {
+        detail.push_str(": ");
+        detail.extend(line.chars().take(MAX_DETAIL_CHARS));
+    }
+    ("fail", detail)
+}
+
+/// Check every claim, as findings and the report's `claims` entries. Also
+/// returns whether any claim failed and whether any was skipped.
+pub(crate) fn check_all(claims: &[Claim], cwd: &str, allow_commands: bool) -> (Vec<Value>, Vec<Value>, bool, bool) {
+    let mut findings = Vec::new();
+    let mut results = Vec::new();
+    let (mut failed, mut skipped) = (false, false);
+    for claim in claims {
+        let (status, detail) = claim.check(cwd, allow_commands);
+        let label = claim.label();
+        let (severity, summary, confidence) = match status {
+            "pass" => ("P3", format!("Claim verified: {}", label), 0.95),
+            "fail" => {
+                failed = true;
+                ("P1", format!("Claim failed: {} ({})", label, detail), 0.9)
+            }
+            _ => {
+                skipped = true;
+                ("P2", format!("Claim not checked: {} ({})", label, detail), 0.5)
+            }
+        };
+        findings.push(json!({
+            "severity": severity,
+            "summary": summary,
+            "evidence": [claim.evidence()],
+            "confidence": confidence
+        }));
+        results.push(json!({ "type": claim.type_name(), "claim": label, "status": status, "detail": detail }));
+    }
+    (findings, results, failed, skipped)
+}
+
+/// Report every problem with the claim at `path` for `bridge handoff validate`.
+pub(crate) fn validate(path: &str, value: &Value, issues: &mut Vec<HandoffIssue>) {
+    let Some(object) = value.as_object() else {
+        issues.push(HandoffIssue::new(path, "WRONG_TYPE", "Each claim must be an object"));
+        return;
+    };
+    let claim_type = match object.get("type") {
+        None => {
+            issues.push(HandoffIssue::new(format!("{}.type", path), "MISSING_FIELD", "Each claim must include string field: type"));
+            return;
+        }
+        Some(Value::String(claim_type)) => claim_type.as_str(),
+        Some(_) => {
+            issues.push(HandoffIssue::new(format!("{}.type", path), "WRONG_TYPE", "type must be a string"));
+            return;
+        }
+    };
+    let Some(fields) = required_fields(claim_type) else {
+        issues.push(HandoffIssue::new(format!("{}.type", path), "BAD_CLAIM", unsupported_type(claim_type)));
+        return;
+    };
+    for key in object.keys().filter(|key| !["type", "description"].contains(&key.as_str()) && !fields.contains(&key.as_str())) {
+        issues.push(HandoffIssue::new(format!("{}.{}", path, key), "UNEXPECTED_FIELD", format!("Unexpected field: {}", key)));
+    }
+    for field in fields {
+        let field_path = format!("{}.{}", path, field);
+        match object.get(*field) {
+            None => issues.push(HandoffIssue::new(
+                field_path,
+                "MISSING_FIELD",
+                format!("{} claims must include string field: {}", claim_type, field),
+            )),
+            Some(Value::String(text)) if text.trim().is_empty() => {
+                issues.push(HandoffIssue::new(field_path, "EMPTY", format!("{} must not be empty", field)))
+            }
+            Some(Value::String(_)) => {}
+            Some(_) => issues.push(HandoffIssue::new(field_path, "WRONG_TYPE", format!("{} must be a string", field))),
+        }
+    }
+    if object.get("description").is_some_and(|description| !description.is_string()) {
+        issues.push(HandoffIssue::new(format!("{}.description", path), "WRONG_TYPE", "description must be a string"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_all, Claim};
+    use serde_json::json;
+
+    #[test]
+    fn claims_check_files_and_skip_commands_unless_allowed() {
+        let dir = std::env::temp_dir().join(format!("bridge-claims-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.rs"), "pub fn parse() {}\n").unwrap();
+        let claims = [
+            json!({"type": "file_contains", "path": "lib.rs", "text": "fn parse"}),
+            json!({"type": "file_exists", "path": "missing.rs", "description": "missing.rs was added"}),
+            json!({"type": "command", "run": "exit 3"}),
+        ]
+        .iter()
+        .map(|claim| Claim::parse(claim).unwrap())
+        .collect::<Vec<_>>();
+
+        let (findings, results, failed, skipped) = check_all(&claims, dir.to_str().unwrap(), false);
+        assert_eq!(findings[0]["summary"], "Claim verified: lib.rs contains \"fn parse\"");
+        assert_eq!(findings[1]["summary"], "Claim failed: missing.rs was added (not found)");
+        assert_eq!(results[2]["status"], "skipped");
+        assert!(failed && skipped);
+
+        #[cfg(unix)]
+        {
+            let (_, results, _, skipped) = check_all(&claims[2..], dir.to_str().unwrap(), true);
+            assert_eq!(results[0]["detail"], "exited 3");
+            assert!(!skipped);
+        }
+        assert!(Claim::parse(&json!({"type": "tests_pass"})).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
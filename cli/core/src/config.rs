@@ -0,0 +1,105 @@
+use crate::utils::expand_home;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+static CONFIG: OnceLock<Value> = OnceLock::new();
+static PORTABLE_HOME_FLAG: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep all bridge-owned state under `dir` (`--portable`).
+pub fn set_portable_home(dir: &str) {
+    if let Some(path) = expand_home(dir) {
+        let _ = PORTABLE_HOME_FLAG.set(path);
+    }
+}
+
+/// Portable state root: `--portable`, then `BRIDGE_HOME`.
+pub fn portable_home() -> Option<PathBuf> {
+    PORTABLE_HOME_FLAG.get().cloned().or_else(|| {
+        std::env::var("BRIDGE_HOME")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .and_then(|value| expand_home(&value))
+    })
+}
+
+/// `$<var>/agent-bridge` when the XDG variable holds an absolute path (the
+/// spec says relative values are ignored), else `~/<fallback>/agent-bridge`.
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+    std::env::var(var)
+        .ok()
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| expand_home(&format!("~/{}", fallback)))
+        .unwrap_or_else(|| PathBuf::from(format!("~/{}", fallback)))
+        .join("agent-bridge")
+}
+
+/// Directory for the config file and aliases: the portable root, else
+/// `$XDG_CONFIG_HOME/agent-bridge`, else `~/.config/agent-bridge`.
+pub fn config_dir() -> PathBuf {
+    portable_home().unwrap_or_else(|| xdg_dir("XDG_CONFIG_HOME", ".config"))
+}
+
+/// Directory for rebuildable caches and indexes: `<portable root>/cache`,
+/// else `$XDG_CACHE_HOME/agent-bridge`, else `~/.cache/agent-bridge`.
+pub fn cache_dir() -> PathBuf {
+    match portable_home() {
+        Some(home) => home.join("cache"),
+        None => xdg_dir("XDG_CACHE_HOME", ".cache"),
+    }
+}
+
+/// Location of the user config file (`BRIDGE_CONFIG` overrides the default).
+pub fn config_path() -> PathBuf {
+    std::env::var("BRIDGE_CONFIG")
+        .ok()
+        .and_then(|value| expand_home(&value))
+        .unwrap_or_else(|| config_dir().join("config.toml"))
+}
+
+/// The parsed config file, loaded once per process. A missing file is an
+/// empty config; a malformed one is reported on stderr and ignored so a bad
+/// edit never takes the whole CLI down.
+pub fn get() -> &'static Value {
+    CONFIG.get_or_init(|| {
+        let path = config_path();
+        let Ok(raw) = fs::read_to_string(&path) else {
+            return Value::Object(Default::default());
+        };
+        match toml::from_str::<Value>(&raw) {
+            Ok(value) => value,
+            Err(err) => {
+                eprintln!("Warning: ignoring invalid config {}: {}", path.display(), err);
+                Value::Object(Default::default())
+            }
+        }
+    })
+}
+
+/// A top-level config table such as `[theme]`, or `Value::Null` when absent.
+pub fn section(name: &str) -> &'static Value {
+    &get()[name]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::xdg_dir;
+    use crate::utils::expand_home;
+    use std::path::PathBuf;
+
+    #[test]
+    fn absolute_xdg_paths_hold_the_state_directory() {
+        std::env::set_var("BRIDGE_TEST_XDG_ABSOLUTE", "/srv/state");
+        assert_eq!(xdg_dir("BRIDGE_TEST_XDG_ABSOLUTE", ".config"), PathBuf::from("/srv/state/agent-bridge"));
+    }
+
+    #[test]
+    fn relative_or_missing_xdg_paths_fall_back_to_home() {
+        std::env::set_var("BRIDGE_TEST_XDG_RELATIVE", "state");
+        let fallback = expand_home("~/.cache").unwrap().join("agent-bridge");
+        assert_eq!(xdg_dir("BRIDGE_TEST_XDG_RELATIVE", ".cache"), fallback);
+        assert_eq!(xdg_dir("BRIDGE_TEST_XDG_UNSET", ".cache"), fallback);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::relevance::RelevanceRules;
 use anyhow::{anyhow, Context, Result};
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
@@ -11,8 +12,32 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+mod agents_md;
+mod history;
+mod lint;
+mod preview;
+mod render;
+mod snapshot;
+mod sync_agents;
+
+pub use agents_md::agents_md;
+pub use history::compact as compact_history;
+pub use lint::lint;
+pub use render::{render, RenderFormat, RenderOptions};
+pub use sync_agents::sync_agents;
+
 const ZERO_SHA: &str = "0000000000000000000000000000000000000000";
 
+/// Pack files in the read order `00_START_HERE.md` prescribes, with what each
+/// one is for.
+const READ_ORDER: &[(&str, &str)] = &[
+    ("00_START_HERE.md", "index, snapshot, and fast facts"),
+    ("10_SYSTEM_OVERVIEW.md", "architecture and execution paths"),
+    ("30_BEHAVIORAL_INVARIANTS.md", "contracts to preserve; read before changing behavior"),
+    ("20_CODE_MAP.md", "deep dive only the files relevant to the task"),
+    ("40_OPERATIONS_AND_RELEASE.md", "tests, release, and maintenance"),
+];
+
 pub struct BuildOptions {
     pub reason: Option<String>,
     pub base: Option<String>,
@@ -29,6 +54,13 @@ struct FileMeta {
     words: usize,
 }
 
+/// Where `head` sits relative to the nearest tag (`git describe --tags`).
+struct TagInfo {
+    describe: Option<String>,
+    latest_tag: Option<String>,
+    commits_since_tag: Option<u64>,
+}
+
 struct ManifestBundle {
     value: Value,
     stable_checksum: String,
@@ -47,9 +79,10 @@ pub fn build(options: BuildOptions) -> Result<()> {
     let package_json_path = repo_root.join("package.json");
     let package_version = read_package_version(&package_json_path).unwrap_or_else(|| "unknown".to_string());
 
-    let cargo_toml_path = repo_root.join("cli").join("Cargo.toml");
-    let cargo_version = fs::read_to_string(&cargo_toml_path)
-        .ok()
+    // The crate lives in cli/ here; fall back to a root Cargo.toml for other layouts.
+    let cargo_version = [repo_root.join("cli").join("Cargo.toml"), repo_root.join("Cargo.toml")]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
         .and_then(|text| parse_cargo_version(&text))
         .unwrap_or_else(|| "unknown".to_string());
 
@@ -91,14 +124,14 @@ pub fn build(options: BuildOptions) -> Result<()> {
     let path_counts = summarize_path_counts(&tracked_files);
 
     let command_surface = vec![
-        ("read", "Read latest or selected session content", vec!["scripts/read_session.cjs", "cli/src/main.rs", "cli/src/agents.rs"]),
-        ("list", "List recent sessions by agent", vec!["scripts/read_session.cjs", "cli/src/agents.rs"]),
-        ("search", "Find sessions containing text", vec!["scripts/read_session.cjs", "cli/src/agents.rs"]),
-        ("compare", "Compare multiple agent outputs", vec!["scripts/read_session.cjs", "cli/src/report.rs"]),
-        ("report", "Build coordinator report from handoff JSON", vec!["scripts/read_session.cjs", "cli/src/report.rs"]),
+        ("read", "Read latest or selected session content", vec!["scripts/read_session.cjs", "cli/src/main.rs", "cli/core/src/agents.rs"]),
+        ("list", "List recent sessions by agent", vec!["scripts/read_session.cjs", "cli/core/src/agents.rs"]),
+        ("search", "Find sessions containing text", vec!["scripts/read_session.cjs", "cli/core/src/agents.rs"]),
+        ("compare", "Compare multiple agent outputs", vec!["scripts/read_session.cjs", "cli/core/src/report.rs"]),
+        ("report", "Build coordinator report from handoff JSON", vec!["scripts/read_session.cjs", "cli/core/src/report.rs"]),
         ("setup", "Write provider instruction wiring files", vec!["scripts/read_session.cjs"]),
         ("doctor", "Check setup and path wiring", vec!["scripts/read_session.cjs"]),
-        ("trash-talk", "Roast active agents from session content", vec!["scripts/read_session.cjs", "cli/src/agents.rs"]),
+        ("trash-talk", "Roast active agents from session content", vec!["scripts/read_session.cjs", "cli/core/src/agents.rs"]),
         ("context-pack", "Build/sync/install context-pack automation", vec!["scripts/read_session.cjs", "scripts/context_pack"]),
     ];
 
@@ -158,6 +191,7 @@ pub fn build(options: BuildOptions) -> Result<()> {
         head_sha.as_deref(),
         &package_version,
         &cargo_version,
+        &describe_tags(&repo_root, head_sha.as_deref())?,
         &reason,
         options.base.as_deref(),
         &changed_files,
@@ -167,6 +201,7 @@ pub fn build(options: BuildOptions) -> Result<()> {
         &manifest_path,
         &format!("{}\n", serde_json::to_string_pretty(&manifest.value)?),
     )?;
+    sync_agents::refresh_existing(&repo_root, &pack_root)?;
 
     let previous_stable = previous_manifest
         .as_ref()
@@ -190,8 +225,7 @@ pub fn build(options: BuildOptions) -> Result<()> {
             compact_timestamp(&generated_at),
             short_sha(head_sha.as_deref())
         );
-        let snapshot_dir = snapshots_dir.join(&snapshot_id);
-        copy_dir_recursive(&current_dir, &snapshot_dir)?;
+        snapshot::write_snapshot(&current_dir, &snapshots_dir, &snapshot_id)?;
 
         let history_entry = json!({
             "snapshot_id": snapshot_id,
@@ -204,6 +238,7 @@ pub fn build(options: BuildOptions) -> Result<()> {
             "pack_checksum": manifest.pack_checksum,
         });
         append_jsonl(&history_path, &history_entry)?;
+        history::rotate(&pack_root, &history_path)?;
 
         println!(
             "[context-pack] updated: {} (snapshot {})",
@@ -236,10 +271,11 @@ pub fn sync_main(
         return Ok(());
     }
 
-    let changed_files = compute_changed_files(&repo_root, Some(remote_sha), local_sha)?;
+    let (base, changed_files) = resolve_push_range(&repo_root, remote_sha, local_sha)?;
+    let rules = RelevanceRules::load(&resolve_pack_root(&repo_root, None))?;
     let relevant = changed_files
         .iter()
-        .filter(|path| is_context_relevant(path))
+        .filter(|path| rules.is_relevant(path))
         .collect::<Vec<_>>();
 
     if relevant.is_empty() {
@@ -253,7 +289,7 @@ pub fn sync_main(
             short_sha(Some(remote_sha)),
             short_sha(Some(local_sha))
         )),
-        base: Some(remote_sha.to_string()),
+        base,
         head: Some(local_sha.to_string()),
         pack_dir: None,
         changed_files,
@@ -261,20 +297,62 @@ pub fn sync_main(
     })
 }
 
-pub fn rollback(snapshot: Option<&str>, pack_dir: Option<&str>) -> Result<()> {
+/// The diff base and changed files for a push. A fast-forward diffs from the
+/// remote SHA. A force push or rebase (remote not an ancestor of local) diffs
+/// from the merge-base instead, and when no merge-base is known locally every
+/// file tracked at the local SHA is scanned.
+fn resolve_push_range(
+    repo_root: &Path,
+    remote_sha: &str,
+    local_sha: &str,
+) -> Result<(Option<String>, Vec<String>)> {
+    let remote_sha = remote_sha.trim();
+    if remote_sha.is_empty() || is_zero_sha(remote_sha) {
+        let changed = compute_changed_files(repo_root, None, local_sha)?;
+        return Ok((None, changed));
+    }
+
+    if git_succeeds(&["merge-base", "--is-ancestor", remote_sha, local_sha], repo_root) {
+        let changed = compute_changed_files(repo_root, Some(remote_sha), local_sha)?;
+        return Ok((Some(remote_sha.to_string()), changed));
+    }
+
+    let merge_base = run_git(&["merge-base", remote_sha, local_sha], repo_root, true)?;
+    if !merge_base.is_empty() {
+        println!(
+            "[context-pack] non-fast-forward push (force push or rebase): diffing from merge-base {}",
+            short_sha(Some(&merge_base))
+        );
+        let changed = compute_changed_files(repo_root, Some(&merge_base), local_sha)?;
+        return Ok((Some(merge_base), changed));
+    }
+
+    println!(
+        "[context-pack] non-fast-forward push with no merge-base for remote {}: scanning all tracked files",
+        short_sha(Some(remote_sha))
+    );
+    let tracked = run_git(&["ls-tree", "-r", "--name-only", local_sha], repo_root, true)?;
+    Ok((
+        None,
+        tracked
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+    ))
+}
+
+/// Prefix of the snapshot a rollback saves of `current/` before replacing it.
+const PRE_ROLLBACK_PREFIX: &str = "pre-rollback-";
+
+pub fn rollback(snapshot: Option<&str>, pack_dir: Option<&str>, dry_run: bool) -> Result<()> {
     let cwd = env::current_dir().context("Failed to resolve current directory")?;
     let repo_root = git_repo_root(&cwd)?;
     let pack_root = resolve_pack_root(&repo_root, pack_dir);
     let current_dir = pack_root.join("current");
     let snapshots_dir = pack_root.join("snapshots");
 
-    let mut snapshot_ids = fs::read_dir(&snapshots_dir)
-        .with_context(|| format!("Failed to list snapshots at {}", snapshots_dir.display()))?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().is_dir())
-        .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
-        .collect::<Vec<_>>();
-    snapshot_ids.sort();
+    let snapshot_ids = snapshot::list_ids(&snapshots_dir)?;
 
     if snapshot_ids.is_empty() {
         return Err(anyhow!(
@@ -283,21 +361,66 @@ pub fn rollback(snapshot: Option<&str>, pack_dir: Option<&str>) -> Result<()> {
         ));
     }
 
-    let target_snapshot = snapshot
-        .map(|value| value.to_string())
-        .unwrap_or_else(|| snapshot_ids.last().cloned().unwrap_or_default());
+    // Safety backups are only restored by name, so the default stays the latest build.
+    let target_snapshot = snapshot.map(|value| value.to_string()).unwrap_or_else(|| {
+        snapshot_ids
+            .iter()
+            .rev()
+            .find(|id| !id.starts_with(PRE_ROLLBACK_PREFIX))
+            .or(snapshot_ids.last())
+            .cloned()
+            .unwrap_or_default()
+    });
 
     if !snapshot_ids.iter().any(|id| id == &target_snapshot) {
         return Err(anyhow!("[context-pack] snapshot not found: {}", target_snapshot));
     }
 
-    let source_dir = snapshots_dir.join(&target_snapshot);
-    if current_dir.exists() {
-        fs::remove_dir_all(&current_dir)
-            .with_context(|| format!("Failed to clear {}", current_dir.display()))?;
+    let files = snapshot::load(&snapshots_dir, &target_snapshot)?;
+    let current_files = if current_dir.is_dir() {
+        snapshot::read_dir_files(&current_dir)?
+    } else {
+        snapshot::SnapshotFiles::new()
+    };
+    let current_label = rel_path(&current_dir, &repo_root);
+
+    if current_files == files {
+        println!(
+            "[context-pack] {} already matches snapshot {}",
+            current_label, target_snapshot
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "[context-pack] rollback preview: {} -> {} (dry run, nothing written)",
+            target_snapshot, current_label
+        );
+        let changes = preview::describe_changes(&current_files, &files);
+        let file_count = changes.iter().filter(|line| !line.starts_with(' ')).count();
+        for line in changes {
+            println!("{}", line);
+        }
+        println!("[context-pack] {} file(s) would change", file_count);
+        return Ok(());
     }
-    ensure_dir(&current_dir)?;
-    copy_dir_recursive(&source_dir, &current_dir)?;
+
+    if !current_files.is_empty() {
+        let stamp = format!("{}{}", PRE_ROLLBACK_PREFIX, compact_timestamp(&now_stamp()));
+        let mut backup_id = stamp.clone();
+        let mut attempt = 1;
+        while snapshot_ids.contains(&backup_id) {
+            attempt += 1;
+            backup_id = format!("{}-{}", stamp, attempt);
+        }
+        snapshot::write_snapshot(&current_dir, &snapshots_dir, &backup_id)?;
+        println!(
+            "[context-pack] saved {} as snapshot {} (undo: bridge context-pack rollback --snapshot {})",
+            current_label, backup_id, backup_id
+        );
+    }
+    snapshot::restore(&files, &current_dir)?;
 
     println!(
         "[context-pack] restored snapshot {} -> {}",
@@ -375,15 +498,19 @@ pub fn check_freshness(base: &str, cwd: &str) -> Result<()> {
         }
     };
 
+    let repo_root = git_repo_root(&cwd_path).unwrap_or_else(|_| cwd_path.clone());
+    let rules = RelevanceRules::load(&resolve_pack_root(&repo_root, None))?;
     let mut pack_touched = false;
+    let mut touched_sections = Vec::new();
     let mut relevant = Vec::new();
 
     for file_path in changed_files.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
-        if file_path.starts_with(".agent-context/current/") {
+        if let Some(section) = file_path.strip_prefix(".agent-context/current/") {
             pack_touched = true;
+            touched_sections.push(section.to_string());
             continue;
         }
-        if is_context_relevant(file_path) {
+        if rules.is_relevant(file_path) {
             relevant.push(file_path.to_string());
         }
     }
@@ -393,6 +520,10 @@ pub fn check_freshness(base: &str, cwd: &str) -> Result<()> {
         return Ok(());
     }
 
+    if rules.has_sections() {
+        return report_stale_sections(&rules, &relevant, &touched_sections, pack_touched);
+    }
+
     if pack_touched {
         println!("PASS context-pack-freshness (context pack was updated)");
         return Ok(());
@@ -410,6 +541,60 @@ pub fn check_freshness(base: &str, cwd: &str) -> Result<()> {
     Ok(())
 }
 
+/// Freshness when `relevance.json` maps paths to sections: each mapped section
+/// must be updated in the same diff; relevant paths outside every mapping are
+/// satisfied by any pack update, as without a mapping.
+fn report_stale_sections(
+    rules: &RelevanceRules,
+    relevant: &[String],
+    touched_sections: &[String],
+    pack_touched: bool,
+) -> Result<()> {
+    let mut stale: Vec<(String, Vec<&str>)> = Vec::new();
+    let mut unmapped = Vec::new();
+    for file_path in relevant {
+        let sections = rules.sections_for(file_path);
+        if sections.is_empty() {
+            unmapped.push(file_path.as_str());
+            continue;
+        }
+        for section in sections.into_iter().filter(|section| !touched_sections.contains(section)) {
+            match stale.iter_mut().find(|(name, _)| *name == section) {
+                Some((_, paths)) => paths.push(file_path),
+                None => stale.push((section, vec![file_path])),
+            }
+        }
+    }
+    let unmapped_stale = !unmapped.is_empty() && !pack_touched;
+
+    if stale.is_empty() && !unmapped_stale {
+        println!("PASS context-pack-freshness (mapped sections were updated)");
+        return Ok(());
+    }
+
+    if !stale.is_empty() {
+        println!(
+            "WARNING: {} context-pack section(s) are stale for the changed files:",
+            stale.len()
+        );
+        for (section, paths) in &stale {
+            println!("  - {} ({})", section, paths.join(", "));
+        }
+    }
+    if unmapped_stale {
+        println!(
+            "WARNING: {} context-relevant file(s) changed but .agent-context/current/ was not updated:",
+            unmapped.len()
+        );
+        for file_path in unmapped {
+            println!("  - {}", file_path);
+        }
+    }
+    println!();
+    println!("Consider running: bridge context-pack build");
+    Ok(())
+}
+
 fn git_repo_root(cwd: &Path) -> Result<PathBuf> {
     let root = run_git(&["rev-parse", "--show-toplevel"], cwd, true)?;
     if root.trim().is_empty() {
@@ -419,6 +604,15 @@ fn git_repo_root(cwd: &Path) -> Result<PathBuf> {
     }
 }
 
+fn git_succeeds(args: &[&str], cwd: &Path) -> bool {
+    Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
 fn run_git(args: &[&str], cwd: &Path, allow_failure: bool) -> Result<String> {
     let output = Command::new("git")
         .args(args)
@@ -492,6 +686,24 @@ fn parse_cargo_version(raw: &str) -> Option<String> {
     None
 }
 
+fn describe_tags(repo_root: &Path, head: Option<&str>) -> Result<TagInfo> {
+    let head = head.unwrap_or("HEAD");
+    let non_empty = |value: String| (!value.is_empty()).then_some(value);
+    let describe = non_empty(run_git(&["describe", "--tags", head], repo_root, true)?);
+    let latest_tag = non_empty(run_git(&["describe", "--tags", "--abbrev=0", head], repo_root, true)?);
+    let commits_since_tag = match &latest_tag {
+        Some(tag) => run_git(&["rev-list", "--count", &format!("{}..{}", tag, head)], repo_root, true)?
+            .parse::<u64>()
+            .ok(),
+        None => None,
+    };
+    Ok(TagInfo {
+        describe,
+        latest_tag,
+        commits_since_tag,
+    })
+}
+
 fn compute_changed_files(repo_root: &Path, base: Option<&str>, head: &str) -> Result<Vec<String>> {
     if head.trim().is_empty() {
         return Ok(Vec::new());
@@ -526,6 +738,7 @@ fn summarize_path_counts(paths: &[String]) -> Vec<(String, usize)> {
     let mut buckets = vec![
         ("scripts/".to_string(), "scripts".to_string(), 0usize),
         ("cli/src/".to_string(), "cli/src".to_string(), 0usize),
+        ("cli/core/src/".to_string(), "cli/core/src".to_string(), 0usize),
         ("schemas/".to_string(), "schemas".to_string(), 0usize),
         ("fixtures/".to_string(), "fixtures".to_string(), 0usize),
         (".github/workflows/".to_string(), ".github/workflows".to_string(), 0usize),
@@ -566,6 +779,7 @@ fn collect_files_meta(current_dir: &Path, relative_paths: &[String]) -> Result<V
     Ok(out)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_manifest(
     generated_at: &str,
     repo_root: &Path,
@@ -574,6 +788,7 @@ fn build_manifest(
     head_sha: Option<&str>,
     package_version: &str,
     cargo_version: &str,
+    tag_info: &TagInfo,
     reason: &str,
     base_sha: Option<&str>,
     changed_files: &[String],
@@ -618,6 +833,9 @@ fn build_manifest(
         "head_sha": head_sha,
         "package_version": package_version,
         "cargo_version": cargo_version,
+        "git_describe": tag_info.describe,
+        "latest_tag": tag_info.latest_tag,
+        "commits_since_tag": tag_info.commits_since_tag,
         "build_reason": reason,
         "base_sha": base_sha,
         "changed_files": changed_files,
@@ -661,32 +879,6 @@ fn read_json(path: &Path) -> Result<Option<Value>> {
     Ok(Some(value))
 }
 
-fn copy_dir_recursive(source: &Path, destination: &Path) -> Result<()> {
-    ensure_dir(destination)?;
-    for entry in fs::read_dir(source)
-        .with_context(|| format!("Failed to read {}", source.display()))?
-    {
-        let entry = entry.with_context(|| format!("Failed to read entry in {}", source.display()))?;
-        let source_path = entry.path();
-        let destination_path = destination.join(entry.file_name());
-        if source_path.is_dir() {
-            copy_dir_recursive(&source_path, &destination_path)?;
-        } else {
-            if let Some(parent) = destination_path.parent() {
-                ensure_dir(parent)?;
-            }
-            fs::copy(&source_path, &destination_path).with_context(|| {
-                format!(
-                    "Failed to copy {} -> {}",
-                    source_path.display(),
-                    destination_path.display()
-                )
-            })?;
-        }
-    }
-    Ok(())
-}
-
 fn rel_path(path: &Path, base: &Path) -> String {
     path.strip_prefix(base)
         .map(|relative| relative.display().to_string())
@@ -712,7 +904,7 @@ fn short_sha(sha: Option<&str>) -> String {
 }
 
 fn compact_timestamp(iso: &str) -> String {
-    let mut compact = iso.replace('-', "").replace(':', "");
+    let mut compact = iso.replace(['-', ':'], "");
     if let Some(dot_idx) = compact.find('.') {
         if let Some(z_rel) = compact[dot_idx..].find('Z') {
             let end = dot_idx + z_rel + 1;
@@ -746,44 +938,6 @@ fn is_main_push(local_ref: &str, remote_ref: &str) -> bool {
     local_ref == "refs/heads/main" || remote_ref == "refs/heads/main"
 }
 
-fn is_context_relevant(file_path: &str) -> bool {
-    let normalized = file_path.replace('\\', "/");
-    if normalized.starts_with("blog/")
-        || normalized.starts_with("notes/")
-        || normalized.starts_with("drafts/")
-        || normalized.starts_with("scratch/")
-        || normalized.starts_with("tmp/")
-        || normalized.starts_with(".agent-context/")
-        || normalized.starts_with("docs/demo-")
-    {
-        return false;
-    }
-
-    if matches!(
-        normalized.as_str(),
-        "README.md"
-            | "PROTOCOL.md"
-            | "CONTRIBUTING.md"
-            | "SKILL.md"
-            | "AGENTS.md"
-            | "package.json"
-            | "package-lock.json"
-            | "cli/Cargo.toml"
-            | "cli/Cargo.lock"
-            | "docs/architecture.svg"
-            | "docs/silo-tax-before-after.webp"
-    ) {
-        return true;
-    }
-
-    normalized.starts_with("scripts/")
-        || normalized.starts_with("cli/src/")
-        || normalized.starts_with("schemas/")
-        || normalized.starts_with("fixtures/golden/")
-        || normalized.starts_with("fixtures/session-store/")
-        || normalized.starts_with(".github/workflows/")
-}
-
 fn build_start_here(
     repo_name: &str,
     branch: &str,
@@ -844,7 +998,7 @@ fn build_system_overview(
     };
 
     format!(
-        "# System Overview\n\n## Product Shape\n- Package version: `{package_version}`\n- Crate version: `{cargo_version}`\n- Tracked files: `{tracked_file_count}`\n- Delivery: npm package (`bridge`) + Rust binary (`bridge`).\n\n## Runtime Architecture\n1. User asks a provider agent for cross-agent status.\n2. Agent invokes bridge command (`read`, `list`, `search`, `compare`, `report`, `setup`, `doctor`, `trash-talk`, `context-pack`).\n3. Bridge resolves session stores (Codex/Claude/Gemini/Cursor), applies redaction, and returns terminal text or JSON.\n4. Agent answers user with evidence from bridge output.\n\n## Dual-Implementation Contract\n- Node path: `scripts/read_session.cjs` + `scripts/adapters/*.cjs`.\n- Rust path: `cli/src/main.rs`, `cli/src/agents.rs`, `cli/src/report.rs`, `cli/src/adapters/*.rs`.\n- Protocol authority: `PROTOCOL.md` and `schemas/*.json`.\n- Parity guard: `scripts/conformance.sh`.\n\n## Command Surface\n| Command | Intent | Primary Paths |\n| --- | --- | --- |\n{command_rows}\n\n## Tracked Path Density\n{path_lines}\n"
+        "# System Overview\n\n## Product Shape\n- Package version: `{package_version}`\n- Crate version: `{cargo_version}`\n- Tracked files: `{tracked_file_count}`\n- Delivery: npm package (`bridge`) + Rust binary (`bridge`).\n\n## Runtime Architecture\n1. User asks a provider agent for cross-agent status.\n2. Agent invokes bridge command (`read`, `list`, `search`, `compare`, `report`, `setup`, `doctor`, `trash-talk`, `context-pack`).\n3. Bridge resolves session stores (Codex/Claude/Gemini/Cursor), applies redaction, and returns terminal text or JSON.\n4. Agent answers user with evidence from bridge output.\n\n## Dual-Implementation Contract\n- Node path: `scripts/read_session.cjs` + `scripts/adapters/*.cjs`.\n- Rust path: `cli/src/main.rs`, `cli/core/src/agents.rs`, `cli/core/src/report.rs`, `cli/core/src/adapters/*.rs`.\n- Protocol authority: `PROTOCOL.md` and `schemas/*.json`.\n- Parity guard: `scripts/conformance.sh`.\n\n## Command Surface\n| Command | Intent | Primary Paths |\n| --- | --- | --- |\n{command_rows}\n\n## Tracked Path Density\n{path_lines}\n"
     )
 }
 
@@ -857,8 +1011,8 @@ fn build_code_map() -> String {
 | `scripts/read_session.cjs` | Node CLI command parser + execution engine | Defines behavior for all user-facing commands in Node distribution | High |
 | `scripts/adapters/*.cjs` | Node agent-specific session adapters | Session discovery, parsing, and metadata quality for each provider | High |
 | `cli/src/main.rs` | Rust CLI command/arg handling | Entry-point parity with Node and release binary behavior | High |
-| `cli/src/agents.rs` | Rust session parsing + redaction + read/list/search | Largest behavioral surface and most error-code paths | High |
-| `cli/src/report.rs` | Rust compare/report logic | Cross-agent divergence logic and report markdown/json structure | High |
+| `cli/core/src/agents.rs` | Rust session parsing + redaction + read/list/search | Largest behavioral surface and most error-code paths | High |
+| `cli/core/src/report.rs` | Rust compare/report logic | Cross-agent divergence logic and report markdown/json structure | High |
 | `schemas/*.json` | JSON contract definitions | External compatibility for `--json` users and tests | High |
 | `PROTOCOL.md` | Versioned protocol contract | Human contract that aligns Node, Rust, and tests | High |
 | `README.md` | Public command docs and examples | User expectations and documentation-driven behavior | Medium |
@@ -868,7 +1022,7 @@ fn build_code_map() -> String {
 | `.github/workflows/release.yml` | Release pipeline | Controls publish safety and artifact generation | Medium |
 
 ## Extension Recipe (New Agent)
-1. Implement adapter in Rust: `cli/src/adapters/<agent>.rs` and register in `cli/src/adapters/mod.rs`.
+1. Implement adapter in Rust: `cli/core/src/adapters/<agent>.rs` and register in `cli/core/src/adapters/mod.rs`.
 2. Implement adapter in Node: `scripts/adapters/<agent>.cjs` and register in `scripts/adapters/registry.cjs`.
 3. Add schema enum coverage in `schemas/*.json`.
 4. Add fixtures and golden expectations under `fixtures/`.
@@ -984,3 +1138,96 @@ done
 "#
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_tags, resolve_push_range, run_git};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// A scratch repository on `main`; removed when dropped.
+    struct Repo(PathBuf);
+
+    impl Repo {
+        fn new(name: &str) -> Repo {
+            let dir = std::env::temp_dir().join(format!("bridge-pack-{}-{}", name, std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            run_git(&["init", "-q", "-b", "main"], &dir, false).unwrap();
+            Repo(dir)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+
+        fn git(&self, args: &[&str]) -> String {
+            run_git(args, &self.0, false).unwrap()
+        }
+
+        /// Commit a new `file` and return the commit's SHA.
+        fn commit(&self, file: &str) -> String {
+            fs::write(self.0.join(file), file).unwrap();
+            self.git(&["add", file]);
+            self.git(&["-c", "user.name=t", "-c", "user.email=t@example.com", "-c", "commit.gpgsign=false", "commit", "-q", "-m", file]);
+            self.git(&["rev-parse", "HEAD"])
+        }
+    }
+
+    impl Drop for Repo {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn fast_forward_pushes_diff_from_the_remote_sha() {
+        let repo = Repo::new("ff");
+        let remote = repo.commit("a.md");
+        let local = repo.commit("b.md");
+        let (base, changed) = resolve_push_range(repo.path(), &remote, &local).unwrap();
+        assert_eq!(base, Some(remote));
+        assert_eq!(changed, ["b.md"]);
+    }
+
+    #[test]
+    fn force_pushes_diff_from_the_merge_base() {
+        let repo = Repo::new("force");
+        let shared = repo.commit("a.md");
+        let remote = repo.commit("b.md");
+        repo.git(&["reset", "-q", "--hard", &shared]);
+        let local = repo.commit("c.md");
+        let (base, changed) = resolve_push_range(repo.path(), &remote, &local).unwrap();
+        assert_eq!(base, Some(shared));
+        assert_eq!(changed, ["c.md"]);
+    }
+
+    #[test]
+    fn unknown_remote_history_scans_every_tracked_file() {
+        let repo = Repo::new("unrelated");
+        repo.commit("a.md");
+        let local = repo.commit("b.md");
+        let (base, changed) = resolve_push_range(repo.path(), "1111111111111111111111111111111111111111", &local).unwrap();
+        assert_eq!(base, None);
+        assert_eq!(changed, ["a.md", "b.md"]);
+    }
+    #[test]
+    fn manifest_tag_info_counts_commits_since_the_latest_tag() {
+        let repo = Repo::new("tags");
+        repo.commit("a.md");
+        repo.git(&["tag", "v1.0.0"]);
+        repo.commit("b.md");
+        let head = repo.commit("c.md");
+        let info = describe_tags(repo.path(), Some(&head)).unwrap();
+        assert_eq!(info.latest_tag.as_deref(), Some("v1.0.0"));
+        assert_eq!(info.commits_since_tag, Some(2));
+        assert!(info.describe.unwrap().starts_with("v1.0.0-2-g"));
+    }
+
+    #[test]
+    fn untagged_repositories_have_no_tag_info() {
+        let repo = Repo::new("untagged");
+        repo.commit("a.md");
+        let info = describe_tags(repo.path(), None).unwrap();
+        assert_eq!((info.describe, info.latest_tag, info.commits_since_tag), (None, None, None));
+    }
+}
@@ -0,0 +1,194 @@
+//! Distill the built pack into a compact, standards-style AGENTS.md block.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::sync_agents::upsert_block;
+
+const START_MARKER: &str = "<!-- agent-bridge:agents-md:start -->";
+const END_MARKER: &str = "<!-- agent-bridge:agents-md:end -->";
+
+/// List items (`- x` or `1. x`) under the `## ` sections of `markdown`
+/// whose heading satisfies `wanted`.
+fn section_items(markdown: &str, wanted: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut in_section = false;
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some(heading) = line.strip_prefix("## ") {
+            in_section = wanted(heading.trim());
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let trimmed = line.trim();
+        let item = trimmed.strip_prefix("- ").or_else(|| {
+            let digits = trimmed.chars().take_while(|ch| ch.is_ascii_digit()).count();
+            (digits > 0).then(|| trimmed[digits..].strip_prefix(". ")).flatten()
+        });
+        if let Some(item) = item {
+            items.push(item.trim().to_string());
+        }
+    }
+    items
+}
+
+/// Lines inside fenced code blocks under the `## ` sections whose heading
+/// satisfies `wanted`.
+fn section_commands(markdown: &str, wanted: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut in_section = false;
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            if let Some(heading) = line.strip_prefix("## ") {
+                in_section = wanted(heading.trim());
+            }
+            continue;
+        }
+        if in_section && !line.trim().is_empty() {
+            commands.push(line.trim_end().to_string());
+        }
+    }
+    commands
+}
+
+fn read_pack_file(current_dir: &Path, name: &str) -> Result<String> {
+    let path = current_dir.join(name);
+    fs::read_to_string(&path).with_context(|| {
+        format!(
+            "Failed to read {} (run: bridge context-pack build)",
+            path.display()
+        )
+    })
+}
+
+/// Pack files whose content no longer matches the checksums `build` recorded.
+fn drifted_files(current_dir: &Path, manifest: &Value) -> Vec<String> {
+    manifest["files"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry["path"].as_str()?;
+            let recorded = entry["sha256"].as_str()?;
+            let content = fs::read(current_dir.join(name)).unwrap_or_default();
+            (super::sha256_hex(&content) != recorded).then(|| name.to_string())
+        })
+        .collect()
+}
+
+fn build_block(current_dir: &Path, current_label: &str) -> Result<String> {
+    let start_here = read_pack_file(current_dir, "00_START_HERE.md")?;
+    let invariants = read_pack_file(current_dir, "30_BEHAVIORAL_INVARIANTS.md")?;
+    let operations = read_pack_file(current_dir, "40_OPERATIONS_AND_RELEASE.md")?;
+
+    let facts = section_items(&start_here, |heading| heading == "Fast Facts");
+    let rules = section_items(&invariants, |heading| !heading.starts_with("Update Checklist"));
+    let mut commands = section_commands(&operations, |heading| heading.to_lowercase().contains("validation"));
+    if commands.is_empty() {
+        commands = section_commands(&operations, |_| true);
+    }
+
+    let bullets = |items: &[String]| {
+        items
+            .iter()
+            .map(|item| format!("- {}", item))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let mut block = format!(
+        "{START_MARKER}\n## Project Guide\n\nGenerated from the context pack in `{current_label}`. Run `bridge context-pack agents-md` after `bridge context-pack build` to refresh.\n"
+    );
+    if !facts.is_empty() {
+        block.push_str(&format!("\n### Fast Facts\n{}\n", bullets(&facts)));
+    }
+    if !rules.is_empty() {
+        block.push_str(&format!("\n### Invariants\n{}\n", bullets(&rules)));
+    }
+    if !commands.is_empty() {
+        block.push_str(&format!("\n### Validation\n```bash\n{}\n```\n", commands.join("\n")));
+    }
+    block.push_str(&format!(
+        "\nFor anything deeper, start at `{}00_START_HERE.md` and follow its read order.\n{}",
+        current_label, END_MARKER
+    ));
+    Ok(block)
+}
+
+/// Write (or with `dry_run`, print) the generated block into `AGENTS.md` at
+/// the repo root, or `output` when given. Text outside the markers is kept.
+pub fn agents_md(cwd: &str, pack_dir: Option<&str>, output: Option<&str>, dry_run: bool) -> Result<()> {
+    let repo_root = super::git_repo_root(&PathBuf::from(cwd))?;
+    let pack_root = super::resolve_pack_root(&repo_root, pack_dir);
+    let current_dir = pack_root.join("current");
+    let manifest = super::read_json(&current_dir.join("manifest.json"))?.ok_or_else(|| {
+        anyhow!(
+            "Context pack not built: {} has no manifest.json (run: bridge context-pack build)",
+            current_dir.display()
+        )
+    })?;
+    let drifted = drifted_files(&current_dir, &manifest);
+    if !drifted.is_empty() {
+        eprintln!(
+            "[context-pack] warning: {} changed since the last build; generating from the edited files",
+            drifted.join(", ")
+        );
+    }
+
+    let current_label = format!("{}/", super::rel_path(&current_dir, &repo_root).replace('\\', "/"));
+    let block = build_block(&current_dir, &current_label)?;
+    if dry_run {
+        println!("{}", block);
+        return Ok(());
+    }
+
+    let target = output
+        .map(|path| repo_root.join(path))
+        .unwrap_or_else(|| repo_root.join("AGENTS.md"));
+    let existing = match fs::read_to_string(&target) {
+        Ok(text) => Some(text),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err).with_context(|| format!("Failed to read {}", target.display())),
+    };
+    let next = upsert_block(existing.as_deref().unwrap_or(""), &block, START_MARKER, END_MARKER);
+    let status = match &existing {
+        Some(text) if *text == next => "unchanged",
+        Some(_) => "updated",
+        None => "created",
+    };
+    if status != "unchanged" {
+        super::write_text(&target, &next)?;
+    }
+    println!("[context-pack] {}: {}", status, super::rel_path(&target, &repo_root));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{section_commands, section_items};
+
+    #[test]
+    fn extracts_list_items_and_fenced_commands_by_section() {
+        let markdown = "# Ops\n\n## Fast Facts\n- One.\n2. Two.\nprose\n\n## Other\n- skip\n\n## Standard Validation\n```bash\nnpm test\n\ncargo test\n```\n";
+        assert_eq!(section_items(markdown, |heading| heading == "Fast Facts"), vec!["One.", "Two."]);
+        assert_eq!(
+            section_commands(markdown, |heading| heading.contains("Validation")),
+            vec!["npm test", "cargo test"]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Keep `history.jsonl` bounded by moving old entries into the gzip-compressed
+//! `history.archive.jsonl.gz`.
+
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::agents::parse_iso_millis;
+
+const ARCHIVE_FILE: &str = "history.archive.jsonl.gz";
+const DEFAULT_MAX_KB: u64 = 512;
+const DEFAULT_MAX_DAYS: u64 = 180;
+
+/// A limit from `name`, falling back to `default`; `0` disables the limit.
+fn env_limit(name: &str, default: u64) -> Option<u64> {
+    let value = env::var(name)
+        .ok()
+        .and_then(|raw| raw.trim().parse::<u64>().ok())
+        .unwrap_or(default);
+    (value > 0).then_some(value)
+}
+
+fn read_lines(history_path: &Path) -> Result<Vec<String>> {
+    if !history_path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(history_path)
+        .with_context(|| format!("Failed to read {}", history_path.display()))?;
+    Ok(raw
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn generated_at_millis(line: &str) -> Option<i64> {
+    let value = serde_json::from_str::<Value>(line).ok()?;
+    parse_iso_millis(value.get("generated_at")?.as_str()?)
+}
+
+/// Number of leading (oldest) lines to archive: entries generated before
+/// `cutoff_ms`, then more of the oldest until the rest fits in half of
+/// `max_bytes`, so rotation does not run again on the next build. The latest
+/// entry is always kept.
+fn rotation_split(lines: &[String], cutoff_ms: Option<i64>, max_bytes: Option<u64>) -> usize {
+    let mut split = 0;
+    if let Some(cutoff) = cutoff_ms {
+        while split + 1 < lines.len() && generated_at_millis(&lines[split]).is_some_and(|ms| ms < cutoff) {
+            split += 1;
+        }
+    }
+    if let Some(max_bytes) = max_bytes {
+        let size = |lines: &[String]| lines.iter().map(|line| line.len() as u64 + 1).sum::<u64>();
+        if size(&lines[split..]) > max_bytes {
+            while split + 1 < lines.len() && size(&lines[split..]) > max_bytes / 2 {
+                split += 1;
+            }
+        }
+    }
+    split
+}
+
+/// Append `lines[..split]` to the archive as a new gzip member, then rewrite
+/// the history with the rest. The archive is written first so an interrupted
+/// run duplicates entries instead of losing them.
+fn archive_prefix(pack_root: &Path, history_path: &Path, lines: &[String], split: usize) -> Result<PathBuf> {
+    let archive_path = pack_root.join(ARCHIVE_FILE);
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&archive_path)
+        .with_context(|| format!("Failed to open {}", archive_path.display()))?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    for line in &lines[..split] {
+        writeln!(encoder, "{}", line)?;
+    }
+    encoder
+        .finish()
+        .with_context(|| format!("Failed to append {}", archive_path.display()))?;
+
+    let kept = lines[split..]
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect::<String>();
+    super::write_text(history_path, &kept)?;
+    Ok(archive_path)
+}
+
+/// Rotate after a build appended to `history.jsonl`. Limits come from
+/// `BRIDGE_CONTEXT_PACK_HISTORY_MAX_KB` and `BRIDGE_CONTEXT_PACK_HISTORY_MAX_DAYS`.
+pub(super) fn rotate(pack_root: &Path, history_path: &Path) -> Result<()> {
+    let max_bytes = env_limit("BRIDGE_CONTEXT_PACK_HISTORY_MAX_KB", DEFAULT_MAX_KB).map(|kb| kb * 1024);
+    let cutoff_ms = env_limit("BRIDGE_CONTEXT_PACK_HISTORY_MAX_DAYS", DEFAULT_MAX_DAYS).map(|days| {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+        now_ms - days as i64 * 86_400_000
+    });
+
+    let lines = read_lines(history_path)?;
+    let split = rotation_split(&lines, cutoff_ms, max_bytes);
+    if split == 0 {
+        return Ok(());
+    }
+    archive_prefix(pack_root, history_path, &lines, split)?;
+    println!("[context-pack] rotated {} history entr(ies) into {}", split, ARCHIVE_FILE);
+    Ok(())
+}
+
+/// Move every entry except the latest `keep` into the archive.
+pub fn compact(pack_dir: Option<&str>, keep: usize) -> Result<()> {
+    let cwd = env::current_dir().context("Failed to resolve current directory")?;
+    let repo_root = super::git_repo_root(&cwd)?;
+    let pack_root = super::resolve_pack_root(&repo_root, pack_dir);
+    let history_path = pack_root.join("history.jsonl");
+
+    let lines = read_lines(&history_path)?;
+    let split = lines.len().saturating_sub(keep);
+    if split == 0 {
+        println!("[context-pack] history already compact ({} entr(ies))", lines.len());
+        return Ok(());
+    }
+    let archive_path = archive_prefix(&pack_root, &history_path, &lines, split)?;
+    println!(
+        "[context-pack] history compacted: archived {} entr(ies) to {}, kept {}",
+        split,
+        super::rel_path(&archive_path, &repo_root),
+        lines.len() - split
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rotation_split;
+
+    #[test]
+    fn rotation_archives_old_entries_then_trims_to_half_the_size_limit() {
+        let entry = |stamp: &str| format!("{{\"generated_at\":\"{}\",\"pad\":\"{}\"}}", stamp, "x".repeat(50));
+        let lines = vec![
+            entry("2025-01-01T00:00:00Z"),
+            entry("2025-06-01T00:00:00Z"),
+            entry("2026-01-01T00:00:00Z"),
+            entry("2026-02-01T00:00:00Z"),
+        ];
+        let cutoff = crate::agents::parse_iso_millis("2025-12-01T00:00:00Z");
+        assert_eq!(rotation_split(&lines, cutoff, None), 2);
+        assert_eq!(rotation_split(&lines, None, Some(1024)), 0);
+        assert_eq!(rotation_split(&lines, None, Some(200)), 3);
+        assert_eq!(rotation_split(&lines[..1], cutoff, Some(1)), 0);
+    }
+}
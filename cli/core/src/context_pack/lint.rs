@@ -0,0 +1,249 @@
+//! Content checks for `.agent-context/current/*.md` before a pack is
+//! committed: leftover template markers, empty sections, broken relative
+//! links, TODO density, and per-file word budgets.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const DEFAULT_MAX_WORDS: usize = 1500;
+const DEFAULT_MAX_TODOS_PER_100_WORDS: f64 = 1.0;
+const TODO_WORDS: &[&str] = &["TODO", "FIXME", "TBD"];
+
+struct Issue {
+    file: String,
+    line: Option<usize>,
+    rule: &'static str,
+    message: String,
+}
+
+/// Budgets from `<pack>/lint.json`:
+/// `{"max_words": {"default": 1500, "20_CODE_MAP.md": 2500}, "max_todos_per_100_words": 1}`.
+struct LintConfig {
+    raw: Value,
+}
+
+impl LintConfig {
+    fn load(pack_root: &Path) -> Result<Self> {
+        let path = pack_root.join("lint.json");
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Value::Null,
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(Self { raw })
+    }
+
+    fn max_words(&self, file: &str) -> usize {
+        let budgets = &self.raw["max_words"];
+        budgets[file]
+            .as_u64()
+            .or_else(|| budgets["default"].as_u64())
+            .or_else(|| budgets.as_u64())
+            .map(|value| value as usize)
+            .unwrap_or(DEFAULT_MAX_WORDS)
+    }
+
+    fn max_todos_per_100_words(&self) -> f64 {
+        self.raw["max_todos_per_100_words"]
+            .as_f64()
+            .unwrap_or(DEFAULT_MAX_TODOS_PER_100_WORDS)
+    }
+}
+
+/// Lint the current pack. Prints a text or JSON summary and returns whether
+/// the pack is clean, so the caller can exit non-zero for CI.
+pub fn lint(pack_dir: Option<&str>, as_json: bool) -> Result<bool> {
+    let cwd = env::current_dir().context("Failed to resolve current directory")?;
+    let repo_root = super::git_repo_root(&cwd)?;
+    let pack_root = super::resolve_pack_root(&repo_root, pack_dir);
+    let current_dir = pack_root.join("current");
+    if !current_dir.is_dir() {
+        return Err(anyhow!(
+            "Context pack not found: {} (run: bridge context-pack build)",
+            current_dir.display()
+        ));
+    }
+
+    let config = LintConfig::load(&pack_root)?;
+    let mut files = fs::read_dir(&current_dir)
+        .with_context(|| format!("Failed to read {}", current_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".md"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut issues = Vec::new();
+    for file in &files {
+        let path = current_dir.join(file);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        lint_file(file, &content, &current_dir, &config, &mut issues);
+    }
+
+    let ok = issues.is_empty();
+    if as_json {
+        let value = json!({
+            "pack": super::rel_path(&pack_root, &repo_root),
+            "files": files.len(),
+            "ok": ok,
+            "issues": issues
+                .iter()
+                .map(|issue| json!({
+                    "file": issue.file,
+                    "line": issue.line,
+                    "rule": issue.rule,
+                    "message": issue.message,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else if ok {
+        println!("PASS context-pack-lint ({} file(s) checked)", files.len());
+    } else {
+        println!("FAIL context-pack-lint ({} issue(s)):", issues.len());
+        for issue in &issues {
+            let location = match issue.line {
+                Some(line) => format!("{}:{}", issue.file, line),
+                None => issue.file.clone(),
+            };
+            println!("  - {} [{}] {}", location, issue.rule, issue.message);
+        }
+    }
+    Ok(ok)
+}
+
+fn lint_file(file: &str, content: &str, current_dir: &Path, config: &LintConfig, issues: &mut Vec<Issue>) {
+    let mut push = |line: Option<usize>, rule: &'static str, message: String| {
+        issues.push(Issue {
+            file: file.to_string(),
+            line,
+            rule,
+            message,
+        })
+    };
+
+    // (line number, text) pairs outside fenced code blocks.
+    let mut prose = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            prose.push((index + 1, line));
+        }
+    }
+
+    for (line_no, line) in &prose {
+        if line.contains("<!-- AGENT:") {
+            push(Some(*line_no), "agent-marker", "leftover `<!-- AGENT:` marker".to_string());
+        }
+        for target in link_targets(line) {
+            let path = target.split('#').next().unwrap_or_default();
+            if !path.is_empty() && !current_dir.join(path).exists() {
+                push(Some(*line_no), "broken-link", format!("link target not found: {}", target));
+            }
+        }
+    }
+
+    for (position, (line_no, line)) in prose.iter().enumerate() {
+        let Some(level) = heading_level(line) else {
+            continue;
+        };
+        let has_body = prose[position + 1..]
+            .iter()
+            .take_while(|(_, next)| heading_level(next).map_or(true, |next_level| next_level > level))
+            .any(|(_, next)| !next.trim().is_empty());
+        // Code blocks are dropped from `prose`, so a section holding only a
+        // fence still counts as having a body.
+        let fenced = content
+            .lines()
+            .skip(*line_no)
+            .take_while(|next| heading_level(next).map_or(true, |next_level| next_level > level))
+            .any(|next| next.trim_start().starts_with("```"));
+        if !has_body && !fenced {
+            push(Some(*line_no), "empty-section", format!("section `{}` has no content", line.trim()));
+        }
+    }
+
+    let words = content.split_whitespace().count();
+    let max_words = config.max_words(file);
+    if words > max_words {
+        push(None, "word-budget", format!("{} words exceeds the budget of {}", words, max_words));
+    }
+
+    let todos = content
+        .split_whitespace()
+        .filter(|word| TODO_WORDS.contains(&word.trim_matches(|ch: char| !ch.is_alphanumeric())))
+        .count();
+    let max_density = config.max_todos_per_100_words();
+    if todos > 0 && todos as f64 * 100.0 > words as f64 * max_density {
+        push(
+            None,
+            "todo-density",
+            format!("{} TODO/FIXME/TBD markers in {} words (max {} per 100 words)", todos, words, max_density),
+        );
+    }
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|ch| *ch == '#').count();
+    (level > 0 && level <= 6 && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// Relative targets of `[text](target)` links; external URLs and in-page
+/// anchors are skipped.
+fn link_targets(line: &str) -> Vec<&str> {
+    let mut targets = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("](") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let target = rest[..end].split_whitespace().next().unwrap_or_default();
+        rest = &rest[end + 1..];
+        if target.is_empty() || target.starts_with('#') || target.contains("://") || target.starts_with("mailto:") {
+            continue;
+        }
+        targets.push(target);
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint_file, LintConfig};
+    use serde_json::Value;
+    use std::path::Path;
+
+    #[test]
+    fn flags_markers_empty_sections_links_and_budgets() {
+        let content = "# Title\n\n## Empty\n\n## Filled\n<!-- AGENT: fill me -->\nSee [map](20_CODE_MAP.md#paths) and [site](https://example.com).\n\n## Fenced\n```bash\nnpm test\n```\nTODO TODO\n";
+        let config = LintConfig { raw: serde_json::json!({"max_words": {"pack.md": 10}}) };
+        let mut issues = Vec::new();
+        lint_file("pack.md", content, Path::new("/nonexistent"), &config, &mut issues);
+        let rules = issues.iter().map(|issue| (issue.rule, issue.line)).collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                ("agent-marker", Some(6)),
+                ("broken-link", Some(7)),
+                ("empty-section", Some(3)),
+                ("word-budget", None),
+                ("todo-density", None),
+            ]
+        );
+
+        let mut clean = Vec::new();
+        let config = LintConfig { raw: Value::Null };
+        lint_file("ok.md", "# Title\nBody text.\n", Path::new("/nonexistent"), &config, &mut clean);
+        assert!(clean.is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Line-level preview of what a rollback would change in `current/`.
+
+use super::snapshot::SnapshotFiles;
+
+/// Cap on `old lines * new lines` for the LCS table; bigger files are
+/// summarized by line count instead.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+fn split_lines(content: &[u8]) -> Vec<String> {
+    let text = String::from_utf8_lossy(content);
+    let mut lines = text.split('\n').map(str::to_string).collect::<Vec<_>>();
+    if lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// `-`/`+` lines turning `old` into `new` (longest-common-subsequence diff,
+/// unchanged lines omitted).
+fn line_diff(old: &[String], new: &[String]) -> Vec<String> {
+    if old.len() * new.len() > MAX_DIFF_CELLS {
+        return vec![format!("({} -> {} lines; too large to diff)", old.len(), new.len())];
+    }
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(format!("-{}", old[i]));
+            i += 1;
+        } else {
+            out.push(format!("+{}", new[j]));
+            j += 1;
+        }
+    }
+    out.extend(old[i..].iter().map(|line| format!("-{}", line)));
+    out.extend(new[j..].iter().map(|line| format!("+{}", line)));
+    out
+}
+
+/// Report lines for restoring `target` over `current`: `+ file` (added),
+/// `- file` (removed), and `~ file` (modified) followed by its line diff.
+pub(super) fn describe_changes(current: &SnapshotFiles, target: &SnapshotFiles) -> Vec<String> {
+    let mut names = current.keys().chain(target.keys()).collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+
+    let mut out = Vec::new();
+    for name in names {
+        match (current.get(name), target.get(name)) {
+            (None, Some(_)) => out.push(format!("+ {} (added)", name)),
+            (Some(_), None) => out.push(format!("- {} (removed)", name)),
+            (Some(old), Some(new)) if old != new => {
+                out.push(format!("~ {}", name));
+                let diff = line_diff(&split_lines(old), &split_lines(new));
+                out.extend(diff.into_iter().map(|line| format!("    {}", line)));
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_changes, SnapshotFiles};
+
+    #[test]
+    fn describes_added_removed_and_modified_files() {
+        let files = |entries: &[(&str, &str)]| {
+            entries
+                .iter()
+                .map(|(name, text)| (name.to_string(), text.as_bytes().to_vec()))
+                .collect::<SnapshotFiles>()
+        };
+        let current = files(&[("a.md", "one\ntwo\nthree\n"), ("gone.md", "x\n"), ("same.md", "s\n")]);
+        let target = files(&[("a.md", "one\n2\nthree\nfour\n"), ("new.md", "y\n"), ("same.md", "s\n")]);
+        assert_eq!(
+            describe_changes(&current, &target),
+            vec![
+                "~ a.md",
+                "    -two",
+                "    +2",
+                "    +four",
+                "- gone.md (removed)",
+                "+ new.md (added)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Flatten the current pack into one prompt-sized blob.
+
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use std::env;
+use std::fs;
+
+use super::READ_ORDER;
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum RenderFormat {
+    Markdown,
+    Text,
+}
+
+pub struct RenderOptions {
+    pub pack_dir: Option<String>,
+    pub format: RenderFormat,
+    pub max_tokens: Option<usize>,
+    pub output: Option<String>,
+}
+
+/// Rough token count (4 characters per token), the same heuristic agents
+/// use for prompt budgeting.
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+pub fn render(options: RenderOptions) -> Result<()> {
+    let cwd = env::current_dir().context("Failed to resolve current directory")?;
+    let repo_root = super::git_repo_root(&cwd)?;
+    let current_dir = super::resolve_pack_root(&repo_root, options.pack_dir.as_deref()).join("current");
+    if !current_dir.is_dir() {
+        return Err(anyhow!(
+            "Context pack not found: {} (run: bridge context-pack build)",
+            current_dir.display()
+        ));
+    }
+
+    let mut extra = fs::read_dir(&current_dir)
+        .with_context(|| format!("Failed to read {}", current_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".md") && !READ_ORDER.iter().any(|(file, _)| file == name))
+        .collect::<Vec<_>>();
+    extra.sort();
+    // Files outside the read order follow it in name order.
+    let files = READ_ORDER
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| current_dir.join(name).is_file())
+        .chain(extra);
+
+    // (file index, section text) in priority order; trimming drops from the end.
+    let mut sections = Vec::new();
+    for (index, name) in files.enumerate() {
+        let path = current_dir.join(&name);
+        let raw = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        for section in split_sections(&strip_comments(&raw)) {
+            let section = match options.format {
+                RenderFormat::Markdown => section,
+                RenderFormat::Text => to_plain_text(&section).trim().to_string(),
+            };
+            sections.push((index, section));
+        }
+    }
+
+    let mut rendered = join_sections(&sections);
+    let mut dropped = 0;
+    if let Some(ceiling) = options.max_tokens {
+        while sections.len() > 1 && estimate_tokens(&rendered) > ceiling {
+            sections.pop();
+            dropped += 1;
+            rendered = join_sections(&sections);
+        }
+        if dropped > 0 {
+            eprintln!(
+                "[context-pack] trimmed {} lowest-priority section(s) to fit {} tokens",
+                dropped, ceiling
+            );
+        }
+        if estimate_tokens(&rendered) > ceiling {
+            eprintln!(
+                "[context-pack] warning: first section alone is ~{} tokens, over the {} ceiling",
+                estimate_tokens(&rendered),
+                ceiling
+            );
+        }
+    }
+
+    match options.output {
+        Some(path) => {
+            super::write_text(std::path::Path::new(&path), &rendered)?;
+            println!("[context-pack] rendered ~{} tokens to {}", estimate_tokens(&rendered), path);
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Remove `<!-- ... -->` comments and the blank runs they leave behind.
+fn strip_comments(raw: &str) -> String {
+    let mut out = String::new();
+    let mut rest = raw;
+    while let Some(start) = rest.find("<!--") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start..].find("-->") {
+            Some(end) => &rest[start + end + 3..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+
+    let mut collapsed = String::new();
+    let mut blank_run = 0;
+    for line in out.lines() {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        collapsed.push_str(line.trim_end());
+        collapsed.push('\n');
+    }
+    collapsed
+}
+
+/// Drop markdown syntax that costs tokens without adding meaning in a plain
+/// prompt: heading markers, code fences, bold markers, and inline backticks.
+fn to_plain_text(markdown: &str) -> String {
+    let mut out = String::new();
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            continue;
+        }
+        let line = line.trim_start_matches('#').trim_start_matches(' ');
+        out.push_str(&line.replace("**", "").replace('`', ""));
+        out.push('\n');
+    }
+    out
+}
+
+/// Split one file at its `## ` headings (outside code fences); the title and
+/// preamble form the first section.
+fn split_sections(text: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && line.starts_with("## ") && !sections.last().is_some_and(|s| s.trim().is_empty()) {
+            sections.push(String::new());
+        }
+        let current = sections.last_mut().expect("sections is never empty");
+        current.push_str(line);
+        current.push('\n');
+    }
+    sections
+        .into_iter()
+        .map(|section| section.trim().to_string())
+        .filter(|section| !section.is_empty())
+        .collect()
+}
+
+fn join_sections(sections: &[(usize, String)]) -> String {
+    let mut out = String::new();
+    for (position, (file, section)) in sections.iter().enumerate() {
+        if position > 0 {
+            let same_file = sections[position - 1].0 == *file;
+            out.push_str(if same_file { "\n\n" } else { "\n\n---\n\n" });
+        }
+        out.push_str(section);
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{join_sections, split_sections, strip_comments};
+
+    #[test]
+    fn strips_comments_and_splits_at_second_level_headings() {
+        let text = strip_comments("# Title\n<!-- AGENT: fill -->\n\nIntro\n\n## A\nOne\n```bash\n## not a heading\n```\n## B\nTwo\n");
+        let sections = split_sections(&text);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0], "# Title\n\nIntro");
+        assert!(sections[1].contains("## not a heading"));
+
+        let joined = join_sections(&[(0, "# A".to_string()), (0, "## A1".to_string()), (1, "# B".to_string())]);
+        assert_eq!(joined, "# A\n\n## A1\n\n---\n\n# B\n");
+    }
+}
@@ -0,0 +1,259 @@
+//! Snapshot storage under `<pack>/snapshots/`. New snapshots are written as
+//! `<id>.json` manifests that reference gzip-compressed, content-addressed
+//! blobs in `objects/`, so files unchanged between builds are stored once.
+//! `<id>.tar.gz` archives and plain `<id>/` directories from older builds
+//! stay readable.
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+const ARCHIVE_SUFFIX: &str = ".tar.gz";
+const MANIFEST_SUFFIX: &str = ".json";
+const OBJECTS_DIR: &str = "objects";
+
+/// Files of a snapshot keyed by `/`-separated path relative to `current/`.
+pub(super) type SnapshotFiles = BTreeMap<String, Vec<u8>>;
+
+/// `objects/<first 2 hex>/<remaining hex>.gz`, git-style fan-out.
+fn object_path(snapshots_dir: &Path, sha: &str) -> PathBuf {
+    snapshots_dir
+        .join(OBJECTS_DIR)
+        .join(&sha[..2])
+        .join(format!("{}.gz", &sha[2..]))
+}
+
+/// Store each file of `source_dir` as an object (skipping objects that
+/// already exist) and write the `<id>.json` manifest referencing them.
+pub(super) fn write_snapshot(source_dir: &Path, snapshots_dir: &Path, id: &str) -> Result<()> {
+    let mut entries = Vec::new();
+    for (name, content) in read_dir_files(source_dir)? {
+        let sha = super::sha256_hex(&content);
+        let path = object_path(snapshots_dir, &sha);
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                super::ensure_dir(parent)?;
+            }
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&content)?;
+            fs::write(&path, encoder.finish()?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        entries.push(json!({ "path": name, "sha256": sha }));
+    }
+
+    let manifest = json!({ "snapshot_id": id, "files": entries });
+    super::write_text(
+        &snapshots_dir.join(format!("{}{}", id, MANIFEST_SUFFIX)),
+        &format!("{}\n", serde_json::to_string_pretty(&manifest)?),
+    )
+}
+
+/// Snapshot IDs in both storage formats, sorted oldest first (IDs start with
+/// a compact timestamp).
+pub(super) fn list_ids(snapshots_dir: &Path) -> Result<Vec<String>> {
+    let mut ids = fs::read_dir(snapshots_dir)
+        .with_context(|| format!("Failed to list snapshots at {}", snapshots_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if entry.path().is_dir() {
+                (name != OBJECTS_DIR).then_some(name)
+            } else {
+                name.strip_suffix(ARCHIVE_SUFFIX)
+                    .or_else(|| name.strip_suffix(MANIFEST_SUFFIX))
+                    .map(str::to_string)
+            }
+        })
+        .collect::<Vec<_>>();
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+pub(super) fn load(snapshots_dir: &Path, id: &str) -> Result<SnapshotFiles> {
+    let manifest_path = snapshots_dir.join(format!("{}{}", id, MANIFEST_SUFFIX));
+    if let Some(manifest) = super::read_json(&manifest_path)? {
+        return load_objects(snapshots_dir, &manifest_path, &manifest);
+    }
+    let dir = snapshots_dir.join(id);
+    if dir.is_dir() {
+        return read_dir_files(&dir);
+    }
+
+    let archive_path = snapshots_dir.join(format!("{}{}", id, ARCHIVE_SUFFIX));
+    let file = fs::File::open(&archive_path)
+        .with_context(|| format!("Failed to open {}", archive_path.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut files = SnapshotFiles::new();
+    for entry in archive
+        .entries()
+        .with_context(|| format!("Failed to read {}", archive_path.display()))?
+    {
+        let mut entry = entry.with_context(|| format!("Failed to read {}", archive_path.display()))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().replace('\\', "/");
+        if name.starts_with('/') || name.split('/').any(|part| part == "..") {
+            return Err(anyhow!("Unsafe path in {}: {}", archive_path.display(), name));
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        files.insert(name.trim_start_matches("./").to_string(), content);
+    }
+    Ok(files)
+}
+
+fn load_objects(snapshots_dir: &Path, manifest_path: &Path, manifest: &Value) -> Result<SnapshotFiles> {
+    let mut files = SnapshotFiles::new();
+    for entry in manifest["files"].as_array().into_iter().flatten() {
+        let (Some(name), Some(sha)) = (entry["path"].as_str(), entry["sha256"].as_str()) else {
+            return Err(anyhow!("Invalid file entry in {}", manifest_path.display()));
+        };
+        if sha.len() != 64 || !sha.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return Err(anyhow!("Invalid sha256 for {} in {}", name, manifest_path.display()));
+        }
+        if name.starts_with('/') || name.split('/').any(|part| part == "..") {
+            return Err(anyhow!("Unsafe path in {}: {}", manifest_path.display(), name));
+        }
+        let path = object_path(snapshots_dir, sha);
+        let file = fs::File::open(&path)
+            .with_context(|| format!("Missing snapshot object {} for {}", path.display(), name))?;
+        let mut content = Vec::new();
+        GzDecoder::new(file)
+            .read_to_end(&mut content)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if super::sha256_hex(&content) != sha {
+            return Err(anyhow!("Corrupt snapshot object {} for {}", path.display(), name));
+        }
+        files.insert(name.to_string(), content);
+    }
+    Ok(files)
+}
+
+/// Replace `current_dir` with the files of a loaded snapshot.
+pub(super) fn restore(files: &SnapshotFiles, current_dir: &Path) -> Result<()> {
+    if current_dir.exists() {
+        fs::remove_dir_all(current_dir)
+            .with_context(|| format!("Failed to clear {}", current_dir.display()))?;
+    }
+    super::ensure_dir(current_dir)?;
+    for (name, content) in files {
+        let path = current_dir.join(name);
+        if let Some(parent) = path.parent() {
+            super::ensure_dir(parent)?;
+        }
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub(super) fn read_dir_files(dir: &Path) -> Result<SnapshotFiles> {
+    let mut files = SnapshotFiles::new();
+    collect_files(dir, "", &mut files)?;
+    Ok(files)
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut SnapshotFiles) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let entry = entry.with_context(|| format!("Failed to read entry in {}", dir.display()))?;
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, &format!("{}/", name), files)?;
+        } else {
+            let content = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            files.insert(name, content);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_ids, load, object_path, write_snapshot, SnapshotFiles, OBJECTS_DIR};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bridge-snapshots-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content.as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn archived_snapshots_list_and_load() {
+        let dir = scratch("archive");
+        fs::write(dir.join("20260101T000000Z.tar.gz"), archive(&[("./manifest.json", "{}"), ("notes/a.md", "alpha")])).unwrap();
+        let ids = list_ids(&dir).unwrap();
+        let files = load(&dir, "20260101T000000Z").unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(ids, ["20260101T000000Z"]);
+        let expected: SnapshotFiles = [("manifest.json", "{}"), ("notes/a.md", "alpha")]
+            .into_iter()
+            .map(|(name, content)| (name.to_string(), content.as_bytes().to_vec()))
+            .collect();
+        assert_eq!(files, expected);
+    }
+    fn object_count(dir: &std::path::Path) -> usize {
+        fs::read_dir(dir.join(OBJECTS_DIR)).unwrap().flatten().map(|fanout| fs::read_dir(fanout.path()).unwrap().count()).sum()
+    }
+
+    #[test]
+    fn unchanged_files_are_stored_once_across_snapshots() {
+        let dir = scratch("dedup");
+        let current = dir.join("current");
+        fs::create_dir_all(current.join("notes")).unwrap();
+        fs::write(current.join("notes/a.md"), "alpha").unwrap();
+        fs::write(current.join("b.md"), "beta").unwrap();
+        write_snapshot(&current, &dir.join("snapshots"), "20260101T000000Z").unwrap();
+        fs::write(current.join("b.md"), "beta, revised").unwrap();
+        write_snapshot(&current, &dir.join("snapshots"), "20260102T000000Z").unwrap();
+
+        let objects = object_count(&dir.join("snapshots"));
+        let first = load(&dir.join("snapshots"), "20260101T000000Z").unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(objects, 3);
+        assert_eq!(first["b.md"], b"beta");
+        assert_eq!(first["notes/a.md"], b"alpha");
+    }
+
+    #[test]
+    fn corrupt_objects_fail_to_load() {
+        let dir = scratch("corrupt");
+        let current = dir.join("current");
+        fs::create_dir_all(&current).unwrap();
+        fs::write(current.join("a.md"), "alpha").unwrap();
+        write_snapshot(&current, &dir, "20260101T000000Z").unwrap();
+        let object = object_path(&dir, &crate::context_pack::sha256_hex(b"alpha"));
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        std::io::Write::write_all(&mut encoder, b"tampered").unwrap();
+        fs::write(&object, encoder.finish().unwrap()).unwrap();
+
+        let error = load(&dir, "20260101T000000Z").unwrap_err().to_string();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(error.starts_with("Corrupt snapshot object"), "{}", error);
+    }
+}
@@ -0,0 +1,129 @@
+//! Keep a managed block pointing at the pack in each agent instruction file.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::READ_ORDER;
+
+const INSTRUCTION_FILES: &[&str] = &["CLAUDE.md", "AGENTS.md", "GEMINI.md"];
+const START_MARKER: &str = "<!-- agent-bridge:context-pack:start -->";
+const END_MARKER: &str = "<!-- agent-bridge:context-pack:end -->";
+
+fn managed_block(current_dir: &str) -> String {
+    let mut lines = vec![
+        START_MARKER.to_string(),
+        "## Context Pack Read Order".to_string(),
+        String::new(),
+        format!(
+            "Before exploring this repository, read the context pack in `{}` in this order:",
+            current_dir
+        ),
+    ];
+    for (index, (file, purpose)) in READ_ORDER.iter().enumerate() {
+        lines.push(format!("{}. `{}`: {}.", index + 1, file, purpose));
+    }
+    lines.extend([
+        String::new(),
+        "Open source files only after the pack identifies a precise target.".to_string(),
+        "If the pack is missing or stale, run `bridge context-pack build`.".to_string(),
+        END_MARKER.to_string(),
+    ]);
+    lines.join("\n")
+}
+
+/// `existing` with the block between `start_marker` and `end_marker`
+/// replaced, or `block` appended when the markers are absent.
+pub(super) fn upsert_block(existing: &str, block: &str, start_marker: &str, end_marker: &str) -> String {
+    match (existing.find(start_marker), existing.find(end_marker)) {
+        (Some(start), Some(end)) if end > start => format!(
+            "{}{}{}",
+            &existing[..start],
+            block,
+            &existing[end + end_marker.len()..]
+        ),
+        _ => {
+            let trimmed = existing.trim_end();
+            if trimmed.is_empty() {
+                format!("{}\n", block)
+            } else {
+                format!("{}\n\n{}\n", trimmed, block)
+            }
+        }
+    }
+}
+
+fn block_for(repo_root: &Path, pack_root: &Path) -> String {
+    let current = super::rel_path(&pack_root.join("current"), repo_root).replace('\\', "/");
+    managed_block(&format!("{}/", current))
+}
+
+/// Insert or refresh the managed block in CLAUDE.md, AGENTS.md, and
+/// GEMINI.md at the repo root, creating files that do not exist yet.
+pub fn sync_agents(cwd: &str, pack_dir: Option<&str>, dry_run: bool) -> Result<()> {
+    let repo_root = super::git_repo_root(&PathBuf::from(cwd))?;
+    let pack_root = super::resolve_pack_root(&repo_root, pack_dir);
+    let block = block_for(&repo_root, &pack_root);
+
+    for name in INSTRUCTION_FILES {
+        let path = repo_root.join(name);
+        let existing = match fs::read_to_string(&path) {
+            Ok(text) => Some(text),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let next = upsert_block(existing.as_deref().unwrap_or(""), &block, START_MARKER, END_MARKER);
+        let status = match &existing {
+            Some(text) if *text == next => "unchanged",
+            _ if dry_run => "planned",
+            Some(_) => "updated",
+            None => "created",
+        };
+        if status != "unchanged" && !dry_run {
+            super::write_text(&path, &next)?;
+        }
+        println!("[context-pack] {}: {}", status, name);
+    }
+    Ok(())
+}
+
+/// Refresh blocks already present after a build, so instruction files that
+/// opted in through `sync-agents` never drift from the pack. Files without
+/// the block are left alone.
+pub(super) fn refresh_existing(repo_root: &Path, pack_root: &Path) -> Result<()> {
+    let block = block_for(repo_root, pack_root);
+    for name in INSTRUCTION_FILES {
+        let path = repo_root.join(name);
+        let Ok(existing) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if !existing.contains(START_MARKER) {
+            continue;
+        }
+        let next = upsert_block(&existing, &block, START_MARKER, END_MARKER);
+        if next != existing {
+            super::write_text(&path, &next)?;
+            println!("[context-pack] updated: {}", name);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{managed_block, upsert_block, END_MARKER, START_MARKER};
+
+    #[test]
+    fn upsert_replaces_existing_block_and_appends_otherwise() {
+        let upsert = |existing: &str, block: &str| upsert_block(existing, block, START_MARKER, END_MARKER);
+        let block = managed_block(".agent-context/current/");
+        let appended = upsert("# Notes\n\nKeep me.\n", &block);
+        assert_eq!(appended, format!("# Notes\n\nKeep me.\n\n{}\n", block));
+
+        let stale = appended.replace("Open source files", "Open files");
+        assert_ne!(stale, appended);
+        assert_eq!(upsert(&stale, &block), appended);
+        assert_eq!(upsert(&appended, &block), appended);
+        assert_eq!(upsert("", &block), format!("{}\n", block));
+    }
+}
@@ -0,0 +1,328 @@
+//! Correlation groups for `bridge correlate` and `compare --correlate`.
+//! Sessions from different agents are linked when they ran in the same
+//! working directory and either their active windows overlap (within
+//! `window_minutes` of slack) or their transcripts mention the same files.
+//! Linked sessions form groups, so a compare can pick the counterpart of a
+//! session instead of being handed its id.
+
+use crate::adapters;
+use crate::agents::{self, CwdMatch};
+use crate::report::SourceSpec;
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fs;
+
+/// File mentions kept per session.
+const MAX_FILES: usize = 200;
+
+pub struct CorrelateOptions {
+    pub cwd: Option<String>,
+    pub cwd_match: CwdMatch,
+    /// Sessions scanned across all agents, newest first.
+    pub limit: usize,
+    /// Slack added on both sides of a session's active window.
+    pub window_minutes: u64,
+}
+
+struct Candidate {
+    entry: Value,
+    cwd: String,
+    /// Epoch milliseconds of the first and last timestamped message, or of
+    /// `modified_at` when the transcript has no timestamps.
+    start: Option<i64>,
+    end: Option<i64>,
+    first_at: Option<String>,
+    last_at: Option<String>,
+    files: BTreeSet<String>,
+}
+
+/// Whether `token` looks like a file path: it has a directory separator, or
+/// ends in an extension of two to five characters with a letter in it.
+fn is_file_like(token: &str) -> bool {
+    if token.contains("://") || token.starts_with("//") {
+        return false;
+    }
+    if token.contains('/') {
+        return token.chars().any(|ch| ch.is_alphanumeric());
+    }
+    match token.rsplit_once('.') {
+        Some((stem, ext)) => {
+            !stem.is_empty()
+                && (2..=5).contains(&ext.len())
+                && ext.chars().all(|ch| ch.is_ascii_alphanumeric())
+                && ext.chars().any(|ch| ch.is_ascii_alphabetic())
+        }
+        None => false,
+    }
+}
+
+/// Path-like tokens of `text`, relative to `cwd` when they are under it.
+fn file_mentions(text: &str, cwd: &str, files: &mut BTreeSet<String>) {
+    let prefix = format!("{}/", cwd.trim_end_matches('/'));
+    for raw in text.split_whitespace() {
+        if files.len() >= MAX_FILES {
+            return;
+        }
+        let token = raw.trim_matches(|ch: char| !(ch.is_alphanumeric() || matches!(ch, '/' | '_' | '-' | '~' | '.')));
+        // `src/main.rs:42` and `src/main.rs#L42` name the file.
+        let token = token.split([':', '#']).next().unwrap_or("").trim_end_matches('.');
+        if !is_file_like(token) {
+            continue;
+        }
+        let token = token.strip_prefix(&prefix).unwrap_or(token);
+        let token = token.strip_prefix("./").unwrap_or(token);
+        files.insert(token.to_string());
+    }
+}
+
+fn candidate(entry: Value) -> Option<Candidate> {
+    let cwd = entry["cwd"].as_str().filter(|cwd| !cwd.is_empty())?.to_string();
+    let file_path = entry["file_path"].as_str()?.to_string();
+    let messages = fs::metadata(&file_path)
+        .ok()
+        .filter(|meta| meta.len() <= adapters::common::max_file_size())
+        .and_then(|_| fs::read(&file_path).ok())
+        .map(|bytes| agents::transcript(entry["agent"].as_str().unwrap_or(""), &bytes, entry["session_id"].as_str()))
+        .unwrap_or_default();
+    let mut files = BTreeSet::new();
+    let mut stamps = Vec::new();
+    for message in &messages {
+        file_mentions(&message.text, &cwd, &mut files);
+        if let Some(timestamp) = &message.timestamp {
+            if let Some(millis) = agents::parse_iso_millis(timestamp) {
+                stamps.push((millis, timestamp.clone()));
+            }
+        }
+    }
+    if stamps.is_empty() {
+        if let Some(modified) = entry["modified_at"].as_str() {
+            if let Some(millis) = agents::parse_iso_millis(modified) {
+                stamps.push((millis, modified.to_string()));
+            }
+        }
+    }
+    stamps.sort();
+    Some(Candidate {
+        cwd,
+        start: stamps.first().map(|(millis, _)| *millis),
+        end: stamps.last().map(|(millis, _)| *millis),
+        first_at: stamps.first().map(|(_, raw)| raw.clone()),
+        last_at: stamps.last().map(|(_, raw)| raw.clone()),
+        files,
+        entry,
+    })
+}
+
+/// Why `a` and `b` belong together, with a score from 0 to 1, or `None`
+/// when they do not.
+fn link(a: &Candidate, b: &Candidate, slack_millis: i64) -> Option<(f64, Vec<String>)> {
+    if a.entry["agent"] == b.entry["agent"] || a.cwd != b.cwd {
+        return None;
+    }
+    let overlap = match (a.start, a.end, b.start, b.end) {
+        (Some(a_start), Some(a_end), Some(b_start), Some(b_end)) => {
+            a_start.saturating_sub(slack_millis) <= b_end && b_start.saturating_sub(slack_millis) <= a_end
+        }
+        _ => false,
+    };
+    let shared = a.files.intersection(&b.files).count();
+    if !overlap && shared == 0 {
+        return None;
+    }
+    let mut score = 0.4;
+    let mut reasons = vec!["same cwd".to_string()];
+    if overlap {
+        score += 0.3;
+        reasons.push("overlapping time window".to_string());
+    }
+    if shared > 0 {
+        let smaller = a.files.len().min(b.files.len());
+        score += 0.3 * shared as f64 / smaller as f64;
+        reasons.push(format!("{} shared file mention(s)", shared));
+    }
+    Some(((score * 100.0).round() / 100.0, reasons))
+}
+
+fn find(parent: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[index] = root;
+    root
+}
+
+fn session_json(candidate: &Candidate) -> Value {
+    json!({
+        "agent": candidate.entry["agent"],
+        "session_id": candidate.entry["session_id"],
+        "file_path": candidate.entry["file_path"],
+        "started_at": candidate.first_at,
+        "ended_at": candidate.last_at,
+        "files": candidate.files.len(),
+    })
+}
+
+/// Correlation groups among `entries` (as `list --json` returns them),
+/// newest group first. Each group spans at least two agents.
+pub fn group_entries(entries: Vec<Value>, window_minutes: u64) -> Vec<Value> {
+    let candidates = entries.into_par_iter().filter_map(candidate).collect::<Vec<_>>();
+    let slack_millis = window_minutes.saturating_mul(60_000).min(i64::MAX as u64) as i64;
+    let mut parent = (0..candidates.len()).collect::<Vec<_>>();
+    let mut links = Vec::new();
+    for a in 0..candidates.len() {
+        for b in a + 1..candidates.len() {
+            if let Some((score, reasons)) = link(&candidates[a], &candidates[b], slack_millis) {
+                let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+                parent[root_b] = root_a;
+                links.push((a, b, score, reasons));
+            }
+        }
+    }
+
+    // Candidates arrive newest first, so groups keep that order by their first member.
+    let mut roots = Vec::new();
+    for index in 0..candidates.len() {
+        let root = find(&mut parent, index);
+        if links.iter().any(|(a, b, _, _)| *a == index || *b == index) && !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+        .into_iter()
+        .enumerate()
+        .map(|(position, root)| {
+            let members = (0..candidates.len()).filter(|index| find(&mut parent, *index) == root).collect::<Vec<_>>();
+            let mut agents = Vec::new();
+            let mut shared = BTreeSet::new();
+            for &index in &members {
+                let agent = &candidates[index].entry["agent"];
+                if !agents.contains(agent) {
+                    agents.push(agent.clone());
+                }
+            }
+            let group_links = links
+                .iter()
+                .filter(|(a, _, _, _)| members.contains(a))
+                .map(|(a, b, score, reasons)| {
+                    shared.extend(candidates[*a].files.intersection(&candidates[*b].files).cloned());
+                    json!({
+                        "sessions": [candidates[*a].entry["session_id"], candidates[*b].entry["session_id"]],
+                        "score": score,
+                        "reasons": reasons,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let score = group_links.iter().filter_map(|link| link["score"].as_f64()).fold(0.0, f64::max);
+            json!({
+                "group": position + 1,
+                "cwd": candidates[root].cwd,
+                "agents": agents,
+                "score": score,
+                "sessions": members.iter().map(|index| session_json(&candidates[*index])).collect::<Vec<_>>(),
+                "shared_files": shared,
+                "links": group_links,
+            })
+        })
+        .collect()
+}
+
+/// `bridge correlate`: scan recent sessions of every agent and group them.
+pub fn correlate(options: &CorrelateOptions) -> Vec<Value> {
+    let entries = adapters::list_all(options.cwd.as_deref(), options.cwd_match, options.limit);
+    group_entries(entries, options.window_minutes)
+}
+
+/// `compare --correlate`: give every source named only by agent the session
+/// of that agent correlated with the anchor, the first source with a session
+/// id (or else the first source's current session). Returns a note for each
+/// source left on its current session.
+pub fn pick_counterparts(sources: &mut [SourceSpec], options: &CorrelateOptions) -> Result<Vec<String>> {
+    let entries = adapters::list_all(options.cwd.as_deref(), options.cwd_match, options.limit);
+    let anchor = sources
+        .iter()
+        .find(|source| source.session_id.is_some())
+        .or_else(|| sources.first())
+        .ok_or_else(|| anyhow!("compare --correlate needs at least one source"))?;
+    let anchor_entry = entries.iter().find(|entry| {
+        entry["agent"] == anchor.agent.as_str()
+            && anchor.session_id.as_deref().map_or(true, |id| {
+                entry["session_id"].as_str().is_some_and(|session| session.contains(id))
+                    || entry["file_path"].as_str().is_some_and(|path| path.contains(id))
+            })
+    });
+    let Some(anchor_entry) = anchor_entry else {
+        return Err(anyhow!(
+            "compare --correlate could not find the {} session to correlate from",
+            anchor.agent
+        ));
+    };
+    let anchor_path = anchor_entry["file_path"].clone();
+    let groups = group_entries(entries, options.window_minutes);
+    let group = groups
+        .iter()
+        .find(|group| group["sessions"].as_array().is_some_and(|sessions| sessions.iter().any(|s| s["file_path"] == anchor_path)));
+
+    let mut notes = Vec::new();
+    for source in sources.iter_mut().filter(|source| source.session_id.is_none()) {
+        let picked = group.and_then(|group| {
+            group["sessions"]
+                .as_array()?
+                .iter()
+                .find(|session| session["agent"] == source.agent.as_str())
+                .and_then(|session| session["session_id"].as_str())
+        });
+        match picked {
+            Some(session_id) => {
+                source.session_id = Some(session_id.to_string());
+                source.current_session = false;
+            }
+            None => notes.push(format!("No correlated {} session found; using its current session", source.agent)),
+        }
+    }
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::group_entries;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn correlate_links_agents_by_cwd_time_and_shared_files() {
+        let dir = std::env::temp_dir().join(format!("bridge-correlate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let claude = |name: &str, text: &str| {
+            let path = dir.join(name);
+            let line = json!({"type": "assistant", "message": {"role": "assistant", "content": [{"type": "text", "text": text}]}});
+            fs::write(&path, format!("{}\n", line)).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let codex = |name: &str, text: &str, at: &str| {
+            let path = dir.join(name);
+            let line = json!({"timestamp": at, "type": "response_item", "payload": {"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": text}]}});
+            fs::write(&path, format!("{}\n", line)).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let entry = |agent: &str, id: &str, cwd: &str, path: String, at: &str| {
+            json!({"agent": agent, "session_id": id, "cwd": cwd, "file_path": path, "modified_at": at})
+        };
+        let entries = vec![
+            entry("claude", "c-1", "/repo", claude("c1.jsonl", "Fixed `/repo/src/parser.rs:40` and README.md."), "2026-03-01T12:30:00Z"),
+            entry("codex", "x-1", "/repo", codex("x1.jsonl", "Edited src/parser.rs", "2026-03-01T09:00:00Z"), "2026-03-01T09:00:00Z"),
+            entry("codex", "x-2", "/other", codex("x2.jsonl", "Edited src/parser.rs", "2026-03-01T12:00:00Z"), "2026-03-01T12:00:00Z"),
+            entry("gemini", "g-1", "/repo", claude("g1.jsonl", "e.g. v1.2 of https://x.io/a.rs"), "2026-03-01T18:00:00Z"),
+        ];
+
+        let groups = group_entries(entries, 60);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0]["agents"], json!(["claude", "codex"]));
+        assert_eq!(groups[0]["shared_files"], json!(["src/parser.rs"]));
+        assert_eq!(groups[0]["links"][0]["reasons"], json!(["same cwd", "1 shared file mention(s)"]));
+        assert_eq!(groups[0]["links"][0]["score"], 0.7);
+    }
+}
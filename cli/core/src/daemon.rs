@@ -0,0 +1,342 @@
+//! `bridge daemon`: serve `read`, `list`, `search`, and `compare` as JSON-RPC
+//! 2.0 over a Unix socket, one request object per line. Results are cached
+//! until a filesystem event under any agent's session store invalidates them,
+//! so repeated queries skip the directory scan. Aider stores live next to each
+//! project rather than under one root, so Aider requests are never cached.
+
+use crate::adapters;
+use crate::agents::{self, CwdMatch, IdMatch, ReadOptions, SearchField, SearchQuery, SearchRole};
+use crate::config;
+use crate::page::{Listing, Page};
+use crate::report;
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+/// JSON-RPC error codes: the spec's own, plus one for bridge failures whose
+/// `data.error_code` carries the CLI's error code.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const BRIDGE_ERROR: i64 = -32000;
+
+/// `--socket`, else `BRIDGE_DAEMON_SOCKET`, else `daemon.sock` in the cache
+/// directory.
+pub fn socket_path(flag: Option<&str>) -> PathBuf {
+    flag.map(PathBuf::from)
+        .or_else(|| std::env::var_os("BRIDGE_DAEMON_SOCKET").map(PathBuf::from))
+        .unwrap_or_else(|| config::cache_dir().join("daemon.sock"))
+}
+
+struct State {
+    /// Bumped on every create/modify/remove event under a watched store.
+    generation: AtomicU64,
+    /// Request key -> (generation it was computed at, result).
+    cache: Mutex<HashMap<String, (u64, Value)>>,
+    started: Instant,
+    watched: Vec<PathBuf>,
+    stopping: AtomicBool,
+}
+
+pub fn serve(socket: &Path) -> Result<()> {
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(anyhow!("A daemon is already listening on {}", socket.display()));
+        }
+        // Left behind by a daemon that did not shut down cleanly.
+        std::fs::remove_file(socket).with_context(|| format!("Failed to remove stale socket {}", socket.display()))?;
+    }
+    if let Some(parent) = socket.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let listener = UnixListener::bind(socket).with_context(|| format!("Failed to bind {}", socket.display()))?;
+
+    let mut watched = adapters::agent_names()
+        .into_iter()
+        .flat_map(agents::store_roots)
+        .filter(|root| root.is_dir())
+        .collect::<Vec<_>>();
+    watched.sort();
+    watched.dedup();
+    let state = Arc::new(State {
+        generation: AtomicU64::new(0),
+        cache: Mutex::new(HashMap::new()),
+        started: Instant::now(),
+        watched,
+        stopping: AtomicBool::new(false),
+    });
+    let _watcher = watch_stores(&state)?;
+    eprintln!(
+        "bridge daemon listening on {} (watching {} store root(s))",
+        socket.display(),
+        state.watched.len()
+    );
+
+    for stream in listener.incoming() {
+        if state.stopping.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let state = Arc::clone(&state);
+        thread::spawn(move || {
+            let _ = handle_connection(stream, &state);
+        });
+    }
+    let _ = std::fs::remove_file(socket);
+    Ok(())
+}
+
+fn watch_stores(state: &Arc<State>) -> Result<RecommendedWatcher> {
+    let events = Arc::clone(state);
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        // Reads open files too; only changes make cached results stale.
+        if let Ok(event) = event {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                events.generation.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    })
+    .context("Failed to start filesystem watcher")?;
+    for root in &state.watched {
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", root.display()))?;
+    }
+    Ok(watcher)
+}
+
+fn handle_connection(stream: UnixStream, state: &State) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(request) => respond(&request, state),
+            Err(err) => error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {}", err), None),
+        };
+        writeln!(writer, "{}", response)?;
+        if state.stopping.load(Ordering::SeqCst) {
+            // Wake the accept loop so it sees the flag.
+            let _ = UnixStream::connect(writer.local_addr()?.as_pathname().unwrap_or(Path::new("")));
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn respond(request: &Value, state: &State) -> Value {
+    let id = request["id"].clone();
+    let Some(method) = request["method"].as_str() else {
+        return error_response(id, INVALID_REQUEST, "Invalid request: missing method", None);
+    };
+    let params = &request["params"];
+    let result = match method {
+        "read" | "list" | "search" | "compare" => cached(method, params, state),
+        "status" => Ok(json!({
+            "pid": std::process::id(),
+            "uptime_seconds": state.started.elapsed().as_secs(),
+            "generation": state.generation.load(Ordering::SeqCst),
+            "cached_results": state.cache.lock().map(|cache| cache.len()).unwrap_or(0),
+            "watched_roots": state.watched.iter().map(|root| root.to_string_lossy()).collect::<Vec<_>>(),
+        })),
+        "shutdown" => {
+            state.stopping.store(true, Ordering::SeqCst);
+            Ok(json!({ "stopping": true }))
+        }
+        _ => return error_response(id, METHOD_NOT_FOUND, &format!("Method not found: {}", method), None),
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => {
+            let message = format!("{:#}", err);
+            let code = agents::error_code(&err).as_str();
+            let mut response = error_response(id, BRIDGE_ERROR, &message, Some(code));
+            let candidates = agents::error_candidates(&err);
+            if !candidates.is_empty() {
+                response["error"]["data"]["candidates"] = json!(candidates);
+            }
+            response
+        }
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str, error_code: Option<&str>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(error_code) = error_code {
+        error["data"] = json!({ "error_code": error_code });
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn cached(method: &str, params: &Value, state: &State) -> Result<Value> {
+    let cacheable = !mentions_aider(method, params);
+    let key = format!("{} {}", method, params);
+    let generation = state.generation.load(Ordering::SeqCst);
+    if cacheable {
+        if let Some((seen, result)) = state.cache.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+            if seen == generation {
+                return Ok(result);
+            }
+        }
+    }
+    let result = dispatch(method, params)?;
+    if cacheable {
+        if let Ok(mut cache) = state.cache.lock() {
+            cache.retain(|_, (seen, _)| *seen == generation);
+            cache.insert(key, (generation, result.clone()));
+        }
+    }
+    Ok(result)
+}
+
+fn mentions_aider(method: &str, params: &Value) -> bool {
+    let is_aider = |raw: &str| {
+        let agent = raw.split(':').next().unwrap_or(raw);
+        agent.eq_ignore_ascii_case("all") || adapters::normalize_agent(agent).is_ok_and(|agent| agent == "aider")
+    };
+    match method {
+        "compare" => params["sources"].as_array().into_iter().flatten().filter_map(Value::as_str).any(is_aider),
+        _ => params["agent"].as_str().is_some_and(is_aider),
+    }
+}
+
+fn dispatch(method: &str, params: &Value) -> Result<Value> {
+    let cwd = match params["cwd"].as_str() {
+        Some(cwd) => cwd.to_string(),
+        None => std::env::current_dir()?.to_string_lossy().to_string(),
+    };
+    let cwd_match = enum_param::<CwdMatch>(params, "cwd_match")?.unwrap_or_default();
+    let limit = params["limit"].as_u64().map(|limit| limit as usize).unwrap_or(10);
+    match method {
+        "read" => {
+            let agent = adapters::normalize_agent(str_param(params, "agent")?)?;
+            let adapter = adapters::get_adapter(agent).with_context(|| format!("Unsupported agent: {}", agent))?;
+            let id = params["id"].as_str().map(|id| crate::aliases::resolve_id(agent, id)).transpose()?;
+            let session = adapter.read_session(&ReadOptions {
+                id,
+                id_match: enum_param::<IdMatch>(params, "id_match")?.unwrap_or_default(),
+                cwd,
+                cwd_match,
+                chats_dir: params["chats_dir"].as_str().map(str::to_string),
+                last_n: params["last"].as_u64().map(|last| last.max(1) as usize).unwrap_or(1),
+                ..ReadOptions::default()
+            })?;
+            Ok(agents::session_json(&session))
+        }
+        "list" | "search" => {
+            let raw_agent = str_param(params, "agent")?;
+            let cwd = params["cwd"].as_str();
+            let query = if method == "search" {
+                let terms = |name: &str| -> Vec<String> {
+                    params[name].as_array().into_iter().flatten().filter_map(Value::as_str).map(str::to_string).collect()
+                };
+                let mut search = SearchQuery::new(
+                    params["query"].as_str(),
+                    &terms("all_of"),
+                    &terms("any_of"),
+                    &terms("not"),
+                    params["regex"].as_bool().unwrap_or(false),
+                )?;
+                search.role = enum_param::<SearchRole>(params, "in")?;
+                search.field = enum_param::<SearchField>(params, "field")?.unwrap_or_default();
+                Some(search)
+            } else {
+                None
+            };
+            // A cursor is bound to every param except the page size and position.
+            let mut listing = params.clone();
+            if let Some(listing) = listing.as_object_mut() {
+                for key in ["limit", "offset", "cursor"] {
+                    listing.remove(key);
+                }
+            }
+            let offset = params["offset"].as_u64().map(|offset| offset as usize);
+            let page = Page::request(offset, params["cursor"].as_str(), &format!("{} {}", method, listing))?;
+            let fetch = page.as_ref().map_or(limit, |page| page.fetch(limit));
+            let entries = if raw_agent.eq_ignore_ascii_case("all") {
+                match &query {
+                    Some(query) => adapters::search_all(query, cwd, cwd_match, fetch),
+                    None => adapters::list_all(cwd, cwd_match, fetch),
+                }
+            } else {
+                let agent = adapters::normalize_agent(raw_agent)?;
+                let adapter = adapters::get_adapter(agent).with_context(|| format!("Unsupported agent: {}", agent))?;
+                match &query {
+                    Some(query) => adapter.search_sessions(query, cwd, cwd_match, fetch)?,
+                    None => adapter.list_sessions(cwd, cwd_match, fetch)?,
+                }
+            };
+            let mut listing = Listing::new(page.as_ref(), entries, limit);
+            if query.is_none() {
+                agents::describe_list_entries(&mut listing.sessions);
+            }
+            Ok(listing.into_json())
+        }
+        "compare" => {
+            let sources = params["sources"]
+                .as_array()
+                .context("missing required param: sources")?
+                .iter()
+                .map(|source| source.as_str().map(str::to_string).context("sources must be strings"))
+                .collect::<Result<Vec<_>>>()?;
+            let normalize = params["normalize"].as_bool().unwrap_or(false);
+            let request = report::compare_request(&sources, normalize, cwd_match)?;
+            Ok(report::build_report(&request, &cwd))
+        }
+        _ => Err(anyhow!("Unsupported mode: {}", method)),
+    }
+}
+
+fn str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str> {
+    params[name].as_str().with_context(|| format!("missing required param: {}", name))
+}
+
+fn enum_param<T: ValueEnum>(params: &Value, name: &str) -> Result<Option<T>> {
+    params[name]
+        .as_str()
+        .map(|raw| T::from_str(raw, true).map_err(|_| anyhow!("Invalid {}: {}", name, raw)))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mentions_aider, respond, State};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, AtomicU64};
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    #[test]
+    fn answers_json_rpc_errors_and_skips_caching_aider() {
+        let state = State {
+            generation: AtomicU64::new(0),
+            cache: Mutex::new(HashMap::new()),
+            started: Instant::now(),
+            watched: Vec::new(),
+            stopping: AtomicBool::new(false),
+        };
+        let unknown = respond(&json!({"jsonrpc": "2.0", "id": 1, "method": "nope"}), &state);
+        assert_eq!(unknown["error"]["code"], -32601);
+        let missing = respond(&json!({"jsonrpc": "2.0", "id": 2, "method": "read", "params": {}}), &state);
+        assert_eq!(missing["id"], 2);
+        assert_eq!(missing["error"]["code"], -32000);
+
+        assert!(mentions_aider("list", &json!({"agent": "aider-chat"})));
+        assert!(mentions_aider("compare", &json!({"sources": ["codex", "aider:2024"]})));
+        assert!(!mentions_aider("read", &json!({"agent": "claude"})));
+    }
+}
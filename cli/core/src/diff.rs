@@ -0,0 +1,156 @@
+//! Line diffs between diverging source outputs for `compare` and `report`.
+//! Lines are compared with whitespace collapsed and blank lines dropped, so
+//! reflowed text does not show up as a change. The Node CLI mirrors this in
+//! `scripts/read_session.cjs`; both walk the same LCS table with the same
+//! tie-breaking, so the diffs are identical.
+
+use serde_json::{json, Value};
+
+/// Lines per side beyond which the diff covers only the first lines.
+const MAX_DIFF_LINES: usize = 2000;
+/// Unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Equal,
+    Remove,
+    Add,
+}
+
+fn diff_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Edit script turning `left` into `right`: each op with the line it carries.
+fn edit_script<'a>(left: &'a [String], right: &'a [String]) -> Vec<(Op, &'a str)> {
+    let (n, m) = (left.len(), right.len());
+    // lcs[i][j]: longest common subsequence of left[i..] and right[j..].
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if left[i] == right[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < n || j < m {
+        if i < n && j < m && left[i] == right[j] {
+            ops.push((Op::Equal, left[i].as_str()));
+            i += 1;
+            j += 1;
+        } else if j >= m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Remove, left[i].as_str()));
+            i += 1;
+        } else {
+            ops.push((Op::Add, right[j].as_str()));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// `start..end` ranges of `ops` to print: each change with its context,
+/// merging changes whose context would overlap.
+fn hunk_ranges(ops: &[(Op, &str)]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index < ops.len() {
+        if ops[index].0 == Op::Equal {
+            index += 1;
+            continue;
+        }
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let mut last = index;
+        let mut cursor = index + 1;
+        while cursor < ops.len() {
+            if ops[cursor].0 != Op::Equal {
+                last = cursor;
+                cursor += 1;
+                continue;
+            }
+            let run = ops[cursor..].iter().take_while(|(op, _)| *op == Op::Equal).count();
+            if cursor + run >= ops.len() || run > 2 * CONTEXT_LINES {
+                break;
+            }
+            cursor += run;
+        }
+        let end = (last + 1 + CONTEXT_LINES).min(ops.len());
+        ranges.push((start, end));
+        index = end;
+    }
+    ranges
+}
+
+/// A unified diff from `left` to `right`, or `None` when their lines match.
+/// The value carries the `sources` labels, `added` and `removed` line
+/// counts, the `unified` text, and whether either side was `truncated`.
+pub(crate) fn unified(left_label: &str, right_label: &str, left: &str, right: &str) -> Option<Value> {
+    let (mut left_lines, mut right_lines) = (diff_lines(left), diff_lines(right));
+    let truncated = left_lines.len() > MAX_DIFF_LINES || right_lines.len() > MAX_DIFF_LINES;
+    left_lines.truncate(MAX_DIFF_LINES);
+    right_lines.truncate(MAX_DIFF_LINES);
+    let ops = edit_script(&left_lines, &right_lines);
+    let removed = ops.iter().filter(|(op, _)| *op == Op::Remove).count();
+    let added = ops.iter().filter(|(op, _)| *op == Op::Add).count();
+    if added == 0 && removed == 0 {
+        return None;
+    }
+
+    let mut text = format!("--- {}\n+++ {}\n", left_label, right_label);
+    for (start, end) in hunk_ranges(&ops) {
+        let before = &ops[..start];
+        let hunk = &ops[start..end];
+        let left_before = before.iter().filter(|(op, _)| *op != Op::Add).count();
+        let right_before = before.iter().filter(|(op, _)| *op != Op::Remove).count();
+        let left_count = hunk.iter().filter(|(op, _)| *op != Op::Add).count();
+        let right_count = hunk.iter().filter(|(op, _)| *op != Op::Remove).count();
+        text.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            if left_count == 0 { left_before } else { left_before + 1 },
+            left_count,
+            if right_count == 0 { right_before } else { right_before + 1 },
+            right_count
+        ));
+        for (op, line) in hunk {
+            let prefix = match op {
+                Op::Equal => ' ',
+                Op::Remove => '-',
+                Op::Add => '+',
+            };
+            text.push(prefix);
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    Some(json!({
+        "sources": [left_label, right_label],
+        "added": added,
+        "removed": removed,
+        "unified": text,
+        "truncated": truncated,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unified;
+
+    #[test]
+    fn unified_diff_ignores_whitespace_and_keeps_context() {
+        let left = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten";
+        let right = "one\n  two  \nthree\nfour\nFIVE\nsix\nseven\neight\nnine\nten\neleven";
+        let diff = unified("[codex:a]", "[claude:b]", left, right).unwrap();
+
+        assert_eq!(diff["added"], 2);
+        assert_eq!(diff["removed"], 1);
+        assert_eq!(
+            diff["unified"],
+            "--- [codex:a]\n+++ [claude:b]\n@@ -2,9 +2,10 @@\n two\n three\n four\n-five\n+FIVE\n six\n seven\n eight\n nine\n ten\n+eleven\n"
+        );
+        assert!(unified("a", "b", "same  line\n\n", "same line").is_none());
+    }
+}
@@ -0,0 +1,272 @@
+//! Whole-session export for `bridge export`: every user and assistant
+//! message, redacted, as Markdown, standalone HTML, or JSON. Also renders the
+//! messages `read` selected for `read --format markdown`.
+
+use crate::agents::{Session, TranscriptMessage};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// Text layout for `read` when `--json` is not set.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ReadFormat {
+    /// Source line, separator, then the raw content
+    Text,
+    /// Front matter with session metadata, then one section per message
+    Markdown,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ExportFormat {
+    Md,
+    Html,
+    Json,
+}
+
+/// Render `messages` from `session` in `format`. `agent_name` is the display
+/// name used in headings.
+pub fn render(session: &Session, agent_name: &str, messages: &[TranscriptMessage], format: ExportFormat) -> Result<String> {
+    Ok(match format {
+        ExportFormat::Md => markdown(session, agent_name, messages),
+        ExportFormat::Html => html(session, agent_name, messages),
+        ExportFormat::Json => format!("{}\n", serde_json::to_string_pretty(&to_json(session, messages))?),
+    })
+}
+
+/// Write to `output`, or stdout when it is `None`.
+pub fn write(rendered: &str, output: Option<&str>) -> Result<()> {
+    match output {
+        Some(path) => {
+            let path = Path::new(path);
+            if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            fs::write(path, rendered).with_context(|| format!("Failed to write {}", path.display()))
+        }
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+fn title(session: &Session, agent_name: &str) -> String {
+    match &session.session_id {
+        Some(id) => format!("{} session {}", agent_name, id),
+        None => format!("{} session", agent_name),
+    }
+}
+
+/// Source, cwd, model, and message count, skipping what the session lacks.
+fn details(session: &Session, messages: &[TranscriptMessage]) -> Vec<(&'static str, String)> {
+    let mut details = vec![("Source", session.source.clone())];
+    details.extend(session.cwd.clone().map(|cwd| ("Working directory", cwd)));
+    details.extend(session.model.clone().map(|model| ("Model", model)));
+    details.extend(session.timestamp.clone().map(|timestamp| ("Last modified", timestamp)));
+    details.push(("Messages", messages.len().to_string()));
+    details
+}
+
+fn heading(index: usize, message: &TranscriptMessage) -> String {
+    let role = if message.role == "user" { "User" } else { "Assistant" };
+    match &message.timestamp {
+        Some(timestamp) => format!("{}. {} · {}", index + 1, role, timestamp),
+        None => format!("{}. {}", index + 1, role),
+    }
+}
+
+fn markdown(session: &Session, agent_name: &str, messages: &[TranscriptMessage]) -> String {
+    let mut out = format!("# {}\n\n", title(session, agent_name));
+    for (label, value) in details(session, messages) {
+        out.push_str(&format!("- {}: `{}`\n", label, value));
+    }
+    for (index, message) in messages.iter().enumerate() {
+        out.push_str(&format!("\n## {}\n\n{}\n", heading(index, message), message.text));
+    }
+    out
+}
+
+fn html(session: &Session, agent_name: &str, messages: &[TranscriptMessage]) -> String {
+    let title = escape_html(&title(session, agent_name));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+         body {{ font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }}\n\
+         section {{ border-left: 4px solid #ccc; margin: 1.5rem 0; padding-left: 1rem; }}\n\
+         section.user {{ border-color: #4a7bd0; }}\n\
+         section.assistant {{ border-color: #3a9a5b; }}\n\
+         h2 {{ font-size: 1rem; color: #555; }}\n\
+         pre {{ white-space: pre-wrap; word-wrap: break-word; font-family: ui-monospace, monospace; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n<ul>\n"
+    );
+    for (label, value) in details(session, messages) {
+        out.push_str(&format!("<li>{}: <code>{}</code></li>\n", label, escape_html(&value)));
+    }
+    out.push_str("</ul>\n");
+    for (index, message) in messages.iter().enumerate() {
+        out.push_str(&format!(
+            "<section class=\"{}\">\n<h2>{}</h2>\n<pre>{}</pre>\n</section>\n",
+            message.role,
+            escape_html(&heading(index, message)),
+            escape_html(&message.text)
+        ));
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// `read --format markdown`: a YAML front-matter block with the session
+/// metadata, then each selected message under a role header. `first_index` is
+/// the 0-based position of the first selected reply in the session, when
+/// known, so headers can say which reply they are.
+pub fn read_markdown(session: &Session, first_index: Option<usize>) -> String {
+    let mut out = String::from("---\n");
+    let fields = [
+        ("agent", Some(session.agent.to_string())),
+        ("session_id", session.session_id.clone()),
+        ("source", Some(session.source.clone())),
+        ("cwd", session.cwd.clone()),
+        ("model", session.model.clone()),
+        ("timestamp", session.timestamp.clone()),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            // A JSON string is a valid YAML scalar, quotes and escapes included.
+            out.push_str(&format!("{}: {}\n", key, Value::String(value)));
+        }
+    }
+    out.push_str(&format!("message_count: {}\n", session.message_count));
+    out.push_str(&format!("messages_returned: {}\n", session.messages_returned));
+    out.push_str("---\n");
+
+    if session.messages.is_empty() {
+        out.push_str(&format!("\n{}\n", close_fences(&session.content)));
+        return out;
+    }
+    for (offset, message) in session.messages.iter().enumerate() {
+        let heading = match first_index {
+            Some(first) => format!("Assistant {} of {}", first + offset + 1, session.message_count),
+            None => "Assistant".to_string(),
+        };
+        out.push_str(&format!("\n## {}\n\n{}\n", heading, close_fences(message)));
+    }
+    out
+}
+
+/// Close a code fence a message left open, so it cannot swallow the
+/// following headers.
+fn close_fences(text: &str) -> String {
+    let text = text.trim_end();
+    let fences = text.lines().filter(|line| line.trim_start().starts_with("```")).count();
+    if fences % 2 == 1 {
+        format!("{}\n```", text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn to_json(session: &Session, messages: &[TranscriptMessage]) -> Value {
+    json!({
+        "agent": session.agent,
+        "session_id": session.session_id,
+        "source": session.source,
+        "cwd": session.cwd,
+        "model": session.model,
+        "modified_at": session.timestamp,
+        "warnings": session.warnings,
+        "message_count": messages.len(),
+        "messages": messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| json!({
+                "index": index,
+                "role": message.role,
+                "timestamp": message.timestamp,
+                "text": message.text,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_html, read_markdown, render, ExportFormat};
+    use crate::agents::{Session, TranscriptMessage};
+
+    #[test]
+    fn renders_each_format() {
+        let session = Session {
+            agent: "claude",
+            content: String::new(),
+            messages: Vec::new(),
+            source: "/tmp/s.jsonl".to_string(),
+            warnings: Vec::new(),
+            session_id: Some("s".to_string()),
+            cwd: None,
+            timestamp: None,
+            message_count: 1,
+            messages_returned: 1,
+            tool_usage: None,
+            timing: None,
+            model: None,
+            usage: None,
+        };
+        let messages = vec![
+            TranscriptMessage { role: "user", text: "Fix <div>".to_string(), timestamp: None },
+            TranscriptMessage {
+                role: "assistant",
+                text: "Done".to_string(),
+                timestamp: Some("2026-01-15T10:00:00Z".to_string()),
+            },
+        ];
+        let md = render(&session, "Claude", &messages, ExportFormat::Md).unwrap();
+        assert!(md.starts_with("# Claude session s\n"));
+        assert!(md.contains("## 2. Assistant · 2026-01-15T10:00:00Z\n\nDone\n"));
+        let html = render(&session, "Claude", &messages, ExportFormat::Html).unwrap();
+        assert!(html.contains("<pre>Fix &lt;div&gt;</pre>"));
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&session, "Claude", &messages, ExportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["messages"][1]["role"], "assistant");
+        assert_eq!(escape_html("a & 'b'"), "a &amp; &#39;b&#39;");
+    }
+
+    #[test]
+    fn read_markdown_has_front_matter_and_closes_fences() {
+        let session = Session {
+            agent: "codex",
+            content: String::new(),
+            messages: vec!["```rust\nfn main() {}".to_string(), "Done".to_string()],
+            source: "/tmp/\"s\".jsonl".to_string(),
+            warnings: Vec::new(),
+            session_id: Some("s".to_string()),
+            cwd: None,
+            timestamp: None,
+            message_count: 5,
+            messages_returned: 2,
+            tool_usage: None,
+            timing: None,
+            model: None,
+            usage: None,
+        };
+        let md = read_markdown(&session, Some(3));
+        assert!(md.starts_with("---\nagent: \"codex\"\nsession_id: \"s\"\nsource: \"/tmp/\\\"s\\\".jsonl\"\n"));
+        assert!(md.contains("\n## Assistant 4 of 5\n\n```rust\nfn main() {}\n```\n"));
+        assert!(md.ends_with("## Assistant 5 of 5\n\nDone\n"));
+    }
+}
@@ -0,0 +1,254 @@
+//! `bridge fsck`: find session files that `read` can only partly parse
+//! (zero-byte files, invalid UTF-8, truncated or malformed JSON/JSONL) and,
+//! with `--repair`, salvage what still parses into a `<file>.repaired` copy.
+//! Original session files are never modified.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::agents;
+
+/// How many candidate cut points `salvage_json` tries before giving up.
+const MAX_SALVAGE_ATTEMPTS: usize = 256;
+
+struct Issue {
+    line: Option<usize>,
+    rule: &'static str,
+    message: String,
+}
+
+struct FileCheck {
+    issues: Vec<Issue>,
+    /// Salvaged file content, when anything parseable survived.
+    repaired: Option<String>,
+}
+
+/// Check every session file of `agent`. Prints a text or JSON summary and
+/// returns whether all files are clean, so the caller can exit non-zero.
+pub fn fsck(agent: &str, repair: bool, as_json: bool) -> Result<bool> {
+    let files = agents::session_files(agent)?;
+    let mut damaged = Vec::new();
+    for path in &files {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let check = check_bytes(&bytes, is_jsonl(path));
+        if check.issues.is_empty() {
+            continue;
+        }
+        let repaired_path = match (&check.repaired, repair) {
+            (Some(content), true) => {
+                let target = repaired_path(path);
+                fs::write(&target, content).with_context(|| format!("Failed to write {}", target.display()))?;
+                Some(target)
+            }
+            _ => None,
+        };
+        damaged.push((path, check, repaired_path));
+    }
+
+    let ok = damaged.is_empty();
+    if as_json {
+        let value = json!({
+            "agent": agent,
+            "files": files.len(),
+            "ok": ok,
+            "damaged": damaged
+                .iter()
+                .map(|(path, check, repaired_path)| json!({
+                    "path": path.to_string_lossy(),
+                    "salvageable": check.repaired.is_some(),
+                    "repaired_path": repaired_path.as_ref().map(|target| target.to_string_lossy().to_string()),
+                    "issues": check
+                        .issues
+                        .iter()
+                        .map(|issue| json!({
+                            "line": issue.line,
+                            "rule": issue.rule,
+                            "message": issue.message,
+                        }))
+                        .collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else if ok {
+        println!("PASS fsck {} ({} file(s) checked)", agent, files.len());
+    } else {
+        println!("FAIL fsck {} ({} of {} file(s) damaged):", agent, damaged.len(), files.len());
+        for (path, check, repaired_path) in &damaged {
+            println!("  {}", path.display());
+            for issue in &check.issues {
+                match issue.line {
+                    Some(line) => println!("    - line {} [{}] {}", line, issue.rule, issue.message),
+                    None => println!("    - [{}] {}", issue.rule, issue.message),
+                }
+            }
+            match (repaired_path, &check.repaired) {
+                (Some(target), _) => println!("    repaired copy: {}", target.display()),
+                (None, Some(_)) => println!("    salvageable: rerun with --repair"),
+                (None, None) => println!("    nothing salvageable"),
+            }
+        }
+    }
+    Ok(ok)
+}
+
+fn is_jsonl(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("jsonl"))
+        .unwrap_or(false)
+}
+
+fn repaired_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".repaired");
+    path.with_file_name(name)
+}
+
+fn check_bytes(bytes: &[u8], jsonl: bool) -> FileCheck {
+    if bytes.iter().all(u8::is_ascii_whitespace) {
+        return FileCheck {
+            issues: vec![Issue { line: None, rule: "empty", message: "file has no content".to_string() }],
+            repaired: None,
+        };
+    }
+    if jsonl {
+        check_jsonl(bytes)
+    } else {
+        check_json(bytes)
+    }
+}
+
+/// Each line is checked on its own; lines that parse (after lossy UTF-8
+/// decoding, if needed) are kept in the repaired copy.
+fn check_jsonl(bytes: &[u8]) -> FileCheck {
+    let mut issues = Vec::new();
+    let mut kept = Vec::new();
+    let ends_with_newline = bytes.ends_with(b"\n");
+    let lines: Vec<&[u8]> = bytes.split(|byte| *byte == b'\n').collect();
+    for (index, raw) in lines.iter().enumerate() {
+        let line = index + 1;
+        let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+        if raw.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let text = match std::str::from_utf8(raw) {
+            Ok(text) => text.to_string(),
+            Err(err) => {
+                issues.push(Issue {
+                    line: Some(line),
+                    rule: "encoding",
+                    message: format!("invalid UTF-8 at byte {}", err.valid_up_to()),
+                });
+                String::from_utf8_lossy(raw).into_owned()
+            }
+        };
+        match serde_json::from_str::<Value>(&text) {
+            Ok(_) => kept.push(text),
+            Err(err) => {
+                let is_last = index + 1 == lines.len() && !ends_with_newline;
+                let rule = if err.is_eof() && is_last { "truncated" } else { "invalid-json" };
+                issues.push(Issue { line: Some(line), rule, message: err.to_string() });
+            }
+        }
+    }
+    let repaired = (!kept.is_empty()).then(|| format!("{}\n", kept.join("\n")));
+    FileCheck { issues, repaired }
+}
+
+/// Whole-file JSON. A truncated document is cut back to the last complete
+/// object or array and its open containers are closed.
+fn check_json(bytes: &[u8]) -> FileCheck {
+    let mut issues = Vec::new();
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(err) => {
+            issues.push(Issue {
+                line: Some(1 + bytes[..err.valid_up_to()].iter().filter(|byte| **byte == b'\n').count()),
+                rule: "encoding",
+                message: format!("invalid UTF-8 at byte {}", err.valid_up_to()),
+            });
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+    };
+    let repaired = match serde_json::from_str::<Value>(&text) {
+        Ok(_) => Some(text),
+        Err(err) => {
+            let rule = if err.is_eof() { "truncated" } else { "invalid-json" };
+            issues.push(Issue { line: Some(err.line()), rule, message: err.to_string() });
+            salvage_json(&text).and_then(|value| serde_json::to_string_pretty(&value).ok())
+        }
+    };
+    FileCheck { issues, repaired }
+}
+
+/// Longest prefix of `text` that ends on a closed object or array and parses
+/// once the containers still open at that point are closed.
+fn salvage_json(text: &str) -> Option<Value> {
+    let mut open = Vec::new();
+    let mut cut_points = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, ch) in text.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' => open.push('}'),
+            '[' => open.push(']'),
+            '}' | ']' => {
+                open.pop();
+                if !open.is_empty() {
+                    cut_points.push((index + 1, open.iter().rev().collect::<String>()));
+                }
+            }
+            _ => {}
+        }
+    }
+    cut_points
+        .into_iter()
+        .rev()
+        .take(MAX_SALVAGE_ATTEMPTS)
+        .find_map(|(end, closers)| serde_json::from_str(&format!("{}{}", &text[..end], closers)).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_bytes, salvage_json};
+
+    #[test]
+    fn flags_and_salvages_damaged_sessions() {
+        let jsonl = b"{\"a\":1}\nnot json\n{\"b\":\xff2}\n{\"c\":";
+        let check = check_bytes(jsonl, true);
+        let rules = check.issues.iter().map(|issue| (issue.rule, issue.line)).collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                ("invalid-json", Some(2)),
+                ("encoding", Some(3)),
+                ("invalid-json", Some(3)),
+                ("truncated", Some(4)),
+            ]
+        );
+        assert_eq!(check.repaired.as_deref(), Some("{\"a\":1}\n"));
+
+        let truncated = check_bytes(br#"{"messages": [{"type": "user", "content": "hi"}, {"type": "gem"#, false);
+        assert_eq!(truncated.issues[0].rule, "truncated");
+        assert_eq!(
+            salvage_json(r#"{"messages": [{"type": "user", "content": "a}"}, {"type": "gem"#),
+            Some(serde_json::json!({"messages": [{"type": "user", "content": "a}"}]}))
+        );
+
+        assert_eq!(check_bytes(b"", true).issues[0].rule, "empty");
+        assert!(check_bytes(b"{\"ok\":true}\n", true).issues.is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+//! Reverse lookup from Gemini project hashes to project roots. Gemini CLI
+//! keeps each project's chats under `<tmp>/<sha256 of the project root>`, so a
+//! session's cwd can only be recovered by hashing candidate directories until
+//! one matches. Matches are remembered in `<cache dir>/gemini-projects.json`;
+//! `BRIDGE_NO_INDEX=1` keeps them in memory only.
+//!
+//! Candidates are the `--cwd` in use and the current directory (with their
+//! ancestors), the cwds the Codex and Claude session indexes have recorded,
+//! and the directories under `[gemini] project_roots` in the config file or
+//! `BRIDGE_GEMINI_PROJECT_ROOTS` (each root and two levels below it).
+
+use crate::adapters;
+use crate::config;
+use crate::index::SessionIndex;
+use crate::utils::{expand_home, hash_path, normalize_path};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAP_VERSION: u64 = 1;
+/// Levels below each configured project root that are hashed.
+const ROOT_DEPTH: usize = 2;
+
+/// Known project roots by hash, loaded once per command and written back on
+/// drop if a new root was found.
+pub struct ProjectRoots {
+    file: Option<PathBuf>,
+    known: HashMap<String, PathBuf>,
+    hints: Vec<PathBuf>,
+    scanned: bool,
+    dirty: bool,
+}
+
+impl ProjectRoots {
+    /// `hints` are directories the caller already has reason to check, such
+    /// as `--cwd`.
+    pub fn load(hints: &[&str]) -> Self {
+        let file = if std::env::var("BRIDGE_NO_INDEX").map(|value| value == "1").unwrap_or(false) {
+            None
+        } else {
+            Some(config::cache_dir().join("gemini-projects.json"))
+        };
+        let known = file
+            .as_ref()
+            .and_then(|file| fs::read_to_string(file).ok())
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            .filter(|root| root["version"].as_u64() == Some(MAP_VERSION))
+            .and_then(|root| {
+                root["projects"].as_object().map(|projects| {
+                    projects
+                        .iter()
+                        .filter_map(|(hash, path)| Some((hash.clone(), PathBuf::from(path.as_str()?))))
+                        .collect()
+                })
+            })
+            .unwrap_or_default();
+        let hints = hints.iter().filter_map(|hint| normalize_path(hint).ok()).collect();
+        Self { file, known, hints, scanned: false, dirty: false }
+    }
+
+    /// Project root whose hash is `hash`. Candidates are hashed at most once
+    /// per command, on the first miss.
+    pub fn resolve(&mut self, hash: &str) -> Option<PathBuf> {
+        if let Some(root) = self.known.get(hash) {
+            return Some(root.clone());
+        }
+        if !self.scanned {
+            self.scanned = true;
+            let tmp_roots = adapters::gemini::tmp_base_dirs();
+            for candidate in candidates(&self.hints) {
+                let candidate_hash = hash_path(&candidate);
+                // Only roots some Gemini project actually uses are worth keeping.
+                if !self.known.contains_key(&candidate_hash) && tmp_roots.iter().any(|root| root.join(&candidate_hash).is_dir()) {
+                    self.known.insert(candidate_hash, candidate);
+                    self.dirty = true;
+                }
+            }
+        }
+        self.known.get(hash).cloned()
+    }
+
+    /// Remember `root` as a project root found some other way.
+    pub fn record(&mut self, root: &Path) {
+        let hash = hash_path(root);
+        if self.known.get(&hash).map(PathBuf::as_path) != Some(root) {
+            self.known.insert(hash, root.to_path_buf());
+            self.dirty = true;
+        }
+    }
+
+    fn save(&mut self) {
+        let Some(file) = self.file.as_ref().filter(|_| self.dirty) else {
+            return;
+        };
+        let projects = self
+            .known
+            .iter()
+            .map(|(hash, path)| (hash.clone(), json!(path.to_string_lossy())))
+            .collect::<serde_json::Map<_, _>>();
+        let Some(parent) = file.parent() else {
+            return;
+        };
+        let tmp = parent.join(format!(".gemini-projects.json.{}", std::process::id()));
+        let written = fs::create_dir_all(parent)
+            .and_then(|_| fs::write(&tmp, json!({ "version": MAP_VERSION, "projects": projects }).to_string()))
+            .and_then(|_| fs::rename(&tmp, file));
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        self.dirty = false;
+    }
+}
+
+impl Drop for ProjectRoots {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+/// Every directory worth hashing, most specific first.
+fn candidates(hints: &[PathBuf]) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let current = std::env::current_dir().ok().and_then(|dir| normalize_path(&dir.to_string_lossy()).ok());
+    for start in hints.iter().chain(current.as_ref()) {
+        found.extend(start.ancestors().map(Path::to_path_buf));
+    }
+    for agent in ["codex", "claude"] {
+        found.extend(SessionIndex::load(agent).cwds().map(PathBuf::from));
+    }
+    for root in configured_roots() {
+        walk(&root, ROOT_DEPTH, &mut found);
+    }
+    found
+}
+
+/// `[gemini] project_roots` from the config file, then
+/// `BRIDGE_GEMINI_PROJECT_ROOTS` (a path list, like `PATH`).
+fn configured_roots() -> Vec<PathBuf> {
+    let mut roots = match &config::section("gemini")["project_roots"] {
+        Value::String(path) => expand_home(path).into_iter().collect(),
+        Value::Array(paths) => paths.iter().filter_map(Value::as_str).filter_map(expand_home).collect(),
+        _ => Vec::new(),
+    };
+    if let Some(value) = std::env::var_os("BRIDGE_GEMINI_PROJECT_ROOTS") {
+        roots.extend(std::env::split_paths(&value).filter_map(|path| expand_home(&path.to_string_lossy())));
+    }
+    roots
+}
+
+fn walk(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    found.push(dir.to_path_buf());
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            walk(&entry.path(), depth - 1, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk, ProjectRoots};
+    use crate::utils::{hash_path, normalize_path};
+    use std::collections::HashMap;
+    use std::fs;
+
+    #[test]
+    fn project_hashes_resolve_to_the_hinted_directory() {
+        let base = std::env::temp_dir().join(format!("bridge-gemini-projects-{}", std::process::id()));
+        let project = base.join("work").join("app");
+        fs::create_dir_all(&project).unwrap();
+        let project = normalize_path(&project.to_string_lossy()).unwrap();
+        let hash = hash_path(&project);
+        fs::create_dir_all(base.join("tmp").join(&hash).join("chats")).unwrap();
+        std::env::set_var("BRIDGE_GEMINI_TMP_DIR", base.join("tmp"));
+
+        let mut roots = ProjectRoots { file: None, known: HashMap::new(), hints: vec![project.clone()], scanned: false, dirty: false };
+        let resolved = roots.resolve(&hash);
+        let unknown = roots.resolve(&"0".repeat(64));
+        fs::remove_dir_all(&base).unwrap();
+        assert_eq!(resolved, Some(project));
+        assert_eq!(unknown, None);
+    }
+
+    #[test]
+    fn project_roots_are_walked_two_levels_deep_skipping_hidden_directories() {
+        let base = std::env::temp_dir().join(format!("bridge-gemini-walk-{}", std::process::id()));
+        for dir in ["org/app/src", "org/.git", "solo"] {
+            fs::create_dir_all(base.join(dir)).unwrap();
+        }
+        let mut found = Vec::new();
+        walk(&base, 2, &mut found);
+        fs::remove_dir_all(&base).unwrap();
+        found.sort();
+        assert_eq!(found, [base.clone(), base.join("org"), base.join("org/app"), base.join("solo")]);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::config;
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// The shell command configured as `[hooks] <name>`, if any. Hooks never run
+/// unless the user's config names one explicitly.
+fn command_for(name: &str) -> Option<&'static str> {
+    config::section("hooks")[name]
+        .as_str()
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Print `output`, first piping it through the `name` hook when one is
+/// configured. The hook receives the output on stdin and its stdout is
+/// printed in place of the original; a failing hook is an error so nothing
+/// unfiltered leaks out.
+pub fn emit(name: &str, output: &str) -> Result<()> {
+    let Some(command) = command_for(name) else {
+        print!("{}", output);
+        return Ok(());
+    };
+    std::io::stdout().write_all(&run(name, command, output)?)?;
+    Ok(())
+}
+
+/// Run `command` as the `name` hook with `output` on stdin; its stdout.
+fn run(name: &str, command: &str, output: &str) -> Result<Vec<u8>> {
+    let mut child = shell(command)
+        .env("BRIDGE_HOOK", name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {} hook: {}", name, command))?;
+
+    let mut stdin = child.stdin.take().context("Hook stdin unavailable")?;
+    let input = output.to_string();
+    let writer = std::thread::spawn(move || {
+        // A hook that exits without reading stdin closes the pipe early; its
+        // exit status decides the outcome, not the write.
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let result = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run {} hook: {}", name, command))?;
+    let _ = writer.join();
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr).trim().to_string();
+        let code = result
+            .status
+            .code()
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "signal".to_string());
+        if stderr.is_empty() {
+            bail!("{} hook failed (exit {}): {}", name, code, command);
+        }
+        bail!("{} hook failed (exit {}): {}: {}", name, code, command, stderr);
+    }
+    Ok(result.stdout)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::run;
+
+    #[test]
+    fn hook_stdout_replaces_the_output() {
+        let filtered = run("post_read", "tr a-z A-Z", "handoff ready\n").unwrap();
+        assert_eq!(String::from_utf8(filtered).unwrap(), "HANDOFF READY\n");
+    }
+
+    #[test]
+    fn hook_sees_its_name_in_the_environment() {
+        let filtered = run("post_report", "cat >/dev/null; printf %s \"$BRIDGE_HOOK\"", "report").unwrap();
+        assert_eq!(filtered, b"post_report");
+    }
+
+    #[test]
+    fn failing_hooks_are_errors_with_their_stderr() {
+        let error = run("post_read", "echo denied >&2; exit 3", "secret output").unwrap_err();
+        assert_eq!(error.to_string(), "post_read hook failed (exit 3): echo denied >&2; exit 3: denied");
+    }
+}
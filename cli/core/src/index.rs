@@ -0,0 +1,242 @@
+//! Persistent per-agent session metadata index. `list`, `search`, and
+//! cwd-scoped `read` need each JSONL session's cwd, which otherwise means
+//! opening every file on every run. Entries live in
+//! `<cache dir>/index/<agent>.json`, keyed by path, and are reused only while
+//! the file's mtime and size are unchanged. `BRIDGE_NO_INDEX=1` bypasses the
+//! index entirely.
+
+use crate::config;
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const INDEX_VERSION: u64 = 2;
+
+/// What the index remembers about one session file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionMeta {
+    pub session_id: Option<String>,
+    /// Normalized working directory, when the session records one.
+    pub cwd: Option<String>,
+    pub model: Option<String>,
+    pub message_count: usize,
+    pub timestamp: Option<String>,
+    /// One-line label for `list`: the first user prompt (Claude only).
+    pub title: Option<String>,
+    /// The agent's own summary of the session, when it writes one (Claude).
+    pub summary: Option<String>,
+}
+
+struct Entry {
+    mtime_ns: u128,
+    size: u64,
+    meta: SessionMeta,
+}
+
+/// One agent's index, loaded once per scan and written back on drop if any
+/// entry was added or refreshed.
+pub struct SessionIndex {
+    file: Option<PathBuf>,
+    entries: HashMap<String, Entry>,
+    dirty: bool,
+}
+
+impl SessionIndex {
+    pub fn load(agent: &str) -> Self {
+        if std::env::var("BRIDGE_NO_INDEX").map(|value| value == "1").unwrap_or(false) {
+            return Self { file: None, entries: HashMap::new(), dirty: false };
+        }
+        let file = config::cache_dir().join("index").join(format!("{}.json", agent));
+        // A missing, corrupt, or older-version index is rebuilt from scratch.
+        let entries = fs::read_to_string(&file)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            .filter(|root| root["version"].as_u64() == Some(INDEX_VERSION))
+            .and_then(|root| root["entries"].as_object().map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|(path, entry)| Some((path.clone(), entry_from_json(entry)?)))
+                    .collect()
+            }))
+            .unwrap_or_default();
+        Self { file: Some(file), entries, dirty: false }
+    }
+
+    /// Metadata for `path`, from the index when the file is unchanged since it
+    /// was recorded, else from `scan` (and recorded).
+    pub fn lookup(&mut self, path: &Path, scan: fn(&Path) -> SessionMeta) -> SessionMeta {
+        self.lookup_many(&[path], scan).pop().unwrap_or_default()
+    }
+
+    /// `lookup` for several files at once, in order. Stats and scans of files
+    /// the index cannot answer run in parallel.
+    pub fn lookup_many(&mut self, paths: &[&Path], scan: fn(&Path) -> SessionMeta) -> Vec<SessionMeta> {
+        let stamps = paths.par_iter().map(|path| stamp(path)).collect::<Vec<_>>();
+        let hits = paths
+            .iter()
+            .zip(&stamps)
+            .map(|(path, stamp)| self.fresh(path, *stamp))
+            .collect::<Vec<_>>();
+        let metas = paths
+            .par_iter()
+            .zip(hits.par_iter())
+            .map(|(path, hit)| hit.clone().unwrap_or_else(|| scan(path)))
+            .collect::<Vec<_>>();
+        if self.file.is_some() {
+            for ((path, stamp), (hit, meta)) in paths.iter().zip(stamps).zip(hits.iter().zip(&metas)) {
+                if let (None, Some((mtime_ns, size))) = (hit, stamp) {
+                    let entry = Entry { mtime_ns, size, meta: meta.clone() };
+                    self.entries.insert(path.to_string_lossy().to_string(), entry);
+                    self.dirty = true;
+                }
+            }
+        }
+        metas
+    }
+
+    /// Every cwd recorded in the index, for reverse lookups such as
+    /// `gemini_projects`.
+    pub fn cwds(&self) -> impl Iterator<Item = &str> {
+        self.entries.values().filter_map(|entry| entry.meta.cwd.as_deref())
+    }
+
+    fn fresh(&self, path: &Path, stamp: Option<(u128, u64)>) -> Option<SessionMeta> {
+        let (mtime_ns, size) = stamp?;
+        let entry = self.entries.get(path.to_string_lossy().as_ref())?;
+        (entry.mtime_ns == mtime_ns && entry.size == size).then(|| entry.meta.clone())
+    }
+
+    fn save(&mut self) {
+        let Some(file) = self.file.as_ref().filter(|_| self.dirty) else {
+            return;
+        };
+        // Sessions deleted since they were indexed would otherwise linger forever.
+        self.entries.retain(|path, _| Path::new(path).exists());
+        let entries = self
+            .entries
+            .iter()
+            .map(|(path, entry)| (path.clone(), entry_to_json(entry)))
+            .collect::<serde_json::Map<_, _>>();
+        let Some(parent) = file.parent() else {
+            return;
+        };
+        // Write then rename, so a concurrent reader never sees a partial file.
+        let tmp = parent.join(format!(".{}.{}", file.file_name().unwrap_or_default().to_string_lossy(), std::process::id()));
+        let written = fs::create_dir_all(parent)
+            .and_then(|_| fs::write(&tmp, json!({ "version": INDEX_VERSION, "entries": entries }).to_string()))
+            .and_then(|_| fs::rename(&tmp, file));
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        self.dirty = false;
+    }
+}
+
+impl Drop for SessionIndex {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+fn stamp(path: &Path) -> Option<(u128, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime_ns = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    Some((mtime_ns, meta.len()))
+}
+
+fn entry_to_json(entry: &Entry) -> Value {
+    json!({
+        // u128 does not fit a JSON number; nanoseconds are kept as a string.
+        "mtime_ns": entry.mtime_ns.to_string(),
+        "size": entry.size,
+        "session_id": entry.meta.session_id,
+        "cwd": entry.meta.cwd,
+        "model": entry.meta.model,
+        "message_count": entry.meta.message_count,
+        "timestamp": entry.meta.timestamp,
+        "title": entry.meta.title,
+        "summary": entry.meta.summary,
+    })
+}
+
+fn entry_from_json(value: &Value) -> Option<Entry> {
+    let text = |key: &str| value[key].as_str().map(str::to_string);
+    Some(Entry {
+        mtime_ns: value["mtime_ns"].as_str()?.parse().ok()?,
+        size: value["size"].as_u64()?,
+        meta: SessionMeta {
+            session_id: text("session_id"),
+            cwd: text("cwd"),
+            model: text("model"),
+            message_count: value["message_count"].as_u64().unwrap_or(0) as usize,
+            timestamp: text("timestamp"),
+            title: text("title"),
+            summary: text("summary"),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{entry_from_json, entry_to_json, Entry, SessionIndex, SessionMeta};
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SCANS: AtomicUsize = AtomicUsize::new(0);
+
+    fn scan(path: &Path) -> SessionMeta {
+        SCANS.fetch_add(1, Ordering::SeqCst);
+        SessionMeta { session_id: path.file_stem().map(|stem| stem.to_string_lossy().to_string()), ..SessionMeta::default() }
+    }
+
+    #[test]
+    fn entries_round_trip_through_json() {
+        let entry = Entry {
+            mtime_ns: 1_768_471_200_123_456_789,
+            size: 42,
+            meta: SessionMeta {
+                session_id: Some("s".to_string()),
+                cwd: Some("/work/app".to_string()),
+                model: None,
+                message_count: 3,
+                timestamp: Some("2026-01-15T10:00:00Z".to_string()),
+                title: Some("Add retries".to_string()),
+                summary: None,
+            },
+        };
+        let restored = entry_from_json(&entry_to_json(&entry)).unwrap();
+        assert_eq!(restored.mtime_ns, entry.mtime_ns);
+        assert_eq!(restored.size, 42);
+        assert_eq!(restored.meta, entry.meta);
+        assert!(entry_from_json(&serde_json::json!({ "size": 1 })).is_none());
+    }
+    #[test]
+    fn lookup_many_keeps_order_and_rescans_only_changed_files() {
+        let dir = std::env::temp_dir().join(format!("bridge-index-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let paths = (0..5).map(|index| dir.join(format!("s{}.jsonl", index))).collect::<Vec<_>>();
+        for path in &paths {
+            fs::write(path, "{}").unwrap();
+        }
+        let paths = paths.iter().map(|path| path.as_path()).collect::<Vec<_>>();
+        let mut index = SessionIndex { file: Some(dir.join("index.json")), entries: HashMap::new(), dirty: false };
+
+        let ids = index.lookup_many(&paths, scan).into_iter().map(|meta| meta.session_id.unwrap()).collect::<Vec<_>>();
+        let first_scans = SCANS.load(Ordering::SeqCst);
+        index.lookup_many(&paths, scan);
+        let cached_scans = SCANS.load(Ordering::SeqCst);
+        fs::write(paths[2], "{\"grown\": true}").unwrap();
+        index.lookup_many(&paths, scan);
+        let changed_scans = SCANS.load(Ordering::SeqCst);
+        drop(index);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(ids, ["s0", "s1", "s2", "s3", "s4"]);
+        assert_eq!((first_scans, cached_scans, changed_scans), (5, 5, 6));
+    }
+}
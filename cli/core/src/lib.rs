@@ -0,0 +1,72 @@
+//! Agent Bridge core: session adapters, redaction, reports, and context-pack
+//! automation. The `bridge` binary is a CLI over this library; other Rust
+//! tools can embed the same behavior without shelling out to it.
+//!
+//! The main entry points are re-exported at the crate root:
+//!
+//! - [`get_adapter`] returns the [`AgentAdapter`] for an agent name (see
+//!   [`normalize_agent`] for aliases), whose `read_session`, `list_sessions`,
+//!   and `search_sessions` back `bridge read`, `list`, and `search`.
+//! - [`Session`] is what a read returns, with content already redacted;
+//!   [`session_json`] renders it as `read --json` does.
+//! - [`build_report`] compares sources the way `bridge compare` and
+//!   `bridge report` do; [`parse_handoff`] and [`compare_request`] build its
+//!   [`ReportRequest`].
+//! - [`redact_sensitive_text`] applies the bridge's credential redaction.
+//!
+//! ```no_run
+//! use agent_bridge_core::{get_adapter, normalize_agent, ReadOptions};
+//!
+//! let agent = normalize_agent("claude-code")?;
+//! let adapter = get_adapter(agent).expect("built-in agent");
+//! let session = adapter.read_session(&ReadOptions { last_n: 3, ..ReadOptions::default() })?;
+//! println!("{} ({}): {}", session.agent, session.source, session.content);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod adapters;
+pub mod agents;
+pub mod aliases;
+pub mod bench;
+pub mod claims;
+pub mod config;
+pub mod context_pack;
+pub mod correlate;
+#[cfg(unix)]
+pub mod daemon;
+mod diff;
+pub mod export;
+pub mod fsck;
+pub mod gemini_projects;
+pub mod hooks;
+pub mod index;
+// The `#[napi]` exports are only reachable from Node, so a test build would
+// see them as dead code.
+#[cfg(all(feature = "napi", not(test)))]
+mod napi;
+pub mod page;
+#[cfg(feature = "wasm-plugins")]
+pub mod plugin;
+pub mod pricing;
+pub mod query;
+pub mod redact;
+pub mod relevance;
+pub mod report;
+pub mod rubric;
+pub mod search_index;
+mod semantic;
+pub mod setup;
+pub mod stats;
+mod steer;
+pub mod template;
+pub mod theme;
+pub mod transfer;
+pub mod utils;
+pub mod watch;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+pub use adapters::{get_adapter, normalize_agent, AgentAdapter};
+pub use agents::{session_json, CwdMatch, IdMatch, ReadOptions, SearchQuery, Session};
+pub use redact::redact_sensitive_text;
+pub use report::{build_report, compare_request, parse_handoff, report_to_markdown, ReportRequest, SourceSpec};
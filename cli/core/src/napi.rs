@@ -0,0 +1,114 @@
+//! Node-API bindings so the npm package can delegate to this core instead of
+//! its JavaScript parsers. Every function takes and returns JSON text, which
+//! keeps the boundary identical to the CLI's `--json` output.
+
+use anyhow::{Context, Result};
+use napi_derive::napi;
+use serde_json::Value;
+
+use crate::adapters;
+use crate::agents::{self, IdMatch, ReadOptions};
+use crate::aliases;
+use crate::redact;
+use crate::report;
+
+fn to_napi(err: anyhow::Error) -> napi::Error {
+    napi::Error::from_reason(format!("{:#}", err))
+}
+
+fn read_session_json(agent: &str, options: Option<&str>) -> Result<String> {
+    let agent = adapters::normalize_agent(agent)?;
+    let options: Value = match options {
+        Some(raw) => serde_json::from_str(raw).context("Invalid read options JSON")?,
+        None => Value::Null,
+    };
+    let adapter = adapters::get_adapter(agent).with_context(|| format!("Unsupported agent: {}", agent))?;
+    let id = options["id"]
+        .as_str()
+        .map(|value| aliases::resolve_id(agent, value))
+        .transpose()?;
+    let mut read_options = ReadOptions {
+        id,
+        id_match: if options["exact"].as_bool() == Some(true) { IdMatch::Exact } else { IdMatch::default() },
+        chats_dir: options["chats_dir"].as_str().map(str::to_string),
+        last_n: options["last"].as_u64().map(|value| value.max(1) as usize).unwrap_or(1),
+        ..ReadOptions::default()
+    };
+    if let Some(cwd) = options["cwd"].as_str() {
+        read_options.cwd = cwd.to_string();
+    }
+    let session_json = |mut session: agents::Session| {
+        if adapters::common::scan_truncated() {
+            session.warnings.push(adapters::common::scan_truncation_warning());
+        }
+        agents::session_json(&session)
+    };
+    if options["all_matching"].as_bool() == Some(true) {
+        let sessions = adapters::read_matching(&read_options, |options| adapter.read_session(options))?;
+        return Ok(Value::Array(sessions.into_iter().map(session_json).collect()).to_string());
+    }
+    Ok(session_json(adapter.read_session(&read_options)?).to_string())
+}
+
+/// `bridge read --json` for `agent`. `options` is JSON with optional `id`,
+/// `exact`, `all_matching` (return an array of every session `id` matches),
+/// `cwd`, `chats_dir`, and `last`.
+#[napi]
+pub fn read_session(agent: String, options: Option<String>) -> napi::Result<String> {
+    read_session_json(&agent, options.as_deref()).map_err(to_napi)
+}
+
+fn compare_json(sources: &[String], cwd: &str, options: Option<&str>) -> Result<String> {
+    let options: Value = match options {
+        Some(raw) => serde_json::from_str(raw).context("Invalid compare options JSON")?,
+        None => Value::Null,
+    };
+    let normalize = options["normalize"].as_bool().unwrap_or(false);
+    let mut request = report::compare_request(sources, normalize, Default::default())?;
+    request.semantic = options["semantic"].as_bool().unwrap_or(false);
+    request.last_n = if options["full"].as_bool() == Some(true) {
+        usize::MAX
+    } else {
+        options["last"].as_u64().map_or(1, |last| last.max(1) as usize)
+    };
+    request.thresholds = report::Thresholds {
+        fail_on: options["fail_on"].as_str().map(report::parse_severity).transpose()?,
+        min_score: options["min_score"].as_u64().map(|score| score.min(100) as i64),
+    };
+    Ok(report::build_report(&request, cwd).to_string())
+}
+
+/// `bridge compare --json` over `<agent>` or `<agent>:<session-id>` sources.
+/// `options` is JSON with optional `normalize`, `semantic`, `last` (`--last
+/// N`), `full` (every assistant message), `fail_on`, and `min_score`.
+#[napi]
+pub fn compare(sources: Vec<String>, cwd: String, options: Option<String>) -> napi::Result<String> {
+    compare_json(&sources, &cwd, options.as_deref()).map_err(to_napi)
+}
+
+/// `bridge report --json` for handoff packet JSON text. `fail_on` and
+/// `min_score` override the handoff's thresholds.
+#[napi]
+pub fn build_report(
+    handoff: String,
+    cwd: String,
+    allow_commands: Option<bool>,
+    fail_on: Option<String>,
+    min_score: Option<u32>,
+) -> napi::Result<String> {
+    let mut request = report::parse_handoff(&handoff).map_err(to_napi)?;
+    request.allow_commands = allow_commands.unwrap_or(false);
+    if let Some(fail_on) = fail_on {
+        request.thresholds.fail_on = Some(report::parse_severity(&fail_on).map_err(to_napi)?);
+    }
+    if let Some(min_score) = min_score {
+        request.thresholds.min_score = Some(i64::from(min_score.min(100)));
+    }
+    Ok(report::build_report(&request, &cwd).to_string())
+}
+
+/// Apply the bridge redaction rules to `text`.
+#[napi]
+pub fn redact(text: String) -> String {
+    redact::redact_sensitive_text(&text)
+}
@@ -0,0 +1,114 @@
+//! Offset pagination for `list` and `search`. With `--offset` or `--cursor`,
+//! JSON output becomes `{sessions, next_cursor}`: `next_cursor` resumes after
+//! the last entry returned and is `null` on the last page. A cursor is bound
+//! to the listing that issued it, so one cannot page a different query.
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// One requested page of a listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub offset: usize,
+    /// Fingerprint of the listing, carried in every cursor it issues.
+    listing: String,
+}
+
+impl Page {
+    /// The page `--offset` or `--cursor` asks for, or `None` without either,
+    /// when output stays a bare array. `listing` names everything that picks
+    /// and orders the entries (agent, cwd, query, sort), but not `--limit`.
+    pub fn request(offset: Option<usize>, cursor: Option<&str>, listing: &str) -> Result<Option<Page>> {
+        let digest = Sha256::digest(listing.as_bytes());
+        let listing = digest[..8].iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+        let offset = match (offset, cursor) {
+            (_, Some(cursor)) => decode(cursor, &listing)?,
+            (Some(offset), None) => offset,
+            (None, None) => return Ok(None),
+        };
+        Ok(Some(Page { offset, listing }))
+    }
+
+    /// How many entries of the listing to collect: enough to fill a page of
+    /// `limit` and tell whether another follows.
+    pub fn fetch(&self, limit: usize) -> usize {
+        self.offset.saturating_add(limit).saturating_add(1)
+    }
+}
+
+/// The entries a listing shows, and how its JSON presents them.
+pub struct Listing {
+    pub sessions: Vec<Value>,
+    /// `Some` when a page was requested; `Some(None)` on the last page.
+    next_cursor: Option<Option<String>>,
+}
+
+impl Listing {
+    /// `entries`, at least the first `fetch(limit)` of the listing, cut to
+    /// the requested page, or to `limit` when none was.
+    pub fn new(page: Option<&Page>, entries: Vec<Value>, limit: usize) -> Listing {
+        let Some(page) = page else {
+            return Listing { sessions: entries.into_iter().take(limit).collect(), next_cursor: None };
+        };
+        let end = page.offset.saturating_add(limit);
+        let next_cursor = (entries.len() > end).then(|| encode(end, &page.listing));
+        let sessions = entries.into_iter().skip(page.offset).take(limit).collect();
+        Listing { sessions, next_cursor: Some(next_cursor) }
+    }
+
+    /// The bare array, or `{sessions, next_cursor}` for a page.
+    pub fn into_json(self) -> Value {
+        match self.next_cursor {
+            Some(next_cursor) => json!({ "sessions": self.sessions, "next_cursor": next_cursor }),
+            None => json!(self.sessions),
+        }
+    }
+}
+
+fn encode(offset: usize, listing: &str) -> String {
+    format!("{}:{}", offset, listing).bytes().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode(cursor: &str, listing: &str) -> Result<usize> {
+    let invalid = || anyhow!("Invalid --cursor: pass a next_cursor value unchanged");
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|start| cursor.get(start..start + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let (offset, issued_for) = text.split_once(':').ok_or_else(invalid)?;
+    let offset = offset.parse::<usize>().map_err(|_| invalid())?;
+    if issued_for != listing {
+        return Err(anyhow!("--cursor was issued for a different listing; start again with --offset 0"));
+    }
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Listing, Page};
+    use serde_json::json;
+
+    #[test]
+    fn cursors_walk_a_listing_and_stay_bound_to_it() {
+        let entries = (0..5).map(|index| json!({ "session_id": index })).collect::<Vec<_>>();
+        let first = Page::request(Some(0), None, "list codex").unwrap().unwrap();
+        assert_eq!(first.fetch(2), 3);
+        let page = Listing::new(Some(&first), entries[..3].to_vec(), 2).into_json();
+        assert_eq!(page["sessions"], json!([{ "session_id": 0 }, { "session_id": 1 }]));
+
+        let cursor = page["next_cursor"].as_str().unwrap();
+        let second = Page::request(None, Some(cursor), "list codex").unwrap().unwrap();
+        assert_eq!(second.offset, 2);
+        let last = Page::request(Some(4), None, "list codex").unwrap().unwrap();
+        let last = Listing::new(Some(&last), entries.clone(), 2).into_json();
+        assert_eq!(last, json!({ "sessions": [{ "session_id": 4 }], "next_cursor": null }));
+        assert_eq!(Listing::new(None, entries.clone(), 2).into_json(), json!(entries[..2]));
+
+        assert!(Page::request(None, Some(cursor), "list claude").is_err());
+        assert!(Page::request(None, Some("zz"), "list codex").is_err());
+        assert_eq!(Page::request(None, None, "list codex").unwrap(), None);
+    }
+}
@@ -0,0 +1,460 @@
+//! Host for sandboxed session parsers compiled to WASM components.
+//!
+//! A plugin implements the `session-parser` world in `wit/session-parser.wit`.
+//! The host resolves and reads the session file itself and passes only its
+//! bytes to the guest, which gets no imports: no filesystem, network, clock,
+//! or environment. Execution is bounded by fuel and a memory cap.
+//!
+//! `parse-session` is the whole host interface: `read`, `list`, and `search`
+//! are built on it here, so a plugin registers as an agent (`--agent <name>`)
+//! without the bridge or the plugin changing when either adds features.
+
+use crate::adapters::{self, common, AgentAdapter};
+use crate::agents::{self, CwdMatch, MessageWindow, ReadOptions, SearchQuery, TranscriptMessage};
+use crate::config;
+use crate::redact;
+use crate::utils::{expand_home, normalize_path};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder};
+
+wasmtime::component::bindgen!({
+    world: "session-parser",
+    path: "wit",
+});
+
+/// Instruction budget for one `parse-session` call; enough for large
+/// sessions, small enough that a looping plugin fails quickly.
+const FUEL: u64 = 2_000_000_000;
+const MAX_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+
+static PLUGINS: OnceLock<Vec<PluginConfig>> = OnceLock::new();
+
+/// One `[plugins.<name>]` config table, or a `<name>.wasm` in the plugins
+/// directory with its `<name>.toml` manifest.
+struct PluginConfig {
+    name: &'static str,
+    wasm: PathBuf,
+    sessions_dir: PathBuf,
+    extension: Option<String>,
+    /// Compiled on first use and reused for every session the process parses.
+    compiled: OnceLock<Result<(Engine, Component), String>>,
+}
+
+pub struct PluginAdapter {
+    plugin: &'static PluginConfig,
+}
+
+/// Directory scanned for `<name>.wasm` plugins: `BRIDGE_PLUGINS_DIR`, else
+/// `plugins/` under the config directory.
+pub fn plugins_dir() -> PathBuf {
+    std::env::var("BRIDGE_PLUGINS_DIR")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .and_then(|value| expand_home(&value))
+        .unwrap_or_else(|| config::config_dir().join("plugins"))
+}
+
+/// The plugin registered as `name`, as an adapter.
+pub fn adapter(name: &str) -> Option<PluginAdapter> {
+    plugins().iter().find(|plugin| plugin.name == name).map(|plugin| PluginAdapter { plugin })
+}
+
+/// Names of the registered plugins: config tables first, then the plugins
+/// directory, each in name order.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    plugins().iter().map(|plugin| plugin.name)
+}
+
+fn config_path(table: &Value, key: &str, name: &str, origin: &str) -> Result<PathBuf> {
+    let raw = table[key]
+        .as_str()
+        .ok_or_else(|| anyhow!("Plugin '{}' is missing `{}` in {}", name, key, origin))?;
+    Ok(expand_home(raw).unwrap_or_else(|| PathBuf::from(raw)))
+}
+
+fn check_name(name: &str) -> Result<()> {
+    if adapters::is_reserved_name(name) || adapters::custom::names().any(|custom| custom == name) {
+        return Err(anyhow!("Plugin '{}' would shadow an agent of the same name", name));
+    }
+    Ok(())
+}
+
+fn plugin_config(name: &str, wasm: PathBuf, table: &Value, origin: &str) -> Result<PluginConfig> {
+    Ok(PluginConfig {
+        sessions_dir: config_path(table, "sessions_dir", name, origin)?,
+        extension: table["extension"].as_str().map(str::to_string),
+        // Plugins are registered once per process, so leaking the names is bounded.
+        name: Box::leak(name.to_string().into_boxed_str()),
+        wasm,
+        compiled: OnceLock::new(),
+    })
+}
+
+/// Every registered plugin, loaded once per process. A `[plugins.<name>]`
+/// table wins over a directory plugin of the same name; invalid entries are
+/// reported on stderr and skipped.
+fn plugins() -> &'static [PluginConfig] {
+    PLUGINS.get_or_init(|| {
+        let mut plugins: Vec<PluginConfig> = Vec::new();
+        for (name, table) in config::section("plugins").as_object().into_iter().flatten() {
+            let origin = format!("[plugins.{}]", name);
+            let loaded = check_name(name)
+                .and_then(|_| config_path(table, "wasm", name, &origin))
+                .and_then(|wasm| plugin_config(name, wasm, table, &origin));
+            match loaded {
+                Ok(plugin) => plugins.push(plugin),
+                Err(err) => eprintln!("Warning: skipped plugin: {}", err),
+            }
+        }
+
+        load_directory(&plugins_dir(), &mut plugins);
+        plugins
+    })
+}
+
+/// Add each `<name>.wasm` in `dir` that has a valid `<name>.toml` manifest,
+/// unless `plugins` already has one of that name.
+fn load_directory(dir: &Path, plugins: &mut Vec<PluginConfig>) {
+    let mut found = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| common::has_extension(path, "wasm"))
+        .collect::<Vec<_>>();
+    found.sort();
+    for wasm in found {
+        let name = common::file_stem(&wasm);
+        if plugins.iter().any(|plugin| plugin.name == name) {
+            continue;
+        }
+        let manifest = wasm.with_extension("toml");
+        let loaded = check_name(&name)
+            .and_then(|_| {
+                fs::read_to_string(&manifest)
+                    .map_err(|err| anyhow!("Plugin '{}' has no manifest {}: {}", name, manifest.display(), err))
+            })
+            .and_then(|raw| {
+                toml::from_str::<Value>(&raw)
+                    .map_err(|err| anyhow!("Plugin '{}' has an invalid manifest: {}", name, err))
+            })
+            .and_then(|table| plugin_config(&name, wasm.clone(), &table, &manifest.display().to_string()));
+        match loaded {
+            Ok(plugin) => plugins.push(plugin),
+            Err(err) => eprintln!("Warning: skipped plugin: {}", err),
+        }
+    }
+}
+
+fn find(name: &str) -> Result<&'static PluginConfig> {
+    plugins().iter().find(|plugin| plugin.name == name).ok_or_else(|| {
+        anyhow!(
+            "Unknown plugin '{}': add a [plugins.{}] table to {}, or {}.wasm and {}.toml to {}",
+            name,
+            name,
+            config::config_path().display(),
+            name,
+            name,
+            plugins_dir().display()
+        )
+    })
+}
+
+/// Registered plugins as `{name, wasm, sessions_dir, extension}` objects.
+pub fn list() -> Vec<Value> {
+    plugins()
+        .iter()
+        .map(|plugin| {
+            json!({
+                "name": plugin.name,
+                "wasm": plugin.wasm.to_string_lossy(),
+                "sessions_dir": plugin.sessions_dir.to_string_lossy(),
+                "extension": plugin.extension,
+            })
+        })
+        .collect()
+}
+
+struct HostState {
+    limits: StoreLimits,
+}
+
+impl PluginConfig {
+    fn compiled(&self) -> Result<&(Engine, Component)> {
+        self.compiled
+            .get_or_init(|| {
+                let mut engine_config = Config::new();
+                engine_config.wasm_component_model(true);
+                engine_config.consume_fuel(true);
+                let engine = Engine::new(&engine_config).map_err(|err| err.to_string())?;
+                let component = Component::from_file(&engine, &self.wasm)
+                    .map_err(|err| format!("Failed to load plugin {}: {:#}", self.wasm.display(), err))?;
+                Ok((engine, component))
+            })
+            .as_ref()
+            .map_err(|err| anyhow!("{}", err))
+    }
+
+    /// Run `parse-session` in a fresh instance, so no state or fuel carries
+    /// over between sessions.
+    fn parse(&self, bytes: &[u8]) -> Result<Session> {
+        let (engine, component) = self.compiled()?;
+        let linker = Linker::<HostState>::new(engine);
+        let mut store = Store::new(
+            engine,
+            HostState {
+                limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL)?;
+
+        let (bindings, _) = SessionParser::instantiate(&mut store, component, &linker)
+            .with_context(|| format!("Failed to instantiate plugin '{}'", self.name))?;
+        bindings
+            .call_parse_session(&mut store, bytes)
+            .with_context(|| format!("Plugin '{}' trapped", self.name))?
+            .map_err(|err| anyhow!("Plugin '{}' failed to parse session: {}", self.name, err))
+    }
+
+    fn parse_file(&self, path: &Path) -> Result<Session> {
+        if fs::metadata(path).map(|meta| meta.len() > common::max_file_size()).unwrap_or(false) {
+            return Err(anyhow!(
+                "Skipped {} (exceeds {}MB size limit)",
+                path.display(),
+                common::max_file_size() / (1024 * 1024)
+            ));
+        }
+        let bytes = fs::read(path).map_err(|err| anyhow!("Failed to read {}: {}", path.display(), err))?;
+        self.parse(&bytes)
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        let extension = self.extension.as_deref();
+        common::collect_paths(std::slice::from_ref(&self.sessions_dir), &|path: &Path| {
+            extension.map_or(true, |ext| common::has_extension(path, ext))
+        })
+    }
+
+    /// Content is redacted like every built-in adapter's output.
+    fn to_session(&self, source: &str, parsed: Session) -> agents::Session {
+        agents::Session {
+            agent: self.name,
+            content: redact::redact_sensitive_text(&parsed.content),
+            messages: Vec::new(),
+            source: source.to_string(),
+            warnings: parsed.warnings,
+            session_id: parsed.session_id.or_else(|| Some(common::file_stem(Path::new(source)))),
+            cwd: parsed.cwd,
+            timestamp: parsed.timestamp,
+            message_count: parsed.message_count as usize,
+            messages_returned: parsed.messages_returned as usize,
+            tool_usage: None,
+            timing: None,
+            model: parsed.model,
+            usage: None,
+        }
+    }
+
+    /// A list or search entry for a parsed session file.
+    fn entry(&self, path: &Path, parsed: &Session) -> Value {
+        json!({
+            "session_id": parsed.session_id.clone().unwrap_or_else(|| common::file_stem(path)),
+            "agent": self.name,
+            "cwd": parsed.cwd,
+            "model": parsed.model,
+            "modified_at": agents::file_modified_iso(path),
+            "file_path": path.to_string_lossy(),
+        })
+    }
+}
+
+/// Read a session through the named plugin: the newest file under its
+/// `sessions_dir`, or the one matching `options.id`.
+pub fn read_session(name: &str, options: &ReadOptions) -> Result<agents::Session> {
+    let plugin = find(name)?;
+    let (path, bytes) =
+        common::read_plugin_session_file(&plugin.sessions_dir, plugin.extension.as_deref(), options, plugin.name)?;
+    let parsed = plugin.parse(&bytes)?;
+    Ok(plugin.to_session(&path.to_string_lossy(), parsed))
+}
+
+fn in_scope(session_cwd: Option<&str>, expected: Option<&Path>, cwd_match: CwdMatch) -> bool {
+    match expected {
+        None => true,
+        Some(expected) => session_cwd
+            .and_then(|cwd| normalize_path(cwd).ok())
+            .is_some_and(|cwd| cwd_match.matches(&cwd, expected)),
+    }
+}
+
+impl AgentAdapter for PluginAdapter {
+    fn read_session(&self, options: &ReadOptions) -> Result<agents::Session> {
+        let plugin = self.plugin;
+        if options.id.is_some() {
+            return read_session(plugin.name, options);
+        }
+        let files = plugin.session_files()?;
+        let newest = files.first().ok_or_else(|| anyhow!("No {} session found.", plugin.name))?;
+        let expected = normalize_path(&options.cwd)?;
+        let scoped = files.iter().find_map(|file| {
+            let parsed = plugin.parse_file(file).ok()?;
+            in_scope(parsed.cwd.as_deref(), Some(&expected), options.cwd_match).then_some((file, parsed))
+        });
+        match scoped {
+            Some((file, parsed)) => Ok(plugin.to_session(&file.to_string_lossy(), parsed)),
+            None => {
+                let mut session = plugin.to_session(&newest.to_string_lossy(), plugin.parse_file(newest)?);
+                session.warnings.insert(
+                    0,
+                    format!(
+                        "Warning: no {} session matched cwd {}; falling back to latest session.",
+                        plugin.name,
+                        expected.display()
+                    ),
+                );
+                Ok(session)
+            }
+        }
+    }
+
+    fn list_sessions(&self, cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+        let plugin = self.plugin;
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for file in plugin.session_files()? {
+            let Ok(parsed) = plugin.parse_file(&file) else {
+                continue;
+            };
+            if !in_scope(parsed.cwd.as_deref(), expected.as_deref(), cwd_match) {
+                continue;
+            }
+            entries.push(plugin.entry(&file, &parsed));
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    fn search_sessions(
+        &self,
+        query: &SearchQuery,
+        cwd: Option<&str>,
+        cwd_match: CwdMatch,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let plugin = self.plugin;
+        let expected = cwd.map(normalize_path).transpose()?;
+        let mut entries = Vec::new();
+        for file in plugin.session_files()? {
+            if entries.len() >= limit {
+                break;
+            }
+            let Ok(parsed) = plugin.parse_file(&file) else {
+                continue;
+            };
+            if !in_scope(parsed.cwd.as_deref(), expected.as_deref(), cwd_match) {
+                continue;
+            }
+            let session_id = parsed.session_id.clone().unwrap_or_else(|| common::file_stem(&file));
+            if query.matches_session(plugin.name, &parsed.content, Some(&session_id), parsed.cwd.as_deref()) {
+                entries.push(plugin.entry(&file, &parsed));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_bytes(&self, name: &str, text: &str, _window: MessageWindow) -> Result<agents::Session> {
+        let parsed = self.plugin.parse(text.as_bytes())?;
+        Ok(self.plugin.to_session(name, parsed))
+    }
+
+    /// Plugins return only the joined replies, so there is no prompt to find.
+    fn first_prompt(&self, _text: &str, _session_id: Option<&str>) -> Option<String> {
+        None
+    }
+
+    fn transcript(&self, text: &str, _session_id: Option<&str>) -> Vec<TranscriptMessage> {
+        match self.plugin.parse(text.as_bytes()) {
+            Ok(parsed) => {
+                vec![TranscriptMessage::new("assistant", parsed.content, &json!(parsed.timestamp))]
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn store_roots(&self) -> Vec<PathBuf> {
+        vec![self.plugin.sessions_dir.clone()]
+    }
+
+    fn session_files(&self) -> Result<Vec<PathBuf>> {
+        self.plugin.session_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_directory, PluginConfig, Session};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::OnceLock;
+
+    fn plugin(wasm: PathBuf) -> PluginConfig {
+        PluginConfig { name: "acme", wasm, sessions_dir: PathBuf::from("/acme"), extension: None, compiled: OnceLock::new() }
+    }
+
+    #[test]
+    fn plugin_sessions_are_redacted_and_named_after_the_file() {
+        let parsed = Session {
+            content: "key sk-abcdefghijklmnopqrstuvwxyz".to_string(),
+            session_id: None,
+            cwd: Some("/repo".to_string()),
+            timestamp: None,
+            model: None,
+            message_count: 3,
+            messages_returned: 1,
+            warnings: Vec::new(),
+        };
+        let session = plugin(PathBuf::from("acme.wasm")).to_session("/acme/run-7.log", parsed);
+        assert_eq!(session.agent, "acme");
+        assert_eq!(session.content, "key sk-[REDACTED]");
+        assert_eq!(session.session_id.as_deref(), Some("run-7"));
+        assert_eq!((session.message_count, session.messages_returned), (3, 1));
+    }
+
+    #[test]
+    fn files_that_are_not_components_fail_to_load() {
+        let wasm = std::env::temp_dir().join(format!("bridge-plugin-{}.wasm", std::process::id()));
+        fs::write(&wasm, b"not a component").unwrap();
+        let error = plugin(wasm.clone()).parse(b"{}").unwrap_err().to_string();
+        fs::remove_file(&wasm).unwrap();
+        assert!(error.starts_with(&format!("Failed to load plugin {}", wasm.display())), "{}", error);
+    }
+    #[test]
+    fn plugins_directory_registers_wasm_files_with_valid_manifests() {
+        let dir = std::env::temp_dir().join(format!("bridge-plugins-dir-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["acme", "configured", "bare", "broken", "codex"] {
+            fs::write(dir.join(format!("{}.wasm", name)), b"").unwrap();
+        }
+        for name in ["acme", "configured", "codex"] {
+            fs::write(dir.join(format!("{}.toml", name)), "sessions_dir = \"/sessions\"\nextension = \"log\"\n").unwrap();
+        }
+        fs::write(dir.join("broken.toml"), "sessions_dir = [").unwrap();
+
+        let mut plugins = vec![PluginConfig { name: "configured", ..plugin(PathBuf::from("/config/configured.wasm")) }];
+        load_directory(&dir, &mut plugins);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let loaded = plugins.iter().map(|plugin| (plugin.name, plugin.wasm.clone())).collect::<Vec<_>>();
+        assert_eq!(loaded, [("configured", PathBuf::from("/config/configured.wasm")), ("acme", dir.join("acme.wasm"))]);
+        assert_eq!(plugins[1].sessions_dir, PathBuf::from("/sessions"));
+        assert_eq!(plugins[1].extension.as_deref(), Some("log"));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::agents::TranscriptMessage;
+use crate::config;
+use serde_json::{json, Value};
+
+/// Rates for one model, in USD per million tokens. Cache rates fall back to
+/// the input rate when a table omits them.
+struct Rates {
+    input: f64,
+    output: f64,
+    cache_read: f64,
+    cache_write: f64,
+}
+
+/// Find the `[pricing.<key>]` table for `model`: an exact key wins, otherwise
+/// the longest key that is a prefix of the model name (so `claude-sonnet-4`
+/// prices `claude-sonnet-4-5-20250929`).
+fn lookup(tables: &Value, model: &str) -> Option<(String, Rates)> {
+    let tables = tables.as_object()?;
+    let key = if tables.contains_key(model) {
+        model.to_string()
+    } else {
+        tables
+            .keys()
+            .filter(|key| model.starts_with(key.as_str()))
+            .max_by_key(|key| key.len())?
+            .clone()
+    };
+    let table = &tables[&key];
+    let rate = |name: &str| table[name].as_f64();
+    let input = rate("input")?;
+    let output = rate("output")?;
+    Some((
+        key,
+        Rates {
+            input,
+            output,
+            cache_read: rate("cache_read").unwrap_or(input),
+            cache_write: rate("cache_write").unwrap_or(input),
+        },
+    ))
+}
+
+pub(crate) fn round_usd(value: f64) -> f64 {
+    (value * 1_000_000.0).round() / 1_000_000.0
+}
+
+/// Dollar cost of a normalized `usage` object (see `read --json`), or `None`
+/// when the model has no configured pricing. Nothing is priced by default.
+pub fn cost_for(model: Option<&str>, usage: &Value) -> Option<Value> {
+    cost_with(config::section("pricing"), model, usage)
+}
+
+/// `cost_for` against explicit `[pricing]` tables.
+fn cost_with(tables: &Value, model: Option<&str>, usage: &Value) -> Option<Value> {
+    if !usage.is_object() {
+        return None;
+    }
+    let (key, rates) = lookup(tables, model?)?;
+    let tokens = |name: &str| usage[name].as_u64().unwrap_or(0) as f64 / 1_000_000.0;
+    let input = tokens("input_tokens") * rates.input;
+    let output = tokens("output_tokens") * rates.output;
+    let cache_read = tokens("cache_read_tokens") * rates.cache_read;
+    let cache_write = tokens("cache_write_tokens") * rates.cache_write;
+    Some(json!({
+        "pricing_key": key,
+        "input_usd": round_usd(input),
+        "output_usd": round_usd(output),
+        "cache_read_usd": round_usd(cache_read),
+        "cache_write_usd": round_usd(cache_write),
+        "total_usd": round_usd(input + output + cache_read + cache_write),
+    }))
+}
+
+/// Characters per estimated token, about what BPE tokenizers average on
+/// English prose and code.
+const CHARS_PER_TOKEN: u64 = 4;
+
+/// Estimated token count of `text`: one per four characters, rounded up.
+/// Without the provider's tokenizer this is a rough figure, off by tens of
+/// percent for code or non-English text.
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Estimated `usage` for `messages`, shaped like the provider-reported one:
+/// user messages count as input, assistant messages as output.
+pub fn estimate_usage(messages: &[TranscriptMessage]) -> Value {
+    let tokens = |role: &str| {
+        messages
+            .iter()
+            .filter(|message| message.role == role)
+            .map(|message| estimate_tokens(&message.text))
+            .sum::<u64>()
+    };
+    let (input, output) = (tokens("user"), tokens("assistant"));
+    json!({
+        "input_tokens": input,
+        "output_tokens": output,
+        "cache_read_tokens": 0,
+        "cache_write_tokens": 0,
+        "total_tokens": input + output,
+    })
+}
+
+/// The `tokens` object of `read --tokens`: estimates for each returned
+/// message, their sum, the whole session's transcript, and its cost when
+/// `model` has pricing.
+pub fn token_estimate(returned: &[String], transcript: &[TranscriptMessage], model: Option<&str>) -> Value {
+    let messages = returned.iter().map(|text| estimate_tokens(text)).collect::<Vec<_>>();
+    let session = estimate_usage(transcript);
+    json!({
+        "method": "chars/4",
+        "messages": messages,
+        "returned_tokens": messages.iter().sum::<u64>(),
+        "session": session,
+        "cost": cost_for(model, &session),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cost_with, estimate_tokens, token_estimate};
+    use crate::agents::TranscriptMessage;
+    use serde_json::json;
+
+    fn usage() -> serde_json::Value {
+        json!({"input_tokens": 1_000_000, "output_tokens": 500_000, "cache_read_tokens": 2_000_000, "cache_write_tokens": 0})
+    }
+
+    #[test]
+    fn longest_prefix_table_prices_a_dated_model() {
+        let tables = json!({
+            "claude": {"input": 1.0, "output": 1.0},
+            "claude-sonnet-4": {"input": 3.0, "output": 15.0, "cache_read": 0.3},
+        });
+        let cost = cost_with(&tables, Some("claude-sonnet-4-5-20250929"), &usage()).unwrap();
+        assert_eq!(cost["pricing_key"], "claude-sonnet-4");
+        assert_eq!(cost["total_usd"], json!(11.1));
+    }
+
+    #[test]
+    fn cache_rates_default_to_the_input_rate() {
+        let tables = json!({"gpt-5": {"input": 2.0, "output": 8.0}});
+        let cost = cost_with(&tables, Some("gpt-5"), &usage()).unwrap();
+        assert_eq!(cost["cache_read_usd"], json!(4.0));
+    }
+
+    #[test]
+    fn unpriced_models_have_no_cost() {
+        let tables = json!({"gpt-5": {"input": 2.0, "output": 8.0}});
+        assert!(cost_with(&tables, Some("gemini-2.5-pro"), &usage()).is_none());
+        assert!(cost_with(&tables, None, &usage()).is_none());
+    }
+
+    #[test]
+    fn estimates_round_up_chars_over_four() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("héllo"), 2);
+    }
+
+    #[test]
+    fn token_estimate_counts_returned_messages_and_the_whole_session() {
+        let transcript = [("user", "a".repeat(40)), ("assistant", "b".repeat(10)), ("assistant", "c".repeat(6))]
+            .map(|(role, text)| TranscriptMessage { role, text, timestamp: None });
+        let estimate = token_estimate(&["c".repeat(6)], &transcript, None);
+        assert_eq!(estimate["messages"], json!([2]));
+        assert_eq!(estimate["returned_tokens"], 2);
+        assert_eq!(
+            estimate["session"],
+            json!({"input_tokens": 10, "output_tokens": 5, "cache_read_tokens": 0, "cache_write_tokens": 0, "total_tokens": 15})
+        );
+        assert!(estimate["cost"].is_null());
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Iterate,
+}
+
+/// Parse a jq-lite path: `.`, `.key`, `.key.nested`, `.["odd key"]`,
+/// `.list[0]`, `.list[-1]`, and `.list[]` (iterate).
+fn parse(query: &str) -> Result<Vec<Segment>> {
+    let invalid = |reason: &str| anyhow!("Invalid --query '{}': {}", query, reason);
+    let chars: Vec<char> = query.trim().chars().collect();
+    if chars.first() != Some(&'.') {
+        return Err(invalid("must start with '.'"));
+    }
+
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                i += 1;
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '-')
+                {
+                    i += 1;
+                }
+                if i > start {
+                    segments.push(Segment::Key(chars[start..i].iter().collect()));
+                } else if i < chars.len() && chars[i] != '[' {
+                    return Err(invalid("expected a field name after '.'"));
+                }
+            }
+            '[' => {
+                let close = chars[i..]
+                    .iter()
+                    .position(|ch| *ch == ']')
+                    .map(|offset| i + offset)
+                    .ok_or_else(|| invalid("unclosed '['"))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+                if inner.is_empty() {
+                    segments.push(Segment::Iterate);
+                } else if let Some(key) = inner
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.strip_suffix('"'))
+                {
+                    segments.push(Segment::Key(key.to_string()));
+                } else {
+                    let index = inner
+                        .parse::<i64>()
+                        .map_err(|_| invalid(&format!("bad index '{}'", inner)))?;
+                    segments.push(Segment::Index(index));
+                }
+                i = close + 1;
+            }
+            ch => return Err(invalid(&format!("unexpected '{}'", ch))),
+        }
+    }
+    Ok(segments)
+}
+
+fn step(value: &Value, segment: &Segment) -> Vec<Value> {
+    match segment {
+        Segment::Key(key) => vec![value.get(key).cloned().unwrap_or(Value::Null)],
+        Segment::Index(index) => {
+            let item = value.as_array().and_then(|items| {
+                let len = items.len() as i64;
+                let resolved = if *index < 0 { len + index } else { *index };
+                usize::try_from(resolved).ok().and_then(|idx| items.get(idx))
+            });
+            vec![item.cloned().unwrap_or(Value::Null)]
+        }
+        Segment::Iterate => match value {
+            Value::Array(items) => items.clone(),
+            Value::Object(map) => map.values().cloned().collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+/// Evaluate `query` against `value`. Missing keys and out-of-range indexes
+/// yield `null`. Queries that iterate (`[]`) return an array of every match;
+/// otherwise the single selected value is returned.
+pub fn apply(value: &Value, query: &str) -> Result<Value> {
+    let segments = parse(query)?;
+    let iterates = segments.contains(&Segment::Iterate);
+    let mut current = vec![value.clone()];
+    for segment in &segments {
+        current = current.iter().flat_map(|item| step(item, segment)).collect();
+    }
+    if iterates {
+        Ok(Value::Array(current))
+    } else {
+        Ok(current.into_iter().next().unwrap_or(Value::Null))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply;
+    use serde_json::{json, Value};
+
+    fn report() -> Value {
+        json!({
+            "verdict": "OK",
+            "findings": [{ "summary": "a" }, { "summary": "b" }],
+            "odd key": 1
+        })
+    }
+
+    #[test]
+    fn identity_returns_the_whole_value() {
+        assert_eq!(apply(&report(), ".").unwrap(), report());
+    }
+
+    #[test]
+    fn dotted_paths_and_negative_indexes_walk_the_value() {
+        assert_eq!(apply(&report(), ".verdict").unwrap(), json!("OK"));
+        assert_eq!(apply(&report(), ".findings[-1].summary").unwrap(), json!("b"));
+    }
+
+    #[test]
+    fn empty_brackets_iterate_into_an_array() {
+        assert_eq!(apply(&report(), ".findings[].summary").unwrap(), json!(["a", "b"]));
+    }
+
+    #[test]
+    fn quoted_keys_reach_names_with_spaces() {
+        assert_eq!(apply(&report(), ".[\"odd key\"]").unwrap(), json!(1));
+    }
+
+    #[test]
+    fn missing_paths_are_null() {
+        assert_eq!(apply(&report(), ".missing.deeper").unwrap(), json!(null));
+    }
+
+    #[test]
+    fn malformed_queries_are_rejected() {
+        assert!(apply(&report(), "verdict").is_err());
+        assert!(apply(&report(), ".findings[x]").is_err());
+    }
+}
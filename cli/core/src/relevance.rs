@@ -0,0 +1,280 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Paths that count as context-relevant when `relevance.json` does not
+/// override `include`.
+const DEFAULT_INCLUDE: &[&str] = &[
+    "/README.md",
+    "/PROTOCOL.md",
+    "/CONTRIBUTING.md",
+    "/SKILL.md",
+    "/AGENTS.md",
+    "/package.json",
+    "/package-lock.json",
+    "/cli/Cargo.toml",
+    "/cli/Cargo.lock",
+    "/cli/core/Cargo.toml",
+    "/docs/architecture.svg",
+    "/docs/silo-tax-before-after.webp",
+    "/scripts/",
+    "/cli/src/",
+    "/cli/core/src/",
+    "/cli/core/wit/",
+    "/schemas/",
+    "/fixtures/golden/",
+    "/fixtures/session-store/",
+    "/.github/workflows/",
+];
+
+/// Paths that are never context-relevant when `relevance.json` does not
+/// override `exclude`.
+const DEFAULT_EXCLUDE: &[&str] = &[
+    "/blog/",
+    "/notes/",
+    "/drafts/",
+    "/scratch/",
+    "/tmp/",
+    "/.agent-context/",
+    "/docs/demo-*",
+];
+
+/// One .gitignore-style pattern: `!` negates, a trailing `/` matches only
+/// directories (and so everything under them), a leading or inner `/` anchors
+/// the pattern to the repo root, and otherwise it matches a name at any depth.
+/// `*` and `?` stay within one path segment; `**` spans segments.
+struct Pattern {
+    glob: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl Pattern {
+    fn parse(raw: &str) -> Option<Self> {
+        let mut text = raw.trim();
+        if text.is_empty() || text.starts_with('#') {
+            return None;
+        }
+        let negated = text.starts_with('!');
+        if negated {
+            text = &text[1..];
+        }
+        let dir_only = text.ends_with('/');
+        let text = text.trim_end_matches('/');
+        let anchored = text.contains('/');
+        let text = text.trim_start_matches('/');
+        if text.is_empty() {
+            return None;
+        }
+        Some(Self {
+            glob: text.chars().collect(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// A pattern matches a file when it matches the file itself or any of
+    /// its parent directories.
+    fn matches(&self, path: &str) -> bool {
+        let segments = path.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+        (1..=segments.len()).any(|end| {
+            if self.dir_only && end == segments.len() {
+                return false;
+            }
+            let candidate: Vec<char> = if self.anchored {
+                segments[..end].join("/").chars().collect()
+            } else {
+                segments[end - 1].chars().collect()
+            };
+            glob_match(&self.glob, &candidate)
+        })
+    }
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let mut rest = &pattern[2..];
+            // `**/` also matches zero directories.
+            if rest.first() == Some(&'/') {
+                if glob_match(&rest[1..], text) {
+                    return true;
+                }
+                rest = &rest[1..];
+                return (0..text.len())
+                    .filter(|i| text[*i] == '/')
+                    .any(|i| glob_match(rest, &text[i + 1..]));
+            }
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('*') => (0..=text.len())
+            .take_while(|i| *i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match(&pattern[1..], &text[i..])),
+        Some('?') => !text.is_empty() && text[0] != '/' && glob_match(&pattern[1..], &text[1..]),
+        Some(ch) => text.first() == Some(ch) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Last matching pattern wins, like .gitignore. `None` when nothing matches.
+fn last_match(patterns: &[Pattern], path: &str) -> Option<bool> {
+    patterns
+        .iter()
+        .rev()
+        .find(|pattern| pattern.matches(path))
+        .map(|pattern| !pattern.negated)
+}
+
+/// Which changed paths should refresh the context pack. Loaded from
+/// `<pack>/relevance.json` (`{"include": [...], "exclude": [...]}`); a list
+/// that is absent keeps its built-in default. An optional `sections` object
+/// maps a pattern to the pack files that describe those paths.
+pub struct RelevanceRules {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    sections: Vec<(Pattern, Vec<String>)>,
+}
+
+impl RelevanceRules {
+    pub fn load(pack_root: &Path) -> Result<Self> {
+        let path = pack_root.join("relevance.json");
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let value: Value = serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let list = |key: &str, default: &[&str]| -> Result<Vec<Pattern>> {
+            match &value[key] {
+                Value::Null => Ok(compile(default.iter().copied())),
+                Value::Array(items) => {
+                    let patterns = items
+                        .iter()
+                        .map(|item| item.as_str().ok_or_else(|| anyhow!("`{}` entries must be strings", key)))
+                        .collect::<Result<Vec<_>>>()
+                        .with_context(|| format!("Invalid {}", path.display()))?;
+                    Ok(compile(patterns.into_iter()))
+                }
+                _ => Err(anyhow!("Invalid {}: `{}` must be an array", path.display(), key)),
+            }
+        };
+        let sections = match &value["sections"] {
+            Value::Null => Vec::new(),
+            Value::Object(map) => map
+                .iter()
+                .filter_map(|(raw, files)| Some((raw, Pattern::parse(raw)?, files)))
+                .map(|(raw, pattern, files)| {
+                    let files = match files {
+                        Value::String(file) => vec![file.clone()],
+                        Value::Array(items) => items
+                            .iter()
+                            .map(|item| item.as_str().map(str::to_string))
+                            .collect::<Option<Vec<_>>>()
+                            .ok_or_else(|| anyhow!("Invalid {}: sections for `{}` must be strings", path.display(), raw))?,
+                        _ => return Err(anyhow!("Invalid {}: sections for `{}` must be strings", path.display(), raw)),
+                    };
+                    Ok((pattern, files))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(anyhow!("Invalid {}: `sections` must be an object", path.display())),
+        };
+        Ok(Self {
+            include: list("include", DEFAULT_INCLUDE)?,
+            exclude: list("exclude", DEFAULT_EXCLUDE)?,
+            sections,
+        })
+    }
+
+    pub fn has_sections(&self) -> bool {
+        !self.sections.is_empty()
+    }
+
+    /// Pack files mapped to `file_path` by `sections`, in pattern order.
+    pub fn sections_for(&self, file_path: &str) -> Vec<String> {
+        let normalized = file_path.replace('\\', "/");
+        let mut files: Vec<String> = Vec::new();
+        for (pattern, targets) in &self.sections {
+            if pattern.negated || !pattern.matches(&normalized) {
+                continue;
+            }
+            for target in targets {
+                if !files.contains(target) {
+                    files.push(target.clone());
+                }
+            }
+        }
+        files
+    }
+
+    pub fn is_relevant(&self, file_path: &str) -> bool {
+        let normalized = file_path.replace('\\', "/");
+        last_match(&self.exclude, &normalized) != Some(true)
+            && last_match(&self.include, &normalized) == Some(true)
+    }
+}
+
+impl Default for RelevanceRules {
+    fn default() -> Self {
+        Self {
+            include: compile(DEFAULT_INCLUDE.iter().copied()),
+            exclude: compile(DEFAULT_EXCLUDE.iter().copied()),
+            sections: Vec::new(),
+        }
+    }
+}
+
+fn compile<'a>(patterns: impl Iterator<Item = &'a str>) -> Vec<Pattern> {
+    patterns.filter_map(Pattern::parse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile, Pattern, RelevanceRules};
+
+    #[test]
+    fn last_match_wins_with_negation_and_directory_shorthand() {
+        let defaults = RelevanceRules::default();
+        assert!(defaults.is_relevant("cli/src/main.rs"));
+        assert!(defaults.is_relevant("README.md"));
+        assert!(!defaults.is_relevant("docs/README.md"));
+        assert!(!defaults.is_relevant("docs/demo-01.gif"));
+        assert!(!defaults.is_relevant(".agent-context/current/00_START_HERE.md"));
+
+        let rules = RelevanceRules {
+            include: compile(["docs/", "*.rs", "!cli/src/generated/**"].into_iter()),
+            exclude: compile(["/docs/", "!docs/keep.md", "docs/keep.md.bak"].into_iter()),
+            sections: Vec::new(),
+        };
+        assert!(rules.is_relevant("docs/keep.md"));
+        assert!(!rules.is_relevant("docs/other.md"));
+        assert!(!rules.is_relevant("docs/keep.md.bak"));
+        assert!(rules.is_relevant("cli/src/main.rs"));
+        assert!(!rules.is_relevant("cli/src/generated/schema.rs"));
+        assert!(!rules.is_relevant("docs"));
+    }
+
+    #[test]
+    fn sections_for_merges_every_matching_mapping() {
+        let rules = RelevanceRules {
+            sections: vec![
+                (
+                    Pattern::parse("cli/src/**").unwrap(),
+                    vec!["20_CODE_MAP.md".to_string(), "30_BEHAVIORAL_INVARIANTS.md".to_string()],
+                ),
+                (Pattern::parse("*.rs").unwrap(), vec!["20_CODE_MAP.md".to_string()]),
+                (Pattern::parse("schemas/").unwrap(), vec!["30_BEHAVIORAL_INVARIANTS.md".to_string()]),
+            ],
+            ..RelevanceRules::default()
+        };
+        assert_eq!(
+            rules.sections_for("cli/src/agents.rs"),
+            vec!["20_CODE_MAP.md", "30_BEHAVIORAL_INVARIANTS.md"]
+        );
+        assert_eq!(rules.sections_for("schemas/report.schema.json"), vec!["30_BEHAVIORAL_INVARIANTS.md"]);
+        assert!(rules.sections_for("README.md").is_empty());
+    }
+}
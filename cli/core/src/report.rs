@@ -0,0 +1,1608 @@
+use crate::adapters;
+use crate::aliases;
+use crate::agents::{self, BridgeErrorCode, CwdMatch, ReadOptions, Session};
+use crate::claims::{self, Claim};
+use crate::rubric::{self, Criterion};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+#[derive(Clone, Debug)]
+pub struct SourceSpec {
+    pub agent: String,
+    pub session_id: Option<String>,
+    pub current_session: bool,
+    pub cwd: Option<String>,
+    pub chats_dir: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ReportRequest {
+    pub mode: String,
+    pub task: String,
+    pub success_criteria: Vec<String>,
+    pub sources: Vec<SourceSpec>,
+    pub constraints: Vec<String>,
+    pub normalize: bool,
+    /// Compare outputs sentence by sentence instead of as whole strings.
+    pub semantic: bool,
+    pub cwd_match: CwdMatch,
+    /// Newest assistant messages read from each source; `usize::MAX` reads
+    /// the whole transcript (`compare --full`).
+    pub last_n: usize,
+    /// Earlier steps of a relay, oldest first, as `chain` entries.
+    pub history: Vec<Value>,
+    /// Checks a `verify` handoff makes against the working tree.
+    pub claims: Vec<Claim>,
+    /// Run `command` claims (`report --allow-commands`); they are skipped otherwise.
+    pub allow_commands: bool,
+    /// Handoff `thresholds`, overridden by `--fail-on` and `--min-score`.
+    pub thresholds: Thresholds,
+    /// Criteria a `feedback` handoff grades each source's reply against.
+    pub rubric: Vec<Criterion>,
+}
+
+/// Gates that turn the verdict into `FAIL`: any finding at `fail_on`
+/// severity or worse, or an overall score below `min_score`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Thresholds {
+    pub fail_on: Option<String>,
+    pub min_score: Option<i64>,
+}
+
+impl Thresholds {
+    fn is_set(&self) -> bool {
+        self.fail_on.is_some() || self.min_score.is_some()
+    }
+
+    /// Why `findings` and `score` breach these thresholds; empty when they pass.
+    fn breaches(&self, findings: &[Value], score: i64) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if let Some(fail_on) = &self.fail_on {
+            let count = findings
+                .iter()
+                .filter(|finding| finding["severity"].as_str().is_some_and(|severity| severity <= fail_on.as_str()))
+                .count();
+            if count > 0 {
+                reasons.push(format!("{} finding(s) at {} or worse", count, fail_on));
+            }
+        }
+        if let Some(min_score) = self.min_score {
+            if score < min_score {
+                reasons.push(format!("score {} is below {}", score, min_score));
+            }
+        }
+        reasons
+    }
+}
+
+/// `P0`..`P3`, case-insensitively, as used by `--fail-on` and `thresholds.fail_on`.
+pub fn parse_severity(raw: &str) -> Result<String> {
+    let severity = raw.trim().to_ascii_uppercase();
+    if ["P0", "P1", "P2", "P3"].contains(&severity.as_str()) {
+        Ok(severity)
+    } else {
+        Err(anyhow!("Unsupported severity: {} (expected P0, P1, P2, or P3)", raw))
+    }
+}
+
+/// Exit status for a report: 2 when its verdict is `FAIL` or `INCOMPLETE`,
+/// so CI can gate on it; 0 otherwise.
+pub fn verdict_exit_code(report: &Value) -> i32 {
+    match report["verdict"].as_str() {
+        Some("FAIL" | "INCOMPLETE") => 2,
+        _ => 0,
+    }
+}
+
+pub fn parse_source_arg(raw: &str) -> Result<SourceSpec> {
+    let mut parts = raw.splitn(2, ':');
+    let agent = adapters::normalize_agent(parts.next().unwrap_or(""))?.to_string();
+    let session_id = parts.next().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+    Ok(SourceSpec {
+        agent,
+        session_id: session_id.clone(),
+        current_session: session_id.is_none(),
+        cwd: None,
+        chats_dir: None,
+    })
+}
+
+const MAX_HANDOFF_SIZE: u64 = 1024 * 1024; // 1 MB
+
+/// Compare request for `sources` (`<agent>` or `<agent>:<session-id>`), as
+/// built by `bridge compare`.
+pub fn compare_request(sources: &[String], normalize: bool, cwd_match: CwdMatch) -> Result<ReportRequest> {
+    let sources = sources
+        .iter()
+        .map(|raw| parse_source_arg(raw))
+        .collect::<Result<Vec<SourceSpec>>>()?;
+    Ok(ReportRequest {
+        mode: "analyze".to_string(),
+        task: "Compare agent outputs".to_string(),
+        success_criteria: vec![
+            "Identify agreements and contradictions".to_string(),
+            "Highlight unavailable sources".to_string(),
+        ],
+        sources,
+        constraints: Vec::new(),
+        normalize,
+        semantic: false,
+        cwd_match,
+        last_n: 1,
+        history: Vec::new(),
+        claims: Vec::new(),
+        allow_commands: false,
+        thresholds: Thresholds::default(),
+        rubric: Vec::new(),
+    })
+}
+
+pub fn load_handoff(path: &str) -> Result<ReportRequest> {
+    parse_handoff(&read_handoff(path)?)
+}
+
+/// Handoff file text, refusing files over the 1MB limit.
+pub fn read_handoff(path: &str) -> Result<String> {
+    let meta = std::fs::metadata(path).with_context(|| format!("Failed to read handoff file: {}", path))?;
+    if meta.len() > MAX_HANDOFF_SIZE {
+        return Err(BridgeErrorCode::InvalidHandoff.error("Invalid handoff: file exceeds 1MB size limit"));
+    }
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read handoff file: {}", path))
+}
+
+/// Validate handoff packet JSON text into a report request. A packet that is
+/// not JSON fails with `PARSE_FAILED`; one that breaks the protocol fails
+/// with `INVALID_HANDOFF` unless a more specific code applies (an unknown
+/// agent or mode).
+pub fn parse_handoff(raw: &str) -> Result<ReportRequest> {
+    let root: Value = serde_json::from_str(raw)
+        .map_err(|err| BridgeErrorCode::ParseFailed.error(format!("Failed to parse handoff JSON: {}", err)))?;
+    handoff_request(&root).map_err(|err| agents::with_code(err, BridgeErrorCode::InvalidHandoff))
+}
+
+fn handoff_request(root: &Value) -> Result<ReportRequest> {
+    // Validate no extra fields
+    if let Some(obj) = root.as_object() {
+        let allowed = ["mode", "task", "success_criteria", "sources", "constraints", "history", "claims", "thresholds", "rubric"];
+        let extra: Vec<&String> = obj.keys().filter(|k| !allowed.contains(&k.as_str())).collect();
+        if !extra.is_empty() {
+            return Err(anyhow!("Invalid handoff: unexpected fields: {}", extra.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(", ")));
+        }
+    } else {
+        return Err(anyhow!("Invalid handoff: must be a JSON object"));
+    }
+
+    let mode = root["mode"]
+        .as_str()
+        .map(|v| v.to_ascii_lowercase())
+        .context("Handoff is missing required string field: mode")?;
+    validate_mode(&mode)?;
+
+    let task = root["task"]
+        .as_str()
+        .map(|v| v.to_string())
+        .context("Handoff is missing required string field: task")?;
+
+    let success_criteria = root["success_criteria"]
+        .as_array()
+        .context("Handoff is missing required array field: success_criteria")?
+        .iter()
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .collect::<Vec<String>>();
+    if success_criteria.is_empty() {
+        return Err(anyhow!("Handoff success_criteria must contain at least one string"));
+    }
+
+    let mut sources = Vec::new();
+    for source in root["sources"]
+        .as_array()
+        .context("Handoff is missing required array field: sources")?
+    {
+        let agent = source["agent"]
+            .as_str()
+            .context("Each source must include string field: agent")
+            .and_then(adapters::normalize_agent)?
+            .to_string();
+
+        let session_id = source
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string());
+        let current_session = source
+            .get("current_session")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if session_id.is_none() && !current_session {
+            return Err(anyhow!(
+                "Each source must provide session_id or set current_session=true"
+            ));
+        }
+
+        let cwd = source
+            .get("cwd")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string());
+
+        sources.push(SourceSpec {
+            agent,
+            session_id,
+            current_session,
+            cwd,
+            chats_dir: None,
+        });
+    }
+
+    let constraints = root
+        .get("constraints")
+        .and_then(|v| v.as_array())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+
+    let history = match root.get("history") {
+        None => Vec::new(),
+        Some(Value::Array(entries)) => entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| history_step(index + 1, entry))
+            .collect::<Result<Vec<Value>>>()?,
+        Some(_) => return Err(anyhow!("Invalid handoff: history must be an array")),
+    };
+
+    let claims = match root.get("claims") {
+        None => Vec::new(),
+        Some(_) if mode != "verify" => return Err(anyhow!("Invalid handoff: claims are only checked in verify mode")),
+        Some(Value::Array(entries)) => entries.iter().map(Claim::parse).collect::<Result<Vec<Claim>>>()?,
+        Some(_) => return Err(anyhow!("Invalid handoff: claims must be an array")),
+    };
+
+    let rubric = match root.get("rubric") {
+        None => Vec::new(),
+        Some(_) if mode != "feedback" => return Err(anyhow!("Invalid handoff: rubric is only scored in feedback mode")),
+        Some(Value::Array(entries)) if !entries.is_empty() => {
+            entries.iter().map(Criterion::parse).collect::<Result<Vec<Criterion>>>()?
+        }
+        Some(_) => return Err(anyhow!("Invalid handoff: rubric must be a non-empty array")),
+    };
+
+    let thresholds = match root.get("thresholds") {
+        None => Thresholds::default(),
+        Some(Value::Object(object)) => Thresholds {
+            fail_on: object
+                .get("fail_on")
+                .map(|value| value.as_str().context("Handoff thresholds.fail_on must be a string").and_then(parse_severity))
+                .transpose()?,
+            min_score: object
+                .get("min_score")
+                .map(|value| {
+                    value
+                        .as_i64()
+                        .filter(|score| (0..=100).contains(score))
+                        .context("Handoff thresholds.min_score must be an integer from 0 to 100")
+                })
+                .transpose()?,
+        },
+        Some(_) => return Err(anyhow!("Invalid handoff: thresholds must be an object")),
+    };
+
+    Ok(ReportRequest {
+        mode,
+        task,
+        success_criteria,
+        sources,
+        constraints,
+        normalize: false,
+        semantic: false,
+        cwd_match: CwdMatch::Exact,
+        last_n: 1,
+        history,
+        claims,
+        allow_commands: false,
+        thresholds,
+        rubric,
+    })
+}
+
+/// A prior relay step as a `chain` entry. `entry` is usually an earlier
+/// report's JSON, optionally tagged with the `agent` that produced it; fields
+/// the chain does not carry are ignored.
+fn history_step(step: usize, entry: &Value) -> Result<Value> {
+    if !entry.is_object() {
+        return Err(anyhow!("Invalid handoff: each history entry must be an object"));
+    }
+    let verdict = entry["verdict"]
+        .as_str()
+        .context("Each history entry must include string field: verdict")?;
+    let agent = match entry.get("agent").and_then(Value::as_str) {
+        Some(agent) => Some(adapters::normalize_agent(agent)?),
+        None => None,
+    };
+    let mode = match entry.get("mode").and_then(Value::as_str) {
+        Some(mode) => {
+            let mode = mode.to_ascii_lowercase();
+            validate_mode(&mode)?;
+            Some(mode)
+        }
+        None => None,
+    };
+    Ok(chain_step(
+        step,
+        agent,
+        mode.as_deref(),
+        entry["task"].as_str(),
+        verdict,
+        entry["score"].as_i64(),
+        entry.get("findings").filter(|findings| findings.is_array()).cloned().unwrap_or_else(|| json!([])),
+    ))
+}
+
+fn chain_step(
+    step: usize,
+    agent: Option<&str>,
+    mode: Option<&str>,
+    task: Option<&str>,
+    verdict: &str,
+    score: Option<i64>,
+    findings: Value,
+) -> Value {
+    json!({
+        "step": step,
+        "agent": agent,
+        "mode": mode,
+        "task": task,
+        "verdict": verdict,
+        "score": score,
+        "findings": findings,
+    })
+}
+
+/// One problem `bridge handoff validate` found in a handoff packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandoffIssue {
+    /// Where the problem is, as a JSON path such as `$.sources[0].agent`.
+    pub path: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl HandoffIssue {
+    pub(crate) fn new(path: impl Into<String>, code: &'static str, message: impl Into<String>) -> Self {
+        Self { path: path.into(), code, message: message.into() }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({ "path": self.path, "code": self.code, "message": self.message })
+    }
+}
+
+/// Check handoff JSON text against `schemas/handoff.schema.json` and report
+/// every problem rather than stopping at the first. Agent names are checked
+/// the way `report` resolves them, so aliases and configured adapters pass.
+pub fn validate_handoff(raw: &str) -> Vec<HandoffIssue> {
+    let root: Value = match serde_json::from_str(raw) {
+        Ok(root) => root,
+        Err(error) => return vec![HandoffIssue::new("$", "INVALID_JSON", format!("Failed to parse handoff JSON: {}", error))],
+    };
+    let Some(object) = root.as_object() else {
+        return vec![HandoffIssue::new("$", "WRONG_TYPE", "Handoff must be a JSON object")];
+    };
+
+    let mut issues = Vec::new();
+    let allowed = ["mode", "task", "success_criteria", "sources", "constraints", "history", "claims", "thresholds", "rubric"];
+    for key in object.keys().filter(|key| !allowed.contains(&key.as_str())) {
+        issues.push(HandoffIssue::new(format!("$.{}", key), "UNEXPECTED_FIELD", format!("Unexpected field: {}", key)));
+    }
+
+    match root.get("mode") {
+        None => issues.push(HandoffIssue::new("$.mode", "MISSING_FIELD", "Missing required string field: mode")),
+        Some(Value::String(mode)) => {
+            if validate_mode(&mode.to_ascii_lowercase()).is_err() {
+                issues.push(HandoffIssue::new(
+                    "$.mode",
+                    "BAD_MODE",
+                    format!("Unsupported mode: {} (expected verify, steer, analyze, or feedback)", mode),
+                ));
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new("$.mode", "WRONG_TYPE", "mode must be a string")),
+    }
+
+    match root.get("task") {
+        None => issues.push(HandoffIssue::new("$.task", "MISSING_FIELD", "Missing required string field: task")),
+        Some(Value::String(task)) if task.trim().is_empty() => {
+            issues.push(HandoffIssue::new("$.task", "EMPTY", "task must not be empty"))
+        }
+        Some(Value::String(_)) => {}
+        Some(_) => issues.push(HandoffIssue::new("$.task", "WRONG_TYPE", "task must be a string")),
+    }
+
+    check_string_array(&root, "success_criteria", true, &mut issues);
+    check_string_array(&root, "constraints", false, &mut issues);
+
+    match root.get("sources") {
+        None => issues.push(HandoffIssue::new("$.sources", "MISSING_FIELD", "Missing required array field: sources")),
+        Some(Value::Array(sources)) if sources.is_empty() => {
+            issues.push(HandoffIssue::new("$.sources", "EMPTY", "sources must contain at least one source"))
+        }
+        Some(Value::Array(sources)) => {
+            for (index, source) in sources.iter().enumerate() {
+                validate_source(&format!("$.sources[{}]", index), source, &mut issues);
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new("$.sources", "WRONG_TYPE", "sources must be an array")),
+    }
+
+    match root.get("history") {
+        None => {}
+        Some(Value::Array(entries)) => {
+            for (index, entry) in entries.iter().enumerate() {
+                validate_history_entry(&format!("$.history[{}]", index), entry, &mut issues);
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new("$.history", "WRONG_TYPE", "history must be an array")),
+    }
+
+    match root.get("claims") {
+        None => {}
+        Some(_) if !root["mode"].as_str().is_some_and(|mode| mode.eq_ignore_ascii_case("verify")) => {
+            issues.push(HandoffIssue::new("$.claims", "UNEXPECTED_FIELD", "claims are only checked in verify mode"))
+        }
+        Some(Value::Array(claims)) => {
+            for (index, claim) in claims.iter().enumerate() {
+                claims::validate(&format!("$.claims[{}]", index), claim, &mut issues);
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new("$.claims", "WRONG_TYPE", "claims must be an array")),
+    }
+
+    match root.get("rubric") {
+        None => {}
+        Some(_) if !root["mode"].as_str().is_some_and(|mode| mode.eq_ignore_ascii_case("feedback")) => {
+            issues.push(HandoffIssue::new("$.rubric", "UNEXPECTED_FIELD", "rubric is only scored in feedback mode"))
+        }
+        Some(Value::Array(entries)) if entries.is_empty() => {
+            issues.push(HandoffIssue::new("$.rubric", "EMPTY", "rubric must contain at least one criterion"))
+        }
+        Some(Value::Array(entries)) => {
+            for (index, entry) in entries.iter().enumerate() {
+                rubric::validate(&format!("$.rubric[{}]", index), entry, &mut issues);
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new("$.rubric", "WRONG_TYPE", "rubric must be an array")),
+    }
+
+    match root.get("thresholds") {
+        None => {}
+        Some(Value::Object(thresholds)) => {
+            for key in thresholds.keys().filter(|key| !["fail_on", "min_score"].contains(&key.as_str())) {
+                issues.push(HandoffIssue::new(format!("$.thresholds.{}", key), "UNEXPECTED_FIELD", format!("Unexpected field: {}", key)));
+            }
+            match thresholds.get("fail_on") {
+                None => {}
+                Some(Value::String(severity)) => {
+                    if let Err(error) = parse_severity(severity) {
+                        issues.push(HandoffIssue::new("$.thresholds.fail_on", "BAD_THRESHOLD", error.to_string()));
+                    }
+                }
+                Some(_) => issues.push(HandoffIssue::new("$.thresholds.fail_on", "WRONG_TYPE", "fail_on must be a string")),
+            }
+            match thresholds.get("min_score") {
+                None => {}
+                Some(score) if score.as_i64().is_some_and(|score| (0..=100).contains(&score)) => {}
+                Some(score) if score.is_i64() || score.is_u64() => issues.push(HandoffIssue::new(
+                    "$.thresholds.min_score",
+                    "BAD_THRESHOLD",
+                    "min_score must be from 0 to 100",
+                )),
+                Some(_) => issues.push(HandoffIssue::new("$.thresholds.min_score", "WRONG_TYPE", "min_score must be an integer")),
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new("$.thresholds", "WRONG_TYPE", "thresholds must be an object")),
+    }
+    issues
+}
+
+fn validate_history_entry(path: &str, entry: &Value, issues: &mut Vec<HandoffIssue>) {
+    if !entry.is_object() {
+        issues.push(HandoffIssue::new(path, "WRONG_TYPE", "Each history entry must be an object"));
+        return;
+    }
+    match entry.get("verdict") {
+        None => issues.push(HandoffIssue::new(format!("{}.verdict", path), "MISSING_FIELD", "Each history entry must include string field: verdict")),
+        Some(Value::String(_)) => {}
+        Some(_) => issues.push(HandoffIssue::new(format!("{}.verdict", path), "WRONG_TYPE", "verdict must be a string")),
+    }
+    if let Some(agent) = entry.get("agent").and_then(Value::as_str) {
+        if let Err(error) = adapters::normalize_agent(agent) {
+            issues.push(HandoffIssue::new(format!("{}.agent", path), "BAD_AGENT", error.to_string()));
+        }
+    }
+    if let Some(mode) = entry.get("mode").and_then(Value::as_str) {
+        if validate_mode(&mode.to_ascii_lowercase()).is_err() {
+            issues.push(HandoffIssue::new(format!("{}.mode", path), "BAD_MODE", format!("Unsupported mode: {}", mode)));
+        }
+    }
+    if entry.get("findings").is_some_and(|findings| !findings.is_array()) {
+        issues.push(HandoffIssue::new(format!("{}.findings", path), "WRONG_TYPE", "findings must be an array"));
+    }
+}
+
+fn check_string_array(root: &Value, field: &str, required: bool, issues: &mut Vec<HandoffIssue>) {
+    let path = format!("$.{}", field);
+    match root.get(field) {
+        None if required => {
+            issues.push(HandoffIssue::new(path, "MISSING_FIELD", format!("Missing required array field: {}", field)))
+        }
+        None => {}
+        Some(Value::Array(items)) => {
+            if required && items.is_empty() {
+                issues.push(HandoffIssue::new(path.clone(), "EMPTY", format!("{} must contain at least one string", field)));
+            }
+            for (index, _) in items.iter().enumerate().filter(|(_, item)| !item.is_string()) {
+                issues.push(HandoffIssue::new(format!("{}[{}]", path, index), "WRONG_TYPE", format!("{} entries must be strings", field)));
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new(path, "WRONG_TYPE", format!("{} must be an array of strings", field))),
+    }
+}
+
+fn validate_source(path: &str, source: &Value, issues: &mut Vec<HandoffIssue>) {
+    let Some(object) = source.as_object() else {
+        issues.push(HandoffIssue::new(path, "WRONG_TYPE", "Each source must be an object"));
+        return;
+    };
+    let allowed = ["agent", "session_id", "current_session", "cwd"];
+    for key in object.keys().filter(|key| !allowed.contains(&key.as_str())) {
+        issues.push(HandoffIssue::new(format!("{}.{}", path, key), "UNEXPECTED_FIELD", format!("Unexpected field: {}", key)));
+    }
+
+    match source.get("agent") {
+        None => issues.push(HandoffIssue::new(format!("{}.agent", path), "MISSING_FIELD", "Each source must include string field: agent")),
+        Some(Value::String(agent)) => {
+            if let Err(error) = adapters::normalize_agent(agent) {
+                issues.push(HandoffIssue::new(format!("{}.agent", path), "BAD_AGENT", error.to_string()));
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new(format!("{}.agent", path), "WRONG_TYPE", "agent must be a string")),
+    }
+
+    let session_id = match source.get("session_id") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(id)) => Some(id.as_str()),
+        Some(_) => {
+            issues.push(HandoffIssue::new(format!("{}.session_id", path), "WRONG_TYPE", "session_id must be a string"));
+            None
+        }
+    };
+    let current_session = match source.get("current_session") {
+        None => false,
+        Some(Value::Bool(current)) => *current,
+        Some(_) => {
+            issues.push(HandoffIssue::new(format!("{}.current_session", path), "WRONG_TYPE", "current_session must be a boolean"));
+            false
+        }
+    };
+    if !current_session && session_id.map_or(true, |id| id.trim().is_empty()) {
+        issues.push(HandoffIssue::new(
+            path,
+            "MISSING_SESSION",
+            "Each source must provide session_id or set current_session=true",
+        ));
+    }
+    if source.get("cwd").is_some_and(|cwd| !cwd.is_string()) {
+        issues.push(HandoffIssue::new(format!("{}.cwd", path), "WRONG_TYPE", "cwd must be a string"));
+    }
+}
+
+pub fn build_report(request: &ReportRequest, default_cwd: &str) -> Value {
+    let mut successful: Vec<(SourceSpec, Session, String)> = Vec::new();
+    let mut missing: Vec<(SourceSpec, String, String)> = Vec::new();
+    // Structured copies of the unavailable-source and source-warning
+    // findings, so callers can act on a partial report.
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    for source in &request.sources {
+        let evidence = evidence_tag(source);
+        match read_source(source, default_cwd, request.cwd_match, request.last_n) {
+            Ok(session) => successful.push((source.clone(), session, evidence)),
+            Err(error) => {
+                errors.push(json!({
+                    "source": evidence,
+                    "agent": source.agent,
+                    "error_code": agents::error_code(&error).as_str(),
+                    "message": error.to_string(),
+                }));
+                missing.push((source.clone(), error.to_string(), evidence));
+            }
+        }
+    }
+
+    let mut findings: Vec<Value> = Vec::new();
+
+    for (source, error, evidence) in &missing {
+        findings.push(json!({
+            "severity": "P1",
+            "summary": format!("Source unavailable: {} ({})", source.agent, error),
+            "evidence": [evidence],
+            "confidence": 0.9
+        }));
+    }
+
+    for (_, session, evidence) in &successful {
+        for warning in &session.warnings {
+            warnings.push(json!({ "source": evidence, "message": warning }));
+            findings.push(json!({
+                "severity": "P2",
+                "summary": format!("Source warning: {}", warning),
+                "evidence": [evidence],
+                "confidence": 0.75
+            }));
+        }
+    }
+
+    let texts: Vec<&str> = successful.iter().map(|(_, session, _)| session.content.trim()).collect();
+    let contents: Vec<String> = texts
+        .iter()
+        .map(|text| if request.normalize { normalize_content(text) } else { text.to_string() })
+        .collect();
+    let unique_contents: HashSet<&String> = contents.iter().collect();
+    let successful_tags: Vec<String> = successful.iter().map(|(_, _, tag)| tag.clone()).collect();
+    let mut similarity = None;
+    let mut odd_one_out = None;
+    let mut semantic = None;
+    let mut agreement = None;
+    // A rubric grades each source on its own, so sources are not compared.
+    let grading = !request.rubric.is_empty();
+    let (mut contradicted, mut diverging) = (false, unique_contents.len() > 1 && !grading);
+
+    if grading {
+        // Graded after the stale-source checks.
+    } else if successful.len() >= 2 && unique_contents.len() <= 1 {
+        findings.push(json!({
+            "severity": "P3",
+            "summary": "All available agent outputs are aligned",
+            "evidence": successful_tags,
+            "confidence": 0.9
+        }));
+    } else if request.semantic && successful.len() >= 2 {
+        let (pair_findings, pairs, any_contradiction, any_divergent) =
+            crate::semantic::pairwise(&successful_tags, &contents);
+        findings.extend(pair_findings);
+        semantic = Some(pairs);
+        contradicted = any_contradiction;
+        diverging = any_divergent;
+    } else if successful.len() >= 3 {
+        let matrix = similarity_matrix(&contents);
+        odd_one_out = find_odd_one_out(&matrix);
+        if let Some((finding, pairs)) = consensus(&successful_tags, &contents, &matrix) {
+            // When every pair agrees, a relatively loose source is not worth a P1.
+            if pairs["status"] == "unanimous" {
+                odd_one_out = None;
+            }
+            findings.push(finding);
+            agreement = Some(pairs);
+        }
+        findings.extend(pairwise_findings(&successful_tags, &contents, &matrix, odd_one_out));
+        similarity = Some(json!({ "sources": successful_tags, "matrix": matrix }));
+    } else if successful.len() == 2 {
+        findings.push(json!({
+            "severity": "P1",
+            "summary": "Divergent agent outputs detected",
+            "evidence": successful_tags,
+            "confidence": 0.75
+        }));
+    } else {
+        findings.push(json!({
+            "severity": "P2",
+            "summary": "Insufficient comparable sources",
+            "evidence": successful.iter().map(|(_, _, tag)| tag.clone()).collect::<Vec<String>>(),
+            "confidence": 0.5
+        }));
+    }
+
+    let timestamps: Vec<Option<i64>> = successful
+        .iter()
+        .map(|(_, session, _)| session.timestamp.as_deref().and_then(agents::parse_iso_millis))
+        .collect();
+    let stale = match stale_after_hours() {
+        0 => Vec::new(),
+        hours => stale_sources(&timestamps, head_commit_millis(default_cwd), hours),
+    };
+    let stale_tags: Vec<String> = stale.iter().map(|(index, _, _)| successful_tags[*index].clone()).collect();
+    for finding in &mut findings {
+        let cites_stale = finding["evidence"]
+            .as_array()
+            .is_some_and(|evidence| evidence.iter().any(|tag| stale_tags.iter().any(|stale| tag == stale.as_str())));
+        if cites_stale {
+            let confidence = finding["confidence"].as_f64().unwrap_or(0.0);
+            finding["confidence"] = json!((confidence * STALE_CONFIDENCE_FACTOR * 100.0).round() / 100.0);
+        }
+    }
+    for (index, hours, reference) in &stale {
+        findings.push(json!({
+            "severity": "P2",
+            "summary": format!("Stale source: {} was last updated {}h before {}", successful_tags[*index], hours, reference),
+            "evidence": [successful_tags[*index]],
+            "confidence": 0.75
+        }));
+    }
+
+    let (claim_findings, claim_results, claims_failed, claims_skipped) =
+        claims::check_all(&request.claims, default_cwd, request.allow_commands);
+    findings.extend(claim_findings);
+
+    let mut steering = None;
+    let mut steer_questions = Vec::new();
+    if request.mode == "steer" {
+        let (steer_findings, plan, questions) =
+            crate::steer::steer(&successful_tags, &texts, &request.task, &request.success_criteria);
+        findings.extend(steer_findings);
+        steering = Some(plan);
+        steer_questions = questions;
+    }
+
+    let mut grades = None;
+    let mut rubric_actions = Vec::new();
+    if grading && !successful.is_empty() {
+        let (rubric_findings, rubric_report, scores, actions) = rubric::grade(&request.rubric, &successful_tags, &texts);
+        findings.extend(rubric_findings);
+        grades = Some((rubric_report, scores));
+        rubric_actions = actions;
+    }
+
+    let mut verdict = compute_verdict(&request.mode, &missing, unique_contents.len(), successful.len(), claims_failed);
+    let source_tags = request.sources.iter().map(evidence_tag).collect::<Vec<String>>();
+    let (mut score, mut source_scores) = compute_scores(&findings, &source_tags);
+    // With a rubric the scores are the rubric's, and a source that could not
+    // be read scores 0.
+    if let Some((rubric_report, scores)) = &grades {
+        score = rubric_report["score"].as_i64().unwrap_or(0);
+        source_scores = source_tags
+            .iter()
+            .map(|tag| {
+                let graded = successful_tags.iter().position(|successful| successful == tag).map_or(0, |index| scores[index]);
+                json!({ "source": tag, "score": graded })
+            })
+            .collect();
+    }
+    let breaches = request.thresholds.breaches(&findings, score);
+    if !breaches.is_empty() {
+        verdict = "FAIL";
+    }
+
+    let mut recommended_next_actions = Vec::new();
+    if !breaches.is_empty() {
+        recommended_next_actions.push(format!("Resolve what breaches the report thresholds: {}.", breaches.join("; ")));
+    }
+    if claims_failed {
+        recommended_next_actions.push("Fix the failed claims, then re-run the report.".to_string());
+    }
+    if claims_skipped {
+        recommended_next_actions.push("Re-run with --allow-commands to check command claims.".to_string());
+    }
+    if !missing.is_empty() {
+        recommended_next_actions
+            .push("Provide valid session identifiers or cwd values for unavailable sources.".to_string());
+    }
+    if contradicted {
+        recommended_next_actions.push("Resolve the contradictions between sources before final decisions.".to_string());
+    }
+    if diverging {
+        recommended_next_actions
+            .push("Inspect full transcripts for diverging sources before final decisions.".to_string());
+    }
+    if let Some(index) = odd_one_out {
+        recommended_next_actions.push(format!(
+            "Start with {}: it disagrees with every other source.",
+            successful_tags[index]
+        ));
+    }
+    if !stale_tags.is_empty() {
+        recommended_next_actions.push(format!(
+            "Refresh stale sources before relying on the comparison: {}.",
+            stale_tags.join(", ")
+        ));
+    }
+    recommended_next_actions.extend(rubric_actions);
+    if steering.as_ref().is_some_and(|steering| steering["plan"].as_array().is_some_and(|plan| !plan.is_empty())) {
+        recommended_next_actions.push("Send each source its next prompt from the steering plan.".to_string());
+    }
+    if !request.constraints.is_empty() {
+        recommended_next_actions.push(format!(
+            "Verify recommendations against constraints: {}.",
+            request.constraints.join("; ")
+        ));
+    }
+    if recommended_next_actions.is_empty() {
+        recommended_next_actions.push("No immediate action required.".to_string());
+    }
+
+    let mut open_questions = missing
+        .iter()
+        .map(|(source, error, _)| format!("Missing source {}: {}", source.agent, error))
+        .collect::<Vec<String>>();
+    open_questions.extend(steer_questions);
+
+    let mut diffs = Vec::new();
+    for i in 0..if grading { 0 } else { successful.len() } {
+        for j in i + 1..successful.len() {
+            if contents[i] != contents[j] {
+                diffs.extend(crate::diff::unified(&successful_tags[i], &successful_tags[j], texts[i], texts[j]));
+            }
+        }
+    }
+
+    let mut report = json!({
+        "mode": request.mode,
+        "task": request.task,
+        "success_criteria": request.success_criteria,
+        "sources_used": successful
+            .iter()
+            .map(|(_, session, evidence)| format!("{} {}", evidence, session.source))
+            .collect::<Vec<String>>(),
+        "verdict": verdict,
+        "score": score,
+        "source_scores": source_scores,
+        "findings": findings,
+        "recommended_next_actions": recommended_next_actions,
+        "open_questions": open_questions,
+    });
+    if let Some(similarity) = similarity {
+        report["similarity"] = similarity;
+    }
+    if let Some(semantic) = semantic {
+        report["semantic"] = semantic;
+    }
+    if let Some(agreement) = agreement {
+        report["consensus"] = agreement;
+    }
+    if let Some(steering) = steering {
+        report["steering"] = steering;
+    }
+    if let Some((rubric_report, _)) = grades {
+        report["rubric"] = rubric_report;
+    }
+    if !diffs.is_empty() {
+        report["diffs"] = Value::Array(diffs);
+    }
+    if !errors.is_empty() {
+        report["errors"] = Value::Array(errors);
+    }
+    if !warnings.is_empty() {
+        report["warnings"] = Value::Array(warnings);
+    }
+    if !claim_results.is_empty() {
+        report["claims"] = Value::Array(claim_results);
+    }
+    if request.thresholds.is_set() {
+        report["thresholds"] = json!({
+            "fail_on": request.thresholds.fail_on,
+            "min_score": request.thresholds.min_score,
+            "passed": breaches.is_empty(),
+            "breaches": breaches,
+        });
+    }
+    if !request.history.is_empty() {
+        let mut chain = request.history.clone();
+        chain.push(chain_step(
+            chain.len() + 1,
+            None,
+            Some(&request.mode),
+            Some(&request.task),
+            verdict,
+            Some(score),
+            report["findings"].clone(),
+        ));
+        report["chain"] = Value::Array(chain);
+    }
+    report
+}
+
+/// Confidence multiplier for findings that cite a stale source.
+const STALE_CONFIDENCE_FACTOR: f64 = 0.8;
+
+/// Age gap, in hours, after which a source counts as stale
+/// (`BRIDGE_STALE_SOURCE_HOURS`, default 24; 0 disables the check).
+fn stale_after_hours() -> i64 {
+    std::env::var("BRIDGE_STALE_SOURCE_HOURS")
+        .ok()
+        .and_then(|raw| raw.trim().parse::<i64>().ok())
+        .map(|hours| hours.max(0))
+        .unwrap_or(24)
+}
+
+/// Commit time of HEAD in the repository containing `cwd`, if any.
+fn head_commit_millis(cwd: &str) -> Option<i64> {
+    let output = std::process::Command::new("git")
+        .args(["-C", cwd, "log", "-1", "--format=%ct"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let seconds = String::from_utf8_lossy(&output.stdout).trim().parse::<i64>().ok()?;
+    Some(seconds * 1000)
+}
+
+/// `(index, hours behind, reference)` for each source whose mtime trails the
+/// newest source, or HEAD when that is later, by at least `threshold_hours`.
+fn stale_sources(timestamps: &[Option<i64>], head_ms: Option<i64>, threshold_hours: i64) -> Vec<(usize, i64, &'static str)> {
+    let newest = timestamps.iter().flatten().copied().max();
+    let (reference_ms, reference) = match (newest, head_ms) {
+        (Some(newest), Some(head)) if head > newest => (head, "HEAD"),
+        (Some(newest), _) => (newest, "the newest source"),
+        (None, _) => return Vec::new(),
+    };
+    timestamps
+        .iter()
+        .enumerate()
+        .filter_map(|(index, timestamp)| {
+            let hours = (reference_ms - (*timestamp)?) / 3_600_000;
+            (hours >= threshold_hours).then_some((index, hours, reference))
+        })
+        .collect()
+}
+
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect()
+}
+
+/// Jaccard similarity of the word sets of every pair of outputs, rounded to
+/// two decimals so both CLIs produce identical matrices.
+fn similarity_matrix(contents: &[String]) -> Vec<Vec<f64>> {
+    let sets: Vec<HashSet<String>> = contents.iter().map(|text| word_set(text)).collect();
+    sets.iter()
+        .map(|left| {
+            sets.iter()
+                .map(|right| {
+                    let union = left.union(right).count();
+                    let similarity = if union == 0 {
+                        1.0
+                    } else {
+                        left.intersection(right).count() as f64 / union as f64
+                    };
+                    (similarity * 100.0).round() / 100.0
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The source that is less similar to every other source than any two of the
+/// others are to each other, if there is one.
+fn find_odd_one_out(matrix: &[Vec<f64>]) -> Option<usize> {
+    let count = matrix.len();
+    (0..count).find(|&odd| {
+        let closest = (0..count)
+            .filter(|&other| other != odd)
+            .map(|other| matrix[odd][other])
+            .fold(f64::MIN, f64::max);
+        let loosest = (0..count)
+            .flat_map(|i| (i + 1..count).map(move |j| (i, j)))
+            .filter(|&(i, j)| i != odd && j != odd)
+            .map(|(i, j)| matrix[i][j])
+            .fold(f64::MAX, f64::min);
+        closest < loosest
+    })
+}
+
+/// One P2 finding per pair of differing outputs, weighted by how far apart
+/// they are so near-agreeing pairs barely move the score, plus a P1 finding
+/// naming the odd one out.
+fn pairwise_findings(tags: &[String], contents: &[String], matrix: &[Vec<f64>], odd_one_out: Option<usize>) -> Vec<Value> {
+    let mut findings = Vec::new();
+    if let Some(odd) = odd_one_out {
+        let others = (0..tags.len()).filter(|&other| other != odd);
+        let mean = others.clone().map(|other| matrix[odd][other]).sum::<f64>() / others.count() as f64;
+        let mean = (mean * 100.0).round() / 100.0;
+        findings.push(json!({
+            "severity": "P1",
+            "summary": format!(
+                "Odd one out: {} diverges from every other source (mean similarity {:.2})",
+                tags[odd], mean
+            ),
+            "evidence": [tags[odd]],
+            "confidence": 0.75
+        }));
+    }
+    for i in 0..tags.len() {
+        for j in i + 1..tags.len() {
+            if contents[i] != contents[j] {
+                findings.push(json!({
+                    "severity": "P2",
+                    "summary": format!("Divergent outputs: {} vs {} (similarity {:.2})", tags[i], tags[j], matrix[i][j]),
+                    "evidence": [tags[i], tags[j]],
+                    "confidence": (0.75 * (1.0 - matrix[i][j]) * 100.0).round() / 100.0
+                }));
+            }
+        }
+    }
+    findings
+}
+
+/// Pair similarity at or above which two outputs count as agreeing for the
+/// consensus check.
+const CONSENSUS_THRESHOLD: f64 = 0.8;
+/// Sources beyond which the consensus check (exhaustive over subsets) is skipped.
+const MAX_CONSENSUS_SOURCES: usize = 16;
+
+/// The largest group of sources that all agree pairwise, as a finding and the
+/// `consensus` report object with per-pair evidence. Ties go to the group
+/// listed first.
+fn consensus(tags: &[String], contents: &[String], matrix: &[Vec<f64>]) -> Option<(Value, Value)> {
+    let count = tags.len();
+    if count > MAX_CONSENSUS_SOURCES {
+        return None;
+    }
+    let agree = |i: usize, j: usize| contents[i] == contents[j] || matrix[i][j] >= CONSENSUS_THRESHOLD;
+    let members = |mask: u32| (0..count).filter(move |&index| mask & (1 << index) != 0);
+    let mut best = 0u32;
+    for mask in 1u32..(1 << count) {
+        if mask.count_ones() > best.count_ones()
+            && members(mask).all(|i| members(mask).all(|j| i >= j || agree(i, j)))
+        {
+            best = mask;
+        }
+    }
+    let size = best.count_ones() as usize;
+    let majority = size >= 2 && size * 2 > count;
+    let agreeing: Vec<String> = if majority { members(best).map(|index| tags[index].clone()).collect() } else { Vec::new() };
+    let diverging: Vec<String> =
+        (0..count).filter(|&index| !majority || best & (1 << index) == 0).map(|index| tags[index].clone()).collect();
+
+    let (status, finding) = if size == count {
+        (
+            "unanimous",
+            json!({
+                "severity": "P3",
+                "summary": format!("Consensus: all {} sources agree", count),
+                "evidence": tags,
+                "confidence": 0.8
+            }),
+        )
+    } else if majority {
+        (
+            "majority",
+            json!({
+                "severity": "P2",
+                "summary": format!(
+                    "Majority: {} agree; {} {}",
+                    join_and(&agreeing),
+                    join_and(&diverging),
+                    if diverging.len() == 1 { "diverges" } else { "diverge" }
+                ),
+                "evidence": diverging,
+                "confidence": 0.75
+            }),
+        )
+    } else {
+        (
+            "none",
+            json!({
+                "severity": "P2",
+                "summary": format!("No consensus: no majority of the {} sources agree", count),
+                "evidence": tags,
+                "confidence": 0.6
+            }),
+        )
+    };
+    let pairs = (0..count)
+        .flat_map(|i| (i + 1..count).map(move |j| (i, j)))
+        .map(|(i, j)| json!({ "sources": [tags[i], tags[j]], "similarity": matrix[i][j], "agree": agree(i, j) }))
+        .collect::<Vec<_>>();
+    Some((finding, json!({ "status": status, "agreeing": agreeing, "diverging": diverging, "pairs": pairs })))
+}
+
+/// `a`, `a and b`, or `a, b and c`.
+fn join_and(items: &[String]) -> String {
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => items.join(""),
+    }
+}
+
+pub fn report_to_markdown(report: &Value) -> String {
+    let mut lines = Vec::new();
+    lines.push("### Agent Bridge Coordinator Report".to_string());
+    lines.push(String::new());
+    lines.push(format!("**Mode:** {}", report["mode"].as_str().unwrap_or("unknown")));
+    lines.push(format!("**Task:** {}", report["task"].as_str().unwrap_or("")));
+    lines.push("**Success Criteria:**".to_string());
+
+    if let Some(criteria) = report["success_criteria"].as_array() {
+        for criterion in criteria {
+            lines.push(format!("- {}", criterion.as_str().unwrap_or("")));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push("**Sources Used:**".to_string());
+    if let Some(sources) = report["sources_used"].as_array() {
+        for source in sources {
+            lines.push(format!("- {}", source.as_str().unwrap_or("")));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push(format!("**Verdict:** {}", report["verdict"].as_str().unwrap_or("")));
+    if let Some(score) = report["score"].as_i64() {
+        lines.push(format!("**Score:** {}/100", score));
+    }
+    if let Some(passed) = report["thresholds"]["passed"].as_bool() {
+        let breaches = report["thresholds"]["breaches"].as_array().into_iter().flatten().filter_map(|reason| reason.as_str());
+        lines.push(if passed {
+            "**Thresholds:** passed".to_string()
+        } else {
+            format!("**Thresholds:** failed ({})", breaches.collect::<Vec<_>>().join("; "))
+        });
+    }
+    if let (Some(sources), Some(matrix)) = (
+        report["similarity"]["sources"].as_array(),
+        report["similarity"]["matrix"].as_array(),
+    ) {
+        let labels: Vec<&str> = sources.iter().filter_map(|source| source.as_str()).collect();
+        lines.push(String::new());
+        lines.push("**Similarity Matrix:**".to_string());
+        lines.push(format!("| | {} |", labels.join(" | ")));
+        lines.push(format!("|---|{}", "---|".repeat(labels.len())));
+        for (label, row) in labels.iter().zip(matrix) {
+            let cells = row
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|cell| format!("{:.2}", cell.as_f64().unwrap_or(0.0)))
+                .collect::<Vec<_>>();
+            lines.push(format!("| {} | {} |", label, cells.join(" | ")));
+        }
+    }
+    if let Some(status) = report["consensus"]["status"].as_str() {
+        let list = |key: &str| {
+            report["consensus"][key].as_array().into_iter().flatten().filter_map(|tag| tag.as_str()).collect::<Vec<_>>().join(", ")
+        };
+        lines.push(String::new());
+        lines.push(match status {
+            "majority" => format!("**Consensus:** majority (agreeing: {}; diverging: {})", list("agreeing"), list("diverging")),
+            "unanimous" => "**Consensus:** unanimous".to_string(),
+            _ => "**Consensus:** none".to_string(),
+        });
+    }
+    if let (Some(criteria), Some(sources)) = (report["rubric"]["criteria"].as_array(), report["rubric"]["sources"].as_array()) {
+        let labels = sources.iter().map(|source| source["source"].as_str().unwrap_or("")).collect::<Vec<_>>();
+        lines.push(String::new());
+        lines.push("**Rubric:**".to_string());
+        lines.push(format!("| Criterion | Weight | {} |", labels.join(" | ")));
+        lines.push(format!("|---|---|{}", "---|".repeat(labels.len())));
+        for (position, criterion) in criteria.iter().enumerate() {
+            let cells = sources
+                .iter()
+                .map(|source| {
+                    let result = &source["criteria"][position];
+                    let coverage = result["coverage"].as_f64().unwrap_or(0.0);
+                    format!("{:.2} ({})", coverage, if result["met"] == true { "met" } else { "missed" })
+                })
+                .collect::<Vec<_>>();
+            lines.push(format!(
+                "| {} | {} | {} |",
+                criterion["criterion"].as_str().unwrap_or(""),
+                criterion["weight"].as_f64().unwrap_or(1.0),
+                cells.join(" | ")
+            ));
+        }
+        let totals = sources.iter().map(|source| source["score"].as_i64().unwrap_or(0).to_string()).collect::<Vec<_>>();
+        lines.push(format!("| **Score** | | {} |", totals.join(" | ")));
+    }
+    lines.push(String::new());
+    lines.push("**Findings:**".to_string());
+
+    if let Some(findings) = report["findings"].as_array() {
+        for finding in findings {
+            let severity = finding["severity"].as_str().unwrap_or("P2");
+            let summary = finding["summary"].as_str().unwrap_or("");
+            let confidence = finding["confidence"].as_f64().unwrap_or(0.0);
+            let evidence = finding["evidence"]
+                .as_array()
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            lines.push(format!(
+                "- **{}:** {} (evidence: {}; confidence: {:.2})",
+                severity, summary, evidence, confidence
+            ));
+        }
+    }
+
+    if let Some(plan) = report["steering"]["plan"].as_array() {
+        lines.push(String::new());
+        lines.push("**Steering Plan:**".to_string());
+        if plan.is_empty() {
+            lines.push("- No open questions or incomplete items found.".to_string());
+        }
+        for step in plan {
+            let sources = step["sources"].as_array().into_iter().flatten().filter_map(|s| s.as_str()).collect::<Vec<_>>();
+            lines.push(format!(
+                "{}. {}: {} ({})",
+                step["step"].as_u64().unwrap_or(0),
+                if step["action"] == "resolve" { "Resolve" } else { "Finish" },
+                step["item"].as_str().unwrap_or(""),
+                sources.join(", ")
+            ));
+        }
+        lines.push(String::new());
+        lines.push("**Next Prompts:**".to_string());
+        for source in report["steering"]["sources"].as_array().into_iter().flatten() {
+            lines.push(String::new());
+            lines.push(source["source"].as_str().unwrap_or("").to_string());
+            lines.push("```text".to_string());
+            lines.push(source["next_prompt"].as_str().unwrap_or("").to_string());
+            lines.push("```".to_string());
+        }
+    }
+
+    if let Some(diffs) = report["diffs"].as_array() {
+        lines.push(String::new());
+        lines.push("**Diffs:**".to_string());
+        for diff in diffs {
+            let sources = diff["sources"].as_array().into_iter().flatten().filter_map(|s| s.as_str()).collect::<Vec<_>>();
+            lines.push(String::new());
+            lines.push(format!(
+                "{} (+{} -{} lines{})",
+                sources.join(" vs "),
+                diff["added"].as_u64().unwrap_or(0),
+                diff["removed"].as_u64().unwrap_or(0),
+                if diff["truncated"].as_bool().unwrap_or(false) { ", truncated" } else { "" }
+            ));
+            lines.push("```diff".to_string());
+            lines.push(diff["unified"].as_str().unwrap_or("").trim_end().to_string());
+            lines.push("```".to_string());
+        }
+    }
+
+    lines.push(String::new());
+    lines.push("**Recommended Next Actions:**".to_string());
+    if let Some(actions) = report["recommended_next_actions"].as_array() {
+        for (index, action) in actions.iter().enumerate() {
+            lines.push(format!("{}. {}", index + 1, action.as_str().unwrap_or("")));
+        }
+    }
+
+    if let Some(open_questions) = report["open_questions"].as_array() {
+        if !open_questions.is_empty() {
+            lines.push(String::new());
+            lines.push("**Open Questions:**".to_string());
+            for question in open_questions {
+                lines.push(format!("- {}", question.as_str().unwrap_or("")));
+            }
+        }
+    }
+
+    if let Some(chain) = report["chain"].as_array() {
+        lines.push(String::new());
+        lines.push("**Handoff Chain:**".to_string());
+        for (index, step) in chain.iter().enumerate() {
+            let mut line = format!(
+                "{}. **{}**",
+                step["step"].as_u64().unwrap_or(index as u64 + 1),
+                step["verdict"].as_str().unwrap_or("")
+            );
+            if let Some(mode) = step["mode"].as_str() {
+                line.push_str(&format!(" ({})", mode));
+            }
+            if let Some(agent) = step["agent"].as_str() {
+                line.push_str(&format!(" by {}", agent));
+            }
+            if let Some(score) = step["score"].as_i64() {
+                line.push_str(&format!(", score {}/100", score));
+            }
+            lines.push(line);
+            // The last step is this report, whose findings are listed above.
+            if index + 1 < chain.len() {
+                for finding in step["findings"].as_array().into_iter().flatten() {
+                    lines.push(format!(
+                        "   - {}: {}",
+                        finding["severity"].as_str().unwrap_or("P2"),
+                        finding["summary"].as_str().unwrap_or("")
+                    ));
+                }
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn normalize_content(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn read_source(source: &SourceSpec, default_cwd: &str, cwd_match: CwdMatch, last_n: usize) -> Result<Session> {
+    let cwd = source.cwd.as_deref().unwrap_or(default_cwd);
+    let adapter = adapters::get_adapter(&source.agent)
+        .ok_or_else(|| BridgeErrorCode::UnsupportedAgent.error(format!("Unsupported agent: {}", source.agent)))?;
+    let id = source
+        .session_id
+        .as_deref()
+        .map(|value| aliases::resolve_id(&source.agent, value))
+        .transpose()?;
+    adapter.read_session(&ReadOptions {
+        id,
+        cwd: cwd.to_string(),
+        cwd_match,
+        chats_dir: source.chats_dir.clone(),
+        last_n,
+        ..ReadOptions::default()
+    })
+}
+
+fn evidence_tag(source: &SourceSpec) -> String {
+    let id = source
+        .session_id
+        .as_ref()
+        .map(|value| shorten(value))
+        .unwrap_or_else(|| {
+            if source.current_session {
+                "latest".to_string()
+            } else {
+                "unspecified".to_string()
+            }
+        });
+    format!("[{}:{}]", source.agent, id)
+}
+
+fn shorten(value: &str) -> String {
+    value.chars().take(8).collect()
+}
+
+/// Points a finding deducts from 100 at full confidence.
+fn severity_weight(severity: &str) -> f64 {
+    match severity {
+        "P0" => 60.0,
+        "P1" => 40.0,
+        "P2" => 15.0,
+        _ => 0.0,
+    }
+}
+
+/// Overall score: 100 minus each finding's severity weight scaled by its
+/// confidence, clamped to 0..=100. A source's score only counts findings that
+/// cite it as evidence.
+fn compute_scores(findings: &[Value], source_tags: &[String]) -> (i64, Vec<Value>) {
+    let penalty = |finding: &Value| {
+        severity_weight(finding["severity"].as_str().unwrap_or("")) * finding["confidence"].as_f64().unwrap_or(0.0)
+    };
+    let clamp = |deducted: f64| (100.0 - deducted).clamp(0.0, 100.0).round() as i64;
+
+    let overall = clamp(findings.iter().map(penalty).sum());
+    let per_source = source_tags
+        .iter()
+        .map(|tag| {
+            let deducted = findings
+                .iter()
+                .filter(|finding| {
+                    finding["evidence"]
+                        .as_array()
+                        .map(|evidence| evidence.iter().any(|e| e.as_str() == Some(tag.as_str())))
+                        .unwrap_or(false)
+                })
+                .map(penalty)
+                .sum();
+            json!({ "source": tag, "score": clamp(deducted) })
+        })
+        .collect();
+    (overall, per_source)
+}
+
+fn compute_verdict(
+    mode: &str,
+    missing: &[(SourceSpec, String, String)],
+    unique_contents: usize,
+    success_count: usize,
+    claims_failed: bool,
+) -> &'static str {
+    if success_count == 0 {
+        return "INCOMPLETE";
+    }
+
+    match mode {
+        "verify" => {
+            if missing.is_empty() && unique_contents <= 1 && !claims_failed {
+                "PASS"
+            } else {
+                "FAIL"
+            }
+        }
+        "steer" => "STEERING_PLAN_READY",
+        "analyze" => "ANALYSIS_COMPLETE",
+        "feedback" => "FEEDBACK_COMPLETE",
+        _ => "INCOMPLETE",
+    }
+}
+
+fn validate_mode(mode: &str) -> Result<()> {
+    match mode {
+        "verify" | "steer" | "analyze" | "feedback" => Ok(()),
+        _ => Err(BridgeErrorCode::UnsupportedMode.error(format!("Unsupported mode: {}", mode))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_report, compare_request, compute_scores, consensus, find_odd_one_out, parse_handoff, similarity_matrix, stale_sources,
+        validate_handoff, Thresholds,
+    };
+    use crate::agents::CwdMatch;
+
+    #[test]
+    fn unreadable_sources_are_listed_in_errors_next_to_the_report() {
+        let mut request = compare_request(&["codex:a1".to_string(), "claude:b2".to_string()], false, CwdMatch::Exact).unwrap();
+        for source in &mut request.sources {
+            source.agent = format!("{}-missing", source.agent);
+        }
+        let report = build_report(&request, "/nonexistent");
+        assert_eq!(report["errors"][0]["source"], "[codex-missing:a1]");
+        assert_eq!(report["errors"][1]["error_code"], "UNSUPPORTED_AGENT");
+        assert_eq!(report["errors"][1]["message"], "Unsupported agent: claude-missing");
+        assert_eq!(report["findings"][0]["summary"], "Source unavailable: codex-missing (Unsupported agent: codex-missing)");
+        assert!(report.get("warnings").is_none());
+    }
+
+    #[test]
+    fn handoff_history_becomes_the_report_chain_ending_with_this_step() {
+        let raw = r#"{"mode":"verify","task":"Confirm the relay verdict","success_criteria":["c"],
+            "sources":[{"agent":"codex","session_id":"a1"}],
+            "history":[{"agent":"codex","mode":"analyze","task":"Compare outputs","verdict":"INCOMPLETE","score":72,"extra":true}]}"#;
+        let mut request = parse_handoff(raw).unwrap();
+        request.sources[0].agent = "codex-missing".to_string();
+        let report = build_report(&request, "/nonexistent");
+        let chain = report["chain"].as_array().unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(
+            chain[0],
+            serde_json::json!({"step": 1, "agent": "codex", "mode": "analyze", "task": "Compare outputs", "verdict": "INCOMPLETE", "score": 72, "findings": []})
+        );
+        assert_eq!(chain[1]["step"], 2);
+        assert_eq!(chain[1]["mode"], "verify");
+        assert_eq!(chain[1]["task"], "Confirm the relay verdict");
+        assert_eq!(chain[1]["verdict"], report["verdict"]);
+        assert_eq!(chain[1]["findings"], report["findings"]);
+    }
+
+    #[test]
+    fn handoff_history_entries_need_a_verdict() {
+        let raw = r#"{"mode":"verify","task":"t","success_criteria":["c"],"sources":[{"agent":"codex","session_id":"a1"}],"history":[{"agent":"codex"}]}"#;
+        let error = parse_handoff(raw).unwrap_err();
+        assert_eq!(error.to_string(), "Each history entry must include string field: verdict");
+    }
+
+    #[test]
+    fn compare_last_n_widens_what_each_source_contributes() {
+        let root = std::env::temp_dir().join(format!("bridge-compare-last-{}", std::process::id()));
+        let mut sources = Vec::new();
+        for (name, plan) in [("a", "Use a mutex."), ("b", "Use a channel.")] {
+            let dir = root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            let chat = serde_json::json!({"sessionId": name, "messages": [
+                {"type": "user", "content": "Fix the race"},
+                {"type": "gemini", "content": plan},
+                {"type": "gemini", "content": "Done."},
+            ]});
+            std::fs::write(dir.join(format!("session-{}.json", name)), chat.to_string()).unwrap();
+            sources.push(dir.to_string_lossy().into_owned());
+        }
+        let mut request = compare_request(&["gemini".to_string(), "gemini".to_string()], false, CwdMatch::Exact).unwrap();
+        for (source, dir) in request.sources.iter_mut().zip(&sources) {
+            source.chats_dir = Some(dir.clone());
+        }
+        let latest = build_report(&request, "/nonexistent");
+        request.last_n = usize::MAX;
+        let full = build_report(&request, "/nonexistent");
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(latest["findings"][0]["summary"], "All available agent outputs are aligned");
+        assert_eq!(full["findings"][0]["summary"], "Divergent agent outputs detected");
+    }
+
+    #[test]
+    fn validate_handoff_reports_every_problem_with_its_path() {
+        let raw = r#"{"mode":"review","task":"","success_criteria":[],"sources":[{"agent":"notepad"},{"agent":"codex","current_session":true}],"extra":1}"#;
+        let issues = validate_handoff(raw)
+            .into_iter()
+            .map(|issue| (issue.path, issue.code))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            vec![
+                ("$.extra".to_string(), "UNEXPECTED_FIELD"),
+                ("$.mode".to_string(), "BAD_MODE"),
+                ("$.task".to_string(), "EMPTY"),
+                ("$.success_criteria".to_string(), "EMPTY"),
+                ("$.sources[0].agent".to_string(), "BAD_AGENT"),
+                ("$.sources[0]".to_string(), "MISSING_SESSION"),
+            ]
+        );
+        assert!(validate_handoff(r#"{"mode":"verify","task":"t","success_criteria":["c"],"sources":[{"agent":"claude","session_id":"abc"}]}"#).is_empty());
+    }
+
+    #[test]
+    fn similarity_matrix_singles_out_the_dissenting_source() {
+        let contents = ["Use a mutex here.", "use a Mutex here", "Use a channel instead."].map(String::from);
+        let matrix = similarity_matrix(&contents);
+        assert_eq!(matrix[0], vec![1.0, 1.0, 0.33]);
+        assert_eq!(find_odd_one_out(&matrix), Some(2));
+
+        let evenly_split = similarity_matrix(&["a b", "b c", "c a"].map(String::from));
+        assert_eq!(find_odd_one_out(&evenly_split), None);
+    }
+
+    #[test]
+    fn consensus_names_the_majority_and_the_dissenters() {
+        let tags = ["[codex:a]", "[gemini:b]", "[claude:c]"].map(String::from);
+        let contents = ["Use a mutex here.", "Use a channel instead.", "use a Mutex here"].map(String::from);
+        let (finding, agreement) = consensus(&tags, &contents, &similarity_matrix(&contents)).unwrap();
+        assert_eq!(finding["summary"], "Majority: [codex:a] and [claude:c] agree; [gemini:b] diverges");
+        assert_eq!(finding["evidence"], serde_json::json!(["[gemini:b]"]));
+        assert_eq!(agreement["status"], "majority");
+        assert_eq!(agreement["pairs"][1]["agree"], true);
+
+        let split = ["a b", "b c", "c a"].map(String::from);
+        let (finding, agreement) = consensus(&tags, &split, &similarity_matrix(&split)).unwrap();
+        assert_eq!(finding["summary"], "No consensus: no majority of the 3 sources agree");
+        assert_eq!(agreement["diverging"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn scores_deduct_severity_weighted_by_confidence_per_source() {
+        let findings = vec![
+            serde_json::json!({"severity": "P1", "confidence": 0.5, "evidence": ["codex"]}),
+            serde_json::json!({"severity": "P2", "confidence": 1.0, "evidence": ["codex", "claude"]}),
+            serde_json::json!({"severity": "P0", "confidence": 1.0, "evidence": ["gemini"]}),
+            serde_json::json!({"severity": "P0", "confidence": 1.0, "evidence": ["gemini"]}),
+        ];
+        let tags = ["codex", "claude", "gemini"].map(String::from);
+        let (overall, per_source) = compute_scores(&findings, &tags);
+        assert_eq!(overall, 0);
+        let scores = per_source.iter().map(|entry| entry["score"].as_i64().unwrap()).collect::<Vec<_>>();
+        assert_eq!(scores, [65, 85, 0]);
+    }
+
+    #[test]
+    fn thresholds_breach_on_severity_at_or_above_fail_on_and_low_scores() {
+        let findings = [serde_json::json!({"severity": "P2"}), serde_json::json!({"severity": "P3"})];
+        let thresholds = Thresholds { fail_on: Some("P2".to_string()), min_score: Some(80) };
+        assert_eq!(thresholds.breaches(&findings, 75), vec!["1 finding(s) at P2 or worse", "score 75 is below 80"]);
+        let strict = Thresholds { fail_on: Some("P1".to_string()), min_score: None };
+        assert!(strict.breaches(&findings, 0).is_empty());
+
+        let raw = r#"{"mode":"analyze","task":"t","success_criteria":["c"],"sources":[{"agent":"codex","current_session":true}],"thresholds":{"fail_on":"P5","min_score":101}}"#;
+        let codes = validate_handoff(raw).into_iter().map(|issue| issue.code).collect::<Vec<_>>();
+        assert_eq!(codes, vec!["BAD_THRESHOLD", "BAD_THRESHOLD"]);
+    }
+
+    #[test]
+    fn stale_sources_measure_against_the_later_of_newest_source_and_head() {
+        let hour = 3_600_000;
+        let timestamps = [Some(100 * hour), Some(70 * hour), None];
+        assert_eq!(stale_sources(&timestamps, None, 24), vec![(1, 30, "the newest source")]);
+        assert_eq!(
+            stale_sources(&timestamps, Some(130 * hour), 24),
+            vec![(0, 30, "HEAD"), (1, 60, "HEAD")]
+        );
+        assert!(stale_sources(&timestamps, Some(50 * hour), 48).is_empty());
+    }
+}
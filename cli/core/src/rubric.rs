@@ -0,0 +1,260 @@
+//! Rubrics a `feedback` handoff grades each source's reply against. Each
+//! criterion is matched through its `keywords`, or through the content words
+//! of its text when it has none: its coverage in a reply is the share of
+//! those terms the reply mentions, and it counts as met at half or more.
+//! A source's score is the weight-averaged coverage. The Node CLI mirrors
+//! this in `scripts/read_session.cjs`.
+
+use crate::report::HandoffIssue;
+use crate::semantic::STOPWORDS;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+/// Coverage at or above which a reply meets a criterion.
+const MET_COVERAGE: f64 = 0.5;
+
+/// One entry of a handoff's `rubric` array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Criterion {
+    pub criterion: String,
+    pub weight: f64,
+    pub keywords: Vec<String>,
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect()
+}
+
+/// Distinct words of `text` other than stopwords.
+fn content_words(text: &str) -> Vec<String> {
+    let mut content = Vec::new();
+    for word in words(text) {
+        if !STOPWORDS.contains(&word.as_str()) && !content.contains(&word) {
+            content.push(word);
+        }
+    }
+    content
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl Criterion {
+    pub fn parse(value: &Value) -> Result<Criterion> {
+        if !value.is_object() {
+            return Err(anyhow!("Each rubric entry must be an object"));
+        }
+        let criterion = match value["criterion"].as_str() {
+            Some(text) if !text.trim().is_empty() => text.trim().to_string(),
+            _ => return Err(anyhow!("Each rubric entry must include string field: criterion")),
+        };
+        let weight = match value.get("weight") {
+            None => 1.0,
+            Some(weight) => weight
+                .as_f64()
+                .filter(|weight| *weight > 0.0)
+                .ok_or_else(|| anyhow!("Rubric weight must be a positive number"))?,
+        };
+        let keywords = match value.get("keywords") {
+            None => Vec::new(),
+            Some(Value::Array(keywords)) => keywords
+                .iter()
+                .map(|keyword| match keyword.as_str() {
+                    Some(text) if !words(text).is_empty() => Ok(text.trim().to_string()),
+                    _ => Err(anyhow!("Rubric keywords must be non-empty strings")),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Some(_) => return Err(anyhow!("Rubric keywords must be an array")),
+        };
+        let parsed = Criterion { criterion, weight, keywords };
+        if parsed.terms().is_empty() {
+            return Err(anyhow!("Rubric criterion has no words to match; add keywords: {}", parsed.criterion));
+        }
+        Ok(parsed)
+    }
+
+    /// What a reply must mention: the keywords, or else the criterion's
+    /// content words, each as lowercase words joined by single spaces.
+    fn terms(&self) -> Vec<String> {
+        if self.keywords.is_empty() {
+            return content_words(&self.criterion);
+        }
+        let mut terms = Vec::new();
+        for keyword in &self.keywords {
+            let term = words(keyword).join(" ");
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        terms
+    }
+}
+
+/// Grade every source against `rubric`: per-criterion findings, the report's
+/// `rubric` object, each source's score, and next actions for sources that
+/// missed criteria.
+pub(crate) fn grade(rubric: &[Criterion], tags: &[String], texts: &[&str]) -> (Vec<Value>, Value, Vec<i64>, Vec<String>) {
+    let padded = texts.iter().map(|text| format!(" {} ", words(text).join(" "))).collect::<Vec<_>>();
+    let total_weight = rubric.iter().map(|criterion| criterion.weight).sum::<f64>();
+    // met[source][criterion]
+    let mut met = vec![Vec::new(); tags.len()];
+    let mut sources = Vec::new();
+    let mut scores = Vec::new();
+    for (index, tag) in tags.iter().enumerate() {
+        let mut weighted = 0.0;
+        let mut results = Vec::new();
+        for criterion in rubric {
+            let (matched, missing): (Vec<String>, Vec<String>) =
+                criterion.terms().into_iter().partition(|term| padded[index].contains(&format!(" {} ", term)));
+            let coverage = round2(matched.len() as f64 / (matched.len() + missing.len()) as f64);
+            weighted += criterion.weight * coverage;
+            met[index].push(coverage >= MET_COVERAGE);
+            results.push(json!({
+                "criterion": criterion.criterion,
+                "coverage": coverage,
+                "met": coverage >= MET_COVERAGE,
+                "matched": matched,
+                "missing": missing,
+            }));
+        }
+        let score = (100.0 * weighted / total_weight).round() as i64;
+        scores.push(score);
+        sources.push(json!({ "source": tag, "score": score, "criteria": results }));
+    }
+
+    let mut findings = Vec::new();
+    for (position, criterion) in rubric.iter().enumerate() {
+        let (mut meeting, mut missing) = (Vec::new(), Vec::new());
+        for (index, tag) in tags.iter().enumerate() {
+            if met[index][position] {
+                meeting.push(tag.as_str());
+            } else {
+                missing.push(tag.as_str());
+            }
+        }
+        let (severity, summary, evidence) = if missing.is_empty() {
+            ("P3", format!("Rubric: every source meets \"{}\"", criterion.criterion), meeting)
+        } else if meeting.is_empty() {
+            ("P1", format!("Rubric: no source meets \"{}\"", criterion.criterion), missing)
+        } else {
+            (
+                "P2",
+                format!(
+                    "Rubric: \"{}\" met by {}; missed by {}",
+                    criterion.criterion,
+                    meeting.join(", "),
+                    missing.join(", ")
+                ),
+                missing,
+            )
+        };
+        findings.push(json!({ "severity": severity, "summary": summary, "evidence": evidence, "confidence": 0.7 }));
+    }
+
+    let actions = tags
+        .iter()
+        .enumerate()
+        .filter_map(|(index, tag)| {
+            let missed = rubric
+                .iter()
+                .zip(&met[index])
+                .filter(|(_, met)| !**met)
+                .map(|(criterion, _)| criterion.criterion.as_str())
+                .collect::<Vec<_>>();
+            (!missed.is_empty()).then(|| format!("Ask {} to cover: {}.", tag, missed.join("; ")))
+        })
+        .collect();
+
+    let score = if scores.is_empty() { 0 } else { (scores.iter().sum::<i64>() as f64 / scores.len() as f64).round() as i64 };
+    let criteria = rubric
+        .iter()
+        .map(|criterion| json!({ "criterion": criterion.criterion, "weight": criterion.weight, "terms": criterion.terms() }))
+        .collect::<Vec<_>>();
+    (findings, json!({ "score": score, "criteria": criteria, "sources": sources }), scores, actions)
+}
+
+/// Report every problem with the rubric entry at `path` for `bridge handoff validate`.
+pub(crate) fn validate(path: &str, value: &Value, issues: &mut Vec<HandoffIssue>) {
+    let Some(object) = value.as_object() else {
+        issues.push(HandoffIssue::new(path, "WRONG_TYPE", "Each rubric entry must be an object"));
+        return;
+    };
+    for key in object.keys().filter(|key| !["criterion", "weight", "keywords"].contains(&key.as_str())) {
+        issues.push(HandoffIssue::new(format!("{}.{}", path, key), "UNEXPECTED_FIELD", format!("Unexpected field: {}", key)));
+    }
+    match object.get("criterion") {
+        None => issues.push(HandoffIssue::new(
+            format!("{}.criterion", path),
+            "MISSING_FIELD",
+            "Each rubric entry must include string field: criterion",
+        )),
+        Some(Value::String(text)) if text.trim().is_empty() => {
+            issues.push(HandoffIssue::new(format!("{}.criterion", path), "EMPTY", "criterion must not be empty"))
+        }
+        Some(Value::String(text)) => {
+            let has_keywords = object.get("keywords").and_then(Value::as_array).is_some_and(|keywords| !keywords.is_empty());
+            if !has_keywords && content_words(text).is_empty() {
+                issues.push(HandoffIssue::new(
+                    format!("{}.criterion", path),
+                    "BAD_RUBRIC",
+                    "criterion has no words to match; add keywords",
+                ));
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new(format!("{}.criterion", path), "WRONG_TYPE", "criterion must be a string")),
+    }
+    match object.get("weight") {
+        None => {}
+        Some(weight) if weight.as_f64().is_some_and(|weight| weight > 0.0) => {}
+        Some(weight) if weight.is_number() => {
+            issues.push(HandoffIssue::new(format!("{}.weight", path), "BAD_RUBRIC", "weight must be positive"))
+        }
+        Some(_) => issues.push(HandoffIssue::new(format!("{}.weight", path), "WRONG_TYPE", "weight must be a number")),
+    }
+    match object.get("keywords") {
+        None => {}
+        Some(Value::Array(keywords)) => {
+            for (index, keyword) in keywords.iter().enumerate() {
+                if !keyword.as_str().is_some_and(|text| !words(text).is_empty()) {
+                    issues.push(HandoffIssue::new(
+                        format!("{}.keywords[{}]", path, index),
+                        "BAD_RUBRIC",
+                        "keywords must be non-empty strings",
+                    ));
+                }
+            }
+        }
+        Some(_) => issues.push(HandoffIssue::new(format!("{}.keywords", path), "WRONG_TYPE", "keywords must be an array")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{grade, Criterion};
+    use serde_json::json;
+
+    #[test]
+    fn rubric_scores_each_source_by_weighted_keyword_coverage() {
+        let rubric = [
+            json!({"criterion": "Names the root cause", "weight": 3, "keywords": ["race condition", "mutex"]}),
+            json!({"criterion": "Mentions regression tests"}),
+        ]
+        .iter()
+        .map(|entry| Criterion::parse(entry).unwrap())
+        .collect::<Vec<_>>();
+        let tags = ["[codex:a]", "[claude:b]"].map(String::from);
+        let texts = ["A race condition in the cache; guard it with a Mutex.", "Add regression tests for the cache."];
+
+        let (findings, rubric_json, scores, actions) = grade(&rubric, &tags, &texts);
+        assert_eq!(scores, vec![75, 17]);
+        assert_eq!(rubric_json["score"], 46);
+        assert_eq!(rubric_json["criteria"][1]["terms"], json!(["mentions", "regression", "tests"]));
+        assert_eq!(findings[0]["summary"], "Rubric: \"Names the root cause\" met by [codex:a]; missed by [claude:b]");
+        assert_eq!(actions, vec!["Ask [codex:a] to cover: Mentions regression tests.", "Ask [claude:b] to cover: Names the root cause."]);
+        assert!(Criterion::parse(&json!({"criterion": "is it", "weight": 1})).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! Full-text search index for `bridge index build` and `search --indexed`.
+//! Every session's transcript is tokenized into an inverted index stored as
+//! `fts.json` in the cache directory; queries rank sessions with BM25 and
+//! re-read only the returned sessions to cut a snippet. The index is a
+//! snapshot: sessions written after `index build` are not found until the
+//! next build.
+
+use crate::adapters;
+use crate::agents::{self, CwdMatch};
+use crate::config;
+use crate::utils::normalize_path;
+use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INDEX_VERSION: u64 = 1;
+/// BM25 term-frequency saturation and length normalization.
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+/// Characters of context kept on each side of a snippet's first match.
+const SNIPPET_CONTEXT: usize = 60;
+
+pub fn index_path() -> PathBuf {
+    config::cache_dir().join("fts.json")
+}
+
+struct Document {
+    agent: String,
+    session_id: Option<String>,
+    cwd: Option<String>,
+    modified_at: Option<String>,
+    file_path: String,
+    length: usize,
+}
+
+pub struct BuildSummary {
+    pub sessions: usize,
+    pub terms: usize,
+    pub path: PathBuf,
+}
+
+/// Index every session `list` can see for `agents`, replacing any previous
+/// index. An agent whose store cannot be read is skipped with a warning.
+pub fn build(agents: &[&str]) -> Result<BuildSummary> {
+    let mut sessions = Vec::new();
+    for agent in agents {
+        let Some(adapter) = adapters::get_adapter(agent) else {
+            continue;
+        };
+        match adapter.list_sessions(None, CwdMatch::Exact, usize::MAX) {
+            Ok(entries) => sessions.extend(entries.into_iter().map(|entry| (*agent, entry))),
+            Err(err) => eprintln!("Warning: skipped {} sessions: {:#}", agent, err),
+        }
+    }
+
+    let indexed = sessions
+        .par_iter()
+        .filter_map(|(agent, entry)| {
+            let file_path = entry["file_path"].as_str()?;
+            let terms = term_counts(&session_text(agent, file_path, entry["session_id"].as_str())?);
+            let document = Document {
+                agent: agent.to_string(),
+                session_id: entry["session_id"].as_str().map(str::to_string),
+                cwd: entry["cwd"].as_str().map(str::to_string),
+                modified_at: entry["modified_at"].as_str().map(str::to_string),
+                file_path: file_path.to_string(),
+                length: terms.values().sum(),
+            };
+            Some((document, terms))
+        })
+        .collect::<Vec<_>>();
+
+    let mut documents = Vec::with_capacity(indexed.len());
+    let mut postings: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+    for (doc, (document, terms)) in indexed.into_iter().enumerate() {
+        documents.push(document);
+        for (term, count) in terms {
+            postings.entry(term).or_default().push((doc, count));
+        }
+    }
+
+    let path = index_path();
+    let summary = BuildSummary { sessions: documents.len(), terms: postings.len(), path: path.clone() };
+    let root = json!({
+        "version": INDEX_VERSION,
+        "documents": documents.iter().map(document_to_json).collect::<Vec<_>>(),
+        "postings": postings
+            .into_iter()
+            .map(|(term, list)| (term, json!(list.into_iter().map(|(doc, count)| [doc, count]).collect::<Vec<_>>())))
+            .collect::<serde_json::Map<_, _>>(),
+    });
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, root.to_string()).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(summary)
+}
+
+/// Rank indexed sessions for `query`, best first. Only sessions from `agents`
+/// whose cwd matches `cwd` (when given) are considered.
+pub fn search(query: &str, agents: &[&str], cwd: Option<&str>, cwd_match: CwdMatch, limit: usize) -> Result<Vec<Value>> {
+    let path = index_path();
+    let text = fs::read_to_string(&path)
+        .map_err(|_| anyhow!("Search index not found: {} (run `bridge index build`)", path.display()))?;
+    let root: Value = serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+    if root["version"].as_u64() != Some(INDEX_VERSION) {
+        return Err(anyhow!("Search index at {} is from another version; run `bridge index build`", path.display()));
+    }
+    let documents = root["documents"].as_array().map(|docs| docs.iter().map(document_from_json).collect::<Vec<_>>()).unwrap_or_default();
+    if documents.is_empty() {
+        return Ok(Vec::new());
+    }
+    let expected_cwd = cwd.map(normalize_path).transpose()?;
+    let eligible = documents
+        .iter()
+        .map(|document| {
+            agents.contains(&document.agent.as_str())
+                && expected_cwd.as_ref().map_or(true, |expected| {
+                    document.cwd.as_ref().is_some_and(|doc_cwd| cwd_match.matches(Path::new(doc_cwd), expected))
+                })
+        })
+        .collect::<Vec<_>>();
+
+    let total = documents.len() as f64;
+    let average_length = (documents.iter().map(|document| document.length).sum::<usize>() as f64 / total).max(1.0);
+    let query_terms = term_counts(query).into_keys().collect::<Vec<_>>();
+    let mut scores: HashMap<usize, f64> = HashMap::new();
+    for term in &query_terms {
+        let Some(list) = root["postings"][term].as_array() else {
+            continue;
+        };
+        let frequency = list.len() as f64;
+        let idf = ((total - frequency + 0.5) / (frequency + 0.5) + 1.0).ln();
+        for posting in list {
+            let (Some(doc), Some(count)) = (posting[0].as_u64(), posting[1].as_u64()) else {
+                continue;
+            };
+            let doc = doc as usize;
+            if !eligible.get(doc).copied().unwrap_or(false) {
+                continue;
+            }
+            let count = count as f64;
+            let norm = 1.0 - B + B * documents[doc].length as f64 / average_length;
+            *scores.entry(doc).or_default() += idf * count * (K1 + 1.0) / (count + K1 * norm);
+        }
+    }
+
+    let mut ranked = scores.into_iter().collect::<Vec<_>>();
+    ranked.sort_by(|(a_doc, a), (b_doc, b)| {
+        b.total_cmp(a).then_with(|| documents[*b_doc].modified_at.cmp(&documents[*a_doc].modified_at))
+    });
+    Ok(ranked
+        .into_iter()
+        .filter(|(doc, _)| Path::new(&documents[*doc].file_path).exists())
+        .take(limit)
+        .map(|(doc, score)| {
+            let document = &documents[doc];
+            let snippet = session_text(&document.agent, &document.file_path, document.session_id.as_deref())
+                .and_then(|text| snippet(&text, &query_terms));
+            json!({
+                "session_id": document.session_id,
+                "agent": document.agent,
+                "cwd": document.cwd,
+                "modified_at": document.modified_at,
+                "file_path": document.file_path,
+                "score": (score * 1000.0).round() / 1000.0,
+                "snippet": snippet,
+            })
+        })
+        .collect())
+}
+
+/// The redacted transcript of a session file, one message per line block.
+fn session_text(agent: &str, file_path: &str, session_id: Option<&str>) -> Option<String> {
+    let meta = fs::metadata(file_path).ok()?;
+    if meta.len() > adapters::common::max_file_size() {
+        return None;
+    }
+    let bytes = fs::read(file_path).ok()?;
+    let messages = agents::transcript(agent, &bytes, session_id);
+    Some(messages.into_iter().map(|message| message.text).collect::<Vec<_>>().join("\n\n"))
+}
+
+/// Lowercased alphanumeric words of two or more characters, with counts.
+fn term_counts(text: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for word in text.split(|ch: char| !(ch.is_alphanumeric() || ch == '_')) {
+        if word.chars().nth(1).is_none() || word.len() > 64 {
+            continue;
+        }
+        *counts.entry(word.to_lowercase()).or_default() += 1;
+    }
+    counts
+}
+
+/// One line of context around the first occurrence of any query term.
+fn snippet(text: &str, terms: &[String]) -> Option<String> {
+    let lower = text.to_lowercase();
+    // Lowercasing can change byte lengths; only cut when offsets still line up.
+    let lower = if lower.len() == text.len() { lower } else { text.to_string() };
+    let start = terms.iter().filter_map(|term| lower.find(term.as_str())).min()?;
+    let from = floor_boundary(text, start.saturating_sub(SNIPPET_CONTEXT));
+    let to = floor_boundary(text, (start + SNIPPET_CONTEXT * 2).min(text.len()));
+    let body = text[from..to].split_whitespace().collect::<Vec<_>>().join(" ");
+    let lead = if from > 0 { "…" } else { "" };
+    let tail = if to < text.len() { "…" } else { "" };
+    Some(format!("{}{}{}", lead, body, tail))
+}
+
+fn floor_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn document_to_json(document: &Document) -> Value {
+    json!({
+        "agent": document.agent,
+        "session_id": document.session_id,
+        "cwd": document.cwd,
+        "modified_at": document.modified_at,
+        "file_path": document.file_path,
+        "length": document.length,
+    })
+}
+
+fn document_from_json(value: &Value) -> Document {
+    let text = |key: &str| value[key].as_str().map(str::to_string);
+    Document {
+        agent: text("agent").unwrap_or_default(),
+        session_id: text("session_id"),
+        cwd: text("cwd"),
+        modified_at: text("modified_at"),
+        file_path: text("file_path").unwrap_or_default(),
+        length: value["length"].as_u64().unwrap_or(0) as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{snippet, term_counts};
+
+    #[test]
+    fn tokenizes_and_cuts_snippets_around_matches() {
+        let counts = term_counts("Fix the flaky_test; the TEST passed. a b");
+        assert_eq!(counts.get("the"), Some(&2));
+        assert_eq!(counts.get("flaky_test"), Some(&1));
+        assert_eq!(counts.get("test"), Some(&1));
+        assert!(!counts.contains_key("a"));
+
+        let text = format!("{} the timeout was\nraised {}", "x".repeat(100), "y".repeat(200));
+        let cut = snippet(&text, &["timeout".to_string()]).unwrap();
+        assert!(cut.starts_with('…') && cut.ends_with('…'));
+        assert!(cut.contains("the timeout was raised"));
+        assert_eq!(snippet("short note", &["note".to_string()]).as_deref(), Some("short note"));
+        assert!(snippet("nothing here", &["absent".to_string()]).is_none());
+    }
+}
@@ -0,0 +1,227 @@
+//! Sentence-level comparison for `compare --semantic`. Each output is split
+//! into sentences, and each sentence is matched to its closest counterpart in
+//! the other output by word overlap, ignoring stopwords and negations.
+//! A matched sentence either agrees with its counterpart or contradicts it
+//! (one side negated); an unmatched one is unique to its source. The Node
+//! CLI mirrors this in `scripts/read_session.cjs` so both produce identical
+//! reports.
+
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// Word overlap at which two sentences count as saying the same thing.
+const MATCH_THRESHOLD: f64 = 0.5;
+/// Pair similarity at or above which two outputs agree in substance.
+const ALIGNED_THRESHOLD: f64 = 0.8;
+/// Contradiction findings reported per pair.
+const MAX_CONTRADICTION_FINDINGS: usize = 3;
+
+pub(crate) const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "is", "are", "was", "were", "be", "been", "being", "to", "of", "and", "or", "in", "on", "for",
+    "with", "it", "its", "this", "that", "these", "those", "as", "at", "by", "from", "i", "we", "you", "they", "he",
+    "she", "should", "will", "would", "can", "could", "do", "does", "did", "has", "have", "had", "so", "but", "if",
+    "then", "there", "here", "also", "just", "which", "what",
+];
+
+/// Negation words; `t` is what remains of contractions such as "isn't".
+const NEGATIONS: &[&str] = &["not", "no", "never", "cannot", "none", "nor", "neither", "without", "t"];
+
+struct Sentence {
+    text: String,
+    words: HashSet<String>,
+    negated: bool,
+}
+
+/// Sentences of `text`: lines split after `.`, `!`, or `?` followed by
+/// whitespace. Sentences with no content words are dropped.
+fn sentences(text: &str) -> Vec<Sentence> {
+    let mut pieces = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((index, ch)) = chars.next() {
+            let ends_sentence = matches!(ch, '.' | '!' | '?') && chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+            if ends_sentence {
+                pieces.push(&line[start..index + ch.len_utf8()]);
+                while chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+                    chars.next();
+                }
+                start = chars.peek().map_or(line.len(), |(next, _)| *next);
+            }
+        }
+        pieces.push(&line[start..]);
+    }
+    pieces
+        .into_iter()
+        .filter_map(|piece| {
+            let text = piece.trim();
+            let tokens = text
+                .split(|ch: char| !ch.is_ascii_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(|word| word.to_ascii_lowercase())
+                .collect::<Vec<_>>();
+            let negations = tokens.iter().filter(|word| NEGATIONS.contains(&word.as_str())).count();
+            let words = tokens
+                .into_iter()
+                .filter(|word| !STOPWORDS.contains(&word.as_str()) && !NEGATIONS.contains(&word.as_str()))
+                .collect::<HashSet<_>>();
+            (!words.is_empty()).then(|| Sentence { text: text.to_string(), words, negated: negations % 2 == 1 })
+        })
+        .collect()
+}
+
+fn jaccard(left: &HashSet<String>, right: &HashSet<String>) -> f64 {
+    left.intersection(right).count() as f64 / left.union(right).count() as f64
+}
+
+/// The closest sentence in `others` to `sentence` and its overlap.
+fn best_match<'a>(sentence: &Sentence, others: &'a [Sentence]) -> Option<(f64, &'a Sentence)> {
+    let mut best: Option<(f64, &Sentence)> = None;
+    for other in others {
+        let score = jaccard(&sentence.words, &other.words);
+        if best.map_or(true, |(best_score, _)| score > best_score) {
+            best = Some((score, other));
+        }
+    }
+    best
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// How two outputs relate sentence by sentence.
+pub(crate) struct PairComparison {
+    pub similarity: f64,
+    pub agreements: usize,
+    pub contradictions: Vec<(String, String)>,
+    pub only_left: Vec<String>,
+    pub only_right: Vec<String>,
+}
+
+pub(crate) fn compare_pair(left: &str, right: &str) -> PairComparison {
+    let (left, right) = (sentences(left), sentences(right));
+    let mut total = 0.0;
+    let mut agreements = 0;
+    let mut contradictions = Vec::new();
+    let mut only_left = Vec::new();
+    for sentence in &left {
+        match best_match(sentence, &right) {
+            Some((score, other)) if score >= MATCH_THRESHOLD => {
+                total += score;
+                if sentence.negated == other.negated {
+                    agreements += 1;
+                } else {
+                    contradictions.push((sentence.text.clone(), other.text.clone()));
+                }
+            }
+            best => {
+                total += best.map_or(0.0, |(score, _)| score);
+                only_left.push(sentence.text.clone());
+            }
+        }
+    }
+    let mut only_right = Vec::new();
+    for sentence in &right {
+        let score = best_match(sentence, &left).map_or(0.0, |(score, _)| score);
+        total += score;
+        if score < MATCH_THRESHOLD {
+            only_right.push(sentence.text.clone());
+        }
+    }
+    let count = left.len() + right.len();
+    PairComparison {
+        similarity: if count == 0 { 1.0 } else { round2(total / count as f64) },
+        agreements,
+        contradictions,
+        only_left,
+        only_right,
+    }
+}
+
+/// Findings and the `semantic` report object for every pair of `contents`,
+/// labelled by `tags`. Also returns whether any pair contradicts and whether
+/// any falls short of agreeing in substance.
+pub(crate) fn pairwise(tags: &[String], contents: &[String]) -> (Vec<Value>, Value, bool, bool) {
+    let mut findings = Vec::new();
+    let mut pairs = Vec::new();
+    let (mut any_contradiction, mut any_divergent) = (false, false);
+    for i in 0..tags.len() {
+        for j in i + 1..tags.len() {
+            let pair = compare_pair(&contents[i], &contents[j]);
+            let evidence = json!([tags[i], tags[j]]);
+            if !pair.contradictions.is_empty() {
+                any_contradiction = true;
+                for (left, right) in pair.contradictions.iter().take(MAX_CONTRADICTION_FINDINGS) {
+                    findings.push(json!({
+                        "severity": "P1",
+                        "summary": format!("Contradiction: {} says \"{}\" but {} says \"{}\"", tags[i], left, tags[j], right),
+                        "evidence": evidence,
+                        "confidence": 0.7
+                    }));
+                }
+            } else if pair.similarity >= ALIGNED_THRESHOLD {
+                findings.push(json!({
+                    "severity": "P3",
+                    "summary": format!(
+                        "{} and {} agree in substance (semantic similarity {:.2}, {} matching sentence(s))",
+                        tags[i], tags[j], pair.similarity, pair.agreements
+                    ),
+                    "evidence": evidence,
+                    "confidence": 0.8
+                }));
+            } else {
+                any_divergent = true;
+                findings.push(json!({
+                    "severity": "P2",
+                    "summary": format!(
+                        "{} and {} partially agree (semantic similarity {:.2}): {} matching, {} only in {}, {} only in {}",
+                        tags[i],
+                        tags[j],
+                        pair.similarity,
+                        pair.agreements,
+                        pair.only_left.len(),
+                        tags[i],
+                        pair.only_right.len(),
+                        tags[j]
+                    ),
+                    "evidence": evidence,
+                    "confidence": round2(0.75 * (1.0 - pair.similarity))
+                }));
+            }
+            pairs.push(json!({
+                "sources": [tags[i], tags[j]],
+                "similarity": pair.similarity,
+                "agreements": pair.agreements,
+                "contradictions": pair
+                    .contradictions
+                    .iter()
+                    .map(|(left, right)| json!({ "left": left, "right": right }))
+                    .collect::<Vec<_>>(),
+                "only_left": pair.only_left,
+                "only_right": pair.only_right,
+            }));
+        }
+    }
+    (findings, json!({ "pairs": pairs }), any_contradiction, any_divergent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare_pair;
+
+    #[test]
+    fn rephrased_outputs_agree_and_negated_claims_contradict() {
+        let left = "The fix is in parser.rs. Tests pass on Linux.\nThe cache is safe to remove.";
+        let right = "Tests pass on Linux!  The fix lives in parser.rs. The cache isn't safe to remove. Docs need an update.";
+        let pair = compare_pair(left, right);
+
+        assert_eq!(pair.agreements, 2);
+        assert_eq!(
+            pair.contradictions,
+            vec![("The cache is safe to remove.".to_string(), "The cache isn't safe to remove.".to_string())]
+        );
+        assert!(pair.only_left.is_empty());
+        assert_eq!(pair.only_right, vec!["Docs need an update."]);
+    }
+}
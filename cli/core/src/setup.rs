@@ -0,0 +1,445 @@
+//! `bridge setup`: wire a project for cross-agent coordination. Writes the
+//! intent contract and per-agent snippets under `.agent-bridge/`, a managed
+//! block in each agent's instruction file, and reports which agents are
+//! installed and whether `bridge` itself is on PATH. Mirrors `runSetup` in
+//! `scripts/read_session.cjs`.
+
+use crate::agents;
+use crate::utils::{is_system_directory, normalize_path};
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// An agent setup wires: where its instructions live, and the CLI binaries
+/// whose presence on PATH counts as the agent being installed.
+struct Provider {
+    agent: &'static str,
+    target_file: &'static str,
+    binaries: &'static [&'static str],
+    /// Written before the managed block when the instruction file is new.
+    preamble: &'static str,
+}
+
+const PROVIDERS: &[Provider] = &[
+    Provider { agent: "codex", target_file: "AGENTS.md", binaries: &["codex"], preamble: "" },
+    Provider { agent: "claude", target_file: "CLAUDE.md", binaries: &["claude"], preamble: "" },
+    Provider { agent: "gemini", target_file: "GEMINI.md", binaries: &["gemini"], preamble: "" },
+    Provider {
+        agent: "cursor",
+        target_file: ".cursor/rules/agent-bridge.mdc",
+        binaries: &["cursor-agent", "cursor"],
+        preamble: "---\ndescription: Agent Bridge cross-agent coordination\nalwaysApply: true\n---\n\n",
+    },
+];
+
+const PROJECT_MARKERS: &[&str] = &[".git", "package.json", "Cargo.toml", "pyproject.toml", "go.mod"];
+
+/// The project instruction file setup wires for `agent`, relative to the
+/// project root.
+pub fn instruction_file(agent: &str) -> Option<&'static str> {
+    PROVIDERS.iter().find(|provider| provider.agent == agent).map(|provider| provider.target_file)
+}
+
+pub struct SetupOptions {
+    pub cwd: String,
+    pub dry_run: bool,
+    /// Replace existing snippets and managed blocks.
+    pub force: bool,
+    /// Also build the context pack and install its pre-push hook.
+    pub context_pack: bool,
+}
+
+fn operation(kind: &str, path: &Path, status: &str, note: &str) -> Value {
+    json!({ "type": kind, "path": path.to_string_lossy(), "status": status, "note": note })
+}
+
+fn write_file_ensured(path: &Path, content: &str) -> Result<()> {
+    if fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink()) {
+        return Err(anyhow!("Refusing to write: target is a symlink: {}", path.display()));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Write `content` unless the file exists and `force` is off.
+fn write_owned_file(path: &Path, content: &str, force: bool, dry_run: bool, what: &str) -> Result<Value> {
+    let exists = path.exists();
+    if exists && !force {
+        return Ok(operation("file", path, "unchanged", &format!("{} already exists", what)));
+    }
+    if !dry_run {
+        write_file_ensured(path, &format!("{}\n", content))?;
+    }
+    let (status, note) = if exists {
+        ("updated", format!("Refreshed {}", what.to_ascii_lowercase()))
+    } else {
+        ("created", format!("Created {}", what.to_ascii_lowercase()))
+    };
+    Ok(operation("file", path, status, &note))
+}
+
+fn intents() -> String {
+    [
+        "# Agent Bridge Intents",
+        "",
+        "Use these triggers consistently across agents and providers:",
+        "",
+        "- \"What is Claude doing?\"",
+        "- \"What did Gemini say?\"",
+        "- \"Compare Codex and Claude outputs\"",
+        "- \"Read session <id> from Codex\"",
+        "",
+        "Canonical response behavior:",
+        "1. Default to latest session in current project (`--cwd`) when no session is specified.",
+        "2. \"past session\" means previous session; \"past N sessions\" excludes latest; \"last N sessions\" includes latest.",
+        "3. Fetch evidence with `bridge read` first, then `bridge list/search` only if needed.",
+        "4. For multi-source checks use `bridge compare` or `bridge report`.",
+        "5. Do not ask for session ID before first fetch unless user requested exact ID.",
+        "6. Do not invent missing context; explicitly call out missing sessions.",
+        "",
+        "Core protocol reference: https://github.com/cote-star/agent-bridge/blob/main/PROTOCOL.md.",
+    ]
+    .join("\n")
+}
+
+fn snippet(agent: &str) -> String {
+    [
+        &format!("# Agent Bridge Provider Snippet ({})", agent),
+        "",
+        "When the user asks cross-agent questions, run Agent Bridge first.",
+        "",
+        "Primary trigger examples:",
+        "- \"What is Claude doing?\"",
+        "- \"What did Gemini say?\"",
+        "- \"Compare agent outputs\"",
+        "- \"Show the past 3 sessions from Claude\"",
+        "",
+        "Intent router:",
+        "- \"What is Claude doing?\" -> `bridge read --agent claude --cwd <project-path> --json`",
+        "- \"What did Gemini say?\" -> `bridge read --agent gemini --cwd <project-path> --json`",
+        "- \"Compare Codex and Claude outputs\" -> `bridge compare --source codex --source claude --cwd <project-path> --json`",
+        "",
+        "Session timing defaults:",
+        "- No session ID means latest session in scope.",
+        "- \"past session\" means previous session (exclude latest).",
+        "- \"past N sessions\" means list N+1 and use older N sessions.",
+        "- \"last N sessions\" means list N and include latest session.",
+        "- Ask for session ID only after first fetch fails or exact ID is requested.",
+        "",
+        "Commands:",
+        "- `bridge read --agent <target-agent> --cwd <project-path> --json`",
+        "- `bridge list --agent <agent> --cwd <project-path> --json`",
+        "- `bridge search \"<query>\" --agent <agent> --cwd <project-path> --json`",
+        "- `bridge compare --source codex --source gemini --source claude --cwd <project-path> --json`",
+        "",
+        "Use evidence from command output and explicitly report missing session data.",
+    ]
+    .join("\n")
+}
+
+fn managed_block(agent: &str, snippet_rel_path: &str) -> String {
+    let marker = format!("agent-bridge:{}", agent);
+    [
+        &format!("<!-- {}:start -->", marker),
+        "## Agent Bridge Integration",
+        "",
+        "This project is wired for cross-agent coordination via `bridge`.",
+        &format!("Provider snippet: `{}`", snippet_rel_path),
+        "",
+        "When a user asks for another agent status (for example \"What is Claude doing?\"),",
+        "run Agent Bridge commands first and answer with evidence from session output.",
+        "",
+        "Session routing and defaults:",
+        "1. Start with `bridge read --agent <target-agent> --cwd <project-path> --json` (omit `--id` for latest).",
+        "2. \"past session\" means previous session: list 2 and read the second session ID.",
+        "3. \"past N sessions\" means exclude latest: list N+1 and read the older N session IDs.",
+        "4. \"last N sessions\" means include latest: list N and read/summarize those sessions.",
+        "5. Ask for a session ID only after an initial read/list attempt fails or when exact ID is requested.",
+        "",
+        "Support commands:",
+        "- `bridge list --agent <agent> --cwd <project-path> --json`",
+        "- `bridge search \"<query>\" --agent <agent> --cwd <project-path> --json`",
+        "- `bridge compare --source codex --source gemini --source claude --cwd <project-path> --json`",
+        "",
+        "If command syntax is unclear, run `bridge --help`.",
+        &format!("<!-- {}:end -->", marker),
+    ]
+    .join("\n")
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    Regex::new(r"\n{3,}").expect("valid regex").replace_all(text, "\n\n").to_string()
+}
+
+/// Insert the managed block between `<!-- <marker>:start -->` and its end
+/// marker, appending it when absent. An existing block is only replaced with
+/// `force`; duplicated blocks are collapsed into one.
+fn upsert_managed_block(
+    path: &Path,
+    block: &str,
+    marker: &str,
+    preamble: &str,
+    force: bool,
+    dry_run: bool,
+) -> Result<(&'static str, &'static str)> {
+    let start_marker = format!("<!-- {}:start -->", marker);
+    let end_marker = format!("<!-- {}:end -->", marker);
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let start = existing.find(&start_marker);
+    let end = existing.find(&end_marker);
+
+    let duplicated = start.is_some_and(|start| {
+        existing[start + start_marker.len()..].contains(&start_marker)
+            || end.is_some_and(|end| existing[end + end_marker.len()..].contains(&end_marker))
+    });
+    if duplicated {
+        if !force {
+            return Ok(("unchanged", "Duplicate managed block markers detected (use --force to replace all)"));
+        }
+        let mut cleaned = existing.clone();
+        for _ in 0..10 {
+            let (Some(start), Some(end)) = (cleaned.find(&start_marker), cleaned.find(&end_marker)) else {
+                break;
+            };
+            if end < start {
+                break;
+            }
+            let before = cleaned[..start].trim_end();
+            let after = cleaned[end + end_marker.len()..].trim_start();
+            cleaned = collapse_blank_lines(&format!("{}\n\n{}", before, after));
+        }
+        let trimmed = cleaned.trim_end();
+        let next = if trimmed.is_empty() { format!("{}\n", block) } else { format!("{}\n\n{}\n", trimmed, block) };
+        if !dry_run {
+            write_file_ensured(path, &next)?;
+        }
+        return Ok(("updated", "Replaced duplicate managed blocks"));
+    }
+
+    let (next, status) = match (start, end) {
+        (Some(start), Some(end)) if end > start => {
+            if !force {
+                return Ok(("unchanged", "Managed block already present (use --force to refresh)"));
+            }
+            let before = existing[..start].trim_end();
+            let after = existing[end + end_marker.len()..].trim_start();
+            let after = if after.is_empty() { String::new() } else { format!("\n{}", after) };
+            (collapse_blank_lines(&format!("{}\n\n{}\n{}", before, block, after)), "updated")
+        }
+        _ if existing.trim().is_empty() => (format!("{}{}\n", preamble, block), "created"),
+        _ => (format!("{}\n\n{}\n", existing.trim_end(), block), "updated"),
+    };
+    if !dry_run {
+        write_file_ensured(path, &next)?;
+    }
+    let message = if status == "created" { "Created file with managed block" } else { "Managed block written" };
+    Ok((status, message))
+}
+
+/// Write `block` between `<!-- <marker>:start/end -->` in `agent`'s
+/// instruction file under `cwd`, replacing an earlier block with the same
+/// markers. Returns the `integration` operation.
+pub(crate) fn replace_block(cwd: &Path, agent: &str, marker: &str, block: &str, dry_run: bool) -> Result<Value> {
+    let provider = PROVIDERS
+        .iter()
+        .find(|provider| provider.agent == agent)
+        .with_context(|| format!("No instruction file for {}; use codex, claude, gemini, or cursor", agent))?;
+    if is_system_directory(cwd) {
+        return Err(anyhow!("Refusing to write into system directory: {}", cwd.display()));
+    }
+    let target = cwd.join(provider.target_file);
+    if fs::read_to_string(&target).is_ok_and(|existing| existing.contains(block)) {
+        return Ok(operation("integration", &target, "unchanged", "Managed block already up to date"));
+    }
+    let (status, note) = upsert_managed_block(&target, block, marker, provider.preamble, true, dry_run)?;
+    Ok(operation("integration", &target, status, note))
+}
+
+/// First `name` executable on PATH (`name.exe` on Windows).
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let file_name = if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Whether the agent is installed: its session store exists, or its CLI is
+/// on PATH.
+fn detect(provider: &Provider) -> Value {
+    let store = agents::store_roots(provider.agent).into_iter().find(|root| root.exists());
+    let (detected, detail) = match store {
+        Some(root) => (true, format!("Found sessions in {}", root.display())),
+        None => match provider.binaries.iter().find_map(|binary| find_on_path(binary)) {
+            Some(binary) => (true, format!("Found {} on PATH", binary.display())),
+            None => (false, format!("No {} sessions or CLI found", provider.agent)),
+        },
+    };
+    json!({ "agent": provider.agent, "detected": detected, "detail": detail })
+}
+
+/// Run a `bridge context-pack` subcommand in `cwd` with this binary,
+/// capturing its output so `--json` stays machine-readable.
+fn run_context_pack(cwd: &Path, args: &[&str]) -> Result<String> {
+    let exe = std::env::current_exe().context("Failed to locate the bridge binary")?;
+    let output = Command::new(exe)
+        .arg("context-pack")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .context("Failed to run bridge context-pack")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "bridge context-pack {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run setup and return the `setup --json` object.
+pub fn setup(options: &SetupOptions) -> Result<Value> {
+    let cwd = normalize_path(&options.cwd)?;
+    if is_system_directory(&cwd) {
+        return Err(anyhow!("Refusing to run setup in system directory: {}", cwd.display()));
+    }
+    if fs::symlink_metadata(&cwd).is_ok_and(|meta| meta.file_type().is_symlink()) {
+        return Err(anyhow!("Refusing to run setup: target path is a symlink: {}", cwd.display()));
+    }
+
+    let mut warnings = Vec::new();
+    if !PROJECT_MARKERS.iter().any(|marker| cwd.join(marker).exists()) {
+        warnings.push(format!(
+            "Warning: {} has no recognizable project markers (.git, package.json, etc.)",
+            cwd.display()
+        ));
+    }
+    let bridge_path = find_on_path("bridge");
+    if bridge_path.is_none() {
+        warnings.push(
+            "Warning: `bridge` is not on PATH; agents following these instructions will not be able to run it".to_string(),
+        );
+    }
+    let detected = PROVIDERS.iter().map(detect).collect::<Vec<_>>();
+
+    let setup_root = cwd.join(".agent-bridge");
+    let mut operations = vec![write_owned_file(
+        &setup_root.join("INTENTS.md"),
+        &intents(),
+        options.force,
+        options.dry_run,
+        "Intent contract",
+    )?];
+
+    for provider in PROVIDERS {
+        let snippet_path = setup_root.join("providers").join(format!("{}.md", provider.agent));
+        operations.push(write_owned_file(
+            &snippet_path,
+            &snippet(provider.agent),
+            options.force,
+            options.dry_run,
+            "Provider snippet",
+        )?);
+
+        let target = cwd.join(provider.target_file);
+        let snippet_rel = snippet_path.strip_prefix(&cwd).unwrap_or(&snippet_path).to_string_lossy().replace('\\', "/");
+        let (status, note) = upsert_managed_block(
+            &target,
+            &managed_block(provider.agent, &snippet_rel),
+            &format!("agent-bridge:{}", provider.agent),
+            provider.preamble,
+            options.force,
+            options.dry_run,
+        )?;
+        operations.push(operation("integration", &target, status, note));
+    }
+
+    if options.context_pack {
+        let pack = cwd.join(".agent-context").join("current");
+        let hook = cwd.join(".githooks").join("pre-push");
+        if options.dry_run {
+            operations.push(operation("context-pack", &pack, "planned", "Would build context pack"));
+            operations.push(operation("context-pack", &hook, "planned", "Would install context-pack pre-push hook"));
+        } else {
+            let built = run_context_pack(&cwd, &["build", "--reason", "setup"])?;
+            let status = if built.contains("unchanged") { "unchanged" } else { "updated" };
+            let note = if built.is_empty() { "Context pack build completed" } else { built.as_str() };
+            operations.push(operation("context-pack", &pack, status, note));
+            let installed = run_context_pack(&cwd, &["install-hooks"])?;
+            let note = if installed.is_empty() { "Installed context-pack pre-push hook" } else { installed.as_str() };
+            operations.push(operation("context-pack", &hook, "updated", note));
+        }
+    }
+
+    let changed = operations
+        .iter()
+        .filter(|op| matches!(op["status"].as_str(), Some("created" | "updated")))
+        .count();
+    Ok(json!({
+        "cwd": cwd.to_string_lossy(),
+        "dry_run": options.dry_run,
+        "force": options.force,
+        "agents": detected,
+        "bridge_on_path": bridge_path.map(|path| path.to_string_lossy().to_string()),
+        "operations": operations,
+        "warnings": warnings,
+        "changed": changed,
+    }))
+}
+
+pub fn print_text(result: &Value) {
+    let dry_run = if result["dry_run"].as_bool().unwrap_or(false) { "(dry run) " } else { "" };
+    println!("Agent Bridge setup {}complete for {}", dry_run, result["cwd"].as_str().unwrap_or(""));
+    for warning in result["warnings"].as_array().into_iter().flatten() {
+        println!("- [warn] {}", warning.as_str().unwrap_or(""));
+    }
+    for agent in result["agents"].as_array().into_iter().flatten() {
+        let label = if agent["detected"].as_bool().unwrap_or(false) { "detected" } else { "not found" };
+        println!(
+            "- [{}] {}: {}",
+            label,
+            agent["agent"].as_str().unwrap_or(""),
+            agent["detail"].as_str().unwrap_or("")
+        );
+    }
+    for op in result["operations"].as_array().into_iter().flatten() {
+        println!(
+            "- [{}] {} ({})",
+            op["status"].as_str().unwrap_or(""),
+            op["path"].as_str().unwrap_or(""),
+            op["note"].as_str().unwrap_or("")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{managed_block, upsert_managed_block};
+    use std::fs;
+
+    #[test]
+    fn managed_block_is_appended_once_and_refreshed_only_with_force() {
+        let dir = std::env::temp_dir().join(format!("bridge-setup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("CLAUDE.md");
+        fs::write(&path, "# Project\n\nNotes.\n").unwrap();
+        let block = managed_block("claude", ".agent-bridge/providers/claude.md");
+
+        let first = upsert_managed_block(&path, &block, "agent-bridge:claude", "", false, false).unwrap();
+        assert_eq!(first.0, "updated");
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# Project\n\nNotes.\n\n<!-- agent-bridge:claude:start -->"));
+
+        let again = upsert_managed_block(&path, &block, "agent-bridge:claude", "", false, false).unwrap();
+        assert_eq!(again.0, "unchanged");
+        let forced = upsert_managed_block(&path, &block, "agent-bridge:claude", "", true, false).unwrap();
+        assert_eq!(forced.0, "updated");
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,337 @@
+//! Usage analytics for `bridge stats`: per-agent session and message counts,
+//! active days, average session length, and the busiest projects over a time
+//! window. A session counts when its active window (first to last message
+//! timestamp, or its file mtime when the format has no timestamps) overlaps
+//! the requested one; only its messages inside the window are counted.
+//! Token counts are `pricing::estimate_tokens` estimates of those messages,
+//! priced when the session's model has pricing configured.
+
+use crate::adapters;
+use crate::agents::{self, TimeSpan};
+use crate::pricing;
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fs;
+
+struct SessionUsage {
+    agent: String,
+    cwd: Option<String>,
+    user_messages: usize,
+    assistant_messages: usize,
+    input_tokens: u64,
+    output_tokens: u64,
+    cost_usd: Option<f64>,
+    /// `YYYY-MM-DD` (UTC) of every counted message.
+    days: BTreeSet<String>,
+    /// Milliseconds between the first and last timestamped message, when
+    /// there are at least two.
+    duration: Option<i64>,
+}
+
+#[derive(Default)]
+struct Totals {
+    sessions: usize,
+    user_messages: usize,
+    assistant_messages: usize,
+    input_tokens: u64,
+    output_tokens: u64,
+    /// Summed over priced sessions only; `None` when none were priced.
+    cost_usd: Option<f64>,
+    days: BTreeSet<String>,
+    durations: Vec<i64>,
+}
+
+impl Totals {
+    fn add(&mut self, usage: &SessionUsage) {
+        self.sessions += 1;
+        self.user_messages += usage.user_messages;
+        self.assistant_messages += usage.assistant_messages;
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        if let Some(cost) = usage.cost_usd {
+            self.cost_usd = Some(pricing::round_usd(self.cost_usd.unwrap_or(0.0) + cost));
+        }
+        self.days.extend(usage.days.iter().cloned());
+        self.durations.extend(usage.duration);
+    }
+
+    fn average_minutes(&self) -> Option<f64> {
+        if self.durations.is_empty() {
+            return None;
+        }
+        let mean = self.durations.iter().sum::<i64>() as f64 / self.durations.len() as f64;
+        Some((mean / 6_000.0).round() / 10.0)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "sessions": self.sessions,
+            "messages": self.user_messages + self.assistant_messages,
+            "user_messages": self.user_messages,
+            "assistant_messages": self.assistant_messages,
+            "active_days": self.days.len(),
+            "average_session_minutes": self.average_minutes(),
+            "estimated_tokens": {
+                "input_tokens": self.input_tokens,
+                "output_tokens": self.output_tokens,
+                "total_tokens": self.input_tokens + self.output_tokens,
+            },
+            "estimated_cost_usd": self.cost_usd,
+        })
+    }
+}
+
+fn day(ms: i64) -> String {
+    agents::format_epoch_millis(ms)[..10].to_string()
+}
+
+fn usage(entry: &Value, span: TimeSpan) -> Option<SessionUsage> {
+    let agent = entry["agent"].as_str()?.to_string();
+    let file_path = entry["file_path"].as_str()?;
+    let modified = entry["modified_at"].as_str().and_then(agents::parse_iso_millis);
+    let messages = fs::metadata(file_path)
+        .ok()
+        .filter(|meta| meta.len() <= adapters::common::max_file_size())
+        .and_then(|_| fs::read(file_path).ok())
+        .map(|bytes| agents::transcript(&agent, &bytes, entry["session_id"].as_str()))
+        .unwrap_or_default();
+    let stamps = messages
+        .iter()
+        .map(|message| message.timestamp.as_deref().and_then(agents::parse_iso_millis))
+        .collect::<Vec<_>>();
+    let first = stamps.iter().flatten().min().copied();
+    let last = stamps.iter().flatten().max().copied();
+    let (start, end) = match (first, last) {
+        (Some(first), Some(last)) => (first, last),
+        _ => (modified?, modified?),
+    };
+    if span.since.is_some_and(|since| end < since) || span.until.is_some_and(|until| start > until) {
+        return None;
+    }
+
+    let mut usage = SessionUsage {
+        agent,
+        cwd: entry["cwd"].as_str().filter(|cwd| !cwd.is_empty()).map(str::to_string),
+        user_messages: 0,
+        assistant_messages: 0,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: None,
+        days: BTreeSet::new(),
+        duration: None,
+    };
+    for (message, stamp) in messages.iter().zip(&stamps) {
+        // Untimestamped messages belong to a session already inside the window.
+        let ms = match stamp {
+            Some(ms) if span.contains(Some(*ms)) => *ms,
+            Some(_) => continue,
+            None => start,
+        };
+        usage.days.insert(day(ms));
+        if message.role == "user" {
+            usage.user_messages += 1;
+            usage.input_tokens += pricing::estimate_tokens(&message.text);
+        } else {
+            usage.assistant_messages += 1;
+            usage.output_tokens += pricing::estimate_tokens(&message.text);
+        }
+    }
+    let estimated = json!({ "input_tokens": usage.input_tokens, "output_tokens": usage.output_tokens });
+    usage.cost_usd = pricing::cost_for(entry["model"].as_str(), &estimated).and_then(|cost| cost["total_usd"].as_f64());
+    if usage.days.is_empty() {
+        usage.days.insert(day(start));
+    }
+    let timed = stamps.iter().flatten().filter(|ms| span.contains(Some(**ms))).collect::<Vec<_>>();
+    if timed.len() >= 2 {
+        usage.duration = Some(*timed.iter().copied().max()? - *timed.iter().copied().min()?);
+    }
+    Some(usage)
+}
+
+/// Usage over `span` for `entries` (as `list --json` returns them). `top`
+/// caps the busiest projects, ranked by sessions and then messages.
+pub fn summarize(entries: &[Value], span: TimeSpan, top: usize) -> Value {
+    let sessions = entries.par_iter().filter_map(|entry| usage(entry, span)).collect::<Vec<_>>();
+
+    let mut total = Totals::default();
+    let mut by_agent: Vec<(String, Totals)> = Vec::new();
+    let mut by_project: Vec<(String, Totals, Vec<String>)> = Vec::new();
+    for session in &sessions {
+        total.add(session);
+        match by_agent.iter_mut().find(|(agent, _)| *agent == session.agent) {
+            Some((_, totals)) => totals.add(session),
+            None => {
+                let mut totals = Totals::default();
+                totals.add(session);
+                by_agent.push((session.agent.clone(), totals));
+            }
+        }
+        let Some(cwd) = &session.cwd else {
+            continue;
+        };
+        match by_project.iter_mut().find(|(project, _, _)| project == cwd) {
+            Some((_, totals, agents)) => {
+                totals.add(session);
+                if !agents.contains(&session.agent) {
+                    agents.push(session.agent.clone());
+                }
+            }
+            None => {
+                let mut totals = Totals::default();
+                totals.add(session);
+                by_project.push((cwd.clone(), totals, vec![session.agent.clone()]));
+            }
+        }
+    }
+    by_agent.sort_by(|(a_name, a), (b_name, b)| b.sessions.cmp(&a.sessions).then_with(|| a_name.cmp(b_name)));
+    by_project.sort_by(|(a_cwd, a, _), (b_cwd, b, _)| {
+        let messages = |totals: &Totals| totals.user_messages + totals.assistant_messages;
+        b.sessions.cmp(&a.sessions).then_with(|| messages(b).cmp(&messages(a))).then_with(|| a_cwd.cmp(b_cwd))
+    });
+
+    let mut summary = total.to_json();
+    summary["since"] = json!(span.since.map(agents::format_epoch_millis));
+    summary["until"] = json!(span.until.map(agents::format_epoch_millis));
+    summary["agents"] = by_agent
+        .iter()
+        .map(|(agent, totals)| {
+            let mut value = totals.to_json();
+            value["agent"] = json!(agent);
+            value
+        })
+        .collect();
+    summary["projects"] = by_project
+        .iter()
+        .take(top)
+        .map(|(cwd, totals, agents)| {
+            json!({
+                "cwd": cwd,
+                "sessions": totals.sessions,
+                "messages": totals.user_messages + totals.assistant_messages,
+                "agents": agents,
+            })
+        })
+        .collect();
+    summary
+}
+
+/// `950`, `12.3k`, `4.1M`.
+fn compact(value: &Value) -> String {
+    let count = value.as_u64().unwrap_or(0);
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{:.1}k", count as f64 / 1e3),
+        _ => format!("{:.1}M", count as f64 / 1e6),
+    }
+}
+
+fn minutes(value: &Value) -> String {
+    match value.as_f64() {
+        Some(minutes) if minutes < 60.0 => format!("{}m", minutes.round()),
+        Some(minutes) => format!("{:.1}h", minutes / 60.0),
+        None => "-".to_string(),
+    }
+}
+
+/// The text dashboard `bridge stats` prints without `--json`.
+pub fn to_text(summary: &Value) -> String {
+    let mut lines = vec![format!(
+        "Usage from {} to {}",
+        summary["since"].as_str().map_or("the first session", |since| &since[..10]),
+        summary["until"].as_str().map_or("now", |until| &until[..10])
+    )];
+    lines.push(String::new());
+    lines.push(format!(
+        "{:<10} {:>8}  {:>8}  {:>11}  {:>10}  {:>8}",
+        "Agent", "Sessions", "Messages", "Active days", "Avg length", "~Tokens"
+    ));
+    let row = |name: &str, value: &Value| {
+        format!(
+            "{:<10} {:>8}  {:>8}  {:>11}  {:>10}  {:>8}",
+            name,
+            value["sessions"].to_string(),
+            value["messages"].to_string(),
+            value["active_days"].to_string(),
+            minutes(&value["average_session_minutes"]),
+            compact(&value["estimated_tokens"]["total_tokens"])
+        )
+    };
+    for agent in summary["agents"].as_array().into_iter().flatten() {
+        lines.push(row(agent["agent"].as_str().unwrap_or(""), agent));
+    }
+    lines.push(row("Total", summary));
+    if let Some(usd) = summary["estimated_cost_usd"].as_f64() {
+        lines.push(format!("Estimated cost: ~${:.2} (sessions with priced models only)", usd));
+    }
+
+    let projects = summary["projects"].as_array().map(Vec::as_slice).unwrap_or_default();
+    if !projects.is_empty() {
+        lines.push(String::new());
+        lines.push("Busiest projects".to_string());
+        for project in projects {
+            let agents = project["agents"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>();
+            lines.push(format!(
+                "  {}  {} session(s), {} message(s) ({})",
+                project["cwd"].as_str().unwrap_or(""),
+                project["sessions"],
+                project["messages"],
+                agents.join(", ")
+            ));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{summarize, to_text};
+    use crate::agents::{parse_iso_millis, TimeSpan};
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn stats_count_sessions_messages_and_days_inside_the_window() {
+        let dir = std::env::temp_dir().join(format!("bridge-stats-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let codex = |name: &str, stamps: &[&str]| {
+            let lines = stamps
+                .iter()
+                .enumerate()
+                .map(|(index, at)| {
+                    let role = if index % 2 == 0 { "user" } else { "assistant" };
+                    let content = if role == "user" { "input_text" } else { "output_text" };
+                    json!({"timestamp": at, "type": "response_item", "payload": {"type": "message", "role": role, "content": [{"type": content, "text": format!("message {} of a long enough prompt", index)}]}}).to_string()
+                })
+                .collect::<Vec<_>>();
+            let path = dir.join(name);
+            fs::write(&path, lines.join("\n")).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let entry = |id: &str, cwd: &str, path: String| {
+            json!({"agent": "codex", "session_id": id, "cwd": cwd, "file_path": path, "modified_at": "2026-03-02T00:00:00Z"})
+        };
+        let entries = [
+            entry("a", "/repo", codex("a.jsonl", &["2026-03-01T10:00:00Z", "2026-03-01T10:30:00Z", "2026-03-02T09:00:00Z"])),
+            entry("b", "/repo", codex("b.jsonl", &["2026-03-01T11:00:00Z", "2026-03-01T11:10:00Z"])),
+            entry("c", "/other", codex("c.jsonl", &["2026-02-01T11:00:00Z", "2026-02-01T11:10:00Z"])),
+        ];
+        let span = TimeSpan { since: parse_iso_millis("2026-03-01T00:00:00Z"), until: parse_iso_millis("2026-03-01T23:59:59Z") };
+
+        let summary = summarize(&entries, span, 5);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(summary["sessions"], 2);
+        assert_eq!(summary["messages"], 4);
+        assert_eq!(summary["active_days"], 1);
+        assert_eq!(summary["average_session_minutes"], 20.0);
+        assert_eq!(summary["projects"], json!([{"cwd": "/repo", "sessions": 2, "messages": 4, "agents": ["codex"]}]));
+        assert_eq!(summary["estimated_tokens"]["input_tokens"], 18);
+        assert_eq!(summary["estimated_cost_usd"], serde_json::Value::Null);
+        assert!(to_text(&summary).contains("codex             2         4            1         20m        36"));
+    }
+}
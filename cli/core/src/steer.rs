@@ -0,0 +1,265 @@
+//! Steering plans for `mode: steer` reports. Each source's reply is scanned
+//! for open questions (sentences ending in `?`) and incomplete items
+//! (unchecked `- [ ]` boxes and lines such as "TODO" or "still need to"),
+//! outside code fences. The items are merged across sources into a numbered
+//! plan, questions first, and each source gets a next prompt covering its own
+//! items and what the other sources raised. The Node CLI mirrors this in
+//! `scripts/read_session.cjs`.
+
+use serde_json::{json, Value};
+
+/// Questions and incomplete items kept per source.
+const MAX_ITEMS: usize = 10;
+/// Characters kept of each item.
+const MAX_ITEM_CHARS: usize = 200;
+
+/// Words and phrases that mark a line as unfinished work, matched on whole
+/// lowercase words.
+const INCOMPLETE_MARKERS: &[&str] = &[
+    "todo",
+    "fixme",
+    "tbd",
+    "wip",
+    "not yet",
+    "still need",
+    "still needs",
+    "pending",
+    "left to do",
+    "not implemented",
+    "unfinished",
+    "incomplete",
+    "follow up",
+    "next step",
+    "next steps",
+];
+
+#[derive(Default)]
+struct OpenItems {
+    questions: Vec<String>,
+    incomplete: Vec<String>,
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect()
+}
+
+/// Items that differ only in case, punctuation, or spacing are the same item.
+fn item_key(text: &str) -> String {
+    words(text).join(" ")
+}
+
+fn clip(text: &str) -> String {
+    text.chars().take(MAX_ITEM_CHARS).collect()
+}
+
+/// A list line without its bullet or number, and its checkbox if it has one:
+/// `Some(false)` for `[ ]`, `Some(true)` for `[x]`.
+fn strip_marker(line: &str) -> (&str, Option<bool>) {
+    let mut rest = line;
+    let bullet = rest.strip_prefix(['-', '*', '+']);
+    let numbered = rest
+        .find(|ch: char| !ch.is_ascii_digit())
+        .filter(|&end| end > 0)
+        .and_then(|end| rest[end..].strip_prefix(['.', ')']));
+    if let Some(after) = bullet.or(numbered) {
+        if after.starts_with(char::is_whitespace) {
+            rest = after.trim_start();
+        }
+    }
+    let checkbox = if rest.starts_with("[ ]") {
+        Some(false)
+    } else if rest.starts_with("[x]") || rest.starts_with("[X]") {
+        Some(true)
+    } else {
+        None
+    };
+    if checkbox.is_some() {
+        rest = rest[3..].trim_start();
+    }
+    (rest, checkbox)
+}
+
+fn is_incomplete(text: &str) -> bool {
+    let padded = format!(" {} ", words(text).join(" "));
+    INCOMPLETE_MARKERS.iter().any(|marker| padded.contains(&format!(" {} ", marker)))
+}
+
+/// Sentences of `line` ending in `?`, split after `.`, `!`, or `?` followed
+/// by whitespace, with surrounding emphasis markers removed.
+fn questions(line: &str) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        if matches!(ch, '.' | '!' | '?') && chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+            pieces.push(&line[start..index + ch.len_utf8()]);
+            while chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+                chars.next();
+            }
+            start = chars.peek().map_or(line.len(), |(next, _)| *next);
+        }
+    }
+    pieces.push(&line[start..]);
+    pieces
+        .into_iter()
+        .map(|piece| piece.trim().trim_matches(['*', '_']).trim())
+        .filter(|sentence| sentence.ends_with('?') && words(sentence).len() >= 2)
+        .map(clip)
+        .collect()
+}
+
+fn push_unique(items: &mut Vec<String>, item: String) {
+    if items.len() < MAX_ITEMS && !items.iter().any(|existing| item_key(existing) == item_key(&item)) {
+        items.push(item);
+    }
+}
+
+fn open_items(text: &str) -> OpenItems {
+    let mut items = OpenItems::default();
+    let mut in_fence = false;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let (rest, checkbox) = strip_marker(line);
+        if rest.is_empty() || checkbox == Some(true) {
+            continue;
+        }
+        if checkbox == Some(false) || is_incomplete(rest) {
+            push_unique(&mut items.incomplete, clip(rest));
+            continue;
+        }
+        for question in questions(rest) {
+            push_unique(&mut items.questions, question);
+        }
+    }
+    items
+}
+
+struct Step {
+    action: &'static str,
+    item: String,
+    sources: Vec<String>,
+}
+
+fn add_step(plan: &mut Vec<Step>, action: &'static str, item: &str, tag: &str) {
+    match plan.iter_mut().find(|step| step.action == action && item_key(&step.item) == item_key(item)) {
+        Some(step) => step.sources.push(tag.to_string()),
+        None => plan.push(Step { action, item: item.to_string(), sources: vec![tag.to_string()] }),
+    }
+}
+
+fn numbered(lines: &mut Vec<String>, heading: &str, items: &[String]) {
+    if !items.is_empty() {
+        lines.push(heading.to_string());
+        lines.extend(items.iter().enumerate().map(|(index, item)| format!("{}. {}", index + 1, item)));
+    }
+}
+
+fn next_prompt(tag: &str, items: &OpenItems, plan: &[Step], task: &str, criteria: &[String]) -> String {
+    let mut lines = vec![format!("Continue the task: {}", task)];
+    numbered(&mut lines, "Finish these incomplete items:", &items.incomplete);
+    numbered(&mut lines, "Resolve these open questions:", &items.questions);
+    let others = plan.iter().filter(|step| !step.sources.iter().any(|source| source == tag)).collect::<Vec<_>>();
+    if !others.is_empty() {
+        lines.push("Also address what the other agents raised:".to_string());
+        lines.extend(others.iter().map(|step| format!("- {} ({})", step.item, step.sources.join(", "))));
+    }
+    if lines.len() == 1 {
+        lines.push("No open questions or incomplete items were found in your last reply.".to_string());
+    }
+    lines.push(format!("Check the result against the success criteria: {}.", criteria.join("; ")));
+    lines.join("\n")
+}
+
+/// The steering plan for the sources' replies: findings, the report's
+/// `steering` object, and each source's open questions for `open_questions`.
+pub(crate) fn steer(tags: &[String], texts: &[&str], task: &str, criteria: &[String]) -> (Vec<Value>, Value, Vec<String>) {
+    let items = texts.iter().map(|text| open_items(text)).collect::<Vec<_>>();
+    let mut plan = Vec::new();
+    for (tag, source) in tags.iter().zip(&items) {
+        for question in &source.questions {
+            add_step(&mut plan, "resolve", question, tag);
+        }
+    }
+    for (tag, source) in tags.iter().zip(&items) {
+        for item in &source.incomplete {
+            add_step(&mut plan, "finish", item, tag);
+        }
+    }
+
+    let mut findings = Vec::new();
+    let mut open_questions = Vec::new();
+    let mut sources = Vec::new();
+    for (tag, source) in tags.iter().zip(&items) {
+        if !source.questions.is_empty() || !source.incomplete.is_empty() {
+            findings.push(json!({
+                "severity": "P2",
+                "summary": format!(
+                    "{} left {} incomplete item(s) and {} open question(s)",
+                    tag,
+                    source.incomplete.len(),
+                    source.questions.len()
+                ),
+                "evidence": [tag],
+                "confidence": 0.7
+            }));
+        }
+        open_questions.extend(source.questions.iter().map(|question| format!("{} {}", tag, question)));
+        sources.push(json!({
+            "source": tag,
+            "open_questions": source.questions,
+            "incomplete": source.incomplete,
+            "next_prompt": next_prompt(tag, source, &plan, task, criteria),
+        }));
+    }
+    if plan.is_empty() && !tags.is_empty() {
+        findings.push(json!({
+            "severity": "P3",
+            "summary": "No open questions or incomplete items found in any source",
+            "evidence": tags,
+            "confidence": 0.6
+        }));
+    }
+
+    let plan = plan
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            json!({ "step": index + 1, "action": step.action, "item": step.item, "sources": step.sources })
+        })
+        .collect::<Vec<_>>();
+    (findings, json!({ "plan": plan, "sources": sources }), open_questions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::steer;
+
+    #[test]
+    fn steer_merges_open_items_into_a_plan_and_prompts() {
+        let tags = ["[codex:a]", "[claude:b]"].map(String::from);
+        let codex = "Parser is done. Should we cache tokens?\n- [ ] Add fuzz tests\n- [x] Wire the CLI\n```\n// TODO: not this one?\n```";
+        let claude = "**Should we cache tokens?**\nStill need to update the docs.";
+        let (findings, steering, open_questions) =
+            steer(&tags, &[codex, claude], "Ship the parser", &["tests pass".to_string()]);
+
+        assert_eq!(steering["plan"][0]["item"], "Should we cache tokens?");
+        assert_eq!(steering["plan"][0]["sources"], serde_json::json!(["[codex:a]", "[claude:b]"]));
+        assert_eq!(steering["plan"][1]["action"], "finish");
+        assert_eq!(steering["plan"].as_array().unwrap().len(), 3);
+        assert_eq!(findings[0]["summary"], "[codex:a] left 1 incomplete item(s) and 1 open question(s)");
+        assert_eq!(open_questions, vec!["[codex:a] Should we cache tokens?", "[claude:b] Should we cache tokens?"]);
+        assert_eq!(
+            steering["sources"][0]["next_prompt"],
+            "Continue the task: Ship the parser\nFinish these incomplete items:\n1. Add fuzz tests\nResolve these open questions:\n1. Should we cache tokens?\nAlso address what the other agents raised:\n- Still need to update the docs. ([claude:b])\nCheck the result against the success criteria: tests pass."
+        );
+    }
+}
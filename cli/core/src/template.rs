@@ -0,0 +1,84 @@
+use crate::utils::sanitize_for_terminal;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+
+const NAME: &str = "output";
+
+/// A user-supplied tera template for text output, compiled once and rendered
+/// against the same object the command would emit with `--json`.
+pub struct OutputTemplate {
+    tera: tera::Tera,
+}
+
+impl OutputTemplate {
+    pub fn load(path: &str) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template {}", path))?;
+        let mut tera = tera::Tera::default();
+        tera.autoescape_on(Vec::new());
+        tera.add_raw_template(NAME, &source)
+            .map_err(|err| anyhow::anyhow!("Failed to parse template {}: {}", path, error_chain(&err)))?;
+        Ok(Self { tera })
+    }
+
+    /// Render one value and print it as a line. A single trailing newline in
+    /// the template is folded into the line break.
+    pub fn print(&self, value: &Value) -> Result<()> {
+        print!("{}", self.render(value)?);
+        Ok(())
+    }
+
+    /// The sanitized, newline-terminated text `print` would emit.
+    pub fn render(&self, value: &Value) -> Result<String> {
+        let context = tera::Context::from_value(value.clone())
+            .map_err(|err| anyhow::anyhow!("Failed to render template: {}", error_chain(&err)))?;
+        let rendered = self
+            .tera
+            .render(NAME, &context)
+            .map_err(|err| anyhow::anyhow!("Failed to render template: {}", error_chain(&err)))?;
+        let rendered = sanitize_for_terminal(&rendered);
+        Ok(format!("{}\n", rendered.strip_suffix('\n').unwrap_or(&rendered)))
+    }
+}
+
+/// Tera nests the useful detail (line/column, missing variable) in `source()`.
+fn error_chain(err: &tera::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(err);
+    while let Some(inner) = source {
+        message.push_str(": ");
+        message.push_str(&inner.to_string());
+        source = inner.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutputTemplate;
+    use serde_json::json;
+    use std::fs;
+
+    fn load(name: &str, source: &str) -> OutputTemplate {
+        let path = std::env::temp_dir().join(format!("bridge-template-{}-{}.tera", name, std::process::id()));
+        fs::write(&path, source).unwrap();
+        let template = OutputTemplate::load(&path.to_string_lossy());
+        fs::remove_file(&path).unwrap();
+        template.unwrap()
+    }
+
+    #[test]
+    fn renders_the_json_object_as_one_line() {
+        let template = load("line", "{{ agent }} {{ session_id }}\n");
+        let entry = json!({"agent": "codex", "session_id": "abc"});
+        assert_eq!(template.render(&entry).unwrap(), "codex abc\n");
+    }
+
+    #[test]
+    fn missing_variables_fail_with_the_name() {
+        let template = load("missing", "{{ nope }}");
+        let error = template.render(&json!({"agent": "codex"})).unwrap_err().to_string();
+        assert!(error.starts_with("Failed to render template") && error.contains("nope"), "{}", error);
+    }
+}
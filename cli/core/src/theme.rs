@@ -0,0 +1,161 @@
+use crate::config;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and NO_COLOR is unset
+    Auto,
+    Always,
+    Never,
+}
+
+/// Decide once per process whether text output is colored.
+pub fn init(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").map(|v| v.is_empty()).unwrap_or(true)
+                && std::io::stdout().is_terminal()
+        }
+    };
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn default_style(role: &str) -> &'static str {
+    match role {
+        "codex" => "green",
+        "claude" => "yellow",
+        "gemini" => "blue",
+        "cursor" => "cyan",
+        "p1" => "bold red",
+        "p2" => "yellow",
+        "p3" => "green",
+        "warning" => "yellow",
+        "verdict" => "bold",
+        _ => "",
+    }
+}
+
+/// Translate a style spec like `"bold red"` into SGR parameters. Unknown
+/// words are ignored so a typo in the theme degrades to plain text.
+fn sgr_codes(spec: &str) -> String {
+    spec.split_whitespace()
+        .filter_map(|word| {
+            let code = match word.to_ascii_lowercase().as_str() {
+                "bold" => 1,
+                "dim" => 2,
+                "italic" => 3,
+                "underline" => 4,
+                "black" => 30,
+                "red" => 31,
+                "green" => 32,
+                "yellow" => 33,
+                "blue" => 34,
+                "magenta" => 35,
+                "cyan" => 36,
+                "white" => 37,
+                "bright_black" | "gray" | "grey" => 90,
+                "bright_red" => 91,
+                "bright_green" => 92,
+                "bright_yellow" => 93,
+                "bright_blue" => 94,
+                "bright_magenta" => 95,
+                "bright_cyan" => 96,
+                "bright_white" => 97,
+                _ => return None,
+            };
+            Some(code.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Wrap `text` in the ANSI style configured for `role` (an agent name,
+/// `p1`/`p2`/`p3`, `warning`, or `verdict`). `[theme]` in the config file
+/// overrides the defaults per role.
+pub fn paint(role: &str, text: &str) -> String {
+    if !enabled() {
+        return text.to_string();
+    }
+    let role = role.to_ascii_lowercase();
+    let spec = config::section("theme")[role.as_str()]
+        .as_str()
+        .unwrap_or_else(|| default_style(&role));
+    let codes = sgr_codes(spec);
+    if codes.is_empty() {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", codes, text)
+    }
+}
+
+/// Color severity labels and the verdict line of an already-sanitized
+/// markdown report.
+pub fn colorize_report(markdown: &str) -> String {
+    if !enabled() {
+        return markdown.to_string();
+    }
+    markdown
+        .lines()
+        .map(|line| {
+            for severity in ["P1", "P2", "P3"] {
+                let label = format!("**{}:**", severity);
+                if let Some(rest) = line.strip_prefix(&format!("- {}", label)) {
+                    return format!("- {}{}", paint(severity, &label), rest);
+                }
+            }
+            if let Some(rest) = line.strip_prefix("**Verdict:** ") {
+                return format!("**Verdict:** {}", paint("verdict", rest));
+            }
+            line.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render markdown for the terminal (headings, emphasis, code fences). The
+/// input must already be sanitized; styling follows the `--color` decision.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_markdown(markdown: &str) -> String {
+    let skin = if enabled() {
+        termimad::MadSkin::default()
+    } else {
+        termimad::MadSkin::no_style()
+    };
+    skin.term_text(markdown).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_style, init, paint, render_markdown, sgr_codes, ColorChoice};
+
+    #[test]
+    fn style_specs_become_sgr_codes_and_skip_unknown_words() {
+        assert_eq!(sgr_codes(default_style("p1")), "1;31");
+        assert_eq!(sgr_codes("Bright_Cyan underline"), "96;4");
+        assert_eq!(sgr_codes("sparkly"), "");
+    }
+
+    #[test]
+    fn color_never_leaves_text_plain() {
+        init(ColorChoice::Never);
+        assert_eq!(paint("codex", "codex"), "codex");
+    }
+
+    #[test]
+    fn render_drops_markdown_syntax() {
+        init(ColorChoice::Never);
+        let rendered = render_markdown("Fixed **the parser** in `lib.rs`.\n");
+        assert!(rendered.contains("the parser"), "{}", rendered);
+        assert!(!rendered.contains("**") && !rendered.contains('`'), "{}", rendered);
+        assert!(!rendered.contains('\x1b'), "{}", rendered);
+    }
+}
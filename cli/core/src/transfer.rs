@@ -0,0 +1,387 @@
+//! Continuation packets for `bridge transfer`: distill a session into the
+//! task, decisions, open items, and files it touched, and render that as a
+//! prompt another agent can pick the work up from. The distillation is
+//! heuristic (cue words and path shapes); nothing leaves the machine.
+
+use crate::agents::{Session, TranscriptMessage};
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+const HANDOFF_MARKER: &str = "agent-bridge:handoff";
+const MAX_DECISIONS: usize = 8;
+const MAX_OPEN_ITEMS: usize = 8;
+const MAX_FILES: usize = 15;
+const MAX_ITEM_CHARS: usize = 240;
+const MAX_TASK_CHARS: usize = 2000;
+const MAX_LAST_STATE_CHARS: usize = 1500;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Packet {
+    /// The prompt the session was started with.
+    pub task: Option<String>,
+    pub decisions: Vec<String>,
+    pub open_items: Vec<String>,
+    /// Paths mentioned in the session, most mentioned first.
+    pub files: Vec<String>,
+    /// The final assistant message: where the session stopped.
+    pub last_state: Option<String>,
+}
+
+fn decision_cue() -> &'static Regex {
+    static CUE: OnceLock<Regex> = OnceLock::new();
+    CUE.get_or_init(|| {
+        Regex::new(r"(?i)\b(decided|decision|chose|opted|going with|went with|settled on|switched to|instead of|we'll use|will use|i'll use)\b")
+            .expect("valid regex")
+    })
+}
+
+fn open_item_cue() -> &'static Regex {
+    static CUE: OnceLock<Regex> = OnceLock::new();
+    CUE.get_or_init(|| {
+        Regex::new(r"(?i)(^\[ \]|\b(todo|next steps?|remaining|still need|not yet|follow[- ]up|left to do|pending|unresolved|blocked on)\b)")
+            .expect("valid regex")
+    })
+}
+
+fn file_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?:[\w.-]+/)+[\w.-]+\.\w{1,8}\b|\b[\w-]+\.(?:rs|ts|tsx|js|jsx|cjs|mjs|py|go|java|kt|rb|c|h|cpp|hpp|cs|swift|md|json|toml|yaml|yml|sh|sql|html|css)\b",
+        )
+        .expect("valid regex")
+    })
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut clipped = text.chars().take(max_chars).collect::<String>().trim_end().to_string();
+    clipped.push('…');
+    clipped
+}
+
+/// Prose lines of a message with list markers stripped; fenced code is
+/// skipped so commands and snippets are not mistaken for decisions.
+fn prose_lines(text: &str) -> Vec<String> {
+    let mut in_fence = false;
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || trimmed.is_empty() {
+            continue;
+        }
+        let stripped = trimmed.trim_start_matches(['-', '*', '>', '#']).trim_start();
+        let stripped = match stripped.split_once(". ") {
+            Some((number, rest)) if number.chars().all(|c| c.is_ascii_digit()) => rest,
+            _ => stripped,
+        };
+        let stripped = stripped.strip_prefix("[ ]").map_or(stripped, |rest| rest.trim_start());
+        if !stripped.is_empty() {
+            lines.push(if trimmed.contains("[ ]") { format!("[ ] {}", stripped) } else { stripped.to_string() });
+        }
+    }
+    lines
+}
+
+fn push_unique(items: &mut Vec<String>, item: String) {
+    if !items.iter().any(|existing| existing == &item) {
+        items.push(item);
+    }
+}
+
+/// Distill `messages` (oldest first) into a packet. `task` is the session's
+/// first prompt, when the agent has one.
+pub fn distill(task: Option<String>, messages: &[TranscriptMessage]) -> Packet {
+    let assistant = messages.iter().filter(|message| message.role == "assistant").collect::<Vec<_>>();
+
+    // Later decisions supersede earlier ones, so keep the most recent.
+    let mut decisions = Vec::new();
+    for message in assistant.iter().rev() {
+        for line in prose_lines(&message.text).into_iter().rev() {
+            if decisions.len() < MAX_DECISIONS && decision_cue().is_match(&line) {
+                push_unique(&mut decisions, clip(&line, MAX_ITEM_CHARS));
+            }
+        }
+    }
+    decisions.reverse();
+
+    // Open items only make sense at the end of the session.
+    let mut open_items = Vec::new();
+    let tail = assistant.iter().rev().take(2).rev().map(|message| message.text.as_str());
+    let last_user = messages.last().filter(|message| message.role == "user").map(|message| message.text.as_str());
+    for text in tail.chain(last_user) {
+        for line in prose_lines(text) {
+            if open_items.len() < MAX_OPEN_ITEMS && open_item_cue().is_match(&line) {
+                push_unique(&mut open_items, clip(line.trim_start_matches("[ ] "), MAX_ITEM_CHARS));
+            }
+        }
+    }
+
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for message in messages {
+        for found in file_pattern().find_iter(&message.text) {
+            // Skip URL paths such as https://github.com/org/repo/README.md.
+            if message.text[..found.start()].ends_with(['/', ':']) {
+                continue;
+            }
+            let path = found.as_str().trim_start_matches("./").to_string();
+            let next = counts.len();
+            counts.entry(path).or_insert((0, next)).0 += 1;
+        }
+    }
+    let mut files = counts.into_iter().collect::<Vec<_>>();
+    files.sort_by(|(_, (a_count, a_first)), (_, (b_count, b_first))| b_count.cmp(a_count).then(a_first.cmp(b_first)));
+    files.truncate(MAX_FILES);
+
+    Packet {
+        task: task.map(|task| clip(task.trim(), MAX_TASK_CHARS)),
+        decisions,
+        open_items,
+        files: files.into_iter().map(|(path, _)| path).collect(),
+        last_state: assistant.last().map(|message| clip(message.text.trim(), MAX_LAST_STATE_CHARS)),
+    }
+}
+
+fn section(out: &mut String, heading: &str, title: &str, items: &[String], code: bool) {
+    out.push_str(&format!("{} {}\n", heading, title));
+    if items.is_empty() {
+        out.push_str("- None recorded.\n");
+    }
+    for item in items {
+        if code {
+            out.push_str(&format!("- `{}`\n", item));
+        } else {
+            out.push_str(&format!("- {}\n", item));
+        }
+    }
+    out.push('\n');
+}
+
+/// The packet as Markdown sections, without the prompt framing. `heading`
+/// is the section marker (`##`, or `###` when nested under another heading).
+pub fn summary_markdown(packet: &Packet, heading: &str) -> String {
+    let mut out = format!("{} Task\n", heading);
+    out.push_str(packet.task.as_deref().unwrap_or("Not recorded."));
+    out.push_str("\n\n");
+    section(&mut out, heading, "Decisions so far", &packet.decisions, false);
+    section(&mut out, heading, "Open items", &packet.open_items, false);
+    section(&mut out, heading, "Relevant files", &packet.files, true);
+    out.push_str(&format!("{} Where it stopped\n", heading));
+    match &packet.last_state {
+        Some(state) => {
+            for line in state.lines() {
+                out.push_str(if line.is_empty() { ">" } else { "> " });
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        None => out.push_str("No assistant reply recorded.\n"),
+    }
+    out
+}
+
+/// "a Claude session (<id>) in <cwd>", leaving out what is unknown.
+fn origin(session: &Session, from_name: &str) -> String {
+    match (&session.session_id, &session.cwd) {
+        (Some(id), Some(cwd)) => format!("a {} session ({}) in {}", from_name, id, cwd),
+        (Some(id), None) => format!("a {} session ({})", from_name, id),
+        (None, Some(cwd)) => format!("a {} session in {}", from_name, cwd),
+        (None, None) => format!("a {} session", from_name),
+    }
+}
+
+/// A continuation prompt for `to`: the packet framed with where it came from
+/// and how to fetch more. `from_name` is the source agent's display name and
+/// `instructions` the target's project instruction file, when it has one.
+pub fn prompt(session: &Session, from_name: &str, to: &str, instructions: Option<&str>, packet: &Packet) -> String {
+    let mut out = format!(
+        "You are continuing work started in {}. Pick up where it stopped; do not redo finished work or revisit settled decisions without a reason.\n\n",
+        origin(session, from_name)
+    );
+    out.push_str(&summary_markdown(packet, "##"));
+    out.push('\n');
+    if let Some(file) = instructions {
+        out.push_str(&format!("Follow the project conventions in `{}`. ", file));
+    }
+    let mut fetch = format!("bridge read --agent {}", session.agent);
+    if let Some(id) = &session.session_id {
+        fetch.push_str(&format!(" --id {} --id-match exact", id));
+    }
+    if let Some(cwd) = &session.cwd {
+        fetch.push_str(&format!(" --cwd {}", cwd));
+    }
+    out.push_str(&format!("If you need more of the original session, run `{} --last 5`.\n", fetch));
+    if (to == "codex" || to == "gemini") && !packet.open_items.is_empty() {
+        // CLI agents run the next turn directly from the prompt.
+        out.push_str("Start with the first open item.\n");
+    }
+    out
+}
+
+/// The managed block `bridge inject` writes: the packet under a handoff
+/// heading, between `<!-- agent-bridge:handoff:start/end -->` markers.
+pub fn handoff_block(session: &Session, from_name: &str, packet: &Packet) -> String {
+    format!(
+        "<!-- {marker}:start -->\n## Handoff from {from_name}\n\nContext carried over from {origin}, written by `bridge inject`. Re-run it to refresh this block, and delete the block once the work is picked up.\n\n{summary}<!-- {marker}:end -->",
+        marker = HANDOFF_MARKER,
+        from_name = from_name,
+        origin = origin(session, from_name),
+        summary = summary_markdown(packet, "###"),
+    )
+}
+
+/// Write the handoff block into `target`'s instruction file under `cwd`,
+/// replacing the block an earlier `bridge inject` left.
+pub fn inject(cwd: &Path, target: &str, session: &Session, from_name: &str, packet: &Packet, dry_run: bool) -> Result<Value> {
+    let block = handoff_block(session, from_name, packet);
+    let written = crate::setup::replace_block(cwd, target, HANDOFF_MARKER, &block, dry_run)?;
+    Ok(json!({
+        "target": target,
+        "path": written["path"],
+        "status": written["status"],
+        "note": written["note"],
+        "from": session.agent,
+        "session_id": session.session_id,
+        "dry_run": dry_run,
+    }))
+}
+
+pub fn to_json(session: &Session, to: &str, packet: &Packet, prompt: &str) -> Value {
+    json!({
+        "from": session.agent,
+        "to": to,
+        "session_id": session.session_id,
+        "source": session.source,
+        "cwd": session.cwd,
+        "packet": {
+            "task": packet.task,
+            "decisions": packet.decisions,
+            "open_items": packet.open_items,
+            "files": packet.files,
+            "last_state": packet.last_state,
+        },
+        "prompt": prompt,
+    })
+}
+
+/// Put `text` on the system clipboard with the platform's copy tool and
+/// return the tool used.
+pub fn copy_to_clipboard(text: &str) -> Result<&'static str> {
+    let candidates: &[(&'static str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    };
+    for (tool, args) in candidates {
+        let Ok(mut child) = Command::new(tool).args(*args).stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn()
+        else {
+            continue;
+        };
+        child
+            .stdin
+            .take()
+            .context("Clipboard tool has no stdin")?
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {}", tool))?;
+        if child.wait().with_context(|| format!("Failed to run {}", tool))?.success() {
+            return Ok(tool);
+        }
+    }
+    let tried = candidates.iter().map(|(tool, _)| *tool).collect::<Vec<_>>().join(", ");
+    Err(anyhow!("No clipboard tool available (tried {})", tried))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distill, inject, Packet};
+    use crate::agents::{Session, TranscriptMessage};
+    use std::fs;
+
+    fn message(role: &'static str, text: &str) -> TranscriptMessage {
+        TranscriptMessage { role, text: text.to_string(), timestamp: None }
+    }
+
+    fn session() -> Session {
+        Session {
+            agent: "codex",
+            content: String::new(),
+            messages: Vec::new(),
+            source: "/sessions/rollout-1.jsonl".to_string(),
+            warnings: Vec::new(),
+            session_id: Some("session-1".to_string()),
+            cwd: None,
+            timestamp: None,
+            message_count: 0,
+            messages_returned: 0,
+            tool_usage: None,
+            timing: None,
+            model: None,
+            usage: None,
+        }
+    }
+
+    fn packet(task: &str) -> Packet {
+        Packet { task: Some(task.to_string()), ..Packet::default() }
+    }
+
+    #[test]
+    fn distill_collects_decisions_open_items_and_files() {
+        let messages = [
+            message("user", "Add retries to the fetch client"),
+            message("assistant", "Looking at src/net/client.rs and src/net/retry.rs.\n\n```\ncargo test --decided\n```"),
+            message(
+                "assistant",
+                "I decided to use exponential backoff instead of a fixed delay.\nUpdated src/net/client.rs.\n\nNext steps:\n- [ ] add jitter\n- wire the config in config.toml (still need a default)",
+            ),
+        ];
+        let packet = distill(Some("Add retries to the fetch client".to_string()), &messages);
+
+        assert_eq!(packet.task.as_deref(), Some("Add retries to the fetch client"));
+        assert_eq!(packet.decisions, vec!["I decided to use exponential backoff instead of a fixed delay."]);
+        assert_eq!(
+            packet.open_items,
+            vec!["Next steps:", "add jitter", "wire the config in config.toml (still need a default)"]
+        );
+        assert_eq!(packet.files, vec!["src/net/client.rs", "src/net/retry.rs", "config.toml"]);
+        assert!(packet.last_state.as_deref().unwrap().starts_with("I decided"));
+    }
+
+    #[test]
+    fn inject_replaces_its_own_block_and_keeps_user_content() {
+        let dir = std::env::temp_dir().join(format!("bridge-inject-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("CLAUDE.md");
+        fs::write(&target, "# Project notes\n\nKeep tests fast.\n").unwrap();
+
+        let first = inject(&dir, "claude", &session(), "Codex", &packet("Add retries"), false).unwrap();
+        let second = inject(&dir, "claude", &session(), "Codex", &packet("Add jitter"), false).unwrap();
+        let again = inject(&dir, "claude", &session(), "Codex", &packet("Add jitter"), false).unwrap();
+        let written = fs::read_to_string(&target).unwrap();
+        let dry_run = inject(&dir, "claude", &session(), "Codex", &packet("Add logging"), true).unwrap();
+        let after_dry_run = fs::read_to_string(&target).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(first["status"], "updated");
+        assert_eq!(second["status"], "updated");
+        assert_eq!(again["status"], "unchanged");
+        assert!(written.starts_with("# Project notes\n\nKeep tests fast.\n"));
+        assert_eq!(written.matches("<!-- agent-bridge:handoff:start -->").count(), 1);
+        assert!(written.contains("Add jitter") && !written.contains("Add retries"));
+        assert_eq!(dry_run["dry_run"], true);
+        assert_eq!(after_dry_run, written);
+    }
+}
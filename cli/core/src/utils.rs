@@ -1,5 +1,4 @@
 use anyhow::{Context, Result};
-use dirs;
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
@@ -16,6 +15,19 @@ pub fn expand_home(path_str: &str) -> Option<PathBuf> {
     Some(PathBuf::from(path_str))
 }
 
+/// Whether `dir` is an OS directory the bridge must never scan or write into.
+pub fn is_system_directory(dir: &Path) -> bool {
+    let s = dir.to_string_lossy();
+    let system_prefixes = ["/etc", "/usr", "/var", "/bin", "/sbin", "/System", "/Library",
+        "/Windows", "/Windows/System32", "/Program Files", "/Program Files (x86)"];
+    for prefix in system_prefixes {
+        if s == prefix || s.starts_with(&format!("{}/", prefix)) || s.starts_with(&format!("{}\\", prefix)) {
+            return true;
+        }
+    }
+    false
+}
+
 pub fn normalize_path(path_str: &str) -> Result<PathBuf> {
     let expanded = expand_home(path_str).context("Could not expand home directory")?;
     let absolute = if expanded.is_absolute() {
@@ -86,3 +98,45 @@ pub fn sanitize_for_terminal(input: &str) -> String {
 
     output
 }
+
+/// Keep only the named top-level fields of a JSON object, or of each object
+/// in a JSON array. Names that are absent are skipped; an empty list keeps
+/// everything.
+pub fn select_fields(value: serde_json::Value, fields: &[String]) -> serde_json::Value {
+    use serde_json::Value;
+    if fields.is_empty() {
+        return value;
+    }
+    match value {
+        Value::Object(mut map) => Value::Object(
+            fields
+                .iter()
+                .filter_map(|field| map.remove(field).map(|v| (field.clone(), v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items.into_iter().map(|item| select_fields(item, fields)).collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select_fields;
+    use serde_json::json;
+
+    #[test]
+    fn select_fields_projects_objects_and_each_array_entry() {
+        let fields = ["agent".to_string(), "session_id".to_string(), "missing".to_string()];
+        let entry = json!({"agent": "codex", "session_id": "abc", "cwd": "/repo"});
+        assert_eq!(select_fields(entry.clone(), &fields), json!({"agent": "codex", "session_id": "abc"}));
+        assert_eq!(select_fields(json!([entry]), &fields), json!([{"agent": "codex", "session_id": "abc"}]));
+    }
+
+    #[test]
+    fn select_fields_without_names_keeps_everything() {
+        let entry = json!({"agent": "codex", "cwd": "/repo"});
+        assert_eq!(select_fields(entry.clone(), &[]), entry);
+    }
+}
@@ -0,0 +1,86 @@
+//! `wasm32` exports of the parsing and redaction core for browsers and web
+//! dashboards. Nothing here touches the filesystem: callers pass transcript
+//! bytes and get back the JSON `bridge read --json` would print.
+//!
+//! The ABI is plain linear memory so no JS glue generator is needed (see
+//! `web/agent_bridge.mjs`): allocate with `bridge_alloc`, copy inputs in, and
+//! call an export. Results come back as `(ptr << 32) | len` of UTF-8 text that
+//! the caller releases with `bridge_free`. Parse failures are
+//! `{"error_code", "message"}`, the CLI's `--json` error shape.
+
+use serde_json::json;
+
+use crate::adapters;
+use crate::agents;
+use crate::redact;
+
+#[no_mangle]
+pub extern "C" fn bridge_alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// # Safety
+/// `ptr` and `len` must come from `bridge_alloc` or a returned result.
+#[no_mangle]
+pub unsafe extern "C" fn bridge_free(ptr: *mut u8, len: usize) {
+    drop(Vec::from_raw_parts(ptr, 0, len));
+}
+
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(ptr, len)
+    }
+}
+
+fn output(text: String) -> u64 {
+    let mut bytes = text.into_bytes();
+    bytes.shrink_to_fit();
+    let (ptr, len) = (bytes.as_mut_ptr(), bytes.len());
+    std::mem::forget(bytes);
+    ((ptr as u64) << 32) | len as u64
+}
+
+/// Parse one exported session file. `agent` accepts the same names and
+/// aliases as `--agent`; `name` is the original file name.
+///
+/// # Safety
+/// Each pointer must reference `len` readable bytes in this module's memory.
+#[no_mangle]
+pub unsafe extern "C" fn bridge_parse_session(
+    agent_ptr: *const u8,
+    agent_len: usize,
+    name_ptr: *const u8,
+    name_len: usize,
+    bytes_ptr: *const u8,
+    bytes_len: usize,
+    last_n: u32,
+) -> u64 {
+    let agent = String::from_utf8_lossy(input(agent_ptr, agent_len));
+    let name = String::from_utf8_lossy(input(name_ptr, name_len));
+    let bytes = input(bytes_ptr, bytes_len);
+    let result = adapters::normalize_agent(&agent)
+        .and_then(|agent| agents::parse_session_bytes(agent, &name, bytes, last_n as usize));
+    let value = match result {
+        Ok(session) => agents::session_json(&session),
+        Err(err) => {
+            let message = format!("{:#}", err);
+            json!({ "error_code": agents::error_code(&err).as_str(), "message": message })
+        }
+    };
+    output(value.to_string())
+}
+
+/// Apply the bridge redaction rules to UTF-8 text.
+///
+/// # Safety
+/// `ptr` must reference `len` readable bytes in this module's memory.
+#[no_mangle]
+pub unsafe extern "C" fn bridge_redact(ptr: *const u8, len: usize) -> u64 {
+    let text = String::from_utf8_lossy(input(ptr, len));
+    output(redact::redact_sensitive_text(&text))
+}
@@ -0,0 +1,174 @@
+//! `bridge watch`: poll the newest session of each watched agent and print
+//! assistant messages as they are appended. Polling keeps this dependency-free
+//! and works the same on every store layout; each tick re-reads only the one
+//! session file `read` would pick.
+
+use crate::adapters;
+use crate::agents::{CwdMatch, MessageRange, ReadOptions, Session};
+use crate::theme;
+use crate::utils::sanitize_for_terminal;
+use anyhow::{anyhow, Result};
+use serde_json::json;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::thread;
+use std::time::Duration;
+
+pub struct WatchOptions {
+    pub agents: Vec<&'static str>,
+    pub cwd: String,
+    pub cwd_match: CwdMatch,
+    pub interval: Duration,
+    pub json: bool,
+}
+
+/// The session an agent is being followed in, and how many of its replies
+/// have been printed.
+struct Position {
+    source: String,
+    seen: usize,
+}
+
+pub fn run(options: &WatchOptions) -> Result<()> {
+    // Whatever is already on disk is history, not news.
+    let mut positions = HashMap::new();
+    for agent in &options.agents {
+        if let Ok(session) = newest(agent, options) {
+            positions.insert(*agent, Position { source: session.source, seen: session.messages.len() });
+        }
+    }
+    eprintln!(
+        "Watching {} for new assistant messages every {}ms (Ctrl-C to stop)",
+        options.agents.join(", "),
+        options.interval.as_millis()
+    );
+
+    loop {
+        thread::sleep(options.interval);
+        for agent in &options.agents {
+            // No session yet, or one mid-write that does not parse: try again next tick.
+            let Ok(session) = newest(agent, options) else {
+                continue;
+            };
+            let position = positions.entry(*agent).or_insert_with(|| Position { source: String::new(), seen: 0 });
+            let (switched, unseen) = advance(position, &session);
+            if switched {
+                emit_session(&session, options.json);
+            }
+            for index in unseen {
+                emit_message(&session, index, &session.messages[index], options.json);
+            }
+        }
+    }
+}
+
+/// Move `position` to the end of `session`: whether the agent switched to a
+/// different session file, and the indexes of the replies not printed yet.
+fn advance(position: &mut Position, session: &Session) -> (bool, Range<usize>) {
+    let switched = position.source != session.source;
+    if switched {
+        *position = Position { source: session.source.clone(), seen: 0 };
+    }
+    // A rewritten file can shrink; resume from its new end.
+    let start = position.seen.min(session.messages.len());
+    position.seen = session.messages.len();
+    (switched, start..position.seen)
+}
+
+/// Every reply of the session `read` would pick for `agent`.
+fn newest(agent: &str, options: &WatchOptions) -> Result<Session> {
+    let adapter = adapters::get_adapter(agent).ok_or_else(|| anyhow!("Unsupported agent: {}", agent))?;
+    adapter.read_session(&ReadOptions {
+        cwd: options.cwd.clone(),
+        cwd_match: options.cwd_match,
+        range: Some(MessageRange { from: 0, to: None }),
+        ..ReadOptions::default()
+    })
+}
+
+fn emit_session(session: &Session, json: bool) {
+    if json {
+        println!(
+            "{}",
+            json!({
+                "event": "session",
+                "agent": session.agent,
+                "session_id": session.session_id,
+                "source": session.source,
+                "cwd": session.cwd,
+            })
+        );
+    } else {
+        println!(
+            "{} {}",
+            theme::paint(session.agent, &format!("[{}] session", session.agent)),
+            sanitize_for_terminal(&session.source)
+        );
+    }
+}
+
+fn emit_message(session: &Session, index: usize, message: &str, json: bool) {
+    if json {
+        println!(
+            "{}",
+            json!({
+                "event": "message",
+                "agent": session.agent,
+                "session_id": session.session_id,
+                "index": index,
+                "content": message,
+            })
+        );
+    } else {
+        println!(
+            "{}\n{}\n",
+            theme::paint(session.agent, &format!("[{}] #{}", session.agent, index + 1)),
+            sanitize_for_terminal(message)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{advance, Position};
+    use crate::agents::Session;
+
+    fn session(source: &str, replies: usize) -> Session {
+        Session {
+            agent: "codex",
+            content: String::new(),
+            messages: (0..replies).map(|index| format!("reply {}", index)).collect(),
+            source: source.to_string(),
+            warnings: Vec::new(),
+            session_id: None,
+            cwd: None,
+            timestamp: None,
+            message_count: replies,
+            messages_returned: replies,
+            tool_usage: None,
+            timing: None,
+            model: None,
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn appended_replies_are_the_only_news() {
+        let mut position = Position { source: "/s/a.jsonl".to_string(), seen: 2 };
+        assert_eq!(advance(&mut position, &session("/s/a.jsonl", 2)), (false, 2..2));
+        assert_eq!(advance(&mut position, &session("/s/a.jsonl", 4)), (false, 2..4));
+    }
+
+    #[test]
+    fn a_new_session_file_is_announced_and_printed_from_the_start() {
+        let mut position = Position { source: "/s/a.jsonl".to_string(), seen: 5 };
+        assert_eq!(advance(&mut position, &session("/s/b.jsonl", 2)), (true, 0..2));
+    }
+
+    #[test]
+    fn a_shrunken_file_resumes_from_its_new_end() {
+        let mut position = Position { source: "/s/a.jsonl".to_string(), seen: 5 };
+        assert_eq!(advance(&mut position, &session("/s/a.jsonl", 3)), (false, 3..3));
+        assert_eq!(advance(&mut position, &session("/s/a.jsonl", 4)), (false, 3..4));
+    }
+}
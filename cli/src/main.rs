@@ -1,17 +1,60 @@
-mod adapters;
-mod agents;
-mod context_pack;
-mod report;
-mod utils;
-
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
+mod repl;
+
+#[cfg(feature = "wasm-plugins")]
+use agent_bridge_core::plugin;
+#[cfg(unix)]
+use agent_bridge_core::daemon;
+use agent_bridge_core::{adapters, agents, aliases, bench, config, context_pack, correlate, export, fsck, hooks, page, pricing, query, redact, report, search_index, setup, stats, template, theme, transfer, utils, watch};
+
+use anyhow::{anyhow, Context, Result};
+use clap::builder::ArgPredicate;
+use clap::{CommandFactory, Parser, Subcommand};
 use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
 
 #[derive(Parser)]
 #[command(name = "bridge")]
 #[command(about = "Agent Bridge CLI", long_about = None)]
 struct Cli {
+    /// When to color text output (NO_COLOR disables color in auto mode)
+    #[arg(long, global = true, value_enum, default_value = "auto")]
+    color: theme::ColorChoice,
+
+    /// Same as --color never
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Comma-separated top-level fields to keep in --json output
+    #[arg(long, global = true, value_delimiter = ',')]
+    fields: Vec<String>,
+
+    /// jq-lite path to extract from --json output (e.g. .findings[].summary)
+    // Distinct id so the global flag does not collide with `search <query>`.
+    #[arg(long = "query", id = "json_query", global = true, value_name = "PATH")]
+    json_query: Option<String>,
+
+    /// Skip session files larger than this many megabytes (default: 50)
+    #[arg(long, global = true, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    max_file_size: Option<u64>,
+
+    /// Session store root, as <agent>=<dir> or a bare dir for the command's --agent (repeatable)
+    #[arg(long = "data-dir", global = true, value_name = "[AGENT=]DIR")]
+    data_dirs: Vec<String>,
+
+    /// Keep all bridge-owned state (config, aliases, caches) under this directory
+    #[arg(long, global = true, value_name = "DIR")]
+    portable: Option<String>,
+
+    /// Also mask email addresses, IP addresses, and phone numbers in output
+    #[arg(long, global = true)]
+    redact_pii: bool,
+
+    /// Print text unredacted (needs BRIDGE_ALLOW_UNREDACTED=1 or [redaction] allow_unredacted = true)
+    #[arg(long, global = true, conflicts_with = "redact_pii")]
+    no_redact: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -20,25 +63,245 @@ struct Cli {
 enum Commands {
     /// Read a session from an agent
     Read {
-        /// Agent to read from
-        #[arg(long, value_enum)]
+        /// Agent to read from: codex, gemini, claude, cursor, aider, windsurf, cline, or zed (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_type)]
+        agent: AgentType,
+
+        /// Session ID or UUID (substring match supported); repeat to read several, which --json returns as {sessions, errors}
+        #[arg(long)]
+        id: Vec<String>,
+
+        /// Read every session --id matches, newest first, instead of failing when it matches several
+        #[arg(long, requires = "id")]
+        all_matching: bool,
+
+        /// How --id is matched against session file names
+        #[arg(long, value_enum, default_value = "substring", default_value_if("exact", ArgPredicate::IsPresent, "exact"))]
+        id_match: agents::IdMatch,
+
+        /// Shorthand for --id-match exact
+        #[arg(long, conflicts_with = "id_match")]
+        exact: bool,
+
+        /// Working directory to scope search (defaults to current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
+        /// Explicit path to chats directory (Gemini only)
+        #[arg(long)]
+        chats_dir: Option<String>,
+
+        /// Number of last assistant messages to return [default: 1, or every message with --since/--until]
+        #[arg(long, conflicts_with_all = ["from", "to", "offset", "limit"])]
+        last: Option<usize>,
+
+        /// First assistant message to return (0-based index, oldest first)
+        #[arg(long, conflicts_with_all = ["offset", "limit"])]
+        from: Option<usize>,
+
+        /// Stop before this assistant message index (exclusive)
+        #[arg(long, conflicts_with_all = ["offset", "limit"])]
+        to: Option<usize>,
+
+        /// Skip this many assistant messages (pair with --limit to page through a session)
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Return at most this many assistant messages starting at --offset
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Only messages at or after this time: RFC 3339, YYYY-MM-DD, or an age like 30m, 2h, 1d (codex, claude, gemini)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only messages at or before this time (same formats as --since)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Include session start/end and per-reply durations in the output
+        #[arg(long)]
+        timing: bool,
+
+        /// Include estimated token counts (and cost, when the model has pricing) in the output
+        #[arg(long)]
+        tokens: bool,
+
+        /// Tera template file for text output (rendered against the --json object)
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Render markdown for the terminal instead of printing raw syntax
+        #[arg(long)]
+        render: bool,
+
+        /// Text layout when not using --json
+        #[arg(long, value_enum, default_value = "text", conflicts_with_all = ["json", "template", "render"])]
+        format: export::ReadFormat,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the first substantive user prompt of a session (the task it was started for)
+    Prompt {
+        /// Agent to read from: codex, gemini, claude, cursor, aider, windsurf, cline, or zed (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_type)]
         agent: AgentType,
 
         /// Session ID or UUID (substring match supported)
         #[arg(long)]
         id: Option<String>,
 
+        /// How --id is matched against session file names
+        #[arg(long, value_enum, default_value = "substring", default_value_if("exact", ArgPredicate::IsPresent, "exact"))]
+        id_match: agents::IdMatch,
+
+        /// Shorthand for --id-match exact
+        #[arg(long, conflicts_with = "id_match")]
+        exact: bool,
+
+        /// Working directory to scope search (defaults to current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
+        /// Explicit path to chats directory (Gemini only)
+        #[arg(long)]
+        chats_dir: Option<String>,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Export a whole session (every user and assistant message) as Markdown, HTML, or JSON
+    Export {
+        /// Agent to export from: codex, gemini, claude, cursor, aider, windsurf, cline, or zed (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_type)]
+        agent: AgentType,
+
+        /// Session ID or UUID (substring match supported)
+        #[arg(long)]
+        id: Option<String>,
+
+        /// How --id is matched against session file names
+        #[arg(long, value_enum, default_value = "substring", default_value_if("exact", ArgPredicate::IsPresent, "exact"))]
+        id_match: agents::IdMatch,
+
+        /// Shorthand for --id-match exact
+        #[arg(long, conflicts_with = "id_match")]
+        exact: bool,
+
+        /// Working directory to scope search (defaults to current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
+        /// Explicit path to chats directory (Gemini only)
+        #[arg(long)]
+        chats_dir: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "md")]
+        format: export::ExportFormat,
+
+        /// Write to a file instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Distill a session into a continuation prompt for another agent
+    Transfer {
+        /// Agent whose session to continue: codex, gemini, claude, cursor, aider, windsurf, cline, or zed (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_type)]
+        from: AgentType,
+
+        /// Agent the prompt is written for
+        #[arg(long, value_parser = parse_agent_type)]
+        to: AgentType,
+
+        /// Session ID or UUID (substring match supported)
+        #[arg(long)]
+        id: Option<String>,
+
+        /// How --id is matched against session file names
+        #[arg(long, value_enum, default_value = "substring", default_value_if("exact", ArgPredicate::IsPresent, "exact"))]
+        id_match: agents::IdMatch,
+
+        /// Shorthand for --id-match exact
+        #[arg(long, conflicts_with = "id_match")]
+        exact: bool,
+
         /// Working directory to scope search (defaults to current directory)
         #[arg(long)]
         cwd: Option<String>,
 
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
         /// Explicit path to chats directory (Gemini only)
         #[arg(long)]
         chats_dir: Option<String>,
 
-        /// Number of last assistant messages to return
-        #[arg(long, default_value = "1")]
-        last: usize,
+        /// Also copy the prompt to the system clipboard
+        #[arg(long)]
+        copy: bool,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Write a session's handoff summary into another agent's instruction file
+    Inject {
+        /// Agent whose instruction file receives the handoff
+        #[arg(long, value_parser = ["claude", "codex", "gemini", "cursor"])]
+        target: String,
+
+        /// Agent whose session to hand off: codex, gemini, claude, cursor, aider, windsurf, cline, or zed (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_type)]
+        from: AgentType,
+
+        /// Session ID or UUID (substring match supported)
+        #[arg(long)]
+        id: Option<String>,
+
+        /// How --id is matched against session file names
+        #[arg(long, value_enum, default_value = "substring", default_value_if("exact", ArgPredicate::IsPresent, "exact"))]
+        id_match: agents::IdMatch,
+
+        /// Shorthand for --id-match exact
+        #[arg(long, conflicts_with = "id_match")]
+        exact: bool,
+
+        /// Project to write into and scope search to (defaults to current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
+        /// Explicit path to chats directory (Gemini only)
+        #[arg(long)]
+        chats_dir: Option<String>,
+
+        /// Report what would change without writing files
+        #[arg(long)]
+        dry_run: bool,
 
         /// Emit structured JSON instead of text
         #[arg(long)]
@@ -55,10 +318,46 @@ enum Commands {
         #[arg(long)]
         cwd: Option<String>,
 
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
         /// Apply whitespace normalization before comparing
         #[arg(long)]
         normalize: bool,
 
+        /// Compare sentence by sentence: agreements, contradictions, and similarity per pair
+        #[arg(long)]
+        semantic: bool,
+
+        /// Compare the last N assistant messages of each source instead of only the latest
+        #[arg(long, value_name = "N", default_value = "1", value_parser = clap::value_parser!(u64).range(1..))]
+        last: u64,
+
+        /// Compare every assistant message of each source
+        #[arg(long, conflicts_with = "last")]
+        full: bool,
+
+        /// Pick each source given without a session id from the correlation group of the first source
+        #[arg(long)]
+        correlate: bool,
+
+        /// Fail the verdict on any finding at this severity or worse
+        #[arg(long, value_name = "P0|P1|P2|P3", value_parser = report::parse_severity)]
+        fail_on: Option<String>,
+
+        /// Fail the verdict when the overall score is below N
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=100))]
+        min_score: Option<u8>,
+
+        /// Tera template file for text output (rendered against the --json object)
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Render markdown for the terminal instead of printing raw syntax
+        #[arg(long)]
+        render: bool,
+
         /// Emit structured JSON instead of markdown
         #[arg(long)]
         json: bool,
@@ -70,10 +369,30 @@ enum Commands {
         #[arg(long)]
         handoff: String,
 
-        /// Working directory fallback for source lookups
+        /// Working directory fallback for source lookups; claim paths and commands resolve against it
         #[arg(long)]
         cwd: Option<String>,
 
+        /// Run the handoff's `command` claims (skipped otherwise)
+        #[arg(long)]
+        allow_commands: bool,
+
+        /// Fail the verdict on any finding at this severity or worse (overrides the handoff)
+        #[arg(long, value_name = "P0|P1|P2|P3", value_parser = report::parse_severity)]
+        fail_on: Option<String>,
+
+        /// Fail the verdict when the overall score is below N (overrides the handoff)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=100))]
+        min_score: Option<u8>,
+
+        /// Tera template file for text output (rendered against the --json object)
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Render markdown for the terminal instead of printing raw syntax
+        #[arg(long)]
+        render: bool,
+
         /// Emit structured JSON instead of markdown
         #[arg(long)]
         json: bool,
@@ -81,46 +400,293 @@ enum Commands {
 
     /// List sessions for an agent
     List {
-        /// Agent to list sessions for
-        #[arg(long, value_enum)]
-        agent: AgentType,
+        /// Agent to list sessions for: codex, gemini, claude, cursor, aider, windsurf, cline, zed, or all (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_scope)]
+        agent: AgentScope,
 
         /// Working directory to scope search
         #[arg(long)]
         cwd: Option<String>,
 
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
         /// Maximum number of sessions to return
         #[arg(long, default_value = "10")]
         limit: usize,
 
+        /// Skip this many sessions; JSON output becomes {sessions, next_cursor}
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Resume from a next_cursor of an earlier page
+        #[arg(long, conflicts_with = "offset")]
+        cursor: Option<String>,
+
+        /// Only return the newest session per project (cwd)
+        #[arg(long)]
+        latest_per_project: bool,
+
+        /// Order sessions by this key, newest or largest first
+        #[arg(long, value_enum, default_value = "modified")]
+        sort: agents::ListSort,
+
+        /// Oldest or smallest first
+        #[arg(long)]
+        reverse: bool,
+
+        /// Only sessions whose cwd matches this glob (* within a directory, ** across)
+        #[arg(long, value_name = "GLOB")]
+        filter_cwd: Option<String>,
+
+        /// Also list Codex sessions moved to archived_sessions
+        #[arg(long)]
+        include_archived: bool,
+
+        /// Also list Gemini saved chats and restore checkpoints
+        #[arg(long)]
+        include_checkpoints: bool,
+
+        /// Tera template file for text output (rendered against the --json object)
+        #[arg(long)]
+        template: Option<String>,
+
         /// Emit structured JSON instead of text
         #[arg(long)]
         json: bool,
     },
 
+    /// Group sessions from different agents that worked on the same repo at the same time
+    Correlate {
+        /// Working directory to scope search
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
+        /// Maximum number of recent sessions to scan across all agents
+        #[arg(long, default_value = "50")]
+        limit: usize,
+
+        /// Minutes of slack allowed between two sessions' active windows
+        #[arg(long, value_name = "MINUTES", default_value = "60")]
+        window: u64,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Summarize sessions, messages, active days, and busiest projects per agent
+    Stats {
+        /// Agent to summarize: codex, gemini, claude, cursor, aider, windsurf, cline, zed, or all (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_scope, default_value = "all")]
+        agent: AgentScope,
+
+        /// Working directory to scope search
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
+        /// Start of the window: RFC 3339, YYYY-MM-DD, or an age like 7d
+        #[arg(long, default_value = "30d")]
+        since: String,
+
+        /// End of the window (defaults to now)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Number of busiest projects to show
+        #[arg(long, value_name = "N", default_value = "5")]
+        top: usize,
+
+        /// Emit structured JSON instead of a text dashboard
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Follow the newest session and print assistant messages as they are appended
+    Watch {
+        /// Agent to follow: codex, gemini, claude, cursor, aider, windsurf, cline, zed, or all (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_scope)]
+        agent: AgentScope,
+
+        /// Working directory to scope search (defaults to current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
+        /// Milliseconds between polls
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(100..))]
+        interval: u64,
+
+        /// Emit one JSON event per line instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Serve read/list/search/compare as JSON-RPC over a Unix socket, cached until session files change
+    #[cfg(unix)]
+    Daemon {
+        /// Socket path (defaults to $BRIDGE_DAEMON_SOCKET, then daemon.sock in the cache directory)
+        #[arg(long)]
+        socket: Option<String>,
+    },
+
     /// Search sessions for a keyword
     Search {
-        /// Keyword to search for
-        #[arg(index = 1)]
-        query: String,
+        /// Keyword to search for (optional with --all-of or --any-of)
+        #[arg(index = 1, required_unless_present_any = ["all_of", "any_of"])]
+        query: Option<String>,
 
-        /// Agent to search
-        #[arg(long, value_enum)]
-        agent: AgentType,
+        /// Agent to search: codex, gemini, claude, cursor, aider, windsurf, cline, zed, or all (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_scope)]
+        agent: AgentScope,
 
         /// Working directory to scope search
         #[arg(long)]
         cwd: Option<String>,
 
+        /// How session cwd is matched against --cwd
+        #[arg(long, value_enum, default_value = "exact")]
+        cwd_match: agents::CwdMatch,
+
         /// Maximum number of sessions to return
         #[arg(long, default_value = "10")]
         limit: usize,
 
+        /// Skip this many sessions; JSON output becomes {sessions, next_cursor}
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Resume from a next_cursor of an earlier page
+        #[arg(long, conflicts_with = "offset")]
+        cursor: Option<String>,
+
+        /// Treat the query and every term as a case-insensitive regular expression
+        #[arg(long)]
+        regex: bool,
+
+        /// Term every matching session must also contain (repeatable)
+        #[arg(long = "all-of", value_name = "TERM")]
+        all_of: Vec<String>,
+
+        /// Terms of which a matching session must contain at least one (repeatable)
+        #[arg(long = "any-of", value_name = "TERM")]
+        any_of: Vec<String>,
+
+        /// Exclude sessions containing this term (repeatable)
+        #[arg(long, value_name = "TERM")]
+        not: Vec<String>,
+
+        /// Only search messages from this role
+        #[arg(long = "in", value_enum, value_name = "ROLE", conflicts_with = "field")]
+        in_role: Option<agents::SearchRole>,
+
+        /// Match against session content or a metadata field
+        #[arg(long, value_enum, default_value = "content")]
+        field: agents::SearchField,
+
+        /// Rank results from the full-text index (`bridge index build`) and include snippets
+        #[arg(long, conflicts_with_all = ["regex", "all_of", "any_of", "not", "in_role", "field", "include_archived", "include_checkpoints"])]
+        indexed: bool,
+
+        /// Also search Codex sessions moved to archived_sessions
+        #[arg(long)]
+        include_archived: bool,
+
+        /// Also search Gemini saved chats and restore checkpoints
+        #[arg(long)]
+        include_checkpoints: bool,
+
+        /// Tera template file for text output (rendered against the --json object)
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Manage the full-text search index used by `search --indexed`
+    Index {
+        #[command(subcommand)]
+        command: IndexCommand,
+    },
+
+    /// Check session files for truncation, bad encoding, and empty files
+    Fsck {
+        /// Agent whose session files to check: codex, gemini, claude, cursor, aider, windsurf, cline, or zed (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_type)]
+        agent: AgentType,
+
+        /// Write salvageable content of damaged files to <file>.repaired
+        #[arg(long)]
+        repair: bool,
+
         /// Emit structured JSON instead of text
         #[arg(long)]
         json: bool,
     },
 
+    /// Time list/search/read/redaction against a generated session store
+    Bench {
+        /// Session format to generate: codex or claude
+        #[arg(long, value_parser = parse_agent_type, default_value = "codex")]
+        agent: AgentType,
+
+        /// Number of session files to generate
+        #[arg(long, default_value = "200", value_parser = clap::value_parser!(u64).range(1..))]
+        files: u64,
+
+        /// Approximate size of each session file in KB
+        #[arg(long, default_value = "64", value_parser = clap::value_parser!(u64).range(1..))]
+        size_kb: u64,
+
+        /// Runs per measurement
+        #[arg(long, default_value = "3", value_parser = clap::value_parser!(u64).range(1..))]
+        iterations: u64,
+
+        /// Keep the generated store instead of deleting it
+        #[arg(long)]
+        keep: bool,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check handoff packets before passing them to `report`
+    Handoff {
+        #[command(subcommand)]
+        command: HandoffCommand,
+    },
+
+    /// Manage human-friendly session aliases
+    Alias {
+        #[command(subcommand)]
+        command: AliasCommand,
+    },
+
+    /// Parse sessions with sandboxed WASM plugins (build with --features wasm-plugins)
+    #[cfg(feature = "wasm-plugins")]
+    Plugin {
+        #[command(subcommand)]
+        command: PluginCommand,
+    },
+
+    /// Interactive prompt that keeps the selected agent, cwd, and last results between commands
+    Repl,
+
     /// Roast agents based on their session content (easter egg)
     #[command(name = "trash-talk")]
     TrashTalk {
@@ -129,12 +695,139 @@ enum Commands {
         cwd: Option<String>,
     },
 
+    /// Install cross-provider instruction scaffolding in this project
+    Setup {
+        /// Project directory to set up (defaults to the current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// Report what would change without writing files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Replace existing snippets and managed blocks
+        #[arg(long)]
+        force: bool,
+
+        /// Also build the context pack and install its pre-push hook
+        #[arg(long)]
+        context_pack: bool,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Build/sync/install context-pack automation
     #[command(name = "context-pack")]
     ContextPack {
         #[command(subcommand)]
         command: ContextPackCommand,
     },
+
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the bridge(1) man page, or write one page per command to a directory
+    Man {
+        /// Write bridge.1 and a page per subcommand (bridge-read.1, ...) here instead of printing
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum IndexCommand {
+    /// Rebuild the index from every session on disk
+    Build {
+        /// Agent to index: codex, gemini, claude, cursor, aider, windsurf, cline, zed, or all (aliases such as claude-code, and names from adapters.toml, accepted)
+        #[arg(long, value_parser = parse_agent_scope, default_value = "all")]
+        agent: AgentScope,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HandoffCommand {
+    /// Validate a handoff JSON file against the handoff schema
+    Validate {
+        /// Path to the handoff JSON file
+        #[arg(index = 1)]
+        file: String,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AliasCommand {
+    /// Register or replace an alias for <agent>:<session-id>
+    Add {
+        /// Alias name
+        #[arg(index = 1)]
+        name: String,
+
+        /// Target session spec: <agent>:<session-id>
+        #[arg(index = 2)]
+        target: String,
+    },
+
+    /// List registered aliases
+    List {
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Remove an alias
+    Remove {
+        /// Alias name
+        #[arg(index = 1)]
+        name: String,
+    },
+}
+
+#[cfg(feature = "wasm-plugins")]
+#[derive(Subcommand)]
+enum PluginCommand {
+    /// Read a session through a configured WASM parser plugin
+    Read {
+        /// Plugin name ([plugins.<name>] in the config file, or <name>.wasm in the plugins directory)
+        #[arg(index = 1)]
+        name: String,
+
+        /// Session file name to read (default: newest in sessions_dir)
+        #[arg(long)]
+        id: Option<String>,
+
+        /// How --id is matched against session file names
+        #[arg(long, value_enum, default_value = "substring", default_value_if("exact", ArgPredicate::IsPresent, "exact"))]
+        id_match: agents::IdMatch,
+
+        /// Shorthand for --id-match exact
+        #[arg(long, conflicts_with = "id_match")]
+        exact: bool,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List registered plugins
+    List {
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -203,6 +896,10 @@ enum ContextPackCommand {
         /// Override pack directory (default: .agent-context or BRIDGE_CONTEXT_PACK_DIR)
         #[arg(long)]
         pack_dir: Option<String>,
+
+        /// Show the diff between the snapshot and current/ without restoring
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Warn when context-relevant files changed without pack update
@@ -212,31 +909,144 @@ enum ContextPackCommand {
         #[arg(long)]
         base: Option<String>,
 
-        /// Working directory (default: current directory)
-        #[arg(long)]
-        cwd: Option<String>,
-    },
+        /// Working directory (default: current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+    },
+
+    /// Insert/update the pack read-order block in CLAUDE.md, AGENTS.md, and GEMINI.md
+    #[command(name = "sync-agents")]
+    SyncAgents {
+        /// Target directory inside repo (default: current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// Override pack directory (default: .agent-context or BRIDGE_CONTEXT_PACK_DIR)
+        #[arg(long)]
+        pack_dir: Option<String>,
+
+        /// Preview changes without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Generate a compact AGENTS.md block (fast facts, invariants, validation) from the built pack
+    #[command(name = "agents-md")]
+    AgentsMd {
+        /// Target directory inside repo (default: current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// Override pack directory (default: .agent-context or BRIDGE_CONTEXT_PACK_DIR)
+        #[arg(long)]
+        pack_dir: Option<String>,
+
+        /// File to write, relative to the repo root (default: AGENTS.md)
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Print the generated block without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Concatenate the current pack into one prompt-sized blob
+    Render {
+        /// Override pack directory (default: .agent-context or BRIDGE_CONTEXT_PACK_DIR)
+        #[arg(long)]
+        pack_dir: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: context_pack::RenderFormat,
+
+        /// Approximate token ceiling; lowest-priority sections are dropped to fit
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        max_tokens: Option<u64>,
+
+        /// Write to a file instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Check pack content quality; exits non-zero when issues are found
+    Lint {
+        /// Override pack directory (default: .agent-context or BRIDGE_CONTEXT_PACK_DIR)
+        #[arg(long)]
+        pack_dir: Option<String>,
+
+        /// Emit structured JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Maintain the build history log (history.jsonl)
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+    /// Move all but the latest entries into history.archive.jsonl.gz
+    Compact {
+        /// Number of most recent entries to keep
+        #[arg(long, default_value_t = 20)]
+        keep: usize,
+
+        /// Override pack directory (default: .agent-context or BRIDGE_CONTEXT_PACK_DIR)
+        #[arg(long)]
+        pack_dir: Option<String>,
+    },
+}
+
+/// A canonical adapter name: a built-in agent or one declared in `adapters.toml`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct AgentType(&'static str);
+
+/// `--agent` parser that accepts aliases and any case (see `adapters::normalize_agent`).
+fn parse_agent_type(raw: &str) -> std::result::Result<AgentType, String> {
+    adapters::normalize_agent(raw).map(AgentType).map_err(|err| err.to_string())
+}
+
+/// `--agent` for `list` and `search`: one agent, or `all` to fan out across
+/// every adapter.
+#[derive(Copy, Clone, Debug)]
+enum AgentScope {
+    One(AgentType),
+    All,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
-enum AgentType {
-    Codex,
-    Gemini,
-    Claude,
-    Cursor,
+fn parse_agent_scope(raw: &str) -> std::result::Result<AgentScope, String> {
+    if raw.trim().eq_ignore_ascii_case("all") {
+        return Ok(AgentScope::All);
+    }
+    parse_agent_type(raw).map(AgentScope::One)
 }
 
-impl AgentType {
-    fn as_str(&self) -> &'static str {
+impl AgentScope {
+    fn agent(&self) -> Option<&'static str> {
+        match self {
+            AgentScope::One(agent) => Some(agent.as_str()),
+            AgentScope::All => None,
+        }
+    }
+
+    fn agents(&self) -> Vec<&'static str> {
         match self {
-            AgentType::Codex => "codex",
-            AgentType::Gemini => "gemini",
-            AgentType::Claude => "claude",
-            AgentType::Cursor => "cursor",
+            AgentScope::One(agent) => vec![agent.as_str()],
+            AgentScope::All => adapters::agent_names(),
         }
     }
 }
 
+impl AgentType {
+    fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
 fn main() {
     let cli = match Cli::try_parse() {
         Ok(c) => c,
@@ -257,154 +1067,908 @@ fn main() {
                     "message": msg.to_string().lines().next().unwrap_or(""),
                 });
                 println!("{}", serde_json::to_string_pretty(&error_json).unwrap_or_default());
-                std::process::exit(1);
+                std::process::exit(code.exit_code());
             } else {
                 e.exit();
             }
         }
     };
     let json_mode = is_json_mode(&cli.command);
-
+    if let Some(dir) = &cli.portable {
+        config::set_portable_home(dir);
+    }
     if let Err(err) = run(cli) {
+        if let Some(VerdictExit(code)) = err.downcast_ref::<VerdictExit>() {
+            std::process::exit(*code);
+        }
+        let code = agents::error_code(&err);
         if json_mode {
             let msg = format!("{:#}", err);
-            let code = agents::classify_error(&msg);
-            let error_json = serde_json::json!({
+            let mut error_json = serde_json::json!({
                 "error_code": code.as_str(),
                 "message": msg,
             });
+            let candidates = agents::error_candidates(&err);
+            if !candidates.is_empty() {
+                error_json["candidates"] = serde_json::json!(candidates);
+            }
             println!("{}", serde_json::to_string_pretty(&error_json).unwrap_or_default());
         } else {
             eprintln!("{:#}", err);
         }
-        std::process::exit(1);
+        std::process::exit(code.exit_code());
     }
 }
 
 fn is_json_mode(command: &Commands) -> bool {
     match command {
         Commands::Read { json, .. } => *json,
+        Commands::Prompt { json, .. } => *json,
+        Commands::Export { format, .. } => *format == export::ExportFormat::Json,
+        Commands::Transfer { json, .. } => *json,
+        Commands::Inject { json, .. } => *json,
         Commands::Compare { json, .. } => *json,
         Commands::Report { json, .. } => *json,
         Commands::List { json, .. } => *json,
+        Commands::Correlate { json, .. } => *json,
+        Commands::Stats { json, .. } => *json,
         Commands::Search { json, .. } => *json,
+        Commands::Index { command: IndexCommand::Build { json, .. } } => *json,
+        Commands::Watch { json, .. } => *json,
+        #[cfg(unix)]
+        Commands::Daemon { .. } => false,
+        Commands::Fsck { json, .. } => *json,
+        Commands::Bench { json, .. } => *json,
+        Commands::Handoff { command: HandoffCommand::Validate { json, .. } } => *json,
+        Commands::Alias { command: AliasCommand::List { json } } => *json,
+        Commands::Alias { .. } => false,
+        #[cfg(feature = "wasm-plugins")]
+        Commands::Plugin { command: PluginCommand::Read { json, .. } } => *json,
+        #[cfg(feature = "wasm-plugins")]
+        Commands::Plugin { command: PluginCommand::List { json } } => *json,
+        Commands::Repl => false,
         Commands::TrashTalk { .. } => false,
+        Commands::Setup { json, .. } => *json,
+        Commands::ContextPack { command: ContextPackCommand::Lint { json, .. } } => *json,
         Commands::ContextPack { .. } => false,
+        Commands::Completions { .. } | Commands::Man { .. } => false,
+    }
+}
+
+/// `read --tokens` estimates for `session`. The session estimate covers its
+/// whole transcript; formats the transcript parser cannot read fall back to
+/// the returned messages.
+fn session_tokens(session: &agents::Session) -> serde_json::Value {
+    let returned = if session.messages.is_empty() { vec![session.content.clone()] } else { session.messages.clone() };
+    let mut transcript = std::fs::read(&session.source)
+        .map(|bytes| agents::transcript(session.agent, &bytes, session.session_id.as_deref()))
+        .unwrap_or_default();
+    if transcript.is_empty() {
+        transcript = returned.iter().map(|text| agents::TranscriptMessage { role: "assistant", text: text.clone(), timestamp: None }).collect();
+    }
+    pricing::token_estimate(&returned, &transcript, session.model.as_deref())
+}
+
+/// Read `agent`'s session selected by `options` and distill it for
+/// `transfer` and `inject`. `options.id` may be an alias.
+fn distill_session(agent: &str, mut options: agents::ReadOptions) -> Result<(agents::Session, transfer::Packet)> {
+    let adapter = adapters::get_adapter(agent).with_context(|| format!("Unsupported agent: {}", agent))?;
+    options.id = options.id.map(|value| aliases::resolve_id(agent, &value)).transpose()?;
+    let session = adapter.read_session(&options)?;
+    let bytes = std::fs::read(&session.source).with_context(|| format!("Failed to read {}", session.source))?;
+    let messages = agents::transcript(session.agent, &bytes, session.session_id.as_deref());
+    if messages.is_empty() {
+        return Err(anyhow!("{} session has no messages: {}", format_agent_name(session.agent), session.source));
+    }
+    for warning in &session.warnings {
+        eprintln!("{}", theme::paint("warning", &utils::sanitize_for_terminal(warning)));
+    }
+    let task = agents::first_prompt(session.agent, &bytes, session.session_id.as_deref());
+    let packet = transfer::distill(task, &messages);
+    Ok((session, packet))
+}
+
+/// `--from/--to` or `--offset/--limit` as a message range; `None` keeps `--last`.
+fn message_range(
+    from: Option<usize>,
+    to: Option<usize>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Option<agents::MessageRange>> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if to <= from {
+            return Err(anyhow!("--to ({}) must be greater than --from ({})", to, from));
+        }
+    }
+    if limit == Some(0) {
+        return Err(anyhow!("--limit must be at least 1"));
     }
+    Ok(match (from, to, offset, limit) {
+        (None, None, None, None) => None,
+        (None, None, offset, limit) => {
+            let from = offset.unwrap_or(0);
+            Some(agents::MessageRange { from, to: limit.map(|limit| from + limit) })
+        }
+        (from, to, _, _) => Some(agents::MessageRange { from: from.unwrap_or(0), to }),
+    })
+}
+
+/// `--since/--until` as epoch-millisecond bounds; `None` when neither is set.
+fn time_span(agent: &str, since: Option<&str>, until: Option<&str>) -> Result<Option<agents::TimeSpan>> {
+    if since.is_none() && until.is_none() {
+        return Ok(None);
+    }
+    if !agents::supports_time_span(agent) {
+        return Err(anyhow!(
+            "--since/--until needs per-message timestamps, which {} sessions do not record (supported: codex, claude, gemini)",
+            format_agent_name(agent)
+        ));
+    }
+    let span = agents::TimeSpan {
+        since: since.map(agents::parse_time_bound).transpose()?,
+        until: until.map(agents::parse_time_bound).transpose()?,
+    };
+    if let (Some(since), Some(until)) = (span.since, span.until) {
+        if until < since {
+            return Err(anyhow!("--until must not be earlier than --since"));
+        }
+    }
+    Ok(Some(span))
 }
 
 fn run(cli: Cli) -> Result<()> {
+    // Set every global flag for each command, so one REPL command's flags do
+    // not carry over to the next.
+    let color = if cli.no_color { theme::ColorChoice::Never } else { cli.color };
+    theme::init(color);
+    redact::set_redact_pii(cli.redact_pii);
+    redact::set_no_redact(false);
+    adapters::common::set_max_file_size_mb(cli.max_file_size);
+    adapters::common::reset_scan_truncated();
+    adapters::codex::set_include_archived(false);
+    adapters::gemini::set_include_checkpoints(false);
+    if cli.no_redact {
+        if !redact::unredacted_allowed() {
+            return Err(anyhow!(
+                "--no-redact requires BRIDGE_ALLOW_UNREDACTED=1 or allow_unredacted = true under [redaction] in the config"
+            ));
+        }
+        redact::set_no_redact(true);
+        eprintln!(
+            "{}",
+            theme::paint("warning", "WARNING: redaction is OFF (--no-redact). Output may contain API keys, tokens, and passwords; do not share it.")
+        );
+    }
+    apply_data_dirs(&cli.data_dirs, command_agent(&cli.command))?;
+    let output = JsonOutput { fields: cli.fields, query: cli.json_query };
     match cli.command {
         Commands::Read {
             agent,
             id,
+            all_matching,
+            id_match,
+            exact: _,
             cwd,
+            cwd_match,
             chats_dir,
             last,
+            from,
+            to,
+            offset,
+            limit,
+            since,
+            until,
+            timing,
+            tokens,
+            template,
+            render,
+            format,
             json,
         } => {
-            let effective_cwd = effective_cwd(cwd);
-            let last_n = last.max(1);
             let adapter = adapters::get_adapter(agent.as_str())
                 .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?;
-            let session = adapter.read_session(
-                id.as_deref(),
-                &effective_cwd,
-                chats_dir.as_deref(),
-                last_n,
-            )?;
+            // Several ids (or --all-matching) make --json a `{sessions, errors}`
+            // object, even for one session.
+            let several = id.len() > 1 || all_matching;
+            let ids = id
+                .iter()
+                .map(|value| aliases::resolve_id(agent.as_str(), value).map(Some))
+                .collect::<Result<Vec<_>>>()?;
+            let span = time_span(agent.as_str(), since.as_deref(), until.as_deref())?;
+            let mut range = message_range(from, to, offset, limit)?;
+            if span.is_some() && last.is_none() && range.is_none() {
+                range = Some(agents::MessageRange { from: 0, to: None });
+            }
+            let options = agents::ReadOptions {
+                id: None,
+                id_match,
+                cwd: effective_cwd(cwd),
+                cwd_match,
+                chats_dir,
+                last_n: last.unwrap_or(1).max(1),
+                range,
+                span,
+                all_matching: false,
+            };
+            // With several ids, one that cannot be read is reported in
+            // `errors` instead of failing the rest; it fails only if all do.
+            let mut sessions = Vec::new();
+            let mut errors = Vec::new();
+            for id in if ids.is_empty() { vec![None] } else { ids } {
+                let options = agents::ReadOptions { id, ..options.clone() };
+                let read = if all_matching {
+                    adapters::read_matching(&options, |options| adapter.read_session(options))
+                } else {
+                    adapter.read_session(&options).map(|session| vec![session])
+                };
+                match read {
+                    Ok(read) => sessions.extend(read),
+                    Err(error) if several => errors.push((options.id, error)),
+                    Err(error) => return Err(error),
+                }
+            }
+            if sessions.is_empty() && !errors.is_empty() {
+                return Err(errors.remove(0).1);
+            }
+            let template = template.as_deref().map(template::OutputTemplate::load).transpose()?;
+
+            let mut reports = Vec::new();
+            let mut texts = Vec::new();
+            for mut session in sessions {
+                if adapters::common::scan_truncated() {
+                    session.warnings.push(adapters::common::scan_truncation_warning());
+                }
+
+                let mut report = agents::session_json(&session);
+                if timing {
+                    report["timing"] = session.timing.clone().unwrap_or(serde_json::Value::Null);
+                }
+                if tokens {
+                    report["tokens"] = session_tokens(&session);
+                }
+                if json {
+                    reports.push(report);
+                    continue;
+                }
+
+                for warning in &session.warnings {
+                    eprintln!("{}", theme::paint("warning", &utils::sanitize_for_terminal(warning)));
+                }
+                if let Some(template) = &template {
+                    texts.push(template.render(&report)?);
+                    continue;
+                }
+                if format == export::ReadFormat::Markdown {
+                    // Reply numbers are only known when no time span filtered the list.
+                    let first_index = match (range, span) {
+                        (_, Some(_)) => None,
+                        (Some(range), None) => Some(range.from),
+                        (None, None) => Some(session.message_count.saturating_sub(session.messages_returned)),
+                    };
+                    let markdown = export::read_markdown(&session, first_index);
+                    texts.push(utils::sanitize_for_terminal(&markdown));
+                    continue;
+                }
+                let mut text = String::new();
+                writeln!(
+                    text,
+                    "SOURCE: {} Session ({})",
+                    theme::paint(session.agent, &format_agent_name(session.agent)),
+                    utils::sanitize_for_terminal(&session.source)
+                )?;
+                if timing {
+                    match &session.timing {
+                        Some(t) => writeln!(
+                            text,
+                            "TIMING: {:.1}s across {} replies ({} -> {})",
+                            t["duration_seconds"].as_f64().unwrap_or(0.0),
+                            t["turns"].as_array().map(|turns| turns.len()).unwrap_or(0),
+                            t["started_at"].as_str().unwrap_or(""),
+                            t["ended_at"].as_str().unwrap_or("")
+                        )?,
+                        None => writeln!(text, "TIMING: unavailable (session has no message timestamps)")?,
+                    }
+                }
+                if tokens {
+                    let estimate = &report["tokens"];
+                    let session_usage = &estimate["session"];
+                    write!(
+                        text,
+                        "TOKENS: ~{} returned; session ~{} (~{} in / ~{} out)",
+                        estimate["returned_tokens"],
+                        session_usage["total_tokens"],
+                        session_usage["input_tokens"],
+                        session_usage["output_tokens"]
+                    )?;
+                    match estimate["cost"]["total_usd"].as_f64() {
+                        Some(usd) => writeln!(text, ", ~${:.4}", usd)?,
+                        None => writeln!(text)?,
+                    }
+                }
+                writeln!(text, "---")?;
+                let content = utils::sanitize_for_terminal(&session.content);
+                if render {
+                    text.push_str(&theme::render_markdown(&content));
+                } else {
+                    writeln!(text, "{}", content)?;
+                }
+                texts.push(text);
+            }
+
+            let error_entries = errors
+                .iter()
+                .map(|(id, error)| {
+                    serde_json::json!({
+                        "id": id,
+                        "agent": agent.as_str(),
+                        "error_code": agents::error_code(error).as_str(),
+                        "message": error.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            if json {
+                let value = if several {
+                    serde_json::json!({ "sessions": reports, "errors": error_entries })
+                } else {
+                    reports.remove(0)
+                };
+                hooks::emit("post_read", &output.render(value)?)?;
+            } else {
+                for (id, error) in &errors {
+                    let message = format!("Warning: could not read id {}: {}", id.as_deref().unwrap_or(""), error);
+                    eprintln!("{}", theme::paint("warning", &utils::sanitize_for_terminal(&message)));
+                }
+                hooks::emit("post_read", &texts.join("\n"))?;
+            }
+        }
+        Commands::Prompt { agent, id, id_match, exact: _, cwd, cwd_match, chats_dir, json } => {
+            let adapter = adapters::get_adapter(agent.as_str())
+                .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?;
+            let id = id
+                .map(|value| aliases::resolve_id(agent.as_str(), &value))
+                .transpose()?;
+            let session = adapter.read_session(&agents::ReadOptions {
+                id,
+                id_match,
+                cwd: effective_cwd(cwd),
+                cwd_match,
+                chats_dir,
+                last_n: 1,
+                range: None,
+                span: None,
+                all_matching: false,
+            })?;
+            let bytes = std::fs::read(&session.source)
+                .with_context(|| format!("Failed to read {}", session.source))?;
+            // An Aider history file holds every session run in the repo.
+            let prompt = agents::first_prompt(session.agent, &bytes, session.session_id.as_deref())
+                .with_context(|| format!("No user prompt found in {}", session.source))?;
 
             if json {
-                let report = json!({
+                output.print(json!({
                     "agent": session.agent,
-                    "source": session.source,
-                    "content": session.content,
-                    "warnings": session.warnings,
                     "session_id": session.session_id,
+                    "source": session.source,
                     "cwd": session.cwd,
-                    "timestamp": session.timestamp,
-                    "message_count": session.message_count,
-                    "messages_returned": session.messages_returned,
-                });
-                println!("{}", serde_json::to_string_pretty(&report)?);
+                    "prompt": prompt,
+                }))?;
             } else {
                 for warning in &session.warnings {
-                    eprintln!("{}", utils::sanitize_for_terminal(warning));
+                    eprintln!("{}", theme::paint("warning", &utils::sanitize_for_terminal(warning)));
                 }
-                println!("SOURCE: {} Session ({})", format_agent_name(session.agent), utils::sanitize_for_terminal(&session.source));
-                println!("---");
-                println!("{}", utils::sanitize_for_terminal(&session.content));
+                println!("{}", utils::sanitize_for_terminal(&prompt));
             }
         }
-        Commands::Compare { sources, cwd, normalize, json } => {
-            let effective_cwd = effective_cwd(cwd);
-            let source_specs = sources
-                .iter()
-                .map(|raw| report::parse_source_arg(raw))
-                .collect::<Result<Vec<report::SourceSpec>>>()?;
-
-            let request = report::ReportRequest {
-                mode: "analyze".to_string(),
-                task: "Compare agent outputs".to_string(),
-                success_criteria: vec![
-                    "Identify agreements and contradictions".to_string(),
-                    "Highlight unavailable sources".to_string(),
-                ],
-                sources: source_specs,
-                constraints: Vec::new(),
-                normalize,
+        Commands::Export { agent, id, id_match, exact: _, cwd, cwd_match, chats_dir, format, output } => {
+            let adapter = adapters::get_adapter(agent.as_str())
+                .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?;
+            let id = id
+                .map(|value| aliases::resolve_id(agent.as_str(), &value))
+                .transpose()?;
+            let session = adapter.read_session(&agents::ReadOptions {
+                id,
+                id_match,
+                cwd: effective_cwd(cwd),
+                cwd_match,
+                chats_dir,
+                ..agents::ReadOptions::default()
+            })?;
+            let bytes = std::fs::read(&session.source)
+                .with_context(|| format!("Failed to read {}", session.source))?;
+            let messages = agents::transcript(session.agent, &bytes, session.session_id.as_deref());
+            if messages.is_empty() {
+                return Err(anyhow!("{} session has no messages: {}", format_agent_name(session.agent), session.source));
+            }
+            for warning in &session.warnings {
+                eprintln!("{}", theme::paint("warning", &utils::sanitize_for_terminal(warning)));
+            }
+            let rendered = export::render(&session, &format_agent_name(session.agent), &messages, format)?;
+            export::write(&rendered, output.as_deref())?;
+            if let Some(path) = output {
+                eprintln!("Exported {} message(s) to {}", messages.len(), path);
+            }
+        }
+        Commands::Transfer { from, to, id, id_match, exact: _, cwd, cwd_match, chats_dir, copy, json } => {
+            let options = agents::ReadOptions {
+                id,
+                id_match,
+                cwd: effective_cwd(cwd),
+                cwd_match,
+                chats_dir,
+                ..agents::ReadOptions::default()
             };
-
+            let (session, packet) = distill_session(from.as_str(), options)?;
+            let prompt = transfer::prompt(
+                &session,
+                &format_agent_name(session.agent),
+                to.as_str(),
+                setup::instruction_file(to.as_str()),
+                &packet,
+            );
+            if copy {
+                let tool = transfer::copy_to_clipboard(&prompt)?;
+                eprintln!("Copied continuation prompt for {} to the clipboard ({})", format_agent_name(to.as_str()), tool);
+            }
+            if json {
+                output.print(transfer::to_json(&session, to.as_str(), &packet, &prompt))?;
+            } else {
+                print!("{}", prompt);
+            }
+        }
+        Commands::Inject { target, from, id, id_match, exact: _, cwd, cwd_match, chats_dir, dry_run, json } => {
+            let cwd = effective_cwd(cwd);
+            let options = agents::ReadOptions {
+                id,
+                id_match,
+                cwd: cwd.clone(),
+                cwd_match,
+                chats_dir,
+                ..agents::ReadOptions::default()
+            };
+            let (session, packet) = distill_session(from.as_str(), options)?;
+            let result = transfer::inject(
+                &utils::normalize_path(&cwd)?,
+                &target,
+                &session,
+                &format_agent_name(session.agent),
+                &packet,
+                dry_run,
+            )?;
+            if json {
+                output.print(result)?;
+            } else {
+                println!(
+                    "{}Handoff from {} written to {} ({})",
+                    if dry_run { "(dry run) " } else { "" },
+                    format_agent_name(session.agent),
+                    result["path"].as_str().unwrap_or(""),
+                    result["note"].as_str().unwrap_or("")
+                );
+            }
+        }
+        Commands::Compare {
+            sources,
+            cwd,
+            cwd_match,
+            normalize,
+            semantic,
+            last,
+            full,
+            correlate,
+            fail_on,
+            min_score,
+            template,
+            render,
+            json,
+        } => {
+            let effective_cwd = effective_cwd(cwd);
+            let mut request = report::compare_request(&sources, normalize, cwd_match)?;
+            if correlate {
+                let options = correlate::CorrelateOptions {
+                    cwd: Some(effective_cwd.clone()),
+                    cwd_match,
+                    limit: 50,
+                    window_minutes: 60,
+                };
+                for note in correlate::pick_counterparts(&mut request.sources, &options)? {
+                    eprintln!("{}", theme::paint("warning", &format!("Warning: {}", note)));
+                }
+            }
+            request.semantic = semantic;
+            request.last_n = if full { usize::MAX } else { last as usize };
+            request.thresholds = report::Thresholds { fail_on, min_score: min_score.map(i64::from) };
             let result = report::build_report(&request, &effective_cwd);
-            emit_report_output(&result, json)?;
+            warn_if_scan_truncated();
+            emit_report_output(result, json, template.as_deref(), render, &output)?;
         }
-        Commands::Report { handoff, cwd, json } => {
+        Commands::Report { handoff, cwd, allow_commands, fail_on, min_score, template, render, json } => {
             let effective_cwd = effective_cwd(cwd);
-            let request = report::load_handoff(&handoff)
+            let mut request = report::load_handoff(&handoff)
                 .with_context(|| format!("Failed to load handoff packet from {}", handoff))?;
+            request.allow_commands = allow_commands;
+            if fail_on.is_some() {
+                request.thresholds.fail_on = fail_on;
+            }
+            if let Some(min_score) = min_score {
+                request.thresholds.min_score = Some(i64::from(min_score));
+            }
             let result = report::build_report(&request, &effective_cwd);
-            emit_report_output(&result, json)?;
+            warn_if_scan_truncated();
+            emit_report_output(result, json, template.as_deref(), render, &output)?;
         }
-        Commands::List { agent, cwd, limit, json } => {
+        Commands::List {
+            agent,
+            cwd,
+            cwd_match,
+            limit,
+            offset,
+            cursor,
+            latest_per_project,
+            sort,
+            reverse,
+            filter_cwd,
+            include_archived,
+            include_checkpoints,
+            template,
+            json,
+        } => {
+            let cwd_glob = filter_cwd.as_deref().map(agents::cwd_glob).transpose()?;
+            let key = format!(
+                "list {:?} {:?} {:?} {} {:?} {} {:?} {} {}",
+                agent, cwd, cwd_match, latest_per_project, sort, reverse, filter_cwd, include_archived, include_checkpoints
+            );
+            let page = page::Page::request(offset, cursor.as_deref(), &key)?;
+            let fetch = page.as_ref().map_or(limit, |page| page.fetch(limit));
+            adapters::codex::set_include_archived(include_archived);
+            adapters::gemini::set_include_checkpoints(include_checkpoints);
             let normalized_cwd = cwd.map(|value| {
                 utils::normalize_path(&value)
                     .map(|path| path.to_string_lossy().to_string())
                     .unwrap_or(value)
             });
-            let adapter = adapters::get_adapter(agent.as_str())
-                .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?;
-            let entries = adapter.list_sessions(normalized_cwd.as_deref(), limit)?;
+            let list = |limit: usize| -> Result<Vec<serde_json::Value>> {
+                match agent {
+                    AgentScope::One(agent) => adapters::get_adapter(agent.as_str())
+                        .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?
+                        .list_sessions(normalized_cwd.as_deref(), cwd_match, limit),
+                    AgentScope::All => Ok(adapters::list_all(normalized_cwd.as_deref(), cwd_match, limit)),
+                }
+            };
+            // Anything but the default newest-first order needs every session before --limit applies.
+            let whole = latest_per_project || cwd_glob.is_some() || sort != agents::ListSort::Modified || reverse;
+            let mut entries = list(if whole { usize::MAX } else { fetch })?;
+            if let Some(glob) = &cwd_glob {
+                entries.retain(|entry| entry["cwd"].as_str().is_some_and(|cwd| glob.is_match(cwd)));
+            }
+            if latest_per_project {
+                entries = agents::latest_per_project(entries);
+            }
+            if sort.needs_details() {
+                agents::describe_list_entries(&mut entries);
+            }
+            agents::sort_list_entries(&mut entries, sort, reverse);
+            let mut listing = page::Listing::new(page.as_ref(), entries, limit);
+            if !sort.needs_details() {
+                agents::describe_list_entries(&mut listing.sessions);
+            }
+            warn_if_scan_truncated();
+            agents::flag_truncated(&mut listing.sessions, adapters::common::scan_truncated());
 
             if json {
-                println!("{}", serde_json::to_string_pretty(&entries)?);
+                output.print_sessions(listing.into_json())?;
+            } else if let Some(path) = template {
+                let renderer = template::OutputTemplate::load(&path)?;
+                for entry in &listing.sessions {
+                    renderer.print(entry)?;
+                }
+            } else {
+                print!("{}", agents::sessions_table(&listing.sessions));
+            }
+        }
+        Commands::Correlate { cwd, cwd_match, limit, window, json } => {
+            let cwd = cwd.map(|value| {
+                utils::normalize_path(&value)
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or(value)
+            });
+            let groups = correlate::correlate(&correlate::CorrelateOptions { cwd, cwd_match, limit, window_minutes: window });
+            warn_if_scan_truncated();
+            if json {
+                output.print(json!(groups))?;
+            } else if groups.is_empty() {
+                println!("No correlated sessions found.");
             } else {
-                for entry in &entries {
-                    println!("{}", serde_json::to_string(entry).unwrap_or_default());
+                let mut text = String::new();
+                for group in &groups {
+                    writeln!(
+                        text,
+                        "Group {} ({}) score {}",
+                        group["group"],
+                        group["cwd"].as_str().unwrap_or(""),
+                        group["score"]
+                    )?;
+                    for session in group["sessions"].as_array().into_iter().flatten() {
+                        writeln!(
+                            text,
+                            "  {:<8} {}  {} .. {}",
+                            session["agent"].as_str().unwrap_or(""),
+                            session["session_id"].as_str().unwrap_or(""),
+                            session["started_at"].as_str().unwrap_or("?"),
+                            session["ended_at"].as_str().unwrap_or("?")
+                        )?;
+                    }
+                    let files = group["shared_files"].as_array().into_iter().flatten().filter_map(|file| file.as_str()).collect::<Vec<_>>();
+                    if !files.is_empty() {
+                        writeln!(text, "  shared files: {}", files.join(", "))?;
+                    }
                 }
+                print!("{}", utils::sanitize_for_terminal(&text));
+            }
+        }
+        Commands::Stats { agent, cwd, cwd_match, since, until, top, json } => {
+            let span = agents::TimeSpan {
+                since: Some(agents::parse_time_bound(&since)?),
+                until: until.as_deref().map(agents::parse_time_bound).transpose()?,
+            };
+            if span.until.is_some_and(|until| span.since.is_some_and(|since| until < since)) {
+                return Err(anyhow!("--until must not be earlier than --since"));
+            }
+            let cwd = cwd.map(|value| {
+                utils::normalize_path(&value)
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or(value)
+            });
+            let entries = match agent {
+                AgentScope::One(agent) => adapters::get_adapter(agent.as_str())
+                    .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?
+                    .list_sessions(cwd.as_deref(), cwd_match, usize::MAX)?,
+                AgentScope::All => adapters::list_all(cwd.as_deref(), cwd_match, usize::MAX),
+            };
+            let summary = stats::summarize(&entries, span, top);
+            warn_if_scan_truncated();
+            if json {
+                output.print(summary)?;
+            } else {
+                print!("{}", utils::sanitize_for_terminal(&stats::to_text(&summary)));
             }
         }
-        Commands::Search { query, agent, cwd, limit, json } => {
+        Commands::Watch { agent, cwd, cwd_match, interval, json } => {
+            watch::run(&watch::WatchOptions {
+                agents: agent.agents(),
+                cwd: effective_cwd(cwd),
+                cwd_match,
+                interval: std::time::Duration::from_millis(interval),
+                json,
+            })?;
+        }
+        #[cfg(unix)]
+        Commands::Daemon { socket } => {
+            daemon::serve(&daemon::socket_path(socket.as_deref()))?;
+        }
+        Commands::Search {
+            query,
+            agent,
+            cwd,
+            cwd_match,
+            limit,
+            offset,
+            cursor,
+            regex,
+            all_of,
+            any_of,
+            not,
+            in_role,
+            field,
+            indexed,
+            include_archived,
+            include_checkpoints,
+            template,
+            json,
+        } => {
+            let key = format!(
+                "search {:?} {:?} {:?} {:?} {:?} {:?} {:?} {} {:?} {:?} {} {} {}",
+                agent, cwd, cwd_match, query, all_of, any_of, not, regex, in_role, field, indexed, include_archived, include_checkpoints
+            );
+            let page = page::Page::request(offset, cursor.as_deref(), &key)?;
+            let fetch = page.as_ref().map_or(limit, |page| page.fetch(limit));
+            adapters::codex::set_include_archived(include_archived);
+            adapters::gemini::set_include_checkpoints(include_checkpoints);
             let normalized_cwd = cwd.map(|value| {
                 utils::normalize_path(&value)
                     .map(|path| path.to_string_lossy().to_string())
                     .unwrap_or(value)
             });
-            let adapter = adapters::get_adapter(agent.as_str())
-                .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?;
-            let entries = adapter.search_sessions(&query, normalized_cwd.as_deref(), limit)?;
+            let mut search = agents::SearchQuery::new(query.as_deref(), &all_of, &any_of, &not, regex)?;
+            search.role = in_role;
+            search.field = field;
+            let entries = match agent {
+                _ if indexed => search_index::search(
+                    query.as_deref().unwrap_or_default(),
+                    &agent.agents(),
+                    normalized_cwd.as_deref(),
+                    cwd_match,
+                    fetch,
+                )?,
+                AgentScope::One(agent) => adapters::get_adapter(agent.as_str())
+                    .with_context(|| format!("Unsupported agent: {}", agent.as_str()))?
+                    .search_sessions(&search, normalized_cwd.as_deref(), cwd_match, fetch)?,
+                AgentScope::All => adapters::search_all(&search, normalized_cwd.as_deref(), cwd_match, fetch),
+            };
+            let mut listing = page::Listing::new(page.as_ref(), entries, limit);
+            warn_if_scan_truncated();
+            agents::flag_truncated(&mut listing.sessions, adapters::common::scan_truncated());
 
             if json {
-                println!("{}", serde_json::to_string_pretty(&entries)?);
+                output.print_sessions(listing.into_json())?;
+            } else if let Some(path) = template {
+                let renderer = template::OutputTemplate::load(&path)?;
+                for entry in &listing.sessions {
+                    renderer.print(entry)?;
+                }
+            } else {
+                // Search entries carry no title; look them up for the table.
+                agents::describe_list_entries(&mut listing.sessions);
+                print!("{}", agents::sessions_table(&listing.sessions));
+            }
+        }
+        Commands::Index { command: IndexCommand::Build { agent, json } } => {
+            let summary = search_index::build(&agent.agents())?;
+            warn_if_scan_truncated();
+            if json {
+                output.print(json!({
+                    "sessions": summary.sessions,
+                    "terms": summary.terms,
+                    "path": summary.path.to_string_lossy(),
+                }))?;
+            } else {
+                println!(
+                    "Indexed {} session(s), {} term(s) to {}",
+                    summary.sessions,
+                    summary.terms,
+                    summary.path.display()
+                );
+            }
+        }
+        Commands::Fsck { agent, repair, json } => {
+            let clean = fsck::fsck(agent.as_str(), repair, json)?;
+            warn_if_scan_truncated();
+            if !clean {
+                return Err(VerdictExit(1).into());
+            }
+        }
+        Commands::Bench { agent, files, size_kb, iterations, keep, json } => {
+            let result = bench::run(&bench::BenchOptions {
+                agent: agent.as_str(),
+                files: files as usize,
+                size_kb: size_kb as usize,
+                iterations: iterations as usize,
+                keep,
+            })?;
+            if json {
+                output.print(result)?;
+            } else {
+                bench::print_text(&result);
+            }
+        }
+        Commands::Handoff { command: HandoffCommand::Validate { file, json } } => {
+            let raw = report::read_handoff(&file)?;
+            let issues = report::validate_handoff(&raw);
+            if json {
+                output.print(json!({
+                    "file": file,
+                    "valid": issues.is_empty(),
+                    "errors": issues.iter().map(report::HandoffIssue::to_json).collect::<Vec<_>>(),
+                }))?;
+            } else if issues.is_empty() {
+                println!("{}: valid handoff", file);
             } else {
-                for entry in &entries {
-                    println!("{}", serde_json::to_string(entry).unwrap_or_default());
+                println!("{}: {} error(s)", file, issues.len());
+                for issue in &issues {
+                    println!("- {} [{}] {}", issue.path, issue.code, issue.message);
+                }
+            }
+            if !issues.is_empty() {
+                return Err(VerdictExit(1).into());
+            }
+        }
+        Commands::Alias { command } => match command {
+            AliasCommand::Add { name, target } => {
+                let entry = aliases::add(&name, &target)?;
+                println!(
+                    "Alias {} -> {}:{}",
+                    name,
+                    entry["agent"].as_str().unwrap_or(""),
+                    entry["session_id"].as_str().unwrap_or("")
+                );
+            }
+            AliasCommand::List { json } => {
+                let entries = aliases::list()?;
+                if json {
+                    output.print(json!(entries))?;
+                } else {
+                    for entry in &entries {
+                        println!(
+                            "{}\t{}:{}",
+                            entry["alias"].as_str().unwrap_or(""),
+                            entry["agent"].as_str().unwrap_or(""),
+                            entry["session_id"].as_str().unwrap_or("")
+                        );
+                    }
+                }
+            }
+            AliasCommand::Remove { name } => {
+                aliases::remove(&name)?;
+                println!("Removed alias {}", name);
+            }
+        },
+        #[cfg(feature = "wasm-plugins")]
+        Commands::Plugin { command } => match command {
+            PluginCommand::Read { name, id, id_match, exact: _, json } => {
+                let mut session = plugin::read_session(&name, &agents::ReadOptions { id, id_match, ..Default::default() })?;
+                if adapters::common::scan_truncated() {
+                    session.warnings.push(adapters::common::scan_truncation_warning());
+                }
+                let report = agents::session_json(&session);
+                if json {
+                    hooks::emit("post_read", &output.render(report)?)?;
+                } else {
+                    for warning in &session.warnings {
+                        eprintln!("{}", theme::paint("warning", &utils::sanitize_for_terminal(warning)));
+                    }
+                    let text = format!(
+                        "SOURCE: {} Session ({})\n---\n{}\n",
+                        utils::sanitize_for_terminal(session.agent),
+                        utils::sanitize_for_terminal(&session.source),
+                        utils::sanitize_for_terminal(&session.content)
+                    );
+                    hooks::emit("post_read", &text)?;
+                }
+            }
+            PluginCommand::List { json } => {
+                let entries = plugin::list();
+                if json {
+                    output.print(json!(entries))?;
+                } else {
+                    for entry in &entries {
+                        println!(
+                            "{}\t{}",
+                            entry["name"].as_str().unwrap_or(""),
+                            entry["wasm"].as_str().unwrap_or("")
+                        );
+                    }
                 }
             }
+        },
+        Commands::Repl => {
+            repl::run(|args| {
+                match Cli::try_parse_from(std::iter::once("bridge".to_string()).chain(args)) {
+                    // The config is loaded once per process, from the --portable home.
+                    Ok(cli) if cli.portable.is_some() => Err(anyhow!("--portable applies to the whole REPL; pass it to `bridge repl`")),
+                    Ok(cli) => {
+                        let result = run(cli);
+                        theme::init(color);
+                        match result {
+                            Err(err) if err.is::<VerdictExit>() => Ok(()),
+                            other => other,
+                        }
+                    }
+                    // Usage errors and --help print like they would from the shell.
+                    Err(err) => Ok(err.print()?),
+                }
+            })?;
         }
         Commands::TrashTalk { cwd } => {
             let effective = effective_cwd(cwd);
             agents::trash_talk(&effective);
         }
+        Commands::Setup { cwd, dry_run, force, context_pack, json } => {
+            let result = setup::setup(&setup::SetupOptions { cwd: effective_cwd(cwd), dry_run, force, context_pack })?;
+            if json {
+                output.print(result)?;
+            } else {
+                setup::print_text(&result);
+            }
+        }
         Commands::ContextPack { command } => {
             match command {
                 ContextPackCommand::Build {
@@ -441,8 +2005,8 @@ fn run(cli: Cli) -> Result<()> {
                     let target_cwd = effective_cwd(cwd);
                     context_pack::install_hooks(&target_cwd, dry_run)?;
                 }
-                ContextPackCommand::Rollback { snapshot, pack_dir } => {
-                    context_pack::rollback(snapshot.as_deref(), pack_dir.as_deref())?;
+                ContextPackCommand::Rollback { snapshot, pack_dir, dry_run } => {
+                    context_pack::rollback(snapshot.as_deref(), pack_dir.as_deref(), dry_run)?;
                 }
                 ContextPackCommand::CheckFreshness { base, cwd } => {
                     let target_cwd = effective_cwd(cwd);
@@ -451,6 +2015,59 @@ fn run(cli: Cli) -> Result<()> {
                         &target_cwd,
                     )?;
                 }
+                ContextPackCommand::SyncAgents { cwd, pack_dir, dry_run } => {
+                    let target_cwd = effective_cwd(cwd);
+                    context_pack::sync_agents(&target_cwd, pack_dir.as_deref(), dry_run)?;
+                }
+                ContextPackCommand::AgentsMd {
+                    cwd,
+                    pack_dir,
+                    output,
+                    dry_run,
+                } => {
+                    let target_cwd = effective_cwd(cwd);
+                    context_pack::agents_md(&target_cwd, pack_dir.as_deref(), output.as_deref(), dry_run)?;
+                }
+                ContextPackCommand::Render {
+                    pack_dir,
+                    format,
+                    max_tokens,
+                    output,
+                } => {
+                    context_pack::render(context_pack::RenderOptions {
+                        pack_dir,
+                        format,
+                        max_tokens: max_tokens.map(|tokens| tokens as usize),
+                        output,
+                    })?;
+                }
+                ContextPackCommand::Lint { pack_dir, json } => {
+                    if !context_pack::lint(pack_dir.as_deref(), json)? {
+                        return Err(VerdictExit(1).into());
+                    }
+                }
+                ContextPackCommand::History {
+                    command: HistoryCommand::Compact { keep, pack_dir },
+                } => {
+                    context_pack::compact_history(pack_dir.as_deref(), keep)?;
+                }
+            }
+        }
+        Commands::Completions { shell } => {
+            // Buffered so a closed pipe is an error rather than a panic inside clap_complete.
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut Cli::command(), "bridge", &mut script);
+            std::io::stdout().write_all(&script)?;
+        }
+        Commands::Man { output_dir } => {
+            let command = Cli::command().version(env!("CARGO_PKG_VERSION"));
+            match output_dir {
+                Some(dir) => {
+                    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir))?;
+                    clap_mangen::generate_to(command, &dir).with_context(|| format!("Failed to write man pages to {}", dir))?;
+                    eprintln!("Wrote man pages to {}", dir);
+                }
+                None => clap_mangen::Man::new(command).render(&mut std::io::stdout())?,
             }
         }
     }
@@ -458,13 +2075,144 @@ fn run(cli: Cli) -> Result<()> {
     Ok(())
 }
 
-fn emit_report_output(report_value: &serde_json::Value, json_output: bool) -> Result<()> {
+/// The `--agent` of single-agent commands, which a bare `--data-dir` applies to.
+fn command_agent(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Read { agent, .. }
+        | Commands::Prompt { agent, .. }
+        | Commands::Export { agent, .. }
+        | Commands::Fsck { agent, .. } => Some(agent.as_str()),
+        Commands::Transfer { from, .. } | Commands::Inject { from, .. } => Some(from.as_str()),
+        Commands::List { agent, .. }
+        | Commands::Search { agent, .. }
+        | Commands::Watch { agent, .. }
+        | Commands::Stats { agent, .. }
+        | Commands::Index { command: IndexCommand::Build { agent, .. } } => agent.agent(),
+        _ => None,
+    }
+}
+
+/// Resolve `--data-dir [<agent>=]<dir>` values into per-agent store roots.
+fn apply_data_dirs(values: &[String], command_agent: Option<&'static str>) -> Result<()> {
+    let mut dirs = HashMap::new();
+    for value in values {
+        let prefixed = value
+            .split_once('=')
+            .and_then(|(prefix, dir)| adapters::normalize_agent(prefix).ok().map(|agent| (agent, dir)));
+        let (agent, dir) = match prefixed {
+            Some(pair) => pair,
+            None => (
+                command_agent.with_context(|| format!("--data-dir {} needs the form <agent>=<dir> for this command", value))?,
+                value.as_str(),
+            ),
+        };
+        if dir.is_empty() {
+            return Err(anyhow!("--data-dir {} has no directory", value));
+        }
+        dirs.insert(agent, utils::normalize_path(dir)?);
+    }
+    adapters::common::set_data_dirs(dirs);
+    Ok(())
+}
+
+/// Commands whose output has no warnings channel report a truncated scan on
+/// stderr, which keeps `--json` stdout parseable.
+fn warn_if_scan_truncated() {
+    if adapters::common::scan_truncated() {
+        eprintln!("{}", theme::paint("warning", &adapters::common::scan_truncation_warning()));
+    }
+}
+
+/// Print a report as JSON, through a template, or as markdown, then fail
+/// with [`VerdictExit`] when its verdict is `FAIL` or `INCOMPLETE`.
+fn emit_report_output(
+    report_value: serde_json::Value,
+    json_output: bool,
+    template: Option<&str>,
+    render: bool,
+    output: &JsonOutput,
+) -> Result<()> {
+    let code = report::verdict_exit_code(&report_value);
+    let text = report_text(report_value, json_output, template, render, output)?;
+    hooks::emit("post_report", &text)?;
+    if code != 0 {
+        return Err(VerdictExit(code).into());
+    }
+    Ok(())
+}
+
+/// The text `emit_report_output` prints for a report.
+fn report_text(
+    report_value: serde_json::Value,
+    json_output: bool,
+    template: Option<&str>,
+    render: bool,
+    output: &JsonOutput,
+) -> Result<String> {
     if json_output {
-        println!("{}", serde_json::to_string_pretty(report_value)?);
+        return output.render(report_value);
+    }
+    if let Some(path) = template {
+        return template::OutputTemplate::load(path)?.render(&report_value);
+    }
+    let markdown = utils::sanitize_for_terminal(&report::report_to_markdown(&report_value));
+    Ok(if render {
+        theme::render_markdown(&markdown)
     } else {
-        println!("{}", utils::sanitize_for_terminal(&report::report_to_markdown(report_value)));
+        format!("{}\n", theme::colorize_report(&markdown))
+    })
+}
+
+/// Output printed in full whose outcome should still fail the process: a
+/// report verdict, or the problems `fsck`, `handoff validate`, and
+/// `context-pack lint` found. `main` exits with the code without printing an
+/// error, and the REPL ignores it.
+#[derive(Debug)]
+struct VerdictExit(i32);
+
+impl std::fmt::Display for VerdictExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "command failed (exit {})", self.0)
+    }
+}
+
+impl std::error::Error for VerdictExit {}
+
+/// Post-processing shared by every `--json` output: `--fields` projection,
+/// then `--query` extraction.
+struct JsonOutput {
+    fields: Vec<String>,
+    query: Option<String>,
+}
+
+impl JsonOutput {
+    fn print(&self, value: serde_json::Value) -> Result<()> {
+        print!("{}", self.render(value)?);
+        Ok(())
+    }
+
+    /// `print` for `list` and `search`, whose entries are either the whole
+    /// output or, for a page, its `sessions`. `--fields` applies to each entry.
+    fn print_sessions(&self, mut value: serde_json::Value) -> Result<()> {
+        if value.is_array() {
+            return self.print(value);
+        }
+        value["sessions"] = utils::select_fields(value["sessions"].take(), &self.fields);
+        print!("{}", self.finish(value)?);
+        Ok(())
+    }
+
+    /// The pretty-printed JSON, newline-terminated, as `print` would emit it.
+    fn render(&self, value: serde_json::Value) -> Result<String> {
+        self.finish(utils::select_fields(value, &self.fields))
+    }
+
+    fn finish(&self, mut value: serde_json::Value) -> Result<String> {
+        if let Some(query) = &self.query {
+            value = query::apply(&value, query)?;
+        }
+        Ok(format!("{}\n", serde_json::to_string_pretty(&value)?))
     }
-    Ok(())
 }
 
 fn effective_cwd(cwd: Option<String>) -> String {
@@ -475,12 +2223,55 @@ fn effective_cwd(cwd: Option<String>) -> String {
     })
 }
 
-fn format_agent_name(agent: &str) -> &'static str {
-    match agent {
-        "codex" => "Codex",
-        "gemini" => "Gemini",
-        "claude" => "Claude",
-        "cursor" => "Cursor",
-        _ => "Unknown",
+/// Display name for a canonical agent name: `codex` -> `Codex`. Agents from
+/// adapters.toml and plugins are capitalized the same way.
+fn format_agent_name(agent: &str) -> String {
+    let mut chars = agent.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{report_text, run, Cli, JsonOutput};
+    use clap::{CommandFactory, Parser, ValueEnum};
+    use serde_json::json;
+
+    #[test]
+    fn report_template_renders_against_the_json_report() {
+        let path = std::env::temp_dir().join(format!("bridge-report-template-{}.tera", std::process::id()));
+        std::fs::write(&path, "{{ verdict }} {{ score }}: {{ findings | length }} finding(s)\n").unwrap();
+        let output = JsonOutput { fields: Vec::new(), query: None };
+        let report = json!({"verdict": "FAIL", "score": 40, "findings": [{"severity": "P0"}, {"severity": "P2"}]});
+        let templated = report_text(report.clone(), false, path.to_str(), false, &output).unwrap();
+        let json_wins = report_text(report, true, path.to_str(), false, &output).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(templated, "FAIL 40: 2 finding(s)\n");
+        assert!(json_wins.contains("\"verdict\": \"FAIL\""));
+    }
+
+    #[test]
+    fn completions_cover_every_subcommand_in_each_shell() {
+        for shell in clap_complete::Shell::value_variants() {
+            let mut script = Vec::new();
+            clap_complete::generate(*shell, &mut Cli::command(), "bridge", &mut script);
+            let script = String::from_utf8(script).unwrap();
+            assert!(script.contains("context-pack") && script.contains("completions"), "{} script lacks subcommands", shell);
+        }
+    }
+
+    #[test]
+    fn man_output_dir_writes_a_page_per_subcommand() {
+        let dir = std::env::temp_dir().join(format!("bridge-man-{}", std::process::id()));
+        let dir_arg = dir.to_string_lossy().into_owned();
+        run(Cli::parse_from(["bridge", "man", "--output-dir", &dir_arg])).unwrap();
+        let pages = ["bridge.1", "bridge-read.1", "bridge-context-pack.1"].map(|page| std::fs::read_to_string(dir.join(page)).ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(pages[0].as_deref().is_some_and(|page| page.contains(env!("CARGO_PKG_VERSION"))));
+        assert!(pages[1].is_some() && pages[2].is_some());
     }
 }
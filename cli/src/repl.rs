@@ -0,0 +1,254 @@
+//! `bridge repl`: an interactive prompt that keeps the selected agent, cwd,
+//! and the last listing between commands, so sessions can be picked by number.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+use crate::adapters;
+use crate::agents::{CwdMatch, SearchQuery};
+use crate::theme;
+use crate::utils;
+
+/// The `help` text, naming every registered adapter `use` accepts.
+fn help() -> String {
+    format!(
+        "\
+Commands:
+  use <agent>               Select the agent for list/search/read ({})
+  cd [path]                 Scope list/search/read to a working directory (no path: any cwd)
+  status                    Show the selected agent and cwd
+  list [n]                  List the newest n sessions (default 10), numbered
+  search <text>             Search sessions, numbered like list
+  read [n|id] [flags]       Read result n from the last list/search, a session id, or the latest
+  compare <src> <src> ...   Compare sources: result numbers, <agent>, or <agent>:<id>
+  help                      Show this help
+  quit                      Leave the REPL
+Anything else runs as a bridge command, e.g. `report --handoff packet.json`.",
+        adapters::agent_names().join(", ")
+    )
+}
+
+#[derive(Default)]
+struct ReplState {
+    agent: Option<String>,
+    cwd: Option<String>,
+    last: Vec<Value>,
+}
+
+/// Split a command line into words, honoring single and double quotes.
+fn tokenize(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for ch in line.chars() {
+        match (quote, ch) {
+            (Some(open), ch) if ch == open => quote = None,
+            (Some(_), ch) => current.push(ch),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            (None, ch) if ch.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, ch) => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(anyhow!("Unterminated quote"));
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+impl ReplState {
+    fn prompt(&self) -> String {
+        format!("bridge[{}]> ", self.agent.as_deref().unwrap_or("-"))
+    }
+
+    fn require_agent(&self) -> Result<&str> {
+        self.agent
+            .as_deref()
+            .ok_or_else(|| anyhow!("No agent selected (run: use <agent>)"))
+    }
+
+    /// `(agent, id)` for result `index` (1-based) of the last list/search.
+    /// The file stem is preferred because `--id` matches session file names.
+    fn result(&self, index: usize) -> Result<(String, String)> {
+        let entry = index
+            .checked_sub(1)
+            .and_then(|position| self.last.get(position))
+            .ok_or_else(|| anyhow!("No result #{} (last listing had {})", index, self.last.len()))?;
+        let agent = entry["agent"].as_str().unwrap_or_default().to_string();
+        let id = entry["file_path"]
+            .as_str()
+            .and_then(|path| std::path::Path::new(path).file_stem()?.to_str().map(str::to_string))
+            .or_else(|| entry["session_id"].as_str().map(str::to_string))
+            .ok_or_else(|| anyhow!("Result #{} has no session id", index))?;
+        Ok((agent, id))
+    }
+
+    fn show_results(&mut self, entries: Vec<Value>) {
+        if entries.is_empty() {
+            println!("(no sessions)");
+        }
+        for (index, entry) in entries.iter().enumerate() {
+            let agent = entry["agent"].as_str().unwrap_or("");
+            println!(
+                "{:>3}. {} {}  {}  {}",
+                index + 1,
+                theme::paint(agent, agent),
+                utils::sanitize_for_terminal(entry["session_id"].as_str().unwrap_or("?")),
+                entry["modified_at"].as_str().unwrap_or(""),
+                utils::sanitize_for_terminal(entry["cwd"].as_str().unwrap_or(""))
+            );
+        }
+        self.last = entries;
+    }
+
+    fn cwd_args(&self) -> Vec<String> {
+        match &self.cwd {
+            Some(cwd) => vec!["--cwd".to_string(), cwd.clone()],
+            None => Vec::new(),
+        }
+    }
+
+    /// Handle one command line. Returns `Ok(false)` when the REPL should exit.
+    fn execute(&mut self, words: Vec<String>, dispatch: &mut dyn FnMut(Vec<String>) -> Result<()>) -> Result<bool> {
+        let Some((command, args)) = words.split_first() else {
+            return Ok(true);
+        };
+        match command.as_str() {
+            "quit" | "exit" => return Ok(false),
+            "help" | "?" => println!("{}", help()),
+            "status" => println!(
+                "agent: {}\ncwd: {}\nlast results: {}",
+                self.agent.as_deref().unwrap_or("(none)"),
+                self.cwd.as_deref().unwrap_or("(any)"),
+                self.last.len()
+            ),
+            "use" => {
+                let raw = args.first().ok_or_else(|| anyhow!("Usage: use <agent>"))?;
+                let agent = adapters::normalize_agent(raw)?;
+                println!("Using {}", agent);
+                self.agent = Some(agent.to_string());
+            }
+            "cd" => {
+                self.cwd = match args.first() {
+                    Some(path) => Some(utils::normalize_path(path)?.to_string_lossy().to_string()),
+                    None => None,
+                };
+                println!("cwd: {}", self.cwd.as_deref().unwrap_or("(any)"));
+            }
+            "list" => {
+                let limit = match args.first() {
+                    Some(raw) => raw.parse::<usize>().map_err(|_| anyhow!("Usage: list [n]"))?,
+                    None => 10,
+                };
+                let adapter = adapters::get_adapter(self.require_agent()?)
+                    .ok_or_else(|| anyhow!("Unsupported agent"))?;
+                let entries = adapter.list_sessions(self.cwd.as_deref(), CwdMatch::Exact, limit)?;
+                self.show_results(entries);
+            }
+            "search" => {
+                if args.is_empty() {
+                    return Err(anyhow!("Usage: search <text>"));
+                }
+                let adapter = adapters::get_adapter(self.require_agent()?)
+                    .ok_or_else(|| anyhow!("Unsupported agent"))?;
+                let entries = adapter.search_sessions(&SearchQuery::substring(&args.join(" ")), self.cwd.as_deref(), CwdMatch::Exact, 10)?;
+                self.show_results(entries);
+            }
+            "read" => {
+                let (target, flags) = match args.first() {
+                    Some(first) if !first.starts_with("--") => (Some(first.as_str()), &args[1..]),
+                    _ => (None, args),
+                };
+                let (agent, id) = match target.map(|value| (value, value.parse::<usize>())) {
+                    Some((_, Ok(index))) => {
+                        let (agent, id) = self.result(index)?;
+                        (agent, Some(id))
+                    }
+                    Some((value, Err(_))) => (self.require_agent()?.to_string(), Some(value.to_string())),
+                    None => (self.require_agent()?.to_string(), None),
+                };
+                let mut argv = vec!["read".to_string(), "--agent".to_string(), agent];
+                if let Some(id) = id {
+                    argv.extend(["--id".to_string(), id]);
+                }
+                argv.extend(self.cwd_args());
+                argv.extend(flags.iter().cloned());
+                dispatch(argv)?;
+            }
+            "compare" => {
+                if args.is_empty() {
+                    return Err(anyhow!("Usage: compare <src> <src> ..."));
+                }
+                let mut argv = vec!["compare".to_string()];
+                for source in args {
+                    let spec = match source.parse::<usize>() {
+                        Ok(index) => {
+                            let (agent, id) = self.result(index)?;
+                            format!("{}:{}", agent, id)
+                        }
+                        Err(_) => source.clone(),
+                    };
+                    argv.extend(["--source".to_string(), spec]);
+                }
+                argv.extend(self.cwd_args());
+                dispatch(argv)?;
+            }
+            "repl" => return Err(anyhow!("Already in the REPL")),
+            _ => dispatch(words)?,
+        }
+        Ok(true)
+    }
+}
+
+/// Read commands from stdin until `quit` or end of input. `dispatch` runs a
+/// full bridge command line (without the program name).
+pub fn run(mut dispatch: impl FnMut(Vec<String>) -> Result<()>) -> Result<()> {
+    let mut state = ReplState::default();
+    let stdin = io::stdin();
+    println!("Agent Bridge REPL. Type `help` for commands, `quit` to leave.");
+    loop {
+        print!("{}", state.prompt());
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            println!();
+            return Ok(());
+        }
+        let outcome = tokenize(&line).and_then(|words| state.execute(words, &mut dispatch));
+        match outcome {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(err) => eprintln!("{}", theme::paint("warning", &format!("{:#}", err))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tokenize;
+
+    #[test]
+    fn tokenize_honors_quotes() {
+        assert_eq!(
+            tokenize(r#"search "flaky test" --limit 5"#).unwrap(),
+            vec!["search", "flaky test", "--limit", "5"]
+        );
+        assert_eq!(tokenize("read ''").unwrap(), vec!["read", ""]);
+        assert!(tokenize("read 'open").is_err());
+    }
+}
@@ -6,10 +6,13 @@ const fs = require('fs');
 const path = require('path');
 const {
   normalizePath, collectMatchingFiles, readJsonlLines,
-  findLatestByCwd, getFileTimestamp, extractClaudeText, redactSensitiveText,
+  findLatestByCwd, selectById, getFileTimestamp, extractClaudeText, redactSensitiveText,
+  createToolUsage, createTokenUsage, claudeLineModel, firstJsonlModel, substantivePrompt, sessionTitle,
 } = require('./utils.cjs');
 
-const claudeProjectsBase = normalizePath(process.env.BRIDGE_CLAUDE_PROJECTS_DIR || '~/.claude/projects');
+function claudeProjectsBase() {
+  return normalizePath(process.env.BRIDGE_CLAUDE_PROJECTS_DIR || '~/.claude/projects');
+}
 
 function getClaudeSessionCwd(filePath) {
   try {
@@ -26,20 +29,39 @@ function getClaudeSessionCwd(filePath) {
   return null;
 }
 
+// List labels for a session: `title` is the first line of the first user
+// prompt, `summary` the latest summary entry Claude wrote.
+function getClaudeSessionLabels(filePath) {
+  let title = null;
+  let summary = null;
+  try {
+    for (const line of readJsonlLines(filePath)) {
+      let json;
+      try {
+        json = JSON.parse(line);
+      } catch (error) {
+        continue;
+      }
+      if (json.type === 'summary' && typeof json.summary === 'string') {
+        summary = redactSensitiveText(json.summary);
+      } else if (title === null && json.type === 'user' && json.isMeta !== true) {
+        const prompt = substantivePrompt(extractClaudeText(json.message && json.message.content));
+        if (prompt) title = sessionTitle(redactSensitiveText(prompt));
+      }
+    }
+  } catch (error) { /* unreadable: no labels */ }
+  return { title, summary };
+}
+
 function resolve(id, cwd, opts) {
   const warnings = [];
-  if (!fs.existsSync(claudeProjectsBase)) return null;
+  if (!fs.existsSync(claudeProjectsBase())) return null;
 
+  const files = collectMatchingFiles(claudeProjectsBase(), (_fp, name) => name.endsWith('.jsonl'), true);
   if (id) {
-    const files = collectMatchingFiles(
-      claudeProjectsBase,
-      (fullPath, name) => name.endsWith('.jsonl') && fullPath.includes(id),
-      true
-    );
-    return files.length > 0 ? { path: files[0].path, warnings } : null;
+    const match = selectById(files, id, opts && opts.exact, 'Claude', opts && opts.allMatching);
+    return match ? { path: match, warnings } : null;
   }
-
-  const files = collectMatchingFiles(claudeProjectsBase, (_fp, name) => name.endsWith('.jsonl'), true);
   if (files.length === 0) return null;
 
   const scoped = findLatestByCwd(files, getClaudeSessionCwd, cwd);
@@ -55,12 +77,36 @@ function read(filePath, lastN) {
   const messages = [];
   let skipped = 0;
   let sessionCwd = null;
+  const tools = createToolUsage();
+  let model = null;
+  const tokens = createTokenUsage();
+  const usageMessageIds = new Set();
 
   for (const line of lines) {
     try {
       const json = JSON.parse(line);
+      // Claude Code splits one API response across several lines that repeat
+      // the same usage object; count each message id once.
+      const usage = json.message && json.message.usage;
+      if (json.type === 'assistant' && usage && typeof usage === 'object' && !Array.isArray(usage)) {
+        const id = typeof json.message.id === 'string' ? json.message.id : null;
+        if (!id || !usageMessageIds.has(id)) {
+          if (id) usageMessageIds.add(id);
+          tokens.addClaude(usage);
+        }
+      }
+      model = claudeLineModel(json) || model;
       if (typeof json.cwd === 'string' && !sessionCwd) sessionCwd = json.cwd;
       const message = json.message || json;
+      if (Array.isArray(message.content)) {
+        for (const item of message.content) {
+          if (item && item.type === 'tool_use') {
+            tools.recordCall(item.id, typeof item.name === 'string' ? item.name : 'unknown');
+          } else if (item && item.type === 'tool_result') {
+            tools.recordResult(item.tool_use_id, item.is_error === true);
+          }
+        }
+      }
       if (json.type === 'assistant' || message.role === 'assistant') {
         const content = message.content !== undefined ? message.content : json.content;
         const text = extractClaudeText(content);
@@ -101,16 +147,39 @@ function read(filePath, lastN) {
     warnings,
     session_id: sessionId,
     cwd: sessionCwd,
+    model,
     timestamp: getFileTimestamp(filePath),
     message_count: messageCount,
     messages_returned: messagesReturned,
+    tool_usage: tools.toJSON(),
+    usage: tokens.toJSON(),
   };
 }
 
+// Every user and assistant message, oldest first and unredacted, without
+// meta entries and injected context.
+function transcript(filePath) {
+  const messages = [];
+  for (const line of readJsonlLines(filePath)) {
+    let json;
+    try {
+      json = JSON.parse(line);
+    } catch (error) {
+      continue;
+    }
+    if (json.isMeta === true || (json.type !== 'user' && json.type !== 'assistant')) continue;
+    const content = extractClaudeText(json.message && json.message.content);
+    const text = json.type === 'user' ? substantivePrompt(content) : content;
+    if (text === null) continue;
+    messages.push({ role: json.type, text, timestamp: typeof json.timestamp === 'string' ? json.timestamp : null });
+  }
+  return messages;
+}
+
 function list(cwd, limit) {
   limit = limit || 10;
-  if (!fs.existsSync(claudeProjectsBase)) return [];
-  const files = collectMatchingFiles(claudeProjectsBase, (_fp, name) => name.endsWith('.jsonl'), true);
+  if (!fs.existsSync(claudeProjectsBase())) return [];
+  const files = collectMatchingFiles(claudeProjectsBase(), (_fp, name) => name.endsWith('.jsonl'), true);
   const expectedCwd = cwd ? normalizePath(cwd) : null;
   const entries = [];
   for (const f of files) {
@@ -119,12 +188,16 @@ function list(cwd, limit) {
       continue;
     }
 
+    const { title, summary } = getClaudeSessionLabels(f.path);
     entries.push({
       session_id: path.basename(f.path, path.extname(f.path)),
       agent: 'claude',
       cwd: fileCwd,
+      model: firstJsonlModel(f.path, claudeLineModel),
       modified_at: getFileTimestamp(f.path),
       file_path: f.path,
+      title,
+      summary,
     });
 
     if (entries.length >= limit) break;
@@ -136,9 +209,9 @@ function search(query, cwd, limit) {
   limit = limit || 10;
   const expectedCwd = cwd ? normalizePath(cwd) : null;
   const queryLower = String(query || '').toLowerCase();
-  if (!fs.existsSync(claudeProjectsBase)) return [];
+  if (!fs.existsSync(claudeProjectsBase())) return [];
 
-  const files = collectMatchingFiles(claudeProjectsBase, (_fp, name) => name.endsWith('.jsonl'), true);
+  const files = collectMatchingFiles(claudeProjectsBase(), (_fp, name) => name.endsWith('.jsonl'), true);
   const entries = [];
 
   for (const f of files) {
@@ -172,4 +245,4 @@ function search(query, cwd, limit) {
   return entries;
 }
 
-module.exports = { resolve, read, list, search };
+module.exports = { resolve, read, list, search, transcript };
@@ -6,10 +6,13 @@ const fs = require('fs');
 const path = require('path');
 const {
   normalizePath, collectMatchingFiles, readJsonlLines,
-  findLatestByCwd, getFileTimestamp, extractText, redactSensitiveText,
+  findLatestByCwd, selectById, getFileTimestamp, extractText, redactSensitiveText,
+  createToolUsage, createTokenUsage, codexOutputFailed, codexLineModel, firstJsonlModel, substantivePrompt,
 } = require('./utils.cjs');
 
-const codexSessionsBase = normalizePath(process.env.BRIDGE_CODEX_SESSIONS_DIR || '~/.codex/sessions');
+function codexSessionsBase() {
+  return normalizePath(process.env.BRIDGE_CODEX_SESSIONS_DIR || '~/.codex/sessions');
+}
 
 function getCodexSessionCwd(filePath) {
   try {
@@ -27,18 +30,13 @@ function getCodexSessionCwd(filePath) {
 
 function resolve(id, cwd, opts) {
   const warnings = [];
-  if (!fs.existsSync(codexSessionsBase)) return null;
+  if (!fs.existsSync(codexSessionsBase())) return null;
 
+  const files = collectMatchingFiles(codexSessionsBase(), (_fp, name) => name.endsWith('.jsonl'), true);
   if (id) {
-    const files = collectMatchingFiles(
-      codexSessionsBase,
-      (fullPath, name) => name.endsWith('.jsonl') && fullPath.includes(id),
-      true
-    );
-    return files.length > 0 ? { path: files[0].path, warnings } : null;
+    const match = selectById(files, id, opts && opts.exact, 'Codex', opts && opts.allMatching);
+    return match ? { path: match, warnings } : null;
   }
-
-  const files = collectMatchingFiles(codexSessionsBase, (_fp, name) => name.endsWith('.jsonl'), true);
   if (files.length === 0) return null;
 
   const scoped = findLatestByCwd(files, getCodexSessionCwd, cwd);
@@ -55,10 +53,27 @@ function read(filePath, lastN) {
   let skipped = 0;
   let sessionCwd = null;
   let sessionId = null;
+  const tools = createToolUsage();
+  let model = null;
+  const tokens = createTokenUsage();
 
   for (const line of lines) {
     try {
       const json = JSON.parse(line);
+      if (json.type === 'event_msg' && json.payload && json.payload.type === 'token_count') {
+        const total = json.payload.info && json.payload.info.total_token_usage;
+        if (total && typeof total === 'object' && !Array.isArray(total)) tokens.setCodexTotal(total);
+      }
+      model = codexLineModel(json) || model;
+      if (json.type === 'response_item' && json.payload) {
+        const payload = json.payload;
+        if (['function_call', 'custom_tool_call', 'local_shell_call'].includes(payload.type)) {
+          const fallback = payload.type === 'local_shell_call' ? 'shell' : 'unknown';
+          tools.recordCall(payload.call_id, typeof payload.name === 'string' ? payload.name : fallback);
+        } else if (payload.type === 'function_call_output' || payload.type === 'custom_tool_call_output') {
+          tools.recordResult(payload.call_id, codexOutputFailed(payload.output));
+        }
+      }
       if (json.type === 'session_meta' && json.payload) {
         if (typeof json.payload.cwd === 'string') sessionCwd = json.payload.cwd;
         if (typeof json.payload.session_id === 'string') sessionId = json.payload.session_id;
@@ -105,16 +120,56 @@ function read(filePath, lastN) {
     warnings,
     session_id: sessionId,
     cwd: sessionCwd,
+    model,
     timestamp: getFileTimestamp(filePath),
     message_count: messageCount,
     messages_returned: messagesReturned,
+    tool_usage: tools.toJSON(),
+    usage: tokens.toJSON(),
   };
 }
 
+// Every user and assistant message, oldest first and unredacted. Injected
+// context is left out; reply events stand in only when there are no items.
+function transcript(filePath) {
+  const lines = [];
+  for (const line of readJsonlLines(filePath)) {
+    try {
+      lines.push(JSON.parse(line));
+    } catch (error) { /* skip */ }
+  }
+  const isItem = json => json.type === 'response_item' && json.payload && json.payload.type === 'message';
+  const hasItems = lines.some(json => isItem(json) && json.payload.role === 'assistant');
+  const messages = [];
+  for (const json of lines) {
+    const payload = json.payload || {};
+    let role;
+    let text;
+    if (isItem(json)) {
+      if (payload.role === 'user') {
+        role = 'user';
+        text = substantivePrompt(extractText(payload.content));
+      } else if (payload.role === 'assistant') {
+        role = 'assistant';
+        text = extractText(payload.content);
+      }
+    } else if (!hasItems && json.type === 'event_msg' && payload.type === 'agent_message') {
+      role = 'assistant';
+      text = extractText(payload.message);
+    } else if (json.type === 'compacted' && extractText(payload.message).trim()) {
+      role = 'assistant';
+      text = `[Context compacted] ${extractText(payload.message)}`;
+    }
+    if (!role || text === null) continue;
+    messages.push({ role, text, timestamp: typeof json.timestamp === 'string' ? json.timestamp : null });
+  }
+  return messages;
+}
+
 function list(cwd, limit) {
   limit = limit || 10;
-  if (!fs.existsSync(codexSessionsBase)) return [];
-  const files = collectMatchingFiles(codexSessionsBase, (_fp, name) => name.endsWith('.jsonl'), true);
+  if (!fs.existsSync(codexSessionsBase())) return [];
+  const files = collectMatchingFiles(codexSessionsBase(), (_fp, name) => name.endsWith('.jsonl'), true);
   const expectedCwd = cwd ? normalizePath(cwd) : null;
   const entries = [];
   for (const f of files) {
@@ -127,6 +182,7 @@ function list(cwd, limit) {
       session_id: path.basename(f.path, path.extname(f.path)),
       agent: 'codex',
       cwd: fileCwd,
+      model: firstJsonlModel(f.path, codexLineModel),
       modified_at: getFileTimestamp(f.path),
       file_path: f.path,
     });
@@ -140,9 +196,9 @@ function search(query, cwd, limit) {
   limit = limit || 10;
   const expectedCwd = cwd ? normalizePath(cwd) : null;
   const queryLower = String(query || '').toLowerCase();
-  if (!fs.existsSync(codexSessionsBase)) return [];
+  if (!fs.existsSync(codexSessionsBase())) return [];
 
-  const files = collectMatchingFiles(codexSessionsBase, (_fp, name) => name.endsWith('.jsonl'), true);
+  const files = collectMatchingFiles(codexSessionsBase(), (_fp, name) => name.endsWith('.jsonl'), true);
   const entries = [];
 
   for (const f of files) {
@@ -176,4 +232,4 @@ function search(query, cwd, limit) {
   return entries;
 }
 
-module.exports = { resolve, read, list, search };
+module.exports = { resolve, read, list, search, transcript };
@@ -5,17 +5,19 @@
 const fs = require('fs');
 const path = require('path');
 const {
-  normalizePath, collectMatchingFiles, getFileTimestamp, redactSensitiveText,
+  normalizePath, collectMatchingFiles, getFileTimestamp, redactSensitiveText, extractText,
 } = require('./utils.cjs');
 
-const cursorDataBase = normalizePath(process.env.BRIDGE_CURSOR_DATA_DIR || (
-  process.platform === 'darwin'
-    ? '~/Library/Application Support/Cursor'
-    : '~/.cursor'
-));
+function cursorDataBase() {
+  return normalizePath(process.env.BRIDGE_CURSOR_DATA_DIR || (
+    process.platform === 'darwin'
+      ? '~/Library/Application Support/Cursor'
+      : '~/.cursor'
+  ));
+}
 
 function getWorkspacesDir() {
-  return path.join(cursorDataBase, 'User', 'workspaceStorage');
+  return path.join(cursorDataBase(), 'User', 'workspaceStorage');
 }
 
 function isCursorFile(name) {
@@ -23,18 +25,44 @@ function isCursorFile(name) {
     && (name.includes('chat') || name.includes('composer') || name.includes('conversation'));
 }
 
+// `<workspace hash>/<stem>`: file stems repeat across workspaces.
+function workspaceSessionId(workspacesDir, filePath) {
+  const workspace = path.relative(workspacesDir, filePath).split(path.sep)[0];
+  return `${workspace}/${path.basename(filePath, path.extname(filePath))}`;
+}
+
+// selectById for Cursor: the id may name the stem or `<workspace hash>/<stem>`.
+function selectByWorkspaceId(workspacesDir, files, id, exact, allMatching) {
+  const stem = (file) => path.basename(file.path, path.extname(file.path));
+  const key = (file) => workspaceSessionId(workspacesDir, file.path);
+  const matches = (value) => (exact ? value === id : value.includes(id));
+  const candidates = files.filter((file) => matches(key(file)) || matches(stem(file)));
+  const exactMatches = candidates.filter((file) => key(file) === id || stem(file) === id);
+  if (exactMatches.length === 1 && !allMatching) return exactMatches[0].path;
+  if (candidates.length <= 1) return candidates.length === 1 ? candidates[0].path : null;
+
+  const ids = candidates.map(key);
+  const more = ids.length > 10 ? ` (and ${ids.length - 10} more)` : '';
+  const error = new Error(
+    `Cursor session id '${id}' is ambiguous: matches ${ids.length} sessions: ${ids.slice(0, 10).join(', ')}${more}. Use a longer id or --exact.`
+  );
+  error.code = 'AMBIGUOUS_ID';
+  error.candidates = ids;
+  throw error;
+}
+
 function resolve(id, cwd, opts) {
-  if (!fs.existsSync(cursorDataBase)) return null;
+  if (!fs.existsSync(cursorDataBase())) return null;
   const workspacesDir = getWorkspacesDir();
   if (!fs.existsSync(workspacesDir)) return null;
 
-  const files = collectMatchingFiles(workspacesDir, (fullPath, name) => {
-    if (!isCursorFile(name)) return false;
-    if (id) return fullPath.includes(id);
-    return true;
-  }, true);
-
-  return files.length > 0 ? { path: files[0].path, warnings: [] } : null;
+  const files = collectMatchingFiles(workspacesDir, (fullPath, name) => isCursorFile(name), true);
+  if (files.length === 0) return null;
+  if (id) {
+    const selected = selectByWorkspaceId(workspacesDir, files, id, Boolean(opts && opts.exact), Boolean(opts && opts.allMatching));
+    return selected ? { path: selected, warnings: [] } : null;
+  }
+  return { path: files[0].path, warnings: [] };
 }
 
 function read(filePath, lastN) {
@@ -42,18 +70,21 @@ function read(filePath, lastN) {
   const raw = fs.readFileSync(filePath, 'utf-8');
   let content = '';
   let messageCount = 0;
+  let replies = [];
 
   try {
     const json = JSON.parse(raw);
     if (Array.isArray(json.messages)) {
       const assistantMsgs = json.messages.filter(m => m.role === 'assistant');
       messageCount = assistantMsgs.length;
+      replies = assistantMsgs.map(m => extractText(m.content) || '[No text content]');
       content = assistantMsgs.length > 0
         ? (assistantMsgs[assistantMsgs.length - 1].content || '[No text content]')
         : '[No assistant messages found]';
     } else if (typeof json.content === 'string') {
       content = json.content;
       messageCount = 1;
+      replies = [json.content];
     } else {
       content = JSON.stringify(json, null, 2);
     }
@@ -69,9 +100,14 @@ function read(filePath, lastN) {
       } catch (e) { /* skip */ }
     }
     messageCount = msgs.length;
+    replies = msgs;
     content = msgs.length > 0 ? msgs[msgs.length - 1] : lines.slice(-20).join('\n');
   }
 
+  if (lastN > 1 && replies.length > 0) {
+    content = replies.slice(-lastN).join('\n---\n');
+  }
+
   const sessionId = path.basename(filePath, path.extname(filePath));
 
   return {
@@ -81,15 +117,37 @@ function read(filePath, lastN) {
     warnings: [],
     session_id: sessionId,
     cwd: null,
+    model: null,
     timestamp: getFileTimestamp(filePath),
     message_count: messageCount,
-    messages_returned: 1,
+    messages_returned: lastN > 1 && replies.length > 0 ? Math.min(lastN, replies.length) : 1,
   };
 }
 
+// Every user and assistant message, oldest first and unredacted, from a
+// JSON chat or a JSONL transcript.
+function transcript(filePath) {
+  const raw = fs.readFileSync(filePath, 'utf-8');
+  let items;
+  try {
+    const json = JSON.parse(raw);
+    items = json && Array.isArray(json.messages) ? json.messages : [];
+  } catch (error) {
+    items = [];
+    for (const line of raw.split('\n')) {
+      try {
+        items.push(JSON.parse(line));
+      } catch (e) { /* skip */ }
+    }
+  }
+  return items
+    .filter(item => item && (item.role === 'user' || item.role === 'assistant'))
+    .map(item => ({ role: item.role, text: extractText(item.content), timestamp: typeof item.timestamp === 'string' ? item.timestamp : null }));
+}
+
 function list(cwd, limit) {
   limit = limit || 10;
-  if (!fs.existsSync(cursorDataBase)) return [];
+  if (!fs.existsSync(cursorDataBase())) return [];
   const workspacesDir = getWorkspacesDir();
   if (!fs.existsSync(workspacesDir)) return [];
 
@@ -115,6 +173,7 @@ function list(cwd, limit) {
       session_id: path.basename(f.path, path.extname(f.path)),
       agent: 'cursor',
       cwd: null,
+      model: null,
       modified_at: getFileTimestamp(f.path),
       file_path: f.path,
     });
@@ -127,7 +186,7 @@ function search(query, cwd, limit) {
   limit = limit || 10;
   const queryLower = String(query || '').toLowerCase();
   const expectedCwd = cwd ? normalizePath(cwd).toLowerCase() : null;
-  if (!fs.existsSync(cursorDataBase)) return [];
+  if (!fs.existsSync(cursorDataBase())) return [];
   const workspacesDir = getWorkspacesDir();
   if (!fs.existsSync(workspacesDir)) return [];
 
@@ -163,4 +222,4 @@ function search(query, cwd, limit) {
   return entries;
 }
 
-module.exports = { resolve, read, list, search };
+module.exports = { resolve, read, list, search, transcript };
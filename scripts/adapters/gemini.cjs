@@ -7,9 +7,12 @@ const path = require('path');
 const {
   normalizePath, hashPath, collectMatchingFiles,
   getFileTimestamp, extractText, redactSensitiveText,
+  geminiSessionModel, maxFileSize, selectById,
 } = require('./utils.cjs');
 
-const geminiTmpBase = normalizePath(process.env.BRIDGE_GEMINI_TMP_DIR || '~/.gemini/tmp');
+function geminiTmpBase() {
+  return normalizePath(process.env.BRIDGE_GEMINI_TMP_DIR || '~/.gemini/tmp');
+}
 
 function compareByMtimeDesc(a, b) {
   if (b.mtimeNs !== a.mtimeNs) {
@@ -19,10 +22,10 @@ function compareByMtimeDesc(a, b) {
 }
 
 function listGeminiChatDirs() {
-  if (!fs.existsSync(geminiTmpBase)) return [];
+  if (!fs.existsSync(geminiTmpBase())) return [];
   let entries = [];
   try {
-    entries = fs.readdirSync(geminiTmpBase, { withFileTypes: true });
+    entries = fs.readdirSync(geminiTmpBase(), { withFileTypes: true });
   } catch (error) {
     return [];
   }
@@ -30,7 +33,7 @@ function listGeminiChatDirs() {
   const dirs = [];
   for (const entry of entries) {
     if (!entry.isDirectory()) continue;
-    const chatsDir = path.join(geminiTmpBase, entry.name, 'chats');
+    const chatsDir = path.join(geminiTmpBase(), entry.name, 'chats');
     if (fs.existsSync(chatsDir)) dirs.push(chatsDir);
   }
   return dirs;
@@ -63,7 +66,7 @@ function resolveGeminiChatDirs(chatsDir, cwd) {
     seen.add(dirPath);
   }
   const scopedHash = hashPath(cwd);
-  addDir(path.join(geminiTmpBase, scopedHash, 'chats'));
+  addDir(path.join(geminiTmpBase(), scopedHash, 'chats'));
   for (const dir of listGeminiChatDirs()) addDir(dir);
   return ordered;
 }
@@ -76,15 +79,12 @@ function resolve(id, cwd, opts) {
   const warnings = [];
   const candidates = [];
   for (const dir of dirs) {
-    const files = collectMatchingFiles(dir, (fullPath, name) => {
-      if (!name.endsWith('.json')) return false;
-      if (id) return fullPath.includes(id);
-      return name.startsWith('session-');
-    }, false);
+    const files = collectMatchingFiles(dir, (_fp, name) => name.endsWith('.json') && (id || name.startsWith('session-')), false);
     for (const file of files) candidates.push(file);
   }
   candidates.sort(compareByMtimeDesc);
-  return candidates.length > 0 ? { path: candidates[0].path, warnings, searchedDirs: dirs } : null;
+  const match = id ? selectById(candidates, id, opts && opts.exact, 'Gemini', opts && opts.allMatching) : candidates[0] && candidates[0].path;
+  return match ? { path: match, warnings, searchedDirs: dirs } : null;
 }
 
 function read(filePath, lastN) {
@@ -150,17 +150,54 @@ function read(filePath, lastN) {
     warnings: [],
     session_id: sessionId,
     cwd: null,
+    model: Array.isArray(session.messages) ? geminiSessionModel(session) : null,
     timestamp: getFileTimestamp(filePath),
     message_count: messageCount,
     messages_returned: messagesReturned,
   };
 }
 
+function readGeminiFileModel(filePath) {
+  try {
+    if (fs.statSync(filePath).size > maxFileSize()) return null;
+    return geminiSessionModel(JSON.parse(fs.readFileSync(filePath, 'utf-8')));
+  } catch (error) {
+    return null;
+  }
+}
+
+// Every user and assistant message, oldest first and unredacted. Only
+// `messages` sessions record timestamps.
+function transcript(filePath) {
+  let session;
+  try {
+    session = JSON.parse(fs.readFileSync(filePath, 'utf-8'));
+  } catch (error) {
+    return [];
+  }
+  if (Array.isArray(session)) session = { history: session };
+  else if (session && Array.isArray(session.clientHistory)) session = { history: session.clientHistory };
+  const roleOf = (value) => {
+    const role = typeof value === 'string' ? value.toLowerCase() : '';
+    if (role === 'user') return 'user';
+    return role === 'gemini' || role === 'assistant' || role === 'model' ? 'assistant' : null;
+  };
+  if (session && Array.isArray(session.messages)) {
+    return session.messages
+      .filter(m => roleOf(m.type))
+      .map(m => ({ role: roleOf(m.type), text: extractText(m.content), timestamp: typeof m.timestamp === 'string' ? m.timestamp : null }));
+  }
+  const history = session && Array.isArray(session.history) ? session.history : [];
+  return history
+    .filter(turn => roleOf(turn.role))
+    .map(turn => ({ role: roleOf(turn.role), text: extractText(turn.parts), timestamp: null }));
+}
+
 function list(cwd, limit) {
   limit = limit || 10;
   const dirs = cwd
     ? (() => {
-      const scoped = path.join(geminiTmpBase, hashPath(cwd), 'chats');
+      const scoped = path.join(geminiTmpBase(), hashPath(cwd), 'chats');
       return fs.existsSync(scoped) ? [scoped] : [];
     })()
     : listGeminiChatDirs();
@@ -174,6 +211,7 @@ function list(cwd, limit) {
     session_id: path.basename(f.path, path.extname(f.path)),
     agent: 'gemini',
     cwd: null,
+    model: readGeminiFileModel(f.path),
     modified_at: getFileTimestamp(f.path),
     file_path: f.path,
   }));
@@ -184,7 +222,7 @@ function search(query, cwd, limit) {
   const queryLower = String(query || '').toLowerCase();
   const dirs = cwd
     ? (() => {
-      const scoped = path.join(geminiTmpBase, hashPath(cwd), 'chats');
+      const scoped = path.join(geminiTmpBase(), hashPath(cwd), 'chats');
       return fs.existsSync(scoped) ? [scoped] : [];
     })()
     : listGeminiChatDirs();
@@ -222,4 +260,4 @@ function search(query, cwd, limit) {
   return entries;
 }
 
-module.exports = { resolve, read, list, search };
+module.exports = { resolve, read, list, search, transcript };
@@ -15,6 +15,34 @@ const adapters = {
   cursor,
 };
 
+// Common alternate spellings, mapped to the canonical agent name.
+const AGENT_ALIASES = {
+  'claude-code': 'claude',
+  'codex-cli': 'codex',
+  gpt: 'codex',
+  'gemini-cli': 'gemini',
+  'cursor-agent': 'cursor',
+};
+
+// Former names of renamed agents: still accepted, with a warning on stderr.
+// Add `old: 'new'` here when an adapter is renamed.
+const DEPRECATED_AGENT_NAMES = {};
+
+/**
+ * Map an agent name from --agent, --source, or a handoff packet to its
+ * canonical adapter name. Matching ignores case and treats `_` as `-`.
+ */
+function normalizeAgentName(raw) {
+  const key = String(raw == null ? '' : raw).trim().toLowerCase().replace(/_/g, '-');
+  if (Object.prototype.hasOwnProperty.call(adapters, key)) return key;
+  if (Object.prototype.hasOwnProperty.call(AGENT_ALIASES, key)) return AGENT_ALIASES[key];
+  if (Object.prototype.hasOwnProperty.call(DEPRECATED_AGENT_NAMES, key)) {
+    process.stderr.write(`Warning: agent name '${key}' is deprecated; use '${DEPRECATED_AGENT_NAMES[key]}'\n`);
+    return DEPRECATED_AGENT_NAMES[key];
+  }
+  throw new Error(`Unsupported agent: ${String(raw == null ? '' : raw).trim()}`);
+}
+
 function getAdapter(agent) {
   const adapter = adapters[agent];
   if (!adapter) {
@@ -27,4 +55,4 @@ function listAdapters() {
   return Object.keys(adapters);
 }
 
-module.exports = { getAdapter, listAdapters, adapters };
+module.exports = { getAdapter, listAdapters, normalizeAgentName, adapters };
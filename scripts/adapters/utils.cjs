@@ -7,8 +7,35 @@ const path = require('path');
 const os = require('os');
 const crypto = require('crypto');
 
-const MAX_FILE_SIZE = 50 * 1024 * 1024; // 50 MB
-const MAX_SCAN_FILES = 1000;
+const DEFAULT_MAX_FILE_SIZE_MB = 50;
+const DEFAULT_MAX_SCAN_FILES = 1000;
+
+// Set when any directory scan in this process stopped at the file limit.
+let scanTruncated = false;
+
+/** Largest session file parsed, in bytes (BRIDGE_MAX_FILE_SIZE_MB). */
+function maxFileSize() {
+  const value = parseInt(process.env.BRIDGE_MAX_FILE_SIZE_MB || '', 10);
+  return (value > 0 ? value : DEFAULT_MAX_FILE_SIZE_MB) * 1024 * 1024;
+}
+
+/** Most session files one directory scan collects (BRIDGE_MAX_SCAN_FILES). */
+function maxScanFiles() {
+  const value = parseInt(process.env.BRIDGE_MAX_SCAN_FILES || '', 10);
+  return value > 0 ? value : DEFAULT_MAX_SCAN_FILES;
+}
+
+function markScanTruncated() {
+  scanTruncated = true;
+}
+
+function wasScanTruncated() {
+  return scanTruncated;
+}
+
+function scanTruncationWarning() {
+  return `Warning: session scan stopped after ${maxScanFiles()} files; the newest session may be missing. Raise BRIDGE_MAX_SCAN_FILES to scan more.`;
+}
 
 function expandHome(filepath) {
   if (!filepath) return filepath;
@@ -30,10 +57,12 @@ function hashPath(filepath) {
 function collectMatchingFiles(dirPath, predicate, recursive = false) {
   if (!dirPath || !fs.existsSync(dirPath)) return [];
 
+  const limit = maxScanFiles();
   const matches = [];
+  let stopped = false;
 
   function search(currentDir) {
-    if (matches.length >= MAX_SCAN_FILES) return;
+    if (stopped) return;
 
     let entries = [];
     try {
@@ -43,7 +72,7 @@ function collectMatchingFiles(dirPath, predicate, recursive = false) {
     }
 
     for (const entry of entries) {
-      if (matches.length >= MAX_SCAN_FILES) return;
+      if (stopped) return;
 
       const fullPath = path.join(currentDir, entry.name);
       // Skip symlinked directories by default (Phase 6)
@@ -55,6 +84,12 @@ function collectMatchingFiles(dirPath, predicate, recursive = false) {
 
       if (!predicate(fullPath, entry.name)) continue;
 
+      if (matches.length >= limit) {
+        stopped = true;
+        markScanTruncated();
+        return;
+      }
+
       try {
         // Prefer nanosecond precision to keep "latest" selection stable
         // across runtimes and filesystems.
@@ -85,8 +120,8 @@ function collectMatchingFiles(dirPath, predicate, recursive = false) {
 
 function readJsonlLines(filePath) {
   const stat = fs.statSync(filePath);
-  if (stat.size > MAX_FILE_SIZE) {
-    throw new Error(`Skipped ${filePath} (exceeds ${MAX_FILE_SIZE / (1024 * 1024)}MB size limit)`);
+  if (stat.size > maxFileSize()) {
+    throw new Error(`Skipped ${filePath} (exceeds ${maxFileSize() / (1024 * 1024)}MB size limit)`);
   }
   return fs.readFileSync(filePath, 'utf-8').split('\n').filter(Boolean);
 }
@@ -133,6 +168,36 @@ function extractClaudeText(value) {
     .join('');
 }
 
+// Starts of user turns that agents inject themselves rather than the user
+// typing them; kept in step with the Rust CLI's list.
+const INJECTED_PROMPT_PREFIXES = [
+  '<environment_context>',
+  '<user_instructions>',
+  '<permissions instructions>',
+  '# AGENTS.md instructions',
+  '<command-name>',
+  '<command-message>',
+  '<local-command-stdout>',
+  '<local-command-stderr>',
+  'Caveat: The messages below were generated by the user while running local commands',
+  'This is the Gemini CLI. We are setting up the context for our chat.',
+];
+// Longest session title in list output, in characters.
+const TITLE_CHARS = 80;
+
+function substantivePrompt(text) {
+  const prompt = String(text || '').replace(/<system-reminder>[\s\S]*?(<\/system-reminder>|$)/g, '').trim();
+  if (!prompt || INJECTED_PROMPT_PREFIXES.some(prefix => prompt.startsWith(prefix))) return null;
+  return prompt;
+}
+
+// First non-empty line of a prompt, cut to TITLE_CHARS characters.
+function sessionTitle(prompt) {
+  const firstLine = String(prompt).split(/\r?\n/).map(l => l.trim()).find(Boolean) || '';
+  const chars = Array.from(firstLine);
+  return chars.length > TITLE_CHARS ? `${chars.slice(0, TITLE_CHARS).join('')}…` : firstLine;
+}
+
 function redactSensitiveText(input) {
   let output = String(input || '');
   // OpenAI keys (sk-proj-, sk-ant-, sk-...) with hyphens allowed
@@ -162,17 +227,181 @@ function redactSensitiveText(input) {
   return output;
 }
 
+/**
+ * Tally tool invocations and failed results, pairing results with their call by id.
+ */
+function createToolUsage() {
+  const namesById = new Map();
+  const counts = {};
+  return {
+    recordCall(id, name) {
+      if (!counts[name]) counts[name] = { calls: 0, failures: 0 };
+      counts[name].calls += 1;
+      if (id) namesById.set(id, name);
+    },
+    recordResult(id, failed) {
+      if (!failed || !id) return;
+      const name = namesById.get(id);
+      if (name && counts[name]) counts[name].failures += 1;
+    },
+    toJSON() {
+      const out = {};
+      for (const name of Object.keys(counts).sort()) out[name] = counts[name];
+      return out;
+    },
+  };
+}
+
+/**
+ * Codex tool outputs carry failure either as `success: false`, a JSON string
+ * with `metadata.exit_code`, or a plain `Exit code: N` preamble.
+ */
+function codexOutputFailed(output) {
+  if (output && typeof output === 'object' && typeof output.success === 'boolean') {
+    return !output.success;
+  }
+  if (typeof output !== 'string') return false;
+  try {
+    const parsed = JSON.parse(output);
+    if (parsed && parsed.metadata && Number.isInteger(parsed.metadata.exit_code)) {
+      return parsed.metadata.exit_code !== 0;
+    }
+  } catch (error) {
+    // Not JSON; fall through to the plain-text form.
+  }
+  const match = /^Exit code: (-?\d+)/.exec(output);
+  return match ? Number(match[1]) !== 0 : false;
+}
+
+/**
+ * Token totals summed from provider usage records, normalized so the four
+ * buckets are disjoint (Codex reports cached tokens inside `input_tokens`).
+ */
+function createTokenUsage() {
+  let seen = false;
+  let input = 0;
+  let output = 0;
+  let cacheRead = 0;
+  let cacheWrite = 0;
+  const num = (value) => (Number.isInteger(value) && value >= 0 ? value : 0);
+  return {
+    addClaude(usage) {
+      input += num(usage.input_tokens);
+      output += num(usage.output_tokens);
+      cacheRead += num(usage.cache_read_input_tokens);
+      cacheWrite += num(usage.cache_creation_input_tokens);
+      seen = true;
+    },
+    // Codex `token_count` events carry a running total, so the latest one wins.
+    setCodexTotal(total) {
+      const cached = num(total.cached_input_tokens);
+      input = Math.max(0, num(total.input_tokens) - cached);
+      output = num(total.output_tokens);
+      cacheRead = cached;
+      cacheWrite = 0;
+      seen = true;
+    },
+    toJSON() {
+      if (!seen) return null;
+      return {
+        input_tokens: input,
+        output_tokens: output,
+        cache_read_tokens: cacheRead,
+        cache_write_tokens: cacheWrite,
+        total_tokens: input + output + cacheRead + cacheWrite,
+      };
+    },
+  };
+}
+
+function codexLineModel(json) {
+  if (json.type !== 'session_meta' && json.type !== 'turn_context') return null;
+  return json.payload && typeof json.payload.model === 'string' ? json.payload.model : null;
+}
+
+function claudeLineModel(json) {
+  const model = json.message && json.message.model;
+  // Claude Code writes "<synthetic>" for locally generated messages.
+  if (json.type !== 'assistant' || typeof model !== 'string' || model.startsWith('<')) return null;
+  return model;
+}
+
+function geminiSessionModel(session) {
+  const messages = Array.isArray(session.messages) ? session.messages : [];
+  for (let i = messages.length - 1; i >= 0; i -= 1) {
+    if (messages[i] && typeof messages[i].model === 'string') return messages[i].model;
+  }
+  return typeof session.model === 'string' ? session.model : null;
+}
+
+/**
+ * First model recorded in a JSONL session; `list` stops at the first hit.
+ */
+function firstJsonlModel(filePath, extractor) {
+  let lines;
+  try {
+    lines = readJsonlLines(filePath);
+  } catch (error) {
+    return null;
+  }
+  for (const line of lines) {
+    try {
+      const model = extractor(JSON.parse(line));
+      if (model) return model;
+    } catch (error) {
+      // Skip unparseable lines.
+    }
+  }
+  return null;
+}
+
+/**
+ * Pick the file whose name (without extension) contains `id`, or equals it
+ * with `exact`. `files` must be sorted newest first. An exact name wins
+ * outright (unless `allMatching`); several other matches throw an
+ * AMBIGUOUS_ID error carrying `candidates`, as the Rust CLI does.
+ */
+function selectById(files, id, exact, label, allMatching) {
+  const stem = (file) => path.basename(file.path, path.extname(file.path));
+  const candidates = files.filter((file) => (exact ? stem(file) === id : stem(file).includes(id)));
+  const match = !allMatching && candidates.find((file) => stem(file) === id);
+  if (match) return match.path;
+  if (candidates.length <= 1) return candidates.length === 1 ? candidates[0].path : null;
+
+  const ids = candidates.map(stem);
+  const more = ids.length > 10 ? ` (and ${ids.length - 10} more)` : '';
+  const error = new Error(
+    `${label} session id '${id}' is ambiguous: matches ${ids.length} sessions: ${ids.slice(0, 10).join(', ')}${more}. Use a longer id or --exact.`
+  );
+  error.code = 'AMBIGUOUS_ID';
+  error.candidates = ids;
+  throw error;
+}
+
 module.exports = {
-  MAX_FILE_SIZE,
-  MAX_SCAN_FILES,
+  maxFileSize,
+  maxScanFiles,
+  markScanTruncated,
+  wasScanTruncated,
+  scanTruncationWarning,
   expandHome,
   normalizePath,
   hashPath,
   collectMatchingFiles,
   readJsonlLines,
   findLatestByCwd,
+  selectById,
   getFileTimestamp,
   extractText,
   extractClaudeText,
   redactSensitiveText,
+  substantivePrompt,
+  sessionTitle,
+  createToolUsage,
+  createTokenUsage,
+  codexOutputFailed,
+  codexLineModel,
+  claudeLineModel,
+  geminiSessionModel,
+  firstJsonlModel,
 };
@@ -0,0 +1,35 @@
+#!/usr/bin/env node
+'use strict';
+
+// Build the Rust core as a Node-API addon and install it at
+// native/agent_bridge.node, where scripts/native.cjs looks for it.
+// Usage: npm run build:native [-- --debug]
+
+const fs = require('fs');
+const path = require('path');
+const { execFileSync } = require('child_process');
+
+const repoRoot = path.join(__dirname, '..');
+const manifestPath = path.join(repoRoot, 'cli', 'core', 'Cargo.toml');
+const debug = process.argv.includes('--debug');
+const profile = debug ? 'debug' : 'release';
+
+const cargoArgs = ['rustc', '--manifest-path', manifestPath, '--lib', '--features', 'napi', '--crate-type', 'cdylib'];
+if (!debug) cargoArgs.push('--release');
+if (process.platform === 'darwin') {
+  // Node provides the N-API symbols at load time.
+  cargoArgs.push('--', '-C', 'link-arg=-undefined', '-C', 'link-arg=dynamic_lookup');
+}
+execFileSync('cargo', cargoArgs, { stdio: 'inherit' });
+
+const libraryName = {
+  darwin: 'libagent_bridge_core.dylib',
+  win32: 'agent_bridge_core.dll',
+}[process.platform] || 'libagent_bridge_core.so';
+const targetDir = process.env.CARGO_TARGET_DIR || path.join(repoRoot, 'cli', 'target');
+const built = path.join(targetDir, profile, libraryName);
+const installed = path.join(repoRoot, 'native', 'agent_bridge.node');
+
+fs.mkdirSync(path.dirname(installed), { recursive: true });
+fs.copyFileSync(built, installed);
+console.log(`Installed ${path.relative(repoRoot, installed)}`);
@@ -0,0 +1,28 @@
+#!/usr/bin/env node
+'use strict';
+
+// Build the parsing and redaction core for wasm32 and install it at
+// web/agent_bridge.wasm, next to the web/agent_bridge.mjs loader.
+// Requires: rustup target add wasm32-unknown-unknown
+// Usage: npm run build:wasm [-- --debug]
+
+const fs = require('fs');
+const path = require('path');
+const { execFileSync } = require('child_process');
+
+const repoRoot = path.join(__dirname, '..');
+const manifestPath = path.join(repoRoot, 'cli', 'core', 'Cargo.toml');
+const target = 'wasm32-unknown-unknown';
+const debug = process.argv.includes('--debug');
+const profile = debug ? 'debug' : 'release';
+
+const cargoArgs = ['rustc', '--manifest-path', manifestPath, '--lib', '--target', target, '--crate-type', 'cdylib'];
+if (!debug) cargoArgs.push('--release');
+execFileSync('cargo', cargoArgs, { stdio: 'inherit' });
+
+const targetDir = process.env.CARGO_TARGET_DIR || path.join(repoRoot, 'cli', 'target');
+const built = path.join(targetDir, target, profile, 'agent_bridge_core.wasm');
+const installed = path.join(repoRoot, 'web', 'agent_bridge.wasm');
+
+fs.copyFileSync(built, installed);
+console.log(`Installed ${path.relative(repoRoot, installed)}`);
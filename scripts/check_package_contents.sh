@@ -17,6 +17,7 @@ const files = new Set((parsed?.[0]?.files ?? []).map((entry) => entry.path));
 
 const requiredPaths = [
   'scripts/read_session.cjs',
+  'scripts/native.cjs',
   'scripts/adapters/registry.cjs',
   'scripts/context_pack/build.cjs',
   'scripts/context_pack/install_hooks.cjs',
@@ -7,6 +7,16 @@ GOLDEN="$ROOT/fixtures/golden"
 TMP_DIR="$(mktemp -d)"
 trap 'rm -rf "$TMP_DIR"' EXIT
 
+# Fixture mtimes depend on the checkout, so keep staleness out of the goldens.
+export BRIDGE_STALE_SOURCE_HOURS=0
+
+# Agents without fixtures must not pick up the machine's real stores
+# (`--agent all` fans out to them).
+export BRIDGE_CURSOR_DATA_DIR="$TMP_DIR/missing"
+export BRIDGE_WINDSURF_DATA_DIR="$TMP_DIR/missing"
+export BRIDGE_CLINE_DATA_DIR="$TMP_DIR/missing"
+export BRIDGE_ZED_CONVERSATIONS_DIR="$TMP_DIR/missing"
+
 run_read_case() {
   local agent="$1"
   local session_id="$2"
@@ -72,26 +82,61 @@ run_compare_case() {
   fi
 }
 
+run_semantic_compare_case() {
+  local node_out="$TMP_DIR/compare-semantic-node.json"
+  local rust_out="$TMP_DIR/compare-semantic-rust.json"
+
+  BRIDGE_CODEX_SESSIONS_DIR="$STORE/codex/sessions" \
+  BRIDGE_GEMINI_TMP_DIR="$STORE/gemini/tmp" \
+  BRIDGE_CLAUDE_PROJECTS_DIR="$STORE/claude/projects" \
+  node "$ROOT/scripts/read_session.cjs" compare \
+    --source=codex:codex-fixture \
+    --source=gemini:gemini-fixture \
+    --source=claude:claude-fixture \
+    --semantic \
+    --json > "$node_out"
+
+  BRIDGE_CODEX_SESSIONS_DIR="$STORE/codex/sessions" \
+  BRIDGE_GEMINI_TMP_DIR="$STORE/gemini/tmp" \
+  BRIDGE_CLAUDE_PROJECTS_DIR="$STORE/claude/projects" \
+  cargo run --quiet --manifest-path "$ROOT/cli/Cargo.toml" -- compare \
+    --source codex:codex-fixture \
+    --source gemini:gemini-fixture \
+    --source claude:claude-fixture \
+    --semantic \
+    --json > "$rust_out"
+
+  node "$ROOT/scripts/compare_read_output.cjs" "$node_out" "$rust_out" "compare-semantic"
+}
+
 run_report_case() {
-  local handoff="$ROOT/fixtures/handoff-report.json"
-  local node_out="$TMP_DIR/report-node.json"
-  local rust_out="$TMP_DIR/report-rust.json"
+  local label="$1"
+  local handoff="$ROOT/fixtures/handoff-${label}.json"
+  local node_out="$TMP_DIR/${label}-node.json"
+  local rust_out="$TMP_DIR/${label}-rust.json"
+  # A FAIL or INCOMPLETE verdict exits 2; both CLIs must agree on the status.
+  local node_status=0
+  local rust_status=0
 
   BRIDGE_CODEX_SESSIONS_DIR="$STORE/codex/sessions" \
   BRIDGE_GEMINI_TMP_DIR="$STORE/gemini/tmp" \
   BRIDGE_CLAUDE_PROJECTS_DIR="$STORE/claude/projects" \
-  node "$ROOT/scripts/read_session.cjs" report --handoff="$handoff" --json > "$node_out"
+  node "$ROOT/scripts/read_session.cjs" report --handoff="$handoff" --json > "$node_out" || node_status=$?
 
   BRIDGE_CODEX_SESSIONS_DIR="$STORE/codex/sessions" \
   BRIDGE_GEMINI_TMP_DIR="$STORE/gemini/tmp" \
   BRIDGE_CLAUDE_PROJECTS_DIR="$STORE/claude/projects" \
-  cargo run --quiet --manifest-path "$ROOT/cli/Cargo.toml" -- report --handoff "$handoff" --json > "$rust_out"
+  cargo run --quiet --manifest-path "$ROOT/cli/Cargo.toml" -- report --handoff "$handoff" --json > "$rust_out" || rust_status=$?
 
-  node "$ROOT/scripts/compare_read_output.cjs" "$node_out" "$rust_out" "report"
+  if [[ "$node_status" != "$rust_status" || "$node_status" -gt 2 || "$node_status" -eq 1 ]]; then
+    echo "FAIL ${label}: exit status node=${node_status} rust=${rust_status}" >&2
+    exit 1
+  fi
+  node "$ROOT/scripts/compare_read_output.cjs" "$node_out" "$rust_out" "$label"
 
   # Golden file diff
-  if [[ -f "$GOLDEN/report.json" ]]; then
-    node "$ROOT/scripts/compare_read_output.cjs" "$node_out" "$GOLDEN/report.json" "golden-report"
+  if [[ -f "$GOLDEN/${label}.json" ]]; then
+    node "$ROOT/scripts/compare_read_output.cjs" "$node_out" "$GOLDEN/${label}.json" "golden-${label}"
   fi
 }
 
@@ -148,12 +193,35 @@ run_search_case() {
   fi
 }
 
+# --data-dir must win over the env var, which points nowhere here.
+run_data_dir_case() {
+  local node_out="$TMP_DIR/data-dir-node.json"
+  local rust_out="$TMP_DIR/data-dir-rust.json"
+
+  BRIDGE_CLAUDE_PROJECTS_DIR="$TMP_DIR/missing" \
+  node "$ROOT/scripts/read_session.cjs" list --agent=claude --data-dir="claude=$STORE/claude/projects" --json > "$node_out"
+
+  BRIDGE_CLAUDE_PROJECTS_DIR="$TMP_DIR/missing" \
+  cargo run --quiet --manifest-path "$ROOT/cli/Cargo.toml" -- list --agent claude --data-dir "claude=$STORE/claude/projects" --json > "$rust_out"
+
+  node "$ROOT/scripts/compare_read_output.cjs" "$node_out" "$rust_out" "list-data-dir"
+  if ! grep -q '"session_id"' "$node_out"; then
+    echo "FAIL list-data-dir: no sessions found under --data-dir" >&2
+    exit 1
+  fi
+}
+
 run_read_case codex codex-fixture Codex
 run_read_case gemini gemini-fixture Gemini
 run_read_case claude claude-fixture Claude
 run_compare_case
-run_report_case
+run_semantic_compare_case
+run_report_case report
+run_report_case chain
 run_list_case codex Codex /workspace/demo
 run_search_case codex Codex "Codex fixture assistant output." /workspace/demo
+run_list_case all All /workspace/demo
+run_search_case all All "fixture" /workspace/demo
+run_data_dir_case
 
 echo "Conformance complete: Node and Rust outputs match for read/compare/report/list/search (including golden file diffs)."
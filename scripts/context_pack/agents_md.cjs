@@ -0,0 +1,187 @@
+#!/usr/bin/env node
+'use strict';
+
+// Distill the built pack into a compact, standards-style AGENTS.md block.
+
+const fs = require('fs');
+const path = require('path');
+const crypto = require('crypto');
+const { execFileSync } = require('child_process');
+const { upsertBlock } = require('./sync_agents.cjs');
+
+const START_MARKER = '<!-- agent-bridge:agents-md:start -->';
+const END_MARKER = '<!-- agent-bridge:agents-md:end -->';
+
+function parseArgs(argv) {
+  const out = {
+    cwd: process.cwd(),
+    packDir: process.env.BRIDGE_CONTEXT_PACK_DIR || '.agent-context',
+    output: null,
+    dryRun: false,
+  };
+
+  for (let i = 2; i < argv.length; i += 1) {
+    const token = argv[i];
+    const [name, inlineValue] = token.startsWith('--') ? token.split('=', 2) : [token, null];
+    const next = inlineValue != null ? inlineValue : argv[i + 1];
+
+    switch (name) {
+      case '--cwd':
+        if (next) out.cwd = next;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--pack-dir':
+        out.packDir = next || out.packDir;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--output':
+        out.output = next || null;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--dry-run':
+        out.dryRun = true;
+        break;
+      default:
+        break;
+    }
+  }
+
+  return out;
+}
+
+function runGit(args, cwd, allowFailure = false) {
+  try {
+    return execFileSync('git', args, { cwd, encoding: 'utf8', stdio: ['ignore', 'pipe', 'pipe'] }).trim();
+  } catch (error) {
+    if (allowFailure) return '';
+    throw error;
+  }
+}
+
+function splitLines(markdown) {
+  const lines = markdown.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  return lines;
+}
+
+// List items (`- x` or `1. x`) under the `## ` sections of markdown whose heading satisfies `wanted`.
+function sectionItems(markdown, wanted) {
+  const items = [];
+  let inSection = false;
+  let inFence = false;
+  for (const line of splitLines(markdown)) {
+    if (line.trimStart().startsWith('```')) {
+      inFence = !inFence;
+      continue;
+    }
+    if (inFence) continue;
+    if (line.startsWith('## ')) {
+      inSection = wanted(line.slice(3).trim());
+      continue;
+    }
+    if (!inSection) continue;
+    const match = /^(?:- |\d+\. )(.*)$/.exec(line.trim());
+    if (match) items.push(match[1].trim());
+  }
+  return items;
+}
+
+// Lines inside fenced code blocks under the `## ` sections whose heading satisfies `wanted`.
+function sectionCommands(markdown, wanted) {
+  const commands = [];
+  let inSection = false;
+  let inFence = false;
+  for (const line of splitLines(markdown)) {
+    if (line.trimStart().startsWith('```')) {
+      inFence = !inFence;
+      continue;
+    }
+    if (!inFence) {
+      if (line.startsWith('## ')) inSection = wanted(line.slice(3).trim());
+      continue;
+    }
+    if (inSection && line.trim() !== '') commands.push(line.trimEnd());
+  }
+  return commands;
+}
+
+function readPackFile(currentDir, name) {
+  const filePath = path.join(currentDir, name);
+  if (!fs.existsSync(filePath)) {
+    throw new Error(`Failed to read ${filePath} (run: bridge context-pack build)`);
+  }
+  return fs.readFileSync(filePath, 'utf8');
+}
+
+// Pack files whose content no longer matches the checksums `build` recorded.
+function driftedFiles(currentDir, manifest) {
+  return (Array.isArray(manifest.files) ? manifest.files : [])
+    .filter((entry) => entry && typeof entry.path === 'string' && typeof entry.sha256 === 'string')
+    .filter((entry) => {
+      const filePath = path.join(currentDir, entry.path);
+      const content = fs.existsSync(filePath) ? fs.readFileSync(filePath) : Buffer.alloc(0);
+      return crypto.createHash('sha256').update(content).digest('hex') !== entry.sha256;
+    })
+    .map((entry) => entry.path);
+}
+
+function buildBlock(currentDir, currentLabel) {
+  const startHere = readPackFile(currentDir, '00_START_HERE.md');
+  const invariants = readPackFile(currentDir, '30_BEHAVIORAL_INVARIANTS.md');
+  const operations = readPackFile(currentDir, '40_OPERATIONS_AND_RELEASE.md');
+
+  const facts = sectionItems(startHere, (heading) => heading === 'Fast Facts');
+  const rules = sectionItems(invariants, (heading) => !heading.startsWith('Update Checklist'));
+  let commands = sectionCommands(operations, (heading) => heading.toLowerCase().includes('validation'));
+  if (commands.length === 0) commands = sectionCommands(operations, () => true);
+
+  const bullets = (items) => items.map((item) => `- ${item}`).join('\n');
+  let block = `${START_MARKER}\n## Project Guide\n\nGenerated from the context pack in \`${currentLabel}\`. Run \`bridge context-pack agents-md\` after \`bridge context-pack build\` to refresh.\n`;
+  if (facts.length > 0) block += `\n### Fast Facts\n${bullets(facts)}\n`;
+  if (rules.length > 0) block += `\n### Invariants\n${bullets(rules)}\n`;
+  if (commands.length > 0) block += `\n### Validation\n\`\`\`bash\n${commands.join('\n')}\n\`\`\`\n`;
+  block += `\nFor anything deeper, start at \`${currentLabel}00_START_HERE.md\` and follow its read order.\n${END_MARKER}`;
+  return block;
+}
+
+function main() {
+  const args = parseArgs(process.argv);
+  const repoRoot = runGit(['rev-parse', '--show-toplevel'], args.cwd, true) || path.resolve(args.cwd);
+  const currentDir = path.join(path.resolve(repoRoot, args.packDir), 'current');
+  const manifestPath = path.join(currentDir, 'manifest.json');
+  if (!fs.existsSync(manifestPath)) {
+    throw new Error(`Context pack not built: ${currentDir} has no manifest.json (run: bridge context-pack build)`);
+  }
+  const drifted = driftedFiles(currentDir, JSON.parse(fs.readFileSync(manifestPath, 'utf8')));
+  if (drifted.length > 0) {
+    process.stderr.write(
+      `[context-pack] warning: ${drifted.join(', ')} changed since the last build; generating from the edited files\n`
+    );
+  }
+
+  const currentLabel = `${path.relative(repoRoot, currentDir).split(path.sep).join('/')}/`;
+  const block = buildBlock(currentDir, currentLabel);
+  if (args.dryRun) {
+    process.stdout.write(`${block}\n`);
+    return;
+  }
+
+  const target = path.resolve(repoRoot, args.output || 'AGENTS.md');
+  const existing = fs.existsSync(target) ? fs.readFileSync(target, 'utf8') : null;
+  const next = upsertBlock(existing || '', block, START_MARKER, END_MARKER);
+  let status;
+  if (existing === next) status = 'unchanged';
+  else status = existing == null ? 'created' : 'updated';
+  if (status !== 'unchanged') {
+    fs.mkdirSync(path.dirname(target), { recursive: true });
+    fs.writeFileSync(target, next, 'utf8');
+  }
+  process.stdout.write(`[context-pack] ${status}: ${path.relative(repoRoot, target)}\n`);
+}
+
+try {
+  main();
+} catch (error) {
+  process.stderr.write(`${error.message}\n`);
+  process.exitCode = 1;
+}
@@ -5,6 +5,9 @@ const fs = require('fs');
 const path = require('path');
 const crypto = require('crypto');
 const { execFileSync } = require('child_process');
+const { rotate: rotateHistory } = require('./history.cjs');
+const { writeSnapshot } = require('./snapshot.cjs');
+const { refreshExisting } = require('./sync_agents.cjs');
 
 const ZERO_SHA_RE = /^0{40}$/;
 
@@ -101,6 +104,19 @@ function parseCargoVersion(cargoTomlText) {
   return match ? match[1] : 'unknown';
 }
 
+// Where head sits relative to the nearest tag (`git describe --tags`).
+function describeTags(repoRoot, head) {
+  const target = head || 'HEAD';
+  const describe = runGit(['describe', '--tags', target], repoRoot, true) || null;
+  const latestTag = runGit(['describe', '--tags', '--abbrev=0', target], repoRoot, true) || null;
+  const count = latestTag ? Number.parseInt(runGit(['rev-list', '--count', `${latestTag}..${target}`], repoRoot, true), 10) : NaN;
+  return {
+    describe,
+    latestTag,
+    commitsSinceTag: Number.isNaN(count) ? null : count,
+  };
+}
+
 function computeChangedFiles(repoRoot, base, head) {
   if (!head) return [];
   if (!base || ZERO_SHA_RE.test(base)) {
@@ -115,6 +131,7 @@ function summarizePathCounts(paths) {
   const buckets = [
     { key: 'scripts/', name: 'scripts', count: 0 },
     { key: 'cli/src/', name: 'cli/src', count: 0 },
+    { key: 'cli/core/src/', name: 'cli/core/src', count: 0 },
     { key: 'schemas/', name: 'schemas', count: 0 },
     { key: 'fixtures/', name: 'fixtures', count: 0 },
     { key: '.github/workflows/', name: '.github/workflows', count: 0 },
@@ -223,7 +240,7 @@ function buildSystemOverview({
 
 ## Dual-Implementation Contract
 - Node path: \`scripts/read_session.cjs\` + \`scripts/adapters/*.cjs\`.
-- Rust path: \`cli/src/main.rs\`, \`cli/src/agents.rs\`, \`cli/src/report.rs\`, \`cli/src/adapters/*.rs\`.
+- Rust path: \`cli/src/main.rs\`, \`cli/core/src/agents.rs\`, \`cli/core/src/report.rs\`, \`cli/core/src/adapters/*.rs\`.
 - Protocol authority: \`PROTOCOL.md\` and \`schemas/*.json\`.
 - Parity guard: \`scripts/conformance.sh\`.
 
@@ -246,8 +263,8 @@ function buildCodeMap() {
 | \`scripts/read_session.cjs\` | Node CLI command parser + execution engine | Defines behavior for all user-facing commands in Node distribution | High |
 | \`scripts/adapters/*.cjs\` | Node agent-specific session adapters | Session discovery, parsing, and metadata quality for each provider | High |
 | \`cli/src/main.rs\` | Rust CLI command/arg handling | Entry-point parity with Node and release binary behavior | High |
-| \`cli/src/agents.rs\` | Rust session parsing + redaction + read/list/search | Largest behavioral surface and most error-code paths | High |
-| \`cli/src/report.rs\` | Rust compare/report logic | Cross-agent divergence logic and report markdown/json structure | High |
+| \`cli/core/src/agents.rs\` | Rust session parsing + redaction + read/list/search | Largest behavioral surface and most error-code paths | High |
+| \`cli/core/src/report.rs\` | Rust compare/report logic | Cross-agent divergence logic and report markdown/json structure | High |
 | \`schemas/*.json\` | JSON contract definitions | External compatibility for \`--json\` users and tests | High |
 | \`PROTOCOL.md\` | Versioned protocol contract | Human contract that aligns Node, Rust, and tests | High |
 | \`README.md\` | Public command docs and examples | User expectations and documentation-driven behavior | Medium |
@@ -257,7 +274,7 @@ function buildCodeMap() {
 | \`.github/workflows/release.yml\` | Release pipeline | Controls publish safety and artifact generation | Medium |
 
 ## Extension Recipe (New Agent)
-1. Implement adapter in Rust: \`cli/src/adapters/<agent>.rs\` and register in \`cli/src/adapters/mod.rs\`.
+1. Implement adapter in Rust: \`cli/core/src/adapters/<agent>.rs\` and register in \`cli/core/src/adapters/mod.rs\`.
 2. Implement adapter in Node: \`scripts/adapters/<agent>.cjs\` and register in \`scripts/adapters/registry.cjs\`.
 3. Add schema enum coverage in \`schemas/*.json\`.
 4. Add fixtures and golden expectations under \`fixtures/\`.
@@ -327,11 +344,6 @@ cargo test --manifest-path cli/Cargo.toml
 `;
 }
 
-function copyDir(source, destination) {
-  ensureDir(path.dirname(destination));
-  fs.cpSync(source, destination, { recursive: true });
-}
-
 function appendHistory(historyPath, entry) {
   ensureDir(path.dirname(historyPath));
   fs.appendFileSync(historyPath, `${JSON.stringify(entry)}\n`, 'utf8');
@@ -345,6 +357,7 @@ function buildManifest({
   headSha,
   packageVersion,
   cargoVersion,
+  tagInfo,
   reason,
   baseSha,
   changedFiles,
@@ -373,6 +386,9 @@ function buildManifest({
     head_sha: headSha || null,
     package_version: packageVersion,
     cargo_version: cargoVersion,
+    git_describe: tagInfo.describe,
+    latest_tag: tagInfo.latestTag,
+    commits_since_tag: tagInfo.commitsSinceTag,
     build_reason: reason,
     base_sha: baseSha || null,
     changed_files: changedFiles,
@@ -403,10 +419,13 @@ function main() {
   const repoRoot = runGit(['rev-parse', '--show-toplevel'], process.cwd(), true) || process.cwd();
   const repoName = path.basename(repoRoot);
   const packageJsonPath = path.join(repoRoot, 'package.json');
-  const cargoTomlPath = path.join(repoRoot, 'cli', 'Cargo.toml');
+  // The crate lives in cli/ here; fall back to a root Cargo.toml for other layouts.
+  const cargoTomlPath = [path.join(repoRoot, 'cli', 'Cargo.toml'), path.join(repoRoot, 'Cargo.toml')].find((candidate) =>
+    fs.existsSync(candidate)
+  );
 
   const packageJson = readJson(packageJsonPath) || { version: 'unknown' };
-  const cargoTomlText = fs.existsSync(cargoTomlPath) ? fs.readFileSync(cargoTomlPath, 'utf8') : '';
+  const cargoTomlText = cargoTomlPath ? fs.readFileSync(cargoTomlPath, 'utf8') : '';
 
   const branch = runGit(['rev-parse', '--abbrev-ref', 'HEAD'], repoRoot, true) || 'unknown';
   const headSha = options.head || runGit(['rev-parse', 'HEAD'], repoRoot, true) || null;
@@ -425,14 +444,14 @@ function main() {
   const pathCounts = summarizePathCounts(trackedFiles);
 
   const commandSurface = [
-    { command: 'read', intent: 'Read latest or selected session content', primaryPaths: ['scripts/read_session.cjs', 'cli/src/main.rs', 'cli/src/agents.rs'] },
-    { command: 'list', intent: 'List recent sessions by agent', primaryPaths: ['scripts/read_session.cjs', 'cli/src/agents.rs'] },
-    { command: 'search', intent: 'Find sessions containing text', primaryPaths: ['scripts/read_session.cjs', 'cli/src/agents.rs'] },
-    { command: 'compare', intent: 'Compare multiple agent outputs', primaryPaths: ['scripts/read_session.cjs', 'cli/src/report.rs'] },
-    { command: 'report', intent: 'Build coordinator report from handoff JSON', primaryPaths: ['scripts/read_session.cjs', 'cli/src/report.rs'] },
+    { command: 'read', intent: 'Read latest or selected session content', primaryPaths: ['scripts/read_session.cjs', 'cli/src/main.rs', 'cli/core/src/agents.rs'] },
+    { command: 'list', intent: 'List recent sessions by agent', primaryPaths: ['scripts/read_session.cjs', 'cli/core/src/agents.rs'] },
+    { command: 'search', intent: 'Find sessions containing text', primaryPaths: ['scripts/read_session.cjs', 'cli/core/src/agents.rs'] },
+    { command: 'compare', intent: 'Compare multiple agent outputs', primaryPaths: ['scripts/read_session.cjs', 'cli/core/src/report.rs'] },
+    { command: 'report', intent: 'Build coordinator report from handoff JSON', primaryPaths: ['scripts/read_session.cjs', 'cli/core/src/report.rs'] },
     { command: 'setup', intent: 'Write provider instruction wiring files', primaryPaths: ['scripts/read_session.cjs'] },
     { command: 'doctor', intent: 'Check setup and path wiring', primaryPaths: ['scripts/read_session.cjs'] },
-    { command: 'trash-talk', intent: 'Roast active agents from session content', primaryPaths: ['scripts/read_session.cjs', 'cli/src/agents.rs'] },
+    { command: 'trash-talk', intent: 'Roast active agents from session content', primaryPaths: ['scripts/read_session.cjs', 'cli/core/src/agents.rs'] },
     { command: 'context-pack', intent: 'Build/sync/install context-pack automation', primaryPaths: ['scripts/read_session.cjs', 'scripts/context_pack'] },
   ];
 
@@ -491,6 +510,7 @@ function main() {
     headSha,
     packageVersion: packageJson.version || 'unknown',
     cargoVersion: parseCargoVersion(cargoTomlText),
+    tagInfo: describeTags(repoRoot, headSha),
     reason: options.reason,
     baseSha: options.base,
     changedFiles,
@@ -498,6 +518,7 @@ function main() {
   });
 
   writeText(manifestPath, `${JSON.stringify(manifest, null, 2)}\n`);
+  refreshExisting(repoRoot, packRoot);
 
   // Use a stable checksum for change detection that excludes volatile fields
   // (generated_at timestamp). This prevents unnecessary snapshots on routine
@@ -516,8 +537,7 @@ function main() {
 
   if (changed) {
     const snapshotId = `${compactTimestamp(generatedAt)}_${asShortSha(headSha)}`;
-    const snapshotDir = path.join(snapshotsDir, snapshotId);
-    copyDir(currentDir, snapshotDir);
+    writeSnapshot(currentDir, snapshotsDir, snapshotId);
 
     appendHistory(historyPath, {
       snapshot_id: snapshotId,
@@ -529,6 +549,7 @@ function main() {
       changed_files: changedFiles,
       pack_checksum: manifest.pack_checksum,
     });
+    rotateHistory(packRoot, historyPath);
 
     process.stdout.write(
       `[context-pack] updated: ${path.relative(repoRoot, packRoot)} (snapshot ${snapshotId})\n`
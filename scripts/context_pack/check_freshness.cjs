@@ -2,6 +2,7 @@
 'use strict';
 
 const { execFileSync } = require('child_process');
+const { loadRelevanceRules, resolvePackRoot } = require('./relevance.cjs');
 
 function parseArgs(argv) {
   const options = {
@@ -57,61 +58,67 @@ function getChangedFiles(base, cwd) {
   return fallback.split('\n').map((line) => line.trim()).filter(Boolean);
 }
 
-function isContextRelevant(filePath) {
-  const normalized = filePath.replace(/\\/g, '/');
-
-  if (
-    normalized.startsWith('blog/') ||
-    normalized.startsWith('notes/') ||
-    normalized.startsWith('drafts/') ||
-    normalized.startsWith('scratch/') ||
-    normalized.startsWith('tmp/') ||
-    normalized.startsWith('.agent-context/') ||
-    normalized.startsWith('docs/demo-')
-  ) {
-    return false;
+// Freshness when relevance.json maps paths to sections: each mapped section must be
+// updated in the same diff; relevant paths outside every mapping are satisfied by any
+// pack update, as without a mapping.
+function reportStaleSections(rules, relevant, touchedSections, packTouched) {
+  const stale = new Map();
+  const unmapped = [];
+  for (const filePath of relevant) {
+    const sections = rules.sectionsFor(filePath);
+    if (sections.length === 0) {
+      unmapped.push(filePath);
+      continue;
+    }
+    for (const section of sections) {
+      if (touchedSections.includes(section)) continue;
+      if (!stale.has(section)) stale.set(section, []);
+      stale.get(section).push(filePath);
+    }
   }
+  const unmappedStale = unmapped.length > 0 && !packTouched;
 
-  if (
-    normalized === 'README.md' ||
-    normalized === 'PROTOCOL.md' ||
-    normalized === 'CONTRIBUTING.md' ||
-    normalized === 'SKILL.md' ||
-    normalized === 'AGENTS.md' ||
-    normalized === 'package.json' ||
-    normalized === 'package-lock.json' ||
-    normalized === 'cli/Cargo.toml' ||
-    normalized === 'cli/Cargo.lock' ||
-    normalized === 'docs/architecture.svg' ||
-    normalized === 'docs/silo-tax-before-after.webp'
-  ) {
-    return true;
+  if (stale.size === 0 && !unmappedStale) {
+    process.stdout.write('PASS context-pack-freshness (mapped sections were updated)\n');
+    return;
   }
 
-  return (
-    normalized.startsWith('scripts/') ||
-    normalized.startsWith('cli/src/') ||
-    normalized.startsWith('schemas/') ||
-    normalized.startsWith('fixtures/golden/') ||
-    normalized.startsWith('fixtures/session-store/') ||
-    normalized.startsWith('.github/workflows/')
-  );
+  if (stale.size > 0) {
+    process.stdout.write(`WARNING: ${stale.size} context-pack section(s) are stale for the changed files:\n`);
+    for (const [section, paths] of stale) {
+      process.stdout.write(`  - ${section} (${paths.join(', ')})\n`);
+    }
+  }
+  if (unmappedStale) {
+    process.stdout.write(
+      `WARNING: ${unmapped.length} context-relevant file(s) changed but .agent-context/current/ was not updated:\n`
+    );
+    for (const filePath of unmapped) {
+      process.stdout.write(`  - ${filePath}\n`);
+    }
+  }
+  process.stdout.write('\n');
+  process.stdout.write('Consider running: bridge context-pack build\n');
 }
 
 function main() {
   const options = parseArgs(process.argv);
   const changedFiles = getChangedFiles(options.base, options.cwd);
+  const repoRoot = runGit(['rev-parse', '--show-toplevel'], options.cwd, true) || options.cwd;
+  const rules = loadRelevanceRules(resolvePackRoot(repoRoot));
 
   let packTouched = false;
+  const touchedSections = [];
   const relevant = [];
 
   for (const filePath of changedFiles) {
     if (filePath.startsWith('.agent-context/current/')) {
       packTouched = true;
+      touchedSections.push(filePath.slice('.agent-context/current/'.length));
       continue;
     }
 
-    if (isContextRelevant(filePath)) {
+    if (rules.isRelevant(filePath)) {
       relevant.push(filePath);
     }
   }
@@ -121,6 +128,11 @@ function main() {
     return;
   }
 
+  if (rules.hasSections()) {
+    reportStaleSections(rules, relevant, touchedSections, packTouched);
+    return;
+  }
+
   if (packTouched) {
     process.stdout.write('PASS context-pack-freshness (context pack was updated)\n');
     return;
@@ -0,0 +1,165 @@
+#!/usr/bin/env node
+'use strict';
+
+// Keep `history.jsonl` bounded by moving old entries into the gzip-compressed
+// `history.archive.jsonl.gz`.
+
+const fs = require('fs');
+const path = require('path');
+const zlib = require('zlib');
+const { execFileSync } = require('child_process');
+
+const ARCHIVE_FILE = 'history.archive.jsonl.gz';
+const DEFAULT_MAX_KB = 512;
+const DEFAULT_MAX_DAYS = 180;
+
+function parseArgs(argv) {
+  const out = {
+    command: null,
+    keep: 20,
+    packDir: process.env.BRIDGE_CONTEXT_PACK_DIR || '.agent-context',
+  };
+
+  for (let i = 2; i < argv.length; i += 1) {
+    const token = argv[i];
+    const [name, inlineValue] = token.startsWith('--') ? token.split('=', 2) : [token, null];
+    const next = inlineValue != null ? inlineValue : argv[i + 1];
+
+    switch (name) {
+      case '--keep': {
+        const value = Number(next);
+        if (!Number.isInteger(value) || value < 0) {
+          throw new Error(`Invalid --keep: ${next} (expected a non-negative integer)`);
+        }
+        out.keep = value;
+        if (inlineValue == null) i += 1;
+        break;
+      }
+      case '--pack-dir':
+        out.packDir = next || out.packDir;
+        if (inlineValue == null) i += 1;
+        break;
+      default:
+        if (!name.startsWith('--') && out.command == null) out.command = name;
+        break;
+    }
+  }
+
+  return out;
+}
+
+function runGit(args, cwd, allowFailure = false) {
+  try {
+    return execFileSync('git', args, { cwd, encoding: 'utf8', stdio: ['ignore', 'pipe', 'pipe'] }).trim();
+  } catch (error) {
+    if (allowFailure) return '';
+    throw error;
+  }
+}
+
+// A limit from the environment, falling back to the default; `0` disables the limit.
+function envLimit(name, fallback) {
+  const raw = process.env[name];
+  const parsed = raw != null && /^\s*\d+\s*$/.test(raw) ? Number(raw) : fallback;
+  return parsed > 0 ? parsed : null;
+}
+
+function readLines(historyPath) {
+  if (!fs.existsSync(historyPath)) return [];
+  return fs
+    .readFileSync(historyPath, 'utf8')
+    .split('\n')
+    .filter((line) => line.trim() !== '');
+}
+
+function generatedAtMillis(line) {
+  try {
+    const value = JSON.parse(line).generated_at;
+    if (typeof value !== 'string') return null;
+    const ms = Date.parse(value);
+    return Number.isNaN(ms) ? null : ms;
+  } catch {
+    return null;
+  }
+}
+
+// Number of leading (oldest) lines to archive: entries generated before
+// cutoffMs, then more of the oldest until the rest fits in half of maxBytes,
+// so rotation does not run again on the next build. The latest entry is always kept.
+function rotationSplit(lines, cutoffMs, maxBytes) {
+  let split = 0;
+  if (cutoffMs != null) {
+    while (split + 1 < lines.length) {
+      const ms = generatedAtMillis(lines[split]);
+      if (ms == null || ms >= cutoffMs) break;
+      split += 1;
+    }
+  }
+  if (maxBytes != null) {
+    const size = (from) => lines.slice(from).reduce((total, line) => total + Buffer.byteLength(line) + 1, 0);
+    if (size(split) > maxBytes) {
+      while (split + 1 < lines.length && size(split) > maxBytes / 2) split += 1;
+    }
+  }
+  return split;
+}
+
+// Append lines[..split] to the archive as a new gzip member, then rewrite the
+// history with the rest. The archive is written first so an interrupted run
+// duplicates entries instead of losing them.
+function archivePrefix(packRoot, historyPath, lines, split) {
+  const archivePath = path.join(packRoot, ARCHIVE_FILE);
+  const archived = lines.slice(0, split).map((line) => `${line}\n`).join('');
+  fs.appendFileSync(archivePath, zlib.gzipSync(Buffer.from(archived, 'utf8')));
+  fs.writeFileSync(historyPath, lines.slice(split).map((line) => `${line}\n`).join(''), 'utf8');
+  return archivePath;
+}
+
+// Rotate after a build appended to `history.jsonl`. Limits come from
+// BRIDGE_CONTEXT_PACK_HISTORY_MAX_KB and BRIDGE_CONTEXT_PACK_HISTORY_MAX_DAYS.
+function rotate(packRoot, historyPath) {
+  const maxKb = envLimit('BRIDGE_CONTEXT_PACK_HISTORY_MAX_KB', DEFAULT_MAX_KB);
+  const maxDays = envLimit('BRIDGE_CONTEXT_PACK_HISTORY_MAX_DAYS', DEFAULT_MAX_DAYS);
+  const lines = readLines(historyPath);
+  const split = rotationSplit(
+    lines,
+    maxDays == null ? null : Date.now() - maxDays * 86400000,
+    maxKb == null ? null : maxKb * 1024
+  );
+  if (split === 0) return;
+  archivePrefix(packRoot, historyPath, lines, split);
+  process.stdout.write(`[context-pack] rotated ${split} history entr(ies) into ${ARCHIVE_FILE}\n`);
+}
+
+// Move every entry except the latest `keep` into the archive.
+function compact(repoRoot, packRoot, keep) {
+  const historyPath = path.join(packRoot, 'history.jsonl');
+  const lines = readLines(historyPath);
+  const split = Math.max(lines.length - keep, 0);
+  if (split === 0) {
+    process.stdout.write(`[context-pack] history already compact (${lines.length} entr(ies))\n`);
+    return;
+  }
+  const archivePath = archivePrefix(packRoot, historyPath, lines, split);
+  process.stdout.write(
+    `[context-pack] history compacted: archived ${split} entr(ies) to ${path.relative(repoRoot, archivePath)}, kept ${lines.length - split}\n`
+  );
+}
+
+if (require.main === module) {
+  try {
+    const args = parseArgs(process.argv);
+    if (args.command !== 'compact') {
+      throw new Error(`Unknown context-pack history command: ${args.command || '(none)'}. Expected: compact`);
+    }
+    const repoRoot = runGit(['rev-parse', '--show-toplevel'], process.cwd(), true) || process.cwd();
+    compact(repoRoot, path.resolve(repoRoot, args.packDir), args.keep);
+  } catch (error) {
+    process.stderr.write(`${error.message}\n`);
+    process.exitCode = 1;
+  }
+}
+
+module.exports = {
+  rotate,
+};
@@ -0,0 +1,214 @@
+#!/usr/bin/env node
+'use strict';
+
+// Content checks for `.agent-context/current/*.md` before a pack is committed:
+// leftover template markers, empty sections, broken relative links, TODO
+// density, and per-file word budgets.
+
+const fs = require('fs');
+const path = require('path');
+const { execFileSync } = require('child_process');
+
+const DEFAULT_MAX_WORDS = 1500;
+const DEFAULT_MAX_TODOS_PER_100_WORDS = 1;
+const TODO_WORDS = new Set(['TODO', 'FIXME', 'TBD']);
+
+function parseArgs(argv) {
+  const out = {
+    packDir: process.env.BRIDGE_CONTEXT_PACK_DIR || '.agent-context',
+    json: false,
+  };
+
+  for (let i = 2; i < argv.length; i += 1) {
+    const token = argv[i];
+    const [name, inlineValue] = token.startsWith('--') ? token.split('=', 2) : [token, null];
+    const next = inlineValue != null ? inlineValue : argv[i + 1];
+
+    switch (name) {
+      case '--pack-dir':
+        out.packDir = next || out.packDir;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--json':
+        out.json = true;
+        break;
+      default:
+        break;
+    }
+  }
+
+  return out;
+}
+
+function runGit(args, cwd, allowFailure = false) {
+  try {
+    return execFileSync('git', args, { cwd, encoding: 'utf8', stdio: ['ignore', 'pipe', 'pipe'] }).trim();
+  } catch (error) {
+    if (allowFailure) return '';
+    throw error;
+  }
+}
+
+// Budgets from `<pack>/lint.json`:
+// {"max_words": {"default": 1500, "20_CODE_MAP.md": 2500}, "max_todos_per_100_words": 1}.
+function loadLintConfig(packRoot) {
+  const configPath = path.join(packRoot, 'lint.json');
+  let raw = {};
+  if (fs.existsSync(configPath)) {
+    try {
+      raw = JSON.parse(fs.readFileSync(configPath, 'utf8')) || {};
+    } catch (error) {
+      throw new Error(`Failed to parse ${configPath}: ${error.message}`);
+    }
+  }
+  const isCount = (value) => Number.isInteger(value) && value >= 0;
+  return {
+    maxWords(file) {
+      const budgets = raw.max_words;
+      if (isCount(budgets)) return budgets;
+      if (budgets && isCount(budgets[file])) return budgets[file];
+      if (budgets && isCount(budgets.default)) return budgets.default;
+      return DEFAULT_MAX_WORDS;
+    },
+    maxTodosPer100Words() {
+      return typeof raw.max_todos_per_100_words === 'number'
+        ? raw.max_todos_per_100_words
+        : DEFAULT_MAX_TODOS_PER_100_WORDS;
+    },
+  };
+}
+
+function headingLevel(line) {
+  const match = /^(#{1,6}) /.exec(line);
+  return match ? match[1].length : null;
+}
+
+// Relative targets of `[text](target)` links; external URLs and in-page anchors are skipped.
+function linkTargets(line) {
+  const targets = [];
+  let rest = line;
+  let start = rest.indexOf('](');
+  while (start !== -1) {
+    rest = rest.slice(start + 2);
+    const end = rest.indexOf(')');
+    if (end === -1) break;
+    const target = rest.slice(0, end).trim().split(/\s+/)[0] || '';
+    rest = rest.slice(end + 1);
+    start = rest.indexOf('](');
+    if (!target || target.startsWith('#') || target.includes('://') || target.startsWith('mailto:')) continue;
+    targets.push(target);
+  }
+  return targets;
+}
+
+function lintFile(file, content, currentDir, config, issues) {
+  const push = (line, rule, message) => issues.push({ file, line, rule, message });
+  const lines = content.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+
+  // [line number, text] pairs outside fenced code blocks.
+  const prose = [];
+  let inFence = false;
+  lines.forEach((line, index) => {
+    if (line.trimStart().startsWith('```')) {
+      inFence = !inFence;
+      return;
+    }
+    if (!inFence) prose.push([index + 1, line]);
+  });
+
+  for (const [lineNo, line] of prose) {
+    if (line.includes('<!-- AGENT:')) {
+      push(lineNo, 'agent-marker', 'leftover `<!-- AGENT:` marker');
+    }
+    for (const target of linkTargets(line)) {
+      const targetPath = target.split('#')[0];
+      if (targetPath && !fs.existsSync(path.join(currentDir, targetPath))) {
+        push(lineNo, 'broken-link', `link target not found: ${target}`);
+      }
+    }
+  }
+
+  const inSection = (level) => (next) => {
+    const nextLevel = headingLevel(next);
+    return nextLevel == null || nextLevel > level;
+  };
+  const takeWhile = (items, predicate) => {
+    const out = [];
+    for (const item of items) {
+      if (!predicate(item)) break;
+      out.push(item);
+    }
+    return out;
+  };
+  prose.forEach(([lineNo, line], position) => {
+    const level = headingLevel(line);
+    if (level == null) return;
+    const hasBody = takeWhile(prose.slice(position + 1).map(([, next]) => next), inSection(level))
+      .some((next) => next.trim() !== '');
+    // Code blocks are dropped from `prose`, so a section holding only a fence
+    // still counts as having a body.
+    const fenced = takeWhile(lines.slice(lineNo), inSection(level))
+      .some((next) => next.trimStart().startsWith('```'));
+    if (!hasBody && !fenced) {
+      push(lineNo, 'empty-section', `section \`${line.trim()}\` has no content`);
+    }
+  });
+
+  const words = content.split(/\s+/).filter(Boolean);
+  const maxWords = config.maxWords(file);
+  if (words.length > maxWords) {
+    push(null, 'word-budget', `${words.length} words exceeds the budget of ${maxWords}`);
+  }
+
+  const todos = words.filter((word) => TODO_WORDS.has(word.replace(/^[^\p{L}\p{N}]+|[^\p{L}\p{N}]+$/gu, ''))).length;
+  const maxDensity = config.maxTodosPer100Words();
+  if (todos > 0 && todos * 100 > words.length * maxDensity) {
+    push(null, 'todo-density', `${todos} TODO/FIXME/TBD markers in ${words.length} words (max ${maxDensity} per 100 words)`);
+  }
+}
+
+function main() {
+  const args = parseArgs(process.argv);
+  const repoRoot = runGit(['rev-parse', '--show-toplevel'], process.cwd(), true) || process.cwd();
+  const packRoot = path.resolve(repoRoot, args.packDir);
+  const currentDir = path.join(packRoot, 'current');
+  if (!fs.existsSync(currentDir)) {
+    throw new Error(`Context pack not found: ${currentDir} (run: bridge context-pack build)`);
+  }
+
+  const config = loadLintConfig(packRoot);
+  const files = fs.readdirSync(currentDir).filter((name) => name.endsWith('.md')).sort();
+  const issues = [];
+  for (const file of files) {
+    lintFile(file, fs.readFileSync(path.join(currentDir, file), 'utf8'), currentDir, config, issues);
+  }
+
+  const ok = issues.length === 0;
+  if (args.json) {
+    const relative = path.relative(repoRoot, packRoot);
+    const report = {
+      pack: !relative || relative.startsWith('..') || path.isAbsolute(relative) ? packRoot : relative,
+      files: files.length,
+      ok,
+      issues,
+    };
+    process.stdout.write(`${JSON.stringify(report, null, 2)}\n`);
+  } else if (ok) {
+    process.stdout.write(`PASS context-pack-lint (${files.length} file(s) checked)\n`);
+  } else {
+    process.stdout.write(`FAIL context-pack-lint (${issues.length} issue(s)):\n`);
+    for (const issue of issues) {
+      const location = issue.line == null ? issue.file : `${issue.file}:${issue.line}`;
+      process.stdout.write(`  - ${location} [${issue.rule}] ${issue.message}\n`);
+    }
+  }
+  if (!ok) process.exitCode = 1;
+}
+
+try {
+  main();
+} catch (error) {
+  process.stderr.write(`${error.message}\n`);
+  process.exitCode = 1;
+}
@@ -0,0 +1,82 @@
+'use strict';
+
+// Line-level preview of what a rollback would change in `current/`.
+
+// Cap on `old lines * new lines` for the LCS table; bigger files are
+// summarized by line count instead.
+const MAX_DIFF_CELLS = 4000000;
+
+function splitLines(content) {
+  const lines = content.toString('utf8').split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  return lines;
+}
+
+// `-`/`+` lines turning oldLines into newLines (longest-common-subsequence
+// diff, unchanged lines omitted).
+function lineDiff(oldLines, newLines) {
+  if (oldLines.length * newLines.length > MAX_DIFF_CELLS) {
+    return [`(${oldLines.length} -> ${newLines.length} lines; too large to diff)`];
+  }
+  const lcs = Array.from({ length: oldLines.length + 1 }, () => new Array(newLines.length + 1).fill(0));
+  for (let i = oldLines.length - 1; i >= 0; i -= 1) {
+    for (let j = newLines.length - 1; j >= 0; j -= 1) {
+      lcs[i][j] = oldLines[i] === newLines[j] ? lcs[i + 1][j + 1] + 1 : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+    }
+  }
+
+  let i = 0;
+  let j = 0;
+  const out = [];
+  while (i < oldLines.length && j < newLines.length) {
+    if (oldLines[i] === newLines[j]) {
+      i += 1;
+      j += 1;
+    } else if (lcs[i + 1][j] >= lcs[i][j + 1]) {
+      out.push(`-${oldLines[i]}`);
+      i += 1;
+    } else {
+      out.push(`+${newLines[j]}`);
+      j += 1;
+    }
+  }
+  for (; i < oldLines.length; i += 1) out.push(`-${oldLines[i]}`);
+  for (; j < newLines.length; j += 1) out.push(`+${newLines[j]}`);
+  return out;
+}
+
+// Report lines for restoring target over current (both Maps of name -> Buffer):
+// `+ file` (added), `- file` (removed), and `~ file` (modified) followed by its line diff.
+function describeChanges(current, target) {
+  const names = Array.from(new Set([...current.keys(), ...target.keys()])).sort();
+  const out = [];
+  for (const name of names) {
+    const oldContent = current.get(name);
+    const newContent = target.get(name);
+    if (!oldContent) {
+      out.push(`+ ${name} (added)`);
+    } else if (!newContent) {
+      out.push(`- ${name} (removed)`);
+    } else if (!oldContent.equals(newContent)) {
+      out.push(`~ ${name}`);
+      for (const line of lineDiff(splitLines(oldContent), splitLines(newContent))) {
+        out.push(`    ${line}`);
+      }
+    }
+  }
+  return out;
+}
+
+// True when both Maps hold the same names with identical content.
+function sameFiles(a, b) {
+  if (a.size !== b.size) return false;
+  for (const [name, content] of a) {
+    if (!b.has(name) || !b.get(name).equals(content)) return false;
+  }
+  return true;
+}
+
+module.exports = {
+  describeChanges,
+  sameFiles,
+};
@@ -0,0 +1,14 @@
+'use strict';
+
+// Pack files in the read order 00_START_HERE.md prescribes, with what each one is for.
+const READ_ORDER = [
+  { file: '00_START_HERE.md', purpose: 'index, snapshot, and fast facts' },
+  { file: '10_SYSTEM_OVERVIEW.md', purpose: 'architecture and execution paths' },
+  { file: '30_BEHAVIORAL_INVARIANTS.md', purpose: 'contracts to preserve; read before changing behavior' },
+  { file: '20_CODE_MAP.md', purpose: 'deep dive only the files relevant to the task' },
+  { file: '40_OPERATIONS_AND_RELEASE.md', purpose: 'tests, release, and maintenance' },
+];
+
+module.exports = {
+  READ_ORDER,
+};
@@ -0,0 +1,190 @@
+'use strict';
+
+const fs = require('fs');
+const path = require('path');
+
+// Paths that count as context-relevant when relevance.json does not override `include`.
+const DEFAULT_INCLUDE = [
+  '/README.md',
+  '/PROTOCOL.md',
+  '/CONTRIBUTING.md',
+  '/SKILL.md',
+  '/AGENTS.md',
+  '/package.json',
+  '/package-lock.json',
+  '/cli/Cargo.toml',
+  '/cli/Cargo.lock',
+  '/cli/core/Cargo.toml',
+  '/docs/architecture.svg',
+  '/docs/silo-tax-before-after.webp',
+  '/scripts/',
+  '/cli/src/',
+  '/cli/core/src/',
+  '/cli/core/wit/',
+  '/schemas/',
+  '/fixtures/golden/',
+  '/fixtures/session-store/',
+  '/.github/workflows/',
+];
+
+// Paths that are never context-relevant when relevance.json does not override `exclude`.
+const DEFAULT_EXCLUDE = [
+  '/blog/',
+  '/notes/',
+  '/drafts/',
+  '/scratch/',
+  '/tmp/',
+  '/.agent-context/',
+  '/docs/demo-*',
+];
+
+/**
+ * One .gitignore-style pattern: `!` negates, a trailing `/` matches only
+ * directories (and so everything under them), a leading or inner `/` anchors
+ * the pattern to the repo root, and otherwise it matches a name at any depth.
+ * `*` and `?` stay within one path segment; `**` spans segments.
+ */
+function parsePattern(raw) {
+  let text = String(raw).trim();
+  if (!text || text.startsWith('#')) return null;
+  const negated = text.startsWith('!');
+  if (negated) text = text.slice(1);
+  const dirOnly = text.endsWith('/');
+  text = text.replace(/\/+$/, '');
+  const anchored = text.includes('/');
+  text = text.replace(/^\/+/, '');
+  if (!text) return null;
+  return { glob: text, negated, dirOnly, anchored };
+}
+
+function globMatch(pattern, text) {
+  if (pattern.length === 0) return text.length === 0;
+  if (pattern.startsWith('**')) {
+    let rest = pattern.slice(2);
+    // `**/` also matches zero directories.
+    if (rest.startsWith('/')) {
+      if (globMatch(rest.slice(1), text)) return true;
+      rest = rest.slice(1);
+      for (let i = 0; i < text.length; i += 1) {
+        if (text[i] === '/' && globMatch(rest, text.slice(i + 1))) return true;
+      }
+      return false;
+    }
+    for (let i = 0; i <= text.length; i += 1) {
+      if (globMatch(rest, text.slice(i))) return true;
+    }
+    return false;
+  }
+  if (pattern[0] === '*') {
+    for (let i = 0; i <= text.length; i += 1) {
+      if (i > 0 && text[i - 1] === '/') break;
+      if (globMatch(pattern.slice(1), text.slice(i))) return true;
+    }
+    return false;
+  }
+  if (pattern[0] === '?') {
+    return text.length > 0 && text[0] !== '/' && globMatch(pattern.slice(1), text.slice(1));
+  }
+  return text[0] === pattern[0] && globMatch(pattern.slice(1), text.slice(1));
+}
+
+// A pattern matches a file when it matches the file itself or any of its parent directories.
+function patternMatches(pattern, filePath) {
+  const segments = filePath.split('/').filter(Boolean);
+  for (let end = 1; end <= segments.length; end += 1) {
+    if (pattern.dirOnly && end === segments.length) continue;
+    const candidate = pattern.anchored ? segments.slice(0, end).join('/') : segments[end - 1];
+    if (globMatch(pattern.glob, candidate)) return true;
+  }
+  return false;
+}
+
+// Last matching pattern wins, like .gitignore. null when nothing matches.
+function lastMatch(patterns, filePath) {
+  for (let i = patterns.length - 1; i >= 0; i -= 1) {
+    if (patternMatches(patterns[i], filePath)) return !patterns[i].negated;
+  }
+  return null;
+}
+
+function compile(patterns) {
+  return patterns.map(parsePattern).filter(Boolean);
+}
+
+/**
+ * Which changed paths should refresh the context pack. Loaded from
+ * `<pack>/relevance.json` ({"include": [...], "exclude": [...]}); a list that
+ * is absent keeps its built-in default. An optional `sections` object maps a
+ * pattern to the pack files that describe those paths.
+ */
+function loadRelevanceRules(packRoot) {
+  const rulesPath = path.join(packRoot, 'relevance.json');
+  let parsed = {};
+  if (fs.existsSync(rulesPath)) {
+    try {
+      parsed = JSON.parse(fs.readFileSync(rulesPath, 'utf8'));
+    } catch (error) {
+      throw new Error(`Failed to parse ${rulesPath}: ${error.message}`);
+    }
+  }
+
+  const list = (key, defaults) => {
+    const value = parsed[key];
+    if (value == null) return compile(defaults);
+    if (!Array.isArray(value) || value.some(item => typeof item !== 'string')) {
+      throw new Error(`Invalid ${rulesPath}: \`${key}\` must be an array of strings`);
+    }
+    return compile(value);
+  };
+
+  const include = list('include', DEFAULT_INCLUDE);
+  const exclude = list('exclude', DEFAULT_EXCLUDE);
+
+  const rawSections = parsed.sections;
+  if (rawSections != null && (typeof rawSections !== 'object' || Array.isArray(rawSections))) {
+    throw new Error(`Invalid ${rulesPath}: \`sections\` must be an object`);
+  }
+  // Sorted keys keep the merge order identical to the Rust CLI.
+  const sections = Object.keys(rawSections || {})
+    .sort()
+    .map((raw) => {
+      const value = rawSections[raw];
+      const files = typeof value === 'string' ? [value] : value;
+      if (!Array.isArray(files) || files.some(item => typeof item !== 'string')) {
+        throw new Error(`Invalid ${rulesPath}: sections for \`${raw}\` must be strings`);
+      }
+      return { pattern: parsePattern(raw), files };
+    })
+    .filter(entry => entry.pattern);
+
+  return {
+    hasSections() {
+      return sections.length > 0;
+    },
+    // Pack files mapped to filePath by `sections`, in pattern order.
+    sectionsFor(filePath) {
+      const normalized = filePath.replace(/\\/g, '/');
+      const files = [];
+      for (const { pattern, files: targets } of sections) {
+        if (pattern.negated || !patternMatches(pattern, normalized)) continue;
+        for (const target of targets) {
+          if (!files.includes(target)) files.push(target);
+        }
+      }
+      return files;
+    },
+    isRelevant(filePath) {
+      const normalized = filePath.replace(/\\/g, '/');
+      return lastMatch(exclude, normalized) !== true && lastMatch(include, normalized) === true;
+    },
+  };
+}
+
+function resolvePackRoot(repoRoot) {
+  return path.resolve(repoRoot, process.env.BRIDGE_CONTEXT_PACK_DIR || '.agent-context');
+}
+
+module.exports = {
+  loadRelevanceRules,
+  resolvePackRoot,
+};
@@ -0,0 +1,194 @@
+#!/usr/bin/env node
+'use strict';
+
+// Flatten the current pack into one prompt-sized blob.
+
+const fs = require('fs');
+const path = require('path');
+const { execFileSync } = require('child_process');
+const { READ_ORDER } = require('./read_order.cjs');
+
+function parseArgs(argv) {
+  const out = {
+    packDir: process.env.BRIDGE_CONTEXT_PACK_DIR || '.agent-context',
+    format: 'markdown',
+    maxTokens: null,
+    output: null,
+  };
+
+  for (let i = 2; i < argv.length; i += 1) {
+    const token = argv[i];
+    const [name, inlineValue] = token.startsWith('--') ? token.split('=', 2) : [token, null];
+    const next = inlineValue != null ? inlineValue : argv[i + 1];
+
+    switch (name) {
+      case '--pack-dir':
+        out.packDir = next || out.packDir;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--format':
+        if (next !== 'markdown' && next !== 'text') {
+          throw new Error(`Invalid --format: ${next} (expected markdown or text)`);
+        }
+        out.format = next;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--max-tokens': {
+        const value = Number(next);
+        if (!Number.isInteger(value) || value < 1) {
+          throw new Error(`Invalid --max-tokens: ${next} (expected a positive integer)`);
+        }
+        out.maxTokens = value;
+        if (inlineValue == null) i += 1;
+        break;
+      }
+      case '--output':
+        out.output = next || null;
+        if (inlineValue == null) i += 1;
+        break;
+      default:
+        break;
+    }
+  }
+
+  return out;
+}
+
+function runGit(args, cwd, allowFailure = false) {
+  try {
+    return execFileSync('git', args, { cwd, encoding: 'utf8', stdio: ['ignore', 'pipe', 'pipe'] }).trim();
+  } catch (error) {
+    if (allowFailure) return '';
+    throw error;
+  }
+}
+
+// Rough token count (4 characters per token), the same heuristic agents use for prompt budgeting.
+function estimateTokens(text) {
+  return Math.ceil(Array.from(text).length / 4);
+}
+
+// Remove `<!-- ... -->` comments and the blank runs they leave behind.
+function stripComments(raw) {
+  let out = '';
+  let rest = raw;
+  let start = rest.indexOf('<!--');
+  while (start !== -1) {
+    out += rest.slice(0, start);
+    const end = rest.indexOf('-->', start);
+    rest = end === -1 ? '' : rest.slice(end + 3);
+    start = rest.indexOf('<!--');
+  }
+  out += rest;
+
+  const lines = out.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  let collapsed = '';
+  let blankRun = 0;
+  for (const line of lines) {
+    if (line.trim() === '') {
+      blankRun += 1;
+      if (blankRun > 1) continue;
+    } else {
+      blankRun = 0;
+    }
+    collapsed += `${line.trimEnd()}\n`;
+  }
+  return collapsed;
+}
+
+// Drop markdown syntax that costs tokens without adding meaning in a plain prompt:
+// heading markers, code fences, bold markers, and inline backticks.
+function toPlainText(markdown) {
+  return markdown
+    .split('\n')
+    .filter((line) => !line.trimStart().startsWith('```'))
+    .map((line) => line.replace(/^#+/, '').replace(/^ +/, '').replace(/\*\*/g, '').replace(/`/g, ''))
+    .join('\n');
+}
+
+// Split one file at its `## ` headings (outside code fences); the title and preamble form the first section.
+function splitSections(text) {
+  const sections = [''];
+  let inFence = false;
+  const lines = text.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  for (const line of lines) {
+    if (line.trimStart().startsWith('```')) inFence = !inFence;
+    if (!inFence && line.startsWith('## ') && sections[sections.length - 1].trim() !== '') {
+      sections.push('');
+    }
+    sections[sections.length - 1] += `${line}\n`;
+  }
+  return sections.map((section) => section.trim()).filter(Boolean);
+}
+
+function joinSections(sections) {
+  let out = '';
+  sections.forEach(([file, section], position) => {
+    if (position > 0) {
+      out += sections[position - 1][0] === file ? '\n\n' : '\n\n---\n\n';
+    }
+    out += section;
+  });
+  return out ? `${out}\n` : out;
+}
+
+function main() {
+  const args = parseArgs(process.argv);
+  const repoRoot = runGit(['rev-parse', '--show-toplevel'], process.cwd(), true) || process.cwd();
+  const currentDir = path.join(path.resolve(repoRoot, args.packDir), 'current');
+  if (!fs.existsSync(currentDir)) {
+    throw new Error(`Context pack not found: ${currentDir} (run: bridge context-pack build)`);
+  }
+
+  const ordered = READ_ORDER.map((entry) => entry.file);
+  const extra = fs
+    .readdirSync(currentDir)
+    .filter((name) => name.endsWith('.md') && !ordered.includes(name))
+    .sort();
+  // Files outside the read order follow it in name order.
+  const files = ordered.filter((name) => fs.existsSync(path.join(currentDir, name))).concat(extra);
+
+  // [file index, section text] in priority order; trimming drops from the end.
+  const sections = [];
+  files.forEach((name, index) => {
+    const raw = fs.readFileSync(path.join(currentDir, name), 'utf8');
+    for (const section of splitSections(stripComments(raw))) {
+      sections.push([index, args.format === 'text' ? toPlainText(section).trim() : section]);
+    }
+  });
+
+  let rendered = joinSections(sections);
+  if (args.maxTokens != null) {
+    let dropped = 0;
+    while (sections.length > 1 && estimateTokens(rendered) > args.maxTokens) {
+      sections.pop();
+      dropped += 1;
+      rendered = joinSections(sections);
+    }
+    if (dropped > 0) {
+      process.stderr.write(`[context-pack] trimmed ${dropped} lowest-priority section(s) to fit ${args.maxTokens} tokens\n`);
+    }
+    if (estimateTokens(rendered) > args.maxTokens) {
+      process.stderr.write(
+        `[context-pack] warning: first section alone is ~${estimateTokens(rendered)} tokens, over the ${args.maxTokens} ceiling\n`
+      );
+    }
+  }
+
+  if (args.output) {
+    fs.mkdirSync(path.dirname(path.resolve(args.output)), { recursive: true });
+    fs.writeFileSync(args.output, rendered, 'utf8');
+    process.stdout.write(`[context-pack] rendered ~${estimateTokens(rendered)} tokens to ${args.output}\n`);
+  } else {
+    process.stdout.write(rendered);
+  }
+}
+
+try {
+  main();
+} catch (error) {
+  process.stderr.write(`${error.message}\n`);
+  process.exitCode = 1;
+}
@@ -4,11 +4,17 @@
 const fs = require('fs');
 const path = require('path');
 const { execFileSync } = require('child_process');
+const snapshot = require('./snapshot.cjs');
+const { describeChanges, sameFiles } = require('./preview.cjs');
+
+// Prefix of the snapshot a rollback saves of `current/` before replacing it.
+const PRE_ROLLBACK_PREFIX = 'pre-rollback-';
 
 function parseArgs(argv) {
   const out = {
     snapshot: null,
     packDir: process.env.BRIDGE_CONTEXT_PACK_DIR || '.agent-context',
+    dryRun: false,
   };
 
   for (let i = 2; i < argv.length; i += 1) {
@@ -25,6 +31,9 @@ function parseArgs(argv) {
         out.packDir = next || out.packDir;
         if (inlineValue == null) i += 1;
         break;
+      case '--dry-run':
+        out.dryRun = true;
+        break;
       default:
         break;
     }
@@ -42,15 +51,6 @@ function runGit(args, cwd, allowFailure = false) {
   }
 }
 
-function listSnapshotIds(snapshotsDir) {
-  if (!fs.existsSync(snapshotsDir)) return [];
-  return fs
-    .readdirSync(snapshotsDir, { withFileTypes: true })
-    .filter((entry) => entry.isDirectory())
-    .map((entry) => entry.name)
-    .sort();
-}
-
 function main() {
   const args = parseArgs(process.argv);
   const repoRoot = runGit(['rev-parse', '--show-toplevel'], process.cwd(), true) || process.cwd();
@@ -58,22 +58,48 @@ function main() {
   const currentDir = path.join(packRoot, 'current');
   const snapshotsDir = path.join(packRoot, 'snapshots');
 
-  const snapshotIds = listSnapshotIds(snapshotsDir);
+  const snapshotIds = snapshot.listIds(snapshotsDir);
   if (snapshotIds.length === 0) {
     process.stderr.write(`[context-pack] no snapshots found in ${path.relative(repoRoot, snapshotsDir)}\n`);
     process.exit(1);
   }
 
-  const targetSnapshot = args.snapshot || snapshotIds[snapshotIds.length - 1];
+  // Safety backups are only restored by name, so the default stays the latest build.
+  const builds = snapshotIds.filter((id) => !id.startsWith(PRE_ROLLBACK_PREFIX));
+  const candidates = builds.length > 0 ? builds : snapshotIds;
+  const targetSnapshot = args.snapshot || candidates[candidates.length - 1];
   if (!snapshotIds.includes(targetSnapshot)) {
     process.stderr.write(`[context-pack] snapshot not found: ${targetSnapshot}\n`);
     process.exit(1);
   }
 
-  const sourceDir = path.join(snapshotsDir, targetSnapshot);
-  fs.rmSync(currentDir, { recursive: true, force: true });
-  fs.mkdirSync(currentDir, { recursive: true });
-  fs.cpSync(sourceDir, currentDir, { recursive: true });
+  const files = snapshot.load(snapshotsDir, targetSnapshot);
+  const currentFiles = fs.existsSync(currentDir) ? snapshot.readDirFiles(currentDir) : new Map();
+  const currentLabel = path.relative(repoRoot, currentDir);
+
+  if (sameFiles(currentFiles, files)) {
+    process.stdout.write(`[context-pack] ${currentLabel} already matches snapshot ${targetSnapshot}\n`);
+    return;
+  }
+
+  if (args.dryRun) {
+    process.stdout.write(`[context-pack] rollback preview: ${targetSnapshot} -> ${currentLabel} (dry run, nothing written)\n`);
+    const changes = describeChanges(currentFiles, files);
+    for (const line of changes) process.stdout.write(`${line}\n`);
+    process.stdout.write(`[context-pack] ${changes.filter((line) => !line.startsWith(' ')).length} file(s) would change\n`);
+    return;
+  }
+
+  if (currentFiles.size > 0) {
+    const stamp = `${PRE_ROLLBACK_PREFIX}${new Date().toISOString().replace(/[-:]/g, '').replace(/\.\d+Z$/, 'Z')}`;
+    let backupId = stamp;
+    for (let attempt = 2; snapshotIds.includes(backupId); attempt += 1) backupId = `${stamp}-${attempt}`;
+    snapshot.writeSnapshot(currentDir, snapshotsDir, backupId);
+    process.stdout.write(
+      `[context-pack] saved ${currentLabel} as snapshot ${backupId} (undo: bridge context-pack rollback --snapshot ${backupId})\n`
+    );
+  }
+  snapshot.restore(files, currentDir);
 
   process.stdout.write(
     `[context-pack] restored snapshot ${targetSnapshot} -> ${path.relative(repoRoot, currentDir)}\n`
@@ -0,0 +1,170 @@
+'use strict';
+
+// Snapshot storage under `<pack>/snapshots/`. New snapshots are written as
+// `<id>.json` manifests that reference gzip-compressed, content-addressed
+// blobs in `objects/`, so files unchanged between builds are stored once.
+// `<id>.tar.gz` archives and plain `<id>/` directories from older builds
+// stay readable.
+
+const fs = require('fs');
+const path = require('path');
+const crypto = require('crypto');
+const zlib = require('zlib');
+
+const ARCHIVE_SUFFIX = '.tar.gz';
+const MANIFEST_SUFFIX = '.json';
+const OBJECTS_DIR = 'objects';
+const BLOCK = 512;
+
+function sha256(input) {
+  return crypto.createHash('sha256').update(input).digest('hex');
+}
+
+// `objects/<first 2 hex>/<remaining hex>.gz`, git-style fan-out.
+function objectPath(snapshotsDir, sha) {
+  return path.join(snapshotsDir, OBJECTS_DIR, sha.slice(0, 2), `${sha.slice(2)}.gz`);
+}
+
+// Files under dir as a Map of `/`-separated relative path -> Buffer.
+function readDirFiles(dir, prefix = '', files = new Map()) {
+  for (const entry of fs.readdirSync(dir, { withFileTypes: true }).sort((a, b) => (a.name < b.name ? -1 : 1))) {
+    const name = `${prefix}${entry.name}`;
+    const entryPath = path.join(dir, entry.name);
+    if (entry.isDirectory()) {
+      readDirFiles(entryPath, `${name}/`, files);
+    } else {
+      files.set(name, fs.readFileSync(entryPath));
+    }
+  }
+  return files;
+}
+
+// Store each file of sourceDir as an object (skipping objects that already
+// exist) and write the `<id>.json` manifest referencing them.
+function writeSnapshot(sourceDir, snapshotsDir, id) {
+  const files = [];
+  for (const [name, content] of readDirFiles(sourceDir)) {
+    const sha = sha256(content);
+    const filePath = objectPath(snapshotsDir, sha);
+    if (!fs.existsSync(filePath)) {
+      fs.mkdirSync(path.dirname(filePath), { recursive: true });
+      fs.writeFileSync(filePath, zlib.gzipSync(content));
+    }
+    files.push({ path: name, sha256: sha });
+  }
+
+  fs.mkdirSync(snapshotsDir, { recursive: true });
+  fs.writeFileSync(
+    path.join(snapshotsDir, `${id}${MANIFEST_SUFFIX}`),
+    `${JSON.stringify({ snapshot_id: id, files }, null, 2)}\n`,
+    'utf8'
+  );
+}
+
+// Snapshot IDs in both storage formats, sorted oldest first (IDs start with a compact timestamp).
+function listIds(snapshotsDir) {
+  if (!fs.existsSync(snapshotsDir)) return [];
+  const ids = new Set();
+  for (const entry of fs.readdirSync(snapshotsDir, { withFileTypes: true })) {
+    if (entry.isDirectory()) {
+      if (entry.name !== OBJECTS_DIR) ids.add(entry.name);
+    } else if (entry.name.endsWith(ARCHIVE_SUFFIX)) {
+      ids.add(entry.name.slice(0, -ARCHIVE_SUFFIX.length));
+    } else if (entry.name.endsWith(MANIFEST_SUFFIX)) {
+      ids.add(entry.name.slice(0, -MANIFEST_SUFFIX.length));
+    }
+  }
+  return Array.from(ids).sort();
+}
+
+function readTarString(buffer, offset, length) {
+  const raw = buffer.subarray(offset, offset + length);
+  const end = raw.indexOf(0);
+  return raw.subarray(0, end === -1 ? raw.length : end).toString('utf8');
+}
+
+function isUnsafePath(name) {
+  return name.startsWith('/') || name.split('/').includes('..');
+}
+
+function loadObjects(snapshotsDir, manifestPath) {
+  const manifest = JSON.parse(fs.readFileSync(manifestPath, 'utf8'));
+  const files = new Map();
+  for (const entry of manifest.files || []) {
+    const name = entry && entry.path;
+    const sha = entry && entry.sha256;
+    if (typeof name !== 'string' || typeof sha !== 'string') {
+      throw new Error(`Invalid file entry in ${manifestPath}`);
+    }
+    if (!/^[0-9a-fA-F]{64}$/.test(sha)) {
+      throw new Error(`Invalid sha256 for ${name} in ${manifestPath}`);
+    }
+    if (isUnsafePath(name)) {
+      throw new Error(`Unsafe path in ${manifestPath}: ${name}`);
+    }
+    const filePath = objectPath(snapshotsDir, sha);
+    if (!fs.existsSync(filePath)) {
+      throw new Error(`Missing snapshot object ${filePath} for ${name}`);
+    }
+    const content = zlib.gunzipSync(fs.readFileSync(filePath));
+    if (sha256(content) !== sha) {
+      throw new Error(`Corrupt snapshot object ${filePath} for ${name}`);
+    }
+    files.set(name, content);
+  }
+  return files;
+}
+
+function load(snapshotsDir, id) {
+  const manifestPath = path.join(snapshotsDir, `${id}${MANIFEST_SUFFIX}`);
+  if (fs.existsSync(manifestPath)) {
+    return loadObjects(snapshotsDir, manifestPath);
+  }
+  const dir = path.join(snapshotsDir, id);
+  if (fs.existsSync(dir) && fs.statSync(dir).isDirectory()) {
+    return readDirFiles(dir);
+  }
+
+  const archivePath = path.join(snapshotsDir, `${id}${ARCHIVE_SUFFIX}`);
+  const tar = zlib.gunzipSync(fs.readFileSync(archivePath));
+  const files = new Map();
+  let offset = 0;
+  while (offset + BLOCK <= tar.length) {
+    const header = tar.subarray(offset, offset + BLOCK);
+    if (header.every((byte) => byte === 0)) break;
+    let name = readTarString(header, 0, 100);
+    const prefix = readTarString(header, 345, 155);
+    if (prefix && header.subarray(257, 262).toString('ascii') === 'ustar') name = `${prefix}/${name}`;
+    const size = parseInt(readTarString(header, 124, 12).trim() || '0', 8);
+    const type = String.fromCharCode(header[156] || 48);
+    offset += BLOCK;
+    if (type === '0' || type === '\0') {
+      name = name.replace(/\\/g, '/').replace(/^\.\//, '');
+      if (isUnsafePath(name)) {
+        throw new Error(`Unsafe path in ${archivePath}: ${name}`);
+      }
+      files.set(name, Buffer.from(tar.subarray(offset, offset + size)));
+    }
+    offset += Math.ceil(size / BLOCK) * BLOCK;
+  }
+  return files;
+}
+
+// Replace currentDir with the files of a loaded snapshot.
+function restore(files, currentDir) {
+  fs.rmSync(currentDir, { recursive: true, force: true });
+  fs.mkdirSync(currentDir, { recursive: true });
+  for (const [name, content] of files) {
+    const filePath = path.join(currentDir, name);
+    fs.mkdirSync(path.dirname(filePath), { recursive: true });
+    fs.writeFileSync(filePath, content);
+  }
+}
+
+module.exports = {
+  listIds,
+  load,
+  readDirFiles,
+  restore,
+  writeSnapshot,
+};
@@ -0,0 +1,132 @@
+#!/usr/bin/env node
+'use strict';
+
+// Keep a managed block pointing at the pack in each agent instruction file.
+
+const fs = require('fs');
+const path = require('path');
+const { execFileSync } = require('child_process');
+const { READ_ORDER } = require('./read_order.cjs');
+
+const INSTRUCTION_FILES = ['CLAUDE.md', 'AGENTS.md', 'GEMINI.md'];
+const START_MARKER = '<!-- agent-bridge:context-pack:start -->';
+const END_MARKER = '<!-- agent-bridge:context-pack:end -->';
+
+function parseArgs(argv) {
+  const out = {
+    cwd: process.cwd(),
+    packDir: process.env.BRIDGE_CONTEXT_PACK_DIR || '.agent-context',
+    dryRun: false,
+  };
+
+  for (let i = 2; i < argv.length; i += 1) {
+    const token = argv[i];
+    const [name, inlineValue] = token.startsWith('--') ? token.split('=', 2) : [token, null];
+    const next = inlineValue != null ? inlineValue : argv[i + 1];
+
+    switch (name) {
+      case '--cwd':
+        if (next) out.cwd = next;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--pack-dir':
+        out.packDir = next || out.packDir;
+        if (inlineValue == null) i += 1;
+        break;
+      case '--dry-run':
+        out.dryRun = true;
+        break;
+      default:
+        break;
+    }
+  }
+
+  return out;
+}
+
+function runGit(args, cwd, allowFailure = false) {
+  try {
+    return execFileSync('git', args, { cwd, encoding: 'utf8', stdio: ['ignore', 'pipe', 'pipe'] }).trim();
+  } catch (error) {
+    if (allowFailure) return '';
+    throw error;
+  }
+}
+
+function managedBlock(currentDir) {
+  return [
+    START_MARKER,
+    '## Context Pack Read Order',
+    '',
+    `Before exploring this repository, read the context pack in \`${currentDir}\` in this order:`,
+    ...READ_ORDER.map((entry, index) => `${index + 1}. \`${entry.file}\`: ${entry.purpose}.`),
+    '',
+    'Open source files only after the pack identifies a precise target.',
+    'If the pack is missing or stale, run `bridge context-pack build`.',
+    END_MARKER,
+  ].join('\n');
+}
+
+// `existing` with the block between startMarker and endMarker replaced, or `block`
+// appended when the markers are absent.
+function upsertBlock(existing, block, startMarker = START_MARKER, endMarker = END_MARKER) {
+  const start = existing.indexOf(startMarker);
+  const end = existing.indexOf(endMarker);
+  if (start !== -1 && end > start) {
+    return `${existing.slice(0, start)}${block}${existing.slice(end + endMarker.length)}`;
+  }
+  const trimmed = existing.trimEnd();
+  return trimmed ? `${trimmed}\n\n${block}\n` : `${block}\n`;
+}
+
+function blockFor(repoRoot, packRoot) {
+  const current = path.relative(repoRoot, path.join(packRoot, 'current')).split(path.sep).join('/');
+  return managedBlock(`${current}/`);
+}
+
+// Insert or refresh the managed block in CLAUDE.md, AGENTS.md, and GEMINI.md at the
+// repo root, creating files that do not exist yet.
+function syncAgents(repoRoot, packRoot, dryRun) {
+  const block = blockFor(repoRoot, packRoot);
+  for (const name of INSTRUCTION_FILES) {
+    const filePath = path.join(repoRoot, name);
+    const existing = fs.existsSync(filePath) ? fs.readFileSync(filePath, 'utf8') : null;
+    const next = upsertBlock(existing || '', block);
+    let status;
+    if (existing === next) status = 'unchanged';
+    else if (dryRun) status = 'planned';
+    else status = existing == null ? 'created' : 'updated';
+    if (status !== 'unchanged' && !dryRun) {
+      fs.writeFileSync(filePath, next, 'utf8');
+    }
+    process.stdout.write(`[context-pack] ${status}: ${name}\n`);
+  }
+}
+
+// Refresh blocks already present after a build, so instruction files that opted in
+// through sync-agents never drift from the pack. Files without the block are left alone.
+function refreshExisting(repoRoot, packRoot) {
+  const block = blockFor(repoRoot, packRoot);
+  for (const name of INSTRUCTION_FILES) {
+    const filePath = path.join(repoRoot, name);
+    if (!fs.existsSync(filePath)) continue;
+    const existing = fs.readFileSync(filePath, 'utf8');
+    if (!existing.includes(START_MARKER)) continue;
+    const next = upsertBlock(existing, block);
+    if (next !== existing) {
+      fs.writeFileSync(filePath, next, 'utf8');
+      process.stdout.write(`[context-pack] updated: ${name}\n`);
+    }
+  }
+}
+
+if (require.main === module) {
+  const args = parseArgs(process.argv);
+  const repoRoot = runGit(['rev-parse', '--show-toplevel'], args.cwd, true) || path.resolve(args.cwd);
+  syncAgents(repoRoot, path.resolve(repoRoot, args.packDir), args.dryRun);
+}
+
+module.exports = {
+  refreshExisting,
+  upsertBlock,
+};
@@ -3,6 +3,7 @@
 
 const path = require('path');
 const { execFileSync } = require('child_process');
+const { loadRelevanceRules, resolvePackRoot } = require('./relevance.cjs');
 
 const ZERO_SHA_RE = /^0{40}$/;
 
@@ -73,45 +74,44 @@ function getChangedFiles(repoRoot, baseSha, headSha) {
     .filter(Boolean);
 }
 
-function isContextRelevant(filePath) {
-  const normalized = filePath.replace(/\\/g, '/');
-
-  if (
-    normalized.startsWith('blog/') ||
-    normalized.startsWith('notes/') ||
-    normalized.startsWith('drafts/') ||
-    normalized.startsWith('scratch/') ||
-    normalized.startsWith('tmp/') ||
-    normalized.startsWith('.agent-context/') ||
-    normalized.startsWith('docs/demo-')
-  ) {
+function gitSucceeds(args, cwd) {
+  try {
+    execFileSync('git', args, { cwd, stdio: ['ignore', 'ignore', 'ignore'] });
+    return true;
+  } catch (error) {
     return false;
   }
+}
 
-  if (
-    normalized === 'README.md' ||
-    normalized === 'PROTOCOL.md' ||
-    normalized === 'CONTRIBUTING.md' ||
-    normalized === 'SKILL.md' ||
-    normalized === 'AGENTS.md' ||
-    normalized === 'package.json' ||
-    normalized === 'package-lock.json' ||
-    normalized === 'cli/Cargo.toml' ||
-    normalized === 'cli/Cargo.lock' ||
-    normalized === 'docs/architecture.svg' ||
-    normalized === 'docs/silo-tax-before-after.webp'
-  ) {
-    return true;
+// The diff base and changed files for a push. A fast-forward diffs from the remote SHA.
+// A force push or rebase (remote not an ancestor of local) diffs from the merge-base
+// instead, and when no merge-base is known locally every file tracked at the local SHA
+// is scanned.
+function resolvePushRange(repoRoot, remoteSha, localSha) {
+  if (!remoteSha || ZERO_SHA_RE.test(remoteSha)) {
+    return { base: null, changedFiles: getChangedFiles(repoRoot, null, localSha) };
   }
 
-  return (
-    normalized.startsWith('scripts/') ||
-    normalized.startsWith('cli/src/') ||
-    normalized.startsWith('schemas/') ||
-    normalized.startsWith('fixtures/golden/') ||
-    normalized.startsWith('fixtures/session-store/') ||
-    normalized.startsWith('.github/workflows/')
+  if (gitSucceeds(['merge-base', '--is-ancestor', remoteSha, localSha], repoRoot)) {
+    return { base: remoteSha, changedFiles: getChangedFiles(repoRoot, remoteSha, localSha) };
+  }
+
+  const mergeBase = runGit(['merge-base', remoteSha, localSha], repoRoot, true);
+  if (mergeBase) {
+    process.stdout.write(
+      `[context-pack] non-fast-forward push (force push or rebase): diffing from merge-base ${shortSha(mergeBase)}\n`
+    );
+    return { base: mergeBase, changedFiles: getChangedFiles(repoRoot, mergeBase, localSha) };
+  }
+
+  process.stdout.write(
+    `[context-pack] non-fast-forward push with no merge-base for remote ${shortSha(remoteSha)}: scanning all tracked files\n`
   );
+  const tracked = runGit(['ls-tree', '-r', '--name-only', localSha], repoRoot, true);
+  return {
+    base: null,
+    changedFiles: tracked.split('\n').map((line) => line.trim()).filter(Boolean),
+  };
 }
 
 function shortSha(sha) {
@@ -133,8 +133,9 @@ function main() {
     return;
   }
 
-  const changedFiles = getChangedFiles(repoRoot, args.remoteSha, args.localSha);
-  const relevant = changedFiles.filter(isContextRelevant);
+  const { base, changedFiles } = resolvePushRange(repoRoot, args.remoteSha, args.localSha);
+  const rules = loadRelevanceRules(resolvePackRoot(repoRoot));
+  const relevant = changedFiles.filter(filePath => rules.isRelevant(filePath));
 
   if (relevant.length === 0) {
     process.stdout.write('[context-pack] skipped (no context-relevant file changes)\n');
@@ -146,11 +147,10 @@ function main() {
     buildScript,
     '--reason',
     `main-push:${shortSha(args.remoteSha)}..${shortSha(args.localSha)}`,
-    '--base',
-    args.remoteSha || '',
     '--head',
     args.localSha,
   ];
+  if (base) buildArgs.push('--base', base);
 
   for (const filePath of changedFiles) {
     buildArgs.push('--changed-file', filePath);
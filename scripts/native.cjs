@@ -0,0 +1,33 @@
+'use strict';
+
+// Optional Rust core loaded as a Node-API addon (`npm run build:native`).
+// When present, read/compare/report delegate to it so both CLIs share one
+// parser and redaction engine. Set BRIDGE_NATIVE=0 to force the JavaScript
+// path, or BRIDGE_NATIVE_ADDON to load an addon from another location.
+
+const fs = require('fs');
+const path = require('path');
+
+const DEFAULT_ADDON = path.join(__dirname, '..', 'native', 'agent_bridge.node');
+
+let cached;
+
+function loadNative() {
+  if (cached !== undefined) return cached;
+  cached = null;
+  if (process.env.BRIDGE_NATIVE === '0') return cached;
+
+  const addonPath = process.env.BRIDGE_NATIVE_ADDON || DEFAULT_ADDON;
+  if (!fs.existsSync(addonPath)) {
+    if (process.env.BRIDGE_NATIVE_ADDON) {
+      throw new Error(`BRIDGE_NATIVE_ADDON not found: ${addonPath}`);
+    }
+    return cached;
+  }
+  cached = require(addonPath);
+  return cached;
+}
+
+module.exports = {
+  loadNative,
+};
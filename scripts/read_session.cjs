@@ -4,8 +4,9 @@ const fs = require('fs');
 const path = require('path');
 const os = require('os');
 const crypto = require('crypto');
-const { execFileSync } = require('child_process');
-const { getAdapter } = require('./adapters/registry.cjs');
+const { execFileSync, spawnSync } = require('child_process');
+const { getAdapter, listAdapters, normalizeAgentName } = require('./adapters/registry.cjs');
+const { loadNative } = require('./native.cjs');
 
 const rawArgs = process.argv.slice(2);
 const commandNames = new Set(['read', 'compare', 'report', 'list', 'search', 'setup', 'doctor', 'trash-talk', 'context-pack']);
@@ -58,25 +59,30 @@ function printHelp(topic = null) {
     lines.push('');
     lines.push('read options:');
     lines.push('  --agent <codex|gemini|claude|cursor> (default: codex)');
-    lines.push('  --id <session-substring> (optional; omitted = latest session in scope)');
+    lines.push('  --id <session-substring> (optional; omitted = latest session in scope; repeat to read several)');
+    lines.push('  --all-matching (read every session --id matches instead of failing as ambiguous)');
+    lines.push('  --exact (--id must equal the session id)');
     lines.push('  --cwd <path>');
     lines.push('  --chats-dir <path> (gemini)');
+    lines.push('  --data-dir [<agent>=]<dir> (session store root)');
     lines.push('  --last <N>');
     lines.push('  --json');
   } else if (topic === 'list') {
     lines.push('');
     lines.push('list options:');
-    lines.push('  --agent <codex|gemini|claude|cursor>');
+    lines.push('  --agent <codex|gemini|claude|cursor|all>');
     lines.push('  --cwd <path>');
     lines.push('  --limit <N> (default: 10)');
+    lines.push('  --data-dir [<agent>=]<dir> (session store root)');
     lines.push('  --json');
   } else if (topic === 'search') {
     lines.push('');
     lines.push('search options:');
     lines.push('  <query> (positional, required)');
-    lines.push('  --agent <codex|gemini|claude|cursor> (required)');
+    lines.push('  --agent <codex|gemini|claude|cursor|all> (required)');
     lines.push('  --cwd <path>');
     lines.push('  --limit <N> (default: 10)');
+    lines.push('  --data-dir [<agent>=]<dir> (session store root)');
     lines.push('  --json');
   } else if (topic === 'compare') {
     lines.push('');
@@ -84,12 +90,22 @@ function printHelp(topic = null) {
     lines.push('  --source <agent[:session-substring]> (repeatable, required)');
     lines.push('  --cwd <path>');
     lines.push('  --normalize');
+    lines.push('  --semantic');
+    lines.push('  --last <N> (compare the last N assistant messages; default: 1)');
+    lines.push('  --full (compare every assistant message)');
+    lines.push('  --fail-on <P0|P1|P2|P3> (fail the verdict on any finding at this severity or worse)');
+    lines.push('  --min-score <N> (fail the verdict when the score is below N)');
+    lines.push('  --data-dir <agent>=<dir> (repeatable; session store root)');
     lines.push('  --json');
   } else if (topic === 'report') {
     lines.push('');
     lines.push('report options:');
     lines.push('  --handoff <path-to-handoff.json> (required)');
     lines.push('  --cwd <path>');
+    lines.push('  --allow-commands (run the handoff\'s command claims)');
+    lines.push('  --fail-on <P0|P1|P2|P3> (overrides the handoff\'s thresholds.fail_on)');
+    lines.push('  --min-score <N> (overrides the handoff\'s thresholds.min_score)');
+    lines.push('  --data-dir <agent>=<dir> (repeatable; session store root)');
     lines.push('  --json');
   } else if (topic === 'setup') {
     lines.push('');
@@ -110,8 +126,13 @@ function printHelp(topic = null) {
     lines.push('  context-pack build [--reason <text>] [--base <sha>] [--head <sha>] [--force-snapshot]');
     lines.push('  context-pack sync-main --local-ref <ref> --local-sha <sha> --remote-ref <ref> --remote-sha <sha>');
     lines.push('  context-pack install-hooks');
-    lines.push('  context-pack rollback [--snapshot <id>]');
+    lines.push('  context-pack rollback [--snapshot <id>] [--dry-run]');
     lines.push('  context-pack check-freshness [--base <git-ref>]');
+    lines.push('  context-pack sync-agents [--dry-run]');
+    lines.push('  context-pack agents-md [--output <file>] [--dry-run]');
+    lines.push('  context-pack render [--format markdown|text] [--max-tokens <n>] [--output <file>]');
+    lines.push('  context-pack lint [--pack-dir <dir>] [--json]');
+    lines.push('  context-pack history compact [--keep <n>]');
   }
 
   console.log(lines.join('\n'));
@@ -137,13 +158,27 @@ if (rawArgs.includes('--version') || rawArgs.includes('-v')) {
   process.exit(0);
 }
 
-const codexSessionsBase = normalizePath(process.env.BRIDGE_CODEX_SESSIONS_DIR || '~/.codex/sessions');
-const claudeProjectsBase = normalizePath(process.env.BRIDGE_CLAUDE_PROJECTS_DIR || '~/.claude/projects');
-const geminiTmpBase = normalizePath(process.env.BRIDGE_GEMINI_TMP_DIR || '~/.gemini/tmp');
+function codexSessionsBase() {
+  return normalizePath(process.env.BRIDGE_CODEX_SESSIONS_DIR || '~/.codex/sessions');
+}
+function claudeProjectsBase() {
+  return normalizePath(process.env.BRIDGE_CLAUDE_PROJECTS_DIR || '~/.claude/projects');
+}
+function geminiTmpBase() {
+  return normalizePath(process.env.BRIDGE_GEMINI_TMP_DIR || '~/.gemini/tmp');
+}
 const setupProviders = [
-  { agent: 'codex', targetFile: 'AGENTS.md' },
-  { agent: 'claude', targetFile: 'CLAUDE.md' },
-  { agent: 'gemini', targetFile: 'GEMINI.md' },
+  { agent: 'codex', targetFile: 'AGENTS.md', binaries: ['codex'], storeDir: () => codexSessionsBase() },
+  { agent: 'claude', targetFile: 'CLAUDE.md', binaries: ['claude'], storeDir: () => claudeProjectsBase() },
+  { agent: 'gemini', targetFile: 'GEMINI.md', binaries: ['gemini'], storeDir: () => geminiTmpBase() },
+  {
+    agent: 'cursor',
+    targetFile: path.join('.cursor', 'rules', 'agent-bridge.mdc'),
+    binaries: ['cursor-agent', 'cursor'],
+    storeDir: () => cursorDataBase(),
+    // Written before the managed block when the rules file is new.
+    preamble: '---\ndescription: Agent Bridge cross-agent coordination\nalwaysApply: true\n---\n\n',
+  },
 ];
 
 function expandHome(filepath) {
@@ -190,6 +225,44 @@ function hasFlag(inputArgs, name) {
   return inputArgs.includes(name);
 }
 
+// Store root each agent's --data-dir replaces.
+const DATA_DIR_ENV = {
+  codex: 'BRIDGE_CODEX_SESSIONS_DIR',
+  gemini: 'BRIDGE_GEMINI_TMP_DIR',
+  claude: 'BRIDGE_CLAUDE_PROJECTS_DIR',
+  cursor: 'BRIDGE_CURSOR_DATA_DIR',
+};
+
+// Apply `--data-dir [<agent>=]<dir>` by setting the agent's store-root env var,
+// which the adapters (and the native core) read on every lookup. A bare dir
+// applies to the command's --agent.
+function applyDataDirOverrides(commandName, inputArgs) {
+  for (const value of getOptionValues(inputArgs, '--data-dir')) {
+    const separator = value.indexOf('=');
+    let agent = null;
+    let dir = value;
+    if (separator > 0) {
+      try {
+        agent = normalizeAgentName(value.slice(0, separator));
+        dir = value.slice(separator + 1);
+      } catch (_error) {
+        agent = null;
+      }
+    }
+    if (!agent) {
+      const rawAgent = getOptionValue(inputArgs, '--agent', commandName === 'read' ? 'codex' : null);
+      if (!rawAgent || isAllAgents(rawAgent)) {
+        throw new Error(`--data-dir ${value} needs the form <agent>=<dir> for this command`);
+      }
+      agent = normalizeAgentName(rawAgent);
+    }
+    if (!dir) {
+      throw new Error(`--data-dir ${value} has no directory`);
+    }
+    process.env[DATA_DIR_ENV[agent]] = normalizePath(dir);
+  }
+}
+
 function runInternalNodeScript(scriptRelPath, scriptArgs, options = {}) {
   const scriptPath = path.join(__dirname, scriptRelPath);
   if (!fs.existsSync(scriptPath)) {
@@ -225,6 +298,11 @@ function runContextPackSubcommand(subcommand, subArgs, options = {}) {
     rollback: 'context_pack/rollback.cjs',
     'install-hooks': 'context_pack/install_hooks.cjs',
     'check-freshness': 'context_pack/check_freshness.cjs',
+    'sync-agents': 'context_pack/sync_agents.cjs',
+    'agents-md': 'context_pack/agents_md.cjs',
+    render: 'context_pack/render.cjs',
+    lint: 'context_pack/lint.cjs',
+    history: 'context_pack/history.cjs',
   };
 
   const scriptRelPath = scriptBySubcommand[subcommand];
@@ -242,7 +320,16 @@ function runContextPack(inputArgs) {
     printHelp('context-pack');
     return;
   }
-  runContextPackSubcommand(subcommand, inputArgs.slice(1), { inheritOutput: true });
+  try {
+    runContextPackSubcommand(subcommand, inputArgs.slice(1), { inheritOutput: true });
+  } catch (error) {
+    // The subcommand already printed its own output; keep its exit status (lint gates CI on it).
+    if (typeof error.status === 'number') {
+      process.exitCode = error.status;
+      return;
+    }
+    throw error;
+  }
 }
 
 function writeFileEnsured(filePath, content) {
@@ -288,7 +375,7 @@ function makeManagedBlock(provider, snippetRelPath) {
   ].join('\n');
 }
 
-function upsertManagedBlock(filePath, block, markerPrefix, force, dryRun) {
+function upsertManagedBlock(filePath, block, markerPrefix, force, dryRun, preamble = '') {
   const startMarker = `<!-- ${markerPrefix}:start -->`;
   const endMarker = `<!-- ${markerPrefix}:end -->`;
 
@@ -341,7 +428,7 @@ function upsertManagedBlock(filePath, block, markerPrefix, force, dryRun) {
     next = `${before}\n\n${block}\n${after ? `\n${after}` : ''}`.replace(/\n{3,}/g, '\n\n');
     status = 'updated';
   } else if (!existing.trim()) {
-    next = `${block}\n`;
+    next = `${preamble}${block}\n`;
     status = 'created';
   } else {
     const trimmed = existing.replace(/\s*$/, '');
@@ -379,15 +466,23 @@ function defaultSetupIntents() {
   ].join('\n');
 }
 
-const { MAX_FILE_SIZE, MAX_SCAN_FILES } = require('./adapters/utils.cjs');
+const {
+  maxFileSize,
+  maxScanFiles,
+  markScanTruncated,
+  wasScanTruncated,
+  scanTruncationWarning,
+} = require('./adapters/utils.cjs');
 
 function collectMatchingFiles(dirPath, predicate, recursive = false) {
   if (!dirPath || !fs.existsSync(dirPath)) return [];
 
+  const limit = maxScanFiles();
   const matches = [];
+  let stopped = false;
 
   function search(currentDir) {
-    if (matches.length >= MAX_SCAN_FILES) return;
+    if (stopped) return;
 
     let entries = [];
     try {
@@ -397,7 +492,7 @@ function collectMatchingFiles(dirPath, predicate, recursive = false) {
     }
 
     for (const entry of entries) {
-      if (matches.length >= MAX_SCAN_FILES) return;
+      if (stopped) return;
 
       const fullPath = path.join(currentDir, entry.name);
       if (entry.isDirectory()) {
@@ -408,6 +503,12 @@ function collectMatchingFiles(dirPath, predicate, recursive = false) {
 
       if (!predicate(fullPath, entry.name)) continue;
 
+      if (matches.length >= limit) {
+        stopped = true;
+        markScanTruncated();
+        return;
+      }
+
       try {
         let mtimeNs;
         try {
@@ -436,8 +537,8 @@ function collectMatchingFiles(dirPath, predicate, recursive = false) {
 
 function readJsonlLines(filePath) {
   const stat = fs.statSync(filePath);
-  if (stat.size > MAX_FILE_SIZE) {
-    throw new Error(`Skipped ${filePath} (exceeds ${MAX_FILE_SIZE / (1024 * 1024)}MB size limit)`);
+  if (stat.size > maxFileSize()) {
+    throw new Error(`Skipped ${filePath} (exceeds ${maxFileSize() / (1024 * 1024)}MB size limit)`);
   }
   return fs.readFileSync(filePath, 'utf-8').split('\n').filter(Boolean);
 }
@@ -487,11 +588,11 @@ function getClaudeSessionCwd(filePath) {
 }
 
 function listGeminiChatDirs() {
-  if (!fs.existsSync(geminiTmpBase)) return [];
+  if (!fs.existsSync(geminiTmpBase())) return [];
 
   let entries = [];
   try {
-    entries = fs.readdirSync(geminiTmpBase, { withFileTypes: true });
+    entries = fs.readdirSync(geminiTmpBase(), { withFileTypes: true });
   } catch (error) {
     return [];
   }
@@ -499,7 +600,7 @@ function listGeminiChatDirs() {
   const dirs = [];
   for (const entry of entries) {
     if (!entry.isDirectory()) continue;
-    const chatsDir = path.join(geminiTmpBase, entry.name, 'chats');
+    const chatsDir = path.join(geminiTmpBase(), entry.name, 'chats');
     if (fs.existsSync(chatsDir)) {
       dirs.push(chatsDir);
     }
@@ -537,7 +638,7 @@ function resolveGeminiChatDirs(chatsDir, cwd) {
   }
 
   const scopedHash = hashPath(cwd);
-  addDir(path.join(geminiTmpBase, scopedHash, 'chats'));
+  addDir(path.join(geminiTmpBase(), scopedHash, 'chats'));
 
   for (const dir of listGeminiChatDirs()) {
     addDir(dir);
@@ -547,18 +648,18 @@ function resolveGeminiChatDirs(chatsDir, cwd) {
 }
 
 function resolveCodexTargetFile(id, cwd, warnings) {
-  if (!fs.existsSync(codexSessionsBase)) return null;
+  if (!fs.existsSync(codexSessionsBase())) return null;
 
   if (id) {
     const files = collectMatchingFiles(
-      codexSessionsBase,
+      codexSessionsBase(),
       (fullPath, name) => name.endsWith('.jsonl') && fullPath.includes(id),
       true
     );
     return files.length > 0 ? files[0].path : null;
   }
 
-  const files = collectMatchingFiles(codexSessionsBase, (fullPath, name) => name.endsWith('.jsonl'), true);
+  const files = collectMatchingFiles(codexSessionsBase(), (fullPath, name) => name.endsWith('.jsonl'), true);
   if (files.length === 0) return null;
 
   const scoped = findLatestByCwd(files, getCodexSessionCwd, cwd);
@@ -569,18 +670,18 @@ function resolveCodexTargetFile(id, cwd, warnings) {
 }
 
 function resolveClaudeTargetFile(id, cwd, warnings) {
-  if (!fs.existsSync(claudeProjectsBase)) return null;
+  if (!fs.existsSync(claudeProjectsBase())) return null;
 
   if (id) {
     const files = collectMatchingFiles(
-      claudeProjectsBase,
+      claudeProjectsBase(),
       (fullPath, name) => name.endsWith('.jsonl') && fullPath.includes(id),
       true
     );
     return files.length > 0 ? files[0].path : null;
   }
 
-  const files = collectMatchingFiles(claudeProjectsBase, (fullPath, name) => name.endsWith('.jsonl'), true);
+  const files = collectMatchingFiles(claudeProjectsBase(), (fullPath, name) => name.endsWith('.jsonl'), true);
   if (files.length === 0) return null;
 
   const scoped = findLatestByCwd(files, getClaudeSessionCwd, cwd);
@@ -654,6 +755,7 @@ function redactSensitiveText(input) {
 function classifyError(message) {
   if (/unsupported agent/i.test(message) || /unknown agent/i.test(message)) return 'UNSUPPORTED_AGENT';
   if (/unsupported mode/i.test(message)) return 'UNSUPPORTED_MODE';
+  if (/is ambiguous/i.test(message)) return 'AMBIGUOUS_ID';
   if (/no .* session found/i.test(message)) return 'NOT_FOUND';
   if (/not found/i.test(message)) return 'NOT_FOUND';
   if (/failed to parse/i.test(message) || /failed to read/i.test(message)) return 'PARSE_FAILED';
@@ -662,6 +764,19 @@ function classifyError(message) {
   return 'IO_ERROR';
 }
 
+// Process exit status per error code; the Rust CLI uses the same table.
+// 2 is left to usage errors and report/compare verdicts.
+const EXIT_CODES = {
+  IO_ERROR: 1,
+  NOT_FOUND: 3,
+  PARSE_FAILED: 4,
+  INVALID_HANDOFF: 5,
+  UNSUPPORTED_AGENT: 6,
+  UNSUPPORTED_MODE: 7,
+  EMPTY_SESSION: 8,
+  AMBIGUOUS_ID: 9,
+};
+
 function getFileTimestamp(filePath) {
   try {
     const stat = fs.statSync(filePath);
@@ -848,8 +963,8 @@ function readGeminiSession(id, chatsDir, cwd, lastN) {
 
 function readClaudeSession(id, cwd, lastN) {
   lastN = lastN || 1;
-  if (!fs.existsSync(claudeProjectsBase)) {
-    throw new Error(`Claude projects directory not found: ${claudeProjectsBase}`);
+  if (!fs.existsSync(claudeProjectsBase())) {
+    throw new Error(`Claude projects directory not found: ${claudeProjectsBase()}`);
   }
 
   const warnings = [];
@@ -917,19 +1032,21 @@ function readClaudeSession(id, cwd, lastN) {
   };
 }
 
-const cursorDataBase = normalizePath(process.env.BRIDGE_CURSOR_DATA_DIR || (
-  process.platform === 'darwin'
-    ? '~/Library/Application Support/Cursor'
-    : '~/.cursor'
-));
+function cursorDataBase() {
+  return normalizePath(process.env.BRIDGE_CURSOR_DATA_DIR || (
+    process.platform === 'darwin'
+      ? '~/Library/Application Support/Cursor'
+      : '~/.cursor'
+  ));
+}
 
 function readCursorSession(id, cwd, lastN) {
   lastN = lastN || 1;
-  if (!fs.existsSync(cursorDataBase)) {
-    throw new Error(`No Cursor session found. Data directory not found: ${cursorDataBase}`);
+  if (!fs.existsSync(cursorDataBase())) {
+    throw new Error(`No Cursor session found. Data directory not found: ${cursorDataBase()}`);
   }
 
-  const workspacesDir = path.join(cursorDataBase, 'User', 'workspaceStorage');
+  const workspacesDir = path.join(cursorDataBase(), 'User', 'workspaceStorage');
   if (!fs.existsSync(workspacesDir)) {
     throw new Error(`No Cursor session found. Workspace storage not found: ${workspacesDir}`);
   }
@@ -950,12 +1067,14 @@ function readCursorSession(id, cwd, lastN) {
   const raw = fs.readFileSync(targetFile, 'utf-8');
   let content = '';
   let messageCount = 0;
+  let replies = [];
 
   try {
     const json = JSON.parse(raw);
     if (Array.isArray(json.messages)) {
       const assistantMsgs = json.messages.filter(m => m.role === 'assistant');
       messageCount = assistantMsgs.length;
+      replies = assistantMsgs.map(m => extractText(m.content) || '[No text content]');
       if (assistantMsgs.length > 0) {
         content = assistantMsgs[assistantMsgs.length - 1].content || '[No text content]';
       } else {
@@ -964,6 +1083,7 @@ function readCursorSession(id, cwd, lastN) {
     } else if (typeof json.content === 'string') {
       content = json.content;
       messageCount = 1;
+      replies = [json.content];
     } else {
       content = JSON.stringify(json, null, 2);
     }
@@ -980,9 +1100,14 @@ function readCursorSession(id, cwd, lastN) {
       } catch (e) { /* skip */ }
     }
     messageCount = msgs.length;
+    replies = msgs;
     content = msgs.length > 0 ? msgs[msgs.length - 1] : lines.slice(-20).join('\n');
   }
 
+  if (lastN > 1 && replies.length > 0) {
+    content = replies.slice(-lastN).join('\n---\n');
+  }
+
   const sessionId = path.basename(targetFile, path.extname(targetFile));
 
   return {
@@ -994,16 +1119,42 @@ function readCursorSession(id, cwd, lastN) {
     cwd: null,
     timestamp: getFileTimestamp(targetFile),
     message_count: messageCount,
-    messages_returned: 1,
+    messages_returned: lastN > 1 && replies.length > 0 ? Math.min(lastN, replies.length) : 1,
   };
 }
 
+// `--agent all`: run `query` on every adapter, merge newest first by
+// modified_at (ties keep registry order), and keep `limit` entries. An agent
+// whose store cannot be read is skipped with a warning.
+function fanOutSessions(limit, query) {
+  const entries = [];
+  for (const agent of listAdapters()) {
+    try {
+      entries.push(...query(agent));
+    } catch (error) {
+      process.stderr.write(`Warning: skipped ${agent}: ${error.message}\n`);
+    }
+  }
+  entries.sort((a, b) => String(b.modified_at || '').localeCompare(String(a.modified_at || '')));
+  return entries.slice(0, limit);
+}
+
+function isAllAgents(rawAgent) {
+  return String(rawAgent || '').trim().toLowerCase() === 'all';
+}
+
 function listSessions(agent, cwd, limit) {
+  if (agent === 'all') {
+    return fanOutSessions(limit || 10, (name) => listSessions(name, cwd, limit));
+  }
   const adapter = getAdapter(agent);
   return adapter.list(cwd || null, limit || 10);
 }
 
 function searchSessions(query, agent, cwd, limit) {
+  if (agent === 'all') {
+    return fanOutSessions(limit || 10, (name) => searchSessions(query, name, cwd, limit));
+  }
   const adapter = getAdapter(agent);
   if (typeof adapter.search !== 'function') {
     throw new Error(`Search is not implemented for agent: ${agent}`);
@@ -1011,9 +1162,31 @@ function searchSessions(query, agent, cwd, limit) {
   return adapter.search(query, cwd || null, limit || 10);
 }
 
-function readSessionViaAdapter(agent, { id, cwd, chatsDir, lastN }) {
+// With `allMatching`, an array of every session `id` matches, newest first,
+// instead of an AMBIGUOUS_ID error when there are several (even when one of
+// them equals the id). `everyCandidate` stops an exact id from winning.
+function readSessionViaAdapter(agent, { id, exact, allMatching, everyCandidate, cwd, chatsDir, lastN }) {
+  const native = loadNative();
+  if (native) {
+    const options = {
+      id: id || null, exact: Boolean(exact), all_matching: Boolean(allMatching), cwd, chats_dir: chatsDir || null, last: lastN || 1,
+    };
+    return JSON.parse(native.readSession(agent, JSON.stringify(options)));
+  }
+  if (allMatching) {
+    const options = { id, exact, cwd, chatsDir, lastN };
+    try {
+      return [readSessionViaAdapter(agent, { ...options, everyCandidate: true })];
+    } catch (error) {
+      if (error.code !== 'AMBIGUOUS_ID') throw error;
+      return error.candidates.map((candidate) => readSessionViaAdapter(agent, { ...options, id: candidate, exact: true }));
+    }
+  }
+
   const adapter = getAdapter(agent);
-  const resolved = adapter.resolve(id || null, cwd, { chatsDir: chatsDir || null });
+  const resolved = adapter.resolve(id || null, cwd, {
+    chatsDir: chatsDir || null, exact: Boolean(exact), allMatching: Boolean(everyCandidate),
+  });
 
   if (!resolved || !resolved.path) {
     if (agent === 'gemini' && chatsDir) {
@@ -1025,46 +1198,115 @@ function readSessionViaAdapter(agent, { id, cwd, chatsDir, lastN }) {
   const result = adapter.read(resolved.path, lastN || 1);
   const adapterWarnings = Array.isArray(resolved.warnings) ? resolved.warnings : [];
   result.warnings = [...adapterWarnings, ...(result.warnings || [])];
+  if (wasScanTruncated()) {
+    result.warnings.push(scanTruncationWarning());
+    result.truncated = true;
+  }
   return result;
 }
 
+// What `list` shows about each entry's session besides where it lives:
+// `title` (the first user prompt, else the agent's summary), `message_count`
+// (user prompts and assistant replies), `size_bytes`, `created_at` (first
+// message timestamp), and `duration_seconds` (first to last message
+// timestamp). Mirrors the Rust CLI.
+function describeListEntries(entries) {
+  const { substantivePrompt, sessionTitle } = require('./adapters/utils.cjs');
+  for (const entry of entries) {
+    let size = null;
+    try {
+      size = fs.statSync(entry.file_path).size;
+    } catch (error) { /* missing: no details */ }
+    let messages = null;
+    if (size !== null && size <= maxFileSize()) {
+      try {
+        messages = getAdapter(entry.agent).transcript(entry.file_path, entry.session_id)
+          .filter(message => message.text.trim())
+          .map(message => ({ ...message, text: redactSensitiveText(message.text.trim()) }));
+      } catch (error) { /* unreadable: no details */ }
+    }
+
+    if (entry.title == null) {
+      const prompt = (messages || [])
+        .filter(message => message.role === 'user')
+        .map(message => substantivePrompt(message.text))
+        .find(Boolean);
+      entry.title = prompt ? sessionTitle(prompt) : (entry.summary == null ? null : entry.summary);
+    }
+    const times = (messages || []).map(message => Date.parse(message.timestamp)).filter(Number.isFinite);
+    entry.message_count = messages ? messages.length : null;
+    entry.size_bytes = size;
+    entry.created_at = times.length > 0 ? new Date(Math.min(...times)).toISOString() : null;
+    entry.duration_seconds = times.length > 0 ? Math.max(0, Math.max(...times) - Math.min(...times)) / 1000 : null;
+  }
+  return entries;
+}
+
+// `list` and `search` text output: an aligned row per entry with its agent,
+// session id, age, the tail of its cwd, and its title. Mirrors the Rust CLI,
+// without color.
+function sessionsTable(entries) {
+  if (entries.length === 0) return 'No sessions found.';
+  const now = Date.now();
+  const age = (modifiedAt) => {
+    const ms = Date.parse(modifiedAt);
+    if (!Number.isFinite(ms)) return '-';
+    const seconds = Math.floor(Math.max(0, now - ms) / 1000);
+    if (seconds < 60) return `${seconds}s`;
+    if (seconds < 3600) return `${Math.floor(seconds / 60)}m`;
+    if (seconds < 86400) return `${Math.floor(seconds / 3600)}h`;
+    return `${Math.floor(seconds / 86400)}d`;
+  };
+  const cwdTail = (cwd) => {
+    const parts = cwd.split(/[\\/]/).filter(Boolean);
+    return parts.length <= 2 ? cwd : `…/${parts.slice(-2).join('/')}`;
+  };
+  const cell = value => (typeof value === 'string' ? sanitizeForTerminal(value) : '-');
+  const rows = [['AGENT', 'SESSION', 'AGE', 'CWD', 'TITLE']].concat(entries.map(entry => [
+    cell(entry.agent),
+    cell(entry.session_id),
+    age(entry.modified_at),
+    typeof entry.cwd === 'string' ? sanitizeForTerminal(cwdTail(entry.cwd)) : '-',
+    cell(entry.title),
+  ]));
+  const widths = [0, 1, 2, 3].map(column => Math.max(...rows.map(row => Array.from(row[column]).length)));
+  const pad = (text, width) => text + ' '.repeat(width - Array.from(text).length);
+  return rows.map(row => `${row.slice(0, 4).map((text, column) => pad(text, widths[column])).join('  ')}  ${row[4]}`).join('\n');
+}
+
 function runList(inputArgs) {
-  const agent = getOptionValue(inputArgs, '--agent', 'codex');
+  const rawAgent = getOptionValue(inputArgs, '--agent', 'codex');
+  const agent = isAllAgents(rawAgent) ? 'all' : normalizeAgentName(rawAgent);
   const rawCwd = getOptionValue(inputArgs, '--cwd', null);
   const cwd = rawCwd ? normalizePath(rawCwd) : null;
   const limit = parseInt(getOptionValue(inputArgs, '--limit', '10'), 10) || 10;
   const asJson = hasFlag(inputArgs, '--json');
 
-  const entries = listSessions(agent, cwd, limit);
+  const entries = flagTruncated(describeListEntries(listSessions(agent, cwd, limit)));
+  warnIfScanTruncated();
 
   if (asJson) {
     console.log(JSON.stringify(entries, null, 2));
   } else {
-    for (const entry of entries) {
-      console.log(JSON.stringify(entry));
-    }
+    console.log(sessionsTable(entries));
   }
 }
 
-function readSource(sourceSpec, defaultCwd) {
+function readSource(sourceSpec, defaultCwd, lastN) {
   const effectiveCwd = normalizePath(sourceSpec.cwd || defaultCwd);
   return readSessionViaAdapter(sourceSpec.agent, {
     id: sourceSpec.session_id || null,
     cwd: effectiveCwd,
     chatsDir: sourceSpec.chats_dir || null,
-    lastN: 1,
+    lastN: lastN || 1,
   });
 }
 
 function parseSourceArg(raw) {
   const firstColon = raw.indexOf(':');
-  const agent = (firstColon === -1 ? raw : raw.slice(0, firstColon)).trim().toLowerCase();
+  const agent = normalizeAgentName(firstColon === -1 ? raw : raw.slice(0, firstColon));
   const session = firstColon === -1 ? null : raw.slice(firstColon + 1).trim();
 
-  if (!['codex', 'gemini', 'claude', 'cursor'].includes(agent)) {
-    throw new Error(`Unsupported agent: ${agent}`);
-  }
-
   return {
     agent,
     session_id: session ? session : null,
@@ -1079,11 +1321,11 @@ function evidenceTag(sourceSpec) {
   return `[${sourceSpec.agent}:${id}]`;
 }
 
-function computeVerdict(mode, missingCount, uniqueCount, successCount) {
+function computeVerdict(mode, missingCount, uniqueCount, successCount, claimsFailed) {
   if (successCount === 0) return 'INCOMPLETE';
 
   if (mode === 'verify') {
-    if (missingCount === 0 && uniqueCount <= 1) return 'PASS';
+    if (missingCount === 0 && uniqueCount <= 1 && !claimsFailed) return 'PASS';
     return 'FAIL';
   }
 
@@ -1093,17 +1335,326 @@ function computeVerdict(mode, missingCount, uniqueCount, successCount) {
   return 'INCOMPLETE';
 }
 
+// Working-tree claims of `verify` handoffs; mirrors cli/core/src/claims.rs.
+const CLAIM_COMMAND_TIMEOUT_SECS = 300;
+const MAX_CLAIM_DETAIL_CHARS = 200;
+const CLAIM_FIELDS = { file_exists: ['path'], file_contains: ['path', 'text'], command: ['run'] };
+
+function parseClaim(value) {
+  if (typeof value !== 'object' || value === null || Array.isArray(value)) {
+    throw new Error('Each claim must be an object');
+  }
+  if (typeof value.type !== 'string') {
+    throw new Error('Each claim must include string field: type');
+  }
+  if (!Object.prototype.hasOwnProperty.call(CLAIM_FIELDS, value.type)) {
+    throw new Error(`Unsupported claim type: ${value.type} (expected file_exists, file_contains, or command)`);
+  }
+  const fields = CLAIM_FIELDS[value.type];
+  const claim = { type: value.type };
+  for (const field of fields) {
+    if (typeof value[field] !== 'string' || !value[field].trim()) {
+      throw new Error(`${value.type} claims must include string field: ${field}`);
+    }
+    claim[field] = value[field];
+  }
+  claim.description = typeof value.description === 'string' && value.description.trim() ? value.description : null;
+  return claim;
+}
+
+function claimLabel(claim) {
+  if (claim.description) return claim.description;
+  if (claim.type === 'file_exists') return `${claim.path} exists`;
+  if (claim.type === 'file_contains') return `${claim.path} contains "${claim.text}"`;
+  return `\`${claim.run}\` succeeds`;
+}
+
+function runClaimCommand(run, cwd) {
+  const result = spawnSync(run, {
+    cwd,
+    shell: true,
+    encoding: 'utf8',
+    stdio: ['ignore', 'pipe', 'pipe'],
+    timeout: CLAIM_COMMAND_TIMEOUT_SECS * 1000,
+    maxBuffer: 64 * 1024 * 1024,
+  });
+  if (result.error && result.error.code === 'ETIMEDOUT') {
+    return { status: 'fail', detail: `timed out after ${CLAIM_COMMAND_TIMEOUT_SECS}s` };
+  }
+  if (result.error) {
+    return { status: 'fail', detail: `failed to start: ${result.error.message}` };
+  }
+  if (result.status === 0) {
+    return { status: 'pass', detail: 'exited 0' };
+  }
+  let detail = result.status === null ? 'killed by signal' : `exited ${result.status}`;
+  const output = (result.stderr || '').trim() ? result.stderr : result.stdout || '';
+  const lines = output.split(/\r?\n/).map(line => line.trim()).filter(Boolean);
+  if (lines.length > 0) {
+    detail += `: ${Array.from(lines[lines.length - 1]).slice(0, MAX_CLAIM_DETAIL_CHARS).join('')}`;
+  }
+  return { status: 'fail', detail };
+}
+
+function checkClaim(claim, cwd, allowCommands) {
+  if (claim.type === 'file_exists') {
+    return fs.existsSync(path.resolve(cwd, claim.path))
+      ? { status: 'pass', detail: 'found' }
+      : { status: 'fail', detail: 'not found' };
+  }
+  if (claim.type === 'file_contains') {
+    let text;
+    try {
+      text = fs.readFileSync(path.resolve(cwd, claim.path), 'utf8');
+    } catch (_error) {
+      return { status: 'fail', detail: 'file not found' };
+    }
+    return text.includes(claim.text) ? { status: 'pass', detail: 'found' } : { status: 'fail', detail: 'text not found' };
+  }
+  if (!allowCommands) {
+    return { status: 'skipped', detail: 'command claims run only with --allow-commands' };
+  }
+  return runClaimCommand(claim.run, cwd);
+}
+
+function checkClaims(claims, cwd, allowCommands) {
+  const findings = [];
+  const results = [];
+  let failed = false;
+  let skipped = false;
+  for (const claim of claims) {
+    const { status, detail } = checkClaim(claim, cwd, allowCommands);
+    const label = claimLabel(claim);
+    let finding;
+    if (status === 'pass') {
+      finding = { severity: 'P3', summary: `Claim verified: ${label}`, confidence: 0.95 };
+    } else if (status === 'fail') {
+      failed = true;
+      finding = { severity: 'P1', summary: `Claim failed: ${label} (${detail})`, confidence: 0.9 };
+    } else {
+      skipped = true;
+      finding = { severity: 'P2', summary: `Claim not checked: ${label} (${detail})`, confidence: 0.5 };
+    }
+    findings.push({
+      severity: finding.severity,
+      summary: finding.summary,
+      evidence: [claim.type === 'command' ? claim.run : claim.path],
+      confidence: finding.confidence,
+    });
+    results.push({ type: claim.type, claim: label, status, detail });
+  }
+  return { findings, results, failed, skipped };
+}
+
+// Rubric grading for `mode: feedback`; mirrors cli/core/src/rubric.rs.
+const RUBRIC_MET_COVERAGE = 0.5;
+
+function rubricWords(text) {
+  return text.split(/[^A-Za-z0-9]+/).filter(Boolean).map(word => word.toLowerCase());
+}
+
+// What a reply must mention: the keywords, or else the criterion's content words.
+function rubricTerms(criterion) {
+  const terms = [];
+  const candidates = criterion.keywords.length === 0
+    ? rubricWords(criterion.criterion).filter(word => !SEMANTIC_STOPWORDS.has(word))
+    : criterion.keywords.map(keyword => rubricWords(keyword).join(' '));
+  for (const term of candidates) {
+    if (!terms.includes(term)) terms.push(term);
+  }
+  return terms;
+}
+
+function parseCriterion(value) {
+  if (typeof value !== 'object' || value === null || Array.isArray(value)) {
+    throw new Error('Each rubric entry must be an object');
+  }
+  if (typeof value.criterion !== 'string' || !value.criterion.trim()) {
+    throw new Error('Each rubric entry must include string field: criterion');
+  }
+  let weight = 1;
+  if (value.weight !== undefined) {
+    if (typeof value.weight !== 'number' || !(value.weight > 0)) {
+      throw new Error('Rubric weight must be a positive number');
+    }
+    weight = value.weight;
+  }
+  let keywords = [];
+  if (value.keywords !== undefined) {
+    if (!Array.isArray(value.keywords)) {
+      throw new Error('Rubric keywords must be an array');
+    }
+    keywords = value.keywords.map(keyword => {
+      if (typeof keyword !== 'string' || rubricWords(keyword).length === 0) {
+        throw new Error('Rubric keywords must be non-empty strings');
+      }
+      return keyword.trim();
+    });
+  }
+  const criterion = { criterion: value.criterion.trim(), weight, keywords };
+  if (rubricTerms(criterion).length === 0) {
+    throw new Error(`Rubric criterion has no words to match; add keywords: ${criterion.criterion}`);
+  }
+  return criterion;
+}
+
+function gradeRubric(rubric, tags, texts) {
+  const padded = texts.map(text => ` ${rubricWords(text).join(' ')} `);
+  const totalWeight = rubric.reduce((sum, criterion) => sum + criterion.weight, 0);
+  const met = tags.map(() => []);
+  const scores = [];
+  const sources = tags.map((tag, index) => {
+    let weighted = 0;
+    const results = rubric.map(criterion => {
+      const terms = rubricTerms(criterion);
+      const matched = terms.filter(term => padded[index].includes(` ${term} `));
+      const missing = terms.filter(term => !padded[index].includes(` ${term} `));
+      const coverage = round2(matched.length / terms.length);
+      weighted += criterion.weight * coverage;
+      met[index].push(coverage >= RUBRIC_MET_COVERAGE);
+      return { criterion: criterion.criterion, coverage, met: coverage >= RUBRIC_MET_COVERAGE, matched, missing };
+    });
+    const score = Math.round(100 * weighted / totalWeight);
+    scores.push(score);
+    return { source: tag, score, criteria: results };
+  });
+
+  const findings = rubric.map((criterion, position) => {
+    const meeting = tags.filter((_, index) => met[index][position]);
+    const missing = tags.filter((_, index) => !met[index][position]);
+    if (missing.length === 0) {
+      return { severity: 'P3', summary: `Rubric: every source meets "${criterion.criterion}"`, evidence: meeting, confidence: 0.7 };
+    }
+    if (meeting.length === 0) {
+      return { severity: 'P1', summary: `Rubric: no source meets "${criterion.criterion}"`, evidence: missing, confidence: 0.7 };
+    }
+    return {
+      severity: 'P2',
+      summary: `Rubric: "${criterion.criterion}" met by ${meeting.join(', ')}; missed by ${missing.join(', ')}`,
+      evidence: missing,
+      confidence: 0.7,
+    };
+  });
+
+  const actions = [];
+  tags.forEach((tag, index) => {
+    const missed = rubric.filter((_, position) => !met[index][position]).map(criterion => criterion.criterion);
+    if (missed.length > 0) {
+      actions.push(`Ask ${tag} to cover: ${missed.join('; ')}.`);
+    }
+  });
+
+  const score = scores.length === 0 ? 0 : Math.round(scores.reduce((sum, value) => sum + value, 0) / scores.length);
+  const criteria = rubric.map(criterion => ({
+    criterion: criterion.criterion,
+    weight: criterion.weight,
+    terms: rubricTerms(criterion),
+  }));
+  return { findings, rubric: { score, criteria, sources }, scores, actions };
+}
+
+// Points a finding deducts from 100 at full confidence.
+const SEVERITY_WEIGHTS = { P0: 60, P1: 40, P2: 15, P3: 0 };
+
+/**
+ * Overall score: 100 minus each finding's severity weight scaled by its
+ * confidence, clamped to 0..100. A source's score only counts findings that
+ * cite it as evidence.
+ */
+function parseSeverity(raw) {
+  const severity = String(raw).trim().toUpperCase();
+  if (!['P0', 'P1', 'P2', 'P3'].includes(severity)) {
+    throw new Error(`Unsupported severity: ${raw} (expected P0, P1, P2, or P3)`);
+  }
+  return severity;
+}
+
+function parseThresholds(value) {
+  if (value === undefined) {
+    return { fail_on: null, min_score: null };
+  }
+  if (typeof value !== 'object' || value === null || Array.isArray(value)) {
+    throw new Error('Invalid handoff: thresholds must be an object');
+  }
+  let failOn = null;
+  if (value.fail_on !== undefined) {
+    if (typeof value.fail_on !== 'string') {
+      throw new Error('Handoff thresholds.fail_on must be a string');
+    }
+    failOn = parseSeverity(value.fail_on);
+  }
+  let minScore = null;
+  if (value.min_score !== undefined) {
+    if (!Number.isInteger(value.min_score) || value.min_score < 0 || value.min_score > 100) {
+      throw new Error('Handoff thresholds.min_score must be an integer from 0 to 100');
+    }
+    minScore = value.min_score;
+  }
+  return { fail_on: failOn, min_score: minScore };
+}
+
+// --fail-on and --min-score, or null for each flag not given.
+function parseThresholdFlags(inputArgs) {
+  const rawFailOn = getOptionValue(inputArgs, '--fail-on', null);
+  const rawMinScore = getOptionValue(inputArgs, '--min-score', null);
+  const minScore = rawMinScore === null ? null : Number(rawMinScore);
+  if (minScore !== null && (!Number.isInteger(minScore) || minScore < 0 || minScore > 100)) {
+    throw new Error('--min-score must be an integer from 0 to 100');
+  }
+  return { fail_on: rawFailOn === null ? null : parseSeverity(rawFailOn), min_score: minScore };
+}
+
+// Why findings and score breach the thresholds; empty when they pass.
+function thresholdBreaches(thresholds, findings, score) {
+  const breaches = [];
+  if (thresholds.fail_on) {
+    const count = findings.filter(finding => finding.severity <= thresholds.fail_on).length;
+    if (count > 0) {
+      breaches.push(`${count} finding(s) at ${thresholds.fail_on} or worse`);
+    }
+  }
+  if (thresholds.min_score !== null && thresholds.min_score !== undefined && score < thresholds.min_score) {
+    breaches.push(`score ${score} is below ${thresholds.min_score}`);
+  }
+  return breaches;
+}
+
+// 2 when the verdict is FAIL or INCOMPLETE, so CI can gate on it; 0 otherwise.
+function verdictExitCode(report) {
+  return report.verdict === 'FAIL' || report.verdict === 'INCOMPLETE' ? 2 : 0;
+}
+
+function computeScores(findings, sourceTags) {
+  const penalty = (finding) => (SEVERITY_WEIGHTS[finding.severity] || 0) * (Number(finding.confidence) || 0);
+  const clamp = (deducted) => Math.round(Math.min(100, Math.max(0, 100 - deducted)));
+  const sum = (items) => items.reduce((total, finding) => total + penalty(finding), 0);
+
+  return {
+    score: clamp(sum(findings)),
+    sourceScores: sourceTags.map(tag => ({
+      source: tag,
+      score: clamp(sum(findings.filter(f => Array.isArray(f.evidence) && f.evidence.includes(tag)))),
+    })),
+  };
+}
+
 function buildReport(request, defaultCwd) {
   const successful = [];
   const missing = [];
+  // Structured copies of the unavailable-source and source-warning findings,
+  // so callers can act on a partial report.
+  const errors = [];
+  const warnings = [];
 
   for (const sourceSpec of request.sources) {
     const evidence = evidenceTag(sourceSpec);
     try {
-      const session = readSource(sourceSpec, defaultCwd);
+      const session = readSource(sourceSpec, defaultCwd, request.last_n);
       successful.push({ sourceSpec, session, evidence });
     } catch (error) {
-      missing.push({ sourceSpec, error: error.message || String(error), evidence });
+      const message = error.message || String(error);
+      errors.push({ source: evidence, agent: sourceSpec.agent, error_code: classifyError(message), message });
+      missing.push({ sourceSpec, error: message, evidence });
     }
   }
 
@@ -1120,6 +1671,7 @@ function buildReport(request, defaultCwd) {
 
   for (const item of successful) {
     for (const warning of item.session.warnings || []) {
+      warnings.push({ source: item.evidence, message: warning });
       findings.push({
         severity: 'P2',
         summary: `Source warning: ${warning}`,
@@ -1130,27 +1682,55 @@ function buildReport(request, defaultCwd) {
   }
 
   const shouldNormalize = request.normalize === true;
-  const uniqueContents = new Set(successful.map(item => {
-    const text = (item.session.content || '').trim();
-    return shouldNormalize ? normalizeContent(text) : text;
-  }));
-
-  if (successful.length >= 2) {
-    if (uniqueContents.size > 1) {
-      findings.push({
-        severity: 'P1',
-        summary: 'Divergent agent outputs detected',
-        evidence: successful.map(item => item.evidence),
-        confidence: 0.75,
-      });
-    } else {
-      findings.push({
-        severity: 'P3',
-        summary: 'All available agent outputs are aligned',
-        evidence: successful.map(item => item.evidence),
-        confidence: 0.9,
-      });
+  const texts = successful.map(item => (item.session.content || '').trim());
+  const contents = texts.map(text => (shouldNormalize ? normalizeContent(text) : text));
+  const uniqueContents = new Set(contents);
+  const successfulTags = successful.map(item => item.evidence);
+  let similarity = null;
+  let oddOneOut = null;
+  let semantic = null;
+  let consensusResult = null;
+  let contradicted = false;
+  // A rubric grades each source on its own, so sources are not compared.
+  const grading = Array.isArray(request.rubric) && request.rubric.length > 0;
+  let diverging = uniqueContents.size > 1 && !grading;
+
+  if (grading) {
+    // Graded after the stale-source checks.
+  } else if (successful.length >= 2 && uniqueContents.size <= 1) {
+    findings.push({
+      severity: 'P3',
+      summary: 'All available agent outputs are aligned',
+      evidence: successfulTags,
+      confidence: 0.9,
+    });
+  } else if (request.semantic === true && successful.length >= 2) {
+    const result = semanticPairwise(successfulTags, contents);
+    findings.push(...result.findings);
+    semantic = result.semantic;
+    contradicted = result.anyContradiction;
+    diverging = result.anyDivergent;
+  } else if (successful.length >= 3) {
+    const matrix = similarityMatrix(contents);
+    oddOneOut = findOddOneOut(matrix);
+    const agreement = consensus(successfulTags, contents, matrix);
+    if (agreement) {
+      // When every pair agrees, a relatively loose source is not worth a P1.
+      if (agreement.consensus.status === 'unanimous') {
+        oddOneOut = null;
+      }
+      findings.push(agreement.finding);
+      consensusResult = agreement.consensus;
     }
+    findings.push(...pairwiseFindings(successfulTags, contents, matrix, oddOneOut));
+    similarity = { sources: successfulTags, matrix };
+  } else if (successful.length === 2) {
+    findings.push({
+      severity: 'P1',
+      summary: 'Divergent agent outputs detected',
+      evidence: successfulTags,
+      confidence: 0.75,
+    });
   } else {
     findings.push({
       severity: 'P2',
@@ -1160,13 +1740,90 @@ function buildReport(request, defaultCwd) {
     });
   }
 
+  const timestamps = successful.map(item => {
+    const parsed = Date.parse(item.session.timestamp || '');
+    // Whole seconds, matching the Rust CLI's mtime precision.
+    return Number.isNaN(parsed) ? null : Math.floor(parsed / 1000) * 1000;
+  });
+  const staleHours = staleAfterHours();
+  const stale = staleHours === 0 ? [] : staleSources(timestamps, headCommitMillis(defaultCwd), staleHours);
+  const staleTags = stale.map(item => successfulTags[item.index]);
+  for (const finding of findings) {
+    if ((finding.evidence || []).some(tag => staleTags.includes(tag))) {
+      finding.confidence = round2(finding.confidence * STALE_CONFIDENCE_FACTOR);
+    }
+  }
+  for (const item of stale) {
+    findings.push({
+      severity: 'P2',
+      summary: `Stale source: ${successfulTags[item.index]} was last updated ${item.hours}h before ${item.reference}`,
+      evidence: [successfulTags[item.index]],
+      confidence: 0.75,
+    });
+  }
+
+  const claimCheck = checkClaims(request.claims || [], defaultCwd, request.allow_commands === true);
+  findings.push(...claimCheck.findings);
+
+  let steerResult = null;
+  if (request.mode === 'steer') {
+    steerResult = steer(successfulTags, texts, request.task, request.success_criteria);
+    findings.push(...steerResult.findings);
+  }
+
+  let grades = null;
+  if (grading && successful.length > 0) {
+    grades = gradeRubric(request.rubric, successfulTags, texts);
+    findings.push(...grades.findings);
+  }
+
+  let verdict = computeVerdict(request.mode, missing.length, uniqueContents.size, successful.length, claimCheck.failed);
+  let { score, sourceScores } = computeScores(findings, request.sources.map(evidenceTag));
+  // With a rubric the scores are the rubric's, and a source that could not be read scores 0.
+  if (grades) {
+    score = grades.rubric.score;
+    sourceScores = request.sources.map(evidenceTag).map(tag => {
+      const index = successfulTags.indexOf(tag);
+      return { source: tag, score: index === -1 ? 0 : grades.scores[index] };
+    });
+  }
+  const thresholds = request.thresholds || { fail_on: null, min_score: null };
+  const breaches = thresholdBreaches(thresholds, findings, score);
+  if (breaches.length > 0) {
+    verdict = 'FAIL';
+  }
+
   const recommendedNextActions = [];
+  if (breaches.length > 0) {
+    recommendedNextActions.push(`Resolve what breaches the report thresholds: ${breaches.join('; ')}.`);
+  }
+  if (claimCheck.failed) {
+    recommendedNextActions.push('Fix the failed claims, then re-run the report.');
+  }
+  if (claimCheck.skipped) {
+    recommendedNextActions.push('Re-run with --allow-commands to check command claims.');
+  }
   if (missing.length > 0) {
     recommendedNextActions.push('Provide valid session identifiers or cwd values for unavailable sources.');
   }
-  if (uniqueContents.size > 1) {
+  if (contradicted) {
+    recommendedNextActions.push('Resolve the contradictions between sources before final decisions.');
+  }
+  if (diverging) {
     recommendedNextActions.push('Inspect full transcripts for diverging sources before final decisions.');
   }
+  if (oddOneOut !== null) {
+    recommendedNextActions.push(`Start with ${successfulTags[oddOneOut]}: it disagrees with every other source.`);
+  }
+  if (staleTags.length > 0) {
+    recommendedNextActions.push(`Refresh stale sources before relying on the comparison: ${staleTags.join(', ')}.`);
+  }
+  if (grades) {
+    recommendedNextActions.push(...grades.actions);
+  }
+  if (steerResult && steerResult.steering.plan.length > 0) {
+    recommendedNextActions.push('Send each source its next prompt from the steering plan.');
+  }
   if (Array.isArray(request.constraints) && request.constraints.length > 0) {
     recommendedNextActions.push(`Verify recommendations against constraints: ${request.constraints.join('; ')}.`);
   }
@@ -1174,18 +1831,632 @@ function buildReport(request, defaultCwd) {
     recommendedNextActions.push('No immediate action required.');
   }
 
+  const diffs = [];
+  for (let i = 0; i < (grading ? 0 : successful.length); i += 1) {
+    for (let j = i + 1; j < successful.length; j += 1) {
+      if (contents[i] !== contents[j]) {
+        const diff = unifiedDiff(successfulTags[i], successfulTags[j], texts[i], texts[j]);
+        if (diff) diffs.push(diff);
+      }
+    }
+  }
+
   const openQuestions = missing.map(item => `Missing source ${item.sourceSpec.agent}: ${item.error}`);
+  if (steerResult) {
+    openQuestions.push(...steerResult.openQuestions);
+  }
 
-  return {
+  const report = {
     mode: request.mode,
     task: request.task,
     success_criteria: request.success_criteria,
     sources_used: successful.map(item => `${item.evidence} ${item.session.source}`),
-    verdict: computeVerdict(request.mode, missing.length, uniqueContents.size, successful.length),
+    verdict,
+    score,
+    source_scores: sourceScores,
     findings: findings,
     recommended_next_actions: recommendedNextActions,
     open_questions: openQuestions,
   };
+  if (similarity) {
+    report.similarity = similarity;
+  }
+  if (semantic) {
+    report.semantic = semantic;
+  }
+  if (consensusResult) {
+    report.consensus = consensusResult;
+  }
+  if (steerResult) {
+    report.steering = steerResult.steering;
+  }
+  if (grades) {
+    report.rubric = grades.rubric;
+  }
+  if (diffs.length > 0) {
+    report.diffs = diffs;
+  }
+  if (errors.length > 0) {
+    report.errors = errors;
+  }
+  if (warnings.length > 0) {
+    report.warnings = warnings;
+  }
+  if (claimCheck.results.length > 0) {
+    report.claims = claimCheck.results;
+  }
+  if (thresholds.fail_on || (thresholds.min_score !== null && thresholds.min_score !== undefined)) {
+    report.thresholds = {
+      fail_on: thresholds.fail_on,
+      min_score: thresholds.min_score,
+      passed: breaches.length === 0,
+      breaches,
+    };
+  }
+  const history = request.history || [];
+  if (history.length > 0) {
+    report.chain = [
+      ...history,
+      chainStep(history.length + 1, null, request.mode, request.task, report.verdict, report.score, report.findings),
+    ];
+  }
+  return report;
+}
+
+// Confidence multiplier for findings that cite a stale source.
+const STALE_CONFIDENCE_FACTOR = 0.8;
+
+// Age gap, in hours, after which a source counts as stale
+// (BRIDGE_STALE_SOURCE_HOURS, default 24; 0 disables the check).
+function staleAfterHours() {
+  const parsed = Number.parseInt(String(process.env.BRIDGE_STALE_SOURCE_HOURS || '').trim(), 10);
+  return Number.isNaN(parsed) ? 24 : Math.max(parsed, 0);
+}
+
+// Commit time of HEAD in the repository containing cwd, if any.
+function headCommitMillis(cwd) {
+  try {
+    const output = execFileSync('git', ['-C', cwd, 'log', '-1', '--format=%ct'], {
+      encoding: 'utf8',
+      stdio: ['ignore', 'pipe', 'ignore'],
+    });
+    const seconds = Number.parseInt(output.trim(), 10);
+    return Number.isNaN(seconds) ? null : seconds * 1000;
+  } catch (error) {
+    return null;
+  }
+}
+
+// Each source whose mtime trails the newest source, or HEAD when that is
+// later, by at least thresholdHours.
+function staleSources(timestamps, headMs, thresholdHours) {
+  const known = timestamps.filter(value => value !== null);
+  if (known.length === 0) return [];
+  const newest = Math.max(...known);
+  const [referenceMs, reference] = headMs !== null && headMs > newest
+    ? [headMs, 'HEAD']
+    : [newest, 'the newest source'];
+  const stale = [];
+  timestamps.forEach((timestamp, index) => {
+    if (timestamp === null) return;
+    const hours = Math.floor((referenceMs - timestamp) / 3600000);
+    if (hours >= thresholdHours) stale.push({ index, hours, reference });
+  });
+  return stale;
+}
+
+function round2(value) {
+  return Math.round(value * 100) / 100;
+}
+
+function wordSet(text) {
+  return new Set(text.split(/[^A-Za-z0-9]+/).filter(Boolean).map(word => word.toLowerCase()));
+}
+
+// Jaccard similarity of the word sets of every pair of outputs, rounded to
+// two decimals so both CLIs produce identical matrices.
+function similarityMatrix(contents) {
+  const sets = contents.map(wordSet);
+  return sets.map(left => sets.map(right => {
+    const union = new Set([...left, ...right]).size;
+    if (union === 0) return 1;
+    const shared = [...left].filter(word => right.has(word)).length;
+    return round2(shared / union);
+  }));
+}
+
+// The source that is less similar to every other source than any two of the
+// others are to each other, if there is one.
+function findOddOneOut(matrix) {
+  const count = matrix.length;
+  for (let odd = 0; odd < count; odd += 1) {
+    let closest = -Infinity;
+    let loosest = Infinity;
+    for (let i = 0; i < count; i += 1) {
+      if (i !== odd) closest = Math.max(closest, matrix[odd][i]);
+      for (let j = i + 1; j < count; j += 1) {
+        if (i !== odd && j !== odd) loosest = Math.min(loosest, matrix[i][j]);
+      }
+    }
+    if (closest < loosest) return odd;
+  }
+  return null;
+}
+
+// One P2 finding per pair of differing outputs, weighted by how far apart they
+// are, plus a P1 finding naming the odd one out.
+function pairwiseFindings(tags, contents, matrix, oddOneOut) {
+  const findings = [];
+  if (oddOneOut !== null) {
+    const others = matrix[oddOneOut].filter((_, index) => index !== oddOneOut);
+    const mean = round2(others.reduce((sum, value) => sum + value, 0) / others.length);
+    findings.push({
+      severity: 'P1',
+      summary: `Odd one out: ${tags[oddOneOut]} diverges from every other source (mean similarity ${mean.toFixed(2)})`,
+      evidence: [tags[oddOneOut]],
+      confidence: 0.75,
+    });
+  }
+  for (let i = 0; i < tags.length; i += 1) {
+    for (let j = i + 1; j < tags.length; j += 1) {
+      if (contents[i] !== contents[j]) {
+        findings.push({
+          severity: 'P2',
+          summary: `Divergent outputs: ${tags[i]} vs ${tags[j]} (similarity ${matrix[i][j].toFixed(2)})`,
+          evidence: [tags[i], tags[j]],
+          confidence: round2(0.75 * (1 - matrix[i][j])),
+        });
+      }
+    }
+  }
+  return findings;
+}
+
+// Largest group of pairwise-agreeing sources; mirrors `consensus` in cli/core/src/report.rs.
+const CONSENSUS_THRESHOLD = 0.8;
+const MAX_CONSENSUS_SOURCES = 16;
+
+function consensus(tags, contents, matrix) {
+  const count = tags.length;
+  if (count > MAX_CONSENSUS_SOURCES) return null;
+  const agree = (i, j) => contents[i] === contents[j] || matrix[i][j] >= CONSENSUS_THRESHOLD;
+  const members = mask => tags.map((_, index) => index).filter(index => (mask & (1 << index)) !== 0);
+  let best = [];
+  for (let mask = 1; mask < 1 << count; mask += 1) {
+    const group = members(mask);
+    if (group.length > best.length && group.every(i => group.every(j => i >= j || agree(i, j)))) {
+      best = group;
+    }
+  }
+  const majority = best.length >= 2 && best.length * 2 > count;
+  const agreeing = majority ? best.map(index => tags[index]) : [];
+  const diverging = tags.filter((_, index) => !majority || !best.includes(index));
+
+  let status;
+  let finding;
+  if (best.length === count) {
+    status = 'unanimous';
+    finding = { severity: 'P3', summary: `Consensus: all ${count} sources agree`, evidence: tags, confidence: 0.8 };
+  } else if (majority) {
+    status = 'majority';
+    finding = {
+      severity: 'P2',
+      summary: `Majority: ${joinAnd(agreeing)} agree; ${joinAnd(diverging)} ${diverging.length === 1 ? 'diverges' : 'diverge'}`,
+      evidence: diverging,
+      confidence: 0.75,
+    };
+  } else {
+    status = 'none';
+    finding = {
+      severity: 'P2',
+      summary: `No consensus: no majority of the ${count} sources agree`,
+      evidence: tags,
+      confidence: 0.6,
+    };
+  }
+  const pairs = [];
+  for (let i = 0; i < count; i += 1) {
+    for (let j = i + 1; j < count; j += 1) {
+      pairs.push({ sources: [tags[i], tags[j]], similarity: matrix[i][j], agree: agree(i, j) });
+    }
+  }
+  return { finding, consensus: { status, agreeing, diverging, pairs } };
+}
+
+function joinAnd(items) {
+  return items.length > 1 ? `${items.slice(0, -1).join(', ')} and ${items[items.length - 1]}` : items.join('');
+}
+
+// Steering plans for `mode: steer`; mirrors cli/core/src/steer.rs.
+const MAX_STEER_ITEMS = 10;
+const MAX_STEER_ITEM_CHARS = 200;
+const INCOMPLETE_MARKERS = [
+  'todo', 'fixme', 'tbd', 'wip', 'not yet', 'still need', 'still needs', 'pending', 'left to do',
+  'not implemented', 'unfinished', 'incomplete', 'follow up', 'next step', 'next steps',
+];
+
+function steerWords(text) {
+  return text.split(/[^A-Za-z0-9]+/).filter(Boolean).map(word => word.toLowerCase());
+}
+
+function steerItemKey(text) {
+  return steerWords(text).join(' ');
+}
+
+function clipSteerItem(text) {
+  return Array.from(text).slice(0, MAX_STEER_ITEM_CHARS).join('');
+}
+
+// A list line without its bullet or number; checkbox is false for `[ ]`, true for `[x]`.
+function stripListMarker(line) {
+  let rest = line;
+  const marker = /^(?:[-*+]|\d+[.)])(?=\s)/.exec(rest);
+  if (marker) {
+    rest = rest.slice(marker[0].length).trimStart();
+  }
+  let checkbox = null;
+  if (rest.startsWith('[ ]')) {
+    checkbox = false;
+  } else if (rest.startsWith('[x]') || rest.startsWith('[X]')) {
+    checkbox = true;
+  }
+  if (checkbox !== null) {
+    rest = rest.slice(3).trimStart();
+  }
+  return { rest, checkbox };
+}
+
+function isIncompleteItem(text) {
+  const padded = ` ${steerWords(text).join(' ')} `;
+  return INCOMPLETE_MARKERS.some(marker => padded.includes(` ${marker} `));
+}
+
+function steerQuestions(line) {
+  return line
+    .split(/(?<=[.!?])\s+/)
+    .map(piece => piece.trim().replace(/^[*_]+|[*_]+$/g, '').trim())
+    .filter(sentence => sentence.endsWith('?') && steerWords(sentence).length >= 2)
+    .map(clipSteerItem);
+}
+
+function pushUniqueSteerItem(items, item) {
+  if (items.length < MAX_STEER_ITEMS && !items.some(existing => steerItemKey(existing) === steerItemKey(item))) {
+    items.push(item);
+  }
+}
+
+function openItems(text) {
+  const items = { questions: [], incomplete: [] };
+  let inFence = false;
+  for (const line of text.split(/\r?\n/).map(value => value.trim())) {
+    if (line.startsWith('```') || line.startsWith('~~~')) {
+      inFence = !inFence;
+      continue;
+    }
+    if (inFence) {
+      continue;
+    }
+    const { rest, checkbox } = stripListMarker(line);
+    if (!rest || checkbox === true) {
+      continue;
+    }
+    if (checkbox === false || isIncompleteItem(rest)) {
+      pushUniqueSteerItem(items.incomplete, clipSteerItem(rest));
+      continue;
+    }
+    for (const question of steerQuestions(rest)) {
+      pushUniqueSteerItem(items.questions, question);
+    }
+  }
+  return items;
+}
+
+function addSteerStep(plan, action, item, tag) {
+  const existing = plan.find(step => step.action === action && steerItemKey(step.item) === steerItemKey(item));
+  if (existing) {
+    existing.sources.push(tag);
+  } else {
+    plan.push({ action, item, sources: [tag] });
+  }
+}
+
+function nextPrompt(tag, items, plan, task, criteria) {
+  const lines = [`Continue the task: ${task}`];
+  const numbered = (heading, list) => {
+    if (list.length > 0) {
+      lines.push(heading, ...list.map((item, index) => `${index + 1}. ${item}`));
+    }
+  };
+  numbered('Finish these incomplete items:', items.incomplete);
+  numbered('Resolve these open questions:', items.questions);
+  const others = plan.filter(step => !step.sources.includes(tag));
+  if (others.length > 0) {
+    lines.push('Also address what the other agents raised:');
+    lines.push(...others.map(step => `- ${step.item} (${step.sources.join(', ')})`));
+  }
+  if (lines.length === 1) {
+    lines.push('No open questions or incomplete items were found in your last reply.');
+  }
+  lines.push(`Check the result against the success criteria: ${criteria.join('; ')}.`);
+  return lines.join('\n');
+}
+
+function steer(tags, texts, task, criteria) {
+  const items = texts.map(openItems);
+  const plan = [];
+  tags.forEach((tag, index) => {
+    for (const question of items[index].questions) addSteerStep(plan, 'resolve', question, tag);
+  });
+  tags.forEach((tag, index) => {
+    for (const item of items[index].incomplete) addSteerStep(plan, 'finish', item, tag);
+  });
+
+  const findings = [];
+  const openQuestions = [];
+  const sources = tags.map((tag, index) => {
+    const source = items[index];
+    if (source.questions.length > 0 || source.incomplete.length > 0) {
+      findings.push({
+        severity: 'P2',
+        summary: `${tag} left ${source.incomplete.length} incomplete item(s) and ${source.questions.length} open question(s)`,
+        evidence: [tag],
+        confidence: 0.7,
+      });
+    }
+    openQuestions.push(...source.questions.map(question => `${tag} ${question}`));
+    return {
+      source: tag,
+      open_questions: source.questions,
+      incomplete: source.incomplete,
+      next_prompt: nextPrompt(tag, source, plan, task, criteria),
+    };
+  });
+  if (plan.length === 0 && tags.length > 0) {
+    findings.push({
+      severity: 'P3',
+      summary: 'No open questions or incomplete items found in any source',
+      evidence: tags,
+      confidence: 0.6,
+    });
+  }
+  return {
+    findings,
+    steering: {
+      plan: plan.map((step, index) => ({ step: index + 1, action: step.action, item: step.item, sources: step.sources })),
+      sources,
+    },
+    openQuestions,
+  };
+}
+
+// Line diffs between diverging sources; mirrors cli/core/src/diff.rs.
+const MAX_DIFF_LINES = 2000;
+const DIFF_CONTEXT_LINES = 3;
+
+function diffLines(text) {
+  return text
+    .split(/\r?\n/)
+    .map(line => line.split(/\s+/).filter(Boolean).join(' '))
+    .filter(line => line.length > 0);
+}
+
+function diffEditScript(left, right) {
+  const n = left.length;
+  const m = right.length;
+  // lcs[i][j]: longest common subsequence of left[i..] and right[j..].
+  const lcs = Array.from({ length: n + 1 }, () => new Uint32Array(m + 1));
+  for (let i = n - 1; i >= 0; i -= 1) {
+    for (let j = m - 1; j >= 0; j -= 1) {
+      lcs[i][j] = left[i] === right[j] ? lcs[i + 1][j + 1] + 1 : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+    }
+  }
+  const ops = [];
+  let i = 0;
+  let j = 0;
+  while (i < n || j < m) {
+    if (i < n && j < m && left[i] === right[j]) {
+      ops.push([' ', left[i]]);
+      i += 1;
+      j += 1;
+    } else if (j >= m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1])) {
+      ops.push(['-', left[i]]);
+      i += 1;
+    } else {
+      ops.push(['+', right[j]]);
+      j += 1;
+    }
+  }
+  return ops;
+}
+
+function diffHunkRanges(ops) {
+  const ranges = [];
+  let index = 0;
+  while (index < ops.length) {
+    if (ops[index][0] === ' ') {
+      index += 1;
+      continue;
+    }
+    const start = Math.max(0, index - DIFF_CONTEXT_LINES);
+    let last = index;
+    let cursor = index + 1;
+    while (cursor < ops.length) {
+      if (ops[cursor][0] !== ' ') {
+        last = cursor;
+        cursor += 1;
+        continue;
+      }
+      let run = 0;
+      while (cursor + run < ops.length && ops[cursor + run][0] === ' ') run += 1;
+      if (cursor + run >= ops.length || run > 2 * DIFF_CONTEXT_LINES) break;
+      cursor += run;
+    }
+    const end = Math.min(ops.length, last + 1 + DIFF_CONTEXT_LINES);
+    ranges.push([start, end]);
+    index = end;
+  }
+  return ranges;
+}
+
+function unifiedDiff(leftLabel, rightLabel, left, right) {
+  const leftLines = diffLines(left);
+  const rightLines = diffLines(right);
+  const truncated = leftLines.length > MAX_DIFF_LINES || rightLines.length > MAX_DIFF_LINES;
+  const ops = diffEditScript(leftLines.slice(0, MAX_DIFF_LINES), rightLines.slice(0, MAX_DIFF_LINES));
+  const removed = ops.filter(([op]) => op === '-').length;
+  const added = ops.filter(([op]) => op === '+').length;
+  if (added === 0 && removed === 0) return null;
+
+  let text = `--- ${leftLabel}\n+++ ${rightLabel}\n`;
+  for (const [start, end] of diffHunkRanges(ops)) {
+    const before = ops.slice(0, start);
+    const hunk = ops.slice(start, end);
+    const leftBefore = before.filter(([op]) => op !== '+').length;
+    const rightBefore = before.filter(([op]) => op !== '-').length;
+    const leftCount = hunk.filter(([op]) => op !== '+').length;
+    const rightCount = hunk.filter(([op]) => op !== '-').length;
+    text += `@@ -${leftCount === 0 ? leftBefore : leftBefore + 1},${leftCount} +${rightCount === 0 ? rightBefore : rightBefore + 1},${rightCount} @@\n`;
+    for (const [op, line] of hunk) {
+      text += `${op}${line}\n`;
+    }
+  }
+  return { sources: [leftLabel, rightLabel], added, removed, unified: text, truncated };
+}
+
+// Sentence-level comparison for `compare --semantic`; mirrors cli/core/src/semantic.rs.
+const SEMANTIC_MATCH_THRESHOLD = 0.5;
+const SEMANTIC_ALIGNED_THRESHOLD = 0.8;
+const MAX_CONTRADICTION_FINDINGS = 3;
+const SEMANTIC_STOPWORDS = new Set([
+  'a', 'an', 'the', 'is', 'are', 'was', 'were', 'be', 'been', 'being', 'to', 'of', 'and', 'or', 'in', 'on', 'for',
+  'with', 'it', 'its', 'this', 'that', 'these', 'those', 'as', 'at', 'by', 'from', 'i', 'we', 'you', 'they', 'he',
+  'she', 'should', 'will', 'would', 'can', 'could', 'do', 'does', 'did', 'has', 'have', 'had', 'so', 'but', 'if',
+  'then', 'there', 'here', 'also', 'just', 'which', 'what',
+]);
+// `t` is what remains of contractions such as "isn't".
+const SEMANTIC_NEGATIONS = new Set(['not', 'no', 'never', 'cannot', 'none', 'nor', 'neither', 'without', 't']);
+
+function semanticSentences(text) {
+  const pieces = [];
+  for (const line of text.split(/\r?\n/)) {
+    pieces.push(...line.split(/(?<=[.!?])\s+/));
+  }
+  const sentences = [];
+  for (const piece of pieces) {
+    const sentenceText = piece.trim();
+    const tokens = sentenceText.split(/[^A-Za-z0-9]+/).filter(Boolean).map(word => word.toLowerCase());
+    const negations = tokens.filter(word => SEMANTIC_NEGATIONS.has(word)).length;
+    const words = new Set(tokens.filter(word => !SEMANTIC_STOPWORDS.has(word) && !SEMANTIC_NEGATIONS.has(word)));
+    if (words.size > 0) {
+      sentences.push({ text: sentenceText, words, negated: negations % 2 === 1 });
+    }
+  }
+  return sentences;
+}
+
+function jaccard(left, right) {
+  const shared = [...left].filter(word => right.has(word)).length;
+  return shared / new Set([...left, ...right]).size;
+}
+
+function bestSemanticMatch(sentence, others) {
+  let best = null;
+  for (const other of others) {
+    const score = jaccard(sentence.words, other.words);
+    if (best === null || score > best.score) {
+      best = { score, other };
+    }
+  }
+  return best;
+}
+
+function compareSemanticPair(leftText, rightText) {
+  const left = semanticSentences(leftText);
+  const right = semanticSentences(rightText);
+  let total = 0;
+  let agreements = 0;
+  const contradictions = [];
+  const onlyLeft = [];
+  for (const sentence of left) {
+    const best = bestSemanticMatch(sentence, right);
+    if (best && best.score >= SEMANTIC_MATCH_THRESHOLD) {
+      total += best.score;
+      if (sentence.negated === best.other.negated) {
+        agreements += 1;
+      } else {
+        contradictions.push({ left: sentence.text, right: best.other.text });
+      }
+    } else {
+      total += best ? best.score : 0;
+      onlyLeft.push(sentence.text);
+    }
+  }
+  const onlyRight = [];
+  for (const sentence of right) {
+    const best = bestSemanticMatch(sentence, left);
+    const score = best ? best.score : 0;
+    total += score;
+    if (score < SEMANTIC_MATCH_THRESHOLD) {
+      onlyRight.push(sentence.text);
+    }
+  }
+  const count = left.length + right.length;
+  return {
+    similarity: count === 0 ? 1 : round2(total / count),
+    agreements,
+    contradictions,
+    onlyLeft,
+    onlyRight,
+  };
+}
+
+// Findings and the `semantic` report object for every pair of outputs.
+function semanticPairwise(tags, contents) {
+  const findings = [];
+  const pairs = [];
+  let anyContradiction = false;
+  let anyDivergent = false;
+  for (let i = 0; i < tags.length; i += 1) {
+    for (let j = i + 1; j < tags.length; j += 1) {
+      const pair = compareSemanticPair(contents[i], contents[j]);
+      const evidence = [tags[i], tags[j]];
+      if (pair.contradictions.length > 0) {
+        anyContradiction = true;
+        for (const contradiction of pair.contradictions.slice(0, MAX_CONTRADICTION_FINDINGS)) {
+          findings.push({
+            severity: 'P1',
+            summary: `Contradiction: ${tags[i]} says "${contradiction.left}" but ${tags[j]} says "${contradiction.right}"`,
+            evidence,
+            confidence: 0.7,
+          });
+        }
+      } else if (pair.similarity >= SEMANTIC_ALIGNED_THRESHOLD) {
+        findings.push({
+          severity: 'P3',
+          summary: `${tags[i]} and ${tags[j]} agree in substance (semantic similarity ${pair.similarity.toFixed(2)}, ${pair.agreements} matching sentence(s))`,
+          evidence,
+          confidence: 0.8,
+        });
+      } else {
+        anyDivergent = true;
+        findings.push({
+          severity: 'P2',
+          summary: `${tags[i]} and ${tags[j]} partially agree (semantic similarity ${pair.similarity.toFixed(2)}): ${pair.agreements} matching, ${pair.onlyLeft.length} only in ${tags[i]}, ${pair.onlyRight.length} only in ${tags[j]}`,
+          evidence,
+          confidence: round2(0.75 * (1 - pair.similarity)),
+        });
+      }
+      pairs.push({
+        sources: [tags[i], tags[j]],
+        similarity: pair.similarity,
+        agreements: pair.agreements,
+        contradictions: pair.contradictions,
+        only_left: pair.onlyLeft,
+        only_right: pair.onlyRight,
+      });
+    }
+  }
+  return { findings, semantic: { pairs }, anyContradiction, anyDivergent };
 }
 
 function sanitizeForTerminal(text) {
@@ -1198,6 +2469,20 @@ function sanitizeForTerminal(text) {
     .replace(/[\x00-\x08\x0B\x0C\x0E-\x1F]/g, ''); // C0 control chars except \t \n \r
 }
 
+// Commands whose output has no warnings channel report a truncated scan on
+// stderr, which keeps --json stdout parseable.
+// List/search entries flagged `truncated: true` when a scan stopped at the
+// file limit, as a read result is.
+function flagTruncated(entries) {
+  return wasScanTruncated() ? entries.map((entry) => ({ ...entry, truncated: true })) : entries;
+}
+
+function warnIfScanTruncated() {
+  if (wasScanTruncated()) {
+    console.error(scanTruncationWarning());
+  }
+}
+
 function renderReadResult(result, asJson) {
   if (asJson) {
     console.log(JSON.stringify(result, null, 2));
@@ -1214,6 +2499,37 @@ function renderReadResult(result, asJson) {
   console.log(sanitizeForTerminal(result.content));
 }
 
+function chainStep(step, agent, mode, task, verdict, score, findings) {
+  return { step, agent, mode, task, verdict, score, findings };
+}
+
+// A prior relay step as a `chain` entry. `entry` is usually an earlier
+// report's JSON, optionally tagged with the `agent` that produced it; fields
+// the chain does not carry are ignored.
+function historyStep(step, entry) {
+  if (typeof entry !== 'object' || entry === null || Array.isArray(entry)) {
+    throw new Error('Invalid handoff: each history entry must be an object');
+  }
+  if (typeof entry.verdict !== 'string') {
+    throw new Error('Each history entry must include string field: verdict');
+  }
+  const agent = typeof entry.agent === 'string' ? normalizeAgentName(entry.agent) : null;
+  let mode = null;
+  if (typeof entry.mode === 'string') {
+    mode = entry.mode.toLowerCase();
+    validateMode(mode);
+  }
+  return chainStep(
+    step,
+    agent,
+    mode,
+    typeof entry.task === 'string' ? entry.task : null,
+    entry.verdict,
+    Number.isInteger(entry.score) ? entry.score : null,
+    Array.isArray(entry.findings) ? entry.findings : []
+  );
+}
+
 function renderReport(result, asJson) {
   if (asJson) {
     console.log(JSON.stringify(result, null, 2));
@@ -1236,6 +2552,48 @@ function renderReport(result, asJson) {
   }
   lines.push('');
   lines.push(`**Verdict:** ${result.verdict}`);
+  if (Number.isInteger(result.score)) {
+    lines.push(`**Score:** ${result.score}/100`);
+  }
+  if (result.thresholds) {
+    lines.push(result.thresholds.passed
+      ? '**Thresholds:** passed'
+      : `**Thresholds:** failed (${result.thresholds.breaches.join('; ')})`);
+  }
+  if (result.similarity) {
+    const labels = result.similarity.sources;
+    lines.push('');
+    lines.push('**Similarity Matrix:**');
+    lines.push(`| | ${labels.join(' | ')} |`);
+    lines.push(`|---|${'---|'.repeat(labels.length)}`);
+    result.similarity.matrix.forEach((row, index) => {
+      lines.push(`| ${labels[index]} | ${row.map(cell => Number(cell).toFixed(2)).join(' | ')} |`);
+    });
+  }
+  if (result.consensus) {
+    const { status, agreeing, diverging } = result.consensus;
+    lines.push('');
+    if (status === 'majority') {
+      lines.push(`**Consensus:** majority (agreeing: ${agreeing.join(', ')}; diverging: ${diverging.join(', ')})`);
+    } else {
+      lines.push(`**Consensus:** ${status === 'unanimous' ? 'unanimous' : 'none'}`);
+    }
+  }
+  if (result.rubric) {
+    const labels = result.rubric.sources.map(source => source.source);
+    lines.push('');
+    lines.push('**Rubric:**');
+    lines.push(`| Criterion | Weight | ${labels.join(' | ')} |`);
+    lines.push(`|---|---|${'---|'.repeat(labels.length)}`);
+    result.rubric.criteria.forEach((criterion, position) => {
+      const cells = result.rubric.sources.map(source => {
+        const entry = source.criteria[position];
+        return `${entry.coverage.toFixed(2)} (${entry.met ? 'met' : 'missed'})`;
+      });
+      lines.push(`| ${criterion.criterion} | ${criterion.weight} | ${cells.join(' | ')} |`);
+    });
+    lines.push(`| **Score** | | ${result.rubric.sources.map(source => source.score).join(' | ')} |`);
+  }
   lines.push('');
   lines.push('**Findings:**');
   for (const finding of result.findings || []) {
@@ -1243,6 +2601,36 @@ function renderReport(result, asJson) {
       `- **${finding.severity}:** ${finding.summary} (evidence: ${(finding.evidence || []).join(', ')}; confidence: ${Number(finding.confidence || 0).toFixed(2)})`
     );
   }
+  if (result.steering) {
+    lines.push('');
+    lines.push('**Steering Plan:**');
+    if (result.steering.plan.length === 0) {
+      lines.push('- No open questions or incomplete items found.');
+    }
+    for (const step of result.steering.plan) {
+      lines.push(`${step.step}. ${step.action === 'resolve' ? 'Resolve' : 'Finish'}: ${step.item} (${step.sources.join(', ')})`);
+    }
+    lines.push('');
+    lines.push('**Next Prompts:**');
+    for (const source of result.steering.sources) {
+      lines.push('');
+      lines.push(source.source);
+      lines.push('```text');
+      lines.push(source.next_prompt);
+      lines.push('```');
+    }
+  }
+  if (result.diffs) {
+    lines.push('');
+    lines.push('**Diffs:**');
+    for (const diff of result.diffs) {
+      lines.push('');
+      lines.push(`${diff.sources.join(' vs ')} (+${diff.added} -${diff.removed} lines${diff.truncated ? ', truncated' : ''})`);
+      lines.push('```diff');
+      lines.push(diff.unified.trimEnd());
+      lines.push('```');
+    }
+  }
   lines.push('');
   lines.push('**Recommended Next Actions:**');
   (result.recommended_next_actions || []).forEach((action, index) => {
@@ -1255,6 +2643,23 @@ function renderReport(result, asJson) {
       lines.push(`- ${question}`);
     }
   }
+  if (Array.isArray(result.chain)) {
+    lines.push('');
+    lines.push('**Handoff Chain:**');
+    result.chain.forEach((step, index) => {
+      let line = `${Number.isInteger(step.step) ? step.step : index + 1}. **${step.verdict || ''}**`;
+      if (typeof step.mode === 'string') line += ` (${step.mode})`;
+      if (typeof step.agent === 'string') line += ` by ${step.agent}`;
+      if (Number.isInteger(step.score)) line += `, score ${step.score}/100`;
+      lines.push(line);
+      // The last step is this report, whose findings are listed above.
+      if (index + 1 < result.chain.length) {
+        for (const finding of step.findings || []) {
+          lines.push(`   - ${finding.severity || 'P2'}: ${finding.summary || ''}`);
+        }
+      }
+    });
+  }
 
   console.log(sanitizeForTerminal(lines.join('\n')));
 }
@@ -1267,21 +2672,49 @@ function validateMode(mode) {
 }
 
 function runRead(inputArgs) {
-  const agent = getOptionValue(inputArgs, '--agent', 'codex');
-  const id = getOptionValue(inputArgs, '--id', null);
+  const agent = normalizeAgentName(getOptionValue(inputArgs, '--agent', 'codex'));
+  const ids = getOptionValues(inputArgs, '--id');
+  const exact = hasFlag(inputArgs, '--exact');
+  const allMatching = hasFlag(inputArgs, '--all-matching');
   const chatsDir = getOptionValue(inputArgs, '--chats-dir', null);
   const cwd = normalizePath(getOptionValue(inputArgs, '--cwd', process.cwd()));
   const asJson = hasFlag(inputArgs, '--json');
   const lastN = parseInt(getOptionValue(inputArgs, '--last', '1'), 10) || 1;
-
-  const result = readSessionViaAdapter(agent, {
-    id,
-    cwd,
-    chatsDir,
-    lastN,
+  if (allMatching && ids.length === 0) {
+    throw new Error('--all-matching requires --id');
+  }
+
+  // Several ids (or --all-matching) make --json a `{sessions, errors}` object,
+  // even for one session. An id
+  // that cannot be read is then reported in `errors` instead of failing the
+  // rest; the command fails only if every id does.
+  const several = ids.length > 1 || allMatching;
+  const results = [];
+  const errors = [];
+  for (const id of ids.length > 0 ? ids : [null]) {
+    try {
+      const result = readSessionViaAdapter(agent, { id, exact, allMatching, cwd, chatsDir, lastN });
+      results.push(...(allMatching ? result : [result]));
+    } catch (error) {
+      if (!several) throw error;
+      errors.push({ id, agent, error, error_code: classifyError(error.message), message: error.message });
+    }
+  }
+  if (results.length === 0 && errors.length > 0) {
+    throw errors[0].error;
+  }
+  const errorEntries = errors.map(({ error, ...entry }) => entry);
+  if (asJson && several) {
+    console.log(JSON.stringify({ sessions: results, errors: errorEntries }, null, 2));
+    return;
+  }
+  for (const entry of errorEntries) {
+    console.error(`Warning: could not read id ${entry.id}: ${entry.message}`);
+  }
+  results.forEach((result, index) => {
+    if (index > 0) console.log('');
+    renderReadResult(result, asJson);
   });
-
-  renderReadResult(result, asJson);
 }
 
 function runSearch(inputArgs) {
@@ -1290,24 +2723,55 @@ function runSearch(inputArgs) {
     throw new Error('search requires a query string as the first argument');
   }
 
-  const agent = getOptionValue(inputArgs, '--agent', null);
-  if (!agent) {
-    throw new Error('search requires --agent=<codex|gemini|claude|cursor>');
+  const rawAgent = getOptionValue(inputArgs, '--agent', null);
+  if (!rawAgent) {
+    throw new Error('search requires --agent=<codex|gemini|claude|cursor|all>');
   }
+  const agent = isAllAgents(rawAgent) ? 'all' : normalizeAgentName(rawAgent);
 
   const rawCwd = getOptionValue(inputArgs, '--cwd', null);
   const cwd = rawCwd ? normalizePath(rawCwd) : null;
   const limit = parseInt(getOptionValue(inputArgs, '--limit', '10'), 10) || 10;
   const asJson = hasFlag(inputArgs, '--json');
 
-  const entries = searchSessions(query, agent, cwd, limit);
+  const entries = flagTruncated(searchSessions(query, agent, cwd, limit));
+  warnIfScanTruncated();
   if (asJson) {
     console.log(JSON.stringify(entries, null, 2));
   } else {
-    for (const entry of entries) {
-      console.log(JSON.stringify(entry));
+    // Search entries carry no title; look them up for the table.
+    console.log(sessionsTable(describeListEntries(entries)));
+  }
+}
+
+// First `name` executable on PATH (`name.exe` on Windows).
+function findOnPath(name) {
+  const fileName = process.platform === 'win32' ? `${name}.exe` : name;
+  for (const dir of (process.env.PATH || '').split(path.delimiter)) {
+    if (!dir) continue;
+    const candidate = path.join(dir, fileName);
+    try {
+      if (fs.statSync(candidate).isFile()) return candidate;
+    } catch (_error) {
+      // Not in this PATH entry.
     }
   }
+  return null;
+}
+
+// Whether the agent is installed: its session store exists, or its CLI is on PATH.
+function detectSetupAgent(provider) {
+  const storeDir = provider.storeDir();
+  if (fs.existsSync(storeDir)) {
+    return { agent: provider.agent, detected: true, detail: `Found sessions in ${storeDir}` };
+  }
+  for (const binary of provider.binaries) {
+    const found = findOnPath(binary);
+    if (found) {
+      return { agent: provider.agent, detected: true, detail: `Found ${found} on PATH` };
+    }
+  }
+  return { agent: provider.agent, detected: false, detail: `No ${provider.agent} sessions or CLI found` };
 }
 
 function runSetup(inputArgs) {
@@ -1339,6 +2803,11 @@ function runSetup(inputArgs) {
   if (!hasProjectMarker) {
     setupWarnings.push(`Warning: ${cwd} has no recognizable project markers (.git, package.json, etc.)`);
   }
+  const bridgePath = findOnPath('bridge');
+  if (!bridgePath) {
+    setupWarnings.push('Warning: `bridge` is not on PATH; agents following these instructions will not be able to run it');
+  }
+  const detectedAgents = setupProviders.map(detectSetupAgent);
 
   const setupRoot = path.join(cwd, '.agent-bridge');
   const providersDir = path.join(setupRoot, 'providers');
@@ -1424,7 +2893,7 @@ function runSetup(inputArgs) {
     const targetPath = path.join(cwd, provider.targetFile);
     const markerPrefix = `agent-bridge:${provider.agent}`;
     const block = makeManagedBlock(provider, snippetRelPath);
-    const upsert = upsertManagedBlock(targetPath, block, markerPrefix, force, dryRun);
+    const upsert = upsertManagedBlock(targetPath, block, markerPrefix, force, dryRun, provider.preamble);
     operations.push({
       type: 'integration',
       path: targetPath,
@@ -1479,6 +2948,8 @@ function runSetup(inputArgs) {
     cwd,
     dry_run: dryRun,
     force,
+    agents: detectedAgents,
+    bridge_on_path: bridgePath,
     operations,
     warnings: setupWarnings,
     changed: changedCount,
@@ -1493,6 +2964,9 @@ function runSetup(inputArgs) {
   for (const warning of setupWarnings) {
     console.log(`- [warn] ${warning}`);
   }
+  for (const agent of detectedAgents) {
+    console.log(`- [${agent.detected ? 'detected' : 'not found'}] ${agent.agent}: ${agent.detail}`);
+  }
   for (const op of operations) {
     console.log(`- [${op.status}] ${op.path} (${op.note})`);
   }
@@ -1510,9 +2984,9 @@ function runDoctor(inputArgs) {
   addCheck('version', 'pass', `agent-bridge v${getPackageVersion()}`);
 
   const baseChecks = [
-    ['codex_sessions_dir', codexSessionsBase],
-    ['claude_projects_dir', claudeProjectsBase],
-    ['gemini_tmp_dir', geminiTmpBase],
+    ['codex_sessions_dir', codexSessionsBase()],
+    ['claude_projects_dir', claudeProjectsBase()],
+    ['gemini_tmp_dir', geminiTmpBase()],
   ];
   for (const [id, dirPath] of baseChecks) {
     addCheck(id, fs.existsSync(dirPath) ? 'pass' : 'warn', fs.existsSync(dirPath) ? `Found: ${dirPath}` : `Missing: ${dirPath}`);
@@ -1770,6 +3244,25 @@ function runCompare(inputArgs) {
   const cwd = normalizePath(getOptionValue(inputArgs, '--cwd', process.cwd()));
   const asJson = hasFlag(inputArgs, '--json');
   const normalize = hasFlag(inputArgs, '--normalize');
+  const semantic = hasFlag(inputArgs, '--semantic');
+  const full = hasFlag(inputArgs, '--full');
+  const rawLast = getOptionValue(inputArgs, '--last', null);
+  if (full && rawLast !== null) {
+    throw new Error('--full cannot be combined with --last');
+  }
+  const last = rawLast === null ? 1 : Number(rawLast);
+  if (!Number.isInteger(last) || last < 1) {
+    throw new Error('--last must be a positive integer');
+  }
+  const thresholds = parseThresholdFlags(inputArgs);
+  const native = loadNative();
+  if (native) {
+    const options = { normalize, semantic, last, full, fail_on: thresholds.fail_on, min_score: thresholds.min_score };
+    const report = JSON.parse(native.compare(sourcesRaw, cwd, JSON.stringify(options)));
+    renderReport(report, asJson);
+    process.exitCode = verdictExitCode(report);
+    return;
+  }
   const sourceSpecs = sourcesRaw.map(parseSourceArg);
 
   const report = buildReport(
@@ -1783,11 +3276,17 @@ function runCompare(inputArgs) {
       sources: sourceSpecs,
       constraints: [],
       normalize,
+      semantic,
+      // Every message, as a count the native reader also accepts.
+      last_n: full ? Number.MAX_SAFE_INTEGER : last,
+      thresholds,
     },
     cwd
   );
 
+  warnIfScanTruncated();
   renderReport(report, asJson);
+  process.exitCode = verdictExitCode(report);
 }
 
 const MAX_HANDOFF_SIZE = 1024 * 1024; // 1 MB
@@ -1800,6 +3299,8 @@ function runReport(inputArgs) {
 
   const cwd = normalizePath(getOptionValue(inputArgs, '--cwd', process.cwd()));
   const asJson = hasFlag(inputArgs, '--json');
+  const allowCommands = hasFlag(inputArgs, '--allow-commands');
+  const thresholdFlags = parseThresholdFlags(inputArgs);
 
   const resolvedHandoffPath = normalizePath(handoffPath);
   let handoffStat;
@@ -1812,17 +3313,31 @@ function runReport(inputArgs) {
     throw new Error('Invalid handoff: file exceeds 1MB size limit');
   }
 
+  let handoffText;
   let handoff;
   try {
-    handoff = JSON.parse(fs.readFileSync(resolvedHandoffPath, 'utf-8'));
+    handoffText = fs.readFileSync(resolvedHandoffPath, 'utf-8');
+    handoff = JSON.parse(handoffText);
   } catch (error) {
     throw new Error(`Failed to read handoff JSON: ${error.message}`);
   }
 
+  const native = loadNative();
+  if (native) {
+    const report = JSON.parse(native.buildReport(
+      handoffText, cwd, allowCommands, thresholdFlags.fail_on, thresholdFlags.min_score
+    ));
+    renderReport(report, asJson);
+    process.exitCode = verdictExitCode(report);
+    return;
+  }
+
   if (typeof handoff !== 'object' || handoff === null || Array.isArray(handoff)) {
     throw new Error('Invalid handoff: must be a JSON object');
   }
-  const extraKeys = Object.keys(handoff).filter(k => !['mode', 'task', 'success_criteria', 'sources', 'constraints'].includes(k));
+  const extraKeys = Object.keys(handoff).filter(
+    k => !['mode', 'task', 'success_criteria', 'sources', 'constraints', 'history', 'claims', 'thresholds', 'rubric'].includes(k)
+  );
   if (extraKeys.length > 0) {
     throw new Error(`Invalid handoff: unexpected fields: ${extraKeys.join(', ')}`);
   }
@@ -1841,10 +3356,7 @@ function runReport(inputArgs) {
   }
 
   const sourceSpecs = handoff.sources.map(source => {
-    const agent = String(source.agent || '').toLowerCase();
-    if (!['codex', 'gemini', 'claude', 'cursor'].includes(agent)) {
-      throw new Error(`Unsupported agent: ${agent}`);
-    }
+    const agent = normalizeAgentName(source.agent || '');
 
     const sessionId = typeof source.session_id === 'string' && source.session_id.trim()
       ? source.session_id.trim()
@@ -1864,6 +3376,41 @@ function runReport(inputArgs) {
     };
   });
 
+  if (handoff.history !== undefined && !Array.isArray(handoff.history)) {
+    throw new Error('Invalid handoff: history must be an array');
+  }
+  const history = (handoff.history || []).map((entry, index) => historyStep(index + 1, entry));
+
+  let claims = [];
+  if (handoff.claims !== undefined) {
+    if (mode !== 'verify') {
+      throw new Error('Invalid handoff: claims are only checked in verify mode');
+    }
+    if (!Array.isArray(handoff.claims)) {
+      throw new Error('Invalid handoff: claims must be an array');
+    }
+    claims = handoff.claims.map(parseClaim);
+  }
+
+  let rubric = [];
+  if (handoff.rubric !== undefined) {
+    if (mode !== 'feedback') {
+      throw new Error('Invalid handoff: rubric is only scored in feedback mode');
+    }
+    if (!Array.isArray(handoff.rubric) || handoff.rubric.length === 0) {
+      throw new Error('Invalid handoff: rubric must be a non-empty array');
+    }
+    rubric = handoff.rubric.map(parseCriterion);
+  }
+
+  const thresholds = parseThresholds(handoff.thresholds);
+  if (thresholdFlags.fail_on) {
+    thresholds.fail_on = thresholdFlags.fail_on;
+  }
+  if (thresholdFlags.min_score !== null) {
+    thresholds.min_score = thresholdFlags.min_score;
+  }
+
   const report = buildReport(
     {
       mode,
@@ -1871,14 +3418,22 @@ function runReport(inputArgs) {
       success_criteria: handoff.success_criteria.map(String),
       sources: sourceSpecs,
       constraints: Array.isArray(handoff.constraints) ? handoff.constraints.map(String) : [],
+      history,
+      claims,
+      allow_commands: allowCommands,
+      thresholds,
+      rubric,
     },
     cwd
   );
 
+  warnIfScanTruncated();
   renderReport(report, asJson);
+  process.exitCode = verdictExitCode(report);
 }
 
 try {
+  applyDataDirOverrides(command, args);
   if (command === 'read') {
     runRead(args);
   } else if (command === 'compare') {
@@ -1902,10 +3457,13 @@ try {
   }
 } catch (error) {
   const msg = error.message || String(error);
+  const code = EXIT_CODES[error.code] ? error.code : classifyError(msg);
   if (hasFlag(args, '--json')) {
-    console.log(JSON.stringify({ error_code: classifyError(msg), message: msg }, null, 2));
+    const payload = { error_code: code, message: msg };
+    if (Array.isArray(error.candidates)) payload.candidates = error.candidates;
+    console.log(JSON.stringify(payload, null, 2));
   } else {
     console.error(msg);
   }
-  process.exit(1);
+  process.exit(EXIT_CODES[code] || 1);
 }
@@ -10,6 +10,8 @@ if [[ -z "$TAG" && "${GITHUB_REF_TYPE:-}" == "tag" ]]; then
 fi
 NODE_VERSION="$(node -p "require('./package.json').version")"
 RUST_VERSION="$(sed -n 's/^version = "\([^"]*\)"/\1/p' cli/Cargo.toml | head -n1)"
+CORE_VERSION="$(sed -n 's/^version = "\([^"]*\)"/\1/p' cli/core/Cargo.toml | head -n1)"
+CORE_REQUIREMENT="$(sed -n 's/^agent-bridge-core = .*version = "\([^"]*\)".*/\1/p' cli/Cargo.toml | head -n1)"
 
 if [[ -z "$NODE_VERSION" || -z "$RUST_VERSION" ]]; then
   echo "Failed to resolve versions from package.json or cli/Cargo.toml" >&2
@@ -21,6 +23,11 @@ if [[ "$NODE_VERSION" != "$RUST_VERSION" ]]; then
   exit 1
 fi
 
+if [[ "$CORE_VERSION" != "$RUST_VERSION" || "$CORE_REQUIREMENT" != "$RUST_VERSION" ]]; then
+  echo "Version mismatch: cli/Cargo.toml=$RUST_VERSION cli/core/Cargo.toml=$CORE_VERSION agent-bridge-core dependency=$CORE_REQUIREMENT" >&2
+  exit 1
+fi
+
 if [[ -n "$TAG" ]]; then
   EXPECTED_TAG="v$NODE_VERSION"
   if [[ "$TAG" != "$EXPECTED_TAG" ]]; then
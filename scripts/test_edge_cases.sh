@@ -116,6 +116,9 @@ expect_error "unsupported-agent" "UNSUPPORTED_AGENT" read --agent foobar
 # Not found: should fail with NOT_FOUND
 expect_error "not-found" "NOT_FOUND" read --agent codex --id nonexistent-session-xyz
 
+# Several sessions contain the id and none equals it: should fail with AMBIGUOUS_ID
+expect_error "ambiguous-id" "AMBIGUOUS_ID" read --agent codex --id session-codex-m
+
 echo ""
 echo "=== Results: $PASS passed, $FAIL failed ==="
 if [[ "$FAIL" -gt 0 ]]; then
@@ -32,12 +32,12 @@ cargo run --quiet --manifest-path "$ROOT/cli/Cargo.toml" -- read --agent codex -
 BRIDGE_CODEX_SESSIONS_DIR="$STORE/codex/sessions" \
 BRIDGE_GEMINI_TMP_DIR="$STORE/gemini/tmp" \
 BRIDGE_CLAUDE_PROJECTS_DIR="$STORE/claude/projects" \
-node "$ROOT/scripts/read_session.cjs" report --handoff="$ROOT/fixtures/handoff-report.json" --json > "$report_node_json"
+node "$ROOT/scripts/read_session.cjs" report --handoff="$ROOT/fixtures/handoff-report.json" --json > "$report_node_json" || [[ $? -eq 2 ]]
 
 BRIDGE_CODEX_SESSIONS_DIR="$STORE/codex/sessions" \
 BRIDGE_GEMINI_TMP_DIR="$STORE/gemini/tmp" \
 BRIDGE_CLAUDE_PROJECTS_DIR="$STORE/claude/projects" \
-cargo run --quiet --manifest-path "$ROOT/cli/Cargo.toml" -- report --handoff "$ROOT/fixtures/handoff-report.json" --json > "$report_rust_json"
+cargo run --quiet --manifest-path "$ROOT/cli/Cargo.toml" -- report --handoff "$ROOT/fixtures/handoff-report.json" --json > "$report_rust_json" || [[ $? -eq 2 ]]
 
 BRIDGE_CODEX_SESSIONS_DIR="$STORE/codex/sessions" \
 BRIDGE_GEMINI_TMP_DIR="$STORE/gemini/tmp" \
@@ -113,6 +113,7 @@ fi
 AJV_CMD=(npx ajv-cli validate --spec=draft2020)
 
 "${AJV_CMD[@]}" -s "$ROOT/schemas/handoff.schema.json" -d "$ROOT/fixtures/handoff-report.json"
+"${AJV_CMD[@]}" -s "$ROOT/schemas/handoff.schema.json" -d "$ROOT/fixtures/handoff-chain.json"
 
 "${AJV_CMD[@]}" -s "$ROOT/schemas/read-output.schema.json" -d "$read_node_json"
 "${AJV_CMD[@]}" -s "$ROOT/schemas/read-output.schema.json" -d "$read_rust_json"
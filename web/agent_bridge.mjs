@@ -0,0 +1,61 @@
+// Browser/worker loader for the wasm32 build of the Agent Bridge core
+// (`npm run build:wasm`). It parses exported transcripts with the same rules
+// as the CLI; the module has no filesystem or network access.
+//
+//   import { loadAgentBridge } from './agent_bridge.mjs';
+//   const bridge = await loadAgentBridge(fetch('agent_bridge.wasm'));
+//   const session = bridge.parseSession('claude', file.name, await file.arrayBuffer());
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+export async function loadAgentBridge(source) {
+  const resolved = await source;
+  const { instance } = resolved instanceof Response
+    ? await WebAssembly.instantiateStreaming(resolved, {})
+    : await WebAssembly.instantiate(resolved, {});
+  const wasm = instance.exports;
+
+  function withInput(bytes, callback) {
+    const ptr = wasm.bridge_alloc(bytes.length);
+    new Uint8Array(wasm.memory.buffer, ptr, bytes.length).set(bytes);
+    try {
+      return callback(ptr, bytes.length);
+    } finally {
+      wasm.bridge_free(ptr, bytes.length);
+    }
+  }
+
+  function takeOutput(packed) {
+    const ptr = Number(packed >> 32n);
+    const len = Number(packed & 0xffffffffn);
+    const text = decoder.decode(new Uint8Array(wasm.memory.buffer, ptr, len));
+    wasm.bridge_free(ptr, len);
+    return text;
+  }
+
+  return {
+    // Same object `bridge read --json` prints. Throws with `error_code` set
+    // when the transcript cannot be parsed.
+    parseSession(agent, fileName, contents, { last = 1 } = {}) {
+      const bytes = typeof contents === 'string' ? encoder.encode(contents) : new Uint8Array(contents);
+      const result = withInput(encoder.encode(agent), (agentPtr, agentLen) =>
+        withInput(encoder.encode(fileName || ''), (namePtr, nameLen) =>
+          withInput(bytes, (bytesPtr, bytesLen) =>
+            JSON.parse(takeOutput(wasm.bridge_parse_session(agentPtr, agentLen, namePtr, nameLen, bytesPtr, bytesLen, last)))
+          )
+        )
+      );
+      if (result.error_code) {
+        const error = new Error(result.message);
+        error.code = result.error_code;
+        throw error;
+      }
+      return result;
+    },
+
+    redact(text) {
+      return withInput(encoder.encode(text), (ptr, len) => takeOutput(wasm.bridge_redact(ptr, len)));
+    },
+  };
+}